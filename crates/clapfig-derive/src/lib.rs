@@ -32,7 +32,9 @@ use syn::{
 ///   bounds so out-of-range values fail schema validation with the key
 ///   path; see the `LeafTypeStatic::Integer` doc comment for the
 ///   `i64::MAX` caveat on the unsigned variants), `f32`, `f64`,
-///   `clapfig::value::Datetime`, `clapfig::value::Value`.
+///   `clapfig::value::Datetime`, `clapfig::value::Value`, and
+///   `std::path::PathBuf` (a `LeafType::Path` leaf: `~` expands and a
+///   relative path resolves against the config file that set it).
 /// - `Option<T>` of a supported leaf: scalar, `Value`, or unit-only enum
 ///   (`Option<UnitEnum>` included). Unqualified `Option<T>` is not the
 ///   contract — nested structs are not an Option shape (see deferred
@@ -76,11 +78,11 @@ use syn::{
 ///   of any variant). Discriminators follow the same `rename` /
 ///   `rename_all` rules as unit-only enums. A legal document root.
 ///
-/// Field types named `Datetime` / `Value` are matched *by name* (a proc
-/// macro cannot resolve paths), so the macro also emits a compile-time
-/// assertion that the type really is clapfig's — a user-defined lookalike
-/// (`struct Datetime`) is a compile error instead of a silently mis-typed
-/// leaf. Raw identifiers unraw: a field `r#type` emits the schema name
+/// Field types named `Datetime` / `Value` / `PathBuf` are matched *by
+/// name* (a proc macro cannot resolve paths), so the macro also emits a
+/// compile-time assertion that the type really is the claimed one — a
+/// user-defined lookalike (`struct Datetime`) is a compile error instead
+/// of a silently mis-typed leaf. Raw identifiers unraw: a field `r#type` emits the schema name
 /// `type`, matching serde's spelling.
 ///
 /// # Rejected at derive time
@@ -96,12 +98,12 @@ use syn::{
 ///   another map, or of `Vec<NestedStruct>`.
 /// - `Vec<Option<T>>`, `Vec<Vec<...>>`, `Vec<clapfig::value::Value>`,
 ///   `Vec<HashMap<...>>` / `Vec<BTreeMap<...>>`.
-/// - `PathBuf`, `Duration`, `char`, newtypes, type aliases, third-party
-///   maps — no TOML-faithful schema shape; the `Schema` trait's
+/// - `Duration`, `char`, newtypes, type aliases, third-party maps — no
+///   TOML-faithful schema shape; the `Schema` trait's
 ///   `on_unimplemented` diagnostic names the supported set and the
 ///   `#[clapfig(value)]` escape hatch.
-/// - Datetime / Value lookalikes claimed by type name that are not
-///   clapfig's own types.
+/// - Datetime / Value / PathBuf lookalikes claimed by type name that are
+///   not the real types.
 /// - Unknown `#[clapfig(...)]` metas (fields, structs, enum variants).
 /// - `#[clapfig(name)]` / `#[clapfig(strict)]` on unit-only enums
 ///   (flattened away). Internally tagged enums accept both.
@@ -109,7 +111,7 @@ use syn::{
 ///   `value` + `allowed` on the same field; `allowed` on `Vec` /
 ///   nested / map-of-nested fields; defaults on map-typed fields and
 ///   array-of-nested fields; leaf attrs (`default` / `env` / `allowed` /
-///   `optional`) on map-of-nested and array-of-nested fields;
+///   `optional` / `secret`) on map-of-nested and array-of-nested fields;
 ///   `Option<HashMap<String, NestedStruct>>` /
 ///   `Option<BTreeMap<String, NestedStruct>>` (unit-enum values
 ///   included — they classify as Nested at the field site).
@@ -187,6 +189,20 @@ use syn::{
 ///   value is required. Negative integer/float literals are accepted.
 /// - `#[clapfig(optional)]` — force `optional = true` on a non-`Option<T>`
 ///   field (rarely needed; `Option<T>` is the usual spelling)
+/// - `#[clapfig(secret)]` — mark the field sensitive
+///   (`clapfig::runtime::Leaf::sensitive`): its value is redacted in
+///   `config` output and error messages, and the JSON Schema property is
+///   `writeOnly`. On `Vec<T>` / map fields every entry is a secret.
+/// - `#[clapfig(from_file)]` — the value is a path; loading replaces it
+///   with that file's contents (`clapfig::runtime::Leaf::from_file`).
+///   Only valid on `String` / `Option<String>` fields.
+/// - `#[clapfig(merge = "append")]` — how a higher layer's value for this
+///   field combines with the layers below it
+///   (`clapfig::runtime::MergeStrategy`): `append`, `prepend`, `union`,
+///   or `replace` on `Vec<T>` fields; only `replace` on map fields, which
+///   otherwise merge entry by entry.
+/// - `#[clapfig(merge_by_key = "name")]` — on a `Vec<T>` of a struct:
+///   elements whose `name` field matches merge deep; the rest append.
 ///
 /// # Struct attributes
 ///
@@ -345,18 +361,7 @@ fn expand_schema(input: DeriveInput) -> syn::Result<TokenStream2> {
                 let mut seen = std::collections::HashSet::new();
                 for f in &named.named {
                     let expanded = expand_field(f, rename_all.as_deref())?;
-                    if !seen.insert(expanded.name.clone()) {
-                        return Err(syn::Error::new(
-                            f.ident.span(),
-                            format!(
-                                "duplicate schema field name {:?} — two fields (after \
-                                 `rename`/`rename_all`) would collide in the schema, \
-                                 making lookups and unknown-key validation \
-                                 order-dependent. Rename one of them.",
-                                expanded.name
-                            ),
-                        ));
-                    }
+                    claim_field_keys(&mut seen, &expanded, f.ident.span())?;
                     claim_asserts.extend(expanded.claim_asserts);
                     field_entries.push(expanded.entry);
                 }
@@ -674,18 +679,7 @@ fn expand_tagged_enum(
                             ),
                         ));
                     }
-                    if !field_names.insert(expanded.name.clone()) {
-                        return Err(syn::Error::new(
-                            f.ident.span(),
-                            format!(
-                                "duplicate schema field name {:?} — two fields (after \
-                                 `rename`/`rename_all`) would collide in the schema, \
-                                 making lookups and unknown-key validation \
-                                 order-dependent. Rename one of them.",
-                                expanded.name
-                            ),
-                        ));
-                    }
+                    claim_field_keys(&mut field_names, &expanded, f.ident.span())?;
                     claims.extend(expanded.claim_asserts);
                     field_entries.push(expanded.entry);
                 }
@@ -1347,6 +1341,39 @@ struct FieldAttrs {
     force_value: bool,
     allowed: Option<Vec<Expr>>,
     optional: bool,
+    secret: bool,
+    from_file: bool,
+    merge: Option<syn::LitStr>,
+    merge_by_key: Option<syn::LitStr>,
+    aliases: Vec<syn::LitStr>,
+    deprecated: Option<syn::LitStr>,
+    pattern: Option<syn::LitStr>,
+    min_len: Option<syn::LitInt>,
+    max_len: Option<syn::LitInt>,
+    min: Option<Expr>,
+    max: Option<Expr>,
+    exclusive_min: Option<Expr>,
+    exclusive_max: Option<Expr>,
+    non_empty: Option<syn::Path>,
+    min_items: Option<syn::LitInt>,
+    max_items: Option<syn::LitInt>,
+    unique: Option<syn::Path>,
+    expand_vars: Option<syn::Path>,
+    must_exist: Option<syn::Path>,
+    is_dir: Option<syn::Path>,
+    is_file: Option<syn::Path>,
+}
+
+impl FieldAttrs {
+    /// `LeafStatic::aliases` / `LeafStatic::deprecated` initializers.
+    fn alias_exprs(&self) -> (TokenStream2, TokenStream2) {
+        let aliases = &self.aliases;
+        let deprecated = match &self.deprecated {
+            Some(note) => quote! { Some(#note) },
+            None => quote! { None },
+        };
+        (quote! { &[ #(#aliases),* ] }, deprecated)
+    }
 }
 
 fn parse_field_attrs(attrs: &[Attribute]) -> syn::Result<FieldAttrs> {
@@ -1374,6 +1401,71 @@ fn parse_field_attrs(attrs: &[Attribute]) -> syn::Result<FieldAttrs> {
             } else if meta.path.is_ident("optional") {
                 out.optional = true;
                 Ok(())
+            } else if meta.path.is_ident("secret") {
+                out.secret = true;
+                Ok(())
+            } else if meta.path.is_ident("from_file") {
+                out.from_file = true;
+                Ok(())
+            } else if meta.path.is_ident("merge") {
+                out.merge = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("merge_by_key") {
+                out.merge_by_key = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("alias") {
+                let value: syn::LitStr = meta.value()?.parse()?;
+                validate_schema_field_name(&value.value(), "#[clapfig(alias)]", value.span())?;
+                out.aliases.push(value);
+                Ok(())
+            } else if meta.path.is_ident("deprecated") {
+                out.deprecated = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("pattern") {
+                out.pattern = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("min_len") {
+                out.min_len = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("max_len") {
+                out.max_len = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("min") {
+                out.min = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("max") {
+                out.max = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("exclusive_min") {
+                out.exclusive_min = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("exclusive_max") {
+                out.exclusive_max = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("non_empty") {
+                out.non_empty = Some(meta.path.clone());
+                Ok(())
+            } else if meta.path.is_ident("min_items") {
+                out.min_items = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("max_items") {
+                out.max_items = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("unique") {
+                out.unique = Some(meta.path.clone());
+                Ok(())
+            } else if meta.path.is_ident("expand_vars") {
+                out.expand_vars = Some(meta.path.clone());
+                Ok(())
+            } else if meta.path.is_ident("must_exist") {
+                out.must_exist = Some(meta.path.clone());
+                Ok(())
+            } else if meta.path.is_ident("is_dir") {
+                out.is_dir = Some(meta.path.clone());
+                Ok(())
+            } else if meta.path.is_ident("is_file") {
+                out.is_file = Some(meta.path.clone());
+                Ok(())
             } else if meta.path.is_ident("allowed") {
                 let expr: Expr = meta.value()?.parse()?;
                 let items = match expr {
@@ -1390,7 +1482,10 @@ fn parse_field_attrs(attrs: &[Attribute]) -> syn::Result<FieldAttrs> {
             } else {
                 Err(meta.error(format!(
                     "unsupported #[clapfig(...)] field attribute: `{}`. \
-                     Supported: default, env, rename, value, optional, allowed",
+                     Supported: default, env, rename, value, optional, allowed, secret, \
+                     from_file, merge, merge_by_key, alias, deprecated, pattern, min_len, \
+                     max_len, min, max, exclusive_min, exclusive_max, non_empty, min_items, \
+                     max_items, unique, expand_vars, must_exist, is_dir, is_file",
                     meta.path
                         .get_ident()
                         .map(|i| i.to_string())
@@ -1482,6 +1577,7 @@ enum ScalarKind {
     Float,
     Bool,
     DateTime,
+    Path,
 }
 
 impl ScalarKind {
@@ -1492,6 +1588,16 @@ impl ScalarKind {
             ScalarKind::Float => "Float",
             ScalarKind::Bool => "Bool",
             ScalarKind::DateTime => "DateTime",
+            ScalarKind::Path => "PathBuf",
+        }
+    }
+
+    /// The literal kind a value of this kind is written as: a path is a
+    /// string in every format.
+    fn literal(self) -> ScalarKind {
+        match self {
+            ScalarKind::Path => ScalarKind::String,
+            other => other,
         }
     }
 }
@@ -1631,6 +1737,12 @@ fn classify_type(ty: &Type) -> syn::Result<TypeShape> {
             quote! { ::clapfig::static_schema::LeafTypeStatic::DateTime },
         ));
     }
+    if name == "PathBuf" && is_std_pathbuf_path(path) {
+        return Ok(TypeShape::Scalar(
+            ScalarKind::Path,
+            quote! { ::clapfig::static_schema::LeafTypeStatic::Path },
+        ));
+    }
 
     // 128-bit integers don't fit TOML's signed-64-bit integer width and there
     // is no faithful intermediate representation. Reject at derive time with
@@ -1884,14 +1996,26 @@ fn is_clapfig_datetime_path(path: &syn::Path) -> bool {
     )
 }
 
+fn is_std_pathbuf_path(path: &syn::Path) -> bool {
+    // `PathBuf`, `path::PathBuf`, and `std::path::PathBuf`.
+    let segs: Vec<_> = path.segments.iter().map(|s| s.ident.to_string()).collect();
+    matches!(segs.as_slice(),
+        [a] if a == "PathBuf"
+    ) || matches!(segs.as_slice(),
+        [a, b] if a == "path" && b == "PathBuf"
+    ) || matches!(segs.as_slice(),
+        [a, b, c] if a == "std" && b == "path" && c == "PathBuf"
+    )
+}
+
 /// Collect compile-time marker-trait assertions for every type path the
 /// macro claims *by name* — `Datetime` → datetime leaf, `Value` →
-/// free-form leaf. A proc macro cannot resolve names, so a user's own
-/// `struct Datetime` (or `Value`) at a claimed position would otherwise
-/// silently become the wrong leaf type. The emitted `const` turns the
-/// mismatch into a compile error whose message comes from the
-/// `IsClapfigDatetime` / `IsClapfigValue` `on_unimplemented` diagnostics,
-/// spanned at the offending field type.
+/// free-form leaf, `PathBuf` → path leaf. A proc macro cannot resolve
+/// names, so a user's own `struct Datetime` (or `Value`) at a claimed
+/// position would otherwise silently become the wrong leaf type. The
+/// emitted `const` turns the mismatch into a compile error whose message
+/// comes from the `IsClapfigDatetime` / `IsClapfigValue` / `IsStdPathBuf`
+/// `on_unimplemented` diagnostics, spanned at the offending field type.
 ///
 /// Recurses through generic arguments so wrapped positions
 /// (`Option<Datetime>`, `Vec<Datetime>`, `HashMap<String, Value>`) are
@@ -1923,6 +2047,17 @@ fn collect_type_claim_assertions(ty: &Type, out: &mut Vec<TokenStream2>) {
         });
         return;
     }
+    if is_std_pathbuf_path(path) {
+        out.push(quote::quote_spanned! {span=>
+            const _: fn() = || {
+                fn claimed_as_std_pathbuf<
+                    T: ::clapfig::static_schema::IsStdPathBuf,
+                >() {}
+                let _ = claimed_as_std_pathbuf::<#ty>;
+            };
+        });
+        return;
+    }
     if let Some(last) = path.segments.last()
         && let PathArguments::AngleBracketed(args) = &last.arguments
     {
@@ -1934,16 +2069,328 @@ fn collect_type_claim_assertions(ty: &Type, out: &mut Vec<TokenStream2>) {
     }
 }
 
+/// `ConstraintsStatic` initializer for a field's constraint attributes,
+/// rejecting each one on a field it cannot apply to: string constraints
+/// need a `String` (or `Vec<String>`) field, bounds an `f64`/`f32` one,
+/// item constraints a `Vec`, path options a `PathBuf`. Patterns compile at the first `schema()`
+/// call, not here.
+fn constraints_static(attrs: &FieldAttrs, shape: &TypeShape) -> syn::Result<TokenStream2> {
+    let shape = match shape {
+        TypeShape::Optional(inner) => inner.as_ref(),
+        other => other,
+    };
+    let scalar = match shape {
+        TypeShape::Scalar(kind, _) | TypeShape::Array(kind, _) => Some(*kind),
+        _ => None,
+    };
+    let is_array = matches!(shape, TypeShape::Array(..) | TypeShape::ArrayOfNested(_));
+    let enum_leaf = attrs.allowed.is_some() || attrs.force_value;
+    let string_leaf = scalar == Some(ScalarKind::String) && !enum_leaf;
+    let float_leaf = scalar == Some(ScalarKind::Float) && !enum_leaf;
+    let mut fields = Vec::new();
+
+    let string_attrs = [
+        ("pattern", attrs.pattern.as_ref().map(Spanned::span)),
+        ("min_len", attrs.min_len.as_ref().map(Spanned::span)),
+        ("max_len", attrs.max_len.as_ref().map(Spanned::span)),
+    ];
+    for (name, span) in string_attrs {
+        if let Some(span) = span
+            && !string_leaf
+        {
+            return Err(syn::Error::new(
+                span,
+                format!(
+                    "`#[clapfig({name} = ...)]` is only valid on a `String` or \
+                     `Vec<String>` field without `allowed` or `value`."
+                ),
+            ));
+        }
+    }
+    if let Some(pattern) = &attrs.pattern {
+        fields.push(quote! { pattern: Some(#pattern) });
+    }
+    let min_len = usize_attr(attrs.min_len.as_ref())?;
+    let max_len = usize_attr(attrs.max_len.as_ref())?;
+    check_attr_range(
+        "min_len",
+        "max_len",
+        min_len,
+        max_len,
+        attrs.max_len.as_ref(),
+    )?;
+    if let Some(n) = min_len {
+        fields.push(quote! { min_len: Some(#n) });
+    }
+    if let Some(n) = max_len {
+        fields.push(quote! { max_len: Some(#n) });
+    }
+
+    let bounds = [
+        ("min", &attrs.min),
+        ("max", &attrs.max),
+        ("exclusive_min", &attrs.exclusive_min),
+        ("exclusive_max", &attrs.exclusive_max),
+    ];
+    for (name, bound) in bounds {
+        let Some(expr) = bound else { continue };
+        if !float_leaf {
+            return Err(syn::Error::new(
+                expr.span(),
+                format!(
+                    "`#[clapfig({name} = ...)]` is only valid on an `f64`/`f32` field (or a \
+                     `Vec` of them) — integer fields already carry their type's range."
+                ),
+            ));
+        }
+        let value = match parse_lit_value(expr) {
+            Some(LitValue::Float(f)) => f,
+            Some(LitValue::Int(i)) => i as f64,
+            _ => {
+                return Err(syn::Error::new(
+                    expr.span(),
+                    format!("`{name} = ...` requires a number literal"),
+                ));
+            }
+        };
+        let ident = syn::Ident::new(name, expr.span());
+        let literal = proc_macro2::Literal::f64_suffixed(value.abs());
+        let value = if value.is_sign_negative() {
+            quote! { -#literal }
+        } else {
+            quote! { #literal }
+        };
+        fields.push(quote! { #ident: Some(#value) });
+    }
+
+    if let Some(path) = &attrs.non_empty {
+        if !string_leaf && !is_array {
+            return Err(syn::Error::new(
+                path.span(),
+                "`#[clapfig(non_empty)]` is only valid on a `String` field (at least one \
+                 character) or a `Vec` field (at least one item).",
+            ));
+        }
+        fields.push(quote! { non_empty: true });
+    }
+    let item_attrs = [
+        ("min_items", attrs.min_items.as_ref().map(Spanned::span)),
+        ("max_items", attrs.max_items.as_ref().map(Spanned::span)),
+        ("unique", attrs.unique.as_ref().map(Spanned::span)),
+    ];
+    for (name, span) in item_attrs {
+        if let Some(span) = span
+            && !is_array
+        {
+            return Err(syn::Error::new(
+                span,
+                format!("`#[clapfig({name})]` is only valid on a `Vec` field."),
+            ));
+        }
+    }
+    let min_items = usize_attr(attrs.min_items.as_ref())?;
+    let max_items = usize_attr(attrs.max_items.as_ref())?;
+    check_attr_range(
+        "min_items",
+        "max_items",
+        min_items,
+        max_items,
+        attrs.max_items.as_ref(),
+    )?;
+    if let Some(n) = min_items {
+        fields.push(quote! { min_items: Some(#n) });
+    }
+    if let Some(n) = max_items {
+        fields.push(quote! { max_items: Some(#n) });
+    }
+    if attrs.unique.is_some() {
+        fields.push(quote! { unique: true });
+    }
+
+    let path_leaf = scalar == Some(ScalarKind::Path) && !enum_leaf;
+    let path_attrs = [
+        ("expand_vars", &attrs.expand_vars),
+        ("must_exist", &attrs.must_exist),
+        ("is_dir", &attrs.is_dir),
+        ("is_file", &attrs.is_file),
+    ];
+    for (name, attr) in path_attrs {
+        let Some(path) = attr else { continue };
+        if !path_leaf {
+            return Err(syn::Error::new(
+                path.span(),
+                format!(
+                    "`#[clapfig({name})]` is only valid on a `PathBuf` field (or a `Vec` or \
+                     map of them) without `allowed` or `value`."
+                ),
+            ));
+        }
+        let ident = syn::Ident::new(name, path.span());
+        fields.push(quote! { #ident: true });
+    }
+    if let (Some(_), Some(is_file)) = (&attrs.is_dir, &attrs.is_file) {
+        return Err(syn::Error::new(
+            is_file.span(),
+            "`#[clapfig(is_dir)]` and `#[clapfig(is_file)]` exclude each other.",
+        ));
+    }
+
+    if fields.is_empty() {
+        return Ok(quote! { ::clapfig::static_schema::ConstraintsStatic::NONE });
+    }
+    Ok(quote! {
+        ::clapfig::static_schema::ConstraintsStatic {
+            #(#fields,)*
+            ..::clapfig::static_schema::ConstraintsStatic::NONE
+        }
+    })
+}
+
+fn usize_attr(lit: Option<&syn::LitInt>) -> syn::Result<Option<usize>> {
+    lit.map(syn::LitInt::base10_parse).transpose()
+}
+
+/// Reject a `min > max` pair, pointing at the `max` attribute.
+fn check_attr_range(
+    min_name: &str,
+    max_name: &str,
+    min: Option<usize>,
+    max: Option<usize>,
+    max_lit: Option<&syn::LitInt>,
+) -> syn::Result<()> {
+    if let (Some(lo), Some(hi), Some(lit)) = (min, max, max_lit)
+        && lo > hi
+    {
+        return Err(syn::Error::new(
+            lit.span(),
+            format!("`{min_name} = {lo}` is greater than `{max_name} = {hi}` — no value fits."),
+        ));
+    }
+    Ok(())
+}
+
+/// `#[clapfig(merge = ...)]` / `#[clapfig(merge_by_key = ...)]` as a
+/// `MergeStatic` initializer. Checked here as far as the field's type
+/// shows: strategies belong to `Vec` and map fields, a map takes only
+/// `replace`, and `merge_by_key` needs a `Vec` of a nested type. Whether
+/// that type declares the key field is checked at the first `schema()`
+/// call.
+fn merge_static(
+    attrs: &FieldAttrs,
+    shape: &TypeShape,
+    field: &syn::Field,
+) -> syn::Result<TokenStream2> {
+    let shape = match shape {
+        TypeShape::Optional(inner) => inner.as_ref(),
+        other => other,
+    };
+    let is_array = matches!(shape, TypeShape::Array(..) | TypeShape::ArrayOfNested(_));
+    let is_map = matches!(shape, TypeShape::Map(_) | TypeShape::MapOfNested(_));
+    let strategy = match (&attrs.merge, &attrs.merge_by_key) {
+        (None, None) => return Ok(quote! { None }),
+        (Some(_), Some(key)) => {
+            return Err(syn::Error::new(
+                key.span(),
+                "`merge` and `merge_by_key` both set the merge strategy — keep one.",
+            ));
+        }
+        (None, Some(key)) => {
+            if !matches!(shape, TypeShape::ArrayOfNested(_)) {
+                return Err(syn::Error::new(
+                    key.span(),
+                    "`#[clapfig(merge_by_key = ...)]` is only valid on a `Vec<T>` field \
+                     where `T` is a struct deriving `Schema` — elements merge by a field \
+                     of that struct.",
+                ));
+            }
+            let key = key.value();
+            quote! { ::clapfig::static_schema::MergeStatic::MergeByKey(#key) }
+        }
+        (Some(lit), None) => {
+            let variant = match lit.value().as_str() {
+                "replace" => quote! { Replace },
+                "append" => quote! { Append },
+                "prepend" => quote! { Prepend },
+                "union" => quote! { Union },
+                other => {
+                    return Err(syn::Error::new(
+                        lit.span(),
+                        format!(
+                            "unknown merge strategy {other:?}. Supported: replace, append, \
+                             prepend, union (or `merge_by_key = \"field\"`)."
+                        ),
+                    ));
+                }
+            };
+            if is_map && lit.value() != "replace" {
+                return Err(syn::Error::new(
+                    lit.span(),
+                    "a map field merges entry by entry or is replaced whole — the only \
+                     strategy it takes is `merge = \"replace\"`.",
+                ));
+            }
+            quote! { ::clapfig::static_schema::MergeStatic::#variant }
+        }
+    };
+    if !is_array && !is_map {
+        return Err(syn::Error::new(
+            field.ty.span(),
+            "`#[clapfig(merge = ...)]` is only valid on `Vec<T>` and map fields — \
+             other values are replaced by the higher layer, and nested structs \
+             merge key by key.",
+        ));
+    }
+    Ok(quote! { Some(#strategy) })
+}
+
 /// One expanded struct field: the resolved schema-facing name (for the
 /// caller's duplicate check), the `NamedFieldStatic` initializer entry,
 /// and any module-level type-claim assertions to emit alongside the
 /// schema statics.
 struct ExpandedField {
     name: String,
+    aliases: Vec<String>,
     entry: TokenStream2,
     claim_asserts: Vec<TokenStream2>,
 }
 
+/// Claim a field's name and aliases in `seen`, the keys its struct (or
+/// tagged variant) already declares. Post-rename duplicates make
+/// `find_field` lookups and unknown-key validation order-dependent at
+/// runtime — the enum path has the same guard for renamed variants.
+fn claim_field_keys(
+    seen: &mut std::collections::HashSet<String>,
+    expanded: &ExpandedField,
+    span: proc_macro2::Span,
+) -> syn::Result<()> {
+    if !seen.insert(expanded.name.clone()) {
+        return Err(syn::Error::new(
+            span,
+            format!(
+                "duplicate schema field name {:?} — two fields (after \
+                 `rename`/`rename_all`) would collide in the schema, \
+                 making lookups and unknown-key validation \
+                 order-dependent. Rename one of them.",
+                expanded.name
+            ),
+        ));
+    }
+    for alias in &expanded.aliases {
+        if *alias == expanded.name || !seen.insert(alias.clone()) {
+            return Err(syn::Error::new(
+                span,
+                format!(
+                    "#[clapfig(alias = {alias:?})] on field {:?} is already a key of this \
+                     schema (a field name or another alias, after `rename`/`rename_all`), \
+                     so one key would name two fields. Pick a different alias.",
+                    expanded.name
+                ),
+            ));
+        }
+    }
+    Ok(())
+}
+
 fn expand_field(field: &syn::Field, rename_all: Option<&str>) -> syn::Result<ExpandedField> {
     let ident = field
         .ident
@@ -2005,6 +2452,8 @@ fn expand_field(field: &syn::Field, rename_all: Option<&str>) -> syn::Result<Exp
         },
     };
     let doc_expr = doc_slice(&doc_lines);
+    let aliases: Vec<String> = attrs.aliases.iter().map(syn::LitStr::value).collect();
+    let (aliases_expr, deprecated_expr) = attrs.alias_exprs();
     // Type-claim assertions for by-name matches (`Datetime` / `Value`).
     // Skipped under `#[clapfig(value)]`: the fast path never runs the
     // by-name inference, so nothing is claimed.
@@ -2049,6 +2498,26 @@ fn expand_field(field: &syn::Field, rename_all: Option<&str>) -> syn::Result<Exp
         classify_type(&field.ty)?
     };
 
+    if attrs.from_file {
+        let is_string =
+            |shape: &TypeShape| matches!(shape, TypeShape::Scalar(ScalarKind::String, _));
+        let string_leaf = match &shape {
+            TypeShape::Optional(inner) => is_string(inner),
+            other => is_string(other),
+        };
+        if !string_leaf || attrs.allowed.is_some() {
+            return Err(syn::Error::new(
+                field.ty.span(),
+                "`#[clapfig(from_file)]` is only valid on a `String` or \
+                 `Option<String>` field without `allowed` — the value names a \
+                 file, and loading replaces it with the file's contents.",
+            ));
+        }
+    }
+
+    let merge_expr = merge_static(&attrs, &shape, field)?;
+    let constraints_expr = constraints_static(&attrs, &shape)?;
+
     // Nested struct OR unit-only enum field. The macro can't tell the
     // two apart syntactically — so the routing depends on what
     // attributes the user wrote and whether `Option<…>` is in the way:
@@ -2082,7 +2551,12 @@ fn expand_field(field: &syn::Field, rename_all: Option<&str>) -> syn::Result<Exp
         // fold them both into one flag so the rest of the path checks a
         // single condition.
         let is_field_optional = attrs.optional || matches!(&shape, TypeShape::Optional(_));
-        let has_leaf_attrs = attrs.default.is_some() || attrs.env.is_some() || is_field_optional;
+        let has_leaf_attrs = attrs.default.is_some()
+            || attrs.env.is_some()
+            || attrs.secret
+            || is_field_optional
+            || !attrs.aliases.is_empty()
+            || attrs.deprecated.is_some();
         if attrs.allowed.is_some() {
             return Err(syn::Error::new(
                 field.span(),
@@ -2117,6 +2591,7 @@ fn expand_field(field: &syn::Field, rename_all: Option<&str>) -> syn::Result<Exp
                 None => quote! { None },
             };
             let optional_expr = quote! { #is_field_optional };
+            let secret = attrs.secret;
             let leaf = quote! {
                 ::clapfig::static_schema::LeafStatic {
                     doc: #doc_expr,
@@ -2127,10 +2602,17 @@ fn expand_field(field: &syn::Field, rename_all: Option<&str>) -> syn::Result<Exp
                     default: #default_expr,
                     optional: #optional_expr,
                     env: #env_expr,
+                    sensitive: #secret,
+                    from_file: false,
+                    merge: None,
+                    aliases: #aliases_expr,
+                    deprecated: #deprecated_expr,
+                    constraints: ::clapfig::static_schema::ConstraintsStatic::NONE,
                 }
             };
             return Ok(ExpandedField {
                 name: name.clone(),
+                aliases,
                 entry: quote! {
                     ::clapfig::static_schema::NamedFieldStatic {
                         name: #name,
@@ -2146,6 +2628,7 @@ fn expand_field(field: &syn::Field, rename_all: Option<&str>) -> syn::Result<Exp
         // `Option<Mode>` EnumRef path which keeps its field doc).
         return Ok(ExpandedField {
             name: name.clone(),
+            aliases,
             entry: quote! {
                 ::clapfig::static_schema::NamedFieldStatic {
                     name: #name,
@@ -2168,23 +2651,28 @@ fn expand_field(field: &syn::Field, rename_all: Option<&str>) -> syn::Result<Exp
             || attrs.env.is_some()
             || attrs.allowed.is_some()
             || attrs.optional
+            || attrs.secret
+            || !attrs.aliases.is_empty()
+            || attrs.deprecated.is_some()
         {
             return Err(syn::Error::new(
                 field.span(),
-                "leaf attributes (default, env, allowed, optional) are not \
-                 valid on map-of-nested-struct fields — entry presence is \
+                "leaf attributes (default, env, allowed, optional, secret, alias, \
+                 deprecated) are not valid on map-of-nested-struct fields — entry presence is \
                  already user-controlled, and a single per-field default \
                  has no meaning across an arbitrary set of entry keys.",
             ));
         }
         return Ok(ExpandedField {
             name: name.clone(),
+            aliases,
             entry: quote! {
                 ::clapfig::static_schema::NamedFieldStatic {
                     name: #name,
                     field: ::clapfig::static_schema::FieldStatic::MapOf {
                         schema: #inner_expr,
                         doc: #doc_expr,
+                        merge: #merge_expr,
                     },
                 }
             },
@@ -2211,11 +2699,14 @@ fn expand_field(field: &syn::Field, rename_all: Option<&str>) -> syn::Result<Exp
             || attrs.env.is_some()
             || attrs.allowed.is_some()
             || attrs.optional
+            || attrs.secret
+            || !attrs.aliases.is_empty()
+            || attrs.deprecated.is_some()
         {
             return Err(syn::Error::new(
                 field.span(),
-                "leaf attributes (default, env, allowed, optional) are not \
-                 valid on array-of-nested-schema fields — array entries are \
+                "leaf attributes (default, env, allowed, optional, secret, alias, \
+                 deprecated) are not valid on array-of-nested-schema fields — array entries are \
                  user-supplied (an absent array is the empty array), and a \
                  per-field scalar attribute has no meaning across a list of \
                  entries. For an optional list of a unit-only enum, use \
@@ -2233,6 +2724,7 @@ fn expand_field(field: &syn::Field, rename_all: Option<&str>) -> syn::Result<Exp
             // array). Same deferred-error pattern as `Option<Nested>`.
             return Ok(ExpandedField {
                 name: name.clone(),
+                aliases,
                 entry: quote! {
                     ::clapfig::static_schema::NamedFieldStatic {
                         name: #name,
@@ -2248,6 +2740,12 @@ fn expand_field(field: &syn::Field, rename_all: Option<&str>) -> syn::Result<Exp
                                 default: None,
                                 optional: true,
                                 env: None,
+                                sensitive: false,
+                                from_file: false,
+                                merge: #merge_expr,
+                                aliases: &[],
+                                deprecated: None,
+                                constraints: #constraints_expr,
                             }
                         ),
                     }
@@ -2257,12 +2755,15 @@ fn expand_field(field: &syn::Field, rename_all: Option<&str>) -> syn::Result<Exp
         }
         return Ok(ExpandedField {
             name: name.clone(),
+            aliases,
             entry: quote! {
                 ::clapfig::static_schema::NamedFieldStatic {
                     name: #name,
                     field: ::clapfig::static_schema::FieldStatic::ArrayOf {
                         schema: #inner_expr,
                         doc: #doc_expr,
+                        merge: #merge_expr,
+                        constraints: #constraints_expr,
                     },
                 }
             },
@@ -2330,6 +2831,8 @@ fn expand_field(field: &syn::Field, rename_all: Option<&str>) -> syn::Result<Exp
         None => quote! { None },
     };
 
+    let secret = attrs.secret;
+    let from_file = attrs.from_file;
     let leaf = quote! {
         ::clapfig::static_schema::LeafStatic {
             doc: #doc_expr,
@@ -2337,11 +2840,18 @@ fn expand_field(field: &syn::Field, rename_all: Option<&str>) -> syn::Result<Exp
             default: #default_expr,
             optional: #optional,
             env: #env_expr,
+            sensitive: #secret,
+            from_file: #from_file,
+            merge: #merge_expr,
+            aliases: #aliases_expr,
+            deprecated: #deprecated_expr,
+            constraints: #constraints_expr,
         }
     };
 
     Ok(ExpandedField {
         name: name.clone(),
+        aliases,
         entry: quote! {
             ::clapfig::static_schema::NamedFieldStatic {
                 name: #name,
@@ -2630,7 +3140,7 @@ fn value_static_from_expr_with_kind(expr: &Expr, kind: ScalarKind) -> syn::Resul
             ));
         }
     };
-    if literal_kind != kind {
+    if literal_kind != kind.literal() {
         return Err(syn::Error::new(
             expr.span(),
             format!(
@@ -91,14 +91,21 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             make_builder().handle_and_print(&ConfigAction::Schema { output: None })?;
         }
         "list" => {
-            make_builder().handle_and_print(&ConfigAction::List { scope: None })?;
+            make_builder().handle_and_print(&ConfigAction::List {
+                scope: None,
+                origins: false,
+            })?;
             println!();
         }
         "get" => {
             let key = std::env::args()
                 .nth(2)
                 .ok_or("usage: runtime_schema get <key>")?;
-            make_builder().handle_and_print(&ConfigAction::Get { key, scope: None })?;
+            make_builder().handle_and_print(&ConfigAction::Get {
+                key,
+                scope: None,
+                origin: false,
+            })?;
             println!();
         }
         "set" => {
@@ -0,0 +1,425 @@
+//! Field aliases and deprecations.
+//!
+//! A field's [aliases](crate::runtime::Leaf::aliases) are other names it
+//! accepts in every input. Each input is rewritten to the field's own
+//! name before it is validated — a file after normalize and migrations
+//! ([`canonicalize_table`]), the environment before its containers are
+//! built (the same), URL parameters and overrides as their dotted keys
+//! are read ([`canonicalize_keys`]) — so unknown-key checks, origins, and
+//! the merge only ever see one spelling. One input giving a field two of
+//! its spellings is an [`AliasCollision`], the alias counterpart of a
+//! normalize collision.
+//!
+//! [`deprecated_keys`] finds the fields marked
+//! [deprecated](crate::runtime::Leaf::deprecated) that one input sets, so
+//! the resolve pipeline can warn with that input's origin.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::env::{EnvSources, EnvWinners, env_source_vars};
+use crate::error::ClapfigError;
+use crate::format::{ConfigPath, SpanEntry};
+use crate::normalize::normalize_key;
+use crate::runtime::{DocumentRoot, Schema, Shape, TaggedShape};
+use crate::value::{Map, Value};
+
+/// One alias key rewritten to its field's name. Paths are relative to
+/// the table that was canonicalized; a rename under a renamed parent
+/// already names the parent by its field name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Renamed {
+    pub from: ConfigPath,
+    pub to: ConfigPath,
+}
+
+/// One field spelled more than one way in the same input.
+#[derive(Debug, Clone)]
+pub(crate) struct AliasCollision {
+    /// Table holding the field, relative to the input's root.
+    pub at: ConfigPath,
+    /// The field's own name.
+    pub key: String,
+    /// The spellings the input used, sorted.
+    pub originals: Vec<String>,
+}
+
+impl AliasCollision {
+    /// Wrap into [`ClapfigError::AliasCollision`], naming the input (a
+    /// file path, or the layer for env / URL / override keys).
+    pub(crate) fn into_error(self, input: impl Into<String>) -> ClapfigError {
+        ClapfigError::AliasCollision {
+            input: input.into(),
+            section: self.at.to_string(),
+            key: self.key,
+            originals: self.originals,
+        }
+    }
+}
+
+/// Rewrite every alias key in `table` to its field's name, walking the
+/// declared objects, maps, arrays, and selected tagged variants.
+///
+/// With `normalize_keys`, aliases are compared in normalized spelling,
+/// the form the table's keys already take.
+pub(crate) fn canonicalize_table(
+    table: &mut Map,
+    root: DocumentRoot<'_>,
+    normalize_keys: bool,
+) -> Result<Vec<Renamed>, AliasCollision> {
+    let mut walk = Walk {
+        normalize_keys,
+        renamed: Vec::new(),
+    };
+    let at = ConfigPath::new();
+    match root {
+        DocumentRoot::Object(schema) => walk.object(table, schema, &at)?,
+        DocumentRoot::Map(map) => walk.entries(table, &map.item, &at)?,
+        DocumentRoot::Tagged(tagged) => walk.tagged(table, tagged, &at)?,
+    }
+    for renamed in &walk.renamed {
+        crate::trace::alias_applied(&renamed.from, &renamed.to);
+    }
+    Ok(walk.renamed)
+}
+
+/// [`canonicalize_table`] for one parsed document at `path`, or the
+/// profile section of it `at`: the file's spans are re-keyed along.
+pub(crate) fn canonicalize_document(
+    table: &mut Map,
+    spans: &mut BTreeMap<ConfigPath, SpanEntry>,
+    at: &ConfigPath,
+    root: DocumentRoot<'_>,
+    normalize_keys: bool,
+    path: &Path,
+) -> Result<(), ClapfigError> {
+    let renamed = canonicalize_table(table, root, normalize_keys).map_err(|mut collision| {
+        collision.at = prefixed(at, &collision.at);
+        collision.into_error(path.display().to_string())
+    })?;
+    rekey(spans, at, &renamed);
+    Ok(())
+}
+
+/// [`canonicalize_table`] for the env table, re-keying its sources and
+/// winners. A collision names the variables behind each spelling.
+pub(crate) fn canonicalize_env(
+    table: &mut Map,
+    sources: &mut EnvSources,
+    winners: &mut EnvWinners,
+    root: DocumentRoot<'_>,
+    normalize_keys: bool,
+) -> Result<(), ClapfigError> {
+    let renamed = canonicalize_table(table, root, normalize_keys).map_err(|mut collision| {
+        collision.originals = collision
+            .originals
+            .iter()
+            .flat_map(|key| env_source_vars(sources, &collision.at.clone().key(key.clone())))
+            .collect();
+        collision.into_error("environment")
+    })?;
+    let root = ConfigPath::new();
+    rekey(sources, &root, &renamed);
+    rekey(winners, &root, &renamed);
+    Ok(())
+}
+
+/// Re-key every entry at or under each rename's `from`, `at` the
+/// canonicalized table — the span, env-source, and winner indexes that
+/// travel with a table.
+pub(crate) fn rekey<V>(
+    entries: &mut BTreeMap<ConfigPath, V>,
+    at: &ConfigPath,
+    renamed: &[Renamed],
+) {
+    for renamed in renamed {
+        crate::env::rename_prefix(
+            entries,
+            &prefixed(at, &renamed.from),
+            &prefixed(at, &renamed.to),
+        );
+    }
+}
+
+fn prefixed(at: &ConfigPath, path: &ConfigPath) -> ConfigPath {
+    at.segments()
+        .iter()
+        .chain(path.segments())
+        .cloned()
+        .collect::<Vec<_>>()
+        .into()
+}
+
+struct Walk {
+    normalize_keys: bool,
+    renamed: Vec<Renamed>,
+}
+
+impl Walk {
+    fn spell(&self, alias: &str) -> String {
+        if self.normalize_keys {
+            normalize_key(alias)
+        } else {
+            alias.to_owned()
+        }
+    }
+
+    fn object(
+        &mut self,
+        table: &mut Map,
+        schema: &Schema,
+        at: &ConfigPath,
+    ) -> Result<(), AliasCollision> {
+        for nf in &schema.fields {
+            let used: Vec<String> = nf
+                .field
+                .aliases()
+                .iter()
+                .map(|alias| self.spell(alias))
+                .filter(|alias| table.contains_key(alias))
+                .collect();
+            if !used.is_empty() {
+                if used.len() > 1 || table.contains_key(&nf.name) {
+                    let mut originals = used;
+                    if table.contains_key(&nf.name) {
+                        originals.push(nf.name.clone());
+                    }
+                    originals.sort();
+                    return Err(AliasCollision {
+                        at: at.clone(),
+                        key: nf.name.clone(),
+                        originals,
+                    });
+                }
+                let value = table.remove(&used[0]).expect("alias key is present");
+                table.insert(nf.name.clone(), value);
+                self.renamed.push(Renamed {
+                    from: at.clone().key(used[0].clone()),
+                    to: at.clone().key(nf.name.clone()),
+                });
+            }
+            if let Some(value) = table.get_mut(&nf.name) {
+                self.value(value, &nf.field, &at.clone().key(nf.name.clone()))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn value(
+        &mut self,
+        value: &mut Value,
+        shape: &Shape,
+        at: &ConfigPath,
+    ) -> Result<(), AliasCollision> {
+        match (shape, value) {
+            (Shape::Object(schema), Value::Map(table)) => self.object(table, schema, at),
+            (Shape::Tagged(tagged), Value::Map(table)) => self.tagged(table, tagged, at),
+            (Shape::Map(map), Value::Map(entries)) => self.entries(entries, &map.item, at),
+            (Shape::Array(array), Value::Array(items)) => {
+                for (i, item) in items.iter_mut().enumerate() {
+                    self.value(item, &array.item, &at.clone().index(i))?;
+                }
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn entries(
+        &mut self,
+        entries: &mut Map,
+        item: &Shape,
+        at: &ConfigPath,
+    ) -> Result<(), AliasCollision> {
+        for (key, entry) in entries.iter_mut() {
+            self.value(entry, item, &at.clone().key(key.clone()))?;
+        }
+        Ok(())
+    }
+
+    fn tagged(
+        &mut self,
+        table: &mut Map,
+        tagged: &TaggedShape,
+        at: &ConfigPath,
+    ) -> Result<(), AliasCollision> {
+        match tagged.selected(table) {
+            Some(variant) => self.object(table, &variant.schema, at),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Rewrite alias segments of dotted override keys — the `merge_key` of
+/// each `(merge_key, original_key, value)` entry — to field names.
+///
+/// Keys address fields by segment, so a map entry's key is user data
+/// and a tagged union matches a segment against any of its variants.
+/// Entries that reach one aliased field by two spellings collide, even
+/// when they set different keys beneath it.
+pub(crate) fn canonicalize_keys<T>(
+    entries: &mut [(String, String, T)],
+    root: DocumentRoot<'_>,
+    normalize_keys: bool,
+) -> Result<(), AliasCollision> {
+    let walk = Walk {
+        normalize_keys,
+        renamed: Vec::new(),
+    };
+    // Canonical path of each aliased field reached → the spelling used.
+    let mut spellings: BTreeMap<ConfigPath, String> = BTreeMap::new();
+    for (merge_key, _, _) in entries.iter_mut() {
+        let mut level = Level::of_root(root);
+        let mut at = ConfigPath::new();
+        let mut segments: Vec<String> = merge_key.split('.').map(str::to_owned).collect();
+        for segment in &mut segments {
+            let next = match level {
+                Level::Objects(schemas) => schemas.iter().find_map(|schema| {
+                    schema.fields.iter().find(|nf| {
+                        nf.name == *segment
+                            || nf.field.aliases().iter().any(|a| walk.spell(a) == *segment)
+                    })
+                }),
+                Level::Entries(item) => {
+                    at = at.key(segment.clone());
+                    level = Level::of_shape(item);
+                    continue;
+                }
+                Level::Done => break,
+            };
+            let Some(nf) = next else { break };
+            if !nf.field.aliases().is_empty() {
+                let field = at.clone().key(nf.name.clone());
+                match spellings.get(&field) {
+                    Some(seen) if seen != segment => {
+                        let mut originals = vec![seen.clone(), segment.clone()];
+                        originals.sort();
+                        return Err(AliasCollision {
+                            at,
+                            key: nf.name.clone(),
+                            originals,
+                        });
+                    }
+                    Some(_) => {}
+                    None => {
+                        spellings.insert(field, segment.clone());
+                    }
+                }
+                if *segment != nf.name {
+                    crate::trace::alias_applied(
+                        &at.clone().key(segment.clone()),
+                        &at.clone().key(nf.name.clone()),
+                    );
+                    *segment = nf.name.clone();
+                }
+            }
+            at = at.key(nf.name.clone());
+            level = Level::of_shape(&nf.field);
+        }
+        *merge_key = segments.join(".");
+    }
+    Ok(())
+}
+
+/// What the next segment of a dotted key addresses.
+enum Level<'s> {
+    /// A field of one of these objects (several for a tagged union).
+    Objects(Vec<&'s Schema>),
+    /// A user-keyed entry holding this shape.
+    Entries(&'s Shape),
+    /// Nothing declared further down.
+    Done,
+}
+
+impl<'s> Level<'s> {
+    fn of_root(root: DocumentRoot<'s>) -> Self {
+        match root {
+            DocumentRoot::Object(schema) => Level::Objects(vec![schema]),
+            DocumentRoot::Map(map) => Level::Entries(&map.item),
+            DocumentRoot::Tagged(tagged) => Level::tagged(tagged),
+        }
+    }
+
+    fn of_shape(shape: &'s Shape) -> Self {
+        match shape {
+            Shape::Object(schema) => Level::Objects(vec![schema]),
+            Shape::Map(map) => Level::Entries(&map.item),
+            Shape::Tagged(tagged) => Level::tagged(tagged),
+            Shape::Leaf(_) | Shape::Array(_) => Level::Done,
+        }
+    }
+
+    fn tagged(tagged: &'s TaggedShape) -> Self {
+        Level::Objects(tagged.variants.iter().map(|v| &v.schema).collect())
+    }
+}
+
+/// Every field marked deprecated that `table` sets, with its note.
+/// Paths are relative to `table`; a deprecated field's own children are
+/// not reported again.
+pub(crate) fn deprecated_keys<'s>(
+    table: &Map,
+    root: DocumentRoot<'s>,
+) -> Vec<(ConfigPath, &'s str)> {
+    let mut found = Vec::new();
+    let at = ConfigPath::new();
+    match root {
+        DocumentRoot::Object(schema) => deprecated_in(table, schema, &at, &mut found),
+        DocumentRoot::Map(map) => {
+            for (key, entry) in table {
+                deprecated_value(entry, &map.item, &at.clone().key(key.clone()), &mut found);
+            }
+        }
+        DocumentRoot::Tagged(tagged) => {
+            if let Some(variant) = tagged.selected(table) {
+                deprecated_in(table, &variant.schema, &at, &mut found);
+            }
+        }
+    }
+    found
+}
+
+fn deprecated_in<'s>(
+    table: &Map,
+    schema: &'s Schema,
+    at: &ConfigPath,
+    found: &mut Vec<(ConfigPath, &'s str)>,
+) {
+    for nf in &schema.fields {
+        let Some(value) = table.get(&nf.name) else {
+            continue;
+        };
+        let path = at.clone().key(nf.name.clone());
+        match nf.field.deprecated() {
+            Some(note) => found.push((path, note)),
+            None => deprecated_value(value, &nf.field, &path, found),
+        }
+    }
+}
+
+fn deprecated_value<'s>(
+    value: &Value,
+    shape: &'s Shape,
+    at: &ConfigPath,
+    found: &mut Vec<(ConfigPath, &'s str)>,
+) {
+    match (shape, value) {
+        (Shape::Object(schema), Value::Map(table)) => deprecated_in(table, schema, at, found),
+        (Shape::Tagged(tagged), Value::Map(table)) => {
+            if let Some(variant) = tagged.selected(table) {
+                deprecated_in(table, &variant.schema, at, found);
+            }
+        }
+        (Shape::Map(map), Value::Map(entries)) => {
+            for (key, entry) in entries {
+                deprecated_value(entry, &map.item, &at.clone().key(key.clone()), found);
+            }
+        }
+        (Shape::Array(array), Value::Array(items)) => {
+            for (i, item) in items.iter().enumerate() {
+                deprecated_value(item, &array.item, &at.clone().index(i), found);
+            }
+        }
+        _ => {}
+    }
+}
@@ -18,18 +18,25 @@ use std::sync::{Arc, Mutex};
 
 use serde::Serialize;
 
+use crate::diagnostics::{Warning, WarningKind};
+use crate::diff::ConfigDiff;
 use crate::error::{ClapfigError, DiscoveryRecord, FileProbe, ProbeOutcome};
 use crate::file;
 use crate::flatten;
-use crate::format::{self, FormatAdapter, FormatRegistry};
+use crate::format::{self, ConfigPath, FormatAdapter, FormatRegistry};
+use crate::migrate::Migration;
 use crate::ops::{self, ConfigResult};
+use crate::origin::OriginMap;
 use crate::overrides;
 use crate::persist;
+use crate::provenance::{Candidate, Explanation, Provenance};
+use crate::redact;
 use crate::resolve::{self, ResolveInput};
 use crate::runtime::{DocumentRoot, MapShape, Schema, Shape, TaggedShape};
 use crate::strict::{StrictnessOverrides, UnknownKeyHook};
-use crate::types::{ConfigAction, Layer, SearchMode, SearchPath};
+use crate::types::{CacheFreshness, ConfigAction, DiffTarget, Layer, SearchMode, SearchPath};
 use crate::value::{Map, Value};
+use crate::watch::{ConfigWatch, WatchOptions};
 
 /// Post-merge validation hook for the Map-out path: receives the merged
 /// value [`Map`]. (The typed path wraps its `Fn(&C)` hook into this shape
@@ -84,14 +91,8 @@ struct StoredRoot(Arc<Shape>);
 
 impl StoredRoot {
     fn as_document(&self) -> DocumentRoot<'_> {
-        match self.0.as_ref() {
-            Shape::Object(schema) => DocumentRoot::Object(schema),
-            Shape::Map(map) => DocumentRoot::Map(map),
-            Shape::Tagged(tagged) => DocumentRoot::Tagged(tagged),
-            Shape::Leaf(_) | Shape::Array(_) => {
-                unreachable!("illegal document roots are rejected by require_document_root")
-            }
-        }
+        DocumentRoot::of(self.0.as_ref())
+            .expect("illegal document roots are rejected by require_document_root")
     }
 
     fn as_shape(&self) -> &Shape {
@@ -109,17 +110,30 @@ pub struct Builder {
     app_name: Option<String>,
     file_naming: Option<FileNaming>,
     formats: Option<Vec<String>>,
+    format_adapters: Vec<Arc<dyn FormatAdapter>>,
     search_paths: Option<Vec<SearchPath>>,
     search_mode: SearchMode,
+    cache_freshness: CacheFreshness,
     persist_scopes: Vec<(String, SearchPath)>,
+    include_key: Option<String>,
+    profile_key: Option<String>,
+    profile: Option<String>,
     env_prefix: Option<String>,
     env_enabled: bool,
+    env_list_separator: String,
+    env_file_secrets: bool,
+    interpolate: bool,
+    unset_marker: Option<String>,
+    migrations: Vec<Migration>,
+    schema_version_key: Option<String>,
     strict: bool,
     normalize_keys: bool,
     #[cfg(feature = "url")]
     url_overrides: Vec<(String, Value)>,
     cli_overrides: Vec<(String, Value)>,
+    cli_assignments: Vec<String>,
     layer_order: Option<Vec<Layer>>,
+    deny_warnings: Vec<WarningKind>,
     post_validate: Option<PostValidateHook>,
     strict_at_overrides: Vec<(String, bool)>,
     unknown_key_hook: Option<UnknownKeyHook>,
@@ -160,17 +174,30 @@ impl Builder {
             app_name: None,
             file_naming: None,
             formats: None,
+            format_adapters: Vec::new(),
             search_paths: None,
             search_mode: SearchMode::default(),
+            cache_freshness: CacheFreshness::default(),
             persist_scopes: Vec::new(),
+            include_key: None,
+            profile_key: None,
+            profile: None,
             env_prefix: None,
             env_enabled: true,
+            env_list_separator: crate::env::DEFAULT_LIST_SEPARATOR.into(),
+            env_file_secrets: false,
+            interpolate: false,
+            unset_marker: None,
+            migrations: Vec::new(),
+            schema_version_key: None,
             strict: true,
             normalize_keys: false,
             #[cfg(feature = "url")]
             url_overrides: Vec::new(),
             cli_overrides: Vec::new(),
+            cli_assignments: Vec::new(),
             layer_order: None,
+            deny_warnings: Vec::new(),
             post_validate: None,
             strict_at_overrides: Vec::new(),
             unknown_key_hook: None,
@@ -215,10 +242,12 @@ impl Builder {
     }
 
     /// Set the enabled formats for stem-based discovery, in preference
-    /// order (canonical names: `"toml"`, `"yaml"`, `"json"`).
+    /// order (canonical names: `"toml"`, `"yaml"`, `"json"`, `"json5"`,
+    /// `"ini"`, plus the name of every [`format_adapter`](Self::format_adapter)).
     ///
     /// Formats are opt-in and ordered — never inferred from compiled-in
-    /// cargo features; the default with no call is TOML only. The first
+    /// cargo features; the default with no call is TOML followed by any
+    /// registered custom adapters, in registration order. The first
     /// entry is the app's **preferred format**: `config gen` with no
     /// output path renders it, and `config set` against a scope with no
     /// existing file creates `<stem>.<preferred extension>`. An unknown
@@ -234,6 +263,28 @@ impl Builder {
         self
     }
 
+    /// Register a custom [`FormatAdapter`] — a format clapfig does not
+    /// ship (a legacy in-house format).
+    ///
+    /// A registered adapter takes part everywhere the built-ins do: it
+    /// is selectable by name in [`formats`](Self::formats), probed by
+    /// [`file_stem`](Self::file_stem) discovery, picked by extension for
+    /// [`file_name`](Self::file_name), persist scopes, and `config gen`
+    /// output paths, and used by `config set` / `config unset` to edit
+    /// its files. Parse errors render against its spans like any other
+    /// format's.
+    ///
+    /// Without a [`formats`](Self::formats) call, stem discovery enables
+    /// TOML followed by every registered adapter in registration order;
+    /// name the adapter in `formats(...)` to control preference. An
+    /// adapter whose [`name`](FormatAdapter::name) or extension matches a
+    /// built-in replaces it; of two registered adapters with the same
+    /// name, the later wins.
+    pub fn format_adapter(mut self, adapter: Box<dyn FormatAdapter>) -> Self {
+        self.format_adapters.push(Arc::from(adapter));
+        self
+    }
+
     /// Replace the default search paths entirely.
     ///
     /// Paths are listed in **priority-ascending** order: the last entry has
@@ -263,6 +314,19 @@ impl Builder {
         self
     }
 
+    /// Set when a [`Resolver`] built from this builder re-reads files it
+    /// has already cached (default: [`CacheFreshness::Never`]).
+    ///
+    /// Long-lived resolvers that call [`resolve_at`](Resolver::resolve_at)
+    /// repeatedly — a language server resolving per document, say — use
+    /// [`Metadata`](CacheFreshness::Metadata) to see edits without
+    /// rebuilding the resolver. One-shot [`load`](Self::load) reads each
+    /// file once either way.
+    pub fn cache_freshness(mut self, freshness: CacheFreshness) -> Self {
+        self.cache_freshness = freshness;
+        self
+    }
+
     /// Add a named persist scope.
     ///
     /// Scopes are named config file targets for `config set`/`unset` (and
@@ -284,6 +348,67 @@ impl Builder {
         self
     }
 
+    /// Let config files include other files through a top-level `key`
+    /// (off by default), as in `include = ["common.toml", "db/*.toml"]`.
+    ///
+    /// Paths are relative to the including file, and a `*` or `?` in the
+    /// file name matches files in that directory in name order. Included
+    /// files load as overlays just below the file that includes them, in
+    /// any enabled format, with their own spans and origins. A missing
+    /// file is [`ClapfigError::IncludeNotFound`], and a loop is
+    /// [`ClapfigError::IncludeCycle`]. Pick a key the schema does not
+    /// declare, since the directive is removed before validation.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `key` is empty.
+    pub fn include_key(mut self, key: &str) -> Self {
+        assert!(!key.is_empty(), "clapfig: include key must not be empty");
+        self.include_key = Some(key.to_string());
+        self
+    }
+
+    /// Let config files hold named profiles in tables under a top-level
+    /// `key` (off by default), as in `[profile.prod]`.
+    ///
+    /// While a profile is active, each file's section for it is layered
+    /// just above that file, and a sibling file such as `myapp.prod.toml`
+    /// next to a discovered `myapp.toml` loads just above it too. The
+    /// active profile is the one [`profile`](Self::profile) names, else
+    /// the `{PREFIX}_PROFILE` environment variable (`MYAPP_PROFILE`),
+    /// else none. Every section is validated against the schema whether
+    /// or not it is active. Pick a key the schema does not declare, since
+    /// the profile tables are removed before validation.
+    ///
+    /// The environment variable is only read once this is set: without
+    /// `profile_key` or [`profile`](Self::profile), `MYAPP_PROFILE` is
+    /// ignored and a `[profile.*]` table is an unknown key.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `key` is empty.
+    pub fn profile_key(mut self, key: &str) -> Self {
+        assert!(!key.is_empty(), "clapfig: profile key must not be empty");
+        self.profile_key = Some(key.to_string());
+        self
+    }
+
+    /// Select the active profile, ahead of `MYAPP_PROFILE`. Enables
+    /// profiles under the `profile` key unless
+    /// [`profile_key`](Self::profile_key) chose another. A profile no
+    /// file defines loads the base configuration unchanged. To honour
+    /// `MYAPP_PROFILE` when no name is passed here, set
+    /// [`profile_key`](Self::profile_key) as well.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` is empty.
+    pub fn profile(mut self, name: &str) -> Self {
+        assert!(!name.is_empty(), "clapfig: profile name must not be empty");
+        self.profile = Some(name.to_string());
+        self
+    }
+
     /// Override the environment variable prefix (default: uppercased
     /// `app_name`).
     pub fn env_prefix(mut self, prefix: &str) -> Self {
@@ -297,6 +422,114 @@ impl Builder {
         self
     }
 
+    /// Set the separator between the items of an env list value on an
+    /// array field (default: `","`, as in `MYAPP__HOSTS=a,b,c`). Items
+    /// are trimmed; an inline `[...]` literal ignores the separator.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `separator` is empty.
+    pub fn env_list_separator(mut self, separator: &str) -> Self {
+        assert!(
+            !separator.is_empty(),
+            "clapfig: env list separator must not be empty"
+        );
+        self.env_list_separator = separator.to_string();
+        self
+    }
+
+    /// Read Docker-style `_FILE` variables (default: `false`). With this
+    /// on, `MYAPP__DB__PASSWORD_FILE=/run/secrets/db` sets `db.password`
+    /// to the contents of `/run/secrets/db`, one trailing newline
+    /// trimmed, for any leaf the schema declares. The origin names the
+    /// variable and the file. Setting both `MYAPP__DB__PASSWORD` and its
+    /// `_FILE` form, or naming a file that cannot be read, is an
+    /// [`InvalidValue`](ClapfigError::InvalidValue) at `db.password`.
+    ///
+    /// A `_FILE` key the schema declares itself is left alone.
+    pub fn env_file_secrets(mut self, enabled: bool) -> Self {
+        self.env_file_secrets = enabled;
+        self
+    }
+
+    /// Expand `${...}` references in string values (default: `false`).
+    ///
+    /// After every layer merges, `${database.host}` is replaced by that
+    /// key's resolved value, `${env:HOME}` by a process environment
+    /// variable, and `$${` is a literal `${`. The expanded string is then
+    /// parsed by the field's declared type and validated as usual.
+    /// A missing key or variable, a reference to a table or array, and a
+    /// reference cycle are each an
+    /// [`InvalidValue`](ClapfigError::InvalidValue) located at the string
+    /// that holds the reference. [`Provenance::raw`] keeps the string as
+    /// written, and `config get` shows it beside the expanded value.
+    pub fn interpolate(mut self, enabled: bool) -> Self {
+        self.interpolate = enabled;
+        self
+    }
+
+    /// Treat the string `marker` as "unset this key" (default: off).
+    ///
+    /// A higher layer that supplies the marker drops whatever the layers
+    /// below set for that key: an optional leaf becomes absent again, a
+    /// leaf with a schema default reverts to it, and a section or array
+    /// is removed as a whole. It works from every input —
+    /// `port = "<unset>"` in a file, `MYAPP__PORT=<unset>`, a URL
+    /// parameter, `cli_override("port", "<unset>")`, or `-c port=<unset>`. The origin map
+    /// records the reset, naming the input that supplied the marker, and
+    /// `config list --origins` marks it `(reset)`. A required leaf with
+    /// no default that is reset is
+    /// [`MissingRequired`](ClapfigError::MissingRequired) as usual.
+    ///
+    /// Pick a string no real value uses; `"<unset>"` is a common choice.
+    ///
+    /// # Panics
+    ///
+    /// If `marker` is empty.
+    pub fn unset_marker(mut self, marker: impl Into<String>) -> Self {
+        let marker = marker.into();
+        assert!(
+            !marker.is_empty(),
+            "clapfig: the unset marker must not be empty"
+        );
+        self.unset_marker = Some(marker);
+        self
+    }
+
+    /// Register a [`Migration`] for a key the schema renamed, moved, or
+    /// removed. Migrations apply in registration order.
+    ///
+    /// Loading migrates each config file in memory before validation
+    /// and raises [`Warning::UnmigratedFile`] for a file that still
+    /// needs any;
+    /// [`ConfigAction::Migrate`] (`config migrate`) rewrites the scope
+    /// files in place. See [`crate::Migration`].
+    pub fn migration(mut self, migration: Migration) -> Self {
+        self.migrations.push(migration);
+        self
+    }
+
+    /// Keep each config file's schema version under the top-level `key`
+    /// (off by default), as in `schema_version = 2`.
+    ///
+    /// A migration marked [`at_version(n)`](Migration::at_version) then
+    /// applies only to files whose version is below `n`; a file without
+    /// the key is version 0. `config migrate` writes the latest version
+    /// into each file it migrates. The key is removed before validation,
+    /// so pick one the schema does not declare.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `key` is empty.
+    pub fn schema_version_key(mut self, key: &str) -> Self {
+        assert!(
+            !key.is_empty(),
+            "clapfig: schema version key must not be empty"
+        );
+        self.schema_version_key = Some(key.to_string());
+        self
+    }
+
     /// Enable or disable strict mode (default: `true`).
     ///
     /// This is the **whole-resolution default** in the strictness cascade —
@@ -423,6 +656,21 @@ impl Builder {
         self
     }
 
+    /// Fail the load with [`ClapfigError::DeniedWarnings`] when it raises
+    /// a [`Warning`](crate::Warning) of one of these kinds, instead of
+    /// returning it from
+    /// [`load_with_diagnostics`](Self::load_with_diagnostics). Meant for
+    /// CI; [`WarningKind::ALL`] denies every kind. Repeated calls add to
+    /// the set.
+    pub fn deny_warnings(mut self, kinds: impl IntoIterator<Item = WarningKind>) -> Self {
+        for kind in kinds {
+            if !self.deny_warnings.contains(&kind) {
+                self.deny_warnings.push(kind);
+            }
+        }
+        self
+    }
+
     /// Post-merge validation hook. Receives the merged value [`Map`].
     ///
     /// Use it for constraints the schema can't express: numeric ranges,
@@ -461,8 +709,10 @@ impl Builder {
     ///
     /// Parses the query string (e.g. `"port=9090&database.url=pg://prod"`)
     /// into config overrides. Keys use `.` for nesting, values are
-    /// percent-decoded and parsed with the same heuristic as env vars
-    /// (bool > int > float > string). A leading `?` is stripped if present.
+    /// percent-decoded and parsed by the schema like env vars: toward the
+    /// declared leaf type, with the bool > int > float > string heuristic
+    /// only for `Value` leaves and undeclared keys. A leading `?` is
+    /// stripped if present.
     ///
     /// By default, URL parameters sit between env vars and CLI overrides in
     /// precedence: defaults < files < env < **URL** < CLI. This position can
@@ -483,6 +733,60 @@ impl Builder {
         self
     }
 
+    /// Add `key=value` assignments as CLI overrides, the way `git -c` and
+    /// `cargo --config` take them. The clap adapter collects them from a
+    /// repeatable `-c` / `--set` option.
+    ///
+    /// Each value is parsed toward its key's declared type exactly as
+    /// `config set` parses it, so `-c port=8080` is an integer and
+    /// `-c tags=["a","b"]` an array. The assignments are checked before
+    /// any file is read: an unknown key fails with
+    /// [`KeyNotFound`](ClapfigError::KeyNotFound) and the nearest-key
+    /// suggestion, and a value the key cannot hold with
+    /// [`InvalidValue`](ClapfigError::InvalidValue). They apply above
+    /// the other programmatic overrides.
+    pub fn cli_assignments<I, S>(mut self, assignments: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.cli_assignments
+            .extend(assignments.into_iter().map(Into::into));
+        self
+    }
+
+    /// The [`cli_assignments`](Self::cli_assignments) parsed into
+    /// override pairs.
+    fn parsed_assignments(&self) -> Result<Vec<(String, Value)>, ClapfigError> {
+        self.cli_assignments
+            .iter()
+            .map(|assignment| {
+                crate::persist::parse_assignment(
+                    self.schema.as_shape(),
+                    assignment,
+                    self.normalize_keys,
+                    self.unset_marker.as_deref(),
+                )
+            })
+            .collect()
+    }
+
+    /// Add CLI overrides from the flags [`ConfigFlags`](crate::ConfigFlags)
+    /// generated, as clap parsed them. Only flags given on the command
+    /// line are added, typed by their leaves. A schema whose root is not
+    /// an object has no flags, and adds nothing.
+    #[cfg(feature = "clap")]
+    pub fn cli_overrides_from_matches(
+        mut self,
+        flags: &crate::ConfigFlags,
+        matches: &clap::ArgMatches,
+    ) -> Self {
+        if let DocumentRoot::Object(schema) = self.schema.as_document() {
+            self.cli_overrides.extend(flags.overrides(schema, matches));
+        }
+        self
+    }
+
     /// Add CLI overrides from any serializable source, auto-matching by
     /// field name.
     ///
@@ -528,22 +832,27 @@ impl Builder {
     ///   a hard [`ClapfigError::UnknownFormat`] — never a silent TOML
     ///   fallback.
     /// - Stem mode enables the [`formats`](Self::formats) list in order
-    ///   (default: TOML only). The list must be non-empty (the preferred
-    ///   format is the first entry) and free of repeats (a repeated name
-    ///   would collect the same file twice and misreport it as
-    ///   ambiguous); violations are
+    ///   (default: TOML, then every custom adapter). The list must be
+    ///   non-empty (the preferred format is the first entry) and free of
+    ///   repeats (a repeated name would collect the same file twice and
+    ///   misreport it as ambiguous); violations are
     ///   [`ClapfigError::InvalidFormats`].
     fn effective_registry(&self) -> Result<FormatRegistry, ClapfigError> {
         let mut registry = FormatRegistry::new();
         match self.effective_naming()? {
             FileNaming::Exact(name) => {
-                registry.register(adapter_for_explicit_path(Path::new(&name))?);
+                registry.register(self.adapter_for_explicit_path(Path::new(&name))?);
             }
             FileNaming::Stem(_) => {
-                let names = self
-                    .formats
-                    .clone()
-                    .unwrap_or_else(|| vec!["toml".to_string()]);
+                let names = self.formats.clone().unwrap_or_else(|| {
+                    let mut names = vec!["toml".to_string()];
+                    for adapter in &self.format_adapters {
+                        if !names.iter().any(|n| n == adapter.name()) {
+                            names.push(adapter.name().to_string());
+                        }
+                    }
+                    names
+                });
                 if names.is_empty() {
                     return Err(ClapfigError::InvalidFormats {
                         reason: "formats(...) must enable at least one format".into(),
@@ -557,12 +866,12 @@ impl Builder {
                         });
                     }
                     seen.push(name);
-                    let adapter = format::builtin_adapter(name).ok_or_else(|| {
-                        ClapfigError::UnknownFormat {
-                            name: name.clone(),
-                            available: format::builtin_names(),
-                        }
-                    })?;
+                    let adapter =
+                        self.adapter_named(name)
+                            .ok_or_else(|| ClapfigError::UnknownFormat {
+                                name: name.clone(),
+                                available: self.available_formats(),
+                            })?;
                     registry.register(adapter);
                 }
             }
@@ -570,6 +879,62 @@ impl Builder {
         Ok(registry)
     }
 
+    /// The adapter named `name`: the last custom adapter registered
+    /// under it, else the built-in.
+    fn adapter_named(&self, name: &str) -> Option<Box<dyn FormatAdapter>> {
+        match self.format_adapters.iter().rev().find(|a| a.name() == name) {
+            Some(adapter) => Some(Box::new(Arc::clone(adapter))),
+            None => format::builtin_adapter(name),
+        }
+    }
+
+    /// Every selectable format name — built-ins, then custom adapters —
+    /// for [`ClapfigError::UnknownFormat`] messages.
+    fn available_formats(&self) -> Vec<String> {
+        let mut names = format::builtin_names();
+        for adapter in &self.format_adapters {
+            if !names.iter().any(|n| n == adapter.name()) {
+                names.push(adapter.name().to_string());
+            }
+        }
+        names
+    }
+
+    /// Select the format adapter for an explicit file path by its
+    /// extension (independent of the enabled-formats list); custom
+    /// adapters are consulted before the built-ins. Extensionless names
+    /// fall back to TOML (the rc-style preservation rule); an extension
+    /// no adapter claims is a hard [`ClapfigError::UnknownFormat`] —
+    /// never a silent TOML fallback that would write or parse one
+    /// format's content under another format's extension.
+    fn adapter_for_explicit_path(
+        &self,
+        path: &Path,
+    ) -> Result<Box<dyn FormatAdapter>, ClapfigError> {
+        match path.extension() {
+            None => Ok(self
+                .adapter_named("toml")
+                .expect("toml adapter is built in")),
+            Some(ext) => {
+                let ext = ext.to_string_lossy().to_ascii_lowercase();
+                let custom = self
+                    .format_adapters
+                    .iter()
+                    .rev()
+                    .find(|a| a.extensions().contains(&ext.as_str()));
+                match custom {
+                    Some(adapter) => Ok(Box::new(Arc::clone(adapter))),
+                    None => format::builtin_adapter_for_extension(&ext).ok_or_else(|| {
+                        ClapfigError::UnknownFormat {
+                            name: ext,
+                            available: self.available_formats(),
+                        }
+                    }),
+                }
+            }
+        }
+    }
+
     fn effective_search_paths(&self) -> Vec<SearchPath> {
         let mut paths = if let Some(paths) = &self.search_paths {
             paths.clone()
@@ -595,6 +960,70 @@ impl Builder {
         Ok(Some(app.to_uppercase()))
     }
 
+    /// The key profile tables live under: the configured one, or
+    /// `profile` once a profile is selected. `None` while profiles are off.
+    fn effective_profile_key(&self) -> Option<String> {
+        self.profile_key.clone().or_else(|| {
+            self.profile
+                .as_ref()
+                .map(|_| crate::profile::DEFAULT_KEY.to_string())
+        })
+    }
+
+    /// The root-doc lines `config gen` adds while profiles are enabled:
+    /// where profile values go and how one is selected, in `adapter`'s
+    /// file naming.
+    fn profile_notes(&self, adapter: &dyn FormatAdapter) -> Vec<String> {
+        let Some(key) = self.effective_profile_key() else {
+            return Vec::new();
+        };
+        let selected = match self.effective_env_prefix().ok().flatten() {
+            Some(prefix) => format!(
+                "while that profile is active ({}=<name>).",
+                crate::profile::env_var(&prefix)
+            ),
+            None => "while that profile is active.".to_string(),
+        };
+        let mut notes = vec![
+            format!("Profiles: values under `{key}.<name>` override these settings"),
+            selected,
+        ];
+        let sibling = match self.effective_naming().ok() {
+            Some(FileNaming::Exact(name)) => {
+                crate::profile::sibling_path(Path::new(&name), "<name>")
+                    .map(|path| path.display().to_string())
+            }
+            Some(FileNaming::Stem(stem)) => adapter
+                .extensions()
+                .first()
+                .map(|ext| format!("{stem}.<name>.{ext}")),
+            None => None,
+        };
+        if let Some(sibling) = sibling {
+            notes.push(format!(
+                "A sibling file such as {sibling} overrides them the same way."
+            ));
+        }
+        notes
+    }
+
+    /// The active profile: [`profile`](Self::profile), else a non-empty
+    /// `{PREFIX}_PROFILE` variable while profiles and env are enabled.
+    fn effective_profile(&self) -> Result<Option<String>, ClapfigError> {
+        if self.profile.is_some() {
+            return Ok(self.profile.clone());
+        }
+        if self.profile_key.is_none() {
+            return Ok(None);
+        }
+        let Some(prefix) = self.effective_env_prefix()? else {
+            return Ok(None);
+        };
+        Ok(std::env::var(crate::profile::env_var(&prefix))
+            .ok()
+            .filter(|name| !name.is_empty()))
+    }
+
     /// Build a reusable [`Resolver`] that captures the current
     /// builder state and can be called repeatedly with
     /// [`resolve_at(dir)`](Resolver::resolve_at), each call
@@ -612,12 +1041,16 @@ impl Builder {
     ///
     /// Returns [`ClapfigError::AppNameRequired`] if `.app_name()` was not
     /// called on the builder.
-    pub fn build_resolver(self) -> Result<Resolver, ClapfigError> {
+    pub fn build_resolver(mut self) -> Result<Resolver, ClapfigError> {
+        let assignments = self.parsed_assignments()?;
+        self.cli_overrides.extend(assignments);
         let app_name = self.effective_app_name()?.to_string();
         let naming = self.effective_naming()?;
         let registry = self.effective_registry()?;
         let search_paths = self.effective_search_paths();
         let env_prefix = self.effective_env_prefix()?;
+        let profile_key = self.effective_profile_key();
+        let profile = self.effective_profile()?;
         let layer_order = self.layer_order;
         let order = layer_order
             .clone()
@@ -647,8 +1080,18 @@ impl Builder {
             registry,
             search_paths,
             search_mode: self.search_mode,
+            cache_freshness: self.cache_freshness,
+            include_key: self.include_key,
+            profile_key,
+            profile,
             env_prefix,
             env_vars,
+            env_list_separator: self.env_list_separator,
+            env_file_secrets: self.env_file_secrets,
+            interpolate: self.interpolate,
+            unset_marker: self.unset_marker,
+            migrations: self.migrations,
+            schema_version_key: self.schema_version_key,
             strict_default: self.strict,
             strict_overrides,
             unknown_key_hook: self.unknown_key_hook,
@@ -657,6 +1100,7 @@ impl Builder {
             url_overrides: self.url_overrides,
             cli_overrides: self.cli_overrides,
             layer_order,
+            deny_warnings: self.deny_warnings,
             post_validate: self.post_validate.map(Arc::new),
             file_cache: Mutex::new(std::collections::HashMap::new()),
         })
@@ -695,6 +1139,116 @@ impl Builder {
         self.build_resolver()?.resolve_at_with_unknowns(start_dir)
     }
 
+    /// Same as [`load`](Self::load) but also returns the
+    /// [`Warning`](crate::Warning)s the load raised — deprecated keys,
+    /// environment variables shadowing file values, world-readable files
+    /// holding secrets, one config file name in several formats — in the
+    /// order the pipeline met them. Kinds the
+    /// [`deny_warnings`](Self::deny_warnings) policy names fail the load
+    /// instead.
+    pub fn load_with_diagnostics(self) -> Result<(Map, Vec<Warning>), ClapfigError> {
+        let start_dir = std::env::current_dir().map_err(|e| ClapfigError::IoError {
+            path: PathBuf::from("."),
+            source: e,
+        })?;
+        self.build_resolver()?
+            .resolve_at_with_diagnostics(start_dir)
+    }
+
+    /// Same as [`load`](Self::load) but also returns the
+    /// [`Provenance`] of every resolved value: which input type supplied
+    /// it, and the file / span, environment variable, URL query key, or
+    /// override key behind it. Schema-filled defaults report
+    /// [`InputType::Default`](crate::InputType::Default).
+    pub fn load_with_origins(self) -> Result<(Map, Provenance), ClapfigError> {
+        let start_dir = std::env::current_dir().map_err(|e| ClapfigError::IoError {
+            path: PathBuf::from("."),
+            source: e,
+        })?;
+        self.build_resolver()?.resolve_at_with_origins(start_dir)
+    }
+
+    /// Explain where `key`'s value comes from: every input that supplied
+    /// it, in precedence order, and which one won. See
+    /// [`Resolver::explain_at`]; this anchors at the current directory,
+    /// like [`load`](Self::load).
+    pub fn explain(self, key: &str) -> Result<Explanation, ClapfigError> {
+        let start_dir = std::env::current_dir().map_err(|e| ClapfigError::IoError {
+            path: PathBuf::from("."),
+            source: e,
+        })?;
+        self.build_resolver()?.explain_at(start_dir, key)
+    }
+
+    /// Compare the configurations at two [`DiffTarget`]s: every leaf that
+    /// was added, removed, or changed from `left` to `right`, with where
+    /// each side's value came from. A directory side resolves the full
+    /// merged view anchored there (like
+    /// [`Resolver::resolve_at`], sharing one resolver's file cache); a
+    /// scope side reads that scope's file alone. See [`ConfigDiff`].
+    pub fn diff(self, left: &DiffTarget, right: &DiffTarget) -> Result<ConfigDiff, ClapfigError> {
+        // Scope reads borrow the builder; directory resolutions consume it.
+        let mut left_side = self.scope_side(left)?;
+        let mut right_side = self.scope_side(right)?;
+        let shape = Arc::clone(&self.schema.0);
+        if left_side.is_none() || right_side.is_none() {
+            let resolver = self.build_resolver()?;
+            if let DiffTarget::Dir(dir) = left {
+                left_side = Some(resolver.resolve_at_with_origins(dir)?);
+            }
+            if let DiffTarget::Dir(dir) = right {
+                right_side = Some(resolver.resolve_at_with_origins(dir)?);
+            }
+        }
+        let (left, left_origins) = left_side.expect("every target is a scope or a directory");
+        let (right, right_origins) = right_side.expect("every target is a scope or a directory");
+        Ok(ConfigDiff::between(&shape, &left, &right).with_origins(&left_origins, &right_origins))
+    }
+
+    /// A scope [`DiffTarget`]'s file as a table plus per-value origins;
+    /// `None` for a directory target. A missing file is an empty side.
+    fn scope_side(&self, target: &DiffTarget) -> Result<Option<(Map, Provenance)>, ClapfigError> {
+        let DiffTarget::Scope(name) = target else {
+            return Ok(None);
+        };
+        let (path, adapter) = self.resolve_scope_persist_path(Some(name))?;
+        let content = match std::fs::read_to_string(&path) {
+            Ok(c) => c,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(Some((Map::new(), Provenance::default())));
+            }
+            Err(e) => return Err(ClapfigError::IoError { path, source: e }),
+        };
+        let parsed = adapter
+            .parse(&content)
+            .map_err(|e| ClapfigError::ParseError {
+                path: path.clone(),
+                source: Box::new(e),
+                source_text: Some(Arc::from(content.as_str())),
+            })?;
+        let format::Parsed { value, mut spans } = parsed;
+        let mut table = match value {
+            Value::Map(map) => map,
+            other => {
+                return Err(ClapfigError::invalid_value(
+                    path.display().to_string(),
+                    format!(
+                        "config documents must be maps at the root, got {}",
+                        other.type_str()
+                    ),
+                ));
+            }
+        };
+        if self.normalize_keys {
+            crate::normalize::normalize_table_and_spans(&mut table, &mut spans)
+                .map_err(|c| c.into_error(&path))?;
+        }
+        let (source, spans) =
+            ops::scope_file_source(self.schema.as_shape(), &table, spans, &content);
+        let origins = crate::origin::origin_map_from_file(&table, &spans, &path, &source);
+        Ok(Some((table, Provenance::new(origins))))
+    }
+
     /// Dispatch a [`ConfigAction`] and print the result to stdout.
     ///
     /// Convenience wrapper around [`handle()`](Self::handle) for CLI apps
@@ -785,7 +1339,7 @@ impl Builder {
                 }
             }
         };
-        let adapter = adapter_for_explicit_path(&path)?;
+        let adapter = self.adapter_for_explicit_path(&path)?;
         Ok((path, adapter))
     }
 
@@ -794,23 +1348,32 @@ impl Builder {
     /// Returns a [`ConfigResult`]; the typed path's `handle` delegates
     /// here, so downstream rendering / printing code is shared.
     pub fn handle(self, action: &ConfigAction) -> Result<ConfigResult, ClapfigError> {
+        // Bad `-c` assignments fail every action, not only those that
+        // resolve.
+        self.parsed_assignments()?;
         match action {
-            ConfigAction::List { scope } => match scope {
+            ConfigAction::List { scope, origins } => match scope {
                 None => {
                     // The merged view spans formats; display renders in
                     // the preferred (first-enabled) format's spelling.
+                    let shape = Arc::clone(&self.schema.0);
                     let registry = self.effective_registry()?;
-                    let table = self.load()?;
-                    Ok(list_from_table(
-                        &table,
-                        registry
-                            .preferred()
-                            .expect("effective_registry always registers an adapter"),
-                    ))
+                    let preferred = registry
+                        .preferred()
+                        .expect("effective_registry always registers an adapter");
+                    let profile = self.effective_profile()?;
+                    let listing = if *origins {
+                        let (table, provenance) = self.load_with_origins()?;
+                        list_from_table(shape.as_ref(), table, Some(&provenance), preferred)
+                    } else {
+                        let table = self.load()?;
+                        list_from_table(shape.as_ref(), table, None, preferred)
+                    };
+                    Ok(listing.with_profile(preferred, profile))
                 }
                 Some(name) => {
                     let (path, adapter) = self.resolve_scope_persist_path(Some(name))?;
-                    ops::list_scope_file(adapter.as_ref(), &path)
+                    ops::list_scope_file(adapter.as_ref(), self.schema.as_shape(), &path, *origins)
                 }
             },
             ConfigAction::Gen { output } => {
@@ -828,16 +1391,17 @@ impl Builder {
                                 let preferred = registry
                                     .preferred()
                                     .expect("effective_registry always registers an adapter");
-                                format::builtin_adapter(preferred.name())
-                                    .expect("preferred adapters are built in")
+                                self.adapter_named(preferred.name())
+                                    .expect("preferred adapters are registered by name")
                             }
-                            Some(_) => adapter_for_explicit_path(path)?,
+                            Some(_) => self.adapter_for_explicit_path(path)?,
                         };
-                        let template = ops::generate_template(
-                            adapter.as_ref(),
+                        let shape = ops::with_root_notes(
                             self.schema.as_shape(),
-                            self.normalize_keys,
-                        )?;
+                            &self.profile_notes(adapter.as_ref()),
+                        );
+                        let template =
+                            ops::generate_template(adapter.as_ref(), &shape, self.normalize_keys)?;
                         if let Some(parent) = path.parent() {
                             std::fs::create_dir_all(parent).map_err(|e| ClapfigError::IoError {
                                 path: parent.to_path_buf(),
@@ -856,11 +1420,12 @@ impl Builder {
                         let preferred = registry
                             .preferred()
                             .expect("effective_registry always registers an adapter");
-                        let template = ops::generate_template(
-                            preferred,
+                        let shape = ops::with_root_notes(
                             self.schema.as_shape(),
-                            self.normalize_keys,
-                        )?;
+                            &self.profile_notes(preferred),
+                        );
+                        let template =
+                            ops::generate_template(preferred, &shape, self.normalize_keys)?;
                         Ok(ConfigResult::Template(template))
                     }
                 }
@@ -886,7 +1451,7 @@ impl Builder {
                     None => Ok(ConfigResult::Schema(schema)),
                 }
             }
-            ConfigAction::Get { key, scope } => match scope {
+            ConfigAction::Get { key, scope, origin } => match scope {
                 None => {
                     // Clone the Arc so `load()` can consume `self` while
                     // `get_from_table` still borrows the shape.
@@ -895,10 +1460,19 @@ impl Builder {
                     // Merged view: display renders in the preferred
                     // (first-enabled) format's spelling.
                     let registry = self.effective_registry()?;
-                    let table = self.load()?;
+                    // Interpolated values carry their template on the
+                    // provenance tree, so `get` needs it for `raw:` too.
+                    let (table, provenance) = if *origin || self.interpolate {
+                        let (table, provenance) = self.load_with_origins()?;
+                        (table, Some(provenance))
+                    } else {
+                        (self.load()?, None)
+                    };
                     get_from_table(
                         shape.as_ref(),
                         &table,
+                        provenance.as_ref(),
+                        *origin,
                         key,
                         normalize_keys,
                         registry
@@ -915,9 +1489,51 @@ impl Builder {
                         &path,
                         key,
                         self.normalize_keys,
+                        *origin,
                     )
                 }
             },
+            ConfigAction::Explain { key } => {
+                // Explanations span formats; display renders in the
+                // preferred (first-enabled) format's spelling.
+                let shape = Arc::clone(&self.schema.0);
+                let registry = self.effective_registry()?;
+                let mut explanation = self.explain(key)?;
+                if let Some(value) = &mut explanation.value {
+                    redact::redact_at(&shape, &explanation.key, value);
+                }
+                for candidate in &mut explanation.candidates {
+                    redact::redact_at(&shape, &explanation.key, &mut candidate.value);
+                }
+                let combines = crate::merge::combines_at(&shape, &explanation.key);
+                Ok(ConfigResult::explanation(
+                    registry
+                        .preferred()
+                        .expect("effective_registry always registers an adapter"),
+                    explanation,
+                    combines,
+                ))
+            }
+            ConfigAction::Diff { left, right } => {
+                // Diffs span formats; display renders in the preferred
+                // (first-enabled) format's spelling.
+                let shape = Arc::clone(&self.schema.0);
+                let registry = self.effective_registry()?;
+                let mut diff = self.diff(left, right)?;
+                for entry in &mut diff.entries {
+                    for value in [&mut entry.left, &mut entry.right].into_iter().flatten() {
+                        redact::redact_at(&shape, &entry.key, value);
+                    }
+                }
+                Ok(ConfigResult::diff(
+                    registry
+                        .preferred()
+                        .expect("effective_registry always registers an adapter"),
+                    &left.to_string(),
+                    &right.to_string(),
+                    diff,
+                ))
+            }
             ConfigAction::Set { key, value, scope } => {
                 let (path, adapter) = self.resolve_scope_persist_path(scope.as_deref())?;
                 persist::persist_value(
@@ -931,7 +1547,41 @@ impl Builder {
             }
             ConfigAction::Unset { key, scope } => {
                 let (path, adapter) = self.resolve_scope_persist_path(scope.as_deref())?;
-                crate::persist::unset_value(adapter.as_ref(), &path, key, self.normalize_keys)
+                crate::persist::unset_value(
+                    adapter.as_ref(),
+                    self.schema.as_shape(),
+                    &path,
+                    key,
+                    self.normalize_keys,
+                )
+            }
+            ConfigAction::Migrate { scope } => {
+                let scopes: Vec<Option<&str>> = match scope {
+                    Some(name) => vec![Some(name.as_str())],
+                    None if self.persist_scopes.is_empty() => vec![None],
+                    None => self
+                        .persist_scopes
+                        .iter()
+                        .map(|(name, _)| Some(name.as_str()))
+                        .collect(),
+                };
+                let profile_key = self.effective_profile_key();
+                let mut files = Vec::new();
+                for scope in scopes {
+                    let (path, adapter) = self.resolve_scope_persist_path(scope)?;
+                    let applied = crate::migrate::migrate_file(
+                        adapter.as_ref(),
+                        &path,
+                        &self.migrations,
+                        self.schema_version_key.as_deref(),
+                        profile_key.as_deref(),
+                        self.normalize_keys,
+                    )?;
+                    if !applied.is_empty() {
+                        files.push((path, applied));
+                    }
+                }
+                Ok(ConfigResult::Migrated { files })
             }
         }
     }
@@ -954,8 +1604,18 @@ pub struct Resolver {
     registry: FormatRegistry,
     search_paths: Vec<SearchPath>,
     search_mode: SearchMode,
+    cache_freshness: CacheFreshness,
+    include_key: Option<String>,
+    profile_key: Option<String>,
+    profile: Option<String>,
     env_prefix: Option<String>,
     env_vars: Vec<(String, String)>,
+    env_list_separator: String,
+    env_file_secrets: bool,
+    interpolate: bool,
+    unset_marker: Option<String>,
+    migrations: Vec<Migration>,
+    schema_version_key: Option<String>,
     strict_default: bool,
     strict_overrides: StrictnessOverrides,
     unknown_key_hook: Option<UnknownKeyHook>,
@@ -964,8 +1624,25 @@ pub struct Resolver {
     url_overrides: Vec<(String, Value)>,
     cli_overrides: Vec<(String, Value)>,
     layer_order: Option<Vec<Layer>>,
+    deny_warnings: Vec<WarningKind>,
     post_validate: Option<Arc<PostValidateHook>>,
-    file_cache: Mutex<std::collections::HashMap<PathBuf, String>>,
+    file_cache: Mutex<std::collections::HashMap<PathBuf, CachedFile>>,
+}
+
+/// What [`Resolver::resolve_at_inner`] hands each public surface: the
+/// merged table, collected unknown keys, origins, and warnings.
+type Resolved = (
+    Map,
+    Vec<crate::strict::CollectedUnknown>,
+    OriginMap,
+    Vec<Warning>,
+);
+
+/// One cached config file: its contents and the [`file::FileStamp`] taken
+/// just before they were read.
+struct CachedFile {
+    contents: String,
+    stamp: file::FileStamp,
 }
 
 /// Loaded files plus every candidate probe (hits, misses, FirstMatch
@@ -983,9 +1660,16 @@ struct DirProbe {
 }
 
 impl Resolver {
+    /// The active profile, fixed when the resolver was built: the
+    /// builder's [`profile`](Builder::profile), else `MYAPP_PROFILE`.
+    pub fn profile(&self) -> Option<&str> {
+        self.profile.as_deref()
+    }
+
     pub fn resolve_at(&self, start_dir: impl AsRef<std::path::Path>) -> Result<Map, ClapfigError> {
         self.resolve_at_inner(start_dir.as_ref())
-            .map(|(table, _unknowns)| table)
+            .1
+            .map(|(table, _unknowns, _origins, _warnings)| table)
     }
 
     /// Same as [`resolve_at`](Self::resolve_at) but also returns any keys
@@ -996,18 +1680,138 @@ impl Resolver {
         start_dir: impl AsRef<std::path::Path>,
     ) -> Result<(Map, Vec<crate::strict::CollectedUnknown>), ClapfigError> {
         self.resolve_at_inner(start_dir.as_ref())
+            .1
+            .map(|(table, unknowns, _origins, _warnings)| (table, unknowns))
+    }
+
+    /// Same as [`resolve_at`](Self::resolve_at) but also returns the
+    /// [`Provenance`] of every resolved value. The origin tree is the one
+    /// the merge already builds for error locations, so this costs no
+    /// extra resolution work.
+    pub fn resolve_at_with_origins(
+        &self,
+        start_dir: impl AsRef<std::path::Path>,
+    ) -> Result<(Map, Provenance), ClapfigError> {
+        self.resolve_at_inner(start_dir.as_ref())
+            .1
+            .map(|(table, _unknowns, origins, _warnings)| (table, Provenance::new(origins)))
+    }
+
+    /// Same as [`resolve_at`](Self::resolve_at) but also returns the
+    /// [`Warning`](crate::Warning)s the resolution raised. See
+    /// [`Builder::load_with_diagnostics`].
+    pub fn resolve_at_with_diagnostics(
+        &self,
+        start_dir: impl AsRef<std::path::Path>,
+    ) -> Result<(Map, Vec<Warning>), ClapfigError> {
+        self.resolve_at_inner(start_dir.as_ref())
+            .1
+            .map(|(table, _unknowns, _origins, warnings)| (table, warnings))
+    }
+
+    /// Explain where `key`'s value comes from when resolving at
+    /// `start_dir`: every input that supplied it, in precedence order, and
+    /// which one won — the losers [`Provenance`] does not keep.
+    ///
+    /// Re-runs the full resolution (discovery, every layer, validation),
+    /// so it fails exactly where [`resolve_at`](Self::resolve_at) would.
+    /// The `post_validate` hook does not run: an explanation reports
+    /// inputs, not whether the application accepts the result. `key` is
+    /// a dotted path; an unknown key fails as
+    /// [`ClapfigError::KeyNotFound`] with the nearest-key suggestion.
+    pub fn explain_at(
+        &self,
+        start_dir: impl AsRef<std::path::Path>,
+        key: &str,
+    ) -> Result<Explanation, ClapfigError> {
+        let canonical = if self.normalize_keys {
+            crate::normalize::normalize_key(key)
+        } else {
+            key.to_owned()
+        };
+        let shape = self.schema.as_shape();
+        if crate::meta::doc_for_shape(shape, &canonical).is_none() {
+            return Err(ClapfigError::KeyNotFound {
+                key: key.into(),
+                suggestion: crate::meta::nearest_key_shape(shape, &canonical, self.normalize_keys),
+            });
+        }
+        let path = canonical
+            .split('.')
+            .fold(ConfigPath::new(), |path, segment| path.key(segment));
+
+        let input = self.resolve_input(start_dir.as_ref())?;
+        let not_probed = input
+            .discovery
+            .files
+            .iter()
+            .filter(|probe| probe.outcome == ProbeOutcome::NotProbed)
+            .map(|probe| probe.path.clone())
+            .collect();
+        let layer_order = input
+            .layer_order
+            .clone()
+            .unwrap_or_else(resolve::default_layer_order);
+        let (table, origins, recorded) = resolve::resolve_explaining(input, &path)?;
+
+        let winner = crate::origin::lookup(&origins, &path).map(crate::origin::Origin::to_facts);
+        let winning_index = recorded
+            .iter()
+            .rposition(|(origin, _)| Some(origin) == winner.as_ref());
+        let candidates = recorded
+            .into_iter()
+            .enumerate()
+            .map(|(i, (origin, value))| Candidate {
+                origin,
+                value,
+                won: Some(i) == winning_index,
+            })
+            .collect();
+        Ok(Explanation {
+            key: canonical,
+            value: crate::validate::lookup_value(&table, &path).cloned(),
+            candidates,
+            layer_order,
+            not_probed,
+        })
     }
 
-    /// Shared implementation behind [`resolve_at`](Self::resolve_at) and
-    /// [`resolve_at_with_unknowns`](Self::resolve_at_with_unknowns): one
-    /// place owns anchoring, discovery, caching, resolution, and the
-    /// post-validate hook, so the two public surfaces stay thin wrappers
-    /// that only differ in whether the collected-unknowns list is kept
-    /// or dropped.
+    /// Shared implementation behind [`resolve_at`](Self::resolve_at),
+    /// [`resolve_at_with_unknowns`](Self::resolve_at_with_unknowns),
+    /// [`resolve_at_with_origins`](Self::resolve_at_with_origins), and
+    /// [`resolve_at_with_diagnostics`](Self::resolve_at_with_diagnostics):
+    /// one place owns anchoring, discovery, caching, resolution, and the
+    /// post-validate hook, so the public surfaces stay thin wrappers
+    /// that only differ in which of the collected-unknowns list, the
+    /// origin tree, and the warnings they keep. The watcher also goes
+    /// through here, for the candidate paths discovery named (hits,
+    /// misses, not-probed — `None` when discovery itself failed).
     fn resolve_at_inner(
         &self,
         start_dir: &std::path::Path,
-    ) -> Result<(Map, Vec<crate::strict::CollectedUnknown>), ClapfigError> {
+    ) -> (Option<Vec<PathBuf>>, Result<Resolved, ClapfigError>) {
+        let input = match self.resolve_input(start_dir) {
+            Ok(input) => input,
+            Err(e) => return (None, Err(e)),
+        };
+        let paths = input
+            .discovery
+            .files
+            .iter()
+            .map(|probe| probe.path.clone())
+            .collect();
+        let result = resolve::resolve_with_diagnostics(input).and_then(|resolved| {
+            if let Some(hook) = self.post_validate.as_ref() {
+                hook(&resolved.0)?;
+            }
+            Ok(resolved)
+        });
+        (Some(paths), result)
+    }
+
+    /// Anchor at `start_dir`, run discovery (through the file cache), and
+    /// assemble the I/O-free [`ResolveInput`] every resolution consumes.
+    fn resolve_input(&self, start_dir: &std::path::Path) -> Result<ResolveInput<'_>, ClapfigError> {
         let absolute = if start_dir.is_absolute() {
             start_dir.to_path_buf()
         } else {
@@ -1032,7 +1836,14 @@ impl Resolver {
         // as Env.
         let loaded = if order.contains(&Layer::Files) {
             let dirs = file::expand_search_paths(&self.search_paths, &self.app_name, &normalized);
-            self.load_files_cached(&dirs)?
+            let mut loaded = self.load_files_cached(&dirs)?;
+            if let Some(profile) = &self.profile {
+                loaded = self.profile_files(loaded, profile)?;
+            }
+            if let Some(key) = &self.include_key {
+                loaded = self.include_files(loaded, key)?;
+            }
+            loaded
         } else {
             DiscoveryLoad {
                 files: Vec::new(),
@@ -1060,8 +1871,21 @@ impl Resolver {
             registry: &self.registry,
             files: loaded.files,
             discovery,
+            include_key: self.include_key.clone(),
+            profile_key: self.profile_key.clone(),
+            profile: self.profile.clone(),
             env_vars: self.env_vars.clone(),
             env_prefix: self.env_prefix.clone(),
+            env_list_separator: self.env_list_separator.clone(),
+            env_file_secrets: self.env_file_secrets,
+            read_file: crate::file_ref::read_from_disk,
+            file_mode: crate::diagnostics::file_mode_from_disk,
+            stat_path: crate::paths::stat_from_disk,
+            interpolate: self.interpolate,
+            lookup_env: crate::interpolate::lookup_process_env,
+            unset_marker: self.unset_marker.clone(),
+            migrations: self.migrations.clone(),
+            schema_version_key: self.schema_version_key.clone(),
             #[cfg(feature = "url")]
             url_overrides: self.url_overrides.clone(),
             cli_overrides: self.cli_overrides.clone(),
@@ -1070,13 +1894,9 @@ impl Resolver {
             unknown_key_hook: self.unknown_key_hook.clone(),
             normalize_keys: self.normalize_keys,
             layer_order: self.layer_order.clone(),
+            deny_warnings: self.deny_warnings.clone(),
         };
-
-        let (table, unknowns) = resolve::resolve(input)?;
-        if let Some(hook) = self.post_validate.as_ref() {
-            hook(&table)?;
-        }
-        Ok((table, unknowns))
+        Ok(input)
     }
 
     /// Load discovered files and retain every candidate probe.
@@ -1131,6 +1951,67 @@ impl Resolver {
         }
     }
 
+    /// Load each discovered file's `profile` sibling (`myapp.prod.toml`
+    /// beside `myapp.toml`) just above it, recording every probe.
+    fn profile_files(
+        &self,
+        loaded: DiscoveryLoad,
+        profile: &str,
+    ) -> Result<DiscoveryLoad, ClapfigError> {
+        let DiscoveryLoad {
+            files: discovered,
+            mut probes,
+        } = loaded;
+        let mut files = Vec::with_capacity(discovered.len());
+        for (path, contents) in discovered {
+            let sibling = crate::profile::sibling_path(&path, profile);
+            files.push((path, contents));
+            let Some(sibling) = sibling else {
+                continue;
+            };
+            match self.read_cached(&sibling)? {
+                Some(contents) => {
+                    probes.push(FileProbe {
+                        path: sibling.clone(),
+                        outcome: ProbeOutcome::Loaded,
+                    });
+                    files.push((sibling, contents));
+                }
+                None => probes.push(FileProbe {
+                    path: sibling,
+                    outcome: ProbeOutcome::Missing,
+                }),
+            }
+        }
+        Ok(DiscoveryLoad { files, probes })
+    }
+
+    /// Splice every file the discovered files include below its includer
+    /// (see [`crate::include`]). Included files are read through the
+    /// cache and recorded as loaded probes, so a watch reloads when one
+    /// changes.
+    fn include_files(
+        &self,
+        loaded: DiscoveryLoad,
+        key: &str,
+    ) -> Result<DiscoveryLoad, ClapfigError> {
+        let DiscoveryLoad { files, mut probes } = loaded;
+        let discovered: Vec<PathBuf> = files.iter().map(|(path, _)| path.clone()).collect();
+        let files = crate::include::expand_includes(files, key, &self.registry, &mut |path| {
+            self.read_cached(path)
+        })?;
+        probes.extend(
+            files
+                .iter()
+                .filter(|(path, _)| !discovered.contains(path))
+                .map(|(path, _)| FileProbe {
+                    path: path.clone(),
+                    outcome: ProbeOutcome::Loaded,
+                }),
+        );
+        Ok(DiscoveryLoad { files, probes })
+    }
+
     /// Candidate paths this resolver would probe in `dir` (exact name, or
     /// each enabled extension for stem naming). Used to enumerate
     /// FirstMatch's unvisited directories as `not probed` without I/O.
@@ -1238,20 +2119,108 @@ impl Resolver {
             .len()
     }
 
-    fn read_cached(&self, path: &std::path::Path) -> Result<Option<String>, ClapfigError> {
-        {
-            let cache = self.file_cache.lock().expect("file_cache mutex poisoned");
-            if let Some(cached) = cache.get(path) {
-                return Ok(Some(cached.clone()));
-            }
-        }
-        match std::fs::read_to_string(path) {
-            Ok(contents) => {
-                let mut cache = self.file_cache.lock().expect("file_cache mutex poisoned");
-                cache.insert(path.to_path_buf(), contents.clone());
+    /// Hand the resolver to a background watcher that re-resolves at
+    /// `start_dir` whenever a file discovery probed changes, appears, or
+    /// disappears.
+    ///
+    /// The first resolution runs here, synchronously: its failure is
+    /// returned and no watch starts. Every reload re-runs the full
+    /// pipeline, `post_validate` included; configs that validate arrive
+    /// as `Ok` (only when they differ from the last one delivered), failed
+    /// reloads as `Err`. Changed files are evicted from the file cache
+    /// before the reload, so the watch is the supported way to get
+    /// freshness out of a long-lived resolver. See [`crate::watch`].
+    pub fn watch(
+        self,
+        start_dir: impl AsRef<std::path::Path>,
+        options: WatchOptions,
+    ) -> Result<ConfigWatch<Map>, ClapfigError> {
+        crate::watch::spawn(self, start_dir.as_ref(), options, Ok)
+    }
+
+    /// One watched resolution: the candidate paths discovery named and
+    /// the post-validated result. See
+    /// [`resolve_at_inner`](Self::resolve_at_inner).
+    pub(crate) fn resolve_watched(
+        &self,
+        start_dir: &std::path::Path,
+    ) -> (Option<Vec<PathBuf>>, Result<Map, ClapfigError>) {
+        let (paths, result) = self.resolve_at_inner(start_dir);
+        (
+            paths,
+            result.map(|(table, _unknowns, _origins, _warnings)| table),
+        )
+    }
+
+    /// Drop `path` from the file cache so the next resolution re-reads it.
+    /// Returns whether an entry was cached.
+    ///
+    /// The cache is keyed by the absolute, canonicalized candidate path, so
+    /// a relative or non-canonical `path` is resolved against the current
+    /// directory first.
+    pub fn invalidate(&self, path: impl AsRef<std::path::Path>) -> bool {
+        let path = path.as_ref();
+        let mut cache = self.file_cache.lock().expect("file_cache mutex poisoned");
+        if cache.remove(path).is_some() {
+            return true;
+        }
+        let absolute = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+        let canonical = std::fs::canonicalize(&absolute).unwrap_or(absolute);
+        cache.remove(&canonical).is_some()
+    }
+
+    /// Drop every cached file, so the next resolution re-reads everything.
+    /// The environment, CLI overrides, and strictness settings captured at
+    /// build time are kept.
+    pub fn clear_cache(&self) {
+        self.file_cache
+            .lock()
+            .expect("file_cache mutex poisoned")
+            .clear();
+    }
+
+    /// Read `path` through the cache, honoring the
+    /// [`CacheFreshness`] policy on hits. A file that vanished since it was
+    /// cached is evicted under the checking policies and reported missing.
+    fn read_cached(&self, path: &std::path::Path) -> Result<Option<String>, ClapfigError> {
+        // Stamp before reading: a write racing the read then leaves a
+        // stale stamp, which the next check re-reads, never the reverse.
+        let stamp = match self.cache_freshness {
+            CacheFreshness::Never => None,
+            CacheFreshness::Metadata | CacheFreshness::Always => file::stamp(path),
+        };
+        {
+            let cache = self.file_cache.lock().expect("file_cache mutex poisoned");
+            if let Some(cached) = cache.get(path) {
+                let fresh = match self.cache_freshness {
+                    CacheFreshness::Never => true,
+                    CacheFreshness::Metadata => stamp.is_some() && cached.stamp == stamp,
+                    CacheFreshness::Always => false,
+                };
+                if fresh {
+                    return Ok(Some(cached.contents.clone()));
+                }
+            }
+        }
+        match std::fs::read_to_string(path) {
+            Ok(contents) => {
+                let mut cache = self.file_cache.lock().expect("file_cache mutex poisoned");
+                cache.insert(
+                    path.to_path_buf(),
+                    CachedFile {
+                        contents: contents.clone(),
+                        stamp,
+                    },
+                );
                 Ok(Some(contents))
             }
-            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                self.file_cache
+                    .lock()
+                    .expect("file_cache mutex poisoned")
+                    .remove(path);
+                Ok(None)
+            }
             Err(e) => Err(ClapfigError::IoError {
                 path: path.to_path_buf(),
                 source: e,
@@ -1260,44 +2229,50 @@ impl Resolver {
     }
 }
 
-/// Select the format adapter for an explicit file path by its extension
-/// (independent of the enabled-formats list). Extensionless names fall
-/// back to TOML (the rc-style preservation rule); an extension no adapter
-/// claims is a hard [`ClapfigError::UnknownFormat`] — never a silent TOML
-/// fallback that would write or parse one format's content under another
-/// format's extension.
-fn adapter_for_explicit_path(path: &Path) -> Result<Box<dyn FormatAdapter>, ClapfigError> {
-    match path.extension() {
-        None => Ok(format::builtin_adapter("toml").expect("toml adapter is built in")),
-        Some(ext) => {
-            let ext = ext.to_string_lossy();
-            format::builtin_adapter_for_extension(&ext).ok_or_else(|| ClapfigError::UnknownFormat {
-                name: ext.into_owned(),
-                available: format::builtin_names(),
-            })
-        }
-    }
-}
-
 /// Render every leaf in a resolved table as flat dotted-key entries — the
 /// `config list` output shape. Display lines are spelled by `adapter`
-/// (the active format).
-fn list_from_table(table: &Map, adapter: &dyn FormatAdapter) -> ConfigResult {
+/// (the active format). With `provenance`, each entry is annotated with
+/// the origin of its value. Sensitive values are redacted.
+fn list_from_table(
+    shape: &Shape,
+    mut table: Map,
+    provenance: Option<&Provenance>,
+    adapter: &dyn FormatAdapter,
+) -> ConfigResult {
+    redact::redact_table(shape, &mut table);
     let mut entries = Vec::new();
-    flatten_table(table, "", &mut entries);
-    ConfigResult::listing(adapter, entries)
+    flatten_table(&table, "", &ConfigPath::new(), &mut entries);
+    match provenance {
+        None => {
+            let entries = entries.into_iter().map(|(_, k, v)| (k, v)).collect();
+            ConfigResult::listing(adapter, entries)
+        }
+        Some(provenance) => {
+            let annotated = entries
+                .into_iter()
+                .map(|(path, key, value)| (key, value, provenance.get(&path)))
+                .collect();
+            ConfigResult::annotated_listing(adapter, annotated)
+        }
+    }
 }
 
-fn flatten_table(table: &Map, prefix: &str, out: &mut Vec<(String, String)>) {
+fn flatten_table(
+    table: &Map,
+    prefix: &str,
+    path: &ConfigPath,
+    out: &mut Vec<(ConfigPath, String, String)>,
+) {
     for (key, value) in table {
         let full = if prefix.is_empty() {
             key.clone()
         } else {
             format!("{prefix}.{key}")
         };
+        let full_path = path.clone().key(key);
         match value {
-            Value::Map(t) => flatten_table(t, &full, out),
-            _ => out.push((full, format_leaf_value(value))),
+            Value::Map(t) => flatten_table(t, &full, &full_path, out),
+            _ => out.push((full_path, full, format_leaf_value(value))),
         }
     }
 }
@@ -1318,31 +2293,47 @@ fn format_leaf_value(value: &Value) -> String {
 /// `config get` against the merged table. The merged table's keys are
 /// canonical snake_case (the load path normalized them), so with
 /// `normalize_keys` the action key is normalized before lookup — a kebab
-/// action key finds its snake entry. The reported key keeps the caller's
-/// spelling; the display block is spelled by `adapter` (the active
-/// format).
+/// action key finds its snake entry. Inputs are rewritten to field names
+/// on load, so an alias in the action key is too. The reported key keeps
+/// the caller's spelling; the display block is spelled by `adapter` (the
+/// active format). With `origin`, the value is annotated with its origin from
+/// `provenance`; an interpolated value also shows its template.
 fn get_from_table(
     shape: &Shape,
     table: &Map,
+    provenance: Option<&Provenance>,
+    origin: bool,
     key: &str,
     normalize_keys: bool,
     adapter: &dyn FormatAdapter,
 ) -> Result<ConfigResult, ClapfigError> {
-    let canonical = if normalize_keys {
-        crate::normalize::normalize_key(key)
-    } else {
-        key.to_owned()
-    };
+    let canonical = persist::schema_key(shape, key, normalize_keys);
     let value = ops::table_get(table, &canonical).ok_or_else(|| ClapfigError::KeyNotFound {
         key: key.into(),
         suggestion: crate::meta::nearest_key_shape(shape, &canonical, normalize_keys),
     })?;
+    let mut value = value.clone();
+    redact::redact_at(shape, &canonical, &mut value);
     let doc = crate::meta::doc_for_shape(shape, &canonical).unwrap_or_default();
+    let facts = provenance
+        .filter(|_| origin)
+        .and_then(|provenance| provenance.get_dotted(&canonical));
+    let raw = provenance
+        .and_then(|provenance| provenance.raw_dotted(&canonical))
+        .map(|raw| {
+            if redact::is_sensitive_at(shape, &canonical) {
+                redact::REDACTED.to_owned()
+            } else {
+                raw.to_owned()
+            }
+        });
     Ok(ConfigResult::key_value(
         adapter,
         key.into(),
-        format_leaf_value(value),
+        format_leaf_value(&value),
         doc,
+        facts,
+        raw,
     ))
 }
 
@@ -1353,11 +2344,14 @@ fn get_from_table(
 /// either action-key spelling — and a document holding BOTH equivalent
 /// spellings anywhere (even at keys the lookup never touches) fails as
 /// [`ClapfigError::NormalizedKeyCollision`] instead of answering from a
-/// document the load path refuses. The reported key keeps the caller's
-/// spelling; the display block is spelled by `adapter` (the scope file's
-/// format). A scope whose file does not exist fails as
+/// document the load path refuses. An alias names its field, and the
+/// lookup follows whichever of the field's spellings the file uses, as
+/// `config set` does. The reported key keeps the caller's spelling; the
+/// display block is spelled by `adapter` (the scope file's format). A
+/// scope whose file does not exist fails as
 /// [`ClapfigError::ScopeFileMissing`] naming the scope and the file —
-/// the key may be perfectly valid; there is just nothing to read.
+/// the key may be perfectly valid; there is just nothing to read. With
+/// `origin`, the value is annotated with the scope file and its line.
 fn get_scope(
     adapter: &dyn FormatAdapter,
     shape: &Shape,
@@ -1365,6 +2359,7 @@ fn get_scope(
     file_path: &std::path::Path,
     key: &str,
     normalize_keys: bool,
+    origin: bool,
 ) -> Result<ConfigResult, ClapfigError> {
     let content = match std::fs::read_to_string(file_path) {
         Ok(c) => c,
@@ -1382,15 +2377,14 @@ fn get_scope(
         }
     };
 
-    let table = match adapter
+    let parsed = adapter
         .parse(&content)
         .map_err(|e| ClapfigError::ParseError {
             path: file_path.to_path_buf(),
             source: Box::new(e),
             source_text: Some(Arc::from(content.as_str())),
-        })?
-        .value
-    {
+        })?;
+    let table = match &parsed.value {
         Value::Map(map) => map,
         other => {
             return Err(ClapfigError::invalid_value(
@@ -1403,24 +2397,35 @@ fn get_scope(
         }
     };
 
-    let (canonical, value) = if normalize_keys {
-        let canonical = crate::normalize::normalize_key(key);
-        let value =
-            ops::table_get_normalized(&table, &canonical).map_err(|c| c.into_error(file_path))?;
-        (canonical, value)
+    // The file may spell an aliased field by its alias; look it up under
+    // the spelling the file uses.
+    let canonical = persist::schema_key(shape, key, normalize_keys);
+    let spelled = persist::document_key(shape, &parsed.value, &canonical, normalize_keys)
+        .map_err(|e| persist::stamp_collision_path(e, file_path))?;
+    let value = if normalize_keys {
+        ops::table_get_normalized(table, &spelled).map_err(|c| c.into_error(file_path))?
     } else {
-        (key.to_owned(), ops::table_get(&table, key))
+        ops::table_get(table, &spelled)
     };
     let value = value.ok_or_else(|| ClapfigError::KeyNotFound {
         key: key.into(),
         suggestion: crate::meta::nearest_key_shape(shape, &canonical, normalize_keys),
     })?;
+    let mut value = value.clone();
+    redact::redact_at(shape, &canonical, &mut value);
     let doc = crate::meta::doc_for_shape(shape, &canonical).unwrap_or_default();
+    let origin = origin.then(|| {
+        let path = ops::document_path(table, &spelled, normalize_keys);
+        let (source, spans) = ops::scope_file_source(shape, table, parsed.spans.clone(), &content);
+        ops::scope_file_origin(&spans, &path, file_path, &source)
+    });
     Ok(ConfigResult::key_value(
         adapter,
         key.into(),
-        format_leaf_value(value),
+        format_leaf_value(&value),
         doc,
+        origin,
+        None,
     ))
 }
 
@@ -1559,6 +2564,73 @@ mod tests {
         assert_eq!(table.get("port"), Some(&Value::Integer(7000)));
     }
 
+    #[test]
+    fn load_with_origins_names_every_winning_input() {
+        const KEY: &str = "CLAPFIG_RT_BUILDER_ORIGINS_TEST__DB__POOL_SIZE";
+        unsafe { std::env::set_var(KEY, "20") };
+
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("demo.toml");
+        fs::write(&file, "host = \"example\"\n[db]\nurl = \"pg://prod\"\n").unwrap();
+
+        let (table, provenance) = Clapfig::builder(demo_schema())
+            .app_name("demo")
+            .file_name("demo.toml")
+            .env_prefix("CLAPFIG_RT_BUILDER_ORIGINS_TEST")
+            .search_paths(vec![SearchPath::Path(dir.path().to_path_buf())])
+            .cli_override("port", Some(9000i64))
+            .load_with_origins()
+            .unwrap();
+        unsafe { std::env::remove_var(KEY) };
+
+        assert_eq!(table.get("port"), Some(&Value::Integer(9000)));
+        let url = provenance.get_dotted("db.url").unwrap();
+        assert_eq!(url.input_type, Some(crate::InputType::File));
+        assert!(url.file.as_deref().unwrap().ends_with("demo.toml"));
+        assert_eq!(url.line_col(), Some((3, 7)));
+        let pool = provenance.get_dotted("db.pool_size").unwrap();
+        assert_eq!(pool.env_var.as_deref(), Some(KEY));
+        let port = provenance.get_dotted("port").unwrap();
+        assert_eq!(port.input_type, Some(crate::InputType::Override));
+        assert_eq!(port.key.as_deref(), Some("port"));
+        let level = provenance.get_dotted("level").unwrap();
+        assert_eq!(level.input_type, Some(crate::InputType::Default));
+        assert!(provenance.get_dotted("db.missing").is_none());
+    }
+
+    #[test]
+    fn load_with_diagnostics_returns_warnings_the_policy_allows() {
+        let home = TempDir::new().unwrap();
+        let work = TempDir::new().unwrap();
+        fs::write(home.path().join("demo.toml"), "host = \"a\"\n").unwrap();
+        fs::write(work.path().join("demo.json"), "{\"port\": 1}\n").unwrap();
+        let builder = || {
+            Clapfig::builder(demo_schema())
+                .app_name("demo")
+                .file_stem("demo")
+                .formats(["toml", "json"])
+                .search_paths(vec![
+                    SearchPath::Path(home.path().to_path_buf()),
+                    SearchPath::Path(work.path().to_path_buf()),
+                ])
+                .no_env()
+        };
+
+        let (table, warnings) = builder().load_with_diagnostics().unwrap();
+        assert_eq!(table.get("port"), Some(&Value::Integer(1)));
+        assert_eq!(warnings.len(), 1, "{warnings:?}");
+        assert_eq!(warnings[0].kind(), WarningKind::MixedFormats);
+
+        let err = builder()
+            .deny_warnings([WarningKind::MixedFormats])
+            .load()
+            .unwrap_err();
+        assert!(
+            matches!(&err, ClapfigError::DeniedWarnings(w) if w == &warnings),
+            "{err:?}"
+        );
+    }
+
     #[test]
     fn cli_override_wins() {
         let dir = TempDir::new().unwrap();
@@ -2208,6 +3280,7 @@ mod tests {
             .handle(&ConfigAction::Get {
                 key: "port".into(),
                 scope: None,
+                origin: false,
             })
             .unwrap();
 
@@ -2221,506 +3294,1188 @@ mod tests {
     }
 
     #[test]
-    fn handle_set_persists_to_file() {
+    fn handle_get_shows_raw_and_interpolated_values() {
         let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("demo.toml"),
+            "host = \"db.internal\"\n[db]\nurl = \"pg://${host}/app\"\n",
+        )
+        .unwrap();
         let result = Clapfig::builder(demo_schema())
             .app_name("demo")
             .file_name("demo.toml")
-            .persist_scope("local", SearchPath::Path(dir.path().to_path_buf()))
+            .search_paths(vec![SearchPath::Path(dir.path().to_path_buf())])
             .no_env()
-            .handle(&ConfigAction::Set {
-                key: "port".into(),
-                value: "12345".into(),
+            .interpolate(true)
+            .handle(&ConfigAction::Get {
+                key: "db.url".into(),
                 scope: None,
+                origin: false,
             })
             .unwrap();
-        assert!(matches!(result, ConfigResult::ValueSet { .. }));
-        let content = fs::read_to_string(dir.path().join("demo.toml")).unwrap();
-        assert!(content.contains("port = 12345"));
+
+        match &result {
+            ConfigResult::KeyValue {
+                value, raw, origin, ..
+            } => {
+                assert_eq!(value, "pg://db.internal/app");
+                assert_eq!(raw.as_deref(), Some("pg://${host}/app"));
+                assert!(origin.is_none(), "origin not requested");
+            }
+            other => panic!("expected KeyValue, got {other:?}"),
+        }
+        assert_eq!(
+            result.to_string(),
+            "# raw: pg://${host}/app\ndb.url = pg://db.internal/app"
+        );
     }
 
     #[test]
-    fn handle_unset_removes_value() {
+    fn included_files_merge_below_the_includer_with_their_own_origins() {
         let dir = TempDir::new().unwrap();
-        fs::write(dir.path().join("demo.toml"), "port = 12345\nhost = \"x\"\n").unwrap();
-        let result = Clapfig::builder(demo_schema())
+        fs::create_dir(dir.path().join("conf.d")).unwrap();
+        fs::write(
+            dir.path().join("demo.toml"),
+            "include = [\"conf.d/*.json\"]\nport = 9000\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("conf.d/db.json"),
+            "{\"port\": 1, \"db\": {\"pool_size\": 20}}",
+        )
+        .unwrap();
+        let builder = || {
+            Clapfig::builder(demo_schema())
+                .app_name("demo")
+                .file_stem("demo")
+                .formats(["toml", "json"])
+                .search_paths(vec![SearchPath::Path(dir.path().to_path_buf())])
+                .no_env()
+                .include_key("include")
+        };
+        let (table, provenance) = builder().load_with_origins().unwrap();
+        assert_eq!(table["port"], Value::Integer(9000), "includer wins");
+        let pool = &table["db"].as_map().unwrap()["pool_size"];
+        assert_eq!(*pool, Value::Integer(20));
+        let origin = provenance.get_dotted("db.pool_size").unwrap();
+        assert_eq!(
+            origin.file.as_deref(),
+            Some(dir.path().join("conf.d/db.json").as_path())
+        );
+        assert_eq!(origin.line_col().map(|(line, _)| line), Some(1));
+
+        // A type error inside an included file points at that file.
+        fs::write(
+            dir.path().join("conf.d/db.json"),
+            "{\"db\": {\"pool_size\": \"x\"}}",
+        )
+        .unwrap();
+        let err = builder().load().unwrap_err();
+        match &err {
+            ClapfigError::InvalidValue { origin, .. } => assert_eq!(
+                origin.file.as_deref(),
+                Some(dir.path().join("conf.d/db.json").as_path())
+            ),
+            other => panic!("expected InvalidValue, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn a_diamond_include_appends_the_shared_file_once() {
+        let dir = TempDir::new().unwrap();
+        let d = dir.path();
+        fs::write(d.join("demo.toml"), "include = [\"b.toml\", \"c.toml\"]\n").unwrap();
+        fs::write(d.join("b.toml"), "include = \"common.toml\"\n").unwrap();
+        fs::write(
+            d.join("c.toml"),
+            "include = \"common.toml\"\nplugins = [\"c\"]\n",
+        )
+        .unwrap();
+        fs::write(d.join("common.toml"), "plugins = [\"common\"]\n").unwrap();
+        let schema = Schema::object("App")
+            .field(
+                "plugins",
+                RtField::array_of_type(RtField::string())
+                    .merge(crate::runtime::MergeStrategy::Append),
+            )
+            .build();
+        let table = Clapfig::builder(schema)
             .app_name("demo")
             .file_name("demo.toml")
-            .persist_scope("local", SearchPath::Path(dir.path().to_path_buf()))
+            .search_paths(vec![SearchPath::Path(d.to_path_buf())])
             .no_env()
-            .handle(&ConfigAction::Unset {
-                key: "port".into(),
-                scope: None,
-            })
+            .include_key("include")
+            .load()
             .unwrap();
-        assert!(matches!(result, ConfigResult::ValueUnset { .. }));
-        let content = fs::read_to_string(dir.path().join("demo.toml")).unwrap();
-        assert!(!content.contains("port"));
-        assert!(content.contains("host = \"x\""));
+        assert_eq!(
+            table["plugins"],
+            Value::Array(vec![
+                Value::String("common".into()),
+                Value::String("c".into()),
+            ])
+        );
     }
 
-    // --- cli_overrides_from auto-matching ---
-
     #[test]
-    fn cli_overrides_from_matches_known_keys_only() {
-        #[derive(serde::Serialize)]
-        struct Args {
-            host: Option<String>,
-            port: Option<i64>,
-            verbose: bool, // not in schema
-        }
-        let args = Args {
-            host: Some("from-cli".into()),
-            port: Some(4242),
-            verbose: true,
-        };
+    fn include_key_is_not_a_config_key_only_when_enabled() {
         let dir = TempDir::new().unwrap();
-        let table = Clapfig::builder(demo_schema())
+        fs::write(dir.path().join("demo.toml"), "include = \"other.toml\"\n").unwrap();
+        let err = Clapfig::builder(demo_schema())
             .app_name("demo")
             .file_name("demo.toml")
             .search_paths(vec![SearchPath::Path(dir.path().to_path_buf())])
             .no_env()
-            .cli_overrides_from(&args)
             .load()
-            .unwrap();
-        assert_eq!(table.get("host"), Some(&Value::String("from-cli".into())));
-        assert_eq!(table.get("port"), Some(&Value::Integer(4242)));
-        // `verbose` was silently ignored — not in schema.
-        assert!(!table.contains_key("verbose"));
-    }
-
-    // --- Phase 3 cascading strictness (#37) ---
-
-    use crate::{UnknownKeyContext, UnknownKeyDecision};
-
-    fn three_level_schema() -> Schema {
-        // Top -> mid -> deep, each a nested section. Used for the
-        // 3-level-cascade tests.
-        Schema::object("Top")
-            .field("name", RtField::string().default("x"))
-            .nested(
-                "mid",
-                Schema::object("Mid")
-                    .field("m_field", RtField::string().default("mv"))
-                    .nested(
-                        "deep",
-                        Schema::object("Deep").field("d_field", RtField::string().default("dv")),
-                    ),
-            )
-            .build()
-    }
+            .unwrap_err();
+        assert!(err.is_strict_violation(), "{err}");
 
-    #[test]
-    fn schema_strict_cascade_through_three_levels() {
-        // Top: strict false (the runtime equivalent of strict_at("", false))
-        // mid + deep inherit lenient. Unknown key 4 levels deep drops.
-        let dir = TempDir::new().unwrap();
-        fs::write(dir.path().join("demo.toml"), "[mid.deep]\nrogue = 1\n").unwrap();
-        let mut schema = three_level_schema();
-        schema.strict = Some(false);
-        let table = Clapfig::builder(schema)
+        let err = Clapfig::builder(demo_schema())
             .app_name("demo")
             .file_name("demo.toml")
             .search_paths(vec![SearchPath::Path(dir.path().to_path_buf())])
             .no_env()
+            .include_key("include")
             .load()
-            .unwrap();
-        // Unknown key dropped silently; the merged table mirrors what was
-        // in the file.
+            .unwrap_err();
         assert!(
-            table
-                .get("mid")
-                .and_then(|v| v.as_map())
-                .and_then(|t| t.get("deep"))
-                .is_some()
+            matches!(err, ClapfigError::IncludeNotFound { .. }),
+            "{err:?}"
         );
     }
 
     #[test]
-    fn descendant_can_re_tighten_subtree() {
-        // mid is lenient, mid.deep re-tightens — rogue at mid drops, rogue
-        // at mid.deep errors.
+    fn profile_sections_and_siblings_layer_above_their_file() {
         let dir = TempDir::new().unwrap();
         fs::write(
             dir.path().join("demo.toml"),
-            "[mid]\nm_field = \"v\"\nmid_rogue = 1\n[mid.deep]\nd_field = \"v\"\ndeep_rogue = 1\n",
+            "port = 1\nhost = \"base\"\n\n[profile.prod]\nport = 2\n",
         )
         .unwrap();
-        let schema = Schema::object("Top")
-            .field("name", RtField::string().default("x"))
-            .nested(
-                "mid",
-                Schema::object("Mid")
-                    .strict(false)
-                    .field("m_field", RtField::string().default("v"))
-                    .nested(
-                        "deep",
-                        Schema::object("Deep")
-                            .strict(true)
-                            .field("d_field", RtField::string().default("v")),
-                    ),
-            )
-            .build();
-        let result = Clapfig::builder(schema)
-            .app_name("demo")
-            .file_name("demo.toml")
-            .search_paths(vec![SearchPath::Path(dir.path().to_path_buf())])
-            .no_env()
-            .load();
-        let err = result.unwrap_err();
-        let keys = err.unknown_keys().expect("expected UnknownKeys");
-        let names: Vec<&str> = keys.iter().map(|k| k.key.as_str()).collect();
-        assert!(
-            names.contains(&"mid.deep.deep_rogue"),
-            "deep_rogue should be rejected: {names:?}"
-        );
-        assert!(
-            !names.contains(&"mid.mid_rogue"),
-            "mid_rogue should be lenient under strict(false): {names:?}"
+        fs::write(dir.path().join("demo.prod.toml"), "host = \"sibling\"\n").unwrap();
+        let builder = || {
+            Clapfig::builder(demo_schema())
+                .app_name("demo")
+                .file_name("demo.toml")
+                .search_paths(vec![SearchPath::Path(dir.path().to_path_buf())])
+                .no_env()
+        };
+
+        let table = builder().profile("prod").load().unwrap();
+        assert_eq!(table["port"], Value::Integer(2));
+        assert_eq!(table["host"], Value::String("sibling".into()));
+
+        // Another profile loads neither the section nor the sibling, and
+        // `profile_key` alone enables the table without selecting one.
+        let table = builder().profile("dev").load().unwrap();
+        assert_eq!(table["port"], Value::Integer(1));
+        assert_eq!(table["host"], Value::String("base".into()));
+        let resolver = builder().profile_key("profile").build_resolver().unwrap();
+        assert_eq!(resolver.profile(), None);
+        assert_eq!(
+            resolver.resolve_at(dir.path()).unwrap()["port"],
+            Value::Integer(1)
         );
     }
 
     #[test]
-    fn runtime_strict_at_overlays_schema_strict() {
-        // Schema sets mid strict=false; builder strict_at("mid", true)
-        // overrides. Result: mid rogue is rejected.
+    fn profile_env_var_selects_and_the_builder_wins() {
+        const VAR: &str = "CLAPFIG_RT_PROFILE_TEST_PROFILE";
+        unsafe { std::env::set_var(VAR, "prod") };
         let dir = TempDir::new().unwrap();
         fs::write(
             dir.path().join("demo.toml"),
-            "[mid]\nm_field = \"v\"\nrogue = 1\n",
+            "[profile.prod]\nport = 2\n\n[profile.ci]\nport = 3\n",
         )
         .unwrap();
-        let schema = Schema::object("Top")
-            .field("name", RtField::string().default("x"))
-            .nested(
-                "mid",
-                Schema::object("Mid")
-                    .strict(false)
-                    .field("m_field", RtField::string().default("v")),
-            )
-            .build();
-        let result = Clapfig::builder(schema)
+        let builder = || {
+            Clapfig::builder(demo_schema())
+                .app_name("demo")
+                .file_name("demo.toml")
+                .env_prefix("CLAPFIG_RT_PROFILE_TEST")
+                .search_paths(vec![SearchPath::Path(dir.path().to_path_buf())])
+                .profile_key("profile")
+        };
+        let from_env = builder().load();
+        let explicit = builder().profile("ci").load();
+        let listing = builder().handle(&ConfigAction::List {
+            scope: None,
+            origins: false,
+        });
+        unsafe { std::env::remove_var(VAR) };
+
+        assert_eq!(from_env.unwrap()["port"], Value::Integer(2));
+        assert_eq!(explicit.unwrap()["port"], Value::Integer(3));
+        match listing.unwrap() {
+            ConfigResult::Listing {
+                profile, rendered, ..
+            } => {
+                assert_eq!(profile.as_deref(), Some("prod"));
+                assert!(rendered.starts_with("# profile: prod\n"), "{rendered}");
+            }
+            other => panic!("Expected Listing, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn profile_env_var_alone_needs_a_profile_key() {
+        const VAR: &str = "CLAPFIG_RT_PROFILE_ENV_ONLY_PROFILE";
+        unsafe { std::env::set_var(VAR, "prod") };
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("demo.toml"), "port = 1\n").unwrap();
+        fs::write(dir.path().join("demo.prod.toml"), "port = 2\n").unwrap();
+        let builder = || {
+            Clapfig::builder(demo_schema())
+                .app_name("demo")
+                .file_name("demo.toml")
+                .env_prefix("CLAPFIG_RT_PROFILE_ENV_ONLY")
+                .search_paths(vec![SearchPath::Path(dir.path().to_path_buf())])
+        };
+        let keyed = builder().profile_key("profile").load();
+        let unkeyed = builder().load();
+        unsafe { std::env::remove_var(VAR) };
+
+        assert_eq!(keyed.unwrap()["port"], Value::Integer(2));
+        assert_eq!(unkeyed.unwrap()["port"], Value::Integer(1));
+    }
+
+    #[test]
+    fn gen_documents_profiles_when_enabled() {
+        let template = |builder: Builder| {
+            builder
+                .handle(&ConfigAction::Gen { output: None })
+                .unwrap()
+                .to_string()
+        };
+        let plain = template(Clapfig::builder(demo_schema()).app_name("demo"));
+        assert!(!plain.contains("Profiles"), "{plain}");
+
+        let with_profiles = template(
+            Clapfig::builder(demo_schema())
+                .app_name("demo")
+                .profile_key("profile"),
+        );
+        assert!(
+            with_profiles.contains("# Profiles: values under `profile.<name>` override"),
+            "{with_profiles}"
+        );
+        assert!(
+            with_profiles.contains("(DEMO_PROFILE=<name>)"),
+            "{with_profiles}"
+        );
+        assert!(
+            with_profiles.contains("demo.<name>.toml"),
+            "{with_profiles}"
+        );
+        // The notes are comments: the template still parses.
+        crate::format::TomlAdapter.parse(&with_profiles).unwrap();
+    }
+
+    #[test]
+    fn handle_set_persists_to_file() {
+        let dir = TempDir::new().unwrap();
+        let result = Clapfig::builder(demo_schema())
             .app_name("demo")
             .file_name("demo.toml")
-            .search_paths(vec![SearchPath::Path(dir.path().to_path_buf())])
+            .persist_scope("local", SearchPath::Path(dir.path().to_path_buf()))
             .no_env()
-            .strict_at("mid", true) // overlay re-tightens
-            .load();
-        let err = result.unwrap_err();
-        let keys = err.unknown_keys().expect("expected UnknownKeys");
-        assert_eq!(keys.len(), 1);
-        assert_eq!(keys[0].key, "mid.rogue");
+            .handle(&ConfigAction::Set {
+                key: "port".into(),
+                value: "12345".into(),
+                scope: None,
+            })
+            .unwrap();
+        assert!(matches!(result, ConfigResult::ValueSet { .. }));
+        let content = fs::read_to_string(dir.path().join("demo.toml")).unwrap();
+        assert!(content.contains("port = 12345"));
     }
 
     #[test]
-    fn runtime_lex_fmt_style_sibling_callback() {
-        // The use-case from the proposal: typed fields and a free-form
-        // catch-all share a struct level. The cascade alone can't
-        // distinguish them; the callback applies a domain-specific rule
-        // (here: "leaf contains a `.` → accept, else reject").
+    fn handle_unset_removes_value() {
         let dir = TempDir::new().unwrap();
-        fs::write(
-            dir.path().join("demo.toml"),
-            "[diagnostics.rules]\nmissing_footote = \"warn\"\n\"acme.task-due-date-missing\" = \"error\"\n",
-        )
-        .unwrap();
-        let schema = Schema::object("Cfg")
-            .nested(
-                "diagnostics",
-                Schema::object("Diag").nested("rules", Schema::object("Rules")), // empty rules: any key is unknown
-            )
-            .build();
-        let result = Clapfig::builder(schema)
+        fs::write(dir.path().join("demo.toml"), "port = 12345\nhost = \"x\"\n").unwrap();
+        let result = Clapfig::builder(demo_schema())
             .app_name("demo")
             .file_name("demo.toml")
-            .search_paths(vec![SearchPath::Path(dir.path().to_path_buf())])
+            .persist_scope("local", SearchPath::Path(dir.path().to_path_buf()))
             .no_env()
-            .strict(true)
-            .on_unknown_key(|c: &UnknownKeyContext<'_>| {
-                if c.path.starts_with("diagnostics.rules.") && c.leaf.contains('.') {
-                    UnknownKeyDecision::Accept
-                } else {
-                    UnknownKeyDecision::Reject
-                }
+            .handle(&ConfigAction::Unset {
+                key: "port".into(),
+                scope: None,
             })
-            .load();
-        let err = result.unwrap_err();
-        let keys = err.unknown_keys().expect("expected UnknownKeys");
-        let names: Vec<&str> = keys.iter().map(|k| k.key.as_str()).collect();
+            .unwrap();
+        assert!(matches!(result, ConfigResult::ValueUnset { .. }));
+        let content = fs::read_to_string(dir.path().join("demo.toml")).unwrap();
+        assert!(!content.contains("port"));
+        assert!(content.contains("host = \"x\""));
+    }
+
+    #[test]
+    fn migrations_load_old_files_and_migrate_rewrites_every_scope() {
+        let local = TempDir::new().unwrap();
+        let global = TempDir::new().unwrap();
+        fs::write(
+            local.path().join("demo.toml"),
+            "# local overrides\nhost = \"dev\" # kept\nlisten_port = 3000\n",
+        )
+        .unwrap();
+        fs::write(
+            global.path().join("demo.toml"),
+            "[database]\nurl = \"pg://x\"\n",
+        )
+        .unwrap();
+        let builder = || {
+            Clapfig::builder(demo_schema())
+                .app_name("demo")
+                .file_name("demo.toml")
+                .search_paths(vec![
+                    SearchPath::Path(global.path().to_path_buf()),
+                    SearchPath::Path(local.path().to_path_buf()),
+                ])
+                .persist_scope("local", SearchPath::Path(local.path().to_path_buf()))
+                .persist_scope("global", SearchPath::Path(global.path().to_path_buf()))
+                .no_env()
+                .schema_version_key("schema_version")
+                .migration(Migration::rename("listen_port", "port"))
+                .migration(Migration::rename("database", "db").at_version(1))
+        };
+
+        // Strict loading sees only the new keys.
+        let table = builder().load().unwrap();
+        assert_eq!(table["port"], Value::Integer(3000));
+        assert_eq!(table["db"].as_map().unwrap()["url"], Value::from("pg://x"));
+        // Each old file is reported, and CI can refuse to load it.
+        let (_, warnings) = builder().load_with_diagnostics().unwrap();
+        let unmigrated: Vec<&Path> = warnings
+            .iter()
+            .filter_map(|w| match w {
+                Warning::UnmigratedFile { path, .. } => Some(path.as_path()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            unmigrated,
+            [
+                global.path().join("demo.toml"),
+                local.path().join("demo.toml")
+            ],
+            "{warnings:?}"
+        );
+        let err = builder()
+            .deny_warnings([WarningKind::UnmigratedFile])
+            .load()
+            .unwrap_err();
         assert!(
-            names.iter().any(|k| k.contains("missing_footote")),
-            "bare typo must be rejected: {names:?}"
+            matches!(&err, ClapfigError::DeniedWarnings(w) if w == &warnings),
+            "{err:?}"
         );
+
+        let result = builder()
+            .handle(&ConfigAction::Migrate { scope: None })
+            .unwrap();
+        let ConfigResult::Migrated { files } = &result else {
+            panic!("expected Migrated, got {result:?}");
+        };
+        assert_eq!(files.len(), 2);
+        let local_text = fs::read_to_string(local.path().join("demo.toml")).unwrap();
         assert!(
-            !names
-                .iter()
-                .any(|k| k.contains("acme.task-due-date-missing")),
-            "dotted extension key must be accepted: {names:?}"
+            local_text.starts_with("# local overrides\nhost = \"dev\" # kept\n"),
+            "{local_text}"
+        );
+        assert!(local_text.contains("port = 3000"), "{local_text}");
+        assert!(!local_text.contains("listen_port"), "{local_text}");
+        assert!(local_text.contains("schema_version = 1"), "{local_text}");
+        let global_text = fs::read_to_string(global.path().join("demo.toml")).unwrap();
+        assert!(!global_text.contains("[database]"), "{global_text}");
+        assert!(
+            result
+                .to_string()
+                .contains("renamed `database` to `db` (version 1)"),
+            "{result}"
+        );
+
+        // Migrated files load the same, warn no more, and a second run
+        // has nothing to do.
+        assert_eq!(builder().load().unwrap(), table);
+        assert!(
+            builder()
+                .deny_warnings([WarningKind::UnmigratedFile])
+                .load()
+                .is_ok()
         );
+        let again = builder()
+            .handle(&ConfigAction::Migrate { scope: None })
+            .unwrap();
+        assert_eq!(again.to_string(), "Nothing to migrate");
     }
 
-    // --- `accept_dotted_extension_keys_in` helper (issue #54 item 6) ---
+    // --- cli_overrides_from auto-matching ---
 
-    fn dotted_ext_schema() -> Schema {
-        Schema::object("Cfg")
-            .nested(
-                "diagnostics",
-                Schema::object("Diag").nested("rules", Schema::object("Rules")),
-            )
-            .build()
+    #[test]
+    fn cli_overrides_from_matches_known_keys_only() {
+        #[derive(serde::Serialize)]
+        struct Args {
+            host: Option<String>,
+            port: Option<i64>,
+            verbose: bool, // not in schema
+        }
+        let args = Args {
+            host: Some("from-cli".into()),
+            port: Some(4242),
+            verbose: true,
+        };
+        let dir = TempDir::new().unwrap();
+        let table = Clapfig::builder(demo_schema())
+            .app_name("demo")
+            .file_name("demo.toml")
+            .search_paths(vec![SearchPath::Path(dir.path().to_path_buf())])
+            .no_env()
+            .cli_overrides_from(&args)
+            .load()
+            .unwrap();
+        assert_eq!(table.get("host"), Some(&Value::String("from-cli".into())));
+        assert_eq!(table.get("port"), Some(&Value::Integer(4242)));
+        // `verbose` was silently ignored — not in schema.
+        assert!(!table.contains_key("verbose"));
     }
 
+    #[cfg(feature = "clap")]
     #[test]
-    fn dotted_ext_helper_accepts_dotted_under_path() {
+    fn generated_flags_override_files_at_the_cli_layer() {
         let dir = TempDir::new().unwrap();
         fs::write(
             dir.path().join("demo.toml"),
-            "[diagnostics.rules]\n\"acme.task-due-date-missing\" = \"error\"\n",
+            "port = 1111\nhost = \"file\"\n",
         )
         .unwrap();
-        let result = Clapfig::builder(dotted_ext_schema())
+        let flags = crate::ConfigFlags::new();
+        let matches = flags
+            .augment(clap::Command::new("demo"), &demo_schema())
+            .try_get_matches_from(["demo", "--port", "2222"])
+            .unwrap();
+        let table = Clapfig::builder(demo_schema())
             .app_name("demo")
             .file_name("demo.toml")
             .search_paths(vec![SearchPath::Path(dir.path().to_path_buf())])
             .no_env()
-            .strict(true)
-            .accept_dotted_extension_keys_in("diagnostics.rules", crate::UnknownKeyDecision::Accept)
-            .load();
-        assert!(
-            result.is_ok(),
-            "dotted leaf under configured path must be accepted: {:?}",
-            result.err()
-        );
+            .cli_overrides_from_matches(&flags, &matches)
+            .load()
+            .unwrap();
+        assert_eq!(table.get("port"), Some(&Value::Integer(2222)));
+        // A flag not given leaves the file's value alone.
+        assert_eq!(table.get("host"), Some(&Value::String("file".into())));
     }
 
+    #[cfg(feature = "clap")]
     #[test]
-    fn dotted_ext_helper_rejects_bare_typo_under_path() {
+    fn a_generated_bool_flag_turns_off_a_files_true() {
         let dir = TempDir::new().unwrap();
-        fs::write(
-            dir.path().join("demo.toml"),
-            "[diagnostics.rules]\nmissing_footote = \"warn\"\n",
-        )
-        .unwrap();
-        let result = Clapfig::builder(dotted_ext_schema())
+        fs::write(dir.path().join("demo.toml"), "verbose = true\n").unwrap();
+        let schema = Schema::object("App")
+            .field("verbose", RtField::boolean().default(false))
+            .build();
+        let flags = crate::ConfigFlags::new();
+        let matches = flags
+            .augment(clap::Command::new("demo"), &schema)
+            .try_get_matches_from(["demo", "--verbose=false"])
+            .unwrap();
+        let table = Clapfig::builder(schema)
             .app_name("demo")
             .file_name("demo.toml")
             .search_paths(vec![SearchPath::Path(dir.path().to_path_buf())])
             .no_env()
-            .strict(true)
-            .accept_dotted_extension_keys_in("diagnostics.rules", crate::UnknownKeyDecision::Accept)
-            .load();
-        let err = result.unwrap_err();
-        let keys = err.unknown_keys().expect("UnknownKeys");
-        assert_eq!(keys.len(), 1);
-        assert!(keys[0].key.contains("missing_footote"));
+            .cli_overrides_from_matches(&flags, &matches)
+            .load()
+            .unwrap();
+        assert_eq!(table.get("verbose"), Some(&Value::Boolean(false)));
     }
 
     #[test]
-    fn dotted_ext_helper_path_boundary_enforced_by_segment() {
-        // `diag` would substring-match `diagnostics` but the helper
-        // enforces a segment boundary, so the rule does NOT apply.
+    fn cli_assignments_override_files_typed_by_the_schema() {
         let dir = TempDir::new().unwrap();
-        fs::write(
-            dir.path().join("demo.toml"),
-            "[diagnostics.rules]\n\"acme.x\" = \"warn\"\n",
-        )
-        .unwrap();
-        let result = Clapfig::builder(dotted_ext_schema())
+        fs::write(dir.path().join("demo.toml"), "port = 1111\n").unwrap();
+        let table = Clapfig::builder(demo_schema())
             .app_name("demo")
             .file_name("demo.toml")
             .search_paths(vec![SearchPath::Path(dir.path().to_path_buf())])
             .no_env()
-            .strict(true)
-            .accept_dotted_extension_keys_in("diag", crate::UnknownKeyDecision::Accept)
-            .load();
-        // `diag` is not a real prefix of `diagnostics.rules` at segment
-        // level, so the helper's rule doesn't fire — the dotted key
-        // falls through to Reject.
-        assert!(result.is_err());
+            .cli_override("port", Some(2222))
+            .cli_assignments(["port=3333", "host=h"])
+            .load()
+            .unwrap();
+        assert_eq!(table.get("port"), Some(&Value::Integer(3333)));
+        assert_eq!(table.get("host"), Some(&Value::String("h".into())));
     }
 
     #[test]
-    fn dotted_ext_helper_collect_routes_into_load_with_unknowns_list() {
+    fn a_cli_assignment_of_the_unset_marker_resets_the_key() {
         let dir = TempDir::new().unwrap();
-        fs::write(
-            dir.path().join("demo.toml"),
-            "[diagnostics.rules]\n\"acme.x-rule\" = \"warn\"\n",
-        )
-        .unwrap();
-        let (_table, unknowns) = Clapfig::builder(dotted_ext_schema())
+        fs::write(dir.path().join("demo.toml"), "port = 1111\n").unwrap();
+        let table = Clapfig::builder(demo_schema())
             .app_name("demo")
             .file_name("demo.toml")
             .search_paths(vec![SearchPath::Path(dir.path().to_path_buf())])
             .no_env()
-            .strict(true)
-            .accept_dotted_extension_keys_in(
-                "diagnostics.rules",
-                crate::UnknownKeyDecision::Collect,
-            )
-            .load_with_unknowns()
+            .unset_marker("<unset>")
+            .cli_assignments(["port=<unset>"])
+            .load()
             .unwrap();
-        assert_eq!(unknowns.len(), 1);
-        assert_eq!(unknowns[0].leaf, "acme.x-rule");
+        assert_eq!(table.get("port"), Some(&Value::Integer(8080)));
     }
 
     #[test]
-    fn dotted_ext_helper_empty_path_applies_everywhere() {
-        // Empty path → rule applies at every level. A dotted leaf at the
-        // top level (rare but possible with quoted keys) is accepted.
-        let dir = TempDir::new().unwrap();
-        // Use a schema with NO declared sections so the top-level dotted
-        // key is unknown to the schema. Using an empty Cfg schema with
-        // a known field would still leave the dotted key unknown — what
-        // matters is the unknown-key path triggers.
-        fs::write(dir.path().join("demo.toml"), "\"acme.x\" = \"warn\"\n").unwrap();
-        let schema = Schema::object("Cfg").build();
-        let result = Clapfig::builder(schema)
+    fn a_cli_assignment_may_spell_a_field_by_its_alias() {
+        let schema = Schema::object("App")
+            .field(
+                "timeout",
+                RtField::integer().alias("timeout_secs").default(30i64),
+            )
+            .build();
+        let table = Clapfig::builder(schema)
             .app_name("demo")
-            .file_name("demo.toml")
-            .search_paths(vec![SearchPath::Path(dir.path().to_path_buf())])
             .no_env()
-            .strict(true)
-            .accept_dotted_extension_keys_in("", crate::UnknownKeyDecision::Accept)
-            .load();
-        assert!(
-            result.is_ok(),
-            "empty path must apply rule globally: {:?}",
-            result.err()
-        );
+            .search_paths(vec![])
+            .cli_assignments(["timeout_secs=5"])
+            .load()
+            .unwrap();
+        assert_eq!(table.get("timeout"), Some(&Value::Integer(5)));
     }
 
-    // --- Resolver cache behavior ---
-
-    fn resolver_with_path(dir: &std::path::Path) -> Resolver {
-        Clapfig::builder(demo_schema())
+    #[test]
+    fn get_set_and_unset_follow_the_alias_spelling_a_file_uses() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("demo.toml");
+        let builder = || {
+            Clapfig::builder(
+                Schema::object("App")
+                    .nested(
+                        "db",
+                        Schema::object("Db")
+                            .field("pool_size", RtField::integer().alias("pool").optional()),
+                    )
+                    .build(),
+            )
             .app_name("demo")
             .file_name("demo.toml")
-            .search_paths(vec![SearchPath::Path(dir.to_path_buf())])
+            .search_paths(vec![SearchPath::Path(dir.path().to_path_buf())])
+            .persist_scope("local", SearchPath::Path(dir.path().to_path_buf()))
             .no_env()
-            .build_resolver()
-            .unwrap()
+        };
+        let handle = |action: ConfigAction| builder().handle(&action);
+        let get = |key: &str, scope: Option<&str>| {
+            handle(ConfigAction::Get {
+                key: key.into(),
+                scope: scope.map(Into::into),
+                origin: false,
+            })
+            .map(|result| result.to_string())
+        };
+        let set = |key: &str| {
+            handle(ConfigAction::Set {
+                key: key.into(),
+                value: "4".into(),
+                scope: None,
+            })
+        };
+        let unset = |key: &str| {
+            handle(ConfigAction::Unset {
+                key: key.into(),
+                scope: None,
+            })
+        };
+
+        // Reads accept either spelling, merged or scoped.
+        fs::write(&file, "[db]\npool = 7\n").unwrap();
+        for key in ["db.pool", "db.pool_size"] {
+            for scope in [None, Some("local")] {
+                let shown = get(key, scope).unwrap();
+                assert!(shown.ends_with("= 7"), "{key} {scope:?}: {shown}");
+            }
+        }
+
+        // A set replaces the alias in place instead of adding a sibling.
+        set("db.pool_size").unwrap();
+        assert_eq!(fs::read_to_string(&file).unwrap(), "[db]\npool = 4\n");
+        assert_eq!(
+            builder().load().unwrap()["db"].as_map().unwrap()["pool_size"],
+            Value::Integer(4)
+        );
+
+        // Unset removes whichever spelling the file holds.
+        unset("db.pool_size").unwrap();
+        assert_eq!(fs::read_to_string(&file).unwrap(), "[db]\n");
+        fs::write(&file, "[db]\npool_size = 4\n").unwrap();
+        unset("db.pool").unwrap();
+        assert_eq!(fs::read_to_string(&file).unwrap(), "[db]\n");
+
+        // Nothing to remove fails instead of reporting success.
+        let err = unset("db.pool").unwrap_err();
+        assert!(matches!(err, ClapfigError::KeyNotFound { .. }), "{err:?}");
+
+        // A file already holding both spellings is refused, as its load is.
+        fs::write(&file, "[db]\npool = 7\npool_size = 4\n").unwrap();
+        for result in [set("db.pool_size"), unset("db.pool")] {
+            match result.unwrap_err() {
+                ClapfigError::AliasCollision {
+                    input, originals, ..
+                } => {
+                    assert_eq!(input, file.display().to_string());
+                    assert_eq!(originals, ["pool", "pool_size"]);
+                }
+                other => panic!("expected AliasCollision, got {other:?}"),
+            }
+        }
+        assert_eq!(
+            fs::read_to_string(&file).unwrap(),
+            "[db]\npool = 7\npool_size = 4\n"
+        );
     }
 
     #[test]
-    fn cache_populates_on_first_read() {
+    fn unknown_cli_assignment_fails_before_any_file_is_read() {
         let dir = TempDir::new().unwrap();
-        fs::write(dir.path().join("demo.toml"), "port = 3000\n").unwrap();
-        let resolver = resolver_with_path(dir.path());
-        assert_eq!(resolver.cache_size(), 0);
-        resolver.resolve_at(dir.path()).unwrap();
-        assert_eq!(resolver.cache_size(), 1);
+        // A broken file would fail the load; the bad key must fail first.
+        fs::write(dir.path().join("demo.toml"), "port = [\n").unwrap();
+        let builder = || {
+            Clapfig::builder(demo_schema())
+                .app_name("demo")
+                .file_name("demo.toml")
+                .search_paths(vec![SearchPath::Path(dir.path().to_path_buf())])
+                .no_env()
+                .cli_assignments(["prot=1"])
+        };
+        match builder().load().unwrap_err() {
+            ClapfigError::KeyNotFound { key, suggestion } => {
+                assert_eq!(key, "prot");
+                assert_eq!(suggestion.as_deref(), Some("port"));
+            }
+            other => panic!("expected KeyNotFound, got {other:?}"),
+        }
+        let err = builder()
+            .handle(&ConfigAction::Gen { output: None })
+            .unwrap_err();
+        assert!(matches!(err, ClapfigError::KeyNotFound { .. }), "{err:?}");
     }
 
-    #[test]
-    fn cache_hit_on_second_read_of_same_file() {
-        let dir = TempDir::new().unwrap();
-        let path = dir.path().join("demo.toml");
-        fs::write(&path, "port = 3000\n").unwrap();
-        let resolver = resolver_with_path(dir.path());
+    // --- Phase 3 cascading strictness (#37) ---
 
-        let table1 = resolver.resolve_at(dir.path()).unwrap();
-        assert_eq!(table1.get("port"), Some(&Value::Integer(3000)));
-        assert_eq!(resolver.cache_size(), 1);
+    use crate::{UnknownKeyContext, UnknownKeyDecision};
 
-        // Rewrite the file on disk. If the cache is honored, the second
-        // resolve returns the ORIGINAL value, not the new one — the
-        // contract is "no mtime check; build a new resolver for
-        // freshness."
-        fs::write(&path, "port = 9999\n").unwrap();
-        let table2 = resolver.resolve_at(dir.path()).unwrap();
-        assert_eq!(
-            table2.get("port"),
-            Some(&Value::Integer(3000)),
-            "cache should mask on-disk changes"
-        );
-        assert_eq!(resolver.cache_size(), 1, "no new cache entry");
+    fn three_level_schema() -> Schema {
+        // Top -> mid -> deep, each a nested section. Used for the
+        // 3-level-cascade tests.
+        Schema::object("Top")
+            .field("name", RtField::string().default("x"))
+            .nested(
+                "mid",
+                Schema::object("Mid")
+                    .field("m_field", RtField::string().default("mv"))
+                    .nested(
+                        "deep",
+                        Schema::object("Deep").field("d_field", RtField::string().default("dv")),
+                    ),
+            )
+            .build()
     }
 
     #[test]
-    fn cache_shared_ancestor_across_resolves_dedups() {
-        use crate::types::Boundary;
-        let root = TempDir::new().unwrap();
-        let a_leaf = root.path().join("a");
-        let b_leaf = root.path().join("b");
-        fs::create_dir_all(&a_leaf).unwrap();
-        fs::create_dir_all(&b_leaf).unwrap();
-        // Only the shared root file exists.
-        fs::write(root.path().join("demo.toml"), "port = 7777\n").unwrap();
-
-        let resolver = Clapfig::builder(demo_schema())
+    fn schema_strict_cascade_through_three_levels() {
+        // Top: strict false (the runtime equivalent of strict_at("", false))
+        // mid + deep inherit lenient. Unknown key 4 levels deep drops.
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("demo.toml"), "[mid.deep]\nrogue = 1\n").unwrap();
+        let mut schema = three_level_schema();
+        schema.strict = Some(false);
+        let table = Clapfig::builder(schema)
             .app_name("demo")
             .file_name("demo.toml")
-            .search_paths(vec![SearchPath::Ancestors(Boundary::Root)])
+            .search_paths(vec![SearchPath::Path(dir.path().to_path_buf())])
             .no_env()
-            .build_resolver()
+            .load()
             .unwrap();
-
-        resolver.resolve_at(&a_leaf).unwrap();
-        let cache_after_a = resolver.cache_size();
-        resolver.resolve_at(&b_leaf).unwrap();
-        let cache_after_b = resolver.cache_size();
-
-        assert!(cache_after_a >= 1);
-        assert_eq!(
-            cache_after_b, cache_after_a,
-            "shared ancestor file should be deduplicated in cache"
+        // Unknown key dropped silently; the merged table mirrors what was
+        // in the file.
+        assert!(
+            table
+                .get("mid")
+                .and_then(|v| v.as_map())
+                .and_then(|t| t.get("deep"))
+                .is_some()
         );
     }
 
-    // --- search modes ---
-
     #[test]
-    fn first_match_uses_highest_priority_file_only() {
-        let dir1 = TempDir::new().unwrap();
-        let dir2 = TempDir::new().unwrap();
+    fn descendant_can_re_tighten_subtree() {
+        // mid is lenient, mid.deep re-tightens — rogue at mid drops, rogue
+        // at mid.deep errors.
+        let dir = TempDir::new().unwrap();
         fs::write(
-            dir1.path().join("demo.toml"),
-            "port = 1000\nhost = \"low\"\n",
+            dir.path().join("demo.toml"),
+            "[mid]\nm_field = \"v\"\nmid_rogue = 1\n[mid.deep]\nd_field = \"v\"\ndeep_rogue = 1\n",
         )
         .unwrap();
-        fs::write(dir2.path().join("demo.toml"), "port = 2000\n").unwrap();
-
-        let table = Clapfig::builder(demo_schema())
+        let schema = Schema::object("Top")
+            .field("name", RtField::string().default("x"))
+            .nested(
+                "mid",
+                Schema::object("Mid")
+                    .strict(false)
+                    .field("m_field", RtField::string().default("v"))
+                    .nested(
+                        "deep",
+                        Schema::object("Deep")
+                            .strict(true)
+                            .field("d_field", RtField::string().default("v")),
+                    ),
+            )
+            .build();
+        let result = Clapfig::builder(schema)
             .app_name("demo")
             .file_name("demo.toml")
-            .search_paths(vec![
-                SearchPath::Path(dir1.path().to_path_buf()),
-                SearchPath::Path(dir2.path().to_path_buf()), // highest priority
-            ])
-            .search_mode(SearchMode::FirstMatch)
+            .search_paths(vec![SearchPath::Path(dir.path().to_path_buf())])
             .no_env()
-            .load()
-            .unwrap();
-
-        // Should use dir2 only — port from dir2, host from defaults (not dir1!)
-        assert_eq!(table.get("port"), Some(&Value::Integer(2000)));
-        assert_eq!(table.get("host"), Some(&Value::String("localhost".into())));
+            .load();
+        let err = result.unwrap_err();
+        let keys = err.unknown_keys().expect("expected UnknownKeys");
+        let names: Vec<&str> = keys.iter().map(|k| k.key.as_str()).collect();
+        assert!(
+            names.contains(&"mid.deep.deep_rogue"),
+            "deep_rogue should be rejected: {names:?}"
+        );
+        assert!(
+            !names.contains(&"mid.mid_rogue"),
+            "mid_rogue should be lenient under strict(false): {names:?}"
+        );
     }
 
     #[test]
-    fn merge_mode_combines_both_files() {
-        let dir1 = TempDir::new().unwrap();
-        let dir2 = TempDir::new().unwrap();
+    fn runtime_strict_at_overlays_schema_strict() {
+        // Schema sets mid strict=false; builder strict_at("mid", true)
+        // overrides. Result: mid rogue is rejected.
+        let dir = TempDir::new().unwrap();
         fs::write(
-            dir1.path().join("demo.toml"),
-            "port = 1000\nhost = \"base\"\n",
+            dir.path().join("demo.toml"),
+            "[mid]\nm_field = \"v\"\nrogue = 1\n",
         )
         .unwrap();
-        fs::write(dir2.path().join("demo.toml"), "port = 2000\n").unwrap();
-
-        let table = Clapfig::builder(demo_schema())
+        let schema = Schema::object("Top")
+            .field("name", RtField::string().default("x"))
+            .nested(
+                "mid",
+                Schema::object("Mid")
+                    .strict(false)
+                    .field("m_field", RtField::string().default("v")),
+            )
+            .build();
+        let result = Clapfig::builder(schema)
             .app_name("demo")
             .file_name("demo.toml")
-            .search_paths(vec![
-                SearchPath::Path(dir1.path().to_path_buf()),
-                SearchPath::Path(dir2.path().to_path_buf()),
-            ])
-            .search_mode(SearchMode::Merge)
+            .search_paths(vec![SearchPath::Path(dir.path().to_path_buf())])
             .no_env()
-            .load()
-            .unwrap();
+            .strict_at("mid", true) // overlay re-tightens
+            .load();
+        let err = result.unwrap_err();
+        let keys = err.unknown_keys().expect("expected UnknownKeys");
+        assert_eq!(keys.len(), 1);
+        assert_eq!(keys[0].key, "mid.rogue");
+    }
 
-        // Merge: port from dir2 (higher priority), host from dir1 (lower priority)
+    #[test]
+    fn runtime_lex_fmt_style_sibling_callback() {
+        // The use-case from the proposal: typed fields and a free-form
+        // catch-all share a struct level. The cascade alone can't
+        // distinguish them; the callback applies a domain-specific rule
+        // (here: "leaf contains a `.` → accept, else reject").
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("demo.toml"),
+            "[diagnostics.rules]\nmissing_footote = \"warn\"\n\"acme.task-due-date-missing\" = \"error\"\n",
+        )
+        .unwrap();
+        let schema = Schema::object("Cfg")
+            .nested(
+                "diagnostics",
+                Schema::object("Diag").nested("rules", Schema::object("Rules")), // empty rules: any key is unknown
+            )
+            .build();
+        let result = Clapfig::builder(schema)
+            .app_name("demo")
+            .file_name("demo.toml")
+            .search_paths(vec![SearchPath::Path(dir.path().to_path_buf())])
+            .no_env()
+            .strict(true)
+            .on_unknown_key(|c: &UnknownKeyContext<'_>| {
+                if c.path.starts_with("diagnostics.rules.") && c.leaf.contains('.') {
+                    UnknownKeyDecision::Accept
+                } else {
+                    UnknownKeyDecision::Reject
+                }
+            })
+            .load();
+        let err = result.unwrap_err();
+        let keys = err.unknown_keys().expect("expected UnknownKeys");
+        let names: Vec<&str> = keys.iter().map(|k| k.key.as_str()).collect();
+        assert!(
+            names.iter().any(|k| k.contains("missing_footote")),
+            "bare typo must be rejected: {names:?}"
+        );
+        assert!(
+            !names
+                .iter()
+                .any(|k| k.contains("acme.task-due-date-missing")),
+            "dotted extension key must be accepted: {names:?}"
+        );
+    }
+
+    // --- `accept_dotted_extension_keys_in` helper (issue #54 item 6) ---
+
+    fn dotted_ext_schema() -> Schema {
+        Schema::object("Cfg")
+            .nested(
+                "diagnostics",
+                Schema::object("Diag").nested("rules", Schema::object("Rules")),
+            )
+            .build()
+    }
+
+    #[test]
+    fn dotted_ext_helper_accepts_dotted_under_path() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("demo.toml"),
+            "[diagnostics.rules]\n\"acme.task-due-date-missing\" = \"error\"\n",
+        )
+        .unwrap();
+        let result = Clapfig::builder(dotted_ext_schema())
+            .app_name("demo")
+            .file_name("demo.toml")
+            .search_paths(vec![SearchPath::Path(dir.path().to_path_buf())])
+            .no_env()
+            .strict(true)
+            .accept_dotted_extension_keys_in("diagnostics.rules", crate::UnknownKeyDecision::Accept)
+            .load();
+        assert!(
+            result.is_ok(),
+            "dotted leaf under configured path must be accepted: {:?}",
+            result.err()
+        );
+    }
+
+    #[test]
+    fn dotted_ext_helper_rejects_bare_typo_under_path() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("demo.toml"),
+            "[diagnostics.rules]\nmissing_footote = \"warn\"\n",
+        )
+        .unwrap();
+        let result = Clapfig::builder(dotted_ext_schema())
+            .app_name("demo")
+            .file_name("demo.toml")
+            .search_paths(vec![SearchPath::Path(dir.path().to_path_buf())])
+            .no_env()
+            .strict(true)
+            .accept_dotted_extension_keys_in("diagnostics.rules", crate::UnknownKeyDecision::Accept)
+            .load();
+        let err = result.unwrap_err();
+        let keys = err.unknown_keys().expect("UnknownKeys");
+        assert_eq!(keys.len(), 1);
+        assert!(keys[0].key.contains("missing_footote"));
+    }
+
+    #[test]
+    fn dotted_ext_helper_path_boundary_enforced_by_segment() {
+        // `diag` would substring-match `diagnostics` but the helper
+        // enforces a segment boundary, so the rule does NOT apply.
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("demo.toml"),
+            "[diagnostics.rules]\n\"acme.x\" = \"warn\"\n",
+        )
+        .unwrap();
+        let result = Clapfig::builder(dotted_ext_schema())
+            .app_name("demo")
+            .file_name("demo.toml")
+            .search_paths(vec![SearchPath::Path(dir.path().to_path_buf())])
+            .no_env()
+            .strict(true)
+            .accept_dotted_extension_keys_in("diag", crate::UnknownKeyDecision::Accept)
+            .load();
+        // `diag` is not a real prefix of `diagnostics.rules` at segment
+        // level, so the helper's rule doesn't fire — the dotted key
+        // falls through to Reject.
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn dotted_ext_helper_collect_routes_into_load_with_unknowns_list() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("demo.toml"),
+            "[diagnostics.rules]\n\"acme.x-rule\" = \"warn\"\n",
+        )
+        .unwrap();
+        let (_table, unknowns) = Clapfig::builder(dotted_ext_schema())
+            .app_name("demo")
+            .file_name("demo.toml")
+            .search_paths(vec![SearchPath::Path(dir.path().to_path_buf())])
+            .no_env()
+            .strict(true)
+            .accept_dotted_extension_keys_in(
+                "diagnostics.rules",
+                crate::UnknownKeyDecision::Collect,
+            )
+            .load_with_unknowns()
+            .unwrap();
+        assert_eq!(unknowns.len(), 1);
+        assert_eq!(unknowns[0].leaf, "acme.x-rule");
+    }
+
+    #[test]
+    fn dotted_ext_helper_empty_path_applies_everywhere() {
+        // Empty path → rule applies at every level. A dotted leaf at the
+        // top level (rare but possible with quoted keys) is accepted.
+        let dir = TempDir::new().unwrap();
+        // Use a schema with NO declared sections so the top-level dotted
+        // key is unknown to the schema. Using an empty Cfg schema with
+        // a known field would still leave the dotted key unknown — what
+        // matters is the unknown-key path triggers.
+        fs::write(dir.path().join("demo.toml"), "\"acme.x\" = \"warn\"\n").unwrap();
+        let schema = Schema::object("Cfg").build();
+        let result = Clapfig::builder(schema)
+            .app_name("demo")
+            .file_name("demo.toml")
+            .search_paths(vec![SearchPath::Path(dir.path().to_path_buf())])
+            .no_env()
+            .strict(true)
+            .accept_dotted_extension_keys_in("", crate::UnknownKeyDecision::Accept)
+            .load();
+        assert!(
+            result.is_ok(),
+            "empty path must apply rule globally: {:?}",
+            result.err()
+        );
+    }
+
+    // --- Resolver cache behavior ---
+
+    fn resolver_with_path(dir: &std::path::Path) -> Resolver {
+        Clapfig::builder(demo_schema())
+            .app_name("demo")
+            .file_name("demo.toml")
+            .search_paths(vec![SearchPath::Path(dir.to_path_buf())])
+            .no_env()
+            .build_resolver()
+            .unwrap()
+    }
+
+    #[test]
+    fn cache_populates_on_first_read() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("demo.toml"), "port = 3000\n").unwrap();
+        let resolver = resolver_with_path(dir.path());
+        assert_eq!(resolver.cache_size(), 0);
+        resolver.resolve_at(dir.path()).unwrap();
+        assert_eq!(resolver.cache_size(), 1);
+    }
+
+    #[test]
+    fn cache_hit_on_second_read_of_same_file() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("demo.toml");
+        fs::write(&path, "port = 3000\n").unwrap();
+        let resolver = resolver_with_path(dir.path());
+
+        let table1 = resolver.resolve_at(dir.path()).unwrap();
+        assert_eq!(table1.get("port"), Some(&Value::Integer(3000)));
+        assert_eq!(resolver.cache_size(), 1);
+
+        // Rewrite the file on disk. If the cache is honored, the second
+        // resolve returns the ORIGINAL value, not the new one — the
+        // default contract is "no mtime check; opt into a freshness
+        // policy or invalidate for freshness."
+        fs::write(&path, "port = 9999\n").unwrap();
+        let table2 = resolver.resolve_at(dir.path()).unwrap();
+        assert_eq!(
+            table2.get("port"),
+            Some(&Value::Integer(3000)),
+            "cache should mask on-disk changes"
+        );
+        assert_eq!(resolver.cache_size(), 1, "no new cache entry");
+    }
+
+    #[test]
+    fn cache_shared_ancestor_across_resolves_dedups() {
+        use crate::types::Boundary;
+        let root = TempDir::new().unwrap();
+        let a_leaf = root.path().join("a");
+        let b_leaf = root.path().join("b");
+        fs::create_dir_all(&a_leaf).unwrap();
+        fs::create_dir_all(&b_leaf).unwrap();
+        // Only the shared root file exists.
+        fs::write(root.path().join("demo.toml"), "port = 7777\n").unwrap();
+
+        let resolver = Clapfig::builder(demo_schema())
+            .app_name("demo")
+            .file_name("demo.toml")
+            .search_paths(vec![SearchPath::Ancestors(Boundary::Root)])
+            .no_env()
+            .build_resolver()
+            .unwrap();
+
+        resolver.resolve_at(&a_leaf).unwrap();
+        let cache_after_a = resolver.cache_size();
+        resolver.resolve_at(&b_leaf).unwrap();
+        let cache_after_b = resolver.cache_size();
+
+        assert!(cache_after_a >= 1);
+        assert_eq!(
+            cache_after_b, cache_after_a,
+            "shared ancestor file should be deduplicated in cache"
+        );
+    }
+
+    fn resolver_with_freshness(dir: &std::path::Path, freshness: CacheFreshness) -> Resolver {
+        Clapfig::builder(demo_schema())
+            .app_name("demo")
+            .file_name("demo.toml")
+            .search_paths(vec![SearchPath::Path(dir.to_path_buf())])
+            .no_env()
+            .cache_freshness(freshness)
+            .build_resolver()
+            .unwrap()
+    }
+
+    #[test]
+    fn invalidate_drops_one_entry_and_rereads_it() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("demo.toml");
+        fs::write(&path, "port = 3000\n").unwrap();
+        let resolver = resolver_with_path(dir.path());
+        resolver.resolve_at(dir.path()).unwrap();
+
+        fs::write(&path, "port = 9999\n").unwrap();
+        // The cache key is canonical; a non-canonical spelling still hits.
+        let dotted = dir.path().join(".").join("demo.toml");
+        assert!(resolver.invalidate(&dotted));
+        assert_eq!(resolver.cache_size(), 0);
+        assert!(!resolver.invalidate(&path), "already gone");
+
+        let table = resolver.resolve_at(dir.path()).unwrap();
+        assert_eq!(table.get("port"), Some(&Value::Integer(9999)));
+    }
+
+    #[test]
+    fn clear_cache_empties_the_cache() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("demo.toml");
+        fs::write(&path, "port = 3000\n").unwrap();
+        let resolver = resolver_with_path(dir.path());
+        resolver.resolve_at(dir.path()).unwrap();
+
+        fs::write(&path, "port = 9999\n").unwrap();
+        resolver.clear_cache();
+        assert_eq!(resolver.cache_size(), 0);
+        let table = resolver.resolve_at(dir.path()).unwrap();
+        assert_eq!(table.get("port"), Some(&Value::Integer(9999)));
+    }
+
+    #[test]
+    fn metadata_freshness_rereads_a_changed_file() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("demo.toml");
+        fs::write(&path, "port = 3000\n").unwrap();
+        let resolver = resolver_with_freshness(dir.path(), CacheFreshness::Metadata);
+        resolver.resolve_at(dir.path()).unwrap();
+
+        // A different length is a change whatever the timestamp
+        // granularity.
+        fs::write(&path, "port = 31000\n").unwrap();
+        let table = resolver.resolve_at(dir.path()).unwrap();
+        assert_eq!(table.get("port"), Some(&Value::Integer(31000)));
+        assert_eq!(resolver.cache_size(), 1, "the entry is replaced");
+    }
+
+    #[test]
+    fn checking_freshness_forgets_a_deleted_file() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("demo.toml");
+        fs::write(&path, "port = 3000\n").unwrap();
+        let resolver = resolver_with_freshness(dir.path(), CacheFreshness::Metadata);
+        resolver.resolve_at(dir.path()).unwrap();
+
+        fs::remove_file(&path).unwrap();
+        let table = resolver.resolve_at(dir.path()).unwrap();
+        assert_eq!(table.get("port"), Some(&Value::Integer(8080)));
+        assert_eq!(resolver.cache_size(), 0);
+    }
+
+    #[test]
+    fn always_freshness_sees_a_same_length_rewrite() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("demo.toml");
+        fs::write(&path, "port = 3000\n").unwrap();
+        let resolver = resolver_with_freshness(dir.path(), CacheFreshness::Always);
+        resolver.resolve_at(dir.path()).unwrap();
+
+        fs::write(&path, "port = 4000\n").unwrap();
+        let table = resolver.resolve_at(dir.path()).unwrap();
+        assert_eq!(table.get("port"), Some(&Value::Integer(4000)));
+    }
+
+    // --- search modes ---
+
+    #[test]
+    fn first_match_uses_highest_priority_file_only() {
+        let dir1 = TempDir::new().unwrap();
+        let dir2 = TempDir::new().unwrap();
+        fs::write(
+            dir1.path().join("demo.toml"),
+            "port = 1000\nhost = \"low\"\n",
+        )
+        .unwrap();
+        fs::write(dir2.path().join("demo.toml"), "port = 2000\n").unwrap();
+
+        let table = Clapfig::builder(demo_schema())
+            .app_name("demo")
+            .file_name("demo.toml")
+            .search_paths(vec![
+                SearchPath::Path(dir1.path().to_path_buf()),
+                SearchPath::Path(dir2.path().to_path_buf()), // highest priority
+            ])
+            .search_mode(SearchMode::FirstMatch)
+            .no_env()
+            .load()
+            .unwrap();
+
+        // Should use dir2 only — port from dir2, host from defaults (not dir1!)
+        assert_eq!(table.get("port"), Some(&Value::Integer(2000)));
+        assert_eq!(table.get("host"), Some(&Value::String("localhost".into())));
+    }
+
+    #[test]
+    fn merge_mode_combines_both_files() {
+        let dir1 = TempDir::new().unwrap();
+        let dir2 = TempDir::new().unwrap();
+        fs::write(
+            dir1.path().join("demo.toml"),
+            "port = 1000\nhost = \"base\"\n",
+        )
+        .unwrap();
+        fs::write(dir2.path().join("demo.toml"), "port = 2000\n").unwrap();
+
+        let table = Clapfig::builder(demo_schema())
+            .app_name("demo")
+            .file_name("demo.toml")
+            .search_paths(vec![
+                SearchPath::Path(dir1.path().to_path_buf()),
+                SearchPath::Path(dir2.path().to_path_buf()),
+            ])
+            .search_mode(SearchMode::Merge)
+            .no_env()
+            .load()
+            .unwrap();
+
+        // Merge: port from dir2 (higher priority), host from dir1 (lower priority)
         assert_eq!(table.get("port"), Some(&Value::Integer(2000)));
         assert_eq!(table.get("host"), Some(&Value::String("base".into())));
     }
@@ -2808,918 +4563,1476 @@ mod tests {
         match err {
             ClapfigError::MissingRequired { key, discovery } => {
                 assert_eq!(key, "name");
-                assert_eq!(discovery.files.len(), 2);
-                assert_eq!(
-                    probe_by_file_name(&discovery.files, miss.path(), "demo.toml").outcome,
-                    ProbeOutcome::Missing
-                );
-                assert_eq!(
-                    probe_by_file_name(&discovery.files, hit.path(), "demo.toml").outcome,
-                    ProbeOutcome::Loaded
+                assert_eq!(discovery.files.len(), 2);
+                assert_eq!(
+                    probe_by_file_name(&discovery.files, miss.path(), "demo.toml").outcome,
+                    ProbeOutcome::Missing
+                );
+                assert_eq!(
+                    probe_by_file_name(&discovery.files, hit.path(), "demo.toml").outcome,
+                    ProbeOutcome::Loaded
+                );
+            }
+            other => panic!("expected MissingRequired, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn missing_required_stem_names_paths_actually_probed_not_the_registry() {
+        // toml + json enabled: both extensions in the directory are
+        // probes. yaml is compiled in but not enabled — its path must
+        // not appear (the record is not a dump of the format registry).
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("demo.toml"), "# no name\n").unwrap();
+        fs::write(dir.path().join("demo.yaml"), "name: yaml-must-not-count\n").unwrap();
+
+        let err = Clapfig::builder(required_only_schema())
+            .app_name("demo")
+            .file_stem("demo")
+            .formats(["toml", "json"])
+            .search_paths(vec![SearchPath::Path(dir.path().to_path_buf())])
+            .no_env()
+            .load()
+            .unwrap_err();
+        match err {
+            ClapfigError::MissingRequired { key, discovery } => {
+                assert_eq!(key, "name");
+                let names: Vec<_> = discovery
+                    .files
+                    .iter()
+                    .map(|p| p.path.file_name().unwrap().to_string_lossy().into_owned())
+                    .collect();
+                assert_eq!(names, vec!["demo.toml", "demo.json"]);
+                assert_eq!(discovery.files[0].outcome, ProbeOutcome::Loaded);
+                assert_eq!(discovery.files[1].outcome, ProbeOutcome::Missing);
+                assert!(
+                    discovery.files.iter().all(|p| !p
+                        .path
+                        .extension()
+                        .is_some_and(|e| e == "yaml" || e == "yml")),
+                    "stem discovery must not dump un-enabled formats: {discovery:?}"
+                );
+            }
+            other => panic!("expected MissingRequired, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn missing_required_first_match_stem_enumerates_unprobed_extensions() {
+        // FirstMatch stops reading at the high-priority directory; the
+        // lower-priority dir's enabled extensions still exist as
+        // not-probed candidates.
+        let low = TempDir::new().unwrap();
+        let high = TempDir::new().unwrap();
+        fs::write(high.path().join("demo.toml"), "# no name\n").unwrap();
+
+        let err = Clapfig::builder(required_only_schema())
+            .app_name("demo")
+            .file_stem("demo")
+            .formats(["toml", "json"])
+            .search_paths(vec![
+                SearchPath::Path(low.path().to_path_buf()),
+                SearchPath::Path(high.path().to_path_buf()),
+            ])
+            .search_mode(SearchMode::FirstMatch)
+            .no_env()
+            .load()
+            .unwrap_err();
+        match err {
+            ClapfigError::MissingRequired { discovery, .. } => {
+                assert_eq!(discovery.files.len(), 4);
+                assert_eq!(
+                    probe_by_file_name(&discovery.files, low.path(), "demo.toml").outcome,
+                    ProbeOutcome::NotProbed
+                );
+                assert_eq!(
+                    probe_by_file_name(&discovery.files, low.path(), "demo.json").outcome,
+                    ProbeOutcome::NotProbed
+                );
+                assert_eq!(
+                    probe_by_file_name(&discovery.files, high.path(), "demo.toml").outcome,
+                    ProbeOutcome::Loaded
+                );
+                assert_eq!(
+                    probe_by_file_name(&discovery.files, high.path(), "demo.json").outcome,
+                    ProbeOutcome::Missing
+                );
+            }
+            other => panic!("expected MissingRequired, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn nested_missing_leaf_with_parent_map_is_still_missing_required() {
+        // File supplies `[db]` (parent map from an input type) but not
+        // the required leaf. Same MissingRequired diagnostic — no
+        // nearest-ancestor origin.
+        let schema = Schema::object("Req")
+            .nested("db", Schema::object("Db").field("url", RtField::string()))
+            .build();
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("demo.toml"), "[db]\n").unwrap();
+
+        let err = Clapfig::builder(schema)
+            .app_name("demo")
+            .file_name("demo.toml")
+            .search_paths(vec![SearchPath::Path(dir.path().to_path_buf())])
+            .no_env()
+            .load()
+            .unwrap_err();
+        match err {
+            ClapfigError::MissingRequired { key, discovery } => {
+                assert_eq!(key, "db.url");
+                assert_eq!(discovery.files.len(), 1);
+                assert_eq!(discovery.files[0].outcome, ProbeOutcome::Loaded);
+                let msg = ClapfigError::MissingRequired { key, discovery }.to_string();
+                assert!(
+                    !msg.contains("set by"),
+                    "nested miss must not claim a parent origin: {msg}"
+                );
+            }
+            other => panic!("expected MissingRequired, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn missing_required_reports_env_consulted_when_enabled() {
+        let dir = TempDir::new().unwrap();
+        let err = Clapfig::builder(required_only_schema())
+            .app_name("demo")
+            .file_name("demo.toml")
+            .search_paths(vec![SearchPath::Path(dir.path().to_path_buf())])
+            .env_prefix("CLAPFIG_WS06_NO_SUCH_PREFIX")
+            .load()
+            .unwrap_err();
+        match err {
+            ClapfigError::MissingRequired { discovery, .. } => {
+                assert!(discovery.env);
+                assert!(!discovery.overrides);
+            }
+            other => panic!("expected MissingRequired, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn missing_required_reports_overrides_consulted_when_supplied() {
+        let dir = TempDir::new().unwrap();
+        let err = Clapfig::builder(required_only_schema())
+            .app_name("demo")
+            .file_name("demo.toml")
+            .search_paths(vec![SearchPath::Path(dir.path().to_path_buf())])
+            .no_env()
+            .cli_override("other", Some("x"))
+            .load()
+            .unwrap_err();
+        match err {
+            ClapfigError::MissingRequired { discovery, .. } => {
+                assert!(!discovery.env);
+                assert!(discovery.overrides);
+            }
+            other => panic!("expected MissingRequired, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn missing_required_omitted_files_layer_skips_probes() {
+        // A file that *would* supply the required key must not be
+        // probed (or merged) when Files is omitted from layer_order.
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("demo.toml"), "name = \"present\"\n").unwrap();
+
+        let err = Clapfig::builder(required_only_schema())
+            .app_name("demo")
+            .file_name("demo.toml")
+            .search_paths(vec![SearchPath::Path(dir.path().to_path_buf())])
+            .no_env()
+            .layer_order(vec![])
+            .load()
+            .unwrap_err();
+        match err {
+            ClapfigError::MissingRequired { key, discovery } => {
+                assert_eq!(key, "name");
+                assert!(
+                    discovery.files.is_empty(),
+                    "omitted Files layer must not list probes: {discovery:?}"
                 );
+                assert!(!discovery.env);
+                assert!(!discovery.overrides);
             }
             other => panic!("expected MissingRequired, got {other:?}"),
         }
     }
 
+    #[cfg(feature = "url")]
     #[test]
-    fn missing_required_stem_names_paths_actually_probed_not_the_registry() {
-        // toml + json enabled: both extensions in the directory are
-        // probes. yaml is compiled in but not enabled — its path must
-        // not appear (the record is not a dump of the format registry).
+    fn missing_required_reports_url_consulted_when_enabled() {
         let dir = TempDir::new().unwrap();
-        fs::write(dir.path().join("demo.toml"), "# no name\n").unwrap();
-        fs::write(dir.path().join("demo.yaml"), "name: yaml-must-not-count\n").unwrap();
-
         let err = Clapfig::builder(required_only_schema())
             .app_name("demo")
-            .file_stem("demo")
-            .formats(["toml", "json"])
+            .file_name("demo.toml")
             .search_paths(vec![SearchPath::Path(dir.path().to_path_buf())])
             .no_env()
+            .url_query("other=x")
             .load()
             .unwrap_err();
         match err {
-            ClapfigError::MissingRequired { key, discovery } => {
-                assert_eq!(key, "name");
-                let names: Vec<_> = discovery
-                    .files
-                    .iter()
-                    .map(|p| p.path.file_name().unwrap().to_string_lossy().into_owned())
-                    .collect();
-                assert_eq!(names, vec!["demo.toml", "demo.json"]);
-                assert_eq!(discovery.files[0].outcome, ProbeOutcome::Loaded);
-                assert_eq!(discovery.files[1].outcome, ProbeOutcome::Missing);
-                assert!(
-                    discovery.files.iter().all(|p| !p
-                        .path
-                        .extension()
-                        .is_some_and(|e| e == "yaml" || e == "yml")),
-                    "stem discovery must not dump un-enabled formats: {discovery:?}"
-                );
+            ClapfigError::MissingRequired { discovery, .. } => {
+                assert!(discovery.url);
+                assert!(!discovery.overrides);
             }
             other => panic!("expected MissingRequired, got {other:?}"),
         }
     }
 
     #[test]
-    fn missing_required_first_match_stem_enumerates_unprobed_extensions() {
-        // FirstMatch stops reading at the high-priority directory; the
-        // lower-priority dir's enabled extensions still exist as
-        // not-probed candidates.
-        let low = TempDir::new().unwrap();
-        let high = TempDir::new().unwrap();
-        fs::write(high.path().join("demo.toml"), "# no name\n").unwrap();
+    fn first_match_falls_back_when_high_priority_missing() {
+        let dir1 = TempDir::new().unwrap();
+        let dir2 = TempDir::new().unwrap();
+        // Only dir1 (lower priority) has a config
+        fs::write(dir1.path().join("demo.toml"), "port = 1000\n").unwrap();
 
-        let err = Clapfig::builder(required_only_schema())
+        let table = Clapfig::builder(demo_schema())
             .app_name("demo")
-            .file_stem("demo")
-            .formats(["toml", "json"])
+            .file_name("demo.toml")
             .search_paths(vec![
-                SearchPath::Path(low.path().to_path_buf()),
-                SearchPath::Path(high.path().to_path_buf()),
+                SearchPath::Path(dir1.path().to_path_buf()),
+                SearchPath::Path(dir2.path().to_path_buf()),
             ])
             .search_mode(SearchMode::FirstMatch)
             .no_env()
             .load()
-            .unwrap_err();
-        match err {
-            ClapfigError::MissingRequired { discovery, .. } => {
-                assert_eq!(discovery.files.len(), 4);
-                assert_eq!(
-                    probe_by_file_name(&discovery.files, low.path(), "demo.toml").outcome,
-                    ProbeOutcome::NotProbed
-                );
-                assert_eq!(
-                    probe_by_file_name(&discovery.files, low.path(), "demo.json").outcome,
-                    ProbeOutcome::NotProbed
-                );
-                assert_eq!(
-                    probe_by_file_name(&discovery.files, high.path(), "demo.toml").outcome,
-                    ProbeOutcome::Loaded
-                );
-                assert_eq!(
-                    probe_by_file_name(&discovery.files, high.path(), "demo.json").outcome,
-                    ProbeOutcome::Missing
-                );
+            .unwrap();
+
+        assert_eq!(table.get("port"), Some(&Value::Integer(1000)));
+    }
+
+    #[test]
+    fn missing_app_name_errors() {
+        let result = Clapfig::builder(demo_schema()).no_env().load();
+        assert!(matches!(result, Err(ClapfigError::AppNameRequired)));
+    }
+
+    // --- layer order (builder wiring; pipeline-level coverage in resolve.rs) ---
+
+    #[test]
+    fn layer_order_cli_below_files() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("demo.toml"), "port = 3000\n").unwrap();
+
+        let table = Clapfig::builder(demo_schema())
+            .app_name("demo")
+            .file_name("demo.toml")
+            .search_paths(vec![SearchPath::Path(dir.path().to_path_buf())])
+            .no_env()
+            .cli_override("port", Some(9999i64))
+            .layer_order(vec![Layer::Cli, Layer::Files])
+            .load()
+            .unwrap();
+
+        // Files listed after Cli, so the file wins.
+        assert_eq!(table.get("port"), Some(&Value::Integer(3000)));
+    }
+
+    #[test]
+    fn omitted_env_layer_does_not_consult_environment() {
+        // Omitting Layer::Env must skip `std::env::vars()` (and merge): a
+        // matching variable must not win, even though env is otherwise
+        // enabled via the prefix. Synthetic ResolveInput coverage lives
+        // in resolve.rs; this locks the builder capture path.
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("demo.toml"), "port = 3000\n").unwrap();
+        const PREFIX: &str = "CLAPFIG_PRV01_OMIT_ENV";
+        const VAR: &str = "CLAPFIG_PRV01_OMIT_ENV__PORT";
+        unsafe { std::env::set_var(VAR, "9999") };
+
+        let table = Clapfig::builder(demo_schema())
+            .app_name("demo")
+            .file_name("demo.toml")
+            .search_paths(vec![SearchPath::Path(dir.path().to_path_buf())])
+            .env_prefix(PREFIX)
+            .layer_order(vec![Layer::Files])
+            .load();
+
+        unsafe { std::env::remove_var(VAR) };
+
+        let table = table.expect("omitted env layer still loads files");
+        assert_eq!(table.get("port"), Some(&Value::Integer(3000)));
+    }
+
+    // --- normalize_keys (builder wiring; pipeline-level coverage in resolve.rs) ---
+
+    #[test]
+    fn normalize_keys_load_accepts_kebab_in_file() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("demo.toml"), "[db]\npool-size = 42\n").unwrap();
+
+        let table = Clapfig::builder(demo_schema())
+            .app_name("demo")
+            .file_name("demo.toml")
+            .search_paths(vec![SearchPath::Path(dir.path().to_path_buf())])
+            .no_env()
+            .normalize_keys(true)
+            .load()
+            .unwrap();
+
+        let db = table.get("db").and_then(Value::as_map).unwrap();
+        assert_eq!(db.get("pool_size"), Some(&Value::Integer(42)));
+    }
+
+    #[test]
+    fn handle_set_normalized_roundtrip_has_no_collision() {
+        // End-to-end regression for #122: with normalize_keys on, set
+        // seeds a kebab file, later sets in EITHER spelling edit that
+        // same key, and the file stays loadable (no
+        // NormalizedKeyCollision from a snake/kebab duplicate).
+        use crate::format::FormatAdapter as _;
+        use crate::format::TomlAdapter;
+
+        let dir = TempDir::new().unwrap();
+        let builder = || {
+            Clapfig::builder(demo_schema())
+                .app_name("demo")
+                .file_name("demo.toml")
+                .persist_scope("local", SearchPath::Path(dir.path().to_path_buf()))
+                .no_env()
+                .normalize_keys(true)
+        };
+        let file = dir.path().join("demo.toml");
+        let db_map = || {
+            let content = fs::read_to_string(&file).unwrap();
+            let tree = TomlAdapter.parse(&content).unwrap().value;
+            tree.as_map().unwrap()["db"].as_map().unwrap().clone()
+        };
+
+        // Missing file: seeded from the kebab template, snake action key.
+        builder()
+            .handle(&ConfigAction::Set {
+                key: "db.pool_size".into(),
+                value: "10".into(),
+                scope: None,
+            })
+            .unwrap();
+        let db = db_map();
+        assert_eq!(db.get("pool-size"), Some(&Value::Integer(10)));
+        assert!(!db.contains_key("pool_size"));
+
+        // Existing kebab file: both spellings edit the same key.
+        builder()
+            .handle(&ConfigAction::Set {
+                key: "db.pool-size".into(),
+                value: "11".into(),
+                scope: None,
+            })
+            .unwrap();
+        builder()
+            .handle(&ConfigAction::Set {
+                key: "db.pool_size".into(),
+                value: "12".into(),
+                scope: None,
+            })
+            .unwrap();
+        let db = db_map();
+        assert_eq!(db.get("pool-size"), Some(&Value::Integer(12)));
+        assert!(!db.contains_key("pool_size"));
+
+        // The file the persistence path wrote loads cleanly.
+        let table = builder().load().unwrap();
+        let db = table.get("db").and_then(Value::as_map).unwrap();
+        assert_eq!(db.get("pool_size"), Some(&Value::Integer(12)));
+
+        // Unset in the snake spelling removes the kebab entry; the
+        // default shows through again on load.
+        builder()
+            .handle(&ConfigAction::Unset {
+                key: "db.pool_size".into(),
+                scope: None,
+            })
+            .unwrap();
+        let db = db_map();
+        assert!(!db.contains_key("pool-size") && !db.contains_key("pool_size"));
+        let table = builder().load().unwrap();
+        let db = table.get("db").and_then(Value::as_map).unwrap();
+        assert_eq!(db.get("pool_size"), Some(&Value::Integer(5)));
+    }
+
+    #[test]
+    fn scoped_get_accepts_both_spellings_on_normalized_files() {
+        // Scoped get reads the raw (un-normalized) file, so a kebab
+        // document must answer for either action-key spelling — across
+        // all three formats — and `--origin` still locates the kebab
+        // key's line.
+        use crate::format::{FormatAdapter, JsonAdapter, TomlAdapter, YamlAdapter};
+
+        let dir = TempDir::new().unwrap();
+        let files: [(&str, &dyn FormatAdapter, &str); 3] = [
+            ("demo.toml", &TomlAdapter, "[db]\npool-size = 42\n"),
+            ("demo.yaml", &YamlAdapter, "db:\n  pool-size: 42\n"),
+            ("demo.json", &JsonAdapter, "{\"db\": {\n\"pool-size\": 42}}"),
+        ];
+        for (name, adapter, content) in files {
+            let path = dir.path().join(name);
+            fs::write(&path, content).unwrap();
+            for key in ["db.pool-size", "db.pool_size"] {
+                let result = get_scope(
+                    adapter,
+                    &Shape::Object(demo_schema()),
+                    "local",
+                    &path,
+                    key,
+                    true,
+                    true,
+                )
+                .unwrap();
+                match result {
+                    ConfigResult::KeyValue {
+                        key: reported,
+                        value,
+                        origin,
+                        ..
+                    } => {
+                        assert_eq!(value, "42", "{name} / {key}");
+                        assert_eq!(reported, key, "reported key keeps the caller's spelling");
+                        let origin = origin.expect("origin requested");
+                        assert_eq!(origin.file.as_deref(), Some(path.as_path()));
+                        assert_eq!(origin.line_col().map(|(line, _)| line), Some(2), "{name}");
+                    }
+                    other => panic!("expected KeyValue, got {other:?}"),
+                }
             }
-            other => panic!("expected MissingRequired, got {other:?}"),
         }
     }
 
     #[test]
-    fn nested_missing_leaf_with_parent_map_is_still_missing_required() {
-        // File supplies `[db]` (parent map from an input type) but not
-        // the required leaf. Same MissingRequired diagnostic — no
-        // nearest-ancestor origin.
-        let schema = Schema::object("Req")
-            .nested("db", Schema::object("Db").field("url", RtField::string()))
-            .build();
+    fn scoped_get_rejects_ambiguous_spellings_as_collision() {
+        // A raw file holding BOTH equivalent spellings is ambiguous — the
+        // load path refuses it — so scoped get errors with the same
+        // collision (stamped with the scope file's path) instead of one
+        // spelling silently answering.
+        use crate::format::TomlAdapter;
         let dir = TempDir::new().unwrap();
-        fs::write(dir.path().join("demo.toml"), "[db]\n").unwrap();
-
-        let err = Clapfig::builder(schema)
-            .app_name("demo")
-            .file_name("demo.toml")
-            .search_paths(vec![SearchPath::Path(dir.path().to_path_buf())])
-            .no_env()
-            .load()
+        let path = dir.path().join("demo.toml");
+        fs::write(&path, "[db]\npool-size = 5\npool_size = 6\n").unwrap();
+        for key in ["db.pool-size", "db.pool_size"] {
+            let err = get_scope(
+                &TomlAdapter,
+                &Shape::Object(demo_schema()),
+                "local",
+                &path,
+                key,
+                true,
+                false,
+            )
             .unwrap_err();
-        match err {
-            ClapfigError::MissingRequired { key, discovery } => {
-                assert_eq!(key, "db.url");
-                assert_eq!(discovery.files.len(), 1);
-                assert_eq!(discovery.files[0].outcome, ProbeOutcome::Loaded);
-                let msg = ClapfigError::MissingRequired { key, discovery }.to_string();
-                assert!(
-                    !msg.contains("set by"),
-                    "nested miss must not claim a parent origin: {msg}"
-                );
+            match err {
+                ClapfigError::NormalizedKeyCollision {
+                    path: reported,
+                    section,
+                    normalized_key,
+                    originals,
+                } => {
+                    assert_eq!(reported, path, "{key}");
+                    assert_eq!(section, "db");
+                    assert_eq!(normalized_key, "pool_size");
+                    assert_eq!(originals, vec!["pool-size", "pool_size"]);
+                }
+                other => panic!("expected NormalizedKeyCollision, got {other:?}"),
             }
-            other => panic!("expected MissingRequired, got {other:?}"),
         }
     }
 
     #[test]
-    fn missing_required_reports_env_consulted_when_enabled() {
+    fn scoped_get_rejects_collision_off_the_requested_path() {
+        // Whole-document validation: `host` itself is unambiguous, but
+        // the file holds both spellings in the untraversed `db` table —
+        // scoped get still fails, because a document the load path
+        // refuses is never queried.
+        use crate::format::TomlAdapter;
         let dir = TempDir::new().unwrap();
-        let err = Clapfig::builder(required_only_schema())
-            .app_name("demo")
-            .file_name("demo.toml")
-            .search_paths(vec![SearchPath::Path(dir.path().to_path_buf())])
-            .env_prefix("CLAPFIG_WS06_NO_SUCH_PREFIX")
-            .load()
-            .unwrap_err();
+        let path = dir.path().join("demo.toml");
+        fs::write(
+            &path,
+            "host = \"h\"\n\n[db]\npool-size = 5\npool_size = 6\n",
+        )
+        .unwrap();
+        let err = get_scope(
+            &TomlAdapter,
+            &Shape::Object(demo_schema()),
+            "local",
+            &path,
+            "host",
+            true,
+            false,
+        )
+        .unwrap_err();
         match err {
-            ClapfigError::MissingRequired { discovery, .. } => {
-                assert!(discovery.env);
-                assert!(!discovery.overrides);
+            ClapfigError::NormalizedKeyCollision {
+                path: reported,
+                section,
+                normalized_key,
+                ..
+            } => {
+                assert_eq!(reported, path);
+                assert_eq!(section, "db");
+                assert_eq!(normalized_key, "pool_size");
             }
-            other => panic!("expected MissingRequired, got {other:?}"),
+            other => panic!("expected NormalizedKeyCollision, got {other:?}"),
         }
     }
 
     #[test]
-    fn missing_required_reports_overrides_consulted_when_supplied() {
+    fn scoped_get_missing_file_names_scope_and_file_not_the_key() {
+        // The key may be perfectly valid — there is simply no file to
+        // read. Claiming "key not found" would send the user hunting for
+        // a typo that isn't there.
+        use crate::format::TomlAdapter;
         let dir = TempDir::new().unwrap();
-        let err = Clapfig::builder(required_only_schema())
-            .app_name("demo")
-            .file_name("demo.toml")
-            .search_paths(vec![SearchPath::Path(dir.path().to_path_buf())])
-            .no_env()
-            .cli_override("other", Some("x"))
-            .load()
+        let path = dir.path().join("nonexistent.toml");
+        for key in ["db.pool-size", "db.pool_size"] {
+            let err = get_scope(
+                &TomlAdapter,
+                &Shape::Object(demo_schema()),
+                "local",
+                &path,
+                key,
+                true,
+                false,
+            )
             .unwrap_err();
-        match err {
-            ClapfigError::MissingRequired { discovery, .. } => {
-                assert!(!discovery.env);
-                assert!(discovery.overrides);
+            match err {
+                ClapfigError::ScopeFileMissing {
+                    scope,
+                    path: reported,
+                } => {
+                    assert_eq!(scope, "local");
+                    assert_eq!(reported, path);
+                }
+                other => panic!("expected ScopeFileMissing, got {other:?}"),
             }
-            other => panic!("expected MissingRequired, got {other:?}"),
         }
     }
 
     #[test]
-    fn missing_required_omitted_files_layer_skips_probes() {
-        // A file that *would* supply the required key must not be
-        // probed (or merged) when Files is omitted from layer_order.
+    fn get_unknown_key_suggests_near_miss() {
         let dir = TempDir::new().unwrap();
-        fs::write(dir.path().join("demo.toml"), "name = \"present\"\n").unwrap();
-
-        let err = Clapfig::builder(required_only_schema())
+        let err = Clapfig::builder(demo_schema())
             .app_name("demo")
-            .file_name("demo.toml")
             .search_paths(vec![SearchPath::Path(dir.path().to_path_buf())])
             .no_env()
-            .layer_order(vec![])
-            .load()
+            .handle(&ConfigAction::Get {
+                key: "db.pool_sizr".into(),
+                scope: None,
+                origin: false,
+            })
             .unwrap_err();
         match err {
-            ClapfigError::MissingRequired { key, discovery } => {
-                assert_eq!(key, "name");
-                assert!(
-                    discovery.files.is_empty(),
-                    "omitted Files layer must not list probes: {discovery:?}"
-                );
-                assert!(!discovery.env);
-                assert!(!discovery.overrides);
+            ClapfigError::KeyNotFound { key, suggestion } => {
+                assert_eq!(key, "db.pool_sizr");
+                assert_eq!(suggestion.as_deref(), Some("db.pool_size"));
             }
-            other => panic!("expected MissingRequired, got {other:?}"),
+            other => panic!("expected KeyNotFound, got {other:?}"),
         }
     }
 
-    #[cfg(feature = "url")]
     #[test]
-    fn missing_required_reports_url_consulted_when_enabled() {
+    fn get_kebab_key_suggests_snake_when_normalization_is_off() {
+        // Default is normalize_keys(false): pool-size is not the schema
+        // key, and the dash/underscore difference is the useful hint.
         let dir = TempDir::new().unwrap();
-        let err = Clapfig::builder(required_only_schema())
+        let err = Clapfig::builder(demo_schema())
             .app_name("demo")
-            .file_name("demo.toml")
             .search_paths(vec![SearchPath::Path(dir.path().to_path_buf())])
             .no_env()
-            .url_query("other=x")
-            .load()
+            .handle(&ConfigAction::Get {
+                key: "db.pool-size".into(),
+                scope: None,
+                origin: false,
+            })
             .unwrap_err();
         match err {
-            ClapfigError::MissingRequired { discovery, .. } => {
-                assert!(discovery.url);
-                assert!(!discovery.overrides);
+            ClapfigError::KeyNotFound { key, suggestion } => {
+                assert_eq!(key, "db.pool-size");
+                assert_eq!(suggestion.as_deref(), Some("db.pool_size"));
             }
-            other => panic!("expected MissingRequired, got {other:?}"),
+            other => panic!("expected KeyNotFound, got {other:?}"),
         }
     }
 
     #[test]
-    fn first_match_falls_back_when_high_priority_missing() {
-        let dir1 = TempDir::new().unwrap();
-        let dir2 = TempDir::new().unwrap();
-        // Only dir1 (lower priority) has a config
-        fs::write(dir1.path().join("demo.toml"), "port = 1000\n").unwrap();
-
-        let table = Clapfig::builder(demo_schema())
+    fn set_kebab_key_suggests_snake_when_normalization_is_off() {
+        let dir = TempDir::new().unwrap();
+        let err = Clapfig::builder(demo_schema())
             .app_name("demo")
             .file_name("demo.toml")
-            .search_paths(vec![
-                SearchPath::Path(dir1.path().to_path_buf()),
-                SearchPath::Path(dir2.path().to_path_buf()),
-            ])
-            .search_mode(SearchMode::FirstMatch)
+            .search_paths(vec![SearchPath::Path(dir.path().to_path_buf())])
+            .persist_scope("local", SearchPath::Path(dir.path().to_path_buf()))
             .no_env()
-            .load()
-            .unwrap();
-
-        assert_eq!(table.get("port"), Some(&Value::Integer(1000)));
-    }
-
-    #[test]
-    fn missing_app_name_errors() {
-        let result = Clapfig::builder(demo_schema()).no_env().load();
-        assert!(matches!(result, Err(ClapfigError::AppNameRequired)));
+            .handle(&ConfigAction::Set {
+                key: "db.pool-size".into(),
+                value: "10".into(),
+                scope: None,
+            })
+            .unwrap_err();
+        match err {
+            ClapfigError::KeyNotFound { key, suggestion } => {
+                assert_eq!(key, "db.pool-size");
+                assert_eq!(suggestion.as_deref(), Some("db.pool_size"));
+            }
+            other => panic!("expected KeyNotFound, got {other:?}"),
+        }
     }
 
-    // --- layer order (builder wiring; pipeline-level coverage in resolve.rs) ---
-
     #[test]
-    fn layer_order_cli_below_files() {
+    fn handle_get_merged_accepts_kebab_key_with_normalization() {
+        // The merged table's keys are canonical snake_case after the
+        // load-path normalization; the action key follows the same
+        // acceptance, so `get pool-size` answers from the merged view.
         let dir = TempDir::new().unwrap();
-        fs::write(dir.path().join("demo.toml"), "port = 3000\n").unwrap();
+        fs::write(dir.path().join("demo.toml"), "[db]\npool-size = 42\n").unwrap();
 
-        let table = Clapfig::builder(demo_schema())
+        let result = Clapfig::builder(demo_schema())
             .app_name("demo")
             .file_name("demo.toml")
             .search_paths(vec![SearchPath::Path(dir.path().to_path_buf())])
             .no_env()
-            .cli_override("port", Some(9999i64))
-            .layer_order(vec![Layer::Cli, Layer::Files])
-            .load()
+            .normalize_keys(true)
+            .handle(&ConfigAction::Get {
+                key: "db.pool-size".into(),
+                scope: None,
+                origin: false,
+            })
             .unwrap();
 
-        // Files listed after Cli, so the file wins.
-        assert_eq!(table.get("port"), Some(&Value::Integer(3000)));
+        match result {
+            ConfigResult::KeyValue { value, .. } => assert_eq!(value, "42"),
+            other => panic!("expected KeyValue, got {other:?}"),
+        }
     }
 
-    #[test]
-    fn omitted_env_layer_does_not_consult_environment() {
-        // Omitting Layer::Env must skip `std::env::vars()` (and merge): a
-        // matching variable must not win, even though env is otherwise
-        // enabled via the prefix. Synthetic ResolveInput coverage lives
-        // in resolve.rs; this locks the builder capture path.
-        let dir = TempDir::new().unwrap();
-        fs::write(dir.path().join("demo.toml"), "port = 3000\n").unwrap();
-        const PREFIX: &str = "CLAPFIG_PRV01_OMIT_ENV";
-        const VAR: &str = "CLAPFIG_PRV01_OMIT_ENV__PORT";
-        unsafe { std::env::set_var(VAR, "9999") };
+    // --- handle: template + schema output variants ---
 
-        let table = Clapfig::builder(demo_schema())
+    #[test]
+    fn handle_gen_kebab_emits_kebab_keys() {
+        // End-to-end: a builder with .normalize_keys(true) must produce a
+        // template whose keys match what users will type, not the schema
+        // field names.
+        let result = Clapfig::builder(demo_schema())
             .app_name("demo")
-            .file_name("demo.toml")
-            .search_paths(vec![SearchPath::Path(dir.path().to_path_buf())])
-            .env_prefix(PREFIX)
-            .layer_order(vec![Layer::Files])
-            .load();
-
-        unsafe { std::env::remove_var(VAR) };
+            .no_env()
+            .normalize_keys(true)
+            .handle(&ConfigAction::Gen { output: None })
+            .unwrap();
 
-        let table = table.expect("omitted env layer still loads files");
-        assert_eq!(table.get("port"), Some(&Value::Integer(3000)));
+        match result {
+            ConfigResult::Template(t) => {
+                assert!(
+                    t.contains("pool-size"),
+                    "expected kebab key in generated template:\n{t}"
+                );
+                assert!(
+                    !t.contains("pool_size"),
+                    "snake form leaked into normalize_keys template:\n{t}"
+                );
+            }
+            other => panic!("Expected Template, got {other:?}"),
+        }
     }
 
-    // --- normalize_keys (builder wiring; pipeline-level coverage in resolve.rs) ---
-
     #[test]
-    fn normalize_keys_load_accepts_kebab_in_file() {
-        let dir = TempDir::new().unwrap();
-        fs::write(dir.path().join("demo.toml"), "[db]\npool-size = 42\n").unwrap();
-
-        let table = Clapfig::builder(demo_schema())
+    fn handle_gen_default_still_snake() {
+        // Without normalize_keys, the template stays snake_case — defaults
+        // unchanged for callers that haven't opted in.
+        let result = Clapfig::builder(demo_schema())
             .app_name("demo")
-            .file_name("demo.toml")
-            .search_paths(vec![SearchPath::Path(dir.path().to_path_buf())])
             .no_env()
-            .normalize_keys(true)
-            .load()
+            .handle(&ConfigAction::Gen { output: None })
             .unwrap();
 
-        let db = table.get("db").and_then(Value::as_map).unwrap();
-        assert_eq!(db.get("pool_size"), Some(&Value::Integer(42)));
+        match result {
+            ConfigResult::Template(t) => {
+                assert!(t.contains("pool_size"));
+                assert!(!t.contains("pool-size"));
+            }
+            other => panic!("Expected Template, got {other:?}"),
+        }
     }
 
     #[test]
-    fn handle_set_normalized_roundtrip_has_no_collision() {
-        // End-to-end regression for #122: with normalize_keys on, set
-        // seeds a kebab file, later sets in EITHER spelling edit that
-        // same key, and the file stays loadable (no
-        // NormalizedKeyCollision from a snake/kebab duplicate).
-        use crate::format::FormatAdapter as _;
-        use crate::format::TomlAdapter;
-
+    fn handle_gen_with_output() {
         let dir = TempDir::new().unwrap();
-        let builder = || {
-            Clapfig::builder(demo_schema())
-                .app_name("demo")
-                .file_name("demo.toml")
-                .persist_scope("local", SearchPath::Path(dir.path().to_path_buf()))
-                .no_env()
-                .normalize_keys(true)
-        };
-        let file = dir.path().join("demo.toml");
-        let db_map = || {
-            let content = fs::read_to_string(&file).unwrap();
-            let tree = TomlAdapter.parse(&content).unwrap().value;
-            tree.as_map().unwrap()["db"].as_map().unwrap().clone()
-        };
+        let out_path = dir.path().join("generated.toml");
 
-        // Missing file: seeded from the kebab template, snake action key.
-        builder()
-            .handle(&ConfigAction::Set {
-                key: "db.pool_size".into(),
-                value: "10".into(),
-                scope: None,
+        let result = Clapfig::builder(demo_schema())
+            .app_name("demo")
+            .no_env()
+            .handle(&ConfigAction::Gen {
+                output: Some(out_path.clone()),
             })
             .unwrap();
-        let db = db_map();
-        assert_eq!(db.get("pool-size"), Some(&Value::Integer(10)));
-        assert!(!db.contains_key("pool_size"));
 
-        // Existing kebab file: both spellings edit the same key.
-        builder()
-            .handle(&ConfigAction::Set {
-                key: "db.pool-size".into(),
-                value: "11".into(),
-                scope: None,
-            })
-            .unwrap();
-        builder()
-            .handle(&ConfigAction::Set {
-                key: "db.pool_size".into(),
-                value: "12".into(),
-                scope: None,
-            })
-            .unwrap();
-        let db = db_map();
-        assert_eq!(db.get("pool-size"), Some(&Value::Integer(12)));
-        assert!(!db.contains_key("pool_size"));
+        assert!(matches!(result, ConfigResult::TemplateWritten { .. }));
+        let content = fs::read_to_string(&out_path).unwrap();
+        assert!(content.contains("host"));
+        assert!(content.contains("port"));
+    }
 
-        // The file the persistence path wrote loads cleanly.
-        let table = builder().load().unwrap();
-        let db = table.get("db").and_then(Value::as_map).unwrap();
-        assert_eq!(db.get("pool_size"), Some(&Value::Integer(12)));
+    #[test]
+    fn handle_schema_with_output() {
+        let dir = TempDir::new().unwrap();
+        let out_path = dir.path().join("schema.json");
 
-        // Unset in the snake spelling removes the kebab entry; the
-        // default shows through again on load.
-        builder()
-            .handle(&ConfigAction::Unset {
-                key: "db.pool_size".into(),
-                scope: None,
+        let result = Clapfig::builder(demo_schema())
+            .app_name("demo")
+            .no_env()
+            .handle(&ConfigAction::Schema {
+                output: Some(out_path.clone()),
             })
             .unwrap();
-        let db = db_map();
-        assert!(!db.contains_key("pool-size") && !db.contains_key("pool_size"));
-        let table = builder().load().unwrap();
-        let db = table.get("db").and_then(Value::as_map).unwrap();
-        assert_eq!(db.get("pool_size"), Some(&Value::Integer(5)));
-    }
-
-    #[test]
-    fn scoped_get_accepts_both_spellings_on_normalized_files() {
-        // Scoped get reads the raw (un-normalized) file, so a kebab
-        // document must answer for either action-key spelling — across
-        // all three formats.
-        use crate::format::{FormatAdapter, JsonAdapter, TomlAdapter, YamlAdapter};
 
-        let dir = TempDir::new().unwrap();
-        let files: [(&str, &dyn FormatAdapter, &str); 3] = [
-            ("demo.toml", &TomlAdapter, "[db]\npool-size = 42\n"),
-            ("demo.yaml", &YamlAdapter, "db:\n  pool-size: 42\n"),
-            ("demo.json", &JsonAdapter, "{\"db\": {\"pool-size\": 42}}"),
-        ];
-        for (name, adapter, content) in files {
-            let path = dir.path().join(name);
-            fs::write(&path, content).unwrap();
-            for key in ["db.pool-size", "db.pool_size"] {
-                let result = get_scope(
-                    adapter,
-                    &Shape::Object(demo_schema()),
-                    "local",
-                    &path,
-                    key,
-                    true,
-                )
-                .unwrap();
-                match result {
-                    ConfigResult::KeyValue {
-                        key: reported,
-                        value,
-                        ..
-                    } => {
-                        assert_eq!(value, "42", "{name} / {key}");
-                        assert_eq!(reported, key, "reported key keeps the caller's spelling");
-                    }
-                    other => panic!("expected KeyValue, got {other:?}"),
-                }
-            }
-        }
+        assert!(matches!(result, ConfigResult::SchemaWritten { .. }));
+        let content = fs::read_to_string(&out_path).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&content).unwrap();
+        assert_eq!(value["title"], "App");
     }
 
+    // --- persist scopes ---
+
     #[test]
-    fn scoped_get_rejects_ambiguous_spellings_as_collision() {
-        // A raw file holding BOTH equivalent spellings is ambiguous — the
-        // load path refuses it — so scoped get errors with the same
-        // collision (stamped with the scope file's path) instead of one
-        // spelling silently answering.
-        use crate::format::TomlAdapter;
+    fn handle_set_requires_persist_scope() {
         let dir = TempDir::new().unwrap();
-        let path = dir.path().join("demo.toml");
-        fs::write(&path, "[db]\npool-size = 5\npool_size = 6\n").unwrap();
-        for key in ["db.pool-size", "db.pool_size"] {
-            let err = get_scope(
-                &TomlAdapter,
-                &Shape::Object(demo_schema()),
-                "local",
-                &path,
-                key,
-                true,
-            )
-            .unwrap_err();
-            match err {
-                ClapfigError::NormalizedKeyCollision {
-                    path: reported,
-                    section,
-                    normalized_key,
-                    originals,
-                } => {
-                    assert_eq!(reported, path, "{key}");
-                    assert_eq!(section, "db");
-                    assert_eq!(normalized_key, "pool_size");
-                    assert_eq!(originals, vec!["pool-size", "pool_size"]);
-                }
-                other => panic!("expected NormalizedKeyCollision, got {other:?}"),
-            }
-        }
+
+        let result = Clapfig::builder(demo_schema())
+            .app_name("demo")
+            .file_name("demo.toml")
+            .search_paths(vec![SearchPath::Path(dir.path().to_path_buf())])
+            .no_env()
+            .handle(&ConfigAction::Set {
+                key: "port".into(),
+                value: "3000".into(),
+                scope: None,
+            });
+
+        assert!(matches!(result, Err(ClapfigError::NoPersistPath)));
     }
 
     #[test]
-    fn scoped_get_rejects_collision_off_the_requested_path() {
-        // Whole-document validation: `host` itself is unambiguous, but
-        // the file holds both spellings in the untraversed `db` table —
-        // scoped get still fails, because a document the load path
-        // refuses is never queried.
-        use crate::format::TomlAdapter;
+    fn handle_unset_requires_persist_scope() {
         let dir = TempDir::new().unwrap();
-        let path = dir.path().join("demo.toml");
-        fs::write(
-            &path,
-            "host = \"h\"\n\n[db]\npool-size = 5\npool_size = 6\n",
-        )
-        .unwrap();
-        let err = get_scope(
-            &TomlAdapter,
-            &Shape::Object(demo_schema()),
-            "local",
-            &path,
-            "host",
-            true,
-        )
-        .unwrap_err();
-        match err {
-            ClapfigError::NormalizedKeyCollision {
-                path: reported,
-                section,
-                normalized_key,
-                ..
-            } => {
-                assert_eq!(reported, path);
-                assert_eq!(section, "db");
-                assert_eq!(normalized_key, "pool_size");
-            }
-            other => panic!("expected NormalizedKeyCollision, got {other:?}"),
-        }
+
+        let result = Clapfig::builder(demo_schema())
+            .app_name("demo")
+            .file_name("demo.toml")
+            .search_paths(vec![SearchPath::Path(dir.path().to_path_buf())])
+            .no_env()
+            .handle(&ConfigAction::Unset {
+                key: "port".into(),
+                scope: None,
+            });
+
+        assert!(matches!(result, Err(ClapfigError::NoPersistPath)));
     }
 
     #[test]
-    fn scoped_get_missing_file_names_scope_and_file_not_the_key() {
-        // The key may be perfectly valid — there is simply no file to
-        // read. Claiming "key not found" would send the user hunting for
-        // a typo that isn't there.
-        use crate::format::TomlAdapter;
-        let dir = TempDir::new().unwrap();
-        let path = dir.path().join("nonexistent.toml");
-        for key in ["db.pool-size", "db.pool_size"] {
-            let err = get_scope(
-                &TomlAdapter,
-                &Shape::Object(demo_schema()),
-                "local",
-                &path,
-                key,
-                true,
-            )
-            .unwrap_err();
-            match err {
-                ClapfigError::ScopeFileMissing {
-                    scope,
-                    path: reported,
-                } => {
-                    assert_eq!(scope, "local");
-                    assert_eq!(reported, path);
-                }
-                other => panic!("expected ScopeFileMissing, got {other:?}"),
-            }
-        }
+    fn handle_set_rejects_ancestors_scope() {
+        use crate::types::Boundary;
+        let result = Clapfig::builder(demo_schema())
+            .app_name("demo")
+            .persist_scope("bad", SearchPath::Ancestors(Boundary::Root))
+            .no_env()
+            .handle(&ConfigAction::Set {
+                key: "port".into(),
+                value: "3000".into(),
+                scope: None,
+            });
+
+        assert!(matches!(
+            result,
+            Err(ClapfigError::AncestorsNotAllowedAsPersistPath)
+        ));
     }
 
     #[test]
-    fn get_unknown_key_suggests_near_miss() {
+    fn handle_unknown_scope_errors() {
         let dir = TempDir::new().unwrap();
-        let err = Clapfig::builder(demo_schema())
+
+        let result = Clapfig::builder(demo_schema())
             .app_name("demo")
-            .search_paths(vec![SearchPath::Path(dir.path().to_path_buf())])
+            .file_name("demo.toml")
+            .persist_scope("local", SearchPath::Path(dir.path().to_path_buf()))
             .no_env()
-            .handle(&ConfigAction::Get {
-                key: "db.pool_sizr".into(),
-                scope: None,
-            })
-            .unwrap_err();
-        match err {
-            ClapfigError::KeyNotFound { key, suggestion } => {
-                assert_eq!(key, "db.pool_sizr");
-                assert_eq!(suggestion.as_deref(), Some("db.pool_size"));
+            .handle(&ConfigAction::Set {
+                key: "port".into(),
+                value: "3000".into(),
+                scope: Some("nonexistent".into()),
+            });
+
+        match result {
+            Err(ClapfigError::UnknownScope { scope, available }) => {
+                assert_eq!(scope, "nonexistent");
+                assert_eq!(available, vec!["local"]);
             }
-            other => panic!("expected KeyNotFound, got {other:?}"),
+            other => panic!("Expected UnknownScope, got {other:?}"),
         }
     }
 
     #[test]
-    fn get_kebab_key_suggests_snake_when_normalization_is_off() {
-        // Default is normalize_keys(false): pool-size is not the schema
-        // key, and the dash/underscore difference is the useful hint.
+    fn handle_list_with_scope() {
         let dir = TempDir::new().unwrap();
-        let err = Clapfig::builder(demo_schema())
+        fs::write(dir.path().join("demo.toml"), "port = 3000\n").unwrap();
+
+        let result = Clapfig::builder(demo_schema())
             .app_name("demo")
-            .search_paths(vec![SearchPath::Path(dir.path().to_path_buf())])
+            .file_name("demo.toml")
+            .persist_scope("local", SearchPath::Path(dir.path().to_path_buf()))
             .no_env()
-            .handle(&ConfigAction::Get {
-                key: "db.pool-size".into(),
-                scope: None,
+            .handle(&ConfigAction::List {
+                scope: Some("local".into()),
+                origins: false,
             })
-            .unwrap_err();
-        match err {
-            ClapfigError::KeyNotFound { key, suggestion } => {
-                assert_eq!(key, "db.pool-size");
-                assert_eq!(suggestion.as_deref(), Some("db.pool_size"));
+            .unwrap();
+
+        match result {
+            ConfigResult::Listing { entries, .. } => {
+                assert_eq!(entries.len(), 1);
+                assert_eq!(entries[0], ("port".into(), "3000".into()));
             }
-            other => panic!("expected KeyNotFound, got {other:?}"),
+            other => panic!("Expected Listing, got {other:?}"),
         }
     }
 
     #[test]
-    fn set_kebab_key_suggests_snake_when_normalization_is_off() {
+    fn handle_get_with_scope() {
         let dir = TempDir::new().unwrap();
-        let err = Clapfig::builder(demo_schema())
+        fs::write(dir.path().join("demo.toml"), "port = 3000\n").unwrap();
+
+        let result = Clapfig::builder(demo_schema())
             .app_name("demo")
             .file_name("demo.toml")
-            .search_paths(vec![SearchPath::Path(dir.path().to_path_buf())])
             .persist_scope("local", SearchPath::Path(dir.path().to_path_buf()))
             .no_env()
+            .handle(&ConfigAction::Get {
+                key: "port".into(),
+                scope: Some("local".into()),
+                origin: false,
+            })
+            .unwrap();
+
+        match result {
+            ConfigResult::KeyValue { value, .. } => assert_eq!(value, "3000"),
+            other => panic!("Expected KeyValue, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn multiple_scopes_separate_files() {
+        let local_dir = TempDir::new().unwrap();
+        let global_dir = TempDir::new().unwrap();
+
+        let make_builder = || {
+            Clapfig::builder(demo_schema())
+                .app_name("demo")
+                .file_name("demo.toml")
+                .persist_scope("local", SearchPath::Path(local_dir.path().to_path_buf()))
+                .persist_scope("global", SearchPath::Path(global_dir.path().to_path_buf()))
+                .no_env()
+        };
+
+        // Set in local (default scope)
+        make_builder()
             .handle(&ConfigAction::Set {
-                key: "db.pool-size".into(),
-                value: "10".into(),
+                key: "port".into(),
+                value: "3000".into(),
                 scope: None,
             })
-            .unwrap_err();
-        match err {
-            ClapfigError::KeyNotFound { key, suggestion } => {
-                assert_eq!(key, "db.pool-size");
-                assert_eq!(suggestion.as_deref(), Some("db.pool_size"));
+            .unwrap();
+
+        // Set in global
+        make_builder()
+            .handle(&ConfigAction::Set {
+                key: "host".into(),
+                value: "0.0.0.0".into(),
+                scope: Some("global".into()),
+            })
+            .unwrap();
+
+        // Verify separate files
+        let local_content = fs::read_to_string(local_dir.path().join("demo.toml")).unwrap();
+        assert!(local_content.contains("port = 3000"));
+        assert!(!local_content.contains("host = \"0.0.0.0\""));
+
+        let global_content = fs::read_to_string(global_dir.path().join("demo.toml")).unwrap();
+        assert!(global_content.contains("host = \"0.0.0.0\""));
+        assert!(!global_content.contains("port = 3000"));
+
+        // List scoped: only that file's explicitly-set entries (the seeded
+        // template contributes the defaults, so filter to the set key).
+        let local_list = make_builder()
+            .handle(&ConfigAction::List {
+                scope: Some("local".into()),
+                origins: false,
+            })
+            .unwrap();
+        match local_list {
+            ConfigResult::Listing { entries, .. } => {
+                assert!(entries.iter().any(|(k, v)| k == "port" && v == "3000"));
             }
-            other => panic!("expected KeyNotFound, got {other:?}"),
+            other => panic!("Expected Listing, got {other:?}"),
         }
-    }
 
-    #[test]
-    fn handle_get_merged_accepts_kebab_key_with_normalization() {
-        // The merged table's keys are canonical snake_case after the
-        // load-path normalization; the action key follows the same
-        // acceptance, so `get pool-size` answers from the merged view.
-        let dir = TempDir::new().unwrap();
-        fs::write(dir.path().join("demo.toml"), "[db]\npool-size = 42\n").unwrap();
+        // List merged (no scope): sees both files merged + defaults
+        let merged_list = make_builder()
+            .handle(&ConfigAction::List {
+                scope: None,
+                origins: false,
+            })
+            .unwrap();
+        match merged_list {
+            ConfigResult::Listing { entries, .. } => {
+                let keys: Vec<&str> = entries.iter().map(|(k, _)| k.as_str()).collect();
+                assert!(keys.contains(&"port"));
+                assert!(keys.contains(&"host"));
+            }
+            other => panic!("Expected Listing, got {other:?}"),
+        }
+    }
 
+    #[test]
+    fn list_origins_annotates_each_merged_entry() {
+        const KEY: &str = "CLAPFIG_RT_LIST_ORIGINS_TEST__DB__POOL_SIZE";
+        unsafe { std::env::set_var(KEY, "20") };
+
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("demo.toml");
+        fs::write(&file, "port = 3000\n").unwrap();
         let result = Clapfig::builder(demo_schema())
             .app_name("demo")
             .file_name("demo.toml")
+            .env_prefix("CLAPFIG_RT_LIST_ORIGINS_TEST")
             .search_paths(vec![SearchPath::Path(dir.path().to_path_buf())])
-            .no_env()
-            .normalize_keys(true)
-            .handle(&ConfigAction::Get {
-                key: "db.pool-size".into(),
+            .handle(&ConfigAction::List {
                 scope: None,
+                origins: true,
             })
             .unwrap();
+        unsafe { std::env::remove_var(KEY) };
 
-        match result {
-            ConfigResult::KeyValue { value, .. } => assert_eq!(value, "42"),
-            other => panic!("expected KeyValue, got {other:?}"),
+        match &result {
+            ConfigResult::Listing {
+                entries, origins, ..
+            } => assert_eq!(entries.len(), origins.len()),
+            other => panic!("Expected Listing, got {other:?}"),
         }
+        let rendered = result.to_string();
+        let line = |key: &str| {
+            rendered
+                .lines()
+                .find(|l| l.starts_with(&format!("{key} = ")))
+                .unwrap_or_else(|| panic!("no {key} line in:\n{rendered}"))
+                .to_owned()
+        };
+        assert_eq!(
+            line("port"),
+            format!("port = 3000  # file: {}:1", file.display())
+        );
+        assert_eq!(
+            line("db.pool_size"),
+            format!("db.pool_size = 20  # env: {KEY}")
+        );
+        assert_eq!(line("host"), "host = localhost  # default");
     }
 
-    // --- handle: template + schema output variants ---
-
     #[test]
-    fn handle_gen_kebab_emits_kebab_keys() {
-        // End-to-end: a builder with .normalize_keys(true) must produce a
-        // template whose keys match what users will type, not the schema
-        // field names.
-        let result = Clapfig::builder(demo_schema())
-            .app_name("demo")
-            .no_env()
-            .normalize_keys(true)
-            .handle(&ConfigAction::Gen { output: None })
+    fn get_origin_annotates_merged_and_scoped_values() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("demo.toml");
+        fs::write(&file, "host = \"example\"\n\n[db]\npool_size = 9\n").unwrap();
+        let builder = || {
+            Clapfig::builder(demo_schema())
+                .app_name("demo")
+                .file_name("demo.toml")
+                .persist_scope("local", SearchPath::Path(dir.path().to_path_buf()))
+                .no_env()
+                .cli_override("port", Some(9000i64))
+        };
+
+        let merged = builder()
+            .handle(&ConfigAction::Get {
+                key: "port".into(),
+                scope: None,
+                origin: true,
+            })
             .unwrap();
+        assert_eq!(
+            merged.to_string(),
+            "# Port number\nport = 9000  # override: port"
+        );
 
-        match result {
-            ConfigResult::Template(t) => {
-                assert!(
-                    t.contains("pool-size"),
-                    "expected kebab key in generated template:\n{t}"
-                );
-                assert!(
-                    !t.contains("pool_size"),
-                    "snake form leaked into normalize_keys template:\n{t}"
-                );
+        let scoped = builder()
+            .handle(&ConfigAction::Get {
+                key: "db.pool_size".into(),
+                scope: Some("local".into()),
+                origin: true,
+            })
+            .unwrap();
+        match &scoped {
+            ConfigResult::KeyValue { origin, .. } => {
+                let origin = origin.as_ref().unwrap();
+                assert_eq!(origin.input_type, Some(crate::InputType::File));
+                assert_eq!(origin.line_col(), Some((4, 13)));
             }
-            other => panic!("Expected Template, got {other:?}"),
+            other => panic!("Expected KeyValue, got {other:?}"),
         }
+        assert!(
+            scoped
+                .to_string()
+                .ends_with(&format!("db.pool_size = 9  # file: {}:4", file.display()))
+        );
+
+        // Without the flag nothing is annotated.
+        let plain = builder()
+            .handle(&ConfigAction::Get {
+                key: "port".into(),
+                scope: None,
+                origin: false,
+            })
+            .unwrap();
+        assert_eq!(plain.to_string(), "# Port number\nport = 9000");
     }
 
     #[test]
-    fn handle_gen_default_still_snake() {
-        // Without normalize_keys, the template stays snake_case — defaults
-        // unchanged for callers that haven't opted in.
+    fn explain_lists_every_candidate_in_precedence_order() {
+        const KEY: &str = "CLAPFIG_RT_EXPLAIN_TEST__DB__POOL_SIZE";
+        unsafe { std::env::set_var(KEY, "20") };
+
+        let global = TempDir::new().unwrap();
+        let local = TempDir::new().unwrap();
+        fs::write(global.path().join("demo.toml"), "[db]\npool_size = 10\n").unwrap();
+        fs::write(local.path().join("demo.toml"), "\n[db]\npool_size = 15\n").unwrap();
         let result = Clapfig::builder(demo_schema())
             .app_name("demo")
-            .no_env()
-            .handle(&ConfigAction::Gen { output: None })
+            .file_name("demo.toml")
+            .env_prefix("CLAPFIG_RT_EXPLAIN_TEST")
+            .search_paths(vec![
+                SearchPath::Path(global.path().to_path_buf()),
+                SearchPath::Path(local.path().to_path_buf()),
+            ])
+            .handle(&ConfigAction::Explain {
+                key: "db.pool_size".into(),
+            })
             .unwrap();
+        unsafe { std::env::remove_var(KEY) };
 
-        match result {
-            ConfigResult::Template(t) => {
-                assert!(t.contains("pool_size"));
-                assert!(!t.contains("pool-size"));
-            }
-            other => panic!("Expected Template, got {other:?}"),
-        }
+        let ConfigResult::Explanation { explanation, .. } = &result else {
+            panic!("Expected Explanation, got {result:?}");
+        };
+        assert_eq!(explanation.value, Some(Value::Integer(20)));
+        let summary: Vec<_> = explanation
+            .candidates
+            .iter()
+            .map(|c| (c.origin.input_type, c.value.clone(), c.won))
+            .collect();
+        use crate::InputType;
+        assert_eq!(
+            summary,
+            [
+                (Some(InputType::Default), Value::Integer(5), false),
+                (Some(InputType::File), Value::Integer(10), false),
+                (Some(InputType::File), Value::Integer(15), false),
+                (Some(InputType::Env), Value::Integer(20), true),
+            ]
+        );
+        let precedence = if cfg!(feature = "url") {
+            "default < files < env < url < overrides"
+        } else {
+            "default < files < env < overrides"
+        };
+        let expected = format!(
+            "db.pool_size = 20\n\
+             precedence, lowest first: {precedence}\n  \
+             default: 5  (overridden)\n  \
+             file: {}:2: 10  (overridden)\n  \
+             file: {}:3: 15  (overridden)\n  \
+             env: {KEY}: 20  <- wins",
+            global.path().join("demo.toml").display(),
+            local.path().join("demo.toml").display(),
+        );
+        assert_eq!(result.to_string(), expected);
     }
 
     #[test]
-    fn handle_gen_with_output() {
-        let dir = TempDir::new().unwrap();
-        let out_path = dir.path().join("generated.toml");
-
-        let result = Clapfig::builder(demo_schema())
+    fn explain_marks_lower_layers_of_a_combining_array_as_merged() {
+        let global = TempDir::new().unwrap();
+        let local = TempDir::new().unwrap();
+        let text = "plugins = [\"a\"]\nhosts = [\"x\"]\n";
+        fs::write(global.path().join("demo.toml"), text).unwrap();
+        fs::write(
+            local.path().join("demo.toml"),
+            "plugins = [\"b\"]\nhosts = [\"y\"]\n",
+        )
+        .unwrap();
+        let explain = |key: &str| {
+            Clapfig::builder(
+                Schema::object("App")
+                    .field(
+                        "plugins",
+                        RtField::array_of_type(RtField::string())
+                            .merge(crate::runtime::MergeStrategy::Append),
+                    )
+                    .field("hosts", RtField::array_of_type(RtField::string()))
+                    .build(),
+            )
             .app_name("demo")
+            .file_name("demo.toml")
+            .search_paths(vec![
+                SearchPath::Path(global.path().to_path_buf()),
+                SearchPath::Path(local.path().to_path_buf()),
+            ])
             .no_env()
-            .handle(&ConfigAction::Gen {
-                output: Some(out_path.clone()),
-            })
-            .unwrap();
+            .handle(&ConfigAction::Explain { key: key.into() })
+            .unwrap()
+            .to_string()
+        };
+        let global_file = global.path().join("demo.toml");
+        let local_file = local.path().join("demo.toml");
 
-        assert!(matches!(result, ConfigResult::TemplateWritten { .. }));
-        let content = fs::read_to_string(&out_path).unwrap();
-        assert!(content.contains("host"));
-        assert!(content.contains("port"));
+        let plugins = explain("plugins");
+        assert!(
+            plugins.starts_with("plugins = [\"a\", \"b\"]\n"),
+            "{plugins}"
+        );
+        assert!(
+            plugins.ends_with(&format!(
+                "file: {}:1: [\"a\"]  (merged)\n  file: {}:1: [\"b\"]  <- wins",
+                global_file.display(),
+                local_file.display(),
+            )),
+            "{plugins}"
+        );
+        // Without a strategy the higher layer's array replaces the lower.
+        let hosts = explain("hosts");
+        assert!(
+            hosts.ends_with(&format!(
+                "file: {}:2: [\"x\"]  (overridden)\n  file: {}:2: [\"y\"]  <- wins",
+                global_file.display(),
+                local_file.display(),
+            )),
+            "{hosts}"
+        );
     }
 
     #[test]
-    fn handle_schema_with_output() {
-        let dir = TempDir::new().unwrap();
-        let out_path = dir.path().join("schema.json");
-
-        let result = Clapfig::builder(demo_schema())
+    fn explain_names_files_a_first_match_search_never_read() {
+        let low = TempDir::new().unwrap();
+        let high = TempDir::new().unwrap();
+        fs::write(low.path().join("demo.toml"), "port = 1\n").unwrap();
+        fs::write(high.path().join("demo.toml"), "host = \"h\"\n").unwrap();
+        let explanation = Clapfig::builder(demo_schema())
             .app_name("demo")
+            .file_name("demo.toml")
+            .search_paths(vec![
+                SearchPath::Path(low.path().to_path_buf()),
+                SearchPath::Path(high.path().to_path_buf()),
+            ])
+            .search_mode(SearchMode::FirstMatch)
             .no_env()
-            .handle(&ConfigAction::Schema {
-                output: Some(out_path.clone()),
-            })
+            .explain("port")
             .unwrap();
 
-        assert!(matches!(result, ConfigResult::SchemaWritten { .. }));
-        let content = fs::read_to_string(&out_path).unwrap();
-        let value: serde_json::Value = serde_json::from_str(&content).unwrap();
-        assert_eq!(value["title"], "App");
+        // The low-priority file sets `port`, but FirstMatch stopped at the
+        // high-priority file: only the default is a candidate.
+        assert_eq!(explanation.candidates.len(), 1);
+        assert!(explanation.candidates[0].won);
+        assert_eq!(explanation.value, Some(Value::Integer(8080)));
+        assert_eq!(explanation.not_probed, [low.path().join("demo.toml")]);
     }
 
-    // --- persist scopes ---
-
     #[test]
-    fn handle_set_requires_persist_scope() {
-        let dir = TempDir::new().unwrap();
-
-        let result = Clapfig::builder(demo_schema())
+    fn explain_unknown_key_suggests_near_miss() {
+        let err = Clapfig::builder(demo_schema())
             .app_name("demo")
-            .file_name("demo.toml")
-            .search_paths(vec![SearchPath::Path(dir.path().to_path_buf())])
+            .search_paths(vec![])
             .no_env()
-            .handle(&ConfigAction::Set {
-                key: "port".into(),
-                value: "3000".into(),
-                scope: None,
-            });
-
-        assert!(matches!(result, Err(ClapfigError::NoPersistPath)));
+            .explain("db.pool_sizr")
+            .unwrap_err();
+        match err {
+            ClapfigError::KeyNotFound { key, suggestion } => {
+                assert_eq!(key, "db.pool_sizr");
+                assert_eq!(suggestion.as_deref(), Some("db.pool_size"));
+            }
+            other => panic!("Expected KeyNotFound, got {other:?}"),
+        }
     }
 
     #[test]
-    fn handle_unset_requires_persist_scope() {
-        let dir = TempDir::new().unwrap();
-
+    fn diff_between_two_directories_renders_both_sides_with_origins() {
+        let a = TempDir::new().unwrap();
+        let b = TempDir::new().unwrap();
+        fs::write(a.path().join("demo.toml"), "port = 1000\nhost = \"a\"\n").unwrap();
+        fs::write(
+            b.path().join("demo.toml"),
+            "port = 2000\n[db]\nurl = \"pg://\"\n",
+        )
+        .unwrap();
         let result = Clapfig::builder(demo_schema())
             .app_name("demo")
             .file_name("demo.toml")
-            .search_paths(vec![SearchPath::Path(dir.path().to_path_buf())])
+            .search_paths(vec![SearchPath::Cwd])
             .no_env()
-            .handle(&ConfigAction::Unset {
-                key: "port".into(),
-                scope: None,
-            });
+            .handle(&ConfigAction::Diff {
+                left: DiffTarget::Dir(a.path().to_path_buf()),
+                right: DiffTarget::Dir(b.path().to_path_buf()),
+            })
+            .unwrap();
 
-        assert!(matches!(result, Err(ClapfigError::NoPersistPath)));
+        let ConfigResult::Diff { diff, .. } = &result else {
+            panic!("Expected Diff, got {result:?}");
+        };
+        let summary: Vec<_> = diff
+            .entries
+            .iter()
+            .map(|e| (e.key.as_str(), e.kind))
+            .collect();
+        use crate::DiffKind;
+        assert_eq!(
+            summary,
+            [
+                ("db.url", DiffKind::Added),
+                ("host", DiffKind::Changed),
+                ("port", DiffKind::Changed),
+            ]
+        );
+        let (a_file, b_file) = (a.path().join("demo.toml"), b.path().join("demo.toml"));
+        let expected = format!(
+            "--- {}\n\
+             +++ {}\n\
+             + db.url = pg://  # file: {}:3\n\
+             - host = a  # file: {}:2\n\
+             + host = localhost  # default\n\
+             - port = 1000  # file: {}:1\n\
+             + port = 2000  # file: {}:1",
+            a.path().display(),
+            b.path().display(),
+            b_file.display(),
+            a_file.display(),
+            a_file.display(),
+            b_file.display(),
+        );
+        assert_eq!(result.to_string(), expected);
     }
 
     #[test]
-    fn handle_set_rejects_ancestors_scope() {
-        use crate::types::Boundary;
-        let result = Clapfig::builder(demo_schema())
-            .app_name("demo")
-            .persist_scope("bad", SearchPath::Ancestors(Boundary::Root))
-            .no_env()
-            .handle(&ConfigAction::Set {
-                key: "port".into(),
-                value: "3000".into(),
-                scope: None,
-            });
+    fn diff_scope_file_against_merged_directory() {
+        let dir = TempDir::new().unwrap();
+        let scope = TempDir::new().unwrap();
+        fs::write(dir.path().join("demo.toml"), "port = 7\n").unwrap();
+        fs::write(scope.path().join("demo.toml"), "port = 7\n").unwrap();
+        let builder = || {
+            Clapfig::builder(demo_schema())
+                .app_name("demo")
+                .file_name("demo.toml")
+                .search_paths(vec![SearchPath::Cwd])
+                .persist_scope("pinned", SearchPath::Path(scope.path().to_path_buf()))
+                .no_env()
+        };
+        let diff = builder()
+            .diff(
+                &DiffTarget::Scope("pinned".into()),
+                &DiffTarget::Dir(dir.path().to_path_buf()),
+            )
+            .unwrap();
+        // The scope file holds only `port`; every other merged value is
+        // added on the right, with its default origin.
+        assert!(diff.entries.iter().all(|e| e.key != "port"));
+        let host = diff.entries.iter().find(|e| e.key == "host").unwrap();
+        assert_eq!(host.kind, crate::DiffKind::Added);
+        assert_eq!(
+            host.right_origin.as_ref().and_then(|o| o.input_type),
+            Some(crate::InputType::Default)
+        );
 
-        assert!(matches!(
-            result,
-            Err(ClapfigError::AncestorsNotAllowedAsPersistPath)
-        ));
+        let same = builder()
+            .handle(&ConfigAction::Diff {
+                left: DiffTarget::Dir(dir.path().to_path_buf()),
+                right: DiffTarget::Dir(dir.path().to_path_buf()),
+            })
+            .unwrap();
+        assert!(same.to_string().ends_with("\nno differences"));
+    }
+
+    fn secret_schema() -> Schema {
+        Schema::object("App")
+            .field("host", RtField::string().default("localhost"))
+            .field("token", RtField::string().sensitive().optional())
+            .field(
+                "pin",
+                RtField::integer_in(Some(0), Some(9999))
+                    .sensitive()
+                    .optional(),
+            )
+            .build()
     }
 
     #[test]
-    fn handle_unknown_scope_errors() {
+    fn secret_values_are_redacted_in_every_config_result() {
         let dir = TempDir::new().unwrap();
+        let other = TempDir::new().unwrap();
+        fs::write(dir.path().join("demo.toml"), "token = \"hunter2\"\n").unwrap();
+        fs::write(other.path().join("demo.toml"), "token = \"swordfish\"\n").unwrap();
+        let builder = || {
+            Clapfig::builder(secret_schema())
+                .app_name("demo")
+                .file_name("demo.toml")
+                .search_paths(vec![SearchPath::Path(dir.path().to_path_buf())])
+                .persist_scope("local", SearchPath::Path(dir.path().to_path_buf()))
+                .persist_scope("other", SearchPath::Path(other.path().to_path_buf()))
+                .no_env()
+        };
+        // Loading still sees the secret (persist scopes are search paths;
+        // the last one wins).
+        let table = builder().load().unwrap();
+        assert_eq!(table["token"], Value::String("swordfish".into()));
 
-        let result = Clapfig::builder(demo_schema())
-            .app_name("demo")
-            .file_name("demo.toml")
-            .persist_scope("local", SearchPath::Path(dir.path().to_path_buf()))
-            .no_env()
-            .handle(&ConfigAction::Set {
-                key: "port".into(),
-                value: "3000".into(),
-                scope: Some("nonexistent".into()),
-            });
-
-        match result {
-            Err(ClapfigError::UnknownScope { scope, available }) => {
-                assert_eq!(scope, "nonexistent");
-                assert_eq!(available, vec!["local"]);
-            }
-            other => panic!("Expected UnknownScope, got {other:?}"),
+        let outputs = [
+            ConfigAction::List {
+                scope: None,
+                origins: true,
+            },
+            ConfigAction::List {
+                scope: Some("local".into()),
+                origins: false,
+            },
+            ConfigAction::Get {
+                key: "token".into(),
+                scope: None,
+                origin: false,
+            },
+            ConfigAction::Get {
+                key: "token".into(),
+                scope: Some("local".into()),
+                origin: false,
+            },
+            ConfigAction::Explain {
+                key: "token".into(),
+            },
+            ConfigAction::Diff {
+                left: DiffTarget::Scope("local".into()),
+                right: DiffTarget::Scope("other".into()),
+            },
+        ]
+        .map(|action| builder().handle(&action).unwrap());
+        for result in &outputs {
+            let shown = format!("{result}\n{result:?}");
+            assert!(!shown.contains("hunter2"), "{shown}");
+            assert!(!shown.contains("swordfish"), "{shown}");
+            assert!(shown.contains("<redacted>"), "{shown}");
         }
-    }
-
-    #[test]
-    fn handle_list_with_scope() {
-        let dir = TempDir::new().unwrap();
-        fs::write(dir.path().join("demo.toml"), "port = 3000\n").unwrap();
+        assert!(outputs[0].to_string().contains("host = localhost"));
 
-        let result = Clapfig::builder(demo_schema())
-            .app_name("demo")
-            .file_name("demo.toml")
-            .persist_scope("local", SearchPath::Path(dir.path().to_path_buf()))
-            .no_env()
-            .handle(&ConfigAction::List {
+        let set = builder()
+            .handle(&ConfigAction::Set {
+                key: "token".into(),
+                value: "correct-horse".into(),
                 scope: Some("local".into()),
             })
             .unwrap();
-
-        match result {
-            ConfigResult::Listing { entries, .. } => {
-                assert_eq!(entries.len(), 1);
-                assert_eq!(entries[0], ("port".into(), "3000".into()));
-            }
-            other => panic!("Expected Listing, got {other:?}"),
-        }
+        assert_eq!(set.to_string(), "Set token = <redacted>");
+        let written = fs::read_to_string(dir.path().join("demo.toml")).unwrap();
+        assert!(written.contains("correct-horse"));
     }
 
     #[test]
-    fn handle_get_with_scope() {
+    fn interpolated_secrets_stay_redacted_and_only_reach_sensitive_fields() {
         let dir = TempDir::new().unwrap();
-        fs::write(dir.path().join("demo.toml"), "port = 3000\n").unwrap();
-
-        let result = Clapfig::builder(demo_schema())
+        let builder = || {
+            Clapfig::builder(
+                Schema::object("App")
+                    .field("token", RtField::string().sensitive().optional())
+                    .field("dsn", RtField::string().sensitive().optional())
+                    .field("tag", RtField::string().optional())
+                    .build(),
+            )
             .app_name("demo")
             .file_name("demo.toml")
-            .persist_scope("local", SearchPath::Path(dir.path().to_path_buf()))
+            .search_paths(vec![SearchPath::Path(dir.path().to_path_buf())])
             .no_env()
-            .handle(&ConfigAction::Get {
-                key: "port".into(),
-                scope: Some("local".into()),
-            })
-            .unwrap();
-
-        match result {
-            ConfigResult::KeyValue { value, .. } => assert_eq!(value, "3000"),
-            other => panic!("Expected KeyValue, got {other:?}"),
+            .interpolate(true)
+        };
+        fs::write(
+            dir.path().join("demo.toml"),
+            "token = \"hunter2\"\ndsn = \"pg://app:${token}@db\"\n",
+        )
+        .unwrap();
+        let table = builder().load().unwrap();
+        assert_eq!(table["dsn"], Value::from("pg://app:hunter2@db"));
+        for action in [
+            ConfigAction::Get {
+                key: "dsn".into(),
+                scope: None,
+                origin: true,
+            },
+            ConfigAction::List {
+                scope: None,
+                origins: true,
+            },
+            ConfigAction::Explain { key: "dsn".into() },
+        ] {
+            let result = builder().handle(&action).unwrap();
+            let shown = format!("{result}\n{result:?}");
+            assert!(!shown.contains("hunter2"), "{shown}");
+            assert!(shown.contains("<redacted>"), "{shown}");
         }
+
+        // A plain field would print the secret, so the reference fails.
+        fs::write(
+            dir.path().join("demo.toml"),
+            "token = \"hunter2\"\ntag = \"${token}\"\n",
+        )
+        .unwrap();
+        let err = builder().load().unwrap_err();
+        let ClapfigError::InvalidValue { key, reason, .. } = &err else {
+            panic!("Expected InvalidValue, got {err:?}");
+        };
+        assert_eq!(key, "tag");
+        assert_eq!(
+            reason,
+            "`${token}` is sensitive; only a sensitive field can interpolate it"
+        );
+        let rendered = crate::render::render_plain(&err);
+        assert!(!rendered.contains("hunter2"), "{rendered}");
     }
 
     #[test]
-    fn multiple_scopes_separate_files() {
-        let local_dir = TempDir::new().unwrap();
-        let global_dir = TempDir::new().unwrap();
-
-        let make_builder = || {
-            Clapfig::builder(demo_schema())
+    fn secret_values_stay_out_of_invalid_value_errors() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("demo.toml"), "pin = 123456\n").unwrap();
+        let builder = || {
+            Clapfig::builder(secret_schema())
                 .app_name("demo")
                 .file_name("demo.toml")
-                .persist_scope("local", SearchPath::Path(local_dir.path().to_path_buf()))
-                .persist_scope("global", SearchPath::Path(global_dir.path().to_path_buf()))
+                .search_paths(vec![SearchPath::Path(dir.path().to_path_buf())])
+                .persist_scope("local", SearchPath::Path(dir.path().to_path_buf()))
                 .no_env()
         };
+        let err = builder().load().unwrap_err();
+        let ClapfigError::InvalidValue { reason, .. } = &err else {
+            panic!("Expected InvalidValue, got {err:?}");
+        };
+        assert_eq!(
+            reason,
+            "value <redacted> is out of range (allowed: 0..=9999)"
+        );
+        let rendered = crate::render::render_plain(&err);
+        assert!(!rendered.contains("123456"), "{rendered}");
+        assert!(rendered.contains("pin = <redacted>"), "{rendered}");
 
-        // Set in local (default scope)
-        make_builder()
-            .handle(&ConfigAction::Set {
-                key: "port".into(),
-                value: "3000".into(),
-                scope: None,
-            })
-            .unwrap();
-
-        // Set in global
-        make_builder()
+        let err = builder()
             .handle(&ConfigAction::Set {
-                key: "host".into(),
-                value: "0.0.0.0".into(),
-                scope: Some("global".into()),
-            })
-            .unwrap();
-
-        // Verify separate files
-        let local_content = fs::read_to_string(local_dir.path().join("demo.toml")).unwrap();
-        assert!(local_content.contains("port = 3000"));
-        assert!(!local_content.contains("host = \"0.0.0.0\""));
-
-        let global_content = fs::read_to_string(global_dir.path().join("demo.toml")).unwrap();
-        assert!(global_content.contains("host = \"0.0.0.0\""));
-        assert!(!global_content.contains("port = 3000"));
-
-        // List scoped: only that file's explicitly-set entries (the seeded
-        // template contributes the defaults, so filter to the set key).
-        let local_list = make_builder()
-            .handle(&ConfigAction::List {
+                key: "pin".into(),
+                value: "12x4".into(),
                 scope: Some("local".into()),
             })
-            .unwrap();
-        match local_list {
-            ConfigResult::Listing { entries, .. } => {
-                assert!(entries.iter().any(|(k, v)| k == "port" && v == "3000"));
-            }
-            other => panic!("Expected Listing, got {other:?}"),
-        }
-
-        // List merged (no scope): sees both files merged + defaults
-        let merged_list = make_builder()
-            .handle(&ConfigAction::List { scope: None })
-            .unwrap();
-        match merged_list {
-            ConfigResult::Listing { entries, .. } => {
-                let keys: Vec<&str> = entries.iter().map(|(k, _)| k.as_str()).collect();
-                assert!(keys.contains(&"port"));
-                assert!(keys.contains(&"host"));
-            }
-            other => panic!("Expected Listing, got {other:?}"),
-        }
+            .unwrap_err();
+        assert!(!err.to_string().contains("12x4"), "{err}");
+        assert!(err.to_string().contains("got <redacted>"), "{err}");
     }
 
     #[test]
@@ -4048,6 +6361,64 @@ mod tests {
         assert_eq!(table.get("host"), Some(&Value::String("low".into())));
     }
 
+    #[test]
+    fn file_stem_ini_format_loads_and_set_edits_in_place() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("demo.ini"), "; hand-written\nport = 7\n").unwrap();
+        let builder = || {
+            Clapfig::builder(demo_schema())
+                .app_name("demo")
+                .file_stem("demo")
+                .formats(["toml", "ini"])
+                .search_paths(vec![SearchPath::Path(dir.path().to_path_buf())])
+                .persist_scope("local", SearchPath::Path(dir.path().to_path_buf()))
+                .no_env()
+        };
+        let table = builder().load().unwrap();
+        assert_eq!(table.get("port"), Some(&Value::Integer(7)));
+
+        builder()
+            .handle(&ConfigAction::Set {
+                key: "host".into(),
+                value: "example.com".into(),
+                scope: None,
+            })
+            .unwrap();
+        let content = fs::read_to_string(dir.path().join("demo.ini")).unwrap();
+        assert_eq!(content, "; hand-written\nport = 7\nhost = example.com\n");
+        assert!(!dir.path().join("demo.toml").exists());
+    }
+
+    #[test]
+    fn json5_set_into_missing_file_lands_under_the_template_comment() {
+        let dir = TempDir::new().unwrap();
+        let builder = || {
+            Clapfig::builder(demo_schema())
+                .app_name("demo")
+                .file_stem("demo")
+                .formats(["json5"])
+                .search_paths(vec![SearchPath::Path(dir.path().to_path_buf())])
+                .persist_scope("local", SearchPath::Path(dir.path().to_path_buf()))
+                .no_env()
+        };
+        builder()
+            .handle(&ConfigAction::Set {
+                key: "db.url".into(),
+                value: "pg://x".into(),
+                scope: None,
+            })
+            .unwrap();
+        let content = fs::read_to_string(dir.path().join("demo.json5")).unwrap();
+        assert!(
+            content.contains("    // \"url\": \"\",\n    \"url\": \"pg://x\",\n"),
+            "{content}"
+        );
+        assert!(content.contains("// Database settings"), "{content}");
+        let table = builder().load().unwrap();
+        let db = table.get("db").unwrap().as_map().unwrap();
+        assert_eq!(db["url"], Value::from("pg://x"));
+    }
+
     #[test]
     fn formats_unknown_name_errors() {
         let err = Clapfig::builder(demo_schema())
@@ -4060,7 +6431,7 @@ mod tests {
         match err {
             Some(ClapfigError::UnknownFormat { name, available }) => {
                 assert_eq!(name, "xml");
-                assert_eq!(available, ["toml", "yaml", "json"]);
+                assert_eq!(available, ["toml", "yaml", "json", "json5", "ini"]);
             }
             other => panic!("expected UnknownFormat, got {other:?}"),
         }
@@ -4156,14 +6527,14 @@ mod tests {
         // build/load time — never a silent parse-as-TOML fallback.
         let err = Clapfig::builder(demo_schema())
             .app_name("demo")
-            .file_name("demo.ini")
+            .file_name("demo.xml")
             .no_env()
             .build_resolver()
             .err();
         match err {
             Some(ClapfigError::UnknownFormat { name, available }) => {
-                assert_eq!(name, "ini");
-                assert_eq!(available, ["toml", "yaml", "json"]);
+                assert_eq!(name, "xml");
+                assert_eq!(available, ["toml", "yaml", "json", "json5", "ini"]);
             }
             other => panic!("expected UnknownFormat, got {other:?}"),
         }
@@ -4208,10 +6579,10 @@ mod tests {
 
     #[test]
     fn gen_output_unknown_extension_errors() {
-        // `gen --output config.ini` must not silently write the
+        // `gen --output config.xml` must not silently write the
         // preferred format's content under a foreign extension.
         let dir = TempDir::new().unwrap();
-        let out_path = dir.path().join("config.ini");
+        let out_path = dir.path().join("config.xml");
         let err = Clapfig::builder(demo_schema())
             .app_name("demo")
             .no_env()
@@ -4220,8 +6591,8 @@ mod tests {
             })
             .unwrap_err();
         assert!(
-            matches!(err, ClapfigError::UnknownFormat { ref name, .. } if name == "ini"),
-            "expected UnknownFormat('ini'), got {err:?}"
+            matches!(err, ClapfigError::UnknownFormat { ref name, .. } if name == "xml"),
+            "expected UnknownFormat('xml'), got {err:?}"
         );
         assert!(!out_path.exists(), "no file may be written on error");
     }
@@ -4229,11 +6600,11 @@ mod tests {
     #[test]
     fn set_explicit_path_unknown_extension_errors() {
         // Persist targets follow the same explicit-path rule: an
-        // unclaimed extension is a hard error, not TOML-under-.ini.
+        // unclaimed extension is a hard error, not TOML-under-.xml.
         let dir = TempDir::new().unwrap();
         let err = Clapfig::builder(demo_schema())
             .app_name("demo")
-            .file_name("demo.ini")
+            .file_name("demo.xml")
             .persist_scope("local", SearchPath::Path(dir.path().to_path_buf()))
             .no_env()
             .handle(&ConfigAction::Set {
@@ -4243,8 +6614,166 @@ mod tests {
             })
             .unwrap_err();
         assert!(
-            matches!(err, ClapfigError::UnknownFormat { ref name, .. } if name == "ini"),
-            "expected UnknownFormat('ini'), got {err:?}"
+            matches!(err, ClapfigError::UnknownFormat { ref name, .. } if name == "xml"),
+            "expected UnknownFormat('xml'), got {err:?}"
+        );
+    }
+
+    // --- custom format adapters ---
+
+    /// A user-registered format: TOML syntax under a legacy `.cfg`
+    /// extension, with a marker on generated templates so tests can tell
+    /// which adapter rendered them.
+    struct Legacy;
+
+    impl FormatAdapter for Legacy {
+        fn name(&self) -> &'static str {
+            "legacy"
+        }
+
+        fn extensions(&self) -> &'static [&'static str] {
+            &["cfg"]
+        }
+
+        fn capabilities(&self) -> &'static [format::Operation] {
+            format::TomlAdapter.capabilities()
+        }
+
+        fn parse(&self, text: &str) -> Result<format::Parsed, format::FormatError> {
+            format::TomlAdapter.parse(text)
+        }
+
+        fn serialize(&self, value: &Value) -> Result<String, format::FormatError> {
+            format::TomlAdapter.serialize(value)
+        }
+
+        fn template(&self, shape: &Shape) -> Result<String, format::FormatError> {
+            Ok(format!(
+                "# legacy\n{}",
+                format::TomlAdapter.template(shape)?
+            ))
+        }
+
+        fn edit(
+            &self,
+            source: &str,
+            edit: format::FileEdit<'_>,
+        ) -> Result<String, format::FormatError> {
+            format::TomlAdapter.edit(source, edit)
+        }
+    }
+
+    #[test]
+    fn format_adapter_joins_default_stem_discovery() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("demo.cfg"), "port = 31\n").unwrap();
+        let table = Clapfig::builder(demo_schema())
+            .app_name("demo")
+            .file_stem("demo")
+            .format_adapter(Box::new(Legacy))
+            .search_paths(vec![SearchPath::Path(dir.path().to_path_buf())])
+            .no_env()
+            .load()
+            .unwrap();
+        assert_eq!(table.get("port"), Some(&Value::Integer(31)));
+    }
+
+    #[test]
+    fn format_adapter_is_selectable_by_name_and_preferred_for_gen_and_set() {
+        let dir = TempDir::new().unwrap();
+        let builder = || {
+            Clapfig::builder(demo_schema())
+                .app_name("demo")
+                .file_stem("demo")
+                .formats(["legacy", "toml"])
+                .format_adapter(Box::new(Legacy))
+                .persist_scope("local", SearchPath::Path(dir.path().to_path_buf()))
+                .no_env()
+        };
+        let generated = builder()
+            .handle_to_string(&ConfigAction::Gen { output: None })
+            .unwrap();
+        assert!(generated.starts_with("# legacy\n"), "got: {generated}");
+
+        builder()
+            .handle(&ConfigAction::Set {
+                key: "port".into(),
+                value: "77".into(),
+                scope: None,
+            })
+            .unwrap();
+        let created = fs::read_to_string(dir.path().join("demo.cfg")).unwrap();
+        assert!(created.starts_with("# legacy\n"), "seeded by the adapter");
+        assert!(created.contains("port = 77"));
+        assert!(!dir.path().join("demo.toml").exists());
+    }
+
+    #[test]
+    fn format_adapter_claims_its_extension_for_explicit_paths() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("demo.cfg"), "port = 5\n").unwrap();
+        let table = Clapfig::builder(demo_schema())
+            .app_name("demo")
+            .file_name("demo.cfg")
+            .format_adapter(Box::new(Legacy))
+            .search_paths(vec![SearchPath::Path(dir.path().to_path_buf())])
+            .no_env()
+            .load()
+            .unwrap();
+        assert_eq!(table.get("port"), Some(&Value::Integer(5)));
+
+        let out_path = dir.path().join("out.cfg");
+        Clapfig::builder(demo_schema())
+            .app_name("demo")
+            .format_adapter(Box::new(Legacy))
+            .no_env()
+            .handle(&ConfigAction::Gen {
+                output: Some(out_path.clone()),
+            })
+            .unwrap();
+        assert!(
+            fs::read_to_string(&out_path)
+                .unwrap()
+                .starts_with("# legacy\n")
+        );
+    }
+
+    #[test]
+    fn format_adapter_name_is_listed_in_unknown_format_errors() {
+        let err = Clapfig::builder(demo_schema())
+            .app_name("demo")
+            .file_stem("demo")
+            .formats(["xml"])
+            .format_adapter(Box::new(Legacy))
+            .no_env()
+            .build_resolver()
+            .err();
+        match err {
+            Some(ClapfigError::UnknownFormat { available, .. }) => {
+                assert_eq!(
+                    available,
+                    ["toml", "yaml", "json", "json5", "ini", "legacy"]
+                );
+            }
+            other => panic!("expected UnknownFormat, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn format_adapter_parse_errors_render_with_the_file_path() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("demo.cfg"), "port = = 1\n").unwrap();
+        let err = Clapfig::builder(demo_schema())
+            .app_name("demo")
+            .file_stem("demo")
+            .format_adapter(Box::new(Legacy))
+            .search_paths(vec![SearchPath::Path(dir.path().to_path_buf())])
+            .no_env()
+            .load()
+            .unwrap_err();
+        assert!(
+            matches!(&err, ClapfigError::ParseError { path, .. } if path.ends_with("demo.cfg")),
+            "got {err:?}"
         );
     }
 
@@ -4252,9 +6781,8 @@ mod tests {
 
     #[test]
     fn override_string_coerces_into_datetime_leaf() {
-        // CLI/URL/env layers are schema-blind (heuristics deliver
-        // strings); the DateTime leaf declaration coerces at finalize
-        // (ADR-0001).
+        // CLI/URL/env layers deliver datetimes as strings; the DateTime
+        // leaf declaration coerces at finalize (ADR-0001).
         let dir = TempDir::new().unwrap();
         let schema = Schema::object("T")
             .field("stamp", RtField::datetime().optional())
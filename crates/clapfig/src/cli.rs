@@ -7,7 +7,7 @@
 //!
 //! The module provides two clap derive types — [`ConfigArgs`] and
 //! [`ConfigSubcommand`] — that you can embed directly into your clap
-//! `#[derive(Parser)]` struct to get `config gen|list|get|set|unset|schema` subcommands
+//! `#[derive(Parser)]` struct to get `config gen|list|get|explain|diff|set|unset|migrate|schema` subcommands
 //! with no boilerplate.
 //!
 //! The only bridge to the core is [`ConfigArgs::into_action()`], which
@@ -15,16 +15,24 @@
 //! From there, all logic flows through the clap-free
 //! [`handle()`](crate::Builder::handle) API.
 //!
+//! [`ConfigFlags`] goes the other way: it generates a `--long` flag for
+//! every schema leaf and turns the parsed flags into overrides at the
+//! `Cli` layer, so apps need not hand-write one argument per setting.
+//!
 //! If you use a different CLI parser (or no CLI at all), you can skip this
 //! module entirely and construct [`ConfigAction`](crate::ConfigAction) values
 //! directly.
 
 use std::path::PathBuf;
 
-use clap::{Arg, ArgMatches, Args, Command, Subcommand};
+use clap::builder::PossibleValuesParser;
+use clap::parser::ValueSource;
+use clap::{Arg, ArgAction, ArgMatches, Args, Command, Subcommand};
 
 use crate::error::ClapfigError;
-use crate::types::ConfigAction;
+use crate::runtime::{Leaf, LeafType, Schema, Shape};
+use crate::types::{ConfigAction, DiffTarget};
+use crate::value::Value;
 
 /// Clap-derived args for the `config` subcommand group.
 ///
@@ -50,9 +58,30 @@ pub struct ConfigArgs {
     ///
     /// For `list`/`get`: reads from that scope's config file only (instead of
     /// the merged resolved view).
+    ///
+    /// For `migrate`: rewrites only that scope's file instead of every
+    /// scope's.
     #[arg(long, global = true)]
     pub scope: Option<String>,
 
+    /// Load a named configuration profile (e.g. "prod").
+    ///
+    /// Not part of the [`ConfigAction`]: pass it to
+    /// [`Builder::profile`](crate::Builder::profile) before handling the
+    /// action, so every subcommand sees the same profile. Without it,
+    /// `MYAPP_PROFILE` applies only if the builder set
+    /// [`profile_key`](crate::Builder::profile_key).
+    #[arg(long, global = true)]
+    pub profile: Option<String>,
+
+    /// Override a configuration value for this invocation (`-c port=8080`).
+    ///
+    /// Repeatable. Not part of the [`ConfigAction`]: pass the assignments
+    /// to [`Builder::cli_assignments`](crate::Builder::cli_assignments),
+    /// which parses each value the way `config set` does.
+    #[arg(short = 'c', long = "set", global = true, value_name = "KEY=VALUE")]
+    pub overrides: Vec<String>,
+
     #[command(subcommand)]
     pub action: Option<ConfigSubcommand>,
 }
@@ -61,7 +90,11 @@ pub struct ConfigArgs {
 #[derive(Debug, Subcommand)]
 pub enum ConfigSubcommand {
     /// Show all resolved configuration key-value pairs.
-    List,
+    List {
+        /// Annotate every value with where it came from.
+        #[arg(long)]
+        origins: bool,
+    },
     /// Generate a commented sample configuration file.
     Gen {
         /// Write to a file instead of stdout.
@@ -78,6 +111,21 @@ pub enum ConfigSubcommand {
     Get {
         /// Dotted key path (e.g. "database.url").
         key: String,
+        /// Annotate the value with where it came from.
+        #[arg(long)]
+        origin: bool,
+    },
+    /// Show every input that supplied a config key's value, and which one won.
+    Explain {
+        /// Dotted key path (e.g. "database.url").
+        key: String,
+    },
+    /// Show every value that differs between two configurations.
+    Diff {
+        /// Left side: a directory to resolve at, or `@scope` for a scope's file.
+        left: DiffTarget,
+        /// Right side (default: the current directory).
+        right: Option<DiffTarget>,
     },
     /// Persist a configuration value to the config file.
     Set {
@@ -91,6 +139,8 @@ pub enum ConfigSubcommand {
         /// Dotted key path (e.g. "database.url").
         key: String,
     },
+    /// Rewrite config files to the current schema, keeping comments.
+    Migrate,
 }
 
 impl ConfigArgs {
@@ -98,20 +148,37 @@ impl ConfigArgs {
     ///
     /// Bare `config` (no subcommand) and explicit `config list` both map to
     /// `ConfigAction::List`. The `--scope` flag is threaded through to all
-    /// variants except `Gen`.
+    /// variants except `Gen`, `Schema`, `Explain`, and `Diff` (whose sides
+    /// name scopes themselves). A `diff` without a right side compares
+    /// against the current directory.
     pub fn into_action(self) -> ConfigAction {
         let scope = self.scope;
         match self.action {
-            None | Some(ConfigSubcommand::List) => ConfigAction::List { scope },
+            None => ConfigAction::List {
+                scope,
+                origins: false,
+            },
+            Some(ConfigSubcommand::List { origins }) => ConfigAction::List { scope, origins },
             Some(ConfigSubcommand::Gen { output }) => ConfigAction::Gen { output },
             Some(ConfigSubcommand::Schema { output }) => ConfigAction::Schema { output },
-            Some(ConfigSubcommand::Get { key }) => ConfigAction::Get { key, scope },
+            Some(ConfigSubcommand::Get { key, origin }) => ConfigAction::Get { key, scope, origin },
+            Some(ConfigSubcommand::Explain { key }) => ConfigAction::Explain { key },
+            Some(ConfigSubcommand::Diff { left, right }) => ConfigAction::Diff {
+                left,
+                right: right.unwrap_or_else(current_dir_target),
+            },
             Some(ConfigSubcommand::Set { key, value }) => ConfigAction::Set { key, value, scope },
             Some(ConfigSubcommand::Unset { key }) => ConfigAction::Unset { key, scope },
+            Some(ConfigSubcommand::Migrate) => ConfigAction::Migrate { scope },
         }
     }
 }
 
+/// The right side of a `diff` given no right argument.
+fn current_dir_target() -> DiffTarget {
+    DiffTarget::Dir(PathBuf::from("."))
+}
+
 /// Runtime-configurable alternative to [`ConfigArgs`] for apps that need
 /// to rename subcommands or flags to avoid conflicts.
 ///
@@ -142,11 +209,19 @@ pub struct ConfigCommand {
     gen_name: String,
     schema_name: String,
     get_name: String,
+    explain_name: String,
+    diff_name: String,
     set_name: String,
     unset_name: String,
+    migrate_name: String,
     scope_long: String,
+    profile_long: String,
+    override_long: String,
+    override_short: Option<char>,
     output_long: String,
     output_short: Option<char>,
+    origins_long: String,
+    origin_long: String,
 }
 
 impl Default for ConfigCommand {
@@ -156,11 +231,19 @@ impl Default for ConfigCommand {
             gen_name: "gen".into(),
             schema_name: "schema".into(),
             get_name: "get".into(),
+            explain_name: "explain".into(),
+            diff_name: "diff".into(),
             set_name: "set".into(),
             unset_name: "unset".into(),
+            migrate_name: "migrate".into(),
             scope_long: "scope".into(),
+            profile_long: "profile".into(),
+            override_long: "set".into(),
+            override_short: Some('c'),
             output_long: "output".into(),
             output_short: Some('o'),
+            origins_long: "origins".into(),
+            origin_long: "origin".into(),
         }
     }
 }
@@ -195,6 +278,18 @@ impl ConfigCommand {
         self
     }
 
+    /// Rename the `explain` subcommand.
+    pub fn explain_name(mut self, name: impl Into<String>) -> Self {
+        self.explain_name = name.into();
+        self
+    }
+
+    /// Rename the `diff` subcommand.
+    pub fn diff_name(mut self, name: impl Into<String>) -> Self {
+        self.diff_name = name.into();
+        self
+    }
+
     /// Rename the `set` subcommand.
     pub fn set_name(mut self, name: impl Into<String>) -> Self {
         self.set_name = name.into();
@@ -207,12 +302,57 @@ impl ConfigCommand {
         self
     }
 
+    /// Rename the `migrate` subcommand.
+    pub fn migrate_name(mut self, name: impl Into<String>) -> Self {
+        self.migrate_name = name.into();
+        self
+    }
+
     /// Rename the `--scope` flag.
     pub fn scope_long(mut self, name: impl Into<String>) -> Self {
         self.scope_long = name.into();
         self
     }
 
+    /// Rename the `--profile` flag.
+    pub fn profile_long(mut self, name: impl Into<String>) -> Self {
+        self.profile_long = name.into();
+        self
+    }
+
+    /// Rename the `--set` override flag.
+    pub fn override_long(mut self, name: impl Into<String>) -> Self {
+        self.override_long = name.into();
+        self
+    }
+
+    /// Set or disable the short flag for `--set` (default: `Some('c')`).
+    /// Pass `None` to remove the short flag entirely.
+    pub fn override_short(mut self, short: Option<char>) -> Self {
+        self.override_short = short;
+        self
+    }
+
+    /// The repeatable, global `-c key=value` / `--set key=value` argument
+    /// with the configured names. [`as_command`](Self::as_command) adds it
+    /// to the config subcommand; add it to your top-level command as well
+    /// to take overrides for every invocation (`myapp -c port=8080 serve`),
+    /// and read it back with [`parse_overrides`](Self::parse_overrides).
+    /// clap keeps a global option's values from one command level only,
+    /// so all `-c` options go on the same side of a subcommand.
+    pub fn override_arg(&self) -> Arg {
+        let mut arg = Arg::new("overrides")
+            .long(self.override_long.clone())
+            .value_name("KEY=VALUE")
+            .action(ArgAction::Append)
+            .help("Override a configuration value for this invocation.")
+            .global(true);
+        if let Some(short) = self.override_short {
+            arg = arg.short(short);
+        }
+        arg
+    }
+
     /// Rename the `--output` flag on the `gen` subcommand.
     pub fn output_long(mut self, name: impl Into<String>) -> Self {
         self.output_long = name.into();
@@ -226,6 +366,18 @@ impl ConfigCommand {
         self
     }
 
+    /// Rename the `--origins` flag on the `list` subcommand.
+    pub fn origins_long(mut self, name: impl Into<String>) -> Self {
+        self.origins_long = name.into();
+        self
+    }
+
+    /// Rename the `--origin` flag on the `get` subcommand.
+    pub fn origin_long(mut self, name: impl Into<String>) -> Self {
+        self.origin_long = name.into();
+        self
+    }
+
     /// Build a [`clap::Command`] with the configured names.
     ///
     /// The `name` parameter sets the top-level subcommand name
@@ -236,6 +388,11 @@ impl ConfigCommand {
             .help("Target a named persist scope (e.g. \"local\", \"global\").")
             .global(true);
 
+        let profile_arg = Arg::new("profile")
+            .long(self.profile_long.clone())
+            .help("Load a named configuration profile (e.g. \"prod\").")
+            .global(true);
+
         let build_output_arg = || {
             let mut arg = Arg::new("output")
                 .long(self.output_long.clone())
@@ -248,7 +405,13 @@ impl ConfigCommand {
         };
 
         let list_cmd = Command::new(self.list_name.clone())
-            .about("Show all resolved configuration key-value pairs.");
+            .about("Show all resolved configuration key-value pairs.")
+            .arg(
+                Arg::new("origins")
+                    .long(self.origins_long.clone())
+                    .action(ArgAction::SetTrue)
+                    .help("Annotate every value with where it came from."),
+            );
 
         let gen_cmd = Command::new(self.gen_name.clone())
             .about("Generate a commented sample configuration file.")
@@ -260,12 +423,40 @@ impl ConfigCommand {
 
         let get_cmd = Command::new(self.get_name.clone())
             .about("Show the resolved value and documentation for a config key.")
+            .arg(
+                Arg::new("key")
+                    .required(true)
+                    .help("Dotted key path (e.g. \"database.url\")."),
+            )
+            .arg(
+                Arg::new("origin")
+                    .long(self.origin_long.clone())
+                    .action(ArgAction::SetTrue)
+                    .help("Annotate the value with where it came from."),
+            );
+
+        let explain_cmd = Command::new(self.explain_name.clone())
+            .about("Show every input that supplied a config key's value, and which one won.")
             .arg(
                 Arg::new("key")
                     .required(true)
                     .help("Dotted key path (e.g. \"database.url\")."),
             );
 
+        let diff_cmd = Command::new(self.diff_name.clone())
+            .about("Show every value that differs between two configurations.")
+            .arg(
+                Arg::new("left")
+                    .required(true)
+                    .value_parser(clap::value_parser!(DiffTarget))
+                    .help("Left side: a directory to resolve at, or `@scope` for a scope's file."),
+            )
+            .arg(
+                Arg::new("right")
+                    .value_parser(clap::value_parser!(DiffTarget))
+                    .help("Right side (default: the current directory)."),
+            );
+
         let set_cmd = Command::new(self.set_name.clone())
             .about("Persist a configuration value to the config file.")
             .arg(
@@ -283,16 +474,42 @@ impl ConfigCommand {
                     .help("Dotted key path (e.g. \"database.url\")."),
             );
 
+        let migrate_cmd = Command::new(self.migrate_name.clone())
+            .about("Rewrite config files to the current schema, keeping comments.");
+
         Command::new(name.to_owned())
             .about("Manage configuration.")
             .subcommand_required(false)
             .arg(scope_arg)
+            .arg(profile_arg)
+            .arg(self.override_arg())
             .subcommand(list_cmd)
             .subcommand(gen_cmd)
             .subcommand(schema_cmd)
             .subcommand(get_cmd)
+            .subcommand(explain_cmd)
+            .subcommand(diff_cmd)
             .subcommand(set_cmd)
             .subcommand(unset_cmd)
+            .subcommand(migrate_cmd)
+    }
+
+    /// The `--profile` value from parsed [`ArgMatches`], if given. Like
+    /// [`ConfigArgs::profile`], it is not part of the action; pass it to
+    /// [`Builder::profile`](crate::Builder::profile).
+    pub fn parse_profile(&self, matches: &ArgMatches) -> Option<String> {
+        matches.get_one::<String>("profile").cloned()
+    }
+
+    /// The `-c` / `--set` assignments from parsed [`ArgMatches`], in the
+    /// order given. Like [`ConfigArgs::overrides`], they are not part of
+    /// the action; pass them to
+    /// [`Builder::cli_assignments`](crate::Builder::cli_assignments).
+    pub fn parse_overrides(&self, matches: &ArgMatches) -> Vec<String> {
+        matches
+            .get_many::<String>("overrides")
+            .map(|values| values.cloned().collect())
+            .unwrap_or_default()
     }
 
     /// Extract a [`ConfigAction`] from parsed [`ArgMatches`].
@@ -303,8 +520,14 @@ impl ConfigCommand {
         let scope = matches.get_one::<String>("scope").cloned();
 
         match matches.subcommand() {
-            None => Ok(ConfigAction::List { scope }),
-            Some((name, _)) if name == self.list_name => Ok(ConfigAction::List { scope }),
+            None => Ok(ConfigAction::List {
+                scope,
+                origins: false,
+            }),
+            Some((name, sub)) if name == self.list_name => Ok(ConfigAction::List {
+                scope,
+                origins: sub.get_flag("origins"),
+            }),
             Some((name, sub)) if name == self.gen_name => {
                 let output = sub.get_one::<PathBuf>("output").cloned();
                 Ok(ConfigAction::Gen { output })
@@ -315,7 +538,20 @@ impl ConfigCommand {
             }
             Some((name, sub)) if name == self.get_name => {
                 let key = sub.get_one::<String>("key").unwrap().clone();
-                Ok(ConfigAction::Get { key, scope })
+                let origin = sub.get_flag("origin");
+                Ok(ConfigAction::Get { key, scope, origin })
+            }
+            Some((name, sub)) if name == self.explain_name => {
+                let key = sub.get_one::<String>("key").unwrap().clone();
+                Ok(ConfigAction::Explain { key })
+            }
+            Some((name, sub)) if name == self.diff_name => {
+                let left = sub.get_one::<DiffTarget>("left").unwrap().clone();
+                let right = sub
+                    .get_one::<DiffTarget>("right")
+                    .cloned()
+                    .unwrap_or_else(current_dir_target);
+                Ok(ConfigAction::Diff { left, right })
             }
             Some((name, sub)) if name == self.set_name => {
                 let key = sub.get_one::<String>("key").unwrap().clone();
@@ -326,11 +562,273 @@ impl ConfigCommand {
                 let key = sub.get_one::<String>("key").unwrap().clone();
                 Ok(ConfigAction::Unset { key, scope })
             }
+            Some((name, _)) if name == self.migrate_name => Ok(ConfigAction::Migrate { scope }),
             Some((name, _)) => Err(ClapfigError::UnknownSubcommand(name.to_owned())),
         }
     }
 }
 
+/// Generated `--long` flags for every leaf of a schema.
+///
+/// [`args`](Self::args) walks an object schema and produces one
+/// [`clap::Arg`] per leaf, named after its dotted path with `.` and `_`
+/// turned into `-` (`database.pool_size` → `--database-pool-size`). The
+/// leaf type picks the parser: integers are range-checked, enum leaves
+/// list their [`PossibleValues`](clap::builder::PossibleValue), bool
+/// leaves are switches that also take `=false` (`--verbose`,
+/// `--verbose=false`), and arrays of scalars repeat (`--tag a --tag b`).
+/// The first doc line is the help text; the full doc is the long help.
+/// Maps, arrays of tables, and tagged unions get no flags.
+///
+/// [`Builder::cli_overrides_from_matches`](crate::Builder::cli_overrides_from_matches)
+/// reads the parsed flags back as typed overrides at the
+/// [`Cli`](crate::Layer::Cli) layer. Only flags given on the command line
+/// become overrides; the rest leave the lower layers alone.
+///
+/// # Example
+///
+/// ```ignore
+/// let flags = ConfigFlags::new()
+///     .exclude("database.password")
+///     .rename("database.url", "db-url");
+///
+/// let matches = flags.augment(Cli::command(), AppConfig::schema()).get_matches();
+/// let config: AppConfig = Clapfig::typed::<AppConfig>()
+///     .app_name("myapp")
+///     .cli_overrides_from_matches(&flags, &matches)
+///     .load()?;
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ConfigFlags {
+    include: Vec<String>,
+    exclude: Vec<String>,
+    renames: Vec<(String, String)>,
+}
+
+/// One leaf that gets a flag.
+struct FlagLeaf<'s> {
+    key: String,
+    long: String,
+    leaf: &'s Leaf,
+    /// An array of this leaf type: the flag repeats.
+    many: bool,
+}
+
+impl ConfigFlags {
+    /// Flags for every leaf, with the default names.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Generate flags only under the dotted path `path` (a section or a
+    /// single leaf). Repeatable; with no `include`, every leaf gets one.
+    pub fn include(mut self, path: impl Into<String>) -> Self {
+        self.include.push(path.into());
+        self
+    }
+
+    /// Generate no flags under the dotted path `path`. Wins over
+    /// [`include`](Self::include).
+    pub fn exclude(mut self, path: impl Into<String>) -> Self {
+        self.exclude.push(path.into());
+        self
+    }
+
+    /// Name the flag for the leaf at dotted `key` `--{long}` instead of
+    /// the generated name.
+    pub fn rename(mut self, key: impl Into<String>, long: impl Into<String>) -> Self {
+        self.renames.push((key.into(), long.into()));
+        self
+    }
+
+    /// One [`Arg`] per selected leaf of `schema`. The arg id is the
+    /// leaf's dotted key.
+    ///
+    /// # Panics
+    ///
+    /// If an `include` or `exclude` path names nothing in `schema`, a
+    /// `rename` names a leaf that gets no flag, or two leaves get the
+    /// same flag name (`database.url` and `database_url`).
+    pub fn args(&self, schema: &Schema) -> Vec<Arg> {
+        self.leaves(schema).iter().map(flag_arg).collect()
+    }
+
+    /// `command` with [`args`](Self::args) added.
+    pub fn augment(&self, command: Command, schema: &Schema) -> Command {
+        command.args(self.args(schema))
+    }
+
+    /// The flags given on the command line, as `(dotted_key, value)`
+    /// override pairs typed by their leaves.
+    pub fn overrides(&self, schema: &Schema, matches: &ArgMatches) -> Vec<(String, Value)> {
+        self.leaves(schema)
+            .into_iter()
+            .filter(|flag| matches.value_source(&flag.key) == Some(ValueSource::CommandLine))
+            .filter_map(|flag| {
+                let mut values = flag_values(&flag, matches);
+                let value = if flag.many {
+                    Value::Array(values)
+                } else {
+                    values.pop()?
+                };
+                Some((flag.key, value))
+            })
+            .collect()
+    }
+
+    fn leaves<'s>(&self, schema: &'s Schema) -> Vec<FlagLeaf<'s>> {
+        let mut all = Vec::new();
+        collect_leaves(schema, "", &mut all);
+        for (what, paths) in [("include", &self.include), ("exclude", &self.exclude)] {
+            if let Some(path) = paths
+                .iter()
+                .find(|path| !all.iter().any(|leaf| under(&leaf.key, path)))
+            {
+                panic!("clapfig: ConfigFlags::{what}: `{path}` names no flag-able key");
+            }
+        }
+        let mut selected: Vec<FlagLeaf<'s>> = all
+            .into_iter()
+            .filter(|leaf| {
+                (self.include.is_empty() || self.include.iter().any(|p| under(&leaf.key, p)))
+                    && !self.exclude.iter().any(|p| under(&leaf.key, p))
+            })
+            .collect();
+        for (key, long) in &self.renames {
+            let Some(leaf) = selected.iter_mut().find(|leaf| leaf.key == *key) else {
+                panic!("clapfig: ConfigFlags::rename: `{key}` gets no flag");
+            };
+            leaf.long = long.clone();
+        }
+        // `database.url` and `database_url` both spell `--database-url`;
+        // name both keys rather than leave clap to panic on the arg.
+        for (i, flag) in selected.iter().enumerate() {
+            if let Some(other) = selected[..i].iter().find(|other| other.long == flag.long) {
+                panic!(
+                    "clapfig: ConfigFlags: `{}` and `{}` both get `--{}`; rename or exclude one",
+                    other.key, flag.key, flag.long
+                );
+            }
+        }
+        selected
+    }
+}
+
+/// `key` is `path` or lies beneath it.
+fn under(key: &str, path: &str) -> bool {
+    key.strip_prefix(path)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+}
+
+fn collect_leaves<'s>(schema: &'s Schema, prefix: &str, out: &mut Vec<FlagLeaf<'s>>) {
+    for nf in &schema.fields {
+        let key = if prefix.is_empty() {
+            nf.name.clone()
+        } else {
+            format!("{prefix}.{}", nf.name)
+        };
+        let (leaf, many) = match &nf.field {
+            Shape::Leaf(leaf) => (leaf, false),
+            Shape::Array(array) => match array.item.as_ref() {
+                Shape::Leaf(leaf) => (leaf, true),
+                _ => continue,
+            },
+            Shape::Object(nested) => {
+                collect_leaves(nested, &key, out);
+                continue;
+            }
+            Shape::Map(_) | Shape::Tagged(_) => continue,
+        };
+        let long = key.replace(['.', '_'], "-");
+        out.push(FlagLeaf {
+            key,
+            long,
+            leaf,
+            many,
+        });
+    }
+}
+
+fn flag_arg(flag: &FlagLeaf<'_>) -> Arg {
+    let mut arg = Arg::new(flag.key.clone()).long(flag.long.clone());
+    if let Some(first) = flag.leaf.doc.first() {
+        arg = arg.help(first.clone());
+        if flag.leaf.doc.len() > 1 {
+            arg = arg.long_help(flag.leaf.doc.join("\n"));
+        }
+    }
+    if matches!(flag.leaf.ty, LeafType::Bool) && !flag.many {
+        // `--verbose` sets true; `--verbose=false` overrides a lower
+        // layer's true.
+        return arg
+            .action(ArgAction::Set)
+            .num_args(0..=1)
+            .require_equals(true)
+            .default_missing_value("true")
+            .value_parser(clap::value_parser!(bool));
+    }
+    arg = arg.action(if flag.many {
+        ArgAction::Append
+    } else {
+        ArgAction::Set
+    });
+    match &flag.leaf.ty {
+        LeafType::Integer { min, max } => arg.value_parser(
+            clap::value_parser!(i64).range(min.unwrap_or(i64::MIN)..=max.unwrap_or(i64::MAX)),
+        ),
+        LeafType::Float => arg.value_parser(clap::value_parser!(f64)),
+        LeafType::Bool => arg.value_parser(clap::value_parser!(bool)),
+        LeafType::Enum { values } => {
+            arg.value_parser(PossibleValuesParser::new(values.iter().map(enum_label)))
+        }
+        LeafType::String | LeafType::DateTime | LeafType::Path { .. } | LeafType::Value => arg,
+    }
+}
+
+/// Every value clap parsed for `flag`, typed by its leaf.
+fn flag_values(flag: &FlagLeaf<'_>, matches: &ArgMatches) -> Vec<Value> {
+    fn many<T: Clone + Send + Sync + 'static>(matches: &ArgMatches, id: &str) -> Vec<T> {
+        matches
+            .get_many::<T>(id)
+            .map(|values| values.cloned().collect())
+            .unwrap_or_default()
+    }
+    let id = flag.key.as_str();
+    match &flag.leaf.ty {
+        LeafType::Bool => many::<bool>(matches, id)
+            .into_iter()
+            .map(Value::from)
+            .collect(),
+        LeafType::Integer { .. } => many::<i64>(matches, id)
+            .into_iter()
+            .map(Value::from)
+            .collect(),
+        LeafType::Float => many::<f64>(matches, id)
+            .into_iter()
+            .map(Value::from)
+            .collect(),
+        LeafType::Enum { values } => many::<String>(matches, id)
+            .into_iter()
+            .filter_map(|label| values.iter().find(|v| enum_label(v) == label).cloned())
+            .collect(),
+        LeafType::String | LeafType::DateTime | LeafType::Path { .. } | LeafType::Value => {
+            many::<String>(matches, id)
+                .into_iter()
+                .map(Value::from)
+                .collect()
+        }
+    }
+}
+
+/// How an enum value is spelled on the command line: strings bare,
+/// anything else in its inline notation.
+fn enum_label(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -406,6 +904,7 @@ mod tests {
             ConfigAction::Get {
                 key: "database.url".into(),
                 scope: None,
+                origin: false,
             }
         );
     }
@@ -461,14 +960,91 @@ mod tests {
     fn parse_bare_config_is_list() {
         let args = parse(&["test"]);
         let action = args.into_action();
-        assert_eq!(action, ConfigAction::List { scope: None });
+        assert_eq!(
+            action,
+            ConfigAction::List {
+                scope: None,
+                origins: false,
+            }
+        );
     }
 
     #[test]
     fn parse_explicit_list() {
         let args = parse(&["test", "list"]);
         let action = args.into_action();
-        assert_eq!(action, ConfigAction::List { scope: None });
+        assert_eq!(
+            action,
+            ConfigAction::List {
+                scope: None,
+                origins: false,
+            }
+        );
+    }
+
+    #[test]
+    fn parse_explain() {
+        let args = parse(&["test", "explain", "database.url"]);
+        let action = args.into_action();
+        assert_eq!(
+            action,
+            ConfigAction::Explain {
+                key: "database.url".into(),
+            }
+        );
+    }
+
+    #[test]
+    fn parse_diff_of_two_targets() {
+        let args = parse(&["test", "diff", "@global", "pkg/b"]);
+        let action = args.into_action();
+        assert_eq!(
+            action,
+            ConfigAction::Diff {
+                left: DiffTarget::Scope("global".into()),
+                right: DiffTarget::Dir(PathBuf::from("pkg/b")),
+            }
+        );
+    }
+
+    #[test]
+    fn parse_diff_defaults_right_to_current_dir() {
+        let args = parse(&["test", "diff", "../other"]);
+        let action = args.into_action();
+        assert_eq!(
+            action,
+            ConfigAction::Diff {
+                left: DiffTarget::Dir(PathBuf::from("../other")),
+                right: DiffTarget::Dir(PathBuf::from(".")),
+            }
+        );
+    }
+
+    #[test]
+    fn parse_list_with_origins() {
+        let args = parse(&["test", "list", "--origins"]);
+        let action = args.into_action();
+        assert_eq!(
+            action,
+            ConfigAction::List {
+                scope: None,
+                origins: true,
+            }
+        );
+    }
+
+    #[test]
+    fn parse_get_with_origin() {
+        let args = parse(&["test", "get", "database.url", "--origin"]);
+        let action = args.into_action();
+        assert_eq!(
+            action,
+            ConfigAction::Get {
+                key: "database.url".into(),
+                scope: None,
+                origin: true,
+            }
+        );
     }
 
     // --- scope flag tests ---
@@ -501,6 +1077,19 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parse_profile_is_global_and_not_part_of_the_action() {
+        let args = parse(&["test", "list", "--profile", "prod"]);
+        assert_eq!(args.profile.as_deref(), Some("prod"));
+        assert_eq!(
+            args.into_action(),
+            ConfigAction::List {
+                scope: None,
+                origins: false,
+            }
+        );
+    }
+
     #[test]
     fn parse_list_with_scope() {
         let args = parse(&["test", "list", "--scope", "global"]);
@@ -509,6 +1098,7 @@ mod tests {
             action,
             ConfigAction::List {
                 scope: Some("global".into()),
+                origins: false,
             }
         );
     }
@@ -522,6 +1112,21 @@ mod tests {
             ConfigAction::Get {
                 key: "port".into(),
                 scope: Some("local".into()),
+                origin: false,
+            }
+        );
+    }
+
+    #[test]
+    fn parse_migrate_with_and_without_scope() {
+        assert_eq!(
+            parse(&["test", "migrate"]).into_action(),
+            ConfigAction::Migrate { scope: None }
+        );
+        assert_eq!(
+            parse(&["test", "migrate", "--scope", "global"]).into_action(),
+            ConfigAction::Migrate {
+                scope: Some("global".into()),
             }
         );
     }
@@ -547,6 +1152,20 @@ mod tests {
             action,
             ConfigAction::List {
                 scope: Some("global".into()),
+                origins: false,
+            }
+        );
+    }
+
+    #[test]
+    fn parse_repeated_overrides() {
+        let args = parse(&["test", "list", "-c", "port=1", "--set", "host=h"]);
+        assert_eq!(args.overrides, ["port=1", "host=h"]);
+        assert_eq!(
+            args.into_action(),
+            ConfigAction::List {
+                scope: None,
+                origins: false,
             }
         );
     }
@@ -572,7 +1191,13 @@ mod tests {
         let app = Command::new("test").subcommand(cmd.as_command("config"));
         let matches = app.try_get_matches_from(["test", "config"]).unwrap();
         let (_, sub) = matches.subcommand().unwrap();
-        assert_eq!(cmd.parse(sub).unwrap(), ConfigAction::List { scope: None });
+        assert_eq!(
+            cmd.parse(sub).unwrap(),
+            ConfigAction::List {
+                scope: None,
+                origins: false,
+            }
+        );
     }
 
     #[test]
@@ -580,7 +1205,10 @@ mod tests {
         let cmd = ConfigCommand::new();
         assert_eq!(
             cmd_parse(&cmd, &["test", "config", "list"]),
-            ConfigAction::List { scope: None }
+            ConfigAction::List {
+                scope: None,
+                origins: false,
+            }
         );
     }
 
@@ -652,6 +1280,7 @@ mod tests {
             ConfigAction::Get {
                 key: "database.url".into(),
                 scope: None,
+                origin: false,
             }
         );
     }
@@ -692,6 +1321,7 @@ mod tests {
             ConfigAction::Get {
                 key: "port".into(),
                 scope: Some("global".into()),
+                origin: false,
             }
         );
     }
@@ -706,6 +1336,7 @@ mod tests {
             ConfigAction::Get {
                 key: "database.url".into(),
                 scope: None,
+                origin: false,
             }
         );
     }
@@ -735,12 +1366,26 @@ mod tests {
         );
     }
 
+    #[test]
+    fn cmd_renamed_migrate() {
+        let cmd = ConfigCommand::new().migrate_name("upgrade");
+        assert_eq!(
+            cmd_parse(&cmd, &["test", "config", "upgrade", "--scope", "local"]),
+            ConfigAction::Migrate {
+                scope: Some("local".into()),
+            }
+        );
+    }
+
     #[test]
     fn cmd_renamed_list() {
         let cmd = ConfigCommand::new().list_name("show");
         assert_eq!(
             cmd_parse(&cmd, &["test", "config", "show"]),
-            ConfigAction::List { scope: None }
+            ConfigAction::List {
+                scope: None,
+                origins: false,
+            }
         );
     }
 
@@ -766,6 +1411,127 @@ mod tests {
             ConfigAction::Get {
                 key: "port".into(),
                 scope: Some("global".into()),
+                origin: false,
+            }
+        );
+    }
+
+    #[test]
+    fn cmd_override_flag_default_and_renamed() {
+        let cmd = ConfigCommand::new();
+        let matches = Command::new("test")
+            .subcommand(cmd.as_command("config"))
+            .try_get_matches_from([
+                "test", "config", "-c", "port=1", "--set", "a=b", "get", "port",
+            ])
+            .unwrap();
+        let (_, sub) = matches.subcommand().unwrap();
+        assert_eq!(cmd.parse_overrides(sub), ["port=1", "a=b"]);
+
+        let cmd = ConfigCommand::new()
+            .override_long("config")
+            .override_short(None);
+        let app = || Command::new("test").arg(cmd.override_arg());
+        let matches = app()
+            .try_get_matches_from(["test", "--config", "port=1"])
+            .unwrap();
+        assert_eq!(cmd.parse_overrides(&matches), ["port=1"]);
+        assert!(
+            app()
+                .try_get_matches_from(["test", "-c", "port=1"])
+                .is_err()
+        );
+        assert!(
+            cmd.parse_overrides(&app().get_matches_from(["test"]))
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn cmd_renamed_profile_flag() {
+        let cmd = ConfigCommand::new().profile_long("env");
+        let app = Command::new("test").subcommand(cmd.as_command("config"));
+        let matches = app
+            .try_get_matches_from(["test", "config", "get", "port", "--env", "prod"])
+            .unwrap();
+        let (_, sub) = matches.subcommand().unwrap();
+        assert_eq!(cmd.parse_profile(sub).as_deref(), Some("prod"));
+        assert_eq!(
+            cmd.parse(sub).unwrap(),
+            ConfigAction::Get {
+                key: "port".into(),
+                scope: None,
+                origin: false,
+            }
+        );
+    }
+
+    #[test]
+    fn cmd_default_origin_flags() {
+        let cmd = ConfigCommand::new();
+        assert_eq!(
+            cmd_parse(&cmd, &["test", "config", "list", "--origins"]),
+            ConfigAction::List {
+                scope: None,
+                origins: true,
+            }
+        );
+        assert_eq!(
+            cmd_parse(&cmd, &["test", "config", "get", "port", "--origin"]),
+            ConfigAction::Get {
+                key: "port".into(),
+                scope: None,
+                origin: true,
+            }
+        );
+    }
+
+    #[test]
+    fn cmd_renamed_explain() {
+        let cmd = ConfigCommand::new().explain_name("why");
+        assert_eq!(
+            cmd_parse(&cmd, &["test", "config", "why", "port"]),
+            ConfigAction::Explain { key: "port".into() }
+        );
+    }
+
+    #[test]
+    fn cmd_renamed_diff() {
+        let cmd = ConfigCommand::new().diff_name("compare");
+        assert_eq!(
+            cmd_parse(&cmd, &["test", "config", "compare", "a", "@local"]),
+            ConfigAction::Diff {
+                left: DiffTarget::Dir(PathBuf::from("a")),
+                right: DiffTarget::Scope("local".into()),
+            }
+        );
+        assert_eq!(
+            cmd_parse(&cmd, &["test", "config", "compare", "a"]),
+            ConfigAction::Diff {
+                left: DiffTarget::Dir(PathBuf::from("a")),
+                right: DiffTarget::Dir(PathBuf::from(".")),
+            }
+        );
+    }
+
+    #[test]
+    fn cmd_renamed_origin_flags() {
+        let cmd = ConfigCommand::new()
+            .origins_long("sources")
+            .origin_long("source");
+        assert_eq!(
+            cmd_parse(&cmd, &["test", "config", "list", "--sources"]),
+            ConfigAction::List {
+                scope: None,
+                origins: true,
+            }
+        );
+        assert_eq!(
+            cmd_parse(&cmd, &["test", "config", "get", "port", "--source"]),
+            ConfigAction::Get {
+                key: "port".into(),
+                scope: None,
+                origin: true,
             }
         );
     }
@@ -844,6 +1610,7 @@ mod tests {
             ConfigAction::Get {
                 key: "port".into(),
                 scope: None,
+                origin: false,
             }
         );
     }
@@ -893,4 +1660,202 @@ mod tests {
             }
         );
     }
+
+    fn flag_schema() -> Schema {
+        use crate::runtime::Field;
+        Schema::object("App")
+            .field(
+                "port",
+                Field::integer_in(Some(1), Some(65535)).doc("Port to listen on."),
+            )
+            .field("verbose", Field::boolean().default(false))
+            .field(
+                "level",
+                Field::enum_of(["debug", "info"])
+                    .doc("Log level.")
+                    .doc("Second line."),
+            )
+            .field("tags", Field::array_of_type(LeafType::String))
+            .nested(
+                "database",
+                Schema::object("Db")
+                    .field("url", Field::string().optional())
+                    .field("pool_size", Field::integer().default(5i64)),
+            )
+            .map_of(
+                "plugins",
+                Schema::object("Plugin").field("on", Field::boolean()),
+            )
+            .build()
+    }
+
+    fn flag_overrides(flags: &ConfigFlags, args: &[&str]) -> Vec<(String, Value)> {
+        let schema = flag_schema();
+        let matches = flags
+            .augment(Command::new("app"), &schema)
+            .try_get_matches_from(args)
+            .unwrap();
+        flags.overrides(&schema, &matches)
+    }
+
+    #[test]
+    fn flags_cover_every_scalar_leaf() {
+        let schema = flag_schema();
+        let args = ConfigFlags::new().args(&schema);
+        let longs: Vec<_> = args.iter().filter_map(|a| a.get_long()).collect();
+        assert_eq!(
+            longs,
+            [
+                "port",
+                "verbose",
+                "level",
+                "tags",
+                "database-url",
+                "database-pool-size"
+            ]
+        );
+        assert_eq!(args[0].get_id(), "port");
+        assert_eq!(
+            args[0].get_help().unwrap().to_string(),
+            "Port to listen on."
+        );
+        assert_eq!(
+            args[2].get_long_help().unwrap().to_string(),
+            "Log level.\nSecond line."
+        );
+        let levels: Vec<_> = args[2]
+            .get_possible_values()
+            .iter()
+            .map(|v| v.get_name().to_owned())
+            .collect();
+        assert_eq!(levels, ["debug", "info"]);
+    }
+
+    #[test]
+    fn given_flags_become_typed_overrides() {
+        let overrides = flag_overrides(
+            &ConfigFlags::new(),
+            &[
+                "app",
+                "--port",
+                "8080",
+                "--verbose",
+                "--level",
+                "info",
+                "--tags",
+                "a",
+                "--tags",
+                "b",
+                "--database-pool-size",
+                "9",
+            ],
+        );
+        assert_eq!(
+            overrides,
+            [
+                ("port".to_string(), Value::Integer(8080)),
+                ("verbose".to_string(), Value::Boolean(true)),
+                ("level".to_string(), Value::String("info".into())),
+                (
+                    "tags".to_string(),
+                    Value::Array(vec!["a".into(), "b".into()])
+                ),
+                ("database.pool_size".to_string(), Value::Integer(9)),
+            ]
+        );
+        assert!(flag_overrides(&ConfigFlags::new(), &["app"]).is_empty());
+    }
+
+    #[test]
+    fn bool_flags_take_an_optional_false() {
+        let verbose = |args: &[&str]| {
+            flag_overrides(&ConfigFlags::new(), args)
+                .into_iter()
+                .find(|(key, _)| key == "verbose")
+                .map(|(_, value)| value)
+        };
+        assert_eq!(verbose(&["app", "--verbose"]), Some(Value::Boolean(true)));
+        assert_eq!(
+            verbose(&["app", "--verbose=false"]),
+            Some(Value::Boolean(false))
+        );
+        assert_eq!(
+            verbose(&["app", "--verbose=true"]),
+            Some(Value::Boolean(true))
+        );
+        // The value must be attached, so a following word stays positional.
+        let schema = flag_schema();
+        assert!(
+            ConfigFlags::new()
+                .augment(Command::new("app"), &schema)
+                .try_get_matches_from(["app", "--verbose", "false"])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn flags_reject_values_the_leaf_cannot_hold() {
+        let schema = flag_schema();
+        let cmd = || ConfigFlags::new().augment(Command::new("app"), &schema);
+        assert!(cmd().try_get_matches_from(["app", "--port", "0"]).is_err());
+        assert!(
+            cmd()
+                .try_get_matches_from(["app", "--level", "loud"])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn include_exclude_and_rename_shape_the_flags() {
+        let flags = ConfigFlags::new()
+            .include("database")
+            .include("port")
+            .exclude("database.pool_size")
+            .rename("database.url", "db");
+        let longs: Vec<_> = flags
+            .args(&flag_schema())
+            .iter()
+            .filter_map(|a| a.get_long().map(str::to_owned))
+            .collect();
+        assert_eq!(longs, ["port", "db"]);
+        assert_eq!(
+            flag_overrides(&flags, &["app", "--db", "pg://x"]),
+            [("database.url".to_string(), Value::String("pg://x".into()))]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "ConfigFlags::exclude: `databse` names no flag-able key")]
+    fn unknown_flag_path_panics() {
+        ConfigFlags::new().exclude("databse").args(&flag_schema());
+    }
+
+    #[test]
+    fn colliding_flag_names_panic_naming_both_keys() {
+        use crate::runtime::Field;
+        let schema = Schema::object("App")
+            .field("database_url", Field::string().optional())
+            .nested(
+                "database",
+                Schema::object("Db").field("url", Field::string().optional()),
+            )
+            .build();
+        let panic = std::panic::catch_unwind(|| ConfigFlags::new().args(&schema))
+            .expect_err("colliding flags");
+        assert_eq!(
+            panic.downcast_ref::<String>().map(String::as_str),
+            Some(
+                "clapfig: ConfigFlags: `database_url` and `database.url` both get \
+                 `--database-url`; rename or exclude one"
+            )
+        );
+        // Renaming either one settles it.
+        let flags = ConfigFlags::new().rename("database_url", "db-url");
+        let names: Vec<_> = flags
+            .args(&schema)
+            .iter()
+            .map(|arg| arg.get_long().unwrap().to_owned())
+            .collect();
+        assert_eq!(names, ["db-url", "database-url"]);
+    }
 }
@@ -0,0 +1,236 @@
+//! Non-fatal diagnostics: things a load reports without failing.
+//!
+//! A configuration can resolve cleanly and still deserve a second look.
+//! The key it sets may be deprecated, an environment variable may quietly
+//! override a file, a file holding secrets may be readable by anyone, or
+//! a file may still use keys a migration renamed. Every load collects these as [`Warning`]s.
+//! [`load_with_diagnostics`](crate::Builder::load_with_diagnostics) and
+//! [`Resolver::resolve_at_with_diagnostics`](crate::Resolver::resolve_at_with_diagnostics)
+//! return them next to the config; the other load surfaces drop them.
+//!
+//! Like [`ClapfigError`](crate::ClapfigError), a `Warning` is data. Render it
+//! with [`render_plain`](crate::render::render_plain) or
+//! [`render_rich`](crate::render::render_rich), which draw a source
+//! snippet when the warning points into a file.
+//!
+//! [`deny_warnings`](crate::Builder::deny_warnings) turns chosen
+//! [`WarningKind`]s into errors — for CI, where "works, but…" should
+//! fail the build:
+//!
+//! ```ignore
+//! let cfg = Clapfig::typed::<AppConfig>()
+//!     .app_name("myapp")
+//!     .deny_warnings(WarningKind::ALL.iter().copied())
+//!     .load()?;
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::error::{OriginFacts, write_origin_location};
+use crate::format::FormatRegistry;
+
+/// The kind of a [`Warning`], for
+/// [`deny_warnings`](crate::Builder::deny_warnings) policies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[non_exhaustive]
+pub enum WarningKind {
+    /// [`Warning::DeprecatedKey`].
+    DeprecatedKey,
+    /// [`Warning::EnvShadowsFile`].
+    EnvShadowsFile,
+    /// [`Warning::WorldReadableSecrets`].
+    WorldReadableSecrets,
+    /// [`Warning::MixedFormats`].
+    MixedFormats,
+    /// [`Warning::UnmigratedFile`].
+    UnmigratedFile,
+}
+
+impl WarningKind {
+    /// Every kind, for a policy that denies them all.
+    pub const ALL: &'static [WarningKind] = &[
+        WarningKind::DeprecatedKey,
+        WarningKind::EnvShadowsFile,
+        WarningKind::WorldReadableSecrets,
+        WarningKind::MixedFormats,
+        WarningKind::UnmigratedFile,
+    ];
+}
+
+/// Something a successful load wants the user to know.
+///
+/// Marked `#[non_exhaustive]`: future kinds may be added without a
+/// breaking-change major bump.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Warning {
+    /// An input sets a field marked
+    /// [deprecated](crate::runtime::Leaf::deprecated). Raised once per
+    /// input that sets it.
+    DeprecatedKey {
+        /// Dotted key of the deprecated field.
+        key: String,
+        /// The field's deprecation note.
+        note: String,
+        /// The input that set it; a file origin carries the span.
+        origin: Box<OriginFacts>,
+    },
+    /// An environment variable overrides a scalar a config file also
+    /// sets, so editing the file has no effect. Raised only when the
+    /// environment layer merges above the files layer.
+    EnvShadowsFile {
+        /// Dotted key both inputs set.
+        key: String,
+        /// The variable whose value wins.
+        env_var: String,
+        /// The file value it hides, with its span.
+        file: Box<OriginFacts>,
+    },
+    /// A config file sets a [sensitive](crate::runtime::Leaf::sensitive)
+    /// value but every user on the machine may read it. Unix only.
+    WorldReadableSecrets {
+        /// The file.
+        path: PathBuf,
+        /// Its permission bits (`0o644`).
+        mode: u32,
+    },
+    /// Discovery loaded config files of more than one format for the
+    /// same name across directories (`~/.config/myapp/myapp.toml` and
+    /// `./myapp.yaml`), which usually means one is a stale leftover.
+    MixedFormats {
+        /// The files, in precedence order.
+        files: Vec<PathBuf>,
+    },
+    /// A config file still uses keys a [migration](crate::Migration)
+    /// renamed, moved, or removed. Loading migrated it in memory;
+    /// `config migrate` rewrites it for good. Raised once per file.
+    UnmigratedFile {
+        /// The file.
+        path: PathBuf,
+        /// The migrations it needed, in the order they applied
+        /// (``renamed `db` to `database` ``).
+        migrations: Vec<String>,
+    },
+}
+
+impl Warning {
+    /// This warning's kind.
+    pub fn kind(&self) -> WarningKind {
+        match self {
+            Warning::DeprecatedKey { .. } => WarningKind::DeprecatedKey,
+            Warning::EnvShadowsFile { .. } => WarningKind::EnvShadowsFile,
+            Warning::WorldReadableSecrets { .. } => WarningKind::WorldReadableSecrets,
+            Warning::MixedFormats { .. } => WarningKind::MixedFormats,
+            Warning::UnmigratedFile { .. } => WarningKind::UnmigratedFile,
+        }
+    }
+
+    /// The input this warning points at, when it points at one value.
+    /// Renderers draw a snippet from a file origin's span.
+    pub fn origin(&self) -> Option<&OriginFacts> {
+        match self {
+            Warning::DeprecatedKey { origin, .. } => Some(origin),
+            Warning::EnvShadowsFile { file, .. } => Some(file),
+            Warning::WorldReadableSecrets { .. }
+            | Warning::MixedFormats { .. }
+            | Warning::UnmigratedFile { .. } => None,
+        }
+    }
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let out = match self {
+            Warning::DeprecatedKey { key, note, origin } => {
+                let mut out = format!("Deprecated key '{key}': {note}");
+                write_origin_location(&mut out, key, origin);
+                out
+            }
+            Warning::EnvShadowsFile { key, env_var, file } => {
+                let mut out =
+                    format!("Environment variable {env_var} overrides '{key}' from a config file");
+                write_origin_location(&mut out, key, file);
+                out
+            }
+            Warning::WorldReadableSecrets { path, mode } => format!(
+                "{} holds secret values but is readable by every user (mode {:o}) — restrict it with `chmod o-r`",
+                path.display(),
+                mode & 0o7777,
+            ),
+            Warning::MixedFormats { files } => format!(
+                "Config files in more than one format: {} — keep one format",
+                files
+                    .iter()
+                    .map(|p| p.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            ),
+            Warning::UnmigratedFile { path, migrations } => format!(
+                "{} needs migration ({}) — rewrite it with `config migrate`",
+                path.display(),
+                migrations.join(", "),
+            ),
+        };
+        f.write_str(&out)
+    }
+}
+
+/// Reads a file's permission bits; `None` where there are none to read.
+pub(crate) type FileMode = fn(&Path) -> Option<u32>;
+
+/// The production [`FileMode`]: the Unix mode, or `None` elsewhere.
+pub(crate) fn file_mode_from_disk(path: &Path) -> Option<u32> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::metadata(path).ok().map(|m| m.permissions().mode())
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = path;
+        None
+    }
+}
+
+/// [`Warning::WorldReadableSecrets`] for a file holding secrets, when its
+/// mode lets other users read it.
+pub(crate) fn world_readable(path: &Path, file_mode: FileMode) -> Option<Warning> {
+    let mode = file_mode(path)?;
+    (mode & 0o004 != 0).then(|| Warning::WorldReadableSecrets {
+        path: path.to_path_buf(),
+        mode,
+    })
+}
+
+/// [`Warning::MixedFormats`] for each file name loaded in more than one
+/// format. Files are grouped by stem and compared by the adapter that
+/// parses them, so `.yaml` and `.yml` are one format.
+pub(crate) fn mixed_formats(
+    files: &[(PathBuf, String)],
+    registry: &FormatRegistry,
+) -> Vec<Warning> {
+    let mut by_stem: BTreeMap<&std::ffi::OsStr, Vec<&Path>> = BTreeMap::new();
+    for (path, _) in files {
+        if let Some(stem) = path.file_stem() {
+            by_stem.entry(stem).or_default().push(path);
+        }
+    }
+    by_stem
+        .into_values()
+        .filter(|paths| {
+            let mut formats: Vec<&str> = paths
+                .iter()
+                .filter_map(|path| crate::resolve::file_adapter(registry, path).ok())
+                .map(|adapter| adapter.name())
+                .collect();
+            formats.sort_unstable();
+            formats.dedup();
+            formats.len() > 1
+        })
+        .map(|paths| Warning::MixedFormats {
+            files: paths.into_iter().map(Path::to_path_buf).collect(),
+        })
+        .collect()
+}
@@ -0,0 +1,330 @@
+//! Differences between two resolved configurations.
+//!
+//! [`ConfigDiff::between`] compares two value [`Map`]s — two directories'
+//! resolutions, two scope files, or one resolution before and after an
+//! environment change — and reports every leaf that was added, removed,
+//! or changed, keyed by its structured [`ConfigPath`]. The walk follows
+//! the schema [`Shape`]: objects and maps descend key by key, while
+//! arrays and `Value` escape-hatch leaves compare whole, so a reordered
+//! list is one change rather than one per element. A tagged union
+//! descends into its variant only while both sides share a
+//! discriminator; below the document root, a variant switch is reported
+//! as one change of the whole union.
+//!
+//! [`ConfigDiff::with_origins`] attaches each side's [`Provenance`] so a
+//! changed leaf names where both values came from. `config diff` renders
+//! the result through the active format's
+//! [`display_entry`](crate::format::FormatAdapter::display_entry).
+
+use crate::error::OriginFacts;
+use crate::format::ConfigPath;
+use crate::provenance::Provenance;
+use crate::runtime::Shape;
+use crate::value::{Map, Value};
+
+/// How one leaf differs between the left and right configuration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffKind {
+    /// Present only on the right.
+    Added,
+    /// Present only on the left.
+    Removed,
+    /// Present on both sides with different values.
+    Changed,
+}
+
+/// One differing leaf.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiffEntry {
+    /// Structured path of the leaf.
+    pub path: ConfigPath,
+    /// The same path as a dotted key (`"database.pool_size"`).
+    pub key: String,
+    pub kind: DiffKind,
+    /// The left value; `None` for [`DiffKind::Added`].
+    pub left: Option<Value>,
+    /// The right value; `None` for [`DiffKind::Removed`].
+    pub right: Option<Value>,
+    /// Where the left value came from, when origins were attached.
+    pub left_origin: Option<OriginFacts>,
+    /// Where the right value came from, when origins were attached.
+    pub right_origin: Option<OriginFacts>,
+}
+
+/// Every leaf that differs between two configurations, in key order.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConfigDiff {
+    pub entries: Vec<DiffEntry>,
+}
+
+impl ConfigDiff {
+    /// Compare `left` against `right`, walking `shape` (the document root
+    /// both were resolved against). Keys the schema does not declare are
+    /// still compared, value by value.
+    pub fn between(shape: &Shape, left: &Map, right: &Map) -> Self {
+        let mut entries = Vec::new();
+        walk_maps(
+            Some(shape),
+            &ConfigPath::new(),
+            "",
+            Some(left),
+            Some(right),
+            &mut entries,
+        );
+        Self { entries }
+    }
+
+    /// Attach where each side's value came from. `left` and `right` are
+    /// the [`Provenance`] of the maps this diff was computed from.
+    pub fn with_origins(mut self, left: &Provenance, right: &Provenance) -> Self {
+        for entry in &mut self.entries {
+            if entry.left.is_some() {
+                entry.left_origin = left.get(&entry.path);
+            }
+            if entry.right.is_some() {
+                entry.right_origin = right.get(&entry.path);
+            }
+        }
+        self
+    }
+
+    /// Whether the two configurations are identical.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// Compare two (possibly absent) maps key by key. `shape` is the shape
+/// the maps were resolved against, `None` off-schema.
+fn walk_maps(
+    shape: Option<&Shape>,
+    path: &ConfigPath,
+    prefix: &str,
+    left: Option<&Map>,
+    right: Option<&Map>,
+    out: &mut Vec<DiffEntry>,
+) {
+    let mut keys: Vec<&String> = left.into_iter().flat_map(Map::keys).collect();
+    keys.extend(right.into_iter().flat_map(Map::keys));
+    keys.sort();
+    keys.dedup();
+    for key in keys {
+        let child = shape.and_then(|shape| child_shape(shape, key, left, right));
+        let dotted = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{prefix}.{key}")
+        };
+        walk_value(
+            child,
+            path.clone().key(key),
+            dotted,
+            left.and_then(|map| map.get(key)),
+            right.and_then(|map| map.get(key)),
+            out,
+        );
+    }
+}
+
+fn walk_value(
+    shape: Option<&Shape>,
+    path: ConfigPath,
+    key: String,
+    left: Option<&Value>,
+    right: Option<&Value>,
+    out: &mut Vec<DiffEntry>,
+) {
+    if left == right {
+        return;
+    }
+    let maps = match (left, right) {
+        (Some(Value::Map(l)), Some(Value::Map(r))) => Some((Some(l), Some(r))),
+        (Some(Value::Map(l)), None) => Some((Some(l), None)),
+        (None, Some(Value::Map(r))) => Some((None, Some(r))),
+        _ => None,
+    };
+    if let Some((l, r)) = maps.filter(|(l, r)| descends(shape, *l, *r)) {
+        walk_maps(shape, &path, &key, l, r, out);
+        return;
+    }
+    let kind = match (left, right) {
+        (None, _) => DiffKind::Added,
+        (_, None) => DiffKind::Removed,
+        _ => DiffKind::Changed,
+    };
+    out.push(DiffEntry {
+        path,
+        key,
+        kind,
+        left: left.cloned(),
+        right: right.cloned(),
+        left_origin: None,
+        right_origin: None,
+    });
+}
+
+/// Whether a map-valued node is compared key by key (objects, maps,
+/// off-schema sections, a tagged union keeping its variant) or whole
+/// (leaves such as the `Value` escape hatch, a tagged union switching
+/// variants).
+fn descends(shape: Option<&Shape>, left: Option<&Map>, right: Option<&Map>) -> bool {
+    match shape {
+        None | Some(Shape::Object(_)) | Some(Shape::Map(_)) => true,
+        Some(Shape::Tagged(tagged)) => match (left, right) {
+            (Some(l), Some(r)) => l.get(&tagged.tag) == r.get(&tagged.tag),
+            _ => true,
+        },
+        Some(Shape::Leaf(_)) | Some(Shape::Array(_)) => false,
+    }
+}
+
+/// The shape of `key` under `shape`, if declared. A tagged union's
+/// variant is chosen by the discriminator of whichever side has one.
+fn child_shape<'a>(
+    shape: &'a Shape,
+    key: &str,
+    left: Option<&Map>,
+    right: Option<&Map>,
+) -> Option<&'a Shape> {
+    let fields = match shape {
+        Shape::Object(schema) => &schema.fields,
+        Shape::Map(map) => return Some(&map.item),
+        Shape::Tagged(tagged) => {
+            let tag = left
+                .or(right)
+                .and_then(|map| map.get(&tagged.tag))
+                .and_then(Value::as_str)?;
+            &tagged.variant(tag)?.schema.fields
+        }
+        Shape::Leaf(_) | Shape::Array(_) => return None,
+    };
+    fields
+        .iter()
+        .find(|field| field.name == key)
+        .map(|field| &field.field)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::{Field, Schema};
+
+    fn shape() -> Shape {
+        Schema::object("App")
+            .field("host", Field::string().default("localhost"))
+            .field("port", Field::integer().default(8080i64))
+            .field("tags", Field::array_of_type(Field::string()))
+            .nested(
+                "db",
+                Schema::object("Db")
+                    .field("url", Field::string().optional())
+                    .field("pool_size", Field::integer().default(5i64)),
+            )
+            .build()
+            .into()
+    }
+
+    fn map(value: Value) -> Map {
+        match value {
+            Value::Map(map) => map,
+            other => panic!("not a map: {other:?}"),
+        }
+    }
+
+    fn toml(src: &str) -> Map {
+        let parsed = crate::format::builtin_adapter("toml")
+            .unwrap()
+            .parse(src)
+            .unwrap();
+        map(parsed.value)
+    }
+
+    #[test]
+    fn identical_configs_have_no_entries() {
+        let a = toml("port = 1\n[db]\npool_size = 2\n");
+        assert!(ConfigDiff::between(&shape(), &a, &a.clone()).is_empty());
+    }
+
+    #[test]
+    fn reports_added_removed_and_changed_leaves_in_key_order() {
+        let left = toml("host = \"a\"\nport = 1\n[db]\npool_size = 2\n");
+        let right = toml("port = 2\n[db]\npool_size = 2\nurl = \"pg://\"\n");
+        let diff = ConfigDiff::between(&shape(), &left, &right);
+        let summary: Vec<(&str, DiffKind)> = diff
+            .entries
+            .iter()
+            .map(|entry| (entry.key.as_str(), entry.kind))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("db.url", DiffKind::Added),
+                ("host", DiffKind::Removed),
+                ("port", DiffKind::Changed),
+            ]
+        );
+        let port = &diff.entries[2];
+        assert_eq!(port.path, ConfigPath::new().key("port"));
+        assert_eq!(port.left, Some(Value::Integer(1)));
+        assert_eq!(port.right, Some(Value::Integer(2)));
+    }
+
+    #[test]
+    fn a_section_present_on_one_side_reports_its_leaves() {
+        let left = toml("port = 1\n");
+        let right = toml("port = 1\n[db]\npool_size = 9\n");
+        let diff = ConfigDiff::between(&shape(), &left, &right);
+        assert_eq!(diff.entries.len(), 1);
+        assert_eq!(diff.entries[0].key, "db.pool_size");
+        assert_eq!(diff.entries[0].kind, DiffKind::Added);
+    }
+
+    #[test]
+    fn arrays_compare_whole() {
+        let left = toml("tags = [\"a\", \"b\"]\n");
+        let right = toml("tags = [\"b\", \"a\"]\n");
+        let diff = ConfigDiff::between(&shape(), &left, &right);
+        assert_eq!(diff.entries.len(), 1);
+        assert_eq!(diff.entries[0].key, "tags");
+        assert_eq!(diff.entries[0].kind, DiffKind::Changed);
+    }
+
+    #[test]
+    fn a_tagged_union_switching_variants_is_one_change() {
+        let shape = Shape::from(
+            Shape::tagged("Store", "kind")
+                .variant(
+                    "disk",
+                    Schema::object("Disk")
+                        .field("path", Field::string())
+                        .build(),
+                )
+                .variant(
+                    "s3",
+                    Schema::object("S3")
+                        .field("bucket", Field::string())
+                        .build(),
+                )
+                .build(),
+        );
+        let disk = toml("kind = \"disk\"\npath = \"/a\"\n");
+        let moved = toml("kind = \"disk\"\npath = \"/b\"\n");
+        let s3 = toml("kind = \"s3\"\nbucket = \"b\"\n");
+
+        let same_variant = ConfigDiff::between(&shape, &disk, &moved);
+        assert_eq!(same_variant.entries.len(), 1);
+        assert_eq!(same_variant.entries[0].key, "path");
+
+        // At the root there is no enclosing key, so a switch reports
+        // every leaf; nested under a key it is one whole-union change.
+        let nested: Shape = Schema::object("App")
+            .field("store", shape.clone())
+            .build()
+            .into();
+        let wrap = |m: Map| Map::from([("store".to_string(), Value::Map(m))]);
+        let switched = ConfigDiff::between(&nested, &wrap(disk), &wrap(s3));
+        assert_eq!(switched.entries.len(), 1);
+        assert_eq!(switched.entries[0].key, "store");
+        assert_eq!(switched.entries[0].kind, DiffKind::Changed);
+    }
+}
@@ -2,8 +2,11 @@
 //! into config.
 //!
 //! Env vars matching `{PREFIX}__*` are collected, with `__` as the nesting separator
-//! and segments lowercased to match Rust field names. Values are parsed heuristically
-//! (bool > integer > float > string). Takes an iterator for testability.
+//! and segments lowercased to match Rust field names. Values stay raw strings
+//! here; resolve types them by the schema
+//! ([`type_raw_scalars_root`](crate::schema_walk::type_raw_scalars_root)),
+//! falling back to [`parse_env_value`] only where the schema declares no
+//! leaf type. Takes an iterator for testability.
 //!
 //! Each inserted path also records the original environment variable
 //! name that produced it. Case-sensitive platforms accept
@@ -15,6 +18,19 @@
 //! insert order) and every source name is retained so the error can
 //! list them all.
 //!
+//! Arrays come from the schema, not the variable name: once the table is
+//! built, [`build_env_containers`] turns a list value
+//! (`MYAPP__HOSTS=a,b,c`, or an inline `[...]` literal) on a declared
+//! array into an array, an inline `{...}` literal on a declared map into
+//! a map, and an all-index section (`MYAPP__SERVERS__0__HOST`) on a
+//! declared array into an array of tables — renaming the recorded paths
+//! so each element keeps the variable that set it. The indices only
+//! order the elements; gaps close up rather than fail.
+//!
+//! With [`env_file_secrets`](crate::Builder::env_file_secrets) on,
+//! [`read_env_file_secrets`] then swaps each Docker-style `…_FILE`
+//! variable for the contents of the file it names.
+//!
 //! Paths are [`ConfigPath`] identities, not flattened dotted strings:
 //! `APP__PLUGINS__A.CONFIG__X` (`plugins."a.config".x`) and
 //! `APP__PLUGINS__A__CONFIG__X` (`plugins.a.config.x`) coexist.
@@ -23,10 +39,19 @@
 //! on [`EnvSources`] must not leak losing writers into `InvalidValue`.
 
 use std::collections::BTreeMap;
+use std::path::PathBuf;
 
-use crate::format::ConfigPath;
+use crate::error::ClapfigError;
+use crate::file_ref::{ReadFile, read_reference, reference_path};
+use crate::format::{ConfigPath, FormatAdapter, PathSegment};
+use crate::origin::Origin;
+use crate::runtime::{DocumentRoot, Schema, Shape, TaggedShape};
 use crate::value::{Map, Value};
 
+/// Default separator between the items of a list value
+/// (`MYAPP__HOSTS=a,b,c`).
+pub(crate) const DEFAULT_LIST_SEPARATOR: &str = ",";
+
 /// Structured table path → the original environment variable name(s)
 /// that produced it, in first-seen order. Several names can collapse
 /// onto one path (`MYAPP__HOST` and `MYAPP__host` both become `host`);
@@ -48,7 +73,8 @@ pub(crate) type EnvWinners = BTreeMap<ConfigPath, String>;
 /// Single `_` within a segment is literal (part of the field name).
 /// Segments are lowercased to match Rust field names.
 ///
-/// Values are parsed heuristically: bool > integer > float > string.
+/// Values are inserted as raw strings; typing them is the schema's job
+/// (see the module docs).
 ///
 /// When the environment sets the same key both flat and nested
 /// (`MYAPP__DATABASE=x` AND `MYAPP__DATABASE__URL=y`), the shapes
@@ -86,7 +112,7 @@ pub(crate) fn env_to_table_with_sources(
             &mut sources,
             &mut winners,
             &segments,
-            parse_env_value(&value),
+            Value::String(value),
             ConfigPath::new(),
             &key,
         );
@@ -128,6 +154,311 @@ pub(crate) fn env_source_vars(sources: &EnvSources, path: &ConfigPath) -> Vec<St
     names
 }
 
+/// Build the arrays and maps the schema declares out of an env table.
+///
+/// - A string on an array of leaves is a list: split on `separator`,
+///   each item trimmed (an empty value is an empty list). The items stay
+///   strings for [`type_raw_scalars_root`](crate::schema_walk::type_raw_scalars_root).
+/// - A string on any array that starts with `[`, or on a map that starts
+///   with `{`, is an inline literal in TOML or JSON syntax
+///   (`[{host = "a"}]`, `{"a": 1}`).
+/// - A section on an array whose keys are all indices
+///   (`MYAPP__SERVERS__0__HOST`) becomes an array in index order; gaps
+///   close up, so `__0` and `__5` make a two-element array.
+///
+/// `sources` and `winners` are renamed along (`servers."0".host` →
+/// `servers[0].host`) so unknown-key errors and origins still name the
+/// variable behind each element. Values that fit none of these, and the
+/// unset marker, stay as they are, for the type check to report or the
+/// merge to apply. A tagged section is walked only when the env table
+/// carries its discriminator.
+pub(crate) fn build_env_containers(
+    table: &mut Map,
+    sources: &mut EnvSources,
+    winners: &mut EnvWinners,
+    root: DocumentRoot<'_>,
+    separator: &str,
+    unset: Option<&str>,
+) {
+    let mut renames = Vec::new();
+    let mut walk = ContainerWalk {
+        separator,
+        unset,
+        renames: &mut renames,
+    };
+    match root {
+        DocumentRoot::Object(schema) => walk.table(table, schema, &ConfigPath::new()),
+        DocumentRoot::Map(map) => {
+            for (key, value) in table.iter_mut() {
+                walk.value(value, &map.item, &ConfigPath::new().key(key));
+            }
+        }
+        DocumentRoot::Tagged(tagged) => walk.tagged(table, tagged, &ConfigPath::new()),
+    }
+    // Renames are recorded outermost first, each in the coordinates the
+    // previous ones produce, so applying them in order is enough.
+    for (from, to) in &renames {
+        rename_prefix(sources, from, to);
+        rename_prefix(winners, from, to);
+    }
+}
+
+struct ContainerWalk<'a> {
+    separator: &'a str,
+    /// The unset marker, which resets an array rather than becoming a
+    /// one-item list.
+    unset: Option<&'a str>,
+    renames: &'a mut Vec<(ConfigPath, ConfigPath)>,
+}
+
+impl ContainerWalk<'_> {
+    fn table(&mut self, table: &mut Map, schema: &Schema, path: &ConfigPath) {
+        for (key, value) in table.iter_mut() {
+            if let Some(nf) = schema.fields.iter().find(|nf| nf.name == *key) {
+                self.value(value, &nf.field, &path.clone().key(key));
+            }
+        }
+    }
+
+    fn tagged(&mut self, table: &mut Map, tagged: &TaggedShape, path: &ConfigPath) {
+        if let Some(variant) = tagged.selected(table) {
+            self.table(table, &variant.schema, path);
+        }
+    }
+
+    fn value(&mut self, value: &mut Value, shape: &Shape, path: &ConfigPath) {
+        match (shape, &mut *value) {
+            (Shape::Array(array), Value::String(raw)) if Some(raw.as_str()) != self.unset => {
+                if let Some(items) = parse_list(raw, &array.item, self.separator) {
+                    *value = Value::Array(items);
+                }
+            }
+            (Shape::Array(array), Value::Map(entries)) => {
+                let Some(mut indexed) = entries
+                    .keys()
+                    .map(|key| key.parse::<usize>().ok().map(|i| (i, key.clone())))
+                    .collect::<Option<Vec<_>>>()
+                else {
+                    return;
+                };
+                indexed.sort();
+                let mut items = Vec::with_capacity(indexed.len());
+                for (position, (_, key)) in indexed.into_iter().enumerate() {
+                    let mut item = entries.remove(&key).expect("key collected above");
+                    let element = path.clone().index(position);
+                    self.renames.push((path.clone().key(key), element.clone()));
+                    self.value(&mut item, &array.item, &element);
+                    items.push(item);
+                }
+                *value = Value::Array(items);
+            }
+            (Shape::Map(_), Value::String(raw)) if raw.trim_start().starts_with('{') => {
+                if let Some(parsed @ Value::Map(_)) = parse_inline(raw) {
+                    *value = parsed;
+                }
+            }
+            (Shape::Map(map), Value::Map(entries)) => {
+                for (key, entry) in entries.iter_mut() {
+                    self.value(entry, &map.item, &path.clone().key(key));
+                }
+            }
+            (Shape::Object(schema), Value::Map(table)) => self.table(table, schema, path),
+            (Shape::Tagged(tagged), Value::Map(table)) => self.tagged(table, tagged, path),
+            _ => {}
+        }
+    }
+}
+
+/// Replace Docker-style `_FILE` variables with the contents of the file
+/// they name: `MYAPP__DB__PASSWORD_FILE=/run/secrets/db` sets
+/// `db.password`. Only a `…_file` key the schema does not declare, beside
+/// a declared leaf of the stem's name, is read; the contents stay a raw
+/// string for the leaf type to parse, with one trailing newline trimmed.
+/// Setting both the variable and its `_FILE` form is an error, as is a
+/// file that cannot be read.
+///
+/// `sources` and `winners` move to the target path, so the origin names
+/// the `_FILE` variable. Returns the file read for each target path, for
+/// the caller to record on the origin.
+pub(crate) fn read_env_file_secrets(
+    table: &mut Map,
+    sources: &mut EnvSources,
+    winners: &mut EnvWinners,
+    root: DocumentRoot<'_>,
+    read: ReadFile,
+) -> Result<BTreeMap<ConfigPath, PathBuf>, ClapfigError> {
+    let mut walk = FileSecretWalk {
+        sources,
+        winners,
+        read,
+        files: BTreeMap::new(),
+    };
+    match root {
+        DocumentRoot::Object(schema) => walk.table(table, schema, &ConfigPath::new())?,
+        DocumentRoot::Map(map) => {
+            for (key, value) in table.iter_mut() {
+                walk.value(value, &map.item, &ConfigPath::new().key(key))?;
+            }
+        }
+        DocumentRoot::Tagged(tagged) => {
+            if let Some(variant) = tagged.selected(table) {
+                walk.table(table, &variant.schema, &ConfigPath::new())?;
+            }
+        }
+    }
+    Ok(walk.files)
+}
+
+struct FileSecretWalk<'a> {
+    sources: &'a mut EnvSources,
+    winners: &'a mut EnvWinners,
+    read: ReadFile,
+    files: BTreeMap<ConfigPath, PathBuf>,
+}
+
+impl FileSecretWalk<'_> {
+    fn table(
+        &mut self,
+        table: &mut Map,
+        schema: &Schema,
+        path: &ConfigPath,
+    ) -> Result<(), ClapfigError> {
+        let declared = |name: &str| schema.fields.iter().find(|nf| nf.name == name);
+        let references: Vec<(String, String)> = table
+            .keys()
+            .filter(|key| declared(key).is_none())
+            .filter_map(|key| {
+                let stem = key.strip_suffix("_file")?;
+                matches!(declared(stem)?.field, Shape::Leaf(_))
+                    .then(|| (key.clone(), stem.to_owned()))
+            })
+            .collect();
+        for (key, stem) in references {
+            let Some(Value::String(raw)) = table.get(&key).cloned() else {
+                continue;
+            };
+            let from = path.clone().key(&key);
+            let to = path.clone().key(&stem);
+            let var = self.winners.get(&from).cloned().unwrap_or_default();
+            if table.contains_key(&stem) {
+                let other = self.winners.get(&to).cloned().unwrap_or_default();
+                return Err(ClapfigError::InvalidValue {
+                    key: to.to_string(),
+                    reason: format!("set by both {other} and {var}"),
+                    origin: Box::new(Origin::env(vec![var]).to_facts()),
+                });
+            }
+            let file = reference_path(&raw, None);
+            let contents = read_reference(self.read, &file).map_err(|reason| {
+                let mut origin = Origin::env(vec![var.clone()]);
+                origin.secret_file = Some(file.clone());
+                ClapfigError::InvalidValue {
+                    key: to.to_string(),
+                    reason,
+                    origin: Box::new(origin.to_facts()),
+                }
+            })?;
+            table.remove(&key);
+            table.insert(stem, Value::String(contents));
+            rename_prefix(self.sources, &from, &to);
+            rename_prefix(self.winners, &from, &to);
+            self.files.insert(to, file);
+        }
+        for (key, value) in table.iter_mut() {
+            if let Some(nf) = declared(key) {
+                self.value(value, &nf.field, &path.clone().key(key))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn value(
+        &mut self,
+        value: &mut Value,
+        shape: &Shape,
+        path: &ConfigPath,
+    ) -> Result<(), ClapfigError> {
+        match (shape, value) {
+            (Shape::Object(schema), Value::Map(table)) => self.table(table, schema, path),
+            (Shape::Tagged(tagged), Value::Map(table)) => match tagged.selected(table) {
+                Some(variant) => self.table(table, &variant.schema, path),
+                None => Ok(()),
+            },
+            (Shape::Map(map), Value::Map(entries)) => {
+                for (key, entry) in entries.iter_mut() {
+                    self.value(entry, &map.item, &path.clone().key(key))?;
+                }
+                Ok(())
+            }
+            (Shape::Array(array), Value::Array(items)) => {
+                for (i, item) in items.iter_mut().enumerate() {
+                    self.value(item, &array.item, &path.clone().index(i))?;
+                }
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// A list value for an array whose items are `item`: an inline literal,
+/// or — for leaf items — `separator`-split raw strings. `None` leaves the
+/// value for the type check to reject.
+fn parse_list(raw: &str, item: &Shape, separator: &str) -> Option<Vec<Value>> {
+    if raw.trim_start().starts_with('[') {
+        return match parse_inline(raw) {
+            Some(Value::Array(items)) => Some(items),
+            _ => None,
+        };
+    }
+    if !matches!(item, Shape::Leaf(_)) {
+        return None;
+    }
+    if raw.trim().is_empty() {
+        return Some(Vec::new());
+    }
+    Some(
+        raw.split(separator)
+            .map(|part| Value::String(part.trim().to_owned()))
+            .collect(),
+    )
+}
+
+/// An inline array or table literal: TOML inline syntax first (the value
+/// model's baseline vocabulary), then JSON.
+fn parse_inline(raw: &str) -> Option<Value> {
+    let doc = format!("v = {raw}");
+    if let Ok(parsed) = crate::format::TomlAdapter.parse(&doc)
+        && let Value::Map(mut map) = parsed.value
+        && map.len() == 1
+    {
+        return map.remove("v");
+    }
+    crate::format::JsonAdapter
+        .parse(raw)
+        .ok()
+        .map(|parsed| parsed.value)
+}
+
+/// Move every entry at or under `from` to the same place under `to`.
+pub(crate) fn rename_prefix<V>(
+    map: &mut BTreeMap<ConfigPath, V>,
+    from: &ConfigPath,
+    to: &ConfigPath,
+) {
+    let moved: Vec<ConfigPath> = map
+        .keys()
+        .filter(|key| key.segments().starts_with(from.segments()))
+        .cloned()
+        .collect();
+    for key in moved {
+        let value = map.remove(&key).expect("key collected above");
+        let rest = &key.segments()[from.segments().len()..];
+        let renamed: Vec<PathSegment> = to.segments().iter().chain(rest).cloned().collect();
+        map.insert(ConfigPath::from(renamed), value);
+    }
+}
+
 fn insert_nested(
     table: &mut Map,
     sources: &mut EnvSources,
@@ -192,6 +523,9 @@ fn is_descendant(key: &ConfigPath, path: &ConfigPath) -> bool {
 
 /// Parse a string value into a typed config value.
 /// Tries: bool → integer → float → string.
+///
+/// The fallback for raw strings with no declared leaf type to parse
+/// toward: `Value` leaves and undeclared paths.
 pub(crate) fn parse_env_value(s: &str) -> Value {
     if s.eq_ignore_ascii_case("true") {
         return Value::Boolean(true);
@@ -223,8 +557,16 @@ mod tests {
             .collect()
     }
 
+    /// Against an empty schema every path is undeclared, so values take
+    /// the [`parse_env_value`] heuristic.
     fn env_to_table(prefix: &str, vars: impl IntoIterator<Item = (String, String)>) -> Map {
-        env_to_table_with_sources(prefix, vars).0
+        let mut table = env_to_table_with_sources(prefix, vars).0;
+        let empty = crate::runtime::Schema::object("Empty").build();
+        crate::schema_walk::type_raw_scalars_root(
+            &mut table,
+            crate::runtime::DocumentRoot::Object(&empty),
+        );
+        table
     }
 
     fn env_winner(winners: &EnvWinners, path: ConfigPath) -> Option<&str> {
@@ -365,7 +707,7 @@ mod tests {
             "MYAPP",
             vars(&[("MYAPP__rogue_key", "1"), ("MYAPP__Database__Rogue", "x")]),
         );
-        assert_eq!(table["rogue_key"].as_integer().unwrap(), 1);
+        assert_eq!(table["rogue_key"].as_str().unwrap(), "1");
         assert_eq!(
             sources.get(&path(&["rogue_key"])),
             Some(&vec!["MYAPP__rogue_key".to_string()])
@@ -511,4 +853,120 @@ mod tests {
             Some("APP__PLUGINS__A.CONFIG__X")
         );
     }
+
+    fn container_schema() -> Schema {
+        use crate::runtime::Field;
+        Schema::object("App")
+            .field("hosts", Field::array_of_type(Field::string()))
+            .field("ports", Field::array_of_type(Field::integer()))
+            .field("labels", Field::map_of(Field::string()))
+            .array_of(
+                "servers",
+                Schema::object("Server")
+                    .field("host", Field::string())
+                    .field("port", Field::integer()),
+            )
+            .build()
+    }
+
+    fn containers(pairs: &[(&str, &str)], separator: &str) -> (Map, EnvSources, EnvWinners) {
+        let schema = container_schema();
+        let (mut table, mut sources, mut winners) = env_to_table_with_sources("APP", vars(pairs));
+        build_env_containers(
+            &mut table,
+            &mut sources,
+            &mut winners,
+            DocumentRoot::Object(&schema),
+            separator,
+            None,
+        );
+        (table, sources, winners)
+    }
+
+    fn strings(items: &[&str]) -> Value {
+        Value::Array(items.iter().map(|s| Value::String(s.to_string())).collect())
+    }
+
+    #[test]
+    fn list_values_split_on_the_separator() {
+        let (table, ..) = containers(
+            &[("APP__HOSTS", "a, b ,c"), ("APP__PORTS", "")],
+            DEFAULT_LIST_SEPARATOR,
+        );
+        assert_eq!(table["hosts"], strings(&["a", "b", "c"]));
+        assert_eq!(table["ports"], Value::Array(Vec::new()));
+
+        let (table, ..) = containers(&[("APP__HOSTS", "a,b;c")], ";");
+        assert_eq!(table["hosts"], strings(&["a,b", "c"]));
+    }
+
+    #[test]
+    fn inline_literals_parse_as_toml_or_json() {
+        let (table, ..) = containers(
+            &[
+                ("APP__HOSTS", r#"["a,b", "c"]"#),
+                ("APP__LABELS", r#"{"tier": "gold"}"#),
+                ("APP__SERVERS", r#"[{host = "x", port = 1}]"#),
+            ],
+            DEFAULT_LIST_SEPARATOR,
+        );
+        assert_eq!(table["hosts"], strings(&["a,b", "c"]));
+        assert_eq!(
+            table["labels"].as_map().unwrap()["tier"],
+            Value::String("gold".into())
+        );
+        let servers = table["servers"].as_array().unwrap();
+        assert_eq!(servers[0].as_map().unwrap()["port"], Value::Integer(1));
+    }
+
+    #[test]
+    fn indexed_sections_build_arrays_and_rename_their_sources() {
+        let (table, sources, winners) = containers(
+            &[
+                ("APP__SERVERS__0__HOST", "a"),
+                ("APP__SERVERS__5__HOST", "b"),
+                ("APP__PORTS__1", "81"),
+            ],
+            DEFAULT_LIST_SEPARATOR,
+        );
+        let servers = table["servers"].as_array().unwrap();
+        assert_eq!(servers.len(), 2, "gaps close up");
+        assert_eq!(
+            servers[1].as_map().unwrap()["host"],
+            Value::String("b".into())
+        );
+        assert_eq!(table["ports"], strings(&["81"]));
+
+        let second_host = ConfigPath::new().key("servers").index(1).key("host");
+        assert_eq!(
+            env_winner(&winners, second_host.clone()),
+            Some("APP__SERVERS__5__HOST")
+        );
+        assert_eq!(
+            sources.get(&second_host),
+            Some(&vec!["APP__SERVERS__5__HOST".to_string()])
+        );
+        assert!(!sources.contains_key(&path(&["servers", "5", "host"])));
+    }
+
+    #[test]
+    fn sparse_indices_close_up_in_numeric_order() {
+        let (table, _, winners) = containers(
+            &[
+                ("APP__HOSTS__10", "c"),
+                ("APP__HOSTS__5", "b"),
+                ("APP__HOSTS__0", "a"),
+            ],
+            DEFAULT_LIST_SEPARATOR,
+        );
+        assert_eq!(table["hosts"], strings(&["a", "b", "c"]));
+        let last = ConfigPath::new().key("hosts").index(2);
+        assert_eq!(env_winner(&winners, last), Some("APP__HOSTS__10"));
+    }
+
+    #[test]
+    fn non_index_sections_on_arrays_are_left_for_the_type_check() {
+        let (table, ..) = containers(&[("APP__SERVERS__FIRST__HOST", "a")], ",");
+        assert!(table["servers"].as_map().is_some());
+    }
 }
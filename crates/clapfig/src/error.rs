@@ -193,6 +193,63 @@ pub struct OriginFacts {
     pub key: Option<String>,
     /// Which input type produced the value.
     pub input_type: Option<InputType>,
+    /// File the value was read from, when the input named a file instead
+    /// of the value itself (a `_FILE` environment variable or a
+    /// [`from_file`](crate::runtime::Leaf::from_file) leaf).
+    pub secret_file: Option<PathBuf>,
+    /// The input reset the key with the unset marker rather than
+    /// supplying a value.
+    pub reset: bool,
+}
+
+impl OriginFacts {
+    /// 1-indexed line and character column of the value's first byte,
+    /// derived from [`span`](Self::span) over
+    /// [`source`](Self::source). `None` for non-file origins and for
+    /// file origins whose span index had no entry.
+    pub fn line_col(&self) -> Option<(usize, usize)> {
+        let (span, source) = (self.span?, self.source.as_deref()?);
+        Some(crate::format::byte_offset_to_line_col(source, span.start))
+    }
+}
+
+/// One-line, value-free description of the origin — the annotation
+/// `config list --origins` / `config get --origin` print:
+/// `file: /etc/app.toml:12`, `env: APP__PORT`, `url: port`,
+/// `override: port`, `default`. A value read from a named file adds it:
+/// `env: APP__DB__PASSWORD_FILE (read from /run/secrets/db)`. A key
+/// reset by the unset marker says so: `env: APP__PORT (reset)`.
+impl fmt::Display for OriginFacts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.input_type {
+            Some(InputType::File) => match (&self.file, self.line_col()) {
+                (Some(path), Some((line, _))) => write!(f, "file: {}:{line}", path.display()),
+                (Some(path), None) => write!(f, "file: {}", path.display()),
+                (None, _) => f.write_str("file"),
+            },
+            Some(InputType::Env) => match &self.env_var {
+                Some(var) => write!(f, "env: {var}"),
+                None => f.write_str("env"),
+            },
+            Some(InputType::Url) => match &self.url_key {
+                Some(key) => write!(f, "url: {key}"),
+                None => f.write_str("url"),
+            },
+            Some(InputType::Override) => match &self.key {
+                Some(key) => write!(f, "override: {key}"),
+                None => f.write_str("override"),
+            },
+            Some(InputType::Default) => f.write_str("default"),
+            None => f.write_str("unknown"),
+        }?;
+        if let Some(path) = &self.secret_file {
+            write!(f, " (read from {})", path.display())?;
+        }
+        if self.reset {
+            f.write_str(" (reset)")?;
+        }
+        Ok(())
+    }
 }
 
 impl UnknownKeyInfo {
@@ -332,6 +389,25 @@ pub enum ClapfigError {
         originals: Vec<String>,
     },
 
+    /// One input spelled a field two ways — by its name and an
+    /// [alias](crate::runtime::Leaf::aliases), or by two aliases (e.g.
+    /// `timeout` and `timeout_secs`). Like
+    /// [`NormalizedKeyCollision`](Self::NormalizedKeyCollision), neither
+    /// spelling silently wins. `input` is the file path, or the layer
+    /// (`environment`, `URL query`, `overrides`); for the environment,
+    /// `originals` are the variable names. Fix by keeping one spelling.
+    #[error(
+        "Conflicting keys in {input}: '{key}'{} is defined by [{}], which name the same field",
+        if section.is_empty() { String::new() } else { format!(" (under [{section}])") },
+        originals.iter().map(|s| format!("'{s}'")).collect::<Vec<_>>().join(", "),
+    )]
+    AliasCollision {
+        input: String,
+        section: String,
+        key: String,
+        originals: Vec<String>,
+    },
+
     /// A required field declared by the [`Schema`](crate::runtime::Schema)
     /// was not supplied by any layer and has no default.
     ///
@@ -390,6 +466,24 @@ pub enum ClapfigError {
     /// merging of same-stem siblings.
     #[error("Ambiguous config files in {}: {} — keep exactly one of them", dir.display(), files.iter().map(|p| p.display().to_string()).collect::<Vec<_>>().join(", "))]
     AmbiguousConfigFiles { dir: PathBuf, files: Vec<PathBuf> },
+
+    /// A config file's include directive
+    /// ([`Builder::include_key`](crate::Builder::include_key)) names a
+    /// file that does not exist. A wildcard pattern that matches nothing
+    /// is not this error; it includes nothing.
+    #[error("Included file not found: {} (included from {})", path.display(), from.display())]
+    IncludeNotFound { path: PathBuf, from: PathBuf },
+
+    /// Config files include each other in a loop. `chain` runs from the
+    /// first file of the loop back to it again.
+    #[error("Include cycle: {}", chain.iter().map(|p| p.display().to_string()).collect::<Vec<_>>().join(" -> "))]
+    IncludeCycle { chain: Vec<PathBuf> },
+
+    /// The load raised warnings of a kind the builder's
+    /// [`deny_warnings`](crate::Builder::deny_warnings) policy turns into
+    /// errors. The vector holds every denied warning and is never empty.
+    #[error("{}", format_denied_warnings(.0))]
+    DeniedWarnings(Vec<crate::diagnostics::Warning>),
 }
 
 impl ClapfigError {
@@ -418,6 +512,15 @@ impl ClapfigError {
         }
     }
 
+    /// If this error is a [`deny_warnings`](crate::Builder::deny_warnings)
+    /// policy failure, return the denied warnings.
+    pub fn denied_warnings(&self) -> Option<&[crate::diagnostics::Warning]> {
+        match self {
+            ClapfigError::DeniedWarnings(warnings) => Some(warnings),
+            _ => None,
+        }
+    }
+
     /// True if this error represents a strict-mode schema violation
     /// (unknown keys) — useful for callers that want to fail softly on
     /// strict violations but hard on real parse/type errors.
@@ -480,8 +583,17 @@ fn format_unaddressable_key(key: &str, section: &str, kind: &str) -> String {
 }
 
 fn format_invalid_value(key: &str, reason: &str, origin: &OriginFacts) -> String {
-    use std::fmt::Write;
     let mut out = format!("Invalid value for '{key}': {reason}");
+    write_origin_location(&mut out, key, origin);
+    out
+}
+
+/// Append the lines locating `origin` — `--> file:line`, or the variable,
+/// query parameter, override, or default that set `key` — to a one-line
+/// message. Shared by [`ClapfigError::InvalidValue`] and
+/// [`Warning`](crate::diagnostics::Warning).
+pub(crate) fn write_origin_location(out: &mut String, key: &str, origin: &OriginFacts) {
+    use std::fmt::Write;
     match origin.input_type {
         Some(InputType::File) => {
             if let Some(file) = &origin.file {
@@ -516,6 +628,21 @@ fn format_invalid_value(key: &str, reason: &str, origin: &OriginFacts) -> String
         }
         None => {}
     }
+    if let Some(path) = &origin.secret_file {
+        let _ = write!(out, "\n  read from file {}", path.display());
+    }
+}
+
+fn format_denied_warnings(warnings: &[crate::diagnostics::Warning]) -> String {
+    use std::fmt::Write;
+    let mut out = if warnings.len() == 1 {
+        String::from("Warning denied by policy:")
+    } else {
+        format!("{} warnings denied by policy:", warnings.len())
+    };
+    for warning in warnings {
+        let _ = write!(out, "\n{warning}");
+    }
     out
 }
 
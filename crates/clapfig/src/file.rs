@@ -163,6 +163,21 @@ pub fn resolve_persist_path(
     }
 }
 
+/// A file's length and modification time, or `None` when it cannot be
+/// stat'ed (usually: it does not exist). Two equal stamps mean "probably
+/// unchanged" — the resolver's [`CacheFreshness::Metadata`] check and the
+/// config watcher both compare them.
+///
+/// [`CacheFreshness::Metadata`]: crate::CacheFreshness::Metadata
+pub(crate) type FileStamp = Option<(u64, Option<std::time::SystemTime>)>;
+
+/// Stamp `path` (see [`FileStamp`]).
+pub(crate) fn stamp(path: &Path) -> FileStamp {
+    std::fs::metadata(path)
+        .ok()
+        .map(|meta| (meta.len(), meta.modified().ok()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
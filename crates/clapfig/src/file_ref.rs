@@ -0,0 +1,160 @@
+//! Values that name a file instead of holding the value: Docker-style
+//! `_FILE` environment variables (see [`env`](crate::env)) and
+//! [`from_file`](crate::runtime::Leaf::from_file) leaves.
+//!
+//! The resolve walk does no I/O of its own; reads go through the
+//! injected [`ReadFile`]. A reference resolves like a path in the input
+//! that supplied it ([`reference_path`]), the contents lose one trailing
+//! newline (secret files are usually written with one), and the origin
+//! gains [`secret_file`](crate::origin::Origin::secret_file) so `config
+//! list --origins` and errors show both where the reference came from
+//! and which file was read.
+
+use std::path::{Path, PathBuf};
+
+use crate::error::ClapfigError;
+use crate::format::ConfigPath;
+use crate::origin::{Origin, OriginMap, lookup, lookup_mut};
+use crate::runtime::{DocumentRoot, Schema, Shape};
+use crate::types::InputType;
+use crate::value::{Map, Value};
+
+/// Reads a referenced file's contents.
+pub(crate) type ReadFile = fn(&Path) -> std::io::Result<String>;
+
+/// The production [`ReadFile`].
+pub(crate) fn read_from_disk(path: &Path) -> std::io::Result<String> {
+    std::fs::read_to_string(path)
+}
+
+/// The file `raw` names, as written in an input whose origin is `origin`:
+/// `~` and `~/…` are under the home directory, and a relative path from
+/// a config file is relative to that file's directory. Other relative
+/// paths stay relative to the working directory.
+pub(crate) fn reference_path(raw: &str, origin: Option<&Origin>) -> PathBuf {
+    if (raw == "~" || raw.starts_with("~/"))
+        && let Some(user) = directories::UserDirs::new()
+    {
+        // `join("")` would leave a trailing separator on a bare `~`.
+        return match raw.trim_start_matches('~').trim_start_matches('/') {
+            "" => user.home_dir().to_path_buf(),
+            rest => user.home_dir().join(rest),
+        };
+    }
+    let path = PathBuf::from(raw);
+    match origin {
+        Some(origin) if path.is_relative() && origin.layer == InputType::File => {
+            match origin.file.as_deref().and_then(Path::parent) {
+                Some(dir) => dir.join(path),
+                None => path,
+            }
+        }
+        _ => path,
+    }
+}
+
+/// Read `path` with `read`, trimming one trailing newline. The error is
+/// an [`InvalidValue`](ClapfigError::InvalidValue) reason.
+pub(crate) fn read_reference(read: ReadFile, path: &Path) -> Result<String, String> {
+    let mut contents =
+        read(path).map_err(|e| format!("cannot read file {}: {e}", path.display()))?;
+    if contents.ends_with('\n') {
+        contents.pop();
+        if contents.ends_with('\r') {
+            contents.pop();
+        }
+    }
+    Ok(contents)
+}
+
+/// Replace every [`from_file`](crate::runtime::Leaf::from_file) leaf's
+/// path in the merged table with the contents of the file it names, and
+/// record the file on the leaf's origin. Runs after defaults are filled,
+/// so a default path loads too. A read failure is an `InvalidValue`
+/// located at the leaf's origin.
+pub(crate) fn read_file_leaves_root(
+    table: &mut Map,
+    origins: &mut OriginMap,
+    root: DocumentRoot<'_>,
+    read: ReadFile,
+) -> Result<(), ClapfigError> {
+    let mut walk = FileLeafWalk { origins, read };
+    let path = ConfigPath::new();
+    match root {
+        DocumentRoot::Object(schema) => walk.table(table, schema, &path),
+        DocumentRoot::Map(map) => {
+            for (key, value) in table.iter_mut() {
+                walk.value(value, &map.item, &path.clone().key(key))?;
+            }
+            Ok(())
+        }
+        DocumentRoot::Tagged(tagged) => match tagged.selected(table) {
+            Some(variant) => walk.table(table, &variant.schema, &path),
+            None => Ok(()),
+        },
+    }
+}
+
+struct FileLeafWalk<'a> {
+    origins: &'a mut OriginMap,
+    read: ReadFile,
+}
+
+impl FileLeafWalk<'_> {
+    fn table(
+        &mut self,
+        table: &mut Map,
+        schema: &Schema,
+        path: &ConfigPath,
+    ) -> Result<(), ClapfigError> {
+        for nf in &schema.fields {
+            if let Some(value) = table.get_mut(&nf.name) {
+                self.value(value, &nf.field, &path.clone().key(&nf.name))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn value(
+        &mut self,
+        value: &mut Value,
+        shape: &Shape,
+        path: &ConfigPath,
+    ) -> Result<(), ClapfigError> {
+        match (shape, value) {
+            (Shape::Leaf(leaf), value) if leaf.from_file => {
+                // Anything but a string fails the finalize type check.
+                let Value::String(raw) = value else {
+                    return Ok(());
+                };
+                let file = reference_path(raw, lookup(self.origins, path));
+                if let Some(origin) = lookup_mut(self.origins, path) {
+                    origin.secret_file = Some(file.clone());
+                }
+                let contents = read_reference(self.read, &file).map_err(|reason| {
+                    ClapfigError::invalid_value_at(path.to_string(), reason, self.origins, path)
+                })?;
+                *value = Value::String(contents);
+                Ok(())
+            }
+            (Shape::Object(schema), Value::Map(table)) => self.table(table, schema, path),
+            (Shape::Tagged(tagged), Value::Map(table)) => match tagged.selected(table) {
+                Some(variant) => self.table(table, &variant.schema, path),
+                None => Ok(()),
+            },
+            (Shape::Map(map), Value::Map(entries)) => {
+                for (key, entry) in entries.iter_mut() {
+                    self.value(entry, &map.item, &path.clone().key(key))?;
+                }
+                Ok(())
+            }
+            (Shape::Array(array), Value::Array(items)) => {
+                for (i, item) in items.iter_mut().enumerate() {
+                    self.value(item, &array.item, &path.clone().index(i))?;
+                }
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+}
@@ -7,7 +7,7 @@
 //! needs a container — schema-time pre-checks only validate the schema,
 //! not the shape of an existing on-disk file (`config set database.url x`
 //! with `database = "string"` already in the file) — then assign or remove
-//! at the leaf. The walk lives here ONCE, generic over [`EditDoc`]: each
+//! at the leaf — or rename it within its parent. The walk lives here ONCE, generic over [`EditDoc`]: each
 //! adapter's document tree type (`toml_edit::Item`, the owned
 //! [`Value`](crate::value::Value) tree YAML patches against,
 //! `serde_json::Value`) implements the seam and keeps only its
@@ -44,6 +44,9 @@ pub(crate) trait EditDoc {
     /// Remove `key`; `false` when nothing was removed (key missing, or
     /// `self` not a container).
     fn remove_key(&mut self, key: &str) -> bool;
+    /// Rename the child `from` to `to`, keeping its position and its
+    /// formatting. Callers guarantee `from` exists and `to` does not.
+    fn rename_key(&mut self, from: &str, to: &str);
 }
 
 /// Map-key segments of a file-edit path.
@@ -138,6 +141,60 @@ pub(crate) fn unset_at_path<D: EditDoc>(doc: &mut D, keys: &[&str]) -> bool {
     current.remove_key(leaf)
 }
 
+/// Rename the key at `keys` to `to` within its parent; `false` when the
+/// path was already absent (a no-op, like [`unset_at_path`]). Errors when
+/// the parent already holds `to`.
+pub(crate) fn rename_at_path<D: EditDoc>(
+    doc: &mut D,
+    keys: &[&str],
+    to: &str,
+) -> Result<bool, FormatError> {
+    let (leaf, parents) = keys
+        .split_last()
+        .expect("ConfigPath edits always carry at least one segment");
+    let mut current: &mut D = doc;
+    for segment in parents {
+        match current.child_mut(segment) {
+            Some(next) => current = next,
+            None => return Ok(false),
+        }
+    }
+    if !current.has_child(leaf) {
+        return Ok(false);
+    }
+    if *leaf == to {
+        return Ok(true);
+    }
+    if current.has_child(to) {
+        return Err(rename_conflict(D::FORMAT, D::SOURCE, keys, to));
+    }
+    current.rename_key(leaf, to);
+    Ok(true)
+}
+
+/// The typed path-conflict error for renaming `keys` onto a sibling `to`
+/// that already exists. Shared with the adapters that edit text directly
+/// rather than through [`EditDoc`].
+pub(crate) fn rename_conflict(
+    format: &'static str,
+    source: &str,
+    keys: &[&str],
+    to: &str,
+) -> FormatError {
+    let (_, parents) = keys
+        .split_last()
+        .expect("ConfigPath edits always carry at least one segment");
+    let target: Vec<&str> = parents.iter().copied().chain([to]).collect();
+    FormatError::Edit {
+        format,
+        message: format!(
+            "path conflict: existing {source} already has a value at '{}' (renaming '{}')",
+            target.join("."),
+            keys.join(".")
+        ),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     // The walkers run against the owned-model tree (the YAML adapter's
@@ -172,6 +229,20 @@ mod tests {
         }
     }
 
+    #[test]
+    fn rename_is_a_no_op_on_missing_paths_and_refuses_taken_names() {
+        let mut map = Map::new();
+        map.insert("a".into(), Value::Integer(1));
+        map.insert("b".into(), Value::Integer(2));
+        let mut root = Value::Map(map);
+        assert!(!rename_at_path(&mut root, &["x", "y"], "z").unwrap());
+        assert!(rename_at_path(&mut root, &["a"], "b").is_err());
+        assert!(rename_at_path(&mut root, &["a"], "c").unwrap());
+        let map = root.as_map().unwrap();
+        assert_eq!(map["c"], Value::Integer(1));
+        assert!(!map.contains_key("a"));
+    }
+
     #[test]
     fn map_key_segments_keeps_map_keys() {
         let path = crate::format::ConfigPath::new().key("database").key("url");
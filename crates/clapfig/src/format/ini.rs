@@ -0,0 +1,1189 @@
+//! INI format adapter — a clapfig-owned line walk for parse and edit.
+//!
+//! INI has no standard, and the crates that read it disagree on nearly
+//! every corner (inline comments, repeated keys, nesting), so this
+//! adapter owns one small, documented dialect. A line-oriented walk also
+//! yields byte spans for free — the same one-pass `{value, spans}`
+//! contract the other adapters keep (ADR-0005).
+//!
+//! - `key = value` assignments. A `[section]` header opens a nested map
+//!   that lasts until the next header; a dotted header
+//!   (`[database.replica]`) nests one map per segment. Keys before the
+//!   first header sit at the document root. A `.` inside a key is
+//!   literal (one path segment).
+//! - Full-line comments start with `;` or `#`. There are no inline
+//!   comments: `name = a ; b` is the string `a ; b`.
+//! - Every value is a string: the trimmed text after `=`, or a
+//!   double-quoted string (escapes `\\`, `\"`, `\n`, `\r`, `\t`) when the
+//!   value must keep edge whitespace. The adapter declares
+//!   [`scalars_are_strings`](FormatAdapter::scalars_are_strings), so the
+//!   pipeline types values by the schema — `port = 8080` is an integer
+//!   because the schema says so, never because it looks like one
+//!   (ADR-0001's rule for datetimes, extended to every scalar).
+//! - Arrays have no spelling. Templates name array fields in a comment
+//!   instead of inventing a syntax; edits refuse array and map values
+//!   with a typed error.
+//! - A key repeated within one section, a repeated section header, and a
+//!   section that collides with a key are parse errors. Dialects that
+//!   merge or last-win those disagree with each other; clapfig refuses
+//!   rather than pick one silently.
+//!
+//! Declared operations: parse, template, and the five edit rows. Edits
+//! are line-level — every comment and untouched line survives byte for
+//! byte. Serialize is undeclared: nothing in the pipeline serializes a
+//! whole document, and an INI rendering of an arbitrary value tree would
+//! have to drop its arrays.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use crate::runtime::{Schema, Shape};
+use crate::value::{Map, Value};
+
+use super::template::{
+    TemplateRenderer, ValueView, leaf_annotations, placeholder, tagged_variant_example_schema,
+    walk_level, walk_root,
+};
+use super::{ConfigPath, FileEdit, FormatAdapter, FormatError, Operation, Parsed, Span, SpanEntry};
+
+/// The INI format behind the adapter contract.
+///
+/// Parse fills the path → span index for every path — keys and values
+/// for assignments, the header segment and whole header for sections —
+/// so unknown-key and `InvalidValue` errors caret INI source like any
+/// other format's. See the [module docs](self) for the dialect.
+pub struct IniAdapter;
+
+impl FormatAdapter for IniAdapter {
+    fn name(&self) -> &'static str {
+        "ini"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["ini", "conf"]
+    }
+
+    fn capabilities(&self) -> &'static [Operation] {
+        &[
+            Operation::Parse,
+            Operation::Template,
+            Operation::EditSet,
+            Operation::EditCreateKey,
+            Operation::EditCreateFile,
+            Operation::EditUnset,
+            Operation::EditRename,
+        ]
+    }
+
+    fn scalars_are_strings(&self) -> bool {
+        true
+    }
+
+    fn display_comment(&self, line: &str) -> String {
+        format!("; {line}")
+    }
+
+    fn parse(&self, text: &str) -> Result<Parsed, FormatError> {
+        build(&scan(text)?)
+    }
+
+    fn serialize(&self, _value: &Value) -> Result<String, FormatError> {
+        Err(self.require(Operation::Serialize).unwrap_err().into())
+    }
+
+    fn template(&self, shape: &Shape) -> Result<String, FormatError> {
+        let mut out = String::new();
+        let doc = shape.field_doc();
+        for line in doc {
+            push_comment(&mut out, line);
+        }
+        if !doc.is_empty() {
+            out.push('\n');
+        }
+        walk_root(&mut IniTemplate, shape, &String::new(), &mut out)?;
+        Ok(out)
+    }
+
+    fn edit(&self, source: &str, edit: FileEdit<'_>) -> Result<String, FormatError> {
+        let lines = scan(source)?;
+        // Refuse to edit a document the load path would refuse.
+        build(&lines)?;
+        match edit {
+            FileEdit::Set { path, value, .. } => {
+                let keys = super::edit::map_key_segments(path, "ini")?;
+                set(source, &lines, &keys, value)
+            }
+            FileEdit::Unset { path } => {
+                let keys = super::edit::map_key_segments(path, "ini")?;
+                Ok(unset(source, &lines, &keys))
+            }
+            FileEdit::Rename { path, to } => {
+                let keys = super::edit::map_key_segments(path, "ini")?;
+                rename(source, &lines, &keys, to)
+            }
+        }
+    }
+}
+
+// --- the line walk --------------------------------------------------------
+
+/// One significant (non-blank, non-comment) source line. `start..end`
+/// covers the whole line including its newline, so removing a line is
+/// one range and inserting after it starts at `end`.
+struct Line {
+    start: usize,
+    end: usize,
+    kind: LineKind,
+}
+
+enum LineKind {
+    Section {
+        path: Vec<String>,
+        /// One span per path segment, covering the segment's name.
+        segments: Vec<Span>,
+        /// The whole `[...]` header.
+        header: Span,
+    },
+    Entry {
+        key: String,
+        key_span: Span,
+        value: String,
+        /// The raw value token (quotes included); empty for `key =`,
+        /// positioned right after the `=`.
+        value_span: Span,
+    },
+}
+
+fn parse_error(message: String, span: Span) -> FormatError {
+    FormatError::Parse {
+        format: "ini",
+        message,
+        span: Some(span),
+    }
+}
+
+/// Split `text` into its significant lines.
+fn scan(text: &str) -> Result<Vec<Line>, FormatError> {
+    let mut lines = Vec::new();
+    let mut start = 0;
+    while start < text.len() {
+        let end = text[start..]
+            .find('\n')
+            .map_or(text.len(), |i| start + i + 1);
+        let raw = text[start..end].trim_end_matches(['\n', '\r']);
+        let body = raw.trim();
+        let at = start + (raw.len() - raw.trim_start().len());
+        if !(body.is_empty() || body.starts_with(';') || body.starts_with('#')) {
+            let kind = if body.starts_with('[') {
+                scan_section(body, at)?
+            } else {
+                scan_entry(body, at)?
+            };
+            lines.push(Line { start, end, kind });
+        }
+        start = end;
+    }
+    Ok(lines)
+}
+
+fn scan_section(body: &str, at: usize) -> Result<LineKind, FormatError> {
+    let header = Span {
+        start: at,
+        end: at + body.len(),
+    };
+    let Some(inner) = body.strip_prefix('[').and_then(|b| b.strip_suffix(']')) else {
+        return Err(parse_error(
+            "section header is missing its closing ']'".into(),
+            header,
+        ));
+    };
+    let mut path = Vec::new();
+    let mut segments = Vec::new();
+    let mut offset = at + 1;
+    for segment in inner.split('.') {
+        let name = segment.trim();
+        if name.is_empty() {
+            return Err(parse_error(
+                format!("section header [{inner}] has an empty name segment"),
+                header,
+            ));
+        }
+        let name_start = offset + (segment.len() - segment.trim_start().len());
+        segments.push(Span {
+            start: name_start,
+            end: name_start + name.len(),
+        });
+        path.push(name.to_string());
+        offset += segment.len() + 1;
+    }
+    Ok(LineKind::Section {
+        path,
+        segments,
+        header,
+    })
+}
+
+fn scan_entry(body: &str, at: usize) -> Result<LineKind, FormatError> {
+    let line_span = Span {
+        start: at,
+        end: at + body.len(),
+    };
+    let Some(eq) = body.find('=') else {
+        return Err(parse_error(
+            "expected `key = value`, a `[section]` header, or a `;` comment".into(),
+            line_span,
+        ));
+    };
+    let key = body[..eq].trim_end();
+    if key.is_empty() {
+        return Err(parse_error(
+            "assignment is missing its key".into(),
+            Span {
+                start: at + eq,
+                end: at + eq + 1,
+            },
+        ));
+    }
+    let rest = &body[eq + 1..];
+    let raw_value = rest.trim_start();
+    let value_start = at + eq + 1 + (rest.len() - raw_value.len());
+    let value_span = Span {
+        start: value_start,
+        end: value_start + raw_value.len(),
+    };
+    let value = if raw_value.starts_with('"') {
+        unquote(raw_value, value_span)?
+    } else {
+        raw_value.to_string()
+    };
+    Ok(LineKind::Entry {
+        key: key.to_string(),
+        key_span: Span {
+            start: at,
+            end: at + key.len(),
+        },
+        value,
+        value_span,
+    })
+}
+
+/// Decode a double-quoted value token. The closing quote must end the
+/// token — there are no inline comments to skip.
+fn unquote(raw: &str, span: Span) -> Result<String, FormatError> {
+    let mut out = String::new();
+    let mut chars = raw.char_indices().skip(1);
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' if i + 1 == raw.len() => return Ok(out),
+            '"' => {
+                return Err(parse_error(
+                    "unexpected text after the closing quote".into(),
+                    Span {
+                        start: span.start + i + 1,
+                        end: span.end,
+                    },
+                ));
+            }
+            '\\' => match chars.next() {
+                Some((_, '\\')) => out.push('\\'),
+                Some((_, '"')) => out.push('"'),
+                Some((_, 'n')) => out.push('\n'),
+                Some((_, 'r')) => out.push('\r'),
+                Some((_, 't')) => out.push('\t'),
+                Some((j, other)) => {
+                    return Err(parse_error(
+                        format!("unknown escape '\\{other}'"),
+                        Span {
+                            start: span.start + i,
+                            end: span.start + j + other.len_utf8(),
+                        },
+                    ));
+                }
+                None => break,
+            },
+            other => out.push(other),
+        }
+    }
+    Err(parse_error(
+        "quoted value is missing its closing quote".into(),
+        span,
+    ))
+}
+
+/// Assemble the value tree and span index from scanned lines.
+fn build(lines: &[Line]) -> Result<Parsed, FormatError> {
+    let mut root = Map::new();
+    let mut spans = BTreeMap::new();
+    let mut seen: Vec<&[String]> = Vec::new();
+    let mut current: &[String] = &[];
+    for line in lines {
+        match &line.kind {
+            LineKind::Section {
+                path,
+                segments,
+                header,
+            } => {
+                if seen.contains(&path.as_slice()) {
+                    return Err(parse_error(
+                        format!("duplicate section [{}]", path.join(".")),
+                        *header,
+                    ));
+                }
+                seen.push(path);
+                let mut map = &mut root;
+                let mut config_path = ConfigPath::new();
+                for (i, (segment, span)) in path.iter().zip(segments).enumerate() {
+                    config_path = config_path.key(segment);
+                    let node = map
+                        .entry(segment.clone())
+                        .or_insert_with(|| Value::Map(Map::new()));
+                    let Value::Map(next) = node else {
+                        return Err(parse_error(
+                            format!(
+                                "section [{}] collides with the key '{}'",
+                                path.join("."),
+                                path[..=i].join(".")
+                            ),
+                            *header,
+                        ));
+                    };
+                    spans.entry(config_path.clone()).or_insert(SpanEntry {
+                        key: Some(*span),
+                        value: *header,
+                    });
+                    map = next;
+                }
+                current = path;
+            }
+            LineKind::Entry {
+                key,
+                key_span,
+                value,
+                value_span,
+            } => {
+                let map = section_map(&mut root, current);
+                if let Some(existing) = map.get(key) {
+                    let message = match existing {
+                        Value::Map(_) => {
+                            format!("key '{key}' collides with a section of that name")
+                        }
+                        _ => format!("duplicate key '{key}'"),
+                    };
+                    let message = if current.is_empty() {
+                        message
+                    } else {
+                        format!("{message} in section [{}]", current.join("."))
+                    };
+                    return Err(parse_error(message, *key_span));
+                }
+                map.insert(key.clone(), Value::String(value.clone()));
+                let path = current
+                    .iter()
+                    .fold(ConfigPath::new(), |path, segment| path.key(segment))
+                    .key(key);
+                spans.insert(
+                    path,
+                    SpanEntry {
+                        key: Some(*key_span),
+                        value: *value_span,
+                    },
+                );
+            }
+        }
+    }
+    Ok(Parsed {
+        value: Value::Map(root),
+        spans,
+    })
+}
+
+/// The map a section header already created.
+fn section_map<'a>(root: &'a mut Map, section: &[String]) -> &'a mut Map {
+    section.iter().fold(root, |map, segment| {
+        map.get_mut(segment)
+            .and_then(Value::as_map_mut)
+            .expect("section headers create their maps before entries")
+    })
+}
+
+/// The section owning each line: a header's own path, an entry's
+/// enclosing header (empty at the root).
+fn owners(lines: &[Line]) -> Vec<&[String]> {
+    let mut current: &[String] = &[];
+    lines
+        .iter()
+        .map(|line| {
+            if let LineKind::Section { path, .. } = &line.kind {
+                current = path;
+            }
+            current
+        })
+        .collect()
+}
+
+// --- edits ------------------------------------------------------------------
+
+fn edit_error(message: String) -> FormatError {
+    FormatError::Edit {
+        format: "ini",
+        message,
+    }
+}
+
+fn set(source: &str, lines: &[Line], keys: &[&str], value: &Value) -> Result<String, FormatError> {
+    let (leaf, parents) = keys
+        .split_last()
+        .expect("ConfigPath edits always carry at least one segment");
+    let display_path = keys.join(".");
+    let rendered = match value {
+        Value::Array(_) | Value::Map(_) => {
+            return Err(edit_error(format!(
+                "INI values are scalars; cannot write {} at '{display_path}'",
+                value.type_str()
+            )));
+        }
+        scalar => ini_value(scalar),
+    };
+    if !spellable_key(leaf) {
+        return Err(edit_error(format!("INI cannot spell the key '{leaf}'")));
+    }
+    if let Some(segment) = parents.iter().find(|s| !spellable_section(s)) {
+        return Err(edit_error(format!(
+            "INI section names cannot spell '{segment}'"
+        )));
+    }
+
+    let owners = owners(lines);
+    let entry_at = |section: &[&str], key: &str| {
+        lines.iter().zip(&owners).position(|(line, owner)| {
+            matches!(&line.kind, LineKind::Entry { key: k, .. } if k == key)
+                && owner.iter().map(String::as_str).eq(section.iter().copied())
+        })
+    };
+    for (i, segment) in parents.iter().enumerate() {
+        if entry_at(&parents[..i], segment).is_some() {
+            return Err(edit_error(format!(
+                "path conflict: existing file has a non-section value at '{segment}' (setting '{display_path}')"
+            )));
+        }
+    }
+    let names_section = lines.iter().any(|line| {
+        matches!(&line.kind, LineKind::Section { path, .. }
+            if path.len() >= keys.len() && path.iter().zip(keys).all(|(p, k)| p == k))
+    });
+    if names_section {
+        return Err(edit_error(format!(
+            "path conflict: existing file has a section at '{display_path}' (setting '{display_path}')"
+        )));
+    }
+
+    if let Some(i) = entry_at(parents, leaf) {
+        let LineKind::Entry { value_span, .. } = &lines[i].kind else {
+            unreachable!("entry_at only matches entries")
+        };
+        let replacement = if value_span.start == value_span.end {
+            format!(" {rendered}")
+        } else {
+            rendered
+        };
+        return Ok(splice(source, *value_span, &replacement));
+    }
+
+    let assignment = format!("{leaf} = {rendered}\n");
+    let block: Vec<usize> = (0..lines.len())
+        .filter(|&i| {
+            owners[i]
+                .iter()
+                .map(String::as_str)
+                .eq(parents.iter().copied())
+        })
+        .collect();
+    if parents.is_empty() {
+        let at = match (block.last(), lines.first()) {
+            (Some(&last), _) => lines[last].end,
+            (None, Some(first)) => {
+                return Ok(splice(
+                    source,
+                    Span {
+                        start: first.start,
+                        end: first.start,
+                    },
+                    &format!("{assignment}\n"),
+                ));
+            }
+            (None, None) => source.len(),
+        };
+        return Ok(insert_line(source, at, &assignment));
+    }
+    // `block` holds the header (when one exists) and its entries; the
+    // new key lands after the last of them.
+    let header_exists = block
+        .first()
+        .is_some_and(|&i| matches!(lines[i].kind, LineKind::Section { .. }));
+    if header_exists {
+        let at = lines[*block.last().expect("header is in the block")].end;
+        return Ok(insert_line(source, at, &assignment));
+    }
+    let mut out = source.to_string();
+    if !out.is_empty() {
+        if !out.ends_with('\n') {
+            out.push('\n');
+        }
+        out.push('\n');
+    }
+    let _ = write!(out, "[{}]\n{assignment}", parents.join("."));
+    Ok(out)
+}
+
+fn unset(source: &str, lines: &[Line], keys: &[&str]) -> String {
+    let (leaf, parents) = keys
+        .split_last()
+        .expect("ConfigPath edits always carry at least one segment");
+    let owners = owners(lines);
+    let entry = lines.iter().zip(&owners).find(|(line, owner)| {
+        matches!(&line.kind, LineKind::Entry { key, .. } if key == leaf)
+            && owner.iter().map(String::as_str).eq(parents.iter().copied())
+    });
+    if let Some((line, _)) = entry {
+        return splice(
+            source,
+            Span {
+                start: line.start,
+                end: line.end,
+            },
+            "",
+        );
+    }
+    // Unsetting a section removes its block and every subsection's.
+    let mut out = source.to_string();
+    for (i, line) in lines.iter().enumerate().rev() {
+        let LineKind::Section { path, .. } = &line.kind else {
+            continue;
+        };
+        if path.len() >= keys.len() && path.iter().zip(keys).all(|(p, k)| p == k) {
+            let end = lines[i + 1..]
+                .iter()
+                .find(|next| matches!(next.kind, LineKind::Section { .. }))
+                .map_or(source.len(), |next| next.start);
+            out.replace_range(line.start..end, "");
+        }
+    }
+    out
+}
+
+/// Respell the key at `keys` as `to`: an entry's key token, or — for a
+/// section — that segment of its header and of every subsection's. Only
+/// those names change, so values and comments stay put. A missing path
+/// is a no-op.
+fn rename(source: &str, lines: &[Line], keys: &[&str], to: &str) -> Result<String, FormatError> {
+    let (leaf, parents) = keys
+        .split_last()
+        .expect("ConfigPath edits always carry at least one segment");
+    let owners = owners(lines);
+    let entry_at = |key: &str| {
+        lines
+            .iter()
+            .zip(&owners)
+            .find_map(|(line, owner)| match &line.kind {
+                LineKind::Entry {
+                    key: k, key_span, ..
+                } if k == key && owner.iter().map(String::as_str).eq(parents.iter().copied()) => {
+                    Some(*key_span)
+                }
+                _ => None,
+            })
+    };
+    let headers_under = |path: &[&str]| -> Vec<Span> {
+        lines
+            .iter()
+            .filter_map(|line| match &line.kind {
+                LineKind::Section {
+                    path: p, segments, ..
+                } if p.len() >= path.len() && p.iter().zip(path).all(|(a, b)| a == b) => {
+                    Some(segments[path.len() - 1])
+                }
+                _ => None,
+            })
+            .collect()
+    };
+    let entry = entry_at(leaf);
+    let headers = headers_under(keys);
+    if *leaf == to || (entry.is_none() && headers.is_empty()) {
+        return Ok(source.to_string());
+    }
+    let target: Vec<&str> = parents.iter().copied().chain([to]).collect();
+    if entry_at(to).is_some() || !headers_under(&target).is_empty() {
+        return Err(super::edit::rename_conflict("ini", "file", keys, to));
+    }
+    if let Some(key_span) = entry {
+        if !spellable_key(to) {
+            return Err(edit_error(format!("INI cannot spell the key '{to}'")));
+        }
+        return Ok(splice(source, key_span, to));
+    }
+    if !spellable_section(to) {
+        return Err(edit_error(format!("INI section names cannot spell '{to}'")));
+    }
+    let mut out = source.to_string();
+    for span in headers.iter().rev() {
+        out.replace_range(span.start..span.end, to);
+    }
+    Ok(out)
+}
+
+fn splice(source: &str, span: Span, text: &str) -> String {
+    let mut out = String::with_capacity(source.len() + text.len());
+    out.push_str(&source[..span.start]);
+    out.push_str(text);
+    out.push_str(&source[span.end..]);
+    out
+}
+
+/// Insert a whole line at `at` (a line start, or the end of a last line
+/// that may lack its newline).
+fn insert_line(source: &str, at: usize, line: &str) -> String {
+    let text = if at > 0 && !source[..at].ends_with('\n') {
+        format!("\n{line}")
+    } else {
+        line.to_string()
+    };
+    splice(source, Span { start: at, end: at }, &text)
+}
+
+/// Whether `key` reads back as the same key on the left of an `=`.
+fn spellable_key(key: &str) -> bool {
+    !key.is_empty()
+        && key.trim() == key
+        && !key.contains(['=', '\n', '\r'])
+        && !key.starts_with(['[', ';', '#'])
+}
+
+/// Whether `segment` reads back as one segment of a `[section]` header.
+fn spellable_section(segment: &str) -> bool {
+    !segment.is_empty()
+        && segment.trim() == segment
+        && !segment.contains(['.', '[', ']', '\n', '\r'])
+}
+
+/// One scalar's INI spelling: plain text, quoted only when the raw text
+/// would not read back as the same string.
+fn ini_value(value: &Value) -> String {
+    match value {
+        Value::String(s) => {
+            let needs_quotes =
+                s.trim() != s || s.starts_with('"') || s.contains(['\n', '\r', '\t']);
+            if !needs_quotes {
+                return s.clone();
+            }
+            let mut out = String::from("\"");
+            for c in s.chars() {
+                match c {
+                    '\\' => out.push_str("\\\\"),
+                    '"' => out.push_str("\\\""),
+                    '\n' => out.push_str("\\n"),
+                    '\r' => out.push_str("\\r"),
+                    '\t' => out.push_str("\\t"),
+                    other => out.push(other),
+                }
+            }
+            out.push('"');
+            out
+        }
+        other => other.to_string(),
+    }
+}
+
+/// [`ini_value`] for template encoders, which may be handed a container
+/// (a defaulted map entry holding an array).
+fn inline(value: &Value) -> Result<String, FormatError> {
+    match value {
+        Value::Array(_) | Value::Map(_) => Err(FormatError::Serialize {
+            format: "ini",
+            message: format!("INI values are scalars, got {}", value.type_str()),
+        }),
+        scalar => Ok(ini_value(scalar)),
+    }
+}
+
+// --- template emission (shared traversal in `format::template`) ------------
+
+/// The INI template renderer: `key = value` lines under dotted
+/// `[section]` headers, `;` comments, and a comment in place of anything
+/// INI cannot spell. The context is the dotted section path (empty at
+/// the root).
+struct IniTemplate;
+
+/// The comment a template leaves where a field has no INI spelling.
+const UNSPELLABLE: &str = "INI cannot spell this value; set it through another input.";
+
+/// The dotted section path of `name` under `prefix`.
+fn section_path(prefix: &str, name: &str) -> String {
+    if prefix.is_empty() {
+        name.to_string()
+    } else {
+        format!("{prefix}.{name}")
+    }
+}
+
+/// Append one `;` comment line (`;` alone for blank lines).
+fn push_comment(out: &mut String, line: &str) {
+    let trimmed = line.trim_end();
+    if trimmed.is_empty() {
+        out.push_str(";\n");
+    } else {
+        let _ = writeln!(out, "; {trimmed}");
+    }
+}
+
+/// Append `block` with every non-blank line commented out at column zero.
+fn push_commented_block(out: &mut String, block: &str) {
+    for line in block.lines() {
+        if line.is_empty() {
+            out.push('\n');
+        } else {
+            out.push(';');
+            out.push_str(line);
+            out.push('\n');
+        }
+    }
+}
+
+fn emit_object_doc(out: &mut String, item: &Shape) {
+    if let Shape::Object(child) = item {
+        for line in &child.doc {
+            push_comment(out, line);
+        }
+    }
+}
+
+impl TemplateRenderer for IniTemplate {
+    type Ctx = String;
+    type Out = String;
+
+    // Same rule as TOML: every key after a `[section]` header belongs to
+    // that section, so a level's leaves must precede its sections.
+    const LEAVES_FIRST: bool = true;
+
+    fn leaf(
+        &mut self,
+        out: &mut String,
+        prefix: &String,
+        name: &str,
+        field: ValueView<'_>,
+    ) -> Result<(), FormatError> {
+        for line in leaf_annotations(field, "INI", &mut inline)? {
+            push_comment(out, &line);
+        }
+        match field.shape {
+            Shape::Leaf(_) => match field.default {
+                Some(value) => {
+                    let _ = writeln!(out, "{name} = {}", inline(value)?);
+                }
+                None => {
+                    let _ = writeln!(out, ";{name} = {}", placeholder(field.shape, &mut inline)?);
+                }
+            },
+            // A map of scalars is a section of its own; it stays
+            // commented because this level's keys are still being
+            // written.
+            Shape::Map(map) if matches!(map.item.as_ref(), Shape::Leaf(_)) => {
+                let mut buf = String::new();
+                let _ = writeln!(buf, "[{}]", section_path(prefix, name));
+                match field.default {
+                    Some(Value::Map(entries)) if !entries.is_empty() => {
+                        for (key, value) in entries {
+                            let _ = writeln!(buf, "{key} = {}", inline(value)?);
+                        }
+                    }
+                    _ => {
+                        let _ = writeln!(buf, "<key> = {}", placeholder(&map.item, &mut inline)?);
+                    }
+                }
+                push_commented_block(out, &buf);
+            }
+            _ => push_comment(out, UNSPELLABLE),
+        }
+        out.push('\n');
+        Ok(())
+    }
+
+    fn nested(
+        &mut self,
+        out: &mut String,
+        prefix: &String,
+        name: &str,
+        child: &Schema,
+    ) -> Result<(), FormatError> {
+        let path = section_path(prefix, name);
+        for line in &child.doc {
+            push_comment(out, line);
+        }
+        let _ = writeln!(out, "[{path}]");
+        walk_level(self, child, &path, out)
+    }
+
+    fn array_of(
+        &mut self,
+        out: &mut String,
+        prefix: &String,
+        name: &str,
+        item: &Shape,
+    ) -> Result<(), FormatError> {
+        emit_object_doc(out, item);
+        let _ = writeln!(out, "; [{}]", section_path(prefix, name));
+        push_comment(out, UNSPELLABLE);
+        out.push('\n');
+        Ok(())
+    }
+
+    fn map_of(
+        &mut self,
+        out: &mut String,
+        prefix: &String,
+        name: &str,
+        item: &Shape,
+    ) -> Result<(), FormatError> {
+        let entry = format!("{name}.<key>");
+        match item {
+            Shape::Object(schema) => {
+                emit_object_doc(out, item);
+                let path = section_path(prefix, &entry);
+                let mut buf = String::new();
+                let _ = writeln!(buf, "[{path}]");
+                walk_level(self, schema, &path, &mut buf)?;
+                push_commented_block(out, &buf);
+                Ok(())
+            }
+            Shape::Tagged(tagged) => self.tagged(out, prefix, Some(&entry), tagged),
+            _ => self.array_of(out, prefix, &entry, item),
+        }
+    }
+
+    fn root_map(
+        &mut self,
+        out: &mut String,
+        prefix: &String,
+        item: &Shape,
+        _doc: &[String],
+    ) -> Result<(), FormatError> {
+        match item {
+            Shape::Leaf(_) => {
+                let field = ValueView::from_shape(item);
+                for line in leaf_annotations(field, "INI", &mut inline)? {
+                    push_comment(out, &line);
+                }
+                let example = match field.default {
+                    Some(value) => inline(value)?,
+                    None => placeholder(item, &mut inline)?,
+                };
+                let _ = writeln!(out, ";<key> = {example}");
+                Ok(())
+            }
+            Shape::Object(schema) => {
+                emit_object_doc(out, item);
+                let mut buf = String::new();
+                buf.push_str("[<key>]\n");
+                walk_level(self, schema, &"<key>".to_string(), &mut buf)?;
+                push_commented_block(out, &buf);
+                Ok(())
+            }
+            Shape::Tagged(tagged) => self.tagged(out, prefix, Some("<key>"), tagged),
+            _ => self.array_of(out, prefix, "<key>", item),
+        }
+    }
+
+    fn tagged(
+        &mut self,
+        out: &mut String,
+        prefix: &String,
+        name: Option<&str>,
+        tagged: &crate::runtime::TaggedShape,
+    ) -> Result<(), FormatError> {
+        for (i, variant) in tagged.variants.iter().enumerate() {
+            if i > 0 {
+                out.push('\n');
+            }
+            let example = tagged_variant_example_schema(tagged, variant);
+            let mut buf = String::new();
+            if let Some(name) = name {
+                let path = section_path(prefix, name);
+                let _ = writeln!(buf, "[{path}]");
+                walk_level(self, &example, &path, &mut buf)?;
+            } else {
+                walk_level(self, &example, prefix, &mut buf)?;
+            }
+            push_commented_block(out, &buf);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::SetTarget;
+    use super::*;
+    use crate::runtime::Field;
+
+    fn source_slice(source: &str, span: Span) -> &str {
+        &source[span.start..span.end]
+    }
+
+    fn set_edit(source: &str, path: &ConfigPath, value: &Value) -> Result<String, FormatError> {
+        IniAdapter.edit(
+            source,
+            FileEdit::Set {
+                path,
+                value,
+                target: SetTarget::MissingKey,
+            },
+        )
+    }
+
+    #[test]
+    fn parse_sections_nest_and_values_stay_strings() {
+        let source = "\
+; global settings
+name = demo
+port = 8080
+
+[database]
+url = \"  pg://x  \"
+
+[database.replica]
+# read-only
+host = replica ; not a comment
+";
+        let value = IniAdapter.parse(source).unwrap().value;
+        let map = value.as_map().unwrap();
+        assert_eq!(map["name"], Value::from("demo"));
+        assert_eq!(map["port"], Value::from("8080"), "the schema types it");
+        let database = map["database"].as_map().unwrap();
+        assert_eq!(database["url"], Value::from("  pg://x  "));
+        let replica = database["replica"].as_map().unwrap();
+        assert_eq!(replica["host"], Value::from("replica ; not a comment"));
+    }
+
+    #[test]
+    fn parse_fills_key_and_value_spans() {
+        let source = "port = 8080\n[database.replica]\nhost = \"r\"\n";
+        let parsed = IniAdapter.parse(source).unwrap();
+        let port = parsed.spans[&ConfigPath::new().key("port")];
+        assert_eq!(source_slice(source, port.key.unwrap()), "port");
+        assert_eq!(source_slice(source, port.value), "8080");
+        let host = parsed.spans[&ConfigPath::new().key("database").key("replica").key("host")];
+        assert_eq!(source_slice(source, host.value), "\"r\"");
+        let database = parsed.spans[&ConfigPath::new().key("database")];
+        assert_eq!(source_slice(source, database.key.unwrap()), "database");
+        assert_eq!(source_slice(source, database.value), "[database.replica]");
+        assert_eq!(parsed.spans.len(), 4);
+    }
+
+    #[test]
+    fn parse_refuses_duplicates_and_collisions_with_spans() {
+        for (source, message, token) in [
+            ("a = 1\na = 2\n", "duplicate key 'a'", "a"),
+            ("[s]\n[s]\n", "duplicate section [s]", "[s]"),
+            ("s = 1\n[s.t]\n", "collides with the key 's'", "[s.t]"),
+            ("[s.t]\n[s]\nt = 1\n", "collides with a section", "t"),
+            ("[s\n", "missing its closing ']'", "[s"),
+            ("just words\n", "expected `key = value`", "just words"),
+            ("a = \"open\n", "missing its closing quote", "\"open"),
+            ("a = \"x\" y\n", "after the closing quote", " y"),
+        ] {
+            match IniAdapter.parse(source).unwrap_err() {
+                FormatError::Parse {
+                    message: m, span, ..
+                } => {
+                    assert!(m.contains(message), "{source:?}: {m}");
+                    assert_eq!(source_slice(source, span.unwrap()), token, "{source:?}");
+                }
+                other => panic!("expected Parse, got {other:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn edit_set_replaces_in_place_and_keeps_comments() {
+        let source = "; keep me\nport=1\n\n[db]\n; and me\nurl = old\n";
+        let out = set_edit(source, &ConfigPath::new().key("port"), &Value::Integer(2)).unwrap();
+        assert_eq!(out, "; keep me\nport=2\n\n[db]\n; and me\nurl = old\n");
+        let out = set_edit(
+            source,
+            &ConfigPath::new().key("db").key("url"),
+            &Value::from(" padded"),
+        )
+        .unwrap();
+        assert!(out.ends_with("url = \" padded\"\n"), "{out}");
+        let reparsed = IniAdapter.parse(&out).unwrap().value;
+        assert_eq!(reparsed["db"]["url"], Value::from(" padded"));
+    }
+
+    #[test]
+    fn edit_set_creates_keys_and_sections() {
+        let source = "a = 1\n\n[db]\nurl = x";
+        let out = set_edit(source, &ConfigPath::new().key("b"), &Value::Boolean(true)).unwrap();
+        assert_eq!(out, "a = 1\nb = true\n\n[db]\nurl = x");
+        let out = set_edit(
+            source,
+            &ConfigPath::new().key("db").key("pool"),
+            &Value::Integer(4),
+        )
+        .unwrap();
+        assert_eq!(out, "a = 1\n\n[db]\nurl = x\npool = 4\n");
+        let out = set_edit(
+            source,
+            &ConfigPath::new().key("cache").key("ttl"),
+            &Value::Integer(9),
+        )
+        .unwrap();
+        assert_eq!(out, "a = 1\n\n[db]\nurl = x\n\n[cache]\nttl = 9\n");
+        let out = set_edit("[db]\n", &ConfigPath::new().key("a"), &Value::Integer(1)).unwrap();
+        assert_eq!(out, "a = 1\n\n[db]\n");
+    }
+
+    #[test]
+    fn edit_set_refusals_are_typed() {
+        let tags = Value::Array(vec![Value::from("x")]);
+        for (source, path, value, message) in [
+            (
+                "",
+                ConfigPath::new().key("tags"),
+                &tags,
+                "INI values are scalars",
+            ),
+            (
+                "db = x\n",
+                ConfigPath::new().key("db").key("url"),
+                &Value::from("y"),
+                "path conflict",
+            ),
+            (
+                "[db]\nurl = x\n",
+                ConfigPath::new().key("db"),
+                &Value::from("y"),
+                "has a section at 'db'",
+            ),
+            (
+                "",
+                ConfigPath::new().key("a.b").key("c"),
+                &Value::from("y"),
+                "cannot spell 'a.b'",
+            ),
+        ] {
+            match set_edit(source, &path, value).unwrap_err() {
+                FormatError::Edit { format, message: m } => {
+                    assert_eq!(format, "ini");
+                    assert!(m.contains(message), "{m}");
+                }
+                other => panic!("expected Edit, got {other:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn edit_unset_removes_keys_and_sections() {
+        let source = "a = 1\nb = 2\n[db]\nurl = x\n[db.replica]\nhost = y\n[cache]\nttl = 1\n";
+        let out = IniAdapter
+            .edit(
+                source,
+                FileEdit::Unset {
+                    path: &ConfigPath::new().key("a"),
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            out,
+            "b = 2\n[db]\nurl = x\n[db.replica]\nhost = y\n[cache]\nttl = 1\n"
+        );
+        let out = IniAdapter
+            .edit(
+                source,
+                FileEdit::Unset {
+                    path: &ConfigPath::new().key("db"),
+                },
+            )
+            .unwrap();
+        assert_eq!(out, "a = 1\nb = 2\n[cache]\nttl = 1\n");
+        let missing = IniAdapter
+            .edit(
+                source,
+                FileEdit::Unset {
+                    path: &ConfigPath::new().key("nope").key("deep"),
+                },
+            )
+            .unwrap();
+        assert_eq!(missing, source);
+    }
+
+    #[test]
+    fn edit_rename_respells_keys_and_section_headers() {
+        let source = "; host doc\nhost = x\n[db]\n; url doc\nurl = pg\n[db.replica]\nurl = r\n";
+        let rename =
+            |path: ConfigPath, to| IniAdapter.edit(source, FileEdit::Rename { path: &path, to });
+        assert_eq!(
+            rename(ConfigPath::new().key("host"), "hostname").unwrap(),
+            source.replace("host =", "hostname =")
+        );
+        assert_eq!(
+            rename(ConfigPath::new().key("db"), "database").unwrap(),
+            source.replace("[db", "[database")
+        );
+        assert_eq!(
+            rename(ConfigPath::new().key("db").key("replica"), "mirror").unwrap(),
+            source.replace("db.replica", "db.mirror")
+        );
+        assert_eq!(
+            rename(ConfigPath::new().key("nope"), "other").unwrap(),
+            source
+        );
+        match rename(ConfigPath::new().key("host"), "db").unwrap_err() {
+            FormatError::Edit { message, .. } => assert_eq!(
+                message,
+                "path conflict: existing file already has a value at 'db' (renaming 'host')"
+            ),
+            other => panic!("expected Edit, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn template_renders_sections_comments_and_unspellable_fields() {
+        let schema = Schema::object("App")
+            .doc("Demo app.")
+            .field(
+                "host",
+                Field::string().default("localhost").doc("Host name."),
+            )
+            .field("port", Field::integer().optional())
+            .field(
+                "tags",
+                Field::array_of_type(crate::runtime::LeafType::String),
+            )
+            .nested(
+                "database",
+                Schema::object("Database").field("url", Field::string().default("pg://x")),
+            )
+            .build();
+        let template = IniAdapter.template(&Shape::Object(schema)).unwrap();
+        assert!(template.starts_with("; Demo app.\n\n; Host name.\nhost = localhost\n"));
+        assert!(template.contains(";port = 0\n"), "{template}");
+        assert!(template.contains(UNSPELLABLE), "{template}");
+        assert!(
+            template.contains("[database]\nurl = pg://x\n"),
+            "{template}"
+        );
+        let parsed = IniAdapter.parse(&template).unwrap().value;
+        assert_eq!(parsed["database"]["url"], Value::from("pg://x"));
+        assert!(parsed.as_map().unwrap().get("tags").is_none());
+    }
+
+    #[test]
+    fn serialize_is_an_undeclared_operation() {
+        match IniAdapter.serialize(&Value::Map(Map::new())).unwrap_err() {
+            FormatError::Unsupported(refusal) => {
+                assert_eq!(refusal.format, "ini");
+                assert_eq!(refusal.operation, Operation::Serialize);
+            }
+            other => panic!("expected Unsupported, got {other:?}"),
+        }
+    }
+}
@@ -2,7 +2,9 @@
 //!
 //! This file is the ONLY place in the crate that gives `serde_json` a
 //! *format* role (the crate also serves JSON Schema export, which is not a
-//! config format). Parse is a clapfig-owned walk (ADR-0007): `serde_json`
+//! config format; the [`json5`](super::json5) adapter borrows it only to
+//! quote strings and spell floats). Files that want real comments instead
+//! of the `"//"` convention below use that adapter. Parse is a clapfig-owned walk (ADR-0007): `serde_json`
 //! has no byte offsets, and a second locate-keys pass is the desync
 //! ADR-0005 forbids. `serde_json` stays for serialize and edit
 //! (order-preserving pretty-print, comments-as-data). The file implements
@@ -33,7 +35,7 @@
 //!   a real key). The exported JSON Schema allowlists the `^//` pattern
 //!   so third-party validators accept the generated template — clapfig's
 //!   own validation never sees the keys (they are stripped at parse).
-//! - [`edit`](JsonAdapter::edit): set/unset against existing source text.
+//! - [`edit`](JsonAdapter::edit): set/unset/rename against existing source text.
 //!   Comments are data in this convention, so they survive edits for
 //!   free. **Formatting is normalized** (pretty-printed, two-space
 //!   indent, trailing newline) — the documented, expected behavior;
@@ -102,6 +104,7 @@ impl FormatAdapter for JsonAdapter {
             Operation::EditCreateKey,
             Operation::EditCreateFile,
             Operation::EditUnset,
+            Operation::EditRename,
         ]
     }
 
@@ -154,6 +157,16 @@ impl FormatAdapter for JsonAdapter {
                 let keys = key_segments(path)?;
                 super::edit::unset_at_path(&mut doc, &keys);
             }
+            FileEdit::Rename { path, to } => {
+                let keys = key_segments(path)?;
+                if to.starts_with(COMMENT_PREFIX) {
+                    return Err(FormatError::Edit {
+                        format: FORMAT,
+                        message: reserved_key_message(&format!("'{to}'")),
+                    });
+                }
+                super::edit::rename_at_path(&mut doc, &keys, to)?;
+            }
         }
         Ok(render(&doc))
     }
@@ -855,6 +868,24 @@ impl super::edit::EditDoc for Json {
         self.as_object_mut()
             .is_some_and(|obj| obj.remove(key).is_some())
     }
+
+    /// Rebuilds the object in order with the member respelled; its
+    /// `"//from"` comment key follows it, unless `"//to"` already exists.
+    fn rename_key(&mut self, from: &str, to: &str) {
+        let obj = self.as_object_mut().expect("callers guarantee a container");
+        let (comment_from, comment_to) = (comment_key(from), comment_key(to));
+        let move_comment = !obj.contains_key(&comment_to);
+        for (key, entry) in std::mem::take(obj) {
+            let key = if key == from {
+                to.to_string()
+            } else if move_comment && key == comment_from {
+                comment_to.clone()
+            } else {
+                key
+            };
+            obj.insert(key, entry);
+        }
+    }
 }
 
 /// Insert `leaf` into `obj`, keeping comment keys adjacent to the fields
@@ -1255,6 +1286,7 @@ mod tests {
             Operation::EditCreateKey,
             Operation::EditCreateFile,
             Operation::EditUnset,
+            Operation::EditRename,
         ] {
             assert!(
                 JsonAdapter.supports(operation),
@@ -2731,6 +2763,39 @@ mod tests {
         assert!(unchanged.contains(r#""port": 1"#));
     }
 
+    #[test]
+    fn edit_rename_keeps_position_and_the_fields_comment_key() {
+        let source = "{\n  \"//host\": \"Host doc\",\n  \"host\": \"x\",\n  \"port\": 1\n}\n";
+        let path = ConfigPath::new().key("host");
+        let out = JsonAdapter
+            .edit(
+                source,
+                FileEdit::Rename {
+                    path: &path,
+                    to: "hostname",
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            out,
+            "{\n  \"//hostname\": \"Host doc\",\n  \"hostname\": \"x\",\n  \"port\": 1\n}\n"
+        );
+
+        let err = JsonAdapter
+            .edit(
+                source,
+                FileEdit::Rename {
+                    path: &path,
+                    to: "//x",
+                },
+            )
+            .unwrap_err();
+        assert!(
+            matches!(err, FormatError::Edit { ref message, .. } if message.contains("reserved")),
+            "{err:?}"
+        );
+    }
+
     #[test]
     fn edit_set_non_finite_float_is_typed_serialize_error() {
         let path = ConfigPath::new().key("rate");
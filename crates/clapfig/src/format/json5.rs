@@ -0,0 +1,1801 @@
+//! JSON5 format adapter — a clapfig-owned walk for parse and edit.
+//!
+//! Strict JSON has no comments, so the JSON adapter carries prose in
+//! `"//"` comment keys. This adapter reads the JSON superset people
+//! actually hand-write instead — real comments, trailing commas, bare
+//! keys — under its own name (`json5`) and extensions (`.json5`,
+//! `.jsonc`). JSONC (JSON plus comments and trailing commas) is a subset
+//! of JSON5, so one grammar reads both. Like the JSON adapter, parse is
+//! an owned walk (ADR-0007) that yields the value tree and the
+//! path → span index in one pass (ADR-0005).
+//!
+//! - Comments: `// line` and `/* block */`, anywhere whitespace is
+//!   allowed. They are syntax — they never reach the value tree, and no
+//!   key is reserved for them.
+//! - Member names may be double-quoted, single-quoted, or bare
+//!   identifiers (letters, digits, `_`, `$`; not starting with a digit).
+//! - A trailing comma may close any object or array.
+//! - Strings may be single- or double-quoted and carry JSON5's escapes
+//!   (`\v`, `\0`, `\xHH`, line continuations) on top of JSON's.
+//! - Numbers may carry a leading `+`, a leading or trailing decimal
+//!   point, or a hexadecimal integer spelling (`0x1F`); `Infinity`,
+//!   `-Infinity`, and `NaN` are floats.
+//!
+//! The JSON adapter's baseline mapping rules carry over: `null` is a
+//! typed error naming the key, an integer literal outside `i64` is a
+//! typed range error (never a silent float), a decimal literal
+//! overflowing `f64` is a typed range error, a repeated member name
+//! keeps the last value, and whitespace- or comment-only source parses
+//! as the empty map.
+//!
+//! Declared operations: the full matrix. [`edit`](Json5Adapter::edit)
+//! is a span splice, not a re-render: a replaced value swaps only its
+//! own token, a new member lands as one line in its object (right after
+//! a `// "name": …` comment documenting it, when the object has one),
+//! an unset removes only the member's own line, and a rename respells
+//! only the member's name — every comment and untouched byte survives.
+//! New members follow the document's comma style: trailing commas if
+//! any object in the file already ends with one, none otherwise.
+//! Writers always quote member names and strings with double quotes, so
+//! edited `.jsonc` files stay JSONC.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use crate::runtime::{Schema, Shape, TaggedShape, TaggedVariant};
+use crate::value::{Map, Value};
+
+use super::template::{
+    TemplateRenderer, ValueView, doc_lines, example_shape_value, leaf_annotations, placeholder,
+    tagged_variant_example_schema, walk_level, walk_root,
+};
+use super::{
+    ConfigPath, FileEdit, FormatAdapter, FormatError, Operation, Parsed, PathSegment, Span,
+    SpanEntry, walk_label,
+};
+
+/// The canonical format name used in error messages.
+const FORMAT: &str = "json5";
+
+/// The JSON5 format behind the adapter contract.
+///
+/// Declares every ADR-0002 matrix row. [`parse`](Json5Adapter::parse)
+/// fills the span index in the same pass as the [`Value`] tree, so
+/// unknown-key and `InvalidValue` errors caret JSON5 source like any
+/// other format's. See the [module docs](self) for the accepted syntax
+/// and how edits keep comments.
+pub struct Json5Adapter;
+
+impl FormatAdapter for Json5Adapter {
+    fn name(&self) -> &'static str {
+        FORMAT
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["json5", "jsonc"]
+    }
+
+    fn capabilities(&self) -> &'static [Operation] {
+        &[
+            Operation::Parse,
+            Operation::Template,
+            Operation::Serialize,
+            Operation::EditSet,
+            Operation::EditCreateKey,
+            Operation::EditCreateFile,
+            Operation::EditUnset,
+            Operation::EditRename,
+        ]
+    }
+
+    fn display_entry(&self, key: &str, value: &str) -> String {
+        format!("{}: {value}", quote(key))
+    }
+
+    fn display_comment(&self, line: &str) -> String {
+        format!("// {line}")
+    }
+
+    fn parse(&self, text: &str) -> Result<Parsed, FormatError> {
+        Ok(parse_document(text)?.parsed)
+    }
+
+    fn serialize(&self, value: &Value) -> Result<String, FormatError> {
+        let mut out = String::new();
+        render_value(&mut out, value, "", true);
+        out.push('\n');
+        Ok(out)
+    }
+
+    fn template(&self, shape: &Shape) -> Result<String, FormatError> {
+        let mut body = String::new();
+        for line in shape.field_doc() {
+            push_comment(&mut body, "  ", line);
+        }
+        if !shape.field_doc().is_empty() {
+            body.push('\n');
+        }
+        walk_root(&mut Json5Template, shape, &1, &mut body)?;
+        let mut out = String::from("{\n");
+        out.push_str(body.trim_end());
+        out.push_str("\n}\n");
+        Ok(out)
+    }
+
+    fn edit(&self, source: &str, edit: FileEdit<'_>) -> Result<String, FormatError> {
+        // A missing file is seeded from the template before the edit
+        // reaches this adapter, but direct callers may hand an empty (or
+        // comment-only) document — start those from the empty object,
+        // after any comments.
+        let seeded;
+        let source = if parse_document(source)?.parsed.spans.is_empty() {
+            let newline = if source.is_empty() || source.ends_with('\n') {
+                ""
+            } else {
+                "\n"
+            };
+            seeded = format!("{source}{newline}{{}}\n");
+            seeded.as_str()
+        } else {
+            source
+        };
+        match edit {
+            FileEdit::Set { path, value, .. } => {
+                let keys = super::edit::map_key_segments(path, FORMAT)?;
+                set(source, &keys, value)
+            }
+            FileEdit::Unset { path } => {
+                let keys = super::edit::map_key_segments(path, FORMAT)?;
+                unset(source, &keys)
+            }
+            FileEdit::Rename { path, to } => {
+                let keys = super::edit::map_key_segments(path, FORMAT)?;
+                rename(source, &keys, to)
+            }
+        }
+    }
+}
+
+// --- owned parse walk (ADR-0007) -----------------------------------------
+
+/// Matches the JSON adapter's recursion ceiling so a hostile nest is a
+/// typed parse error, not a stack overflow.
+const MAX_DEPTH: usize = 128;
+
+/// A parsed document: the contract's `{value, spans}` plus the object
+/// layout the span splicer edits against.
+struct Document {
+    parsed: Parsed,
+    objects: BTreeMap<ConfigPath, ObjectLayout>,
+}
+
+/// Where one object's braces and members sit in the source.
+struct ObjectLayout {
+    /// Byte offset of the `{`.
+    open: usize,
+    /// Byte offset of the `}`.
+    close: usize,
+    /// Members in source order; a repeated name appears once per
+    /// occurrence (the last one is the value the tree keeps).
+    members: Vec<Member>,
+}
+
+struct Member {
+    key: String,
+    key_span: Span,
+    value_span: Span,
+    /// Byte offset of the `,` that follows the member, if any.
+    comma: Option<usize>,
+}
+
+/// One-pass JSON5 parse: emit the [`Value`] tree, the span index, and
+/// the object layout together, applying the baseline mapping rules as
+/// the tokens are consumed.
+fn parse_document(text: &str) -> Result<Document, FormatError> {
+    let mut parser = Parser::new(text);
+    parser.skip_trivia()?;
+    // An empty (or comment-only) file is "no config", matching TOML's
+    // empty document. There is no source value to locate, so the index
+    // stays empty.
+    if parser.pos >= text.len() {
+        return Ok(Document {
+            parsed: Parsed::from_value(Value::Map(Map::new())),
+            objects: BTreeMap::new(),
+        });
+    }
+    let value = parser.parse_value(None)?;
+    parser.skip_trivia()?;
+    if parser.pos < text.len() {
+        return parser.fail("unexpected trailing content after JSON5 value");
+    }
+    Ok(Document {
+        parsed: Parsed {
+            value,
+            spans: parser.spans,
+        },
+        objects: parser.objects,
+    })
+}
+
+/// Byte-offset walker over one JSON5 document.
+struct Parser<'a> {
+    text: &'a str,
+    pos: usize,
+    depth: usize,
+    path: Vec<PathSegment>,
+    spans: BTreeMap<ConfigPath, SpanEntry>,
+    objects: BTreeMap<ConfigPath, ObjectLayout>,
+}
+
+impl<'a> Parser<'a> {
+    fn new(text: &'a str) -> Self {
+        Self {
+            text,
+            pos: 0,
+            depth: 0,
+            path: Vec::new(),
+            spans: BTreeMap::new(),
+            objects: BTreeMap::new(),
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.text.as_bytes().get(self.pos).copied()
+    }
+
+    fn peek_char(&self) -> Option<char> {
+        self.text[self.pos..].chars().next()
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.text[self.pos..]
+    }
+
+    /// Skip whitespace (JSON5 admits the Unicode space separators and
+    /// the byte-order mark) and comments.
+    fn skip_trivia(&mut self) -> Result<(), FormatError> {
+        loop {
+            match self.peek_char() {
+                Some(c) if c.is_whitespace() || c == '\u{FEFF}' => self.pos += c.len_utf8(),
+                Some('/') if self.rest().starts_with("//") => {
+                    self.pos = self
+                        .rest()
+                        .find('\n')
+                        .map_or(self.text.len(), |i| self.pos + i);
+                }
+                Some('/') if self.rest().starts_with("/*") => {
+                    let Some(end) = self.rest()[2..].find("*/") else {
+                        return Err(self.parse_error(
+                            "unterminated block comment".into(),
+                            Span {
+                                start: self.pos,
+                                end: self.text.len(),
+                            },
+                        ));
+                    };
+                    self.pos += 2 + end + 2;
+                }
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    fn here(&self) -> Span {
+        if self.pos >= self.text.len() {
+            return Span {
+                start: self.text.len(),
+                end: self.text.len(),
+            };
+        }
+        Span {
+            start: self.pos,
+            end: self.text.ceil_char_boundary(self.pos + 1),
+        }
+    }
+
+    fn parse_error(&self, message: String, span: Span) -> FormatError {
+        FormatError::Parse {
+            format: FORMAT,
+            message,
+            span: Some(span),
+        }
+    }
+
+    fn fail<T>(&self, message: impl Into<String>) -> Result<T, FormatError> {
+        Err(self.parse_error(message.into(), self.here()))
+    }
+
+    fn expect(&mut self, wanted: u8) -> Result<(), FormatError> {
+        match self.peek() {
+            Some(b) if b == wanted => {
+                self.pos += 1;
+                Ok(())
+            }
+            _ => self.fail(format!("expected '{}'", wanted as char)),
+        }
+    }
+
+    fn enter(&mut self) -> Result<(), FormatError> {
+        if self.depth >= MAX_DEPTH {
+            return self.fail("nesting exceeds the JSON5 parse limit");
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
+    fn leave(&mut self) {
+        self.depth -= 1;
+    }
+
+    fn record(&mut self, key: Option<Span>, value: Span) {
+        self.spans.insert(
+            ConfigPath::from(self.path.clone()),
+            SpanEntry { key, value },
+        );
+    }
+
+    /// Drop the current path and every descendant from the span index
+    /// and the layout. Used when a later duplicate member replaces an
+    /// earlier one, so neither can retain paths the tree no longer has.
+    fn drop_current_prefix(&mut self) {
+        let prefix = self.path.as_slice();
+        let keep = |path: &ConfigPath| {
+            let segs = path.segments();
+            !(segs.len() >= prefix.len() && segs[..prefix.len()] == *prefix)
+        };
+        self.spans.retain(|path, _| keep(path));
+        self.objects.retain(|path, _| keep(path));
+    }
+
+    /// Parse one value at the current path, apply baseline rules, and
+    /// record its span. `key` is the member-name token, or `None` on the
+    /// document root and on array elements (ADR-0006).
+    fn parse_value(&mut self, key: Option<Span>) -> Result<Value, FormatError> {
+        self.skip_trivia()?;
+        let start = self.pos;
+        let value = match self.peek() {
+            Some(b'{') => self.parse_object()?,
+            Some(b'[') => self.parse_array()?,
+            Some(quote @ (b'"' | b'\'')) => Value::String(self.parse_string(quote)?.0),
+            Some(b't') => {
+                self.expect_literal("true")?;
+                Value::Boolean(true)
+            }
+            Some(b'f') => {
+                self.expect_literal("false")?;
+                Value::Boolean(false)
+            }
+            Some(b'n') => {
+                let span = self.expect_literal("null")?;
+                return Err(self.parse_error(
+                    format!(
+                        "null at {} is not a configuration value: absence expresses unset — omit the key instead",
+                        walk_label(&self.path)
+                    ),
+                    span,
+                ));
+            }
+            Some(b'+' | b'-' | b'.' | b'0'..=b'9' | b'I' | b'N') => self.parse_number()?,
+            Some(_) => return self.fail("expected a JSON5 value"),
+            None => return self.fail("unexpected end of JSON5 input"),
+        };
+        self.record(
+            key,
+            Span {
+                start,
+                end: self.pos,
+            },
+        );
+        Ok(value)
+    }
+
+    fn parse_object(&mut self) -> Result<Value, FormatError> {
+        self.enter()?;
+        let open = self.pos;
+        self.expect(b'{')?;
+        let mut map = Map::new();
+        let mut members = Vec::new();
+        loop {
+            self.skip_trivia()?;
+            if self.peek() == Some(b'}') {
+                break;
+            }
+            let (key, key_span) = self.parse_key()?;
+            self.skip_trivia()?;
+            self.expect(b':')?;
+            self.path.push(PathSegment::Key(key.clone()));
+            if map.contains_key(&key) {
+                self.drop_current_prefix();
+            }
+            self.skip_trivia()?;
+            let value_start = self.pos;
+            let converted = self.parse_value(Some(key_span))?;
+            let value_span = Span {
+                start: value_start,
+                end: self.pos,
+            };
+            self.path.pop();
+            map.insert(key.clone(), converted);
+            self.skip_trivia()?;
+            let comma = match self.peek() {
+                Some(b',') => {
+                    self.pos += 1;
+                    Some(self.pos - 1)
+                }
+                Some(b'}') => None,
+                _ => return self.fail("expected ',' or '}' after object member"),
+            };
+            members.push(Member {
+                key,
+                key_span,
+                value_span,
+                comma,
+            });
+            if comma.is_none() {
+                self.skip_trivia()?;
+                break;
+            }
+        }
+        let close = self.pos;
+        self.expect(b'}')?;
+        self.objects.insert(
+            ConfigPath::from(self.path.clone()),
+            ObjectLayout {
+                open,
+                close,
+                members,
+            },
+        );
+        self.leave();
+        Ok(Value::Map(map))
+    }
+
+    fn parse_array(&mut self) -> Result<Value, FormatError> {
+        self.enter()?;
+        self.expect(b'[')?;
+        let mut items = Vec::new();
+        loop {
+            self.skip_trivia()?;
+            if self.peek() == Some(b']') {
+                self.pos += 1;
+                break;
+            }
+            self.path.push(PathSegment::Index(items.len()));
+            let item = self.parse_value(None)?;
+            self.path.pop();
+            items.push(item);
+            self.skip_trivia()?;
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b']') => {
+                    self.pos += 1;
+                    break;
+                }
+                _ => return self.fail("expected ',' or ']' after array element"),
+            }
+        }
+        self.leave();
+        Ok(Value::Array(items))
+    }
+
+    /// A member name: a quoted string or a bare identifier.
+    fn parse_key(&mut self) -> Result<(String, Span), FormatError> {
+        match self.peek() {
+            Some(quote @ (b'"' | b'\'')) => self.parse_string(quote),
+            _ => {
+                let start = self.pos;
+                let mut chars = self.rest().char_indices();
+                match chars.next() {
+                    Some((_, c)) if is_identifier_start(c) => {}
+                    _ => return self.fail("expected a member name"),
+                }
+                let len = chars
+                    .find(|(_, c)| !is_identifier_part(*c))
+                    .map_or(self.rest().len(), |(i, _)| i);
+                self.pos += len;
+                Ok((
+                    self.text[start..self.pos].to_string(),
+                    Span {
+                        start,
+                        end: self.pos,
+                    },
+                ))
+            }
+        }
+    }
+
+    fn expect_literal(&mut self, literal: &str) -> Result<Span, FormatError> {
+        let start = self.pos;
+        if !self.rest().starts_with(literal) {
+            return self.fail(format!("expected {literal}"));
+        }
+        self.pos += literal.len();
+        if self.peek_char().is_some_and(is_identifier_part) {
+            return self.fail(format!("expected {literal}"));
+        }
+        Ok(Span {
+            start,
+            end: self.pos,
+        })
+    }
+
+    fn parse_string(&mut self, quote: u8) -> Result<(String, Span), FormatError> {
+        let start = self.pos;
+        self.pos += 1;
+        let mut out = String::new();
+        loop {
+            let Some(c) = self.peek_char() else {
+                return Err(self.parse_error(
+                    "unterminated string".into(),
+                    Span {
+                        start,
+                        end: self.text.len(),
+                    },
+                ));
+            };
+            match c {
+                _ if c as u32 == u32::from(quote) => {
+                    self.pos += 1;
+                    return Ok((
+                        out,
+                        Span {
+                            start,
+                            end: self.pos,
+                        },
+                    ));
+                }
+                '\\' => self.parse_escape(&mut out)?,
+                '\n' | '\r' => return self.fail("unescaped line break in string"),
+                _ => {
+                    out.push(c);
+                    self.pos += c.len_utf8();
+                }
+            }
+        }
+    }
+
+    fn parse_escape(&mut self, out: &mut String) -> Result<(), FormatError> {
+        self.pos += 1;
+        let Some(c) = self.peek_char() else {
+            return self.fail("unterminated string escape");
+        };
+        self.pos += c.len_utf8();
+        match c {
+            'b' => out.push('\u{0008}'),
+            'f' => out.push('\u{000c}'),
+            'n' => out.push('\n'),
+            'r' => out.push('\r'),
+            't' => out.push('\t'),
+            'v' => out.push('\u{000b}'),
+            '0' if !self.peek().is_some_and(|b| b.is_ascii_digit()) => out.push('\0'),
+            'x' => {
+                let unit = self.parse_hex(2)?;
+                out.push(char::from_u32(unit).expect("two hex digits are a scalar"));
+            }
+            'u' => self.parse_unicode_escape(out)?,
+            // A line continuation: the escaped line break is dropped.
+            '\r' => {
+                if self.peek() == Some(b'\n') {
+                    self.pos += 1;
+                }
+            }
+            '\n' | '\u{2028}' | '\u{2029}' => {}
+            '1'..='9' | '0' => return self.fail("digit escapes are not allowed in JSON5 strings"),
+            // Every other character escapes to itself (`\"`, `\'`, `\\`, `\/`).
+            other => out.push(other),
+        }
+        Ok(())
+    }
+
+    fn parse_hex(&mut self, digits: usize) -> Result<u32, FormatError> {
+        let Some(hex) = self.text.get(self.pos..self.pos + digits) else {
+            return self.fail("unterminated hex escape");
+        };
+        if !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return self.fail("invalid hex escape");
+        }
+        let unit = u32::from_str_radix(hex, 16).expect("ASCII hex digits parse as a u32");
+        self.pos += digits;
+        Ok(unit)
+    }
+
+    fn parse_unicode_escape(&mut self, out: &mut String) -> Result<(), FormatError> {
+        let unit = self.parse_hex(4)?;
+        if (0xD800..=0xDBFF).contains(&unit) {
+            if !self.rest().starts_with("\\u") {
+                return self.fail("unpaired UTF-16 surrogate");
+            }
+            self.pos += 2;
+            let low = self.parse_hex(4)?;
+            if !(0xDC00..=0xDFFF).contains(&low) {
+                return self.fail("unpaired UTF-16 surrogate");
+            }
+            let cp = 0x10000 + ((unit - 0xD800) << 10) + (low - 0xDC00);
+            out.push(char::from_u32(cp).expect("surrogate pair decodes to a scalar"));
+        } else if (0xDC00..=0xDFFF).contains(&unit) {
+            return self.fail("unpaired UTF-16 surrogate");
+        } else {
+            out.push(char::from_u32(unit).expect("non-surrogate unit is a scalar"));
+        }
+        Ok(())
+    }
+
+    fn parse_number(&mut self) -> Result<Value, FormatError> {
+        let start = self.pos;
+        let negative = self.peek() == Some(b'-');
+        if matches!(self.peek(), Some(b'+' | b'-')) {
+            self.pos += 1;
+        }
+        if self.rest().starts_with("Infinity") {
+            self.expect_literal("Infinity")?;
+            return Ok(Value::Float(if negative {
+                f64::NEG_INFINITY
+            } else {
+                f64::INFINITY
+            }));
+        }
+        if self.rest().starts_with("NaN") {
+            self.expect_literal("NaN")?;
+            return Ok(Value::Float(f64::NAN));
+        }
+        if self.rest().starts_with("0x") || self.rest().starts_with("0X") {
+            self.pos += 2;
+            let digits_start = self.pos;
+            while self.peek().is_some_and(|b| b.is_ascii_hexdigit()) {
+                self.pos += 1;
+            }
+            if self.pos == digits_start {
+                return self.fail("expected a hex digit");
+            }
+            let digits = &self.text[digits_start..self.pos];
+            let parsed = u64::from_str_radix(digits, 16).ok().and_then(|magnitude| {
+                if negative {
+                    0i64.checked_sub_unsigned(magnitude)
+                } else {
+                    i64::try_from(magnitude).ok()
+                }
+            });
+            return parsed.map(Value::Integer).ok_or_else(|| {
+                self.integer_range_error(Span {
+                    start,
+                    end: self.pos,
+                })
+            });
+        }
+        let int_start = self.pos;
+        while self.peek().is_some_and(|b| b.is_ascii_digit()) {
+            self.pos += 1;
+        }
+        let int_digits = self.pos - int_start;
+        if int_digits > 1 && self.text.as_bytes()[int_start] == b'0' {
+            return Err(self.parse_error(
+                "leading zeros are not allowed in JSON5 numbers".into(),
+                Span {
+                    start,
+                    end: self.pos,
+                },
+            ));
+        }
+        let mut is_float = false;
+        let mut frac_digits = 0;
+        if self.peek() == Some(b'.') {
+            is_float = true;
+            self.pos += 1;
+            while self.peek().is_some_and(|b| b.is_ascii_digit()) {
+                self.pos += 1;
+                frac_digits += 1;
+            }
+        }
+        if int_digits == 0 && frac_digits == 0 {
+            return self.fail("expected a JSON5 number");
+        }
+        if matches!(self.peek(), Some(b'e' | b'E')) {
+            is_float = true;
+            self.pos += 1;
+            if matches!(self.peek(), Some(b'+' | b'-')) {
+                self.pos += 1;
+            }
+            if !self.peek().is_some_and(|b| b.is_ascii_digit()) {
+                return self.fail("expected a digit in the exponent");
+            }
+            while self.peek().is_some_and(|b| b.is_ascii_digit()) {
+                self.pos += 1;
+            }
+        }
+        let span = Span {
+            start,
+            end: self.pos,
+        };
+        let lexeme = &self.text[start..self.pos];
+        if is_float {
+            return match lexeme.parse::<f64>() {
+                Ok(f) if f.is_finite() => Ok(Value::Float(f)),
+                // Overflow (e.g. 1e999) becomes infinity; JSON5 spells
+                // infinity `Infinity`, so an overflowing decimal is a
+                // typed range error rather than a silent infinity.
+                _ => Err(self.parse_error(
+                    format!(
+                        "float {lexeme} at {} is out of range: the value model's floats are 64-bit (f64)",
+                        walk_label(&self.path)
+                    ),
+                    span,
+                )),
+            };
+        }
+        lexeme
+            .parse::<i64>()
+            .map(Value::Integer)
+            .map_err(|_| self.integer_range_error(span))
+    }
+
+    fn integer_range_error(&self, span: Span) -> FormatError {
+        self.parse_error(
+            format!(
+                "integer {} at {} is out of range: the value model's integers are 64-bit signed (i64)",
+                &self.text[span.start..span.end],
+                walk_label(&self.path)
+            ),
+            span,
+        )
+    }
+}
+
+fn is_identifier_start(c: char) -> bool {
+    c.is_alphabetic() || c == '_' || c == '$'
+}
+
+fn is_identifier_part(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '$'
+}
+
+// --- rendering -----------------------------------------------------------
+
+/// A member name or string, double-quoted with JSON escapes — valid in
+/// JSON5 and JSONC alike.
+fn quote(s: &str) -> String {
+    serde_json::to_string(s).expect("serializing a string to JSON cannot fail")
+}
+
+/// One value on a single line (arrays and maps included).
+fn inline(value: &Value) -> String {
+    match value {
+        Value::String(s) => quote(s),
+        Value::Integer(i) => i.to_string(),
+        Value::Float(f) if f.is_nan() => "NaN".into(),
+        Value::Float(f) if f.is_infinite() => {
+            if *f > 0.0 { "Infinity" } else { "-Infinity" }.into()
+        }
+        Value::Float(f) => serde_json::Number::from_f64(*f)
+            .expect("finite floats are JSON numbers")
+            .to_string(),
+        Value::Boolean(b) => b.to_string(),
+        // TOML lexical form as a string; the schema-driven coercion pass
+        // reads it back into the Datetime variant (ADR-0001).
+        Value::Datetime(d) => quote(&crate::value::lexical_string(d)),
+        Value::Array(items) => {
+            let items: Vec<String> = items.iter().map(inline).collect();
+            format!("[{}]", items.join(", "))
+        }
+        Value::Map(map) if map.is_empty() => "{}".into(),
+        Value::Map(map) => {
+            let members: Vec<String> = map
+                .iter()
+                .map(|(key, value)| format!("{}: {}", quote(key), inline(value)))
+                .collect();
+            format!("{{{}}}", members.join(", "))
+        }
+    }
+}
+
+/// Render `value` as it sits after `"key": ` on a line indented by
+/// `indent`: non-empty maps open one member per line, everything else
+/// stays [`inline`]. `trailing` puts a comma after every member.
+fn render_value(out: &mut String, value: &Value, indent: &str, trailing: bool) {
+    match value {
+        Value::Map(map) if !map.is_empty() => {
+            let inner = format!("{indent}  ");
+            out.push_str("{\n");
+            for (i, (key, entry)) in map.iter().enumerate() {
+                let _ = write!(out, "{inner}{}: ", quote(key));
+                render_value(out, entry, &inner, trailing);
+                if trailing || i + 1 < map.len() {
+                    out.push(',');
+                }
+                out.push('\n');
+            }
+            let _ = write!(out, "{indent}}}");
+        }
+        _ => out.push_str(&inline(value)),
+    }
+}
+
+// --- edit ----------------------------------------------------------------
+
+/// One pending replacement of `start..end` with `text`.
+struct Splice {
+    start: usize,
+    end: usize,
+    text: String,
+}
+
+/// Apply `splices` (non-overlapping) to `source`, back to front so
+/// earlier offsets stay valid.
+fn apply(source: &str, mut splices: Vec<Splice>) -> String {
+    splices.sort_by_key(|s| std::cmp::Reverse(s.start));
+    let mut out = source.to_string();
+    for splice in splices {
+        out.replace_range(splice.start..splice.end, &splice.text);
+    }
+    out
+}
+
+fn line_start(text: &str, pos: usize) -> usize {
+    text[..pos].rfind('\n').map_or(0, |i| i + 1)
+}
+
+/// The whitespace that indents the line containing `pos`.
+fn indent_of(text: &str, pos: usize) -> &str {
+    let line = &text[line_start(text, pos)..pos];
+    &line[..line.len() - line.trim_start().len()]
+}
+
+/// Whether only whitespace precedes `pos` on its line.
+fn starts_line(text: &str, pos: usize) -> bool {
+    text[line_start(text, pos)..pos].trim().is_empty()
+}
+
+/// The end of the line containing `pos`, past its newline.
+fn line_end(text: &str, pos: usize) -> usize {
+    text[pos..].find('\n').map_or(text.len(), |i| pos + i + 1)
+}
+
+fn conflict(message: String) -> FormatError {
+    FormatError::Edit {
+        format: FORMAT,
+        message,
+    }
+}
+
+/// Walk `parents` through the object layout. Returns the deepest object
+/// reached and how many parents it took to get there (`parents.len()`
+/// when every parent exists). A parent holding a non-object value is a typed conflict —
+/// the same contract as the shared walker in `format::edit`.
+fn descend<'d>(
+    doc: &'d Document,
+    parents: &[&str],
+    display_path: &str,
+) -> Result<(&'d ObjectLayout, usize), FormatError> {
+    let mut path = ConfigPath::new();
+    let Some(mut current) = doc.objects.get(&path) else {
+        return Err(conflict(format!(
+            "path conflict: leaf parent is not an object (setting '{display_path}')"
+        )));
+    };
+    for (depth, segment) in parents.iter().enumerate() {
+        if !current.members.iter().any(|m| m.key == *segment) {
+            return Ok((current, depth));
+        }
+        path = path.key(*segment);
+        match doc.objects.get(&path) {
+            Some(next) => current = next,
+            None => {
+                return Err(conflict(format!(
+                    "path conflict: existing document has a non-object value at '{segment}' (setting '{display_path}')"
+                )));
+            }
+        }
+    }
+    Ok((current, parents.len()))
+}
+
+fn set(source: &str, keys: &[&str], value: &Value) -> Result<String, FormatError> {
+    let doc = parse_document(source)?;
+    let (leaf, parents) = keys
+        .split_last()
+        .expect("ConfigPath edits always carry at least one segment");
+    let display_path = keys.join(".");
+    let (object, reached) = descend(&doc, parents, &display_path)?;
+    let trailing = doc
+        .objects
+        .values()
+        .any(|o| o.members.last().is_some_and(|m| m.comma.is_some()));
+    if reached == parents.len()
+        && let Some(member) = object.members.iter().rev().find(|m| m.key == *leaf)
+    {
+        let mut text = String::new();
+        render_value(
+            &mut text,
+            value,
+            indent_of(source, member.key_span.start),
+            trailing,
+        );
+        return Ok(apply(
+            source,
+            vec![Splice {
+                start: member.value_span.start,
+                end: member.value_span.end,
+                text,
+            }],
+        ));
+    }
+    // Everything from the first missing key down is one new member.
+    let missing = &keys[reached..];
+    let nested = missing[1..].iter().rev().fold(value.clone(), |inner, key| {
+        let mut map = Map::new();
+        map.insert((*key).to_string(), inner);
+        Value::Map(map)
+    });
+    Ok(apply(
+        source,
+        insert_member(source, object, missing[0], &nested, trailing),
+    ))
+}
+
+/// The splices that add `"key": value` to `object`.
+///
+/// Multi-line objects get the member on a line of its own: right after a
+/// `// "key": …` comment line when the object has one (a generated
+/// template documents defaultless fields that way), otherwise before the
+/// closing brace. Single-line objects get it inline after the last
+/// member. Whichever member ends up before the new one gains a comma if
+/// it lacks one.
+fn insert_member(
+    source: &str,
+    object: &ObjectLayout,
+    key: &str,
+    value: &Value,
+    trailing: bool,
+) -> Vec<Splice> {
+    let mut splices = Vec::new();
+    let close_on_own_line = starts_line(source, object.close);
+    let multi_line = close_on_own_line
+        && object
+            .members
+            .iter()
+            .all(|m| starts_line(source, m.key_span.start));
+    if !multi_line {
+        let (at, lead) = match object.members.last() {
+            Some(last) => match last.comma {
+                Some(comma) => (comma + 1, " "),
+                None => (last.value_span.end, ", "),
+            },
+            None => {
+                // `{}` (or `{ /* … */ }`): open it up into a block.
+                let indent = indent_of(source, object.open).to_string();
+                let inner = format!("{indent}  ");
+                let mut text = format!("\n{inner}{}: ", quote(key));
+                render_value(&mut text, value, &inner, trailing);
+                if trailing {
+                    text.push(',');
+                }
+                let _ = write!(text, "\n{indent}");
+                let interior = &source[object.open + 1..object.close];
+                let end = if interior.trim().is_empty() {
+                    object.close
+                } else {
+                    object.open + 1
+                };
+                splices.push(Splice {
+                    start: object.open + 1,
+                    end,
+                    text,
+                });
+                return splices;
+            }
+        };
+        let mut text = format!("{lead}{}: ", quote(key));
+        render_value(&mut text, value, "", trailing);
+        if object.members.last().is_some_and(|m| m.comma.is_some()) {
+            text.push(',');
+        }
+        splices.push(Splice {
+            start: at,
+            end: at,
+            text,
+        });
+        return splices;
+    }
+
+    let (at, indent) = match documenting_comment(source, object, key) {
+        Some(comment) => (
+            line_end(source, comment),
+            indent_of(source, comment).to_string(),
+        ),
+        None => {
+            let indent = match object.members.last() {
+                Some(last) => indent_of(source, last.key_span.start).to_string(),
+                None => format!("{}  ", indent_of(source, object.close)),
+            };
+            (line_start(source, object.close), indent)
+        }
+    };
+    let before = object.members.iter().rev().find(|m| m.value_span.end <= at);
+    let has_next = object.members.iter().any(|m| m.key_span.start >= at);
+    if let Some(before) = before
+        && before.comma.is_none()
+    {
+        splices.push(Splice {
+            start: before.value_span.end,
+            end: before.value_span.end,
+            text: ",".into(),
+        });
+    }
+    let mut text = format!("{indent}{}: ", quote(key));
+    render_value(&mut text, value, &indent, trailing);
+    if trailing || has_next {
+        text.push(',');
+    }
+    text.push('\n');
+    // A template-generated file may end without a newline after the
+    // comment; keep the new member on its own line.
+    if at > 0 && !source[..at].ends_with('\n') {
+        text.insert(0, '\n');
+    }
+    splices.push(Splice {
+        start: at,
+        end: at,
+        text,
+    });
+    splices
+}
+
+/// The offset of a `// "key": …` comment directly inside `object` (not
+/// inside one of its member values), if any.
+fn documenting_comment(source: &str, object: &ObjectLayout, key: &str) -> Option<usize> {
+    let needle = format!("// {}:", quote(key));
+    let mut pos = object.open + 1;
+    while pos < object.close {
+        let end = line_end(source, pos).min(object.close);
+        let line = &source[pos..end];
+        let trimmed = line.trim_start();
+        let at = pos + (line.len() - trimmed.len());
+        let inside_value = object
+            .members
+            .iter()
+            .any(|m| m.value_span.start <= at && at < m.value_span.end);
+        if !inside_value && trimmed.starts_with(&needle) {
+            return Some(at);
+        }
+        pos = end;
+    }
+    None
+}
+
+/// Remove the member at `keys`. A missing parent or leaf is a no-op; a
+/// repeated member name loses every occurrence.
+fn unset(source: &str, keys: &[&str]) -> Result<String, FormatError> {
+    let (leaf, parents) = keys
+        .split_last()
+        .expect("ConfigPath edits always carry at least one segment");
+    let mut text = source.to_string();
+    loop {
+        let doc = parse_document(&text)?;
+        let mut path = ConfigPath::new();
+        for segment in parents {
+            path = path.key(*segment);
+        }
+        let Some(object) = doc.objects.get(&path) else {
+            return Ok(text);
+        };
+        let Some(index) = object.members.iter().rposition(|m| m.key == *leaf) else {
+            return Ok(text);
+        };
+        text = apply(&text, remove_member(&text, object, index));
+    }
+}
+
+/// Respell the member at `keys` as `to`. Only its name token changes —
+/// every occurrence of a repeated name — so its value and the comments
+/// around and inside it stay put. A missing parent or leaf is a no-op.
+fn rename(source: &str, keys: &[&str], to: &str) -> Result<String, FormatError> {
+    let (leaf, parents) = keys
+        .split_last()
+        .expect("ConfigPath edits always carry at least one segment");
+    let doc = parse_document(source)?;
+    let path = parents.iter().fold(ConfigPath::new(), |p, s| p.key(*s));
+    let Some(object) = doc.objects.get(&path) else {
+        return Ok(source.to_string());
+    };
+    if *leaf == to || !object.members.iter().any(|m| m.key == *leaf) {
+        return Ok(source.to_string());
+    }
+    if object.members.iter().any(|m| m.key == to) {
+        return Err(super::edit::rename_conflict(FORMAT, "document", keys, to));
+    }
+    let splices = object
+        .members
+        .iter()
+        .filter(|m| m.key == *leaf)
+        .map(|m| Splice {
+            start: m.key_span.start,
+            end: m.key_span.end,
+            text: quote(to),
+        })
+        .collect();
+    Ok(apply(source, splices))
+}
+
+/// The splices that remove `object.members[index]`: its whole line when
+/// it sits on one of its own (a trailing `//` comment on that line goes
+/// with it), otherwise just the member and its comma. When the member
+/// was the last and had no comma, the comma before it goes too, so the
+/// object keeps its comma style.
+fn remove_member(source: &str, object: &ObjectLayout, index: usize) -> Vec<Splice> {
+    let member = &object.members[index];
+    let mut splices = Vec::new();
+    let mut start = member.key_span.start;
+    let mut end = member.comma.map_or(member.value_span.end, |c| c + 1);
+    let rest_end = line_end(source, end);
+    let rest = source[end..rest_end].trim();
+    let own_line = starts_line(source, start) && (rest.is_empty() || rest.starts_with("//"));
+    if own_line {
+        start = line_start(source, start);
+        end = rest_end;
+    } else {
+        while source[end..].starts_with([' ', '\t']) {
+            end += 1;
+        }
+    }
+    let previous_comma = (member.comma.is_none() && index > 0)
+        .then(|| object.members[index - 1].comma)
+        .flatten();
+    match previous_comma {
+        Some(comma) if !own_line && source[comma + 1..start].trim().is_empty() => {
+            start = comma;
+            end = member.value_span.end;
+        }
+        Some(comma) => splices.push(Splice {
+            start: comma,
+            end: comma + 1,
+            text: String::new(),
+        }),
+        None => {}
+    }
+    splices.push(Splice {
+        start,
+        end,
+        text: String::new(),
+    });
+    splices
+}
+
+// --- template emission ---------------------------------------------------
+
+/// The JSON5 template renderer: one `"key": value,` member per line,
+/// indented by the context depth, with documentation as real `//`
+/// comments. Defaultless leaves and container examples are commented-out
+/// members a user uncomments in place — every member carries a trailing
+/// comma, so uncommenting never needs a comma fix-up. Declaration order,
+/// like the JSON template: JSON5 has no section-header rule forcing
+/// leaves first.
+struct Json5Template;
+
+/// Append one `//` comment line at `indent` (`//` alone for blank lines).
+fn push_comment(out: &mut String, indent: &str, line: &str) {
+    let trimmed = line.trim_end();
+    if trimmed.is_empty() {
+        let _ = writeln!(out, "{indent}//");
+    } else {
+        let _ = writeln!(out, "{indent}// {trimmed}");
+    }
+}
+
+/// Append `block` with every non-blank line commented out after its
+/// indentation.
+fn push_commented_block(out: &mut String, block: &str) {
+    for line in block.lines() {
+        let body = line.trim_start();
+        if body.is_empty() {
+            out.push('\n');
+        } else {
+            let indent = &line[..line.len() - body.len()];
+            let _ = writeln!(out, "{indent}// {body}");
+        }
+    }
+}
+
+/// The example value for one tagged variant: the tag plus the variant's
+/// fields, placeholders where no default is declared.
+fn variant_example(tagged: &TaggedShape, variant: &TaggedVariant) -> Value {
+    let example = tagged_variant_example_schema(tagged, variant);
+    let mut map = Map::new();
+    for nf in &example.fields {
+        map.insert(nf.name.clone(), example_shape_value(&nf.field));
+    }
+    Value::Map(map)
+}
+
+/// One example value per line the template comments for a container
+/// entry: one per tagged variant (directly, or as the item of an
+/// array), otherwise the shared one-entry example.
+fn entry_examples(item: &Shape) -> Vec<Value> {
+    match item {
+        Shape::Tagged(tagged) => tagged
+            .variants
+            .iter()
+            .map(|variant| variant_example(tagged, variant))
+            .collect(),
+        Shape::Array(array) => match array.item.as_ref() {
+            Shape::Tagged(tagged) => tagged
+                .variants
+                .iter()
+                .map(|variant| Value::Array(vec![variant_example(tagged, variant)]))
+                .collect(),
+            _ => vec![example_shape_value(item)],
+        },
+        _ => vec![example_shape_value(item)],
+    }
+}
+
+/// `{"<key>": example}` — a map entry example keyed by the placeholder.
+fn keyed(example: Value) -> Value {
+    let mut map = Map::new();
+    map.insert("<key>".into(), example);
+    Value::Map(map)
+}
+
+impl TemplateRenderer for Json5Template {
+    type Ctx = usize;
+    type Out = String;
+
+    const LEAVES_FIRST: bool = false;
+
+    fn leaf(
+        &mut self,
+        out: &mut String,
+        depth: &usize,
+        name: &str,
+        field: ValueView<'_>,
+    ) -> Result<(), FormatError> {
+        let indent = "  ".repeat(*depth);
+        for line in leaf_annotations(field, "JSON5", &mut |v| Ok(inline(v)))? {
+            push_comment(out, &indent, &line);
+        }
+        match field.default {
+            Some(value) => {
+                let _ = write!(out, "{indent}{}: ", quote(name));
+                render_value(out, value, &indent, true);
+                out.push_str(",\n");
+            }
+            None => {
+                let hint = placeholder(field.shape, &mut |v| Ok(inline(v)))?;
+                let _ = writeln!(out, "{indent}// {}: {hint},", quote(name));
+            }
+        }
+        out.push('\n');
+        Ok(())
+    }
+
+    fn nested(
+        &mut self,
+        out: &mut String,
+        depth: &usize,
+        name: &str,
+        child: &Schema,
+    ) -> Result<(), FormatError> {
+        let indent = "  ".repeat(*depth);
+        for line in &child.doc {
+            push_comment(out, &indent, line);
+        }
+        let mut body = String::new();
+        walk_level(self, child, &(depth + 1), &mut body)?;
+        let _ = writeln!(out, "{indent}{}: {{", quote(name));
+        if !body.trim().is_empty() {
+            out.push_str(body.trim_end_matches('\n'));
+            out.push('\n');
+        }
+        let _ = writeln!(out, "{indent}}},");
+        out.push('\n');
+        Ok(())
+    }
+
+    fn array_of(
+        &mut self,
+        out: &mut String,
+        depth: &usize,
+        name: &str,
+        item: &Shape,
+    ) -> Result<(), FormatError> {
+        // Entry count is the user's call, so no real member is emitted
+        // (an absent array-of resolves to the empty list); the comment
+        // carries a one-entry example (one per tagged variant).
+        let indent = "  ".repeat(*depth);
+        for line in doc_lines(item.field_doc()) {
+            push_comment(out, &indent, &line);
+        }
+        for example in entry_examples(item) {
+            let example = Value::Array(vec![example]);
+            let _ = writeln!(out, "{indent}// {}: {},", quote(name), inline(&example));
+        }
+        out.push('\n');
+        Ok(())
+    }
+
+    fn map_of(
+        &mut self,
+        out: &mut String,
+        depth: &usize,
+        name: &str,
+        item: &Shape,
+    ) -> Result<(), FormatError> {
+        // Entry keys are user-supplied, so no real member is emitted (an
+        // absent map-of resolves to the empty map); the comment carries a
+        // placeholder-keyed example (one per tagged variant).
+        let indent = "  ".repeat(*depth);
+        for line in doc_lines(item.field_doc()) {
+            push_comment(out, &indent, &line);
+        }
+        for example in entry_examples(item) {
+            let _ = writeln!(
+                out,
+                "{indent}// {}: {},",
+                quote(name),
+                inline(&keyed(example))
+            );
+        }
+        out.push('\n');
+        Ok(())
+    }
+
+    fn root_map(
+        &mut self,
+        out: &mut String,
+        depth: &usize,
+        item: &Shape,
+        _doc: &[String],
+    ) -> Result<(), FormatError> {
+        // Root-map prose is already emitted by `template`; the entry
+        // example is a member of the document object itself.
+        let indent = "  ".repeat(*depth);
+        if item.is_value_field() {
+            let field = ValueView::from_shape(item);
+            for line in leaf_annotations(field, "JSON5", &mut |v| Ok(inline(v)))? {
+                push_comment(out, &indent, &line);
+            }
+        } else {
+            for line in doc_lines(item.field_doc()) {
+                push_comment(out, &indent, &line);
+            }
+        }
+        for example in entry_examples(item) {
+            let _ = writeln!(out, "{indent}// \"<key>\": {},", inline(&example));
+        }
+        Ok(())
+    }
+
+    fn tagged(
+        &mut self,
+        out: &mut String,
+        depth: &usize,
+        name: Option<&str>,
+        tagged: &TaggedShape,
+    ) -> Result<(), FormatError> {
+        let indent = "  ".repeat(*depth);
+        for line in doc_lines(&tagged.doc) {
+            push_comment(out, &indent, &line);
+        }
+        match name {
+            Some(name) => {
+                for variant in &tagged.variants {
+                    let example = variant_example(tagged, variant);
+                    let _ = writeln!(out, "{indent}// {}: {},", quote(name), inline(&example));
+                }
+                out.push('\n');
+            }
+            // A tagged document root: the members of each variant,
+            // commented, directly in the document object.
+            None => {
+                for (i, variant) in tagged.variants.iter().enumerate() {
+                    if i > 0 {
+                        out.push('\n');
+                    }
+                    let example = tagged_variant_example_schema(tagged, variant);
+                    let mut buf = String::new();
+                    walk_level(self, &example, depth, &mut buf)?;
+                    push_commented_block(out, buf.trim_end_matches('\n'));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::SetTarget;
+    use super::*;
+    use crate::runtime::Field;
+
+    fn source_slice(source: &str, span: Span) -> &str {
+        &source[span.start..span.end]
+    }
+
+    fn set_edit(source: &str, path: &ConfigPath, value: &Value) -> Result<String, FormatError> {
+        Json5Adapter.edit(
+            source,
+            FileEdit::Set {
+                path,
+                value,
+                target: SetTarget::MissingKey,
+            },
+        )
+    }
+
+    fn unset_edit(source: &str, path: &ConfigPath) -> String {
+        Json5Adapter.edit(source, FileEdit::Unset { path }).unwrap()
+    }
+
+    fn key(path: &str) -> ConfigPath {
+        path.split('.')
+            .fold(ConfigPath::new(), |path, segment| path.key(segment))
+    }
+
+    #[test]
+    fn json5_adapter_declares_its_matrix_rows() {
+        for operation in [
+            Operation::Parse,
+            Operation::Template,
+            Operation::Serialize,
+            Operation::EditSet,
+            Operation::EditCreateKey,
+            Operation::EditCreateFile,
+            Operation::EditUnset,
+            Operation::EditRename,
+        ] {
+            assert!(
+                Json5Adapter.supports(operation),
+                "json5 should declare {operation}"
+            );
+        }
+    }
+
+    // --- parse -----------------------------------------------------------
+
+    #[test]
+    fn parse_accepts_comments_trailing_commas_and_bare_keys() {
+        let source = r#"// settings
+{
+  /* the host */ host: 'example.com',
+  "port": 8080, // inline
+  $ratio: .5,
+  limit: +0x1F,
+  ceiling: Infinity,
+  nested: {deep: [1, 2,],},
+}
+"#;
+        let value = Json5Adapter.parse(source).unwrap().value;
+        let map = value.as_map().unwrap();
+        assert_eq!(map["host"], Value::from("example.com"));
+        assert_eq!(map["port"], Value::Integer(8080));
+        assert_eq!(map["$ratio"], Value::Float(0.5));
+        assert_eq!(map["limit"], Value::Integer(31));
+        assert_eq!(map["ceiling"], Value::Float(f64::INFINITY));
+        let nested = map["nested"].as_map().unwrap();
+        assert_eq!(
+            nested["deep"],
+            Value::Array(vec![Value::Integer(1), Value::Integer(2)])
+        );
+    }
+
+    #[test]
+    fn parse_decodes_json5_string_escapes() {
+        let value = Json5Adapter
+            .parse("{a: 'it\\'s', b: \"\\x41\\v\", c: 'one \\\ntwo'}")
+            .unwrap()
+            .value;
+        let map = value.as_map().unwrap();
+        assert_eq!(map["a"], Value::from("it's"));
+        assert_eq!(map["b"], Value::from("A\u{000b}"));
+        assert_eq!(map["c"], Value::from("one two"));
+    }
+
+    #[test]
+    fn parse_spans_cover_bare_and_quoted_keys() {
+        let source = "{\n  // c\n  host: 'x',\n  \"db\": {url: \"pg\"},\n}\n";
+        let spans = Json5Adapter.parse(source).unwrap().spans;
+        let host = &spans[&key("host")];
+        assert_eq!(source_slice(source, host.key.unwrap()), "host");
+        assert_eq!(source_slice(source, host.value), "'x'");
+        let url = &spans[&key("db.url")];
+        assert_eq!(source_slice(source, url.key.unwrap()), "url");
+        assert_eq!(source_slice(source, url.value), "\"pg\"");
+        assert_eq!(
+            source_slice(source, spans[&key("db")].key.unwrap()),
+            "\"db\""
+        );
+    }
+
+    #[test]
+    fn parse_empty_and_comment_only_documents_are_the_empty_map() {
+        for source in ["", "  \n", "// nothing yet\n/* really */\n"] {
+            let parsed = Json5Adapter.parse(source).unwrap();
+            assert_eq!(parsed.value, Value::Map(Map::new()));
+            assert!(parsed.spans.is_empty());
+        }
+    }
+
+    #[test]
+    fn parse_refuses_null_and_out_of_range_numbers_at_the_token() {
+        for (source, token, needle) in [
+            ("{a: null}", "null", "null at 'a'"),
+            ("{a: 9223372036854775808}", "9223372036854775808", "integer"),
+            ("{a: 0x8000000000000000}", "0x8000000000000000", "integer"),
+            ("{a: 1e999}", "1e999", "float"),
+        ] {
+            match Json5Adapter.parse(source).unwrap_err() {
+                FormatError::Parse {
+                    format,
+                    message,
+                    span,
+                } => {
+                    assert_eq!(format, "json5");
+                    assert!(message.contains(needle), "{message}");
+                    assert_eq!(source_slice(source, span.unwrap()), token);
+                }
+                other => panic!("expected Parse, got {other:?}"),
+            }
+        }
+        assert_eq!(
+            Json5Adapter
+                .parse("{a: -0x10}")
+                .unwrap()
+                .value
+                .as_map()
+                .unwrap()["a"],
+            Value::Integer(-16)
+        );
+    }
+
+    #[test]
+    fn parse_syntax_errors_are_typed() {
+        for source in [
+            "{a: 1 /* open",
+            "{a: 01}",
+            "{1a: 1}",
+            "{a: 'x\ny'}",
+            "{a: 1} x",
+        ] {
+            assert!(
+                matches!(
+                    Json5Adapter.parse(source),
+                    Err(FormatError::Parse {
+                        format: "json5",
+                        ..
+                    })
+                ),
+                "{source:?} should be a parse error"
+            );
+        }
+    }
+
+    #[test]
+    fn parse_repeated_member_keeps_the_last_value_and_its_spans() {
+        let source = "{a: {b: 1}, a: {c: 2}}";
+        let parsed = Json5Adapter.parse(source).unwrap();
+        let a = parsed.value.as_map().unwrap()["a"]
+            .as_map()
+            .unwrap()
+            .clone();
+        assert_eq!(a.len(), 1);
+        assert_eq!(a["c"], Value::Integer(2));
+        assert!(!parsed.spans.contains_key(&key("a.b")));
+        assert!(parsed.spans.contains_key(&key("a.c")));
+    }
+
+    // --- serialize -------------------------------------------------------
+
+    #[test]
+    fn serialize_round_trips_including_non_finite_floats() {
+        let mut db = Map::new();
+        db.insert("url".into(), Value::from("pg://x"));
+        let mut map = Map::new();
+        map.insert("db".into(), Value::Map(db));
+        map.insert("ceiling".into(), Value::Float(f64::NEG_INFINITY));
+        map.insert("tags".into(), Value::Array(vec![Value::from("a")]));
+        let value = Value::Map(map);
+        let text = Json5Adapter.serialize(&value).unwrap();
+        assert_eq!(
+            text,
+            "{\n  \"ceiling\": -Infinity,\n  \"db\": {\n    \"url\": \"pg://x\",\n  },\n  \"tags\": [\"a\"],\n}\n"
+        );
+        assert_eq!(Json5Adapter.parse(&text).unwrap().value, value);
+    }
+
+    // --- edit ------------------------------------------------------------
+
+    const COMMENTED: &str = "\
+// app settings
+{
+  // where to listen
+  \"host\": \"localhost\", // keep this
+  \"port\": 8080
+}
+";
+
+    #[test]
+    fn edit_replaces_only_the_value_token() {
+        let out = set_edit(COMMENTED, &key("host"), &Value::from("example.com")).unwrap();
+        assert_eq!(out, COMMENTED.replace("\"localhost\"", "\"example.com\""));
+    }
+
+    #[test]
+    fn edit_creates_a_key_in_the_documents_comma_style() {
+        let out = set_edit(COMMENTED, &key("level"), &Value::from("debug")).unwrap();
+        assert_eq!(
+            out,
+            "\
+// app settings
+{
+  // where to listen
+  \"host\": \"localhost\", // keep this
+  \"port\": 8080,
+  \"level\": \"debug\"
+}
+"
+        );
+        let trailing = "{\n  a: 1,\n}\n";
+        assert_eq!(
+            set_edit(trailing, &key("b"), &Value::Integer(2)).unwrap(),
+            "{\n  a: 1,\n  \"b\": 2,\n}\n"
+        );
+    }
+
+    #[test]
+    fn edit_creates_missing_objects_as_one_member() {
+        let out = set_edit(COMMENTED, &key("db.url"), &Value::from("pg://x")).unwrap();
+        assert!(
+            out.ends_with("  \"port\": 8080,\n  \"db\": {\n    \"url\": \"pg://x\"\n  }\n}\n"),
+            "{out}"
+        );
+        assert_eq!(
+            Json5Adapter.parse(&out).unwrap().value.as_map().unwrap()["db"]
+                .as_map()
+                .unwrap()["url"],
+            Value::from("pg://x")
+        );
+    }
+
+    #[test]
+    fn edit_single_line_and_empty_objects() {
+        assert_eq!(
+            set_edit("{a: 1}\n", &key("b"), &Value::Integer(2)).unwrap(),
+            "{a: 1, \"b\": 2}\n"
+        );
+        assert_eq!(
+            set_edit("{}\n", &key("a"), &Value::Integer(1)).unwrap(),
+            "{\n  \"a\": 1\n}\n"
+        );
+        assert_eq!(
+            set_edit("", &key("a"), &Value::Integer(1)).unwrap(),
+            "{\n  \"a\": 1\n}\n"
+        );
+        assert_eq!(
+            set_edit("// only a comment", &key("a"), &Value::Integer(1)).unwrap(),
+            "// only a comment\n{\n  \"a\": 1\n}\n"
+        );
+    }
+
+    #[test]
+    fn edit_lands_after_a_documenting_comment() {
+        let source = "{\n  // Token\n  // \"token\": \"\",\n\n  \"port\": 1,\n}\n";
+        assert_eq!(
+            set_edit(source, &key("token"), &Value::from("t")).unwrap(),
+            "{\n  // Token\n  // \"token\": \"\",\n  \"token\": \"t\",\n\n  \"port\": 1,\n}\n"
+        );
+    }
+
+    #[test]
+    fn edit_path_conflict_is_typed() {
+        match set_edit(COMMENTED, &key("host.name"), &Value::from("x")).unwrap_err() {
+            FormatError::Edit { format, message } => {
+                assert_eq!(format, "json5");
+                assert_eq!(
+                    message,
+                    "path conflict: existing document has a non-object value at 'host' (setting 'host.name')"
+                );
+            }
+            other => panic!("expected Edit, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn unset_removes_the_members_line_and_keeps_comments() {
+        assert_eq!(
+            unset_edit(COMMENTED, &key("host")),
+            "// app settings\n{\n  // where to listen\n  \"port\": 8080\n}\n"
+        );
+        // The last member takes the comma before it along.
+        assert_eq!(
+            unset_edit(COMMENTED, &key("port")),
+            "// app settings\n{\n  // where to listen\n  \"host\": \"localhost\" // keep this\n}\n"
+        );
+        assert_eq!(unset_edit("{a: 1, b: 2}", &key("b")), "{a: 1}");
+        assert_eq!(unset_edit("{a: 1, b: 2}", &key("a")), "{b: 2}");
+        assert_eq!(unset_edit("{a: 1, a: 2}", &key("a")), "{}");
+    }
+
+    #[test]
+    fn unset_missing_path_is_a_no_op() {
+        assert_eq!(unset_edit(COMMENTED, &key("missing.deep")), COMMENTED);
+        assert_eq!(unset_edit(COMMENTED, &key("host.deep")), COMMENTED);
+        assert_eq!(unset_edit("", &key("a")), "{}\n");
+    }
+
+    // --- template --------------------------------------------------------
+
+    #[test]
+    fn rename_respells_the_name_and_keeps_comments() {
+        let source = "{\n  // host doc\n  host: \"x\", // trailing\n  'db': {\n    // url doc\n    url: \"pg\",\n  },\n}\n";
+        let rename =
+            |path: &ConfigPath, to| Json5Adapter.edit(source, FileEdit::Rename { path, to });
+        assert_eq!(
+            rename(&key("host"), "hostname").unwrap(),
+            source.replace("host:", "\"hostname\":")
+        );
+        assert_eq!(
+            rename(&key("db"), "database").unwrap(),
+            source.replace("'db'", "\"database\"")
+        );
+        assert_eq!(rename(&key("nope"), "other").unwrap(), source);
+        match rename(&key("host"), "db").unwrap_err() {
+            FormatError::Edit { format, message } => {
+                assert_eq!(format, "json5");
+                assert_eq!(
+                    message,
+                    "path conflict: existing document already has a value at 'db' (renaming 'host')"
+                );
+            }
+            other => panic!("expected Edit, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn template_uses_real_comments_and_parses_back_to_its_defaults() {
+        let schema = Schema::object("App")
+            .doc("Demo")
+            .field("host", Field::string().doc("Host").default("localhost"))
+            .field("token", Field::string().doc("Token"))
+            .array_of(
+                "plugins",
+                Schema::object("Plugin").field("name", Field::string()),
+            )
+            .nested(
+                "db",
+                Schema::object("Db")
+                    .doc("Database")
+                    .field("url", Field::string().optional())
+                    .field("pool", Field::integer().default(5i64)),
+            )
+            .build();
+        let text = Json5Adapter.template(&Shape::Object(schema)).unwrap();
+        assert_eq!(
+            text,
+            "\
+{
+  // Demo
+
+  // Host
+  \"host\": \"localhost\",
+
+  // Token
+  // Required.
+  // \"token\": \"\",
+
+  // \"plugins\": [{\"name\": \"\"}],
+
+  // Database
+  \"db\": {
+    // \"url\": \"\",
+
+    \"pool\": 5,
+  },
+}
+"
+        );
+        let value = Json5Adapter.parse(&text).unwrap().value;
+        let map = value.as_map().unwrap();
+        assert_eq!(map["host"], Value::from("localhost"));
+        assert!(!map.contains_key("token"));
+        assert_eq!(map["db"].as_map().unwrap()["pool"], Value::Integer(5));
+    }
+}
@@ -16,21 +16,26 @@
 //!
 //! Parse returns `{value, spans}`: [`FormatAdapter::parse`] returns the
 //! value tree and a path → span index together ([`Parsed`], ADR-0005).
-//! Shipped adapters (TOML, YAML, JSON) fill the index so unknown-key
-//! and `InvalidValue` errors locate the token from byte spans.
+//! Shipped adapters (TOML, YAML, JSON, JSON5, INI) fill the index so
+//! unknown-key and `InvalidValue` errors locate the token from byte spans.
 //!
 //! This module holds the contract and its pure data structures; the
-//! adapters themselves live in [`toml`], [`yaml`], and [`json`], and the
-//! shared walkers they drive — the schema → template traversal and the
-//! edit path-walk — live in the private `template` and `edit` submodules.
+//! adapters themselves live in [`toml`], [`yaml`], [`json`], [`json5`],
+//! and [`ini`], and the shared walkers they drive — the schema → template
+//! traversal and the edit path-walk — live in the private `template` and
+//! `edit` submodules.
 
 pub(crate) mod edit;
+pub mod ini;
 pub mod json;
+pub mod json5;
 pub(crate) mod template;
 pub mod toml;
 pub mod yaml;
 
+pub use ini::IniAdapter;
 pub use json::JsonAdapter;
+pub use json5::Json5Adapter;
 pub use toml::TomlAdapter;
 pub use yaml::YamlAdapter;
 
@@ -65,6 +70,9 @@ pub enum Operation {
     EditCreateFile,
     /// Edit: remove a key.
     EditUnset,
+    /// Edit: rename a key in place, keeping its value, position, and
+    /// comments.
+    EditRename,
 }
 
 impl fmt::Display for Operation {
@@ -77,6 +85,7 @@ impl fmt::Display for Operation {
             Operation::EditCreateKey => "creating a missing key",
             Operation::EditCreateFile => "creating a missing file",
             Operation::EditUnset => "unsetting a key",
+            Operation::EditRename => "renaming a key",
         })
     }
 }
@@ -400,7 +409,8 @@ pub enum SetTarget {
 ///
 /// The variants deliberately cover only what the capability matrix rows
 /// express: setting (whose [`SetTarget`] names which of the three
-/// set-family rows the request falls under) and unsetting. Creating a
+/// set-family rows the request falls under), unsetting, and renaming.
+/// Creating a
 /// missing file is [`FormatAdapter::template`] plus a set carrying
 /// [`SetTarget::MissingFile`] — no separate entry point.
 #[derive(Debug, Clone, PartialEq)]
@@ -425,12 +435,25 @@ pub enum FileEdit<'a> {
         /// [`FormatError::Edit`].
         path: &'a ConfigPath,
     },
+    /// Rename the key at a path within its parent. The value — a whole
+    /// section included — keeps its place in the file along with its
+    /// comments. A missing path is a no-op; an existing sibling named
+    /// `to` is a typed [`FormatError::Edit`].
+    Rename {
+        /// Structured path of the key to rename. File edits address map
+        /// keys; a path carrying [`PathSegment::Index`] is a typed
+        /// [`FormatError::Edit`].
+        path: &'a ConfigPath,
+        /// The key's new name; the parent stays the same.
+        to: &'a str,
+    },
 }
 
 impl FileEdit<'_> {
     /// The capability-matrix row this edit request falls under (`Set` →
-    /// its [`SetTarget`]'s operation, `Unset` → [`Operation::EditUnset`]),
-    /// for refusal messages and capability checks.
+    /// its [`SetTarget`]'s operation, `Unset` → [`Operation::EditUnset`],
+    /// `Rename` → [`Operation::EditRename`]), for refusal messages and
+    /// capability checks.
     pub fn operation(&self) -> Operation {
         match self {
             FileEdit::Set { target, .. } => match target {
@@ -439,6 +462,7 @@ impl FileEdit<'_> {
                 SetTarget::MissingFile => Operation::EditCreateFile,
             },
             FileEdit::Unset { .. } => Operation::EditUnset,
+            FileEdit::Rename { .. } => Operation::EditRename,
         }
     }
 }
@@ -468,6 +492,17 @@ pub trait FormatAdapter: Send + Sync {
     /// specific shapes at runtime with the same typed error.
     fn capabilities(&self) -> &'static [Operation];
 
+    /// Whether every scalar this format parses is a string, leaving types
+    /// to the schema (INI: `port = 8080` carries no type of its own).
+    /// When true, the pipeline types each parsed file's strings by the
+    /// declared leaf types before the file merges — booleans, integers,
+    /// floats, and non-string enum members — the same schema-driven
+    /// coercion ADR-0001 applies to datetimes, never value sniffing. The
+    /// default is `false`: typed formats keep their own scalar types.
+    fn scalars_are_strings(&self) -> bool {
+        false
+    }
+
     /// Whether `operation` is declared by this adapter.
     fn supports(&self, operation: Operation) -> bool {
         self.capabilities().contains(&operation)
@@ -536,6 +571,58 @@ pub trait FormatAdapter: Send + Sync {
     fn edit(&self, source: &str, edit: FileEdit<'_>) -> Result<String, FormatError>;
 }
 
+/// A shared adapter is the adapter: lets one `Arc` back several
+/// registries (the builder hands a clone to every resolver it builds).
+impl<A: FormatAdapter + ?Sized> FormatAdapter for std::sync::Arc<A> {
+    fn name(&self) -> &'static str {
+        (**self).name()
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        (**self).extensions()
+    }
+
+    fn capabilities(&self) -> &'static [Operation] {
+        (**self).capabilities()
+    }
+
+    fn scalars_are_strings(&self) -> bool {
+        (**self).scalars_are_strings()
+    }
+
+    fn supports(&self, operation: Operation) -> bool {
+        (**self).supports(operation)
+    }
+
+    fn require(&self, operation: Operation) -> Result<(), UnsupportedByFormat> {
+        (**self).require(operation)
+    }
+
+    fn display_entry(&self, key: &str, value: &str) -> String {
+        (**self).display_entry(key, value)
+    }
+
+    fn display_comment(&self, line: &str) -> String {
+        (**self).display_comment(line)
+    }
+
+    fn parse(&self, text: &str) -> Result<Parsed, FormatError> {
+        (**self).parse(text)
+    }
+
+    fn serialize(&self, value: &Value) -> Result<String, FormatError> {
+        (**self).serialize(value)
+    }
+
+    fn template(&self, shape: &Shape) -> Result<String, FormatError> {
+        (**self).template(shape)
+    }
+
+    fn edit(&self, source: &str, edit: FileEdit<'_>) -> Result<String, FormatError> {
+        (**self).edit(source, edit)
+    }
+}
+
 /// Ordered set of enabled format adapters — the single routing seam.
 ///
 /// Registration order is meaning: the first registered adapter is the
@@ -592,23 +679,34 @@ impl FormatRegistry {
     pub fn is_empty(&self) -> bool {
         self.adapters.is_empty()
     }
+
+    /// The registered adapters' names in preference order, for error
+    /// messages.
+    pub(crate) fn names(&self) -> Vec<String> {
+        self.iter().map(|a| a.name().to_string()).collect()
+    }
 }
 
 /// Construct the built-in adapter for a canonical format name, if the name
 /// is known. The name set matches [`FormatAdapter::name`] of the shipped
-/// adapters: `"toml"`, `"yaml"`, `"json"`.
+/// adapters: `"toml"`, `"yaml"`, `"json"`, `"json5"`, `"ini"`.
 pub(crate) fn builtin_adapter(name: &str) -> Option<Box<dyn FormatAdapter>> {
     match name {
         "toml" => Some(Box::new(toml::TomlAdapter)),
         "yaml" => Some(Box::new(yaml::YamlAdapter)),
         "json" => Some(Box::new(json::JsonAdapter)),
+        "json5" => Some(Box::new(json5::Json5Adapter)),
+        "ini" => Some(Box::new(ini::IniAdapter)),
         _ => None,
     }
 }
 
+/// The canonical names of the built-in adapters, in listing order.
+const BUILTIN_NAMES: [&str; 5] = ["toml", "yaml", "json", "json5", "ini"];
+
 /// The canonical names of every built-in adapter, for error messages.
 pub(crate) fn builtin_names() -> Vec<String> {
-    ["toml", "yaml", "json"].map(String::from).to_vec()
+    BUILTIN_NAMES.map(String::from).to_vec()
 }
 
 /// The built-in adapter claiming `extension` (matched case-insensitively,
@@ -618,7 +716,7 @@ pub(crate) fn builtin_names() -> Vec<String> {
 /// formats the discovery list has not enabled.
 pub(crate) fn builtin_adapter_for_extension(extension: &str) -> Option<Box<dyn FormatAdapter>> {
     let extension = extension.to_ascii_lowercase();
-    ["toml", "yaml", "json"].iter().find_map(|name| {
+    BUILTIN_NAMES.iter().find_map(|name| {
         let adapter = builtin_adapter(name).expect("names enumerate the built-in set");
         adapter
             .extensions()
@@ -819,7 +917,17 @@ mod tests {
             builtin_adapter_for_extension("json").unwrap().name(),
             "json"
         );
-        assert!(builtin_adapter_for_extension("ini").is_none());
+        assert_eq!(
+            builtin_adapter_for_extension("json5").unwrap().name(),
+            "json5"
+        );
+        assert_eq!(
+            builtin_adapter_for_extension("jsonc").unwrap().name(),
+            "json5"
+        );
+        assert_eq!(builtin_adapter_for_extension("ini").unwrap().name(), "ini");
+        assert_eq!(builtin_adapter_for_extension("conf").unwrap().name(), "ini");
+        assert!(builtin_adapter_for_extension("xml").is_none());
     }
 
     #[test]
@@ -827,8 +935,10 @@ mod tests {
         assert_eq!(builtin_adapter("toml").unwrap().name(), "toml");
         assert_eq!(builtin_adapter("yaml").unwrap().name(), "yaml");
         assert_eq!(builtin_adapter("json").unwrap().name(), "json");
-        assert!(builtin_adapter("ini").is_none());
-        assert_eq!(builtin_names(), ["toml", "yaml", "json"]);
+        assert_eq!(builtin_adapter("json5").unwrap().name(), "json5");
+        assert_eq!(builtin_adapter("ini").unwrap().name(), "ini");
+        assert!(builtin_adapter("xml").is_none());
+        assert_eq!(builtin_names(), ["toml", "yaml", "json", "json5", "ini"]);
     }
 
     #[test]
@@ -838,7 +948,7 @@ mod tests {
         assert_send_sync::<Box<dyn FormatAdapter>>();
     }
 
-    const ALL_OPERATIONS: [Operation; 7] = [
+    const ALL_OPERATIONS: [Operation; 8] = [
         Operation::Parse,
         Operation::Template,
         Operation::Serialize,
@@ -846,6 +956,7 @@ mod tests {
         Operation::EditCreateKey,
         Operation::EditCreateFile,
         Operation::EditUnset,
+        Operation::EditRename,
     ];
 
     #[test]
@@ -883,6 +994,8 @@ mod tests {
             (&toml::TomlAdapter as &dyn FormatAdapter, ""),
             (&yaml::YamlAdapter, ""),
             (&json::JsonAdapter, ""),
+            (&json5::Json5Adapter, ""),
+            (&ini::IniAdapter, ""),
         ] {
             let parsed = adapter.parse(text).unwrap();
             assert_eq!(parsed.value, Value::Map(Default::default()));
@@ -902,5 +1015,9 @@ mod tests {
         assert_eq!(yaml::YamlAdapter.extensions(), ["yaml", "yml"]);
         assert_eq!(json::JsonAdapter.name(), "json");
         assert_eq!(json::JsonAdapter.extensions(), ["json"]);
+        assert_eq!(json5::Json5Adapter.name(), "json5");
+        assert_eq!(json5::Json5Adapter.extensions(), ["json5", "jsonc"]);
+        assert_eq!(ini::IniAdapter.name(), "ini");
+        assert_eq!(ini::IniAdapter.extensions(), ["ini", "conf"]);
     }
 }
@@ -21,7 +21,7 @@
 
 use std::fmt::Write;
 
-use crate::runtime::{Field, LeafType, Schema, Shape, TaggedShape, TaggedVariant};
+use crate::runtime::{Field, LeafType, PathKind, Schema, Shape, TaggedShape, TaggedVariant};
 use crate::value::{Map, Value};
 
 use super::FormatError;
@@ -361,7 +361,7 @@ pub(crate) fn example_shape_value(shape: &Shape) -> Value {
 /// [`with_example_defaults`] consume it.
 pub(crate) fn example_leaf_value(ty: &LeafType) -> Value {
     match ty {
-        LeafType::String | LeafType::Value => Value::String(String::new()),
+        LeafType::String | LeafType::Path { .. } | LeafType::Value => Value::String(String::new()),
         LeafType::Enum { values } => values
             .first()
             .cloned()
@@ -427,6 +427,9 @@ pub(crate) fn leaf_annotations(
     if matches!(field.shape, Shape::Leaf(leaf) if matches!(leaf.ty, LeafType::Value)) {
         lines.push(format!("Accepts: any {format_display} value"));
     }
+    if let Some(note) = path_note(field.shape) {
+        lines.push(note);
+    }
     match field.shape {
         Shape::Array(array) => {
             lines.push(format!("Elements: {}", describe_shape_item(&array.item)))
@@ -458,6 +461,39 @@ fn enum_values_of(shape: &Shape) -> Option<&[Value]> {
     }
 }
 
+/// The `Path:` hint for a path leaf (or an array or map of them): how
+/// the value is resolved and what it must name. The template is a
+/// config file, so "this file" is exactly where relative paths anchor.
+fn path_note(shape: &Shape) -> Option<String> {
+    let (expand_vars, must_exist, kind) = match shape {
+        Shape::Leaf(leaf) => match leaf.ty {
+            LeafType::Path {
+                expand_vars,
+                must_exist,
+                kind,
+            } => (expand_vars, must_exist, kind),
+            _ => return None,
+        },
+        Shape::Array(array) => return path_note(&array.item),
+        Shape::Map(map) => return path_note(&map.item),
+        Shape::Object(_) | Shape::Tagged(_) => return None,
+    };
+    let expands = if expand_vars {
+        "~ and $VAR expand"
+    } else {
+        "~ expands"
+    };
+    let requirement = match (kind, must_exist) {
+        (Some(PathKind::Dir), _) => "; must be an existing directory",
+        (Some(PathKind::File), _) => "; must be an existing file",
+        (None, true) => "; must exist",
+        (None, false) => "",
+    };
+    Some(format!(
+        "Path: relative to this file's directory; {expands}{requirement}."
+    ))
+}
+
 /// Human-readable name of an item shape for the `Elements:`/`Values:`
 /// hints, recursing through containers (`array of integer`).
 fn describe_shape_item(shape: &Shape) -> String {
@@ -580,6 +616,27 @@ mod tests {
         assert_eq!(lines, ["Values: float"]);
     }
 
+    #[test]
+    fn leaf_annotations_describe_path_resolution() {
+        let plain = Shape::from(Field::path().optional());
+        let lines = leaf_annotations(view(&plain), "TOML", &mut |_| unreachable!()).unwrap();
+        assert_eq!(
+            lines,
+            ["Path: relative to this file's directory; ~ expands."]
+        );
+
+        let dirs = Shape::from(Field::array_of_type(Field::path().expand_vars().is_dir()));
+        let lines = leaf_annotations(view(&dirs), "TOML", &mut |_| unreachable!()).unwrap();
+        assert_eq!(
+            lines,
+            [
+                "Path: relative to this file's directory; ~ and $VAR expand; must be an \
+                 existing directory.",
+                "Elements: path",
+            ]
+        );
+    }
+
     #[test]
     fn example_leaf_value_integer_respects_bounds() {
         assert_eq!(
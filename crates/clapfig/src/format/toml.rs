@@ -10,8 +10,8 @@
 //! - [`template`](TomlAdapter::template): the commented config template
 //!   (doc comments, `# Allowed:` enum lines, commented placeholders for
 //!   defaultless leaves) that `config gen` and file seeding emit.
-//! - [`edit`](TomlAdapter::edit): comment-preserving `toml_edit` set/unset
-//!   against existing source text.
+//! - [`edit`](TomlAdapter::edit): comment-preserving `toml_edit`
+//!   set/unset/rename against existing source text.
 
 use std::collections::BTreeMap;
 
@@ -52,6 +52,7 @@ impl FormatAdapter for TomlAdapter {
             Operation::EditCreateKey,
             Operation::EditCreateFile,
             Operation::EditUnset,
+            Operation::EditRename,
         ]
     }
 
@@ -127,6 +128,10 @@ impl FormatAdapter for TomlAdapter {
                 let keys = key_segments(path)?;
                 super::edit::unset_at_path(doc.as_item_mut(), &keys);
             }
+            FileEdit::Rename { path, to } => {
+                let keys = key_segments(path)?;
+                super::edit::rename_at_path(doc.as_item_mut(), &keys, to)?;
+            }
         }
         Ok(doc.to_string())
     }
@@ -346,6 +351,53 @@ impl super::edit::EditDoc for toml_edit::Item {
         self.as_table_like_mut()
             .is_some_and(|table| table.remove(key).is_some())
     }
+
+    fn rename_key(&mut self, from: &str, to: &str) {
+        match self {
+            toml_edit::Item::Table(table) => {
+                let names = table.iter().map(|(k, _)| k.to_owned()).collect();
+                let tail = detach_from(names, from, |k| table.remove_entry(k));
+                for (key, item) in tail {
+                    table.insert_formatted(&renamed(key, from, to), item);
+                }
+            }
+            toml_edit::Item::Value(toml_edit::Value::InlineTable(table)) => {
+                let names = table.iter().map(|(k, _)| k.to_owned()).collect();
+                let tail = detach_from(names, from, |k| table.remove_entry(k));
+                for (key, value) in tail {
+                    table.insert_formatted(&renamed(key, from, to), value);
+                }
+            }
+            _ => unreachable!("callers guarantee `from` exists in a table"),
+        }
+    }
+}
+
+/// Remove the entries from `from` to the end of a table, in order, so they
+/// can be re-inserted — `toml_edit` has no in-place key rename, and
+/// re-inserting only the renamed entry would move it to the end of its
+/// table. Keys keep their decor, so comments travel with them.
+fn detach_from<T>(
+    names: Vec<String>,
+    from: &str,
+    mut remove: impl FnMut(&str) -> Option<(toml_edit::Key, T)>,
+) -> Vec<(toml_edit::Key, T)> {
+    names
+        .iter()
+        .skip_while(|k| *k != from)
+        .map(|k| remove(k).expect("listed from the same table"))
+        .collect()
+}
+
+/// `key` respelled as `to` when it is the renamed entry, keeping the
+/// comments and whitespace around it.
+fn renamed(key: toml_edit::Key, from: &str, to: &str) -> toml_edit::Key {
+    if key.get() != from {
+        return key;
+    }
+    toml_edit::Key::new(to)
+        .with_leaf_decor(key.leaf_decor().clone())
+        .with_dotted_decor(key.dotted_decor().clone())
 }
 
 /// Convert an owned [`Value`] into a `toml_edit::Value` for edits.
@@ -2019,4 +2071,45 @@ n = 1
             .unwrap();
         assert!(unchanged.contains("port = 1"));
     }
+
+    #[test]
+    fn edit_rename_keeps_position_and_comments() {
+        let source = "# host doc\nhost = \"x\" # trailing\nport = 1\n\n# db doc\n[db]\n# url doc\nurl = \"pg\"\n\n[db.replica]\nurl = \"r\"\n";
+        let rename = |path: &ConfigPath, to| {
+            TomlAdapter
+                .edit(source, FileEdit::Rename { path, to })
+                .unwrap()
+        };
+        assert_eq!(
+            rename(&ConfigPath::new().key("host"), "hostname"),
+            source.replace("host =", "hostname =")
+        );
+        assert_eq!(
+            rename(&ConfigPath::new().key("db"), "database"),
+            source.replace("[db", "[database")
+        );
+        let missing = ConfigPath::new().key("nope");
+        assert_eq!(rename(&missing, "other"), source);
+
+        let taken = ConfigPath::new().key("host");
+        match TomlAdapter
+            .edit(
+                source,
+                FileEdit::Rename {
+                    path: &taken,
+                    to: "port",
+                },
+            )
+            .unwrap_err()
+        {
+            FormatError::Edit { format, message } => {
+                assert_eq!(format, "toml");
+                assert_eq!(
+                    message,
+                    "path conflict: existing file already has a value at 'port' (renaming 'host')"
+                );
+            }
+            other => panic!("expected Edit, got {other:?}"),
+        }
+    }
 }
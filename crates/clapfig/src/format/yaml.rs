@@ -39,7 +39,8 @@
 //! and any shape the stack cannot patch honestly (sequence items, flow
 //! collection members whose line the patcher would mangle) surfaces as the
 //! typed [`UnsupportedByFormat`](super::UnsupportedByFormat) refusal
-//! instead of silent corruption.
+//! instead of silent corruption. A rename respells only the key token,
+//! located through `yamlpath`.
 
 use std::collections::BTreeMap;
 use std::fmt::Write;
@@ -83,6 +84,7 @@ impl FormatAdapter for YamlAdapter {
             Operation::EditCreateKey,
             Operation::EditCreateFile,
             Operation::EditUnset,
+            Operation::EditRename,
         ]
     }
 
@@ -143,6 +145,10 @@ impl FormatAdapter for YamlAdapter {
                 let keys = key_segments(path)?;
                 unset_in_source(source, &keys)
             }
+            FileEdit::Rename { path, to } => {
+                let keys = key_segments(path)?;
+                rename_in_source(source, &keys, to)
+            }
         }
     }
 }
@@ -605,6 +611,12 @@ impl super::edit::EditDoc for Value {
         self.as_map_mut()
             .is_some_and(|map| map.remove(key).is_some())
     }
+
+    fn rename_key(&mut self, from: &str, to: &str) {
+        let map = self.as_map_mut().expect("callers guarantee a container");
+        let value = map.remove(from).expect("callers guarantee `from` exists");
+        map.insert(to.to_string(), value);
+    }
 }
 
 /// Insert `value` at `keys` in `map`, creating intermediate maps. Errors on
@@ -892,6 +904,46 @@ fn unset_in_source(source: &str, keys: &[&str]) -> Result<String, FormatError> {
     )
 }
 
+/// Rename the key at `keys` to `to` by respelling its key token, so the
+/// value — block or flow, with every comment inside and around it —
+/// stays byte for byte. A missing path is a no-op. Verified like every
+/// other edit: a key the stack cannot respell honestly (one that exists
+/// only through an alias) refuses typed.
+fn rename_in_source(source: &str, keys: &[&str], to: &str) -> Result<String, FormatError> {
+    let mut expected = Value::Map(parse_edit_source(source)?);
+    if !super::edit::rename_at_path(&mut expected, keys, to)? {
+        return Ok(source.to_string());
+    }
+    let Value::Map(expected) = expected else {
+        unreachable!("the root stays a map")
+    };
+    let display_path = keys.join(".");
+    let doc = yamlpath::Document::new(source.to_string()).map_err(|e| FormatError::Parse {
+        format: "yaml",
+        message: e.to_string(),
+        span: None,
+    })?;
+    let key = doc
+        .query_key_only(&route_for(keys))
+        .map_err(|e| FormatError::Edit {
+            format: "yaml",
+            message: format!("cannot edit '{display_path}': {e}"),
+        })?;
+    let span = feature_span(&key);
+    let mut out = source.to_string();
+    out.replace_range(span.start..span.end, &inline_scalar(to));
+    let reparsed = parse_edit_source(&out)?;
+    if trees_equal(&Value::Map(reparsed), &Value::Map(expected)) {
+        Ok(out)
+    } else {
+        Err(UnsupportedByFormat {
+            format: "yaml",
+            operation: Operation::EditRename,
+        }
+        .into())
+    }
+}
+
 // --- template emission (native YAML comments) -----------------------------
 
 /// `true` when the schema subtree emits at least one uncommented line — a
@@ -2871,6 +2923,29 @@ db:
         assert_eq!(unchanged, source);
     }
 
+    #[test]
+    fn edit_rename_respells_only_the_key_token() {
+        let source = "# host doc\nhost: x  # trailing\ndb:\n  # url doc\n  url: pg\n  pool: 3\n";
+        let rename = |path: &ConfigPath, to| {
+            YamlAdapter
+                .edit(source, FileEdit::Rename { path, to })
+                .unwrap()
+        };
+        assert_eq!(
+            rename(&ConfigPath::new().key("host"), "hostname"),
+            source.replace("host:", "hostname:")
+        );
+        assert_eq!(
+            rename(&ConfigPath::new().key("db"), "database"),
+            source.replace("db:", "database:")
+        );
+        assert_eq!(
+            rename(&ConfigPath::new().key("db").key("pool"), "pool size"),
+            source.replace("pool:", "pool size:")
+        );
+        assert_eq!(rename(&ConfigPath::new().key("nope"), "other"), source);
+    }
+
     #[test]
     fn edit_unset_sole_nested_child_preserves_parent_as_empty_map() {
         // Removing the last child must not leave `database:` — a null on
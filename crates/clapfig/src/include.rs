@@ -0,0 +1,371 @@
+//! `include = [...]` directives inside config files.
+//!
+//! With [`Builder::include_key`](crate::Builder::include_key) set, a
+//! config file may name other files to load beneath it:
+//!
+//! ```toml
+//! include = ["common.toml", "db/*.toml"]
+//! ```
+//!
+//! Expansion happens on the I/O side, after discovery: each discovered
+//! file is parsed once to read its directive, and the files it names
+//! (recursively) are spliced into the ordered file list **just below**
+//! it. The resolve pipeline then treats every included file as one more
+//! sparse overlay — its own parse, spans, origins, and unknown-key
+//! checks — and drops the directive key from each document
+//! ([`ResolveInput::include_key`](crate::resolve::ResolveInput::include_key)).
+//!
+//! Paths are relative to the including file's directory; `~/` is the
+//! home directory. A `*` or `?` in the final path component matches file
+//! names in that directory, loaded in name order; a pattern that matches
+//! nothing loads nothing, while a plain path that does not exist is
+//! [`ClapfigError::IncludeNotFound`]. A file that includes itself, directly
+//! or through others, is [`ClapfigError::IncludeCycle`]; a pattern match
+//! that names a file already on the include chain is skipped, so
+//! `include = ["*.toml"]` does not include its own file. A file reached
+//! twice without a cycle — two includes sharing a `common.toml` — loads
+//! once, where it was first reached, so an `append` array does not get
+//! its elements twice.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::error::ClapfigError;
+use crate::format::{ConfigPath, FormatRegistry};
+use crate::origin::Origin;
+use crate::value::Value;
+
+/// Splice every file `files` include (transitively) into the list, each
+/// below its includer. `read` loads a file, `None` when it does not
+/// exist. Returns the expanded list, in precedence order like the input.
+pub(crate) fn expand_includes(
+    files: Vec<(PathBuf, String)>,
+    key: &str,
+    registry: &FormatRegistry,
+    read: &mut dyn FnMut(&Path) -> Result<Option<String>, ClapfigError>,
+) -> Result<Vec<(PathBuf, String)>, ClapfigError> {
+    let mut expansion = Expansion {
+        key,
+        registry,
+        read,
+        chain: Vec::new(),
+        expanded: HashSet::new(),
+        out: Vec::new(),
+    };
+    for (path, contents) in files {
+        expansion.file(path, contents)?;
+    }
+    Ok(expansion.out)
+}
+
+struct Expansion<'a> {
+    key: &'a str,
+    registry: &'a FormatRegistry,
+    read: &'a mut dyn FnMut(&Path) -> Result<Option<String>, ClapfigError>,
+    /// Canonical paths of the files being expanded, outermost first.
+    chain: Vec<PathBuf>,
+    /// Canonical paths of every file expanded so far.
+    expanded: HashSet<PathBuf>,
+    out: Vec<(PathBuf, String)>,
+}
+
+impl Expansion<'_> {
+    fn file(&mut self, path: PathBuf, contents: String) -> Result<(), ClapfigError> {
+        let patterns = self.directive(&path, &contents)?;
+        let canonical_path = canonical(&path);
+        self.expanded.insert(canonical_path.clone());
+        self.chain.push(canonical_path);
+        let dir = path.parent().unwrap_or(Path::new("")).to_path_buf();
+        for pattern in patterns {
+            let target = crate::file_ref::reference_path(&pattern, None);
+            let target = if target.is_relative() {
+                dir.join(target)
+            } else {
+                target
+            };
+            if is_pattern(&target) {
+                for matched in self.matches(&target)? {
+                    if self.chain.contains(&canonical(&matched)) {
+                        continue;
+                    }
+                    self.included(matched, &path)?;
+                }
+            } else {
+                self.included(target, &path)?;
+            }
+        }
+        self.chain.pop();
+        self.out.push((path, contents));
+        Ok(())
+    }
+
+    fn included(&mut self, target: PathBuf, from: &Path) -> Result<(), ClapfigError> {
+        let canonical_target = canonical(&target);
+        if let Some(start) = self.chain.iter().position(|p| *p == canonical_target) {
+            let mut chain = self.chain[start..].to_vec();
+            chain.push(canonical_target);
+            return Err(ClapfigError::IncludeCycle { chain });
+        }
+        if self.expanded.contains(&canonical_target) {
+            return Ok(());
+        }
+        match (self.read)(&target)? {
+            Some(contents) => self.file(target, contents),
+            None => Err(ClapfigError::IncludeNotFound {
+                path: target,
+                from: from.to_path_buf(),
+            }),
+        }
+    }
+
+    /// The paths a file's directive names, as written. A file that does
+    /// not parse, or whose directive is missing, names none — the
+    /// resolve pipeline reports parse errors with full context.
+    fn directive(&self, path: &Path, contents: &str) -> Result<Vec<String>, ClapfigError> {
+        let Ok(adapter) = crate::resolve::file_adapter(self.registry, path) else {
+            return Ok(Vec::new());
+        };
+        let Ok(parsed) = adapter.parse(contents) else {
+            return Ok(Vec::new());
+        };
+        let Value::Map(table) = parsed.value else {
+            return Ok(Vec::new());
+        };
+        let paths = match table.get(self.key) {
+            None => return Ok(Vec::new()),
+            Some(Value::String(one)) => Some(vec![one.clone()]),
+            Some(Value::Array(items)) => items
+                .iter()
+                .map(|item| item.as_str().map(str::to_owned))
+                .collect(),
+            Some(_) => None,
+        };
+        paths.ok_or_else(|| {
+            let entry = parsed.spans.get(&ConfigPath::new().key(self.key));
+            let origin = Origin::file_with_spans(
+                path.to_path_buf(),
+                entry.and_then(|e| e.key),
+                entry.map(|e| e.value),
+                Arc::from(contents),
+            );
+            ClapfigError::InvalidValue {
+                key: self.key.to_owned(),
+                reason: "expected a path or a list of paths to include".into(),
+                origin: Box::new(origin.to_facts()),
+            }
+        })
+    }
+
+    /// Files in `pattern`'s directory whose names match its final
+    /// component, in name order.
+    fn matches(&self, pattern: &Path) -> Result<Vec<PathBuf>, ClapfigError> {
+        let dir = pattern.parent().unwrap_or(Path::new(""));
+        let name = pattern
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let entries = match std::fs::read_dir(if dir.as_os_str().is_empty() {
+            Path::new(".")
+        } else {
+            dir
+        }) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => {
+                return Err(ClapfigError::IoError {
+                    path: dir.to_path_buf(),
+                    source: e,
+                });
+            }
+        };
+        let mut found: Vec<PathBuf> = entries
+            .filter_map(Result::ok)
+            .filter(|entry| entry.file_type().is_ok_and(|t| t.is_file()))
+            .filter(|entry| wildcard_match(&name, &entry.file_name().to_string_lossy()))
+            .map(|entry| dir.join(entry.file_name()))
+            .collect();
+        found.sort();
+        Ok(found)
+    }
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+fn is_pattern(path: &Path) -> bool {
+    path.file_name()
+        .is_some_and(|name| name.to_string_lossy().contains(['*', '?']))
+}
+
+/// `*` matches any run of characters, `?` exactly one.
+fn wildcard_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, n));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    p = star + 1;
+                    n = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn registry() -> FormatRegistry {
+        let mut r = FormatRegistry::new();
+        r.register(Box::new(crate::format::TomlAdapter));
+        r.register(Box::new(crate::format::JsonAdapter));
+        r
+    }
+
+    fn expand(root: &Path) -> Result<Vec<PathBuf>, ClapfigError> {
+        let contents = std::fs::read_to_string(root).unwrap();
+        let mut read = |path: &Path| match std::fs::read_to_string(path) {
+            Ok(c) => Ok(Some(c)),
+            Err(_) => Ok(None),
+        };
+        expand_includes(
+            vec![(root.to_path_buf(), contents)],
+            "include",
+            &registry(),
+            &mut read,
+        )
+        .map(|files| files.into_iter().map(|(p, _)| p).collect())
+    }
+
+    #[test]
+    fn wildcards_match_names() {
+        assert!(wildcard_match("*.toml", "db.toml"));
+        assert!(wildcard_match("db-?.toml", "db-1.toml"));
+        assert!(wildcard_match("*", "anything"));
+        assert!(!wildcard_match("*.toml", "db.json"));
+        assert!(!wildcard_match("a*b", "ab-c"));
+    }
+
+    #[test]
+    fn includes_land_below_the_includer_in_order() {
+        let dir = TempDir::new().unwrap();
+        let d = dir.path();
+        std::fs::create_dir(d.join("db")).unwrap();
+        std::fs::write(
+            d.join("app.toml"),
+            "include = [\"common.toml\", \"db/*.toml\"]\n",
+        )
+        .unwrap();
+        std::fs::write(d.join("common.toml"), "include = \"base.json\"\n").unwrap();
+        std::fs::write(d.join("base.json"), "{}").unwrap();
+        std::fs::write(d.join("db/b.toml"), "").unwrap();
+        std::fs::write(d.join("db/a.toml"), "").unwrap();
+        std::fs::write(d.join("db/notes.txt"), "").unwrap();
+
+        let files = expand(&d.join("app.toml")).unwrap();
+        assert_eq!(
+            files,
+            vec![
+                d.join("base.json"),
+                d.join("common.toml"),
+                d.join("db/a.toml"),
+                d.join("db/b.toml"),
+                d.join("app.toml"),
+            ]
+        );
+    }
+
+    #[test]
+    fn missing_include_is_typed() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path().join("app.toml");
+        std::fs::write(&root, "include = [\"gone.toml\"]\n").unwrap();
+        match expand(&root).unwrap_err() {
+            ClapfigError::IncludeNotFound { path, from } => {
+                assert_eq!(path, dir.path().join("gone.toml"));
+                assert_eq!(from, root);
+            }
+            other => panic!("expected IncludeNotFound, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn include_cycle_is_typed() {
+        let dir = TempDir::new().unwrap();
+        let d = dir.path();
+        std::fs::write(d.join("a.toml"), "include = \"b.toml\"\n").unwrap();
+        std::fs::write(d.join("b.toml"), "include = \"a.toml\"\n").unwrap();
+        match expand(&d.join("a.toml")).unwrap_err() {
+            ClapfigError::IncludeCycle { chain } => {
+                let names: Vec<_> = chain
+                    .iter()
+                    .map(|p| p.file_name().unwrap().to_string_lossy().into_owned())
+                    .collect();
+                assert_eq!(names, ["a.toml", "b.toml", "a.toml"]);
+            }
+            other => panic!("expected IncludeCycle, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn a_file_reached_twice_loads_once() {
+        let dir = TempDir::new().unwrap();
+        let d = dir.path();
+        std::fs::write(d.join("app.toml"), "include = [\"b.toml\", \"c.toml\"]\n").unwrap();
+        std::fs::write(d.join("b.toml"), "include = \"common.toml\"\n").unwrap();
+        std::fs::write(d.join("c.toml"), "include = \"common.toml\"\n").unwrap();
+        std::fs::write(d.join("common.toml"), "").unwrap();
+        let files = expand(&d.join("app.toml")).unwrap();
+        assert_eq!(
+            files,
+            vec![
+                d.join("common.toml"),
+                d.join("b.toml"),
+                d.join("c.toml"),
+                d.join("app.toml"),
+            ]
+        );
+    }
+
+    #[test]
+    fn a_pattern_skips_the_including_file() {
+        let dir = TempDir::new().unwrap();
+        let d = dir.path();
+        std::fs::write(d.join("app.toml"), "include = \"*.toml\"\n").unwrap();
+        std::fs::write(d.join("extra.toml"), "").unwrap();
+        let files = expand(&d.join("app.toml")).unwrap();
+        assert_eq!(files, vec![d.join("extra.toml"), d.join("app.toml")]);
+    }
+
+    #[test]
+    fn non_path_directive_is_a_located_invalid_value() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path().join("app.toml");
+        std::fs::write(&root, "include = 3\n").unwrap();
+        match expand(&root).unwrap_err() {
+            ClapfigError::InvalidValue { key, origin, .. } => {
+                assert_eq!(key, "include");
+                assert_eq!(origin.line_col(), Some((1, 11)));
+            }
+            other => panic!("expected InvalidValue, got {other:?}"),
+        }
+    }
+}
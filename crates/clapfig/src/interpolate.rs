@@ -0,0 +1,380 @@
+//! Opt-in `${...}` interpolation over the merged table.
+//!
+//! Runs in [`resolve`](crate::resolve) after every layer has merged and
+//! defaults are filled, and before unknown-key and type validation, so a
+//! reference sees the effective value of the key it names — whichever
+//! layer won it. Enabled with
+//! [`Builder::interpolate`](crate::Builder::interpolate).
+//!
+//! Syntax, inside any string value:
+//!
+//! - `${database.host}` — the resolved value of another key (dotted
+//!   path, map keys only). The referent may itself interpolate; a cycle
+//!   is an error.
+//! - `${env:HOME}` — a process environment variable, read directly (not
+//!   through the env layer's prefix).
+//! - `$${...}` — a literal `${...}`.
+//!
+//! A [`Path`](LeafType::Path) leaf with `expand_vars` is left alone: it
+//! expands `$VAR` and `${VAR}` from the environment itself, after the
+//! merge, so `${...}` there names a variable, never a key.
+//!
+//! A [sensitive](crate::runtime::Leaf::sensitive) key may only be named
+//! from a sensitive leaf (`dsn = "pg://app:${password}@db"`), so a secret
+//! never lands where `config get` and `config list` would print it.
+//!
+//! Only scalars interpolate; naming a table or array is an error. The
+//! result is a string, then parsed by the destination leaf's declared
+//! type, so `port = "${defaults.port}"` loads as an integer. Each
+//! interpolated value keeps its own origin (where the template was
+//! written) and records the template as its
+//! [`raw`](crate::Provenance::raw) value. Errors are `InvalidValue` at the
+//! string holding the bad reference, so a file-sourced template gets a
+//! snippet of its line.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::error::ClapfigError;
+use crate::format::ConfigPath;
+use crate::origin::{OriginMap, lookup_mut};
+use crate::runtime::{DocumentRoot, LeafType, Schema, Shape};
+use crate::value::{Map, Value};
+
+/// Reads one process environment variable for `${env:NAME}`.
+pub(crate) type LookupEnv = fn(&str) -> Option<String>;
+
+/// The production [`LookupEnv`].
+pub(crate) fn lookup_process_env(name: &str) -> Option<String> {
+    std::env::var(name).ok()
+}
+
+/// Interpolate every string in `table` that contains `${`, recording
+/// each template on its origin. `normalize_keys` applies the `-` → `_`
+/// rule to referenced keys, as it does to the keys users write.
+pub(crate) fn interpolate_root(
+    table: &mut Map,
+    origins: &mut OriginMap,
+    root: DocumentRoot<'_>,
+    lookup_env: LookupEnv,
+    normalize_keys: bool,
+) -> Result<(), ClapfigError> {
+    let mut found = Found::default();
+    for (key, value) in table.iter() {
+        let shape = match root {
+            DocumentRoot::Object(schema) => field_shape(schema, key),
+            DocumentRoot::Map(map) => Some(&*map.item),
+            DocumentRoot::Tagged(tagged) => tagged
+                .selected(table)
+                .and_then(|variant| field_shape(&variant.schema, key)),
+        };
+        collect(value, shape, false, ConfigPath::new().key(key), &mut found);
+    }
+    let Found { templates, secrets } = found;
+    if templates.is_empty() {
+        return Ok(());
+    }
+
+    let mut walk = Interpolation {
+        table: &*table,
+        templates: &templates,
+        secrets: &secrets,
+        resolved: BTreeMap::new(),
+        lookup_env,
+        normalize_keys,
+    };
+    for path in templates.keys() {
+        walk.resolve(path, &mut Vec::new())
+            .map_err(|(at, reason)| {
+                ClapfigError::invalid_value_at(at.to_string(), reason, origins, &at)
+            })?;
+    }
+    let resolved = walk.resolved;
+
+    for (path, result) in resolved {
+        let template = &templates[&path];
+        let mut value = Value::String(result);
+        if let Some(ty) = template.leaf
+            && !matches!(ty, LeafType::Value)
+        {
+            crate::schema_walk::type_raw_leaf(&mut value, ty);
+        }
+        if let Some(slot) = value_mut(table, &path) {
+            *slot = value;
+        }
+        if let Some(origin) = lookup_mut(origins, &path) {
+            origin.raw = Some(template.raw.clone());
+        }
+    }
+    Ok(())
+}
+
+/// A string holding `${`, the declared type of the leaf it sits on, and
+/// whether that leaf is sensitive.
+struct Template<'s> {
+    raw: String,
+    leaf: Option<&'s LeafType>,
+    sensitive: bool,
+}
+
+/// What [`collect`] finds: every template, and every scalar the schema
+/// marks sensitive (a reference to one is checked against its holder).
+#[derive(Default)]
+struct Found<'s> {
+    templates: BTreeMap<ConfigPath, Template<'s>>,
+    secrets: BTreeSet<ConfigPath>,
+}
+
+fn field_shape<'s>(schema: &'s Schema, key: &str) -> Option<&'s Shape> {
+    schema
+        .fields
+        .iter()
+        .find(|nf| nf.name == key)
+        .map(|nf| &nf.field)
+}
+
+/// Walk `value` at `path`; `sensitive` is whether an enclosing shape is.
+fn collect<'s>(
+    value: &Value,
+    shape: Option<&'s Shape>,
+    sensitive: bool,
+    path: ConfigPath,
+    out: &mut Found<'s>,
+) {
+    let sensitive = sensitive || shape.is_some_and(Shape::is_sensitive);
+    if sensitive && !matches!(value, Value::Map(_) | Value::Array(_)) {
+        out.secrets.insert(path.clone());
+    }
+    match value {
+        Value::String(s) if s.contains("${") => {
+            let leaf = match shape {
+                Some(Shape::Leaf(leaf)) => Some(&leaf.ty),
+                _ => None,
+            };
+            if matches!(
+                leaf,
+                Some(LeafType::Path {
+                    expand_vars: true,
+                    ..
+                })
+            ) {
+                return;
+            }
+            out.templates.insert(
+                path,
+                Template {
+                    raw: s.clone(),
+                    leaf,
+                    sensitive,
+                },
+            );
+        }
+        Value::Map(entries) => {
+            let schema = match shape {
+                Some(Shape::Object(schema)) => Some(schema),
+                Some(Shape::Tagged(tagged)) => {
+                    tagged.selected(entries).map(|variant| &variant.schema)
+                }
+                _ => None,
+            };
+            for (key, entry) in entries {
+                let child = match shape {
+                    Some(Shape::Map(map)) => Some(&*map.item),
+                    _ => schema.and_then(|schema| field_shape(schema, key)),
+                };
+                collect(entry, child, sensitive, path.clone().key(key), out);
+            }
+        }
+        Value::Array(items) => {
+            let item_shape = match shape {
+                Some(Shape::Array(array)) => Some(&*array.item),
+                _ => None,
+            };
+            for (i, item) in items.iter().enumerate() {
+                collect(item, item_shape, sensitive, path.clone().index(i), out);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// One piece of a parsed template.
+enum Piece<'t> {
+    Text(&'t str),
+    Reference(&'t str),
+}
+
+/// Split `raw` into literal text and `${...}` references, unescaping
+/// `$${`. The error is a reason.
+fn parse_template(raw: &str) -> Result<Vec<Piece<'_>>, String> {
+    let mut pieces = Vec::new();
+    let mut rest = raw;
+    while let Some(at) = rest.find("${") {
+        if rest[..at].ends_with('$') {
+            // `$${` — the first `$` escapes; keep `${` literally.
+            pieces.push(Piece::Text(&rest[..at - 1]));
+            pieces.push(Piece::Text("${"));
+            rest = &rest[at + 2..];
+            continue;
+        }
+        pieces.push(Piece::Text(&rest[..at]));
+        let body = &rest[at + 2..];
+        let Some(end) = body.find('}') else {
+            return Err(format!("unterminated `${{` in {raw:?}"));
+        };
+        let reference = body[..end].trim();
+        if reference.is_empty() {
+            return Err("empty `${}` reference".into());
+        }
+        pieces.push(Piece::Reference(reference));
+        rest = &body[end + 1..];
+    }
+    pieces.push(Piece::Text(rest));
+    Ok(pieces)
+}
+
+struct Interpolation<'a, 's> {
+    table: &'a Map,
+    templates: &'a BTreeMap<ConfigPath, Template<'s>>,
+    secrets: &'a BTreeSet<ConfigPath>,
+    resolved: BTreeMap<ConfigPath, String>,
+    lookup_env: LookupEnv,
+    normalize_keys: bool,
+}
+
+impl Interpolation<'_, '_> {
+    /// The interpolated string for the template at `path`. `stack` holds
+    /// the templates being resolved above this one, for cycle detection.
+    /// The error names the template it belongs to.
+    fn resolve(
+        &mut self,
+        path: &ConfigPath,
+        stack: &mut Vec<ConfigPath>,
+    ) -> Result<String, (ConfigPath, String)> {
+        if let Some(done) = self.resolved.get(path) {
+            return Ok(done.clone());
+        }
+        let templates = self.templates;
+        let pieces = parse_template(&templates[path].raw).map_err(|e| (path.clone(), e))?;
+        stack.push(path.clone());
+        let mut out = String::new();
+        for piece in pieces {
+            match piece {
+                Piece::Text(text) => out.push_str(text),
+                Piece::Reference(reference) => {
+                    let text = self.reference(reference, path, stack)?;
+                    out.push_str(&text);
+                }
+            }
+        }
+        stack.pop();
+        self.resolved.insert(path.clone(), out.clone());
+        Ok(out)
+    }
+
+    /// The text one `${reference}` in the template at `holder` stands
+    /// for. An error in a referenced template stays located there.
+    fn reference(
+        &mut self,
+        reference: &str,
+        holder: &ConfigPath,
+        stack: &mut Vec<ConfigPath>,
+    ) -> Result<String, (ConfigPath, String)> {
+        let here = |reason: String| (holder.clone(), reason);
+        if let Some(name) = reference.strip_prefix("env:") {
+            return (self.lookup_env)(name).ok_or_else(|| {
+                here(format!(
+                    "`${{{reference}}}`: environment variable {name} is not set"
+                ))
+            });
+        }
+        let key = if self.normalize_keys {
+            crate::normalize::normalize_key(reference)
+        } else {
+            reference.to_owned()
+        };
+        let target = key
+            .split('.')
+            .fold(ConfigPath::new(), |path, segment| path.key(segment));
+        if self.secrets.contains(&target) && !self.templates[holder].sensitive {
+            return Err(here(format!(
+                "`${{{reference}}}` is sensitive; only a sensitive field can interpolate it"
+            )));
+        }
+        if self.templates.contains_key(&target) {
+            if let Some(start) = stack.iter().position(|p| *p == target) {
+                let cycle: Vec<String> = stack[start..]
+                    .iter()
+                    .chain([&target])
+                    .map(ToString::to_string)
+                    .collect();
+                return Err((
+                    target,
+                    format!("interpolation cycle: {}", cycle.join(" -> ")),
+                ));
+            }
+            return self.resolve(&target, stack);
+        }
+        match crate::validate::lookup_value(self.table, &target) {
+            None => Err(here(format!("`${{{reference}}}`: no such key"))),
+            Some(Value::Map(_)) => Err(here(format!(
+                "`${{{reference}}}` is a table; only scalars can be interpolated"
+            ))),
+            Some(Value::Array(_)) => Err(here(format!(
+                "`${{{reference}}}` is an array; only scalars can be interpolated"
+            ))),
+            Some(Value::String(s)) => Ok(s.clone()),
+            Some(Value::Integer(i)) => Ok(i.to_string()),
+            Some(Value::Float(f)) => Ok(f.to_string()),
+            Some(Value::Boolean(b)) => Ok(b.to_string()),
+            Some(Value::Datetime(d)) => Ok(crate::value::lexical_string(d)),
+        }
+    }
+}
+
+fn value_mut<'a>(table: &'a mut Map, path: &ConfigPath) -> Option<&'a mut Value> {
+    use crate::format::PathSegment;
+    let (first, rest) = path.segments().split_first()?;
+    let PathSegment::Key(first) = first else {
+        return None;
+    };
+    let mut current = table.get_mut(first)?;
+    for segment in rest {
+        current = match (segment, current) {
+            (PathSegment::Key(key), Value::Map(map)) => map.get_mut(key)?,
+            (PathSegment::Index(i), Value::Array(items)) => items.get_mut(*i)?,
+            _ => return None,
+        };
+    }
+    Some(current)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(raw: &str) -> Result<String, String> {
+        parse_template(raw).map(|pieces| {
+            pieces
+                .into_iter()
+                .map(|piece| match piece {
+                    Piece::Text(t) => t.to_owned(),
+                    Piece::Reference(r) => format!("<{r}>"),
+                })
+                .collect()
+        })
+    }
+
+    #[test]
+    fn parses_references_and_escapes() {
+        assert_eq!(text("${a.b}:5432").unwrap(), "<a.b>:5432");
+        assert_eq!(text("x${ env:HOME }y").unwrap(), "x<env:HOME>y");
+        assert_eq!(text("$${a}").unwrap(), "${a}");
+        assert_eq!(text("cost: $5 ${a}").unwrap(), "cost: $5 <a>");
+    }
+
+    #[test]
+    fn rejects_unterminated_and_empty_references() {
+        assert!(text("${a").unwrap_err().contains("unterminated"));
+        assert!(text("${}").unwrap_err().contains("empty"));
+    }
+}
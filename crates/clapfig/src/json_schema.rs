@@ -41,6 +41,19 @@
 //!   `enum` annotation is omitted entirely.
 //! - **Env vars**: when a field maps to an env var, the name is attached as
 //!   the non-standard `x-env` extension.
+//! - **Secrets**: a [`sensitive`](crate::runtime::Leaf::sensitive) field
+//!   (or an array/map of sensitive items) is `writeOnly: true` and never
+//!   carries its `default`.
+//! - **Deprecations**: a [`deprecated`](crate::runtime::Leaf::deprecated)
+//!   field is `deprecated: true`.
+//! - **Constraints**: a leaf's [`Constraints`](crate::runtime::Constraints)
+//!   become `pattern`, `minLength`/`maxLength`, and
+//!   `minimum`/`maximum`/`exclusiveMinimum`/`exclusiveMaximum`; an
+//!   array's item constraints become `minItems`/`maxItems`/`uniqueItems`.
+//! - **Paths**: a [`Path`](crate::runtime::LeafType::Path) leaf is a
+//!   `string` with the non-standard `x-path` extension describing how it
+//!   loads: `relativeTo: "config-file"`, the `expand` prefixes (`~`, and
+//!   `$VAR` with `expand_vars`), and `mustExist` / `kind` when set.
 //! - **Tagged unions**: an internally tagged shape is JSON Schema `oneOf`.
 //!   Each branch is that variant's object schema plus the tag as a required
 //!   property whose schema is `{ "type": "string", "const": "<discriminator>" }`.
@@ -64,7 +77,9 @@
 
 use serde_json::{Map, Value, json};
 
-use crate::runtime::{Leaf, LeafType, NamedField, Schema, Shape, TaggedShape, TaggedVariant};
+use crate::runtime::{
+    ArrayShape, Leaf, LeafType, NamedField, PathKind, Schema, Shape, TaggedShape, TaggedVariant,
+};
 use crate::value::Value as ConfigValue;
 
 /// JSON Schema dialect emitted in the root `$schema` field.
@@ -239,7 +254,10 @@ fn field_to_property(field: &NamedField) -> (String, Value, bool) {
             if let Some(items) = shape_to_schema(&array.item) {
                 prop.insert("items".into(), items);
             }
+            populate_array_constraints(&mut prop, array);
             populate_container_attrs(&mut prop, array.default.as_ref(), array.env.as_deref());
+            mark_write_only(&mut prop, &field.field);
+            mark_deprecated(&mut prop, &field.field);
             (field.name.clone(), Value::Object(prop), false)
         }
         Shape::Map(map) => {
@@ -257,6 +275,8 @@ fn field_to_property(field: &NamedField) -> (String, Value, bool) {
                 prop.insert("additionalProperties".into(), entry);
             }
             populate_container_attrs(&mut prop, map.default.as_ref(), map.env.as_deref());
+            mark_write_only(&mut prop, &field.field);
+            mark_deprecated(&mut prop, &field.field);
             (field.name.clone(), Value::Object(prop), false)
         }
         Shape::Leaf(leaf) => {
@@ -265,6 +285,8 @@ fn field_to_property(field: &NamedField) -> (String, Value, bool) {
                 prop.insert("description".into(), Value::String(join_doc(&leaf.doc)));
             }
             populate_leaf(&mut prop, leaf);
+            mark_write_only(&mut prop, &field.field);
+            mark_deprecated(&mut prop, &field.field);
             let required = !leaf.optional && leaf.default.is_none();
             (field.name.clone(), Value::Object(prop), required)
         }
@@ -279,13 +301,14 @@ fn field_to_property(field: &NamedField) -> (String, Value, bool) {
 fn shape_to_schema(shape: &Shape) -> Option<Value> {
     match shape {
         Shape::Object(schema) => Some(schema_to_object(schema)),
-        Shape::Leaf(leaf) => leaf_type_to_schema(&leaf.ty).map(Value::Object),
+        Shape::Leaf(leaf) => leaf_to_schema(leaf).map(Value::Object),
         Shape::Array(array) => {
             let mut obj = Map::new();
             obj.insert("type".into(), Value::String("array".into()));
             if let Some(items) = shape_to_schema(&array.item) {
                 obj.insert("items".into(), items);
             }
+            populate_array_constraints(&mut obj, array);
             Some(Value::Object(obj))
         }
         Shape::Map(map) => {
@@ -386,10 +409,27 @@ fn schema_requires_presence(schema: &Schema) -> bool {
     })
 }
 
+/// Mark a secret property `writeOnly` — editors may set it but should
+/// not display it — and drop the default, which is the secret's value.
+fn mark_write_only(prop: &mut Map<String, Value>, shape: &Shape) {
+    if shape.is_sensitive() {
+        prop.remove("default");
+        prop.insert("writeOnly".into(), Value::Bool(true));
+    }
+}
+
+/// Mark a [deprecated](crate::runtime::Leaf::deprecated) property
+/// `deprecated: true`; the note itself goes to the load warning.
+fn mark_deprecated(prop: &mut Map<String, Value>, shape: &Shape) {
+    if shape.deprecated().is_some() {
+        prop.insert("deprecated".into(), Value::Bool(true));
+    }
+}
+
 /// Apply a leaf's declared type, default, and env hint onto its JSON
 /// Schema object.
 fn populate_leaf(prop: &mut Map<String, Value>, leaf: &Leaf) {
-    if let Some(ty_schema) = leaf_type_to_schema(&leaf.ty) {
+    if let Some(ty_schema) = leaf_to_schema(leaf) {
         for (key, value) in ty_schema {
             prop.insert(key, value);
         }
@@ -406,6 +446,49 @@ fn populate_leaf(prop: &mut Map<String, Value>, leaf: &Leaf) {
     }
 }
 
+/// [`leaf_type_to_schema`] plus the leaf's
+/// [`Constraints`](crate::runtime::Constraints). A `Value` leaf takes no
+/// constraints, so `None` stays `None`.
+fn leaf_to_schema(leaf: &Leaf) -> Option<Map<String, Value>> {
+    let mut obj = leaf_type_to_schema(&leaf.ty)?;
+    let constraints = &leaf.constraints;
+    if let Some(pattern) = &constraints.pattern {
+        obj.insert("pattern".into(), Value::String(pattern.as_str().into()));
+    }
+    if let Some(n) = constraints.min_len {
+        obj.insert("minLength".into(), json!(n));
+    }
+    if let Some(n) = constraints.max_len {
+        obj.insert("maxLength".into(), json!(n));
+    }
+    let bounds = [
+        ("minimum", constraints.min),
+        ("maximum", constraints.max),
+        ("exclusiveMinimum", constraints.exclusive_min),
+        ("exclusiveMaximum", constraints.exclusive_max),
+    ];
+    for (keyword, bound) in bounds {
+        if let Some(number) = bound.and_then(serde_json::Number::from_f64) {
+            obj.insert(keyword.into(), Value::Number(number));
+        }
+    }
+    Some(obj)
+}
+
+/// `minItems` / `maxItems` / `uniqueItems` from an array's item
+/// constraints.
+fn populate_array_constraints(obj: &mut Map<String, Value>, array: &ArrayShape) {
+    if let Some(n) = array.min_items {
+        obj.insert("minItems".into(), json!(n));
+    }
+    if let Some(n) = array.max_items {
+        obj.insert("maxItems".into(), json!(n));
+    }
+    if array.unique {
+        obj.insert("uniqueItems".into(), Value::Bool(true));
+    }
+}
+
 /// Recursively convert a runtime [`LeafType`] into the JSON Schema object
 /// constraining values of that type.
 ///
@@ -446,6 +529,17 @@ fn leaf_type_to_schema(ty: &LeafType) -> Option<Map<String, Value>> {
         LeafType::DateTime => {
             obj.extend(datetime_type_schema());
         }
+        LeafType::Path {
+            expand_vars,
+            must_exist,
+            kind,
+        } => {
+            obj.insert("type".into(), Value::String("string".into()));
+            obj.insert(
+                "x-path".into(),
+                path_semantics(*expand_vars, *must_exist, *kind),
+            );
+        }
         LeafType::Enum { values } => {
             if let Some(name) = homogeneous_json_type(values) {
                 obj.insert("type".into(), Value::String(name.into()));
@@ -465,6 +559,32 @@ fn leaf_type_to_schema(ty: &LeafType) -> Option<Map<String, Value>> {
     Some(obj)
 }
 
+/// The `x-path` annotation on a path leaf. JSON Schema has no path
+/// keyword, so clapfig's loading semantics ride a vendor extension that
+/// validators ignore and editors can surface: relative paths resolve
+/// against the config file, which prefixes expand, and whether the path
+/// must exist (and as what).
+fn path_semantics(expand_vars: bool, must_exist: bool, kind: Option<PathKind>) -> Value {
+    let mut expand = vec![json!("~")];
+    if expand_vars {
+        expand.push(json!("$VAR"));
+    }
+    let mut obj = Map::new();
+    obj.insert("relativeTo".into(), json!("config-file"));
+    obj.insert("expand".into(), Value::Array(expand));
+    if must_exist || kind.is_some() {
+        obj.insert("mustExist".into(), Value::Bool(true));
+    }
+    if let Some(kind) = kind {
+        let kind = match kind {
+            PathKind::File => "file",
+            PathKind::Dir => "dir",
+        };
+        obj.insert("kind".into(), json!(kind));
+    }
+    Value::Object(obj)
+}
+
 /// JSON Schema for a datetime leaf: `type: string` plus an `anyOf`
 /// covering TOML's four lexical forms.
 ///
@@ -899,6 +1019,102 @@ mod tests {
         );
     }
 
+    #[test]
+    fn constraints_become_validation_keywords() {
+        use crate::runtime::{Field, Schema as RtSchema};
+        let s = generate_schema(
+            RtSchema::object("App")
+                .field("name", Field::string().pattern("^[a-z]+$").non_empty())
+                .field(
+                    "ratio",
+                    Field::float().exclusive_min(0.0).max(1.0).default(0.5),
+                )
+                .field(
+                    "tags",
+                    Field::array_of_type(Field::string().max_len(16))
+                        .max_items(4)
+                        .unique(),
+                )
+                .build(),
+        );
+        let props = &s["properties"];
+        assert_eq!(props["name"]["pattern"], "^[a-z]+$");
+        assert_eq!(props["name"]["minLength"], 1);
+        assert_eq!(props["ratio"]["exclusiveMinimum"], json!(0.0));
+        assert_eq!(props["ratio"]["maximum"], json!(1.0));
+        assert!(props["ratio"].get("minimum").is_none());
+        assert_eq!(props["tags"]["maxItems"], 4);
+        assert_eq!(props["tags"]["uniqueItems"], true);
+        assert!(props["tags"].get("minItems").is_none());
+        assert_eq!(props["tags"]["items"]["maxLength"], 16);
+    }
+
+    #[test]
+    fn path_leaves_carry_their_semantics_in_x_path() {
+        use crate::runtime::{Field, Schema as RtSchema};
+        let s = generate_schema(
+            RtSchema::object("App")
+                .field("data", Field::path())
+                .field(
+                    "roots",
+                    Field::array_of_type(Field::path().expand_vars().is_dir()),
+                )
+                .build(),
+        );
+        let props = &s["properties"];
+        assert_eq!(props["data"]["type"], "string");
+        assert_eq!(
+            props["data"]["x-path"],
+            json!({"relativeTo": "config-file", "expand": ["~"]})
+        );
+        assert_eq!(
+            props["roots"]["items"]["x-path"],
+            json!({
+                "relativeTo": "config-file",
+                "expand": ["~", "$VAR"],
+                "mustExist": true,
+                "kind": "dir",
+            })
+        );
+    }
+
+    #[test]
+    fn sensitive_fields_are_write_only_without_defaults() {
+        use crate::runtime::{Field, Schema as RtSchema};
+        let s = generate_schema(
+            RtSchema::object("App")
+                .field("token", Field::string().default("dev-token").sensitive())
+                .field("keys", Field::map_of(Field::string()).sensitive())
+                .field("host", Field::string().default("h"))
+                .build(),
+        );
+        let props = &s["properties"];
+        assert_eq!(props["token"]["writeOnly"], true);
+        assert!(props["token"].get("default").is_none());
+        assert_eq!(props["keys"]["writeOnly"], true);
+        assert!(props["host"].get("writeOnly").is_none());
+        assert_eq!(props["host"]["default"], "h");
+    }
+
+    #[test]
+    fn deprecated_fields_are_marked_deprecated() {
+        use crate::runtime::{Field, Schema as RtSchema};
+        let s = generate_schema(
+            RtSchema::object("App")
+                .field("timeout", Field::integer().deprecated("use `deadline`"))
+                .field(
+                    "hosts",
+                    Field::array_of_type(Field::string()).deprecated("gone"),
+                )
+                .field("deadline", Field::integer().alias("timeout_secs"))
+                .build(),
+        );
+        let props = &s["properties"];
+        assert_eq!(props["timeout"]["deprecated"], true);
+        assert_eq!(props["hosts"]["deprecated"], true);
+        assert!(props["deadline"].get("deprecated").is_none());
+    }
+
     #[test]
     fn integer_bounds_emit_minimum_and_maximum() {
         use crate::runtime::{Field, Schema as RtSchema};
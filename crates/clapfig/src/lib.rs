@@ -101,7 +101,7 @@
 //! crate's types. Serialization formats get the same treatment as CLI
 //! frameworks: **adapters at the boundary** (the [`format`](mod@format)
 //! module), one
-//! per format — TOML, YAML, JSON — behind a single contract
+//! per format — TOML, YAML, JSON, JSON5, INI — behind a single contract
 //! ([`format::FormatAdapter`]) that parses text into `Value`, renders
 //! documented templates, serializes, and edits files.
 //!
@@ -114,7 +114,7 @@
 //!   thing in every format: identical schema validation and strict-mode
 //!   accept/reject decisions. Unknown-key and `InvalidValue` errors
 //!   locate the offending token from the adapter's byte-span index in
-//!   TOML, YAML, and JSON (line/column at render time).
+//!   TOML, YAML, JSON, JSON5, and INI (line/column at render time).
 //! - **Datetimes cross formats by schema, not by sniffing.** TOML has
 //!   first-class datetimes; in YAML and JSON they are written as strings
 //!   in TOML's four datetime spellings (offset date-time, local
@@ -136,6 +136,10 @@
 //!   inferred from cargo features); the first entry is the preferred
 //!   format `config gen` renders and file seeding uses. The Discovery
 //!   section below spells out the full file-name contract.
+//! - **The adapter set is open.** A format clapfig does not ship
+//!   registers through [`format_adapter`](Builder::format_adapter) and is
+//!   then discovered, generated, edited, and error-rendered exactly like
+//!   the built-ins.
 //!
 //! # Design: clapfig traces itself
 //!
@@ -158,10 +162,13 @@
 //! - **`info` and above** — silent on a healthy load.
 //!
 //! **Values never appear in events**, at any level. Config values
-//! routinely include tokens and passwords, and clapfig has no sensitivity
-//! metadata. Logs carry key paths, origins, value types, and precedence
+//! routinely include tokens and passwords, and not every secret is marked
+//! as one. Logs carry key paths, origins, value types, and precedence
 //! decisions only. User-facing errors may still quote the offending
-//! value; that is a different contract.
+//! value; that is a different contract — unless the field is
+//! [`sensitive`](runtime::Leaf::sensitive) (`#[clapfig(secret)]`), whose
+//! value is redacted in error reasons, source snippets, and every
+//! `config` result.
 //!
 //! # Core library — no CLI framework required
 //!
@@ -213,6 +220,18 @@
 //! can target a single key, and CLI flags only override what the user
 //! explicitly passes.
 //!
+//! Maps merge entry by entry and arrays replace whole, unless the schema
+//! says otherwise: `#[clapfig(merge = "append")]` (or `prepend`, `union`,
+//! `replace`) and `#[clapfig(merge_by_key = "name")]` pick a
+//! [`MergeStrategy`](runtime::MergeStrategy) per field, and each element
+//! keeps the origin of the layer that supplied it.
+//!
+//! A layer can also take a value back. With
+//! [`.unset_marker("<unset>")`](Builder::unset_marker), an input that
+//! supplies exactly that string unsets the key: an optional leaf becomes
+//! absent and a defaulted leaf reverts to its default, with the reset
+//! recorded in the origin tree.
+//!
 //! # Three axes of file handling
 //!
 //! Config file behavior is controlled by three independent settings on the
@@ -291,6 +310,54 @@
 //! See the [`types`] module for common patterns: layered global + local,
 //! fallback chains, nearest project config, per-directory layering.
 //!
+//! # Includes
+//!
+//! With [`include_key("include")`](Builder::include_key), a config file may
+//! name other files to load: `include = ["common.toml", "conf.d/*.toml"]`.
+//! Paths are relative to the including file, `*` and `?` match in the last
+//! path component, and each included file is layered just below the file
+//! that includes it. Included files keep their own origins and their own
+//! unknown-key checks. A missing file is
+//! [`IncludeNotFound`](ClapfigError::IncludeNotFound) and a loop is
+//! [`IncludeCycle`](ClapfigError::IncludeCycle).
+//!
+//! # Profiles
+//!
+//! With [`profile_key("profile")`](Builder::profile_key), a config file
+//! may hold named variants as tables: `[profile.prod]`. The active profile
+//! comes from [`profile("prod")`](Builder::profile), else the
+//! `MYAPP_PROFILE` environment variable. The variable is only read when
+//! profiles are enabled, so set `profile_key` if it alone should select
+//! one. Each file's section for it is layered just above that file, and a
+//! sibling `myapp.prod.toml` next to a discovered `myapp.toml` loads just
+//! above it too. Every section is
+//! validated against the schema, active or not, and the profile table is
+//! never an unknown key. `config list` names the active profile, and
+//! [`ConfigArgs`] carries a `--profile` flag for the builder.
+//!
+//! # Migrations
+//!
+//! When a release renames, moves, or drops a key, register a
+//! [`Migration`] instead of breaking existing files:
+//! `.migration(Migration::rename("db", "database"))`,
+//! [`Migration::remove`], or [`Migration::transform`] for a value whose
+//! unit changed. Loading applies them in memory before validation and
+//! raises a [`Warning::UnmigratedFile`] for a file that still needs any.
+//! `config migrate` rewrites every scope file in place through the
+//! format adapter's editor.
+//! With [`schema_version_key("schema_version")`](Builder::schema_version_key),
+//! a migration marked `.at_version(n)` applies only to files below
+//! version `n`, and `config migrate` stamps each file with the latest
+//! version.
+//!
+//! A rename that needs no file rewrite can instead keep the old key as an
+//! alias: `#[clapfig(alias = "old_name")]` (or
+//! [`FieldBuilder::alias`](runtime::FieldBuilder::alias)) accepts it in
+//! every input, rewritten to the field name before validation. A field
+//! marked `#[clapfig(deprecated = "...")]` still loads, but each input
+//! that sets it logs a `warn` event with its file and line; the JSON
+//! Schema marks it `deprecated` and `config gen` leaves it out.
+//!
 //! # Environment variables
 //!
 //! With env prefix `MYAPP`, variables map via double-underscore nesting:
@@ -304,10 +371,32 @@
 //! segment is literal (part of the field name). Segments are lowercased to
 //! match Rust field names.
 //!
-//! Values are parsed heuristically: `true`/`false` → bool, then integer,
-//! then float, then string. This works well for the common case (ports,
-//! flags, URLs). If you need exact control over how a value is interpreted,
-//! use serde's `#[serde(deserialize_with = ...)]` on the field.
+//! Values are parsed by the field's declared type: a `String` field takes
+//! `MYAPP__ZIP=01234` verbatim, an integer field parses `8080`, a bool
+//! field takes `true` / `false`, `yes` / `no`, `on` / `off`, or `1` / `0`
+//! in any case, and an enum matches its members literally. A value that
+//! does not spell the declared type fails the type check naming the
+//! variable. Only `Value` fields and keys the
+//! schema does not declare fall back to the heuristic: `true`/`false` →
+//! bool, then integer, then float, then string.
+//!
+//! Arrays and maps follow the schema too. On an array field,
+//! `MYAPP__ALLOWED_HOSTS=a,b,c` is a list (separator configurable with
+//! [`env_list_separator`](Builder::env_list_separator)), and an inline
+//! `[...]` literal in TOML or JSON syntax works for any array. Indexed
+//! variables build arrays of tables: `MYAPP__SERVERS__0__HOST` and
+//! `MYAPP__SERVERS__1__HOST` set `servers[0].host` and `servers[1].host`,
+//! and each element's origin names its own variable. Indices are only an
+//! order: gaps close up, so `__0` and `__5` make a two-element array. A
+//! map field also takes an inline `{...}` literal.
+//!
+//! With [`env_file_secrets(true)`](Builder::env_file_secrets), a
+//! Docker-style `_FILE` variable sets a leaf to the contents of the file
+//! it names: `MYAPP__DATABASE__PASSWORD_FILE=/run/secrets/db` loads
+//! `database.password`. A [`from_file`](runtime::Leaf::from_file) leaf
+//! (`#[clapfig(from_file)]`) does the same from any layer, so
+//! `token = "token.txt"` in a config file loads `token.txt` beside it.
+//! The origin names both the input and the file read.
 //!
 //! If the environment sets the same key both flat and nested
 //! (`MYAPP__DATABASE` and `MYAPP__DATABASE__URL`), the last-processed
@@ -338,14 +427,14 @@
 //! ```
 //!
 //! Keys use `.` for nesting (same as CLI overrides). Values are
-//! percent-decoded and parsed with the same heuristic as env vars. A leading
-//! `?` is stripped if present.
+//! percent-decoded and parsed by the declared field type, the same as env
+//! vars. A leading `?` is stripped if present.
 //!
 //! | Query param | Config key | Parsed value |
 //! |---|---|---|
-//! | `port=9090` | `port` | `9090` (integer) |
-//! | `database.url=pg%3A%2F%2Fprod` | `database.url` | `"pg://prod"` (string) |
-//! | `debug=true` | `debug` | `true` (bool) |
+//! | `port=9090` | `port` (integer field) | `9090` (integer) |
+//! | `database.url=pg%3A%2F%2Fprod` | `database.url` (string field) | `"pg://prod"` (string) |
+//! | `debug=true` | `debug` (bool field) | `true` (bool) |
 //!
 //! Clapfig is framework-agnostic — it takes a raw query string, not a
 //! framework-specific request object. Your app extracts the query string
@@ -375,9 +464,25 @@
 //! the CLI name differs from the config key (e.g. `--db-url` vs
 //! `database.url`), use `cli_override("database.url", cli.db_url)`.
 //!
+//! With clap, [`ConfigFlags`] can generate those flags from the schema
+//! instead; see the [clap adapter](#clap-adapter).
+//!
 //! Both methods push to the same override list and compose freely. Later
 //! calls take precedence.
 //!
+//! # Interpolation
+//!
+//! With [`interpolate(true)`](Builder::interpolate), string values may
+//! reference other keys and the process environment:
+//! `url = "postgres://${database.host}:5432"`, `dir = "${env:HOME}/app"`,
+//! and `$${` for a literal `${`. References see the merged, resolved
+//! value, and the expanded string is parsed by the field's type before
+//! validation. A missing key, an unset variable, a cycle, or a sensitive
+//! key named from a field that is not sensitive is an
+//! [`InvalidValue`](ClapfigError::InvalidValue) located at the string that
+//! holds the reference. [`Provenance::raw`] keeps the string as written,
+//! and `config get` prints it beside the expanded value.
+//!
 //! # Strict mode
 //!
 //! Strict mode is **on by default**. When a config file contains a key that
@@ -594,9 +699,19 @@
 //! - numeric ranges (`port >= 1024`, `quality <= 100`, `pool_size > 0`)
 //! - cross-field invariants (`if tls_enabled then tls_cert_path must be set`)
 //! - enum combinations (`mode == "fast" requires buffer_size < 64k`)
-//! - filesystem preconditions (`output_dir must exist and be writable`)
+//! - filesystem preconditions (`output_dir must be writable`)
 //! - anything that needs the final, fully-merged `&C` to decide
 //!
+//! Single-field rules that the schema can state belong there instead:
+//! `#[clapfig(pattern = "...")]`, `min_len`/`max_len`, float
+//! `min`/`max`/`exclusive_min`/`exclusive_max`, `non_empty`, and
+//! `min_items`/`max_items`/`unique` on arrays (see
+//! [`runtime::Constraints`]), and `must_exist`/`is_dir`/`is_file` on a
+//! `PathBuf` field, whose relative paths resolve against the config file
+//! that set them (see [`runtime::LeafType::Path`]). They are checked with
+//! the types, so a failure names the file and line that set the value,
+//! and JSON Schema export carries them. The hook is for everything else.
+//!
 //! Write them once, in a closure, and register it on the builder:
 //!
 //! ```ignore
@@ -669,11 +784,19 @@
 //! - **Files are cached by absolute path inside the resolver.** A tree walk
 //!   that visits 1000 leaves sharing 5 ancestor config files pays the disk +
 //!   parse cost once per unique file, not 1000×. The resolver is the cache
-//!   scope — drop it to invalidate everything.
-//! - **No mtime checking.** The cache is not invalidated when files change on
-//!   disk. Long-lived processes that need freshness should build a new
-//!   resolver. This is a deliberate "keep it simple" choice; the contract
-//!   is documented and a regression test locks it in.
+//!   scope — drop it to invalidate everything, or drop entries explicitly
+//!   with [`Resolver::invalidate`] and [`Resolver::clear_cache`].
+//! - **No mtime checking by default.** The cache is not invalidated when
+//!   files change on disk: repeated `resolve_at` calls see the contents
+//!   first read. This is a deliberate "keep it simple" choice; the contract
+//!   is documented and a regression test locks it in. A resolver that
+//!   serves repeated `resolve_at` calls opts into
+//!   [`CacheFreshness::Metadata`] (re-read on length or mtime change) or
+//!   [`CacheFreshness::Always`] via [`Builder::cache_freshness`]. Processes
+//!   that want changes pushed to them hand the resolver to
+//!   [`Resolver::watch`], which polls every path discovery probed (misses
+//!   included), evicts what changed, and pushes each re-resolved,
+//!   post-validated config down a channel — see [`watch`].
 //! - **`load()` is the special case.** Internally `load()` is just
 //!   `self.build_resolver()?.resolve_at(std::env::current_dir()?)`, so
 //!   existing single-shot callers get zero behavior change and all resolution
@@ -688,6 +811,28 @@
 //!
 //! See [`Resolver`] and [`TypedResolver`] for the full API.
 //!
+//! # Provenance — where each value came from
+//!
+//! [`Builder::load_with_origins`] (and
+//! [`Resolver::resolve_at_with_origins`], plus the typed
+//! [`TypedBuilder::load_with_origins`] returning `(C, Provenance)`)
+//! returns the resolved config together with a read-only [`Provenance`]
+//! tree: for every value, the input type that won it and the file and
+//! line, environment variable, URL query key, or override key behind it.
+//!
+//! ```ignore
+//! let (cfg, provenance) = Clapfig::typed::<AppConfig>()
+//!     .app_name("myapp")
+//!     .load_with_origins()?;
+//! if let Some(origin) = provenance.get_dotted("database.pool_size") {
+//!     println!("{:?} {:?}", origin.input_type, origin.env_var);
+//! }
+//! ```
+//!
+//! The tree is the one the merge already keeps for error locations
+//! (winner-only): it names the input that supplied the effective value,
+//! never the candidates it beat.
+//!
 //! # Normalizing values
 //!
 //! Use serde's `#[serde(deserialize_with = ...)]` on a field to normalize
@@ -765,6 +910,17 @@
 //! support. Pick `ConfigArgs` for simplicity; reach for `ConfigCommand` only
 //! when you hit naming conflicts.
 //!
+//! Both also take a repeatable global `-c key=value` / `--set key=value`
+//! option for one-off overrides of any key; pass the assignments to
+//! [`cli_assignments()`](Builder::cli_assignments), which types each value
+//! the way `config set` does and rejects unknown keys before loading.
+//!
+//! For settings themselves, [`ConfigFlags`] generates one `--long` flag per
+//! schema leaf (`--database-pool-size`), typed and documented from the
+//! schema, with `include` / `exclude` subtrees and per-flag `rename`.
+//! [`cli_overrides_from_matches()`](Builder::cli_overrides_from_matches)
+//! feeds the flags the user gave back in as `Cli`-layer overrides.
+//!
 //! # Persistence
 //!
 //! `config set` and `config unset` write to config files through named
@@ -799,33 +955,55 @@
 //!
 //! All fallible operations return [`ClapfigError`]. Errors are designed to
 //! be user-facing: unknown keys include file paths and, when the span
-//! index locates the key, line numbers (TOML, YAML, and JSON), unknown
+//! index locates the key, line numbers (TOML, YAML, JSON, JSON5, and INI), unknown
 //! scopes list the available ones, and missing
 //! prerequisites reference the builder method to call. See the [`error`]
 //! module for the full set.
+//!
+//! A load that succeeds can still raise [`Warning`]s: a deprecated key,
+//! an environment variable shadowing a file value, a world-readable file
+//! holding secrets, one config file name found in two formats, a file
+//! that still needs a migration.
+//! [`load_with_diagnostics`](Builder::load_with_diagnostics) returns them
+//! next to the config, [`render`] draws them like errors, and
+//! [`deny_warnings`](Builder::deny_warnings) turns chosen
+//! [`WarningKind`]s into [`ClapfigError::DeniedWarnings`]. See the
+//! [`diagnostics`] module.
 
+pub mod diagnostics;
+pub mod diff;
 pub mod error;
 pub mod format;
 pub mod json_schema;
 pub mod meta;
+pub mod provenance;
 pub mod render;
 pub mod runtime;
 pub mod static_schema;
 pub mod types;
 pub mod value;
+pub mod watch;
 
+mod alias;
 mod builder;
 #[cfg(feature = "clap")]
 mod cli;
 mod env;
 mod file;
+mod file_ref;
 mod flatten;
+mod include;
+mod interpolate;
 pub(crate) mod merge;
+mod migrate;
 mod normalize;
 mod ops;
 mod origin;
 mod overrides;
+mod paths;
 mod persist;
+mod profile;
+mod redact;
 mod resolve;
 mod schema_walk;
 mod strict;
@@ -842,15 +1020,22 @@ pub use builder::{Builder, Resolver};
 #[cfg(feature = "derive")]
 pub use clapfig_derive::Schema;
 #[cfg(feature = "clap")]
-pub use cli::{ConfigArgs, ConfigCommand, ConfigSubcommand};
+pub use cli::{ConfigArgs, ConfigCommand, ConfigFlags, ConfigSubcommand};
+pub use diagnostics::{Warning, WarningKind};
+pub use diff::{ConfigDiff, DiffEntry, DiffKind};
 pub use error::{
     ClapfigError, DiscoveryRecord, FileProbe, OriginFacts, ProbeOutcome, UnknownKeyInfo,
 };
+pub use migrate::Migration;
 pub use ops::ConfigResult;
+pub use provenance::{Candidate, Explanation, Provenance};
 pub use static_schema::{DocumentRoot, Schema};
 pub use strict::{CollectedUnknown, UnknownKeyContext, UnknownKeyDecision};
 pub use typed_builder::{TypedBuilder, TypedResolver};
-pub use types::{Boundary, ConfigAction, InputType, Layer, SearchMode, SearchPath};
+pub use types::{
+    Boundary, CacheFreshness, ConfigAction, DiffTarget, InputType, Layer, SearchMode, SearchPath,
+};
+pub use watch::{ConfigWatch, WatchOptions};
 
 /// Entry point for building a clapfig configuration.
 ///
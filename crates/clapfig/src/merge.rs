@@ -6,52 +6,255 @@
 //! wins. Arrays replace wholesale; the origin subtree replaces wholesale
 //! with them.
 //!
+//! The schema can say otherwise per node ([`MergeStrategy`]): an array may
+//! append, prepend, union, or merge its tables by a key field, and a map
+//! may replace instead of merging. Combined arrays keep each element's
+//! origin from the layer that supplied it. Keys the schema does not
+//! declare merge by the defaults.
+//!
+//! With an unset marker enabled, an overlay string equal to the marker
+//! removes the key instead of setting it. Its origin node stays, flagged
+//! [`reset`](Origin::reset), so a default refilled later still says
+//! which input reset it, and a later merge of the layer holding the reset
+//! drops the key from the layers below as well.
+//!
 //! Every overlay win (an existing value replaced, including file-vs-file
 //! inside `Layer::Files`) emits a `trace` event naming both origins and
 //! both value **types**, never the values (ADR-0004, ADR-0009).
 
 use crate::format::ConfigPath;
-use crate::origin::{Origin, OriginMap, OriginNode, take_map_children};
+use crate::origin::{Origin, OriginMap, OriginNode, take_array_children, take_map_children};
+use crate::runtime::{
+    DocumentRoot, KeyAcrossVariants, MapShape, MergeStrategy, Schema, Shape, TaggedShape,
+};
 use crate::value::{Map, Value};
 
 /// Deep-merge `overlay` on top of `base`, with origin trees in lockstep.
 ///
 /// If both sides have a Map for the same key, recurse. Otherwise
-/// `overlay`'s value **and** origin win. Arrays are not merged element-wise.
+/// `overlay`'s value **and** origin win. Arrays are not merged
+/// element-wise unless `root` gives the node a [`MergeStrategy`]. An
+/// overlay string equal to `unset` removes the key from the result.
 /// Replacing an existing value fires [`crate::trace::overlay_win`].
 /// Path tracking for those events is skipped when TRACE is disabled, so
 /// unused tracing does not allocate per key (ADR-0009).
 pub(crate) fn deep_merge(
+    root: DocumentRoot<'_>,
+    unset: Option<&str>,
     base: Map,
     overlay: Map,
     base_origins: OriginMap,
     overlay_origins: OriginMap,
 ) -> (Map, OriginMap) {
     let path = crate::trace::trace_event_enabled().then(ConfigPath::new);
-    deep_merge_at(path, base, overlay, base_origins, overlay_origins)
+    let scope = match root {
+        DocumentRoot::Object(schema) => Scope::Object(schema),
+        DocumentRoot::Map(map) => Scope::Map(map),
+        DocumentRoot::Tagged(tagged) => Scope::Tagged(tagged),
+    };
+    deep_merge_at(
+        path,
+        scope,
+        unset,
+        base,
+        overlay,
+        base_origins,
+        overlay_origins,
+    )
+}
+
+/// The schema node of a map being merged, for looking up each key's shape.
+#[derive(Clone, Copy)]
+enum Scope<'s> {
+    Object(&'s Schema),
+    Map(&'s MapShape),
+    Tagged(&'s TaggedShape),
+    Unknown,
+}
+
+impl<'s> Scope<'s> {
+    fn of(shape: Option<&'s Shape>) -> Self {
+        match shape {
+            Some(Shape::Object(schema)) => Scope::Object(schema),
+            Some(Shape::Map(map)) => Scope::Map(map),
+            Some(Shape::Tagged(tagged)) => Scope::Tagged(tagged),
+            _ => Scope::Unknown,
+        }
+    }
+
+    /// Settle a union on the variant the higher layer's tag selects, else
+    /// the lower layer's.
+    fn select(self, base: &Map, overlay: &Map) -> Self {
+        match self {
+            Scope::Tagged(tagged) => tagged
+                .selected(overlay)
+                .or_else(|| tagged.selected(base))
+                .map_or(Scope::Unknown, |variant| Scope::Object(&variant.schema)),
+            other => other,
+        }
+    }
+
+    fn child(self, key: &str) -> Option<&'s Shape> {
+        match self {
+            Scope::Object(schema) => schema
+                .fields
+                .iter()
+                .find(|nf| nf.name == key)
+                .map(|nf| &nf.field),
+            Scope::Map(map) => Some(&map.item),
+            Scope::Tagged(_) | Scope::Unknown => None,
+        }
+    }
+}
+
+fn strategy_of(shape: Option<&Shape>) -> Option<&MergeStrategy> {
+    match shape {
+        Some(Shape::Array(array)) => array.merge.as_ref(),
+        Some(Shape::Map(map)) => map.merge.as_ref(),
+        _ => None,
+    }
+}
+
+/// Whether the array at the dotted `key` under the document root `shape`
+/// combines across layers — any strategy but
+/// [`Replace`](MergeStrategy::Replace) — so a lower layer's elements
+/// survive the merge. A union answers for every variant declaring `key`.
+pub(crate) fn combines_at(shape: &Shape, key: &str) -> bool {
+    let mut shapes = vec![shape];
+    for segment in key.split('.') {
+        shapes = shapes
+            .into_iter()
+            .flat_map(|shape| match shape {
+                Shape::Object(schema) => Scope::Object(schema).child(segment).into_iter().collect(),
+                Shape::Map(map) => vec![&*map.item],
+                Shape::Tagged(tagged) => match tagged.resolve_key(segment) {
+                    KeyAcrossVariants::Every(shapes) | KeyAcrossVariants::Partial(shapes) => shapes,
+                    KeyAcrossVariants::Tag | KeyAcrossVariants::Absent => Vec::new(),
+                },
+                Shape::Leaf(_) | Shape::Array(_) => Vec::new(),
+            })
+            .collect();
+    }
+    shapes.into_iter().any(|shape| {
+        matches!(
+            shape,
+            Shape::Array(array)
+                if array.merge.as_ref().is_some_and(|m| *m != MergeStrategy::Replace)
+        )
+    })
 }
 
 fn deep_merge_at(
     path: Option<ConfigPath>,
+    scope: Scope<'_>,
+    unset: Option<&str>,
     mut base: Map,
     overlay: Map,
     mut base_origins: OriginMap,
     mut overlay_origins: OriginMap,
 ) -> (Map, OriginMap) {
+    let scope = scope.select(&base, &overlay);
     for (key, overlay_val) in overlay {
+        let shape = scope.child(&key);
+        let strategy = strategy_of(shape);
         match (base.remove(&key), overlay_val) {
-            (Some(Value::Map(base_map)), Value::Map(overlay_map)) => {
+            (_, Value::String(marker)) if Some(marker.as_str()) == unset => {
+                match overlay_origins.remove(&key) {
+                    Some(mut node) => {
+                        if let Some(path) = &path {
+                            crate::trace::value_reset(
+                                &path.clone().key(&key),
+                                &node.origin.label(),
+                            );
+                        }
+                        node.origin.reset = true;
+                        base_origins.insert(key, OriginNode::leaf(node.origin));
+                    }
+                    None => {
+                        base_origins.remove(&key);
+                    }
+                }
+            }
+            (Some(Value::Map(base_map)), Value::Map(overlay_map))
+                if strategy != Some(&MergeStrategy::Replace) =>
+            {
                 let child = path.as_ref().map(|p| p.clone().key(&key));
                 let (base_origin, base_om) = take_map_children(base_origins.remove(&key));
                 let (overlay_origin, overlay_om) = take_map_children(overlay_origins.remove(&key));
-                let (merged, merged_o) =
-                    deep_merge_at(child, base_map, overlay_map, base_om, overlay_om);
+                let (merged, merged_o) = deep_merge_at(
+                    child,
+                    Scope::of(shape),
+                    unset,
+                    base_map,
+                    overlay_map,
+                    base_om,
+                    overlay_om,
+                );
                 base.insert(key.clone(), Value::Map(merged));
                 let origin = overlay_origin
                     .or(base_origin)
                     .unwrap_or_else(|| Origin::default(key.clone()));
                 base_origins.insert(key, OriginNode::map(origin, merged_o));
             }
+            // A marker nested in a map that lands whole must still unset
+            // its key, so walk the map over an empty base.
+            (base_val, Value::Map(overlay_map)) if unset.is_some() => {
+                let child = path.as_ref().map(|p| p.clone().key(&key));
+                if let (Some(loser), Some(path)) = (&base_val, &child) {
+                    crate::trace::overlay_win(
+                        path,
+                        &origin_node_label(overlay_origins.get(&key)),
+                        &origin_node_label(base_origins.get(&key)),
+                        "map",
+                        loser.type_str(),
+                    );
+                }
+                base_origins.remove(&key);
+                let (overlay_origin, overlay_om) = take_map_children(overlay_origins.remove(&key));
+                let (merged, merged_o) = deep_merge_at(
+                    child,
+                    Scope::of(shape),
+                    unset,
+                    Map::new(),
+                    overlay_map,
+                    OriginMap::new(),
+                    overlay_om,
+                );
+                base.insert(key.clone(), Value::Map(merged));
+                if let Some(origin) = overlay_origin {
+                    base_origins.insert(key, OriginNode::map(origin, merged_o));
+                }
+            }
+            (Some(Value::Array(base_items)), Value::Array(overlay_items))
+                if strategy.is_some_and(|s| *s != MergeStrategy::Replace) =>
+            {
+                let Some(Shape::Array(array)) = shape else {
+                    unreachable!("only an array shape carries an array strategy");
+                };
+                let (base_origin, base_elems) = elements(base_items, base_origins.remove(&key));
+                let (overlay_origin, overlay_elems) =
+                    elements(overlay_items, overlay_origins.remove(&key));
+                let combined = combine(
+                    path.as_ref().map(|p| p.clone().key(&key)),
+                    array.merge.as_ref().expect("checked above"),
+                    Scope::of(Some(&array.item)),
+                    unset,
+                    base_elems,
+                    overlay_elems,
+                );
+                let (items, nodes): (Vec<_>, Vec<_>) = combined.into_iter().unzip();
+                base.insert(key.clone(), Value::Array(items));
+                // An element the lower layers recorded no origin for
+                // leaves the whole array unattributed, as a replace would.
+                match (overlay_origin.or(base_origin), nodes.into_iter().collect()) {
+                    (Some(origin), Some(nodes)) => {
+                        base_origins.insert(key, OriginNode::array(origin, nodes));
+                    }
+                    _ => {
+                        base_origins.remove(&key);
+                    }
+                }
+            }
             (base_val, overlay_val) => {
                 if let (Some(loser), Some(path)) = (&base_val, &path) {
                     crate::trace::overlay_win(
@@ -71,9 +274,94 @@ fn deep_merge_at(
             }
         }
     }
+    // A reset applied while this overlay's own layer was built left an
+    // origin but no value; it still drops whatever lies below.
+    for (key, node) in overlay_origins {
+        if node.origin.reset {
+            base.remove(&key);
+            base_origins.insert(key, node);
+        }
+    }
     (base, base_origins)
 }
 
+/// An array element and its origin subtree, when one was recorded.
+type Element = (Value, Option<OriginNode>);
+
+fn elements(items: Vec<Value>, node: Option<OriginNode>) -> (Option<Origin>, Vec<Element>) {
+    match take_array_children(node, items.len()) {
+        Some((origin, nodes)) => (
+            Some(origin),
+            items.into_iter().zip(nodes.into_iter().map(Some)).collect(),
+        ),
+        None => (None, items.into_iter().map(|item| (item, None)).collect()),
+    }
+}
+
+/// Combine two layers' arrays under a non-`Replace` strategy. `item` is
+/// the element shape, for `MergeByKey`'s deep merge of matching tables.
+fn combine(
+    path: Option<ConfigPath>,
+    strategy: &MergeStrategy,
+    item: Scope<'_>,
+    unset: Option<&str>,
+    mut base: Vec<Element>,
+    mut overlay: Vec<Element>,
+) -> Vec<Element> {
+    match strategy {
+        MergeStrategy::Replace => overlay,
+        MergeStrategy::Append => {
+            base.append(&mut overlay);
+            base
+        }
+        MergeStrategy::Prepend => {
+            overlay.append(&mut base);
+            overlay
+        }
+        MergeStrategy::Union => {
+            for element in overlay {
+                if !base.iter().any(|(value, _)| *value == element.0) {
+                    base.push(element);
+                }
+            }
+            base
+        }
+        MergeStrategy::MergeByKey(field) => {
+            let id = |value: &Value| value.as_map().and_then(|m| m.get(field)).cloned();
+            for (value, node) in overlay {
+                let matched = id(&value).and_then(|wanted| {
+                    base.iter().position(|(v, _)| id(v) == Some(wanted.clone()))
+                });
+                let Some(index) = matched else {
+                    base.push((value, node));
+                    continue;
+                };
+                let (base_value, base_node) =
+                    std::mem::replace(&mut base[index], (Value::Boolean(false), None));
+                let (Value::Map(base_map), Value::Map(overlay_map)) = (base_value, value) else {
+                    unreachable!("only tables carry a key field");
+                };
+                let (base_origin, base_om) = take_map_children(base_node);
+                let (overlay_origin, overlay_om) = take_map_children(node);
+                let (merged, merged_o) = deep_merge_at(
+                    path.as_ref().map(|p| p.clone().index(index)),
+                    item,
+                    unset,
+                    base_map,
+                    overlay_map,
+                    base_om,
+                    overlay_om,
+                );
+                let node = overlay_origin
+                    .or(base_origin)
+                    .map(|origin| OriginNode::map(origin, merged_o));
+                base[index] = (Value::Map(merged), node);
+            }
+            base
+        }
+    }
+}
+
 fn origin_node_label(node: Option<&OriginNode>) -> String {
     node.map(|n| n.origin.label())
         .unwrap_or_else(|| "unknown".into())
@@ -91,7 +379,22 @@ mod tests {
     }
 
     fn merge(base: Map, overlay: Map) -> Map {
-        deep_merge(base, overlay, OriginMap::new(), OriginMap::new()).0
+        deep_merge(
+            root(),
+            None,
+            base,
+            overlay,
+            OriginMap::new(),
+            OriginMap::new(),
+        )
+        .0
+    }
+
+    /// A root that declares nothing: every key merges by the defaults.
+    fn root() -> DocumentRoot<'static> {
+        static EMPTY: std::sync::LazyLock<Schema> =
+            std::sync::LazyLock::new(|| Schema::object("Empty").build());
+        DocumentRoot::Object(&EMPTY)
     }
 
     fn file_leaf(name: &str) -> OriginNode {
@@ -245,7 +548,7 @@ mod tests {
         overlay_db.insert("pool_size".into(), file_leaf("local.toml"));
         overlay_o.insert("database".into(), file_map("local.toml", overlay_db));
 
-        let (merged, origins) = deep_merge(base, overlay, base_o, overlay_o);
+        let (merged, origins) = deep_merge(root(), None, base, overlay, base_o, overlay_o);
         let db = merged["database"].as_map().unwrap();
         assert_eq!(db["url"].as_str().unwrap(), "postgres://old");
         assert_eq!(db["pool_size"].as_integer().unwrap(), 20);
@@ -286,7 +589,7 @@ mod tests {
                 vec![file_leaf("local.toml"), file_leaf("local.toml")],
             ),
         );
-        let (merged, origins) = deep_merge(base, overlay, base_o, overlay_o);
+        let (merged, origins) = deep_merge(root(), None, base, overlay, base_o, overlay_o);
         assert_eq!(
             merged["tags"].as_array().unwrap().len(),
             2,
@@ -319,7 +622,7 @@ mod tests {
         let mut overlay_o = OriginMap::new();
         overlay_o.insert("a.b".into(), file_leaf("local.toml"));
 
-        let (merged, origins) = deep_merge(base, overlay, base_o, overlay_o);
+        let (merged, origins) = deep_merge(root(), None, base, overlay, base_o, overlay_o);
         assert_eq!(merged["a.b"].as_integer().unwrap(), 2);
         assert_eq!(
             lookup(&origins, &ConfigPath::new().key("a.b"))
@@ -340,7 +643,7 @@ mod tests {
         let overlay = table("port = 3000");
         let mut base_o = OriginMap::new();
         base_o.insert("port".into(), file_leaf("base.toml"));
-        let (merged, origins) = deep_merge(base, overlay, base_o, OriginMap::new());
+        let (merged, origins) = deep_merge(root(), None, base, overlay, base_o, OriginMap::new());
         assert_eq!(merged["port"].as_integer().unwrap(), 3000);
         assert!(
             lookup(&origins, &ConfigPath::new().key("port")).is_none(),
@@ -367,7 +670,7 @@ mod tests {
         base_db.insert("url".into(), file_leaf("base.toml"));
         base_o.insert("database".into(), file_map("base.toml", base_db));
 
-        let (merged, origins) = deep_merge(base, overlay, base_o, OriginMap::new());
+        let (merged, origins) = deep_merge(root(), None, base, overlay, base_o, OriginMap::new());
         let db = merged["database"].as_map().unwrap();
         assert_eq!(db["url"].as_str().unwrap(), "postgres://old");
         assert_eq!(db["pool_size"].as_integer().unwrap(), 20);
@@ -382,4 +685,201 @@ mod tests {
             "overlay leaf with no origin must not inherit a synthesized default"
         );
     }
+
+    fn strategy_root(strategy: MergeStrategy) -> Schema {
+        use crate::runtime::Field;
+        let plugin = Schema::object("Plugin")
+            .field("name", Field::string())
+            .field("enabled", Field::boolean())
+            .field("args", Field::array_of_type(Field::string()))
+            .build();
+        let plugins = match &strategy {
+            MergeStrategy::MergeByKey(_) => Shape::array("plugins", plugin),
+            _ => Shape::array("plugins", Field::string()),
+        };
+        Schema::object("App")
+            .field("plugins", plugins.merge(strategy))
+            .field(
+                "aliases",
+                Shape::map("aliases", Field::string()).merge(MergeStrategy::Replace),
+            )
+            .build()
+    }
+
+    fn file_array(name: &str, len: usize) -> OriginNode {
+        OriginNode::array(
+            crate::origin::Origin::file(name.into(), Span { start: 0, end: 1 }, Arc::from("")),
+            (0..len).map(|_| file_leaf(name)).collect(),
+        )
+    }
+
+    fn strings(value: &Value) -> Vec<&str> {
+        value
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|v| v.as_str().unwrap())
+            .collect()
+    }
+
+    fn item_file(origins: &OriginMap, index: usize) -> String {
+        lookup(origins, &ConfigPath::new().key("plugins").index(index))
+            .and_then(|o| o.file.as_ref())
+            .map(|p| p.display().to_string())
+            .expect("item origin")
+    }
+
+    fn merge_plugins(strategy: MergeStrategy, base: &str, overlay: &str) -> (Map, OriginMap) {
+        let schema = strategy_root(strategy);
+        let base = table(base);
+        let overlay = table(overlay);
+        let len = |t: &Map| t["plugins"].as_array().unwrap().len();
+        let mut base_o = OriginMap::new();
+        base_o.insert("plugins".into(), file_array("global.toml", len(&base)));
+        let mut overlay_o = OriginMap::new();
+        overlay_o.insert("plugins".into(), file_array("project.toml", len(&overlay)));
+        deep_merge(
+            DocumentRoot::Object(&schema),
+            None,
+            base,
+            overlay,
+            base_o,
+            overlay_o,
+        )
+    }
+
+    #[test]
+    fn append_and_prepend_keep_each_element_origin() {
+        let (merged, origins) = merge_plugins(
+            MergeStrategy::Append,
+            r#"plugins = ["a", "b"]"#,
+            r#"plugins = ["c"]"#,
+        );
+        assert_eq!(strings(&merged["plugins"]), ["a", "b", "c"]);
+        assert_eq!(item_file(&origins, 0), "global.toml");
+        assert_eq!(item_file(&origins, 2), "project.toml");
+
+        let (merged, origins) = merge_plugins(
+            MergeStrategy::Prepend,
+            r#"plugins = ["a", "b"]"#,
+            r#"plugins = ["c"]"#,
+        );
+        assert_eq!(strings(&merged["plugins"]), ["c", "a", "b"]);
+        assert_eq!(item_file(&origins, 0), "project.toml");
+        assert_eq!(item_file(&origins, 1), "global.toml");
+    }
+
+    #[test]
+    fn union_skips_elements_already_present() {
+        let (merged, origins) = merge_plugins(
+            MergeStrategy::Union,
+            r#"plugins = ["a", "b"]"#,
+            r#"plugins = ["b", "c", "c"]"#,
+        );
+        assert_eq!(strings(&merged["plugins"]), ["a", "b", "c"]);
+        assert_eq!(item_file(&origins, 1), "global.toml");
+        assert_eq!(item_file(&origins, 2), "project.toml");
+    }
+
+    #[test]
+    fn merge_by_key_merges_matching_tables_deep() {
+        let (merged, origins) = merge_plugins(
+            MergeStrategy::merge_by_key("name"),
+            r#"
+            [[plugins]]
+            name = "fmt"
+            enabled = true
+            args = ["--check"]
+
+            [[plugins]]
+            name = "lint"
+            enabled = true
+            "#,
+            r#"
+            [[plugins]]
+            name = "lint"
+            enabled = false
+
+            [[plugins]]
+            name = "test"
+            enabled = true
+            "#,
+        );
+        let plugins = merged["plugins"].as_array().unwrap();
+        let names: Vec<_> = plugins
+            .iter()
+            .map(|p| p["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, ["fmt", "lint", "test"]);
+        assert_eq!(plugins[1]["enabled"], Value::Boolean(false));
+        assert_eq!(plugins[0]["args"].as_array().unwrap().len(), 1);
+        assert_eq!(item_file(&origins, 0), "global.toml");
+        assert_eq!(item_file(&origins, 1), "project.toml");
+        assert_eq!(item_file(&origins, 2), "project.toml");
+    }
+
+    #[test]
+    fn replace_strategy_on_a_map_drops_lower_entries() {
+        let schema = strategy_root(MergeStrategy::Append);
+        let base = table("[aliases]\nb = \"build\"\nt = \"test\"\n");
+        let overlay = table("[aliases]\nc = \"check\"\n");
+        let (merged, _) = deep_merge(
+            DocumentRoot::Object(&schema),
+            None,
+            base,
+            overlay,
+            OriginMap::new(),
+            OriginMap::new(),
+        );
+        let aliases = merged["aliases"].as_map().unwrap();
+        assert_eq!(aliases.keys().collect::<Vec<_>>(), ["c"]);
+    }
+
+    #[test]
+    fn unset_marker_removes_the_lower_value_and_records_a_reset() {
+        let base = table("port = 8080\n[db]\nurl = \"pg://a\"\npool = 5\n");
+        let overlay = table("port = \"-\"\n[db]\nurl = \"-\"\n");
+        let mut base_o = OriginMap::new();
+        base_o.insert("port".into(), file_leaf("base.toml"));
+        let mut overlay_o = OriginMap::new();
+        overlay_o.insert("port".into(), file_leaf("overlay.toml"));
+        let (merged, origins) = deep_merge(root(), Some("-"), base, overlay, base_o, overlay_o);
+        assert!(!merged.contains_key("port"));
+        assert_eq!(winner_file(&origins, "port"), "overlay.toml");
+        assert!(
+            lookup(&origins, &ConfigPath::new().key("port"))
+                .unwrap()
+                .reset
+        );
+        let db = merged["db"].as_map().unwrap();
+        assert_eq!(db.keys().collect::<Vec<_>>(), ["pool"]);
+
+        // Merged as a whole layer over a lower one, the recorded reset
+        // drops that layer's value too.
+        let (merged, origins) = deep_merge(
+            root(),
+            Some("-"),
+            table("port = 9090"),
+            merged,
+            OriginMap::new(),
+            origins,
+        );
+        assert!(!merged.contains_key("port"));
+        assert_eq!(winner_file(&origins, "port"), "overlay.toml");
+    }
+
+    #[test]
+    fn undeclared_arrays_still_replace() {
+        let schema = strategy_root(MergeStrategy::Append);
+        let merged = deep_merge(
+            DocumentRoot::Object(&schema),
+            None,
+            table("extra = [1, 2]"),
+            table("extra = [3]"),
+            OriginMap::new(),
+            OriginMap::new(),
+        )
+        .0;
+        assert_eq!(merged["extra"].as_array().unwrap().len(), 1);
+    }
 }
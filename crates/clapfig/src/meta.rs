@@ -236,7 +236,7 @@ fn edit_distance(a: &str, b: &str) -> usize {
 /// name against a caller-supplied segment. Field names are snake by
 /// convention, but callers may type kebab when their app uses
 /// `.normalize_keys(true)`.
-fn segment_matches(field_name: &str, caller_segment: &str) -> bool {
+pub(crate) fn segment_matches(field_name: &str, caller_segment: &str) -> bool {
     if field_name == caller_segment {
         return true;
     }
@@ -0,0 +1,984 @@
+//! Schema migrations: keys that were renamed, moved, or removed.
+//!
+//! A [`Migration`] records one change to the schema's key layout, in
+//! canonical dotted form (`server.port`). Register them in order with
+//! [`Builder::migration`](crate::Builder::migration):
+//!
+//! ```ignore
+//! Clapfig::builder(schema)
+//!     .schema_version_key("schema_version")
+//!     .migration(Migration::rename("db", "database").at_version(2))
+//!     .migration(Migration::remove("legacy_mode").at_version(2))
+//!     .migration(Migration::rename("database.timeout_secs", "database.timeout"));
+//! ```
+//!
+//! Two places apply them:
+//!
+//! - **Loading** migrates every parsed document in memory, just after
+//!   normalize and before validation, so a file that still uses an old
+//!   key loads under a strict schema. A file that needed any migration
+//!   raises [`Warning::UnmigratedFile`](crate::Warning::UnmigratedFile),
+//!   and each applied migration logs a `warn` event naming the file;
+//!   origins follow a renamed value to its new key. The file on disk is
+//!   untouched.
+//! - **`config migrate`** ([`ConfigAction::Migrate`](crate::ConfigAction::Migrate))
+//!   writes the same changes into each persist scope's file through
+//!   [`FormatAdapter::edit`], one edit at a time, so comments and layout
+//!   survive, and then stamps the version key with the latest version.
+//!   A rename within one section (a renamed key or section) respells the
+//!   key in place through [`FileEdit::Rename`], so the value keeps its
+//!   place and its comments; a move into another section writes each
+//!   value at its new key and removes the old one.
+//!
+//! With a version key, a migration marked [`at_version(n)`](Migration::at_version)
+//! applies only to documents whose version is below `n`. A document with
+//! no version key is version 0. Unmarked migrations apply to every
+//! document that still holds their source key. The version key is
+//! reserved like the include key: it is removed from each document
+//! before validation.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::error::ClapfigError;
+use crate::format::{
+    ConfigPath, FileEdit, FormatAdapter, Operation, PathSegment, SetTarget, SpanEntry,
+};
+use crate::normalize::normalize_key;
+use crate::origin::Origin;
+use crate::value::{Map, Value};
+
+type TransformFn = dyn Fn(&Value) -> Result<Value, String> + Send + Sync;
+
+/// One change to the schema's key layout. See the [module docs](self).
+#[derive(Clone)]
+pub struct Migration {
+    step: Step,
+    version: Option<u64>,
+}
+
+#[derive(Clone)]
+enum Step {
+    Rename {
+        from: String,
+        to: String,
+    },
+    Remove {
+        path: String,
+    },
+    Transform {
+        path: String,
+        apply: Arc<TransformFn>,
+    },
+}
+
+impl Migration {
+    /// The value at `from` now lives at `to`. Either side may be a
+    /// section, so this covers a renamed key, a key moved to another
+    /// section, and a renamed section. A value already present at the
+    /// new key wins over the old one. Sections the move leaves empty are
+    /// removed.
+    ///
+    /// # Panics
+    ///
+    /// If either path is empty, or `to` lies inside `from`.
+    pub fn rename(from: impl Into<String>, to: impl Into<String>) -> Self {
+        let (from, to) = (from.into(), to.into());
+        assert!(
+            !from.is_empty() && !to.is_empty(),
+            "clapfig: Migration::rename paths must not be empty"
+        );
+        assert!(
+            from != to && !to.starts_with(&format!("{from}.")),
+            "clapfig: Migration::rename cannot move `{from}` into itself"
+        );
+        Self::new(Step::Rename { from, to })
+    }
+
+    /// The key (or section) at `path` no longer exists; drop it.
+    ///
+    /// # Panics
+    ///
+    /// If `path` is empty.
+    pub fn remove(path: impl Into<String>) -> Self {
+        let path = path.into();
+        assert!(
+            !path.is_empty(),
+            "clapfig: Migration::remove path must not be empty"
+        );
+        Self::new(Step::Remove { path })
+    }
+
+    /// Rewrite the value at `path` with `apply`, for a key whose meaning
+    /// or unit changed. An `Err` fails the load (or `config migrate`) as
+    /// [`ClapfigError::InvalidValue`] at that key. Under a format whose
+    /// scalars are strings (INI), `apply` sees the value as written.
+    ///
+    /// Without [`at_version`](Self::at_version) the transform runs on
+    /// every load, so it must accept its own output.
+    ///
+    /// # Panics
+    ///
+    /// If `path` is empty.
+    pub fn transform<F>(path: impl Into<String>, apply: F) -> Self
+    where
+        F: Fn(&Value) -> Result<Value, String> + Send + Sync + 'static,
+    {
+        let path = path.into();
+        assert!(
+            !path.is_empty(),
+            "clapfig: Migration::transform path must not be empty"
+        );
+        Self::new(Step::Transform {
+            path,
+            apply: Arc::new(apply),
+        })
+    }
+
+    /// Apply this migration only to documents whose version is below
+    /// `version`. See [`Builder::schema_version_key`](crate::Builder::schema_version_key).
+    pub fn at_version(mut self, version: u64) -> Self {
+        self.version = Some(version);
+        self
+    }
+
+    fn new(step: Step) -> Self {
+        Self {
+            step,
+            version: None,
+        }
+    }
+
+    fn applies_to(&self, version: u64) -> bool {
+        self.version.is_none_or(|v| version < v)
+    }
+}
+
+impl fmt::Display for Migration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.step {
+            Step::Rename { from, to } => write!(f, "renamed `{from}` to `{to}`")?,
+            Step::Remove { path } => write!(f, "removed `{path}`")?,
+            Step::Transform { path, .. } => write!(f, "transformed `{path}`")?,
+        }
+        if let Some(version) = self.version {
+            write!(f, " (version {version})")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Migration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Migration({self})")
+    }
+}
+
+/// The highest version any migration is marked with.
+pub(crate) fn latest_version(migrations: &[Migration]) -> Option<u64> {
+    migrations.iter().filter_map(|m| m.version).max()
+}
+
+/// One edit an applied migration made, against a canonical key path.
+enum Change {
+    Set {
+        path: Vec<String>,
+        value: Value,
+    },
+    Unset {
+        path: Vec<String>,
+    },
+    /// The key at `path` now has the name `to`, under the same parent.
+    Rename {
+        path: Vec<String>,
+        to: String,
+    },
+}
+
+/// A migration that changed a document, and how.
+struct Applied<'m> {
+    migration: &'m Migration,
+    changes: Vec<Change>,
+}
+
+/// A transform that refused the value at `path`.
+struct Refused {
+    path: Vec<String>,
+    reason: String,
+}
+
+/// The migrations one document needs: its version selects them, and
+/// `normalize_keys` spells their paths the way the document's keys are.
+pub(crate) struct Plan<'m> {
+    pub migrations: &'m [Migration],
+    pub version: u64,
+    pub normalize_keys: bool,
+}
+
+/// Apply every migration the plan selects to `doc`, in order.
+fn apply<'m>(doc: &mut Map, plan: &Plan<'m>) -> Result<Vec<Applied<'m>>, Refused> {
+    let normalize_keys = plan.normalize_keys;
+    let segments = |path: &str| -> Vec<String> {
+        path.split('.')
+            .map(|s| {
+                if normalize_keys {
+                    normalize_key(s)
+                } else {
+                    s.to_owned()
+                }
+            })
+            .collect()
+    };
+    let mut applied = Vec::new();
+    for migration in plan
+        .migrations
+        .iter()
+        .filter(|m| m.applies_to(plan.version))
+    {
+        let mut changes = Vec::new();
+        match &migration.step {
+            Step::Rename { from, to } => {
+                let (from, to) = (segments(from), segments(to));
+                let in_place = from.len() == to.len()
+                    && from[..from.len() - 1] == to[..to.len() - 1]
+                    && !occupied(doc, &to);
+                let Some(value) = take_at(doc, &from) else {
+                    continue;
+                };
+                if in_place {
+                    insert_at(doc, &to, value);
+                    let name = to.last().expect("rename paths are not empty").clone();
+                    changes.push(Change::Rename {
+                        path: from,
+                        to: name,
+                    });
+                    applied.push(Applied { migration, changes });
+                    continue;
+                }
+                let mut leaves = Vec::new();
+                collect_leaves(value, &mut Vec::new(), &mut leaves);
+                for (rest, leaf) in leaves {
+                    let dest: Vec<String> = to.iter().cloned().chain(rest).collect();
+                    if occupied(doc, &dest) {
+                        continue;
+                    }
+                    insert_at(doc, &dest, leaf.clone());
+                    changes.push(Change::Set {
+                        path: dest,
+                        value: leaf,
+                    });
+                }
+                changes.push(Change::Unset { path: from.clone() });
+                prune_empty(doc, &from, &mut changes);
+            }
+            Step::Remove { path } => {
+                let path = segments(path);
+                if take_at(doc, &path).is_none() {
+                    continue;
+                }
+                changes.push(Change::Unset { path: path.clone() });
+                prune_empty(doc, &path, &mut changes);
+            }
+            Step::Transform { path, apply } => {
+                let path = segments(path);
+                let Some(slot) = get_mut_at(doc, &path) else {
+                    continue;
+                };
+                let value = apply(slot).map_err(|reason| Refused {
+                    path: path.clone(),
+                    reason,
+                })?;
+                if *slot == value {
+                    continue;
+                }
+                *slot = value.clone();
+                changes.push(Change::Set { path, value });
+            }
+        }
+        applied.push(Applied { migration, changes });
+    }
+    Ok(applied)
+}
+
+/// Every leaf under `value`, with its path relative to `value`. An empty
+/// map is a leaf, so a moved empty section still arrives.
+fn collect_leaves(value: Value, prefix: &mut Vec<String>, out: &mut Vec<(Vec<String>, Value)>) {
+    match value {
+        Value::Map(map) if !map.is_empty() => {
+            for (key, child) in map {
+                prefix.push(key);
+                collect_leaves(child, prefix, out);
+                prefix.pop();
+            }
+        }
+        other => out.push((prefix.clone(), other)),
+    }
+}
+
+fn get_mut_at<'a>(doc: &'a mut Map, path: &[String]) -> Option<&'a mut Value> {
+    let (last, parents) = path.split_last()?;
+    let mut current = doc;
+    for seg in parents {
+        current = current.get_mut(seg)?.as_map_mut()?;
+    }
+    current.get_mut(last)
+}
+
+fn take_at(doc: &mut Map, path: &[String]) -> Option<Value> {
+    let (last, parents) = path.split_last()?;
+    let mut current = doc;
+    for seg in parents {
+        current = current.get_mut(seg)?.as_map_mut()?;
+    }
+    current.remove(last)
+}
+
+/// Whether `path` holds a value, or crosses one that is not a section.
+fn occupied(doc: &Map, path: &[String]) -> bool {
+    let Some((last, parents)) = path.split_last() else {
+        return true;
+    };
+    let mut current = doc;
+    for seg in parents {
+        match current.get(seg) {
+            None => return false,
+            Some(Value::Map(map)) => current = map,
+            Some(_) => return true,
+        }
+    }
+    current.contains_key(last)
+}
+
+/// Insert `value` at `path`, creating missing sections. Callers check
+/// [`occupied`] first.
+fn insert_at(doc: &mut Map, path: &[String], value: Value) {
+    let Some((last, parents)) = path.split_last() else {
+        return;
+    };
+    let mut current = doc;
+    for seg in parents {
+        current = current
+            .entry(seg.clone())
+            .or_insert_with(|| Value::Map(Map::new()))
+            .as_map_mut()
+            .expect("occupied() rules out non-section parents");
+    }
+    current.insert(last.clone(), value);
+}
+
+/// Remove the sections above `path` that its removal left empty, outermost
+/// last, recording one unset for the outermost.
+fn prune_empty(doc: &mut Map, path: &[String], changes: &mut Vec<Change>) {
+    let mut emptied = None;
+    for depth in (1..path.len()).rev() {
+        let parent = &path[..depth];
+        let empty = matches!(
+            get_mut_at(doc, parent),
+            Some(Value::Map(map)) if map.is_empty()
+        );
+        if !empty {
+            break;
+        }
+        take_at(doc, parent);
+        emptied = Some(parent.to_vec());
+    }
+    if let Some(path) = emptied {
+        changes.push(Change::Unset { path });
+    }
+}
+
+fn prefixed(base: &ConfigPath, path: &[String]) -> ConfigPath {
+    path.iter().fold(base.clone(), |p, seg| p.key(seg))
+}
+
+/// Read and remove the version key from a loaded document. A missing key
+/// is version 0; anything but a non-negative integer (or, from a
+/// string-scalar format, its spelling) is [`ClapfigError::InvalidValue`]
+/// located in the file.
+pub(crate) fn take_version(
+    doc: &mut Map,
+    key: &str,
+    spans: &BTreeMap<ConfigPath, SpanEntry>,
+    path: &Path,
+    source: &Arc<str>,
+) -> Result<u64, ClapfigError> {
+    let Some(value) = doc.remove(key) else {
+        return Ok(0);
+    };
+    parse_version(key, &value).map_err(|reason| ClapfigError::InvalidValue {
+        key: key.to_owned(),
+        reason,
+        origin: Box::new(
+            Origin::file_with_span(
+                path.to_path_buf(),
+                spans.get(&ConfigPath::new().key(key)).map(|e| e.value),
+                Arc::clone(source),
+            )
+            .to_facts(),
+        ),
+    })
+}
+
+fn parse_version(key: &str, value: &Value) -> Result<u64, String> {
+    let parsed = match value {
+        Value::Integer(n) => u64::try_from(*n).ok(),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    };
+    parsed.ok_or_else(|| format!("`{key}` must be a non-negative integer version"))
+}
+
+/// Migrate one loaded document (or profile section, rooted at `at` in
+/// the file's span index) in memory. Spans follow renamed values so
+/// origins and error snippets point at the old key's text. Returns the
+/// applied migrations, spelled for
+/// [`Warning::UnmigratedFile`](crate::Warning::UnmigratedFile); each also
+/// logs a `warn` event.
+pub(crate) fn apply_loaded(
+    plan: &Plan<'_>,
+    doc: &mut Map,
+    spans: &mut BTreeMap<ConfigPath, SpanEntry>,
+    at: &ConfigPath,
+    path: &Path,
+    source: &Arc<str>,
+) -> Result<Vec<String>, ClapfigError> {
+    let applied = apply(doc, plan).map_err(|refused| {
+        let key = prefixed(at, &refused.path);
+        ClapfigError::InvalidValue {
+            key: refused.path.join("."),
+            reason: refused.reason,
+            origin: Box::new(
+                Origin::file_with_span(
+                    path.to_path_buf(),
+                    spans.get(&key).map(|e| e.value),
+                    Arc::clone(source),
+                )
+                .to_facts(),
+            ),
+        }
+    })?;
+    for step in &applied {
+        if let Step::Rename { from, to } = &step.migration.step {
+            for change in &step.changes {
+                let leaf = match change {
+                    Change::Set { path: leaf, .. } => leaf.clone(),
+                    Change::Rename {
+                        path: old,
+                        to: name,
+                    } => {
+                        let mut leaf = old.clone();
+                        *leaf.last_mut().expect("rename paths are not empty") = name.clone();
+                        leaf
+                    }
+                    Change::Unset { .. } => continue,
+                };
+                move_span(spans, at, from, to, &leaf, plan.normalize_keys);
+            }
+        }
+        crate::trace::migration_applied(path, &step.migration.to_string());
+    }
+    Ok(applied
+        .iter()
+        .map(|step| step.migration.to_string())
+        .collect())
+}
+
+/// Re-key the spans of one moved leaf (`leaf`, under the rename's `to`)
+/// from where it sat under `from`.
+fn move_span(
+    spans: &mut BTreeMap<ConfigPath, SpanEntry>,
+    at: &ConfigPath,
+    from: &str,
+    to: &str,
+    leaf: &[String],
+    normalize_keys: bool,
+) {
+    let depth = to.split('.').count();
+    let spell = |s: &str| {
+        if normalize_keys {
+            normalize_key(s)
+        } else {
+            s.to_owned()
+        }
+    };
+    let old: Vec<String> = from
+        .split('.')
+        .map(spell)
+        .chain(leaf[depth..].iter().cloned())
+        .collect();
+    let old = prefixed(at, &old);
+    let new = prefixed(at, leaf);
+    let moved: Vec<(ConfigPath, SpanEntry)> = spans
+        .range(old.clone()..)
+        .take_while(|(k, _)| k.segments().starts_with(old.segments()))
+        .map(|(k, v)| (k.clone(), *v))
+        .collect();
+    for (key, entry) in moved {
+        spans.remove(&key);
+        let rest = &key.segments()[old.segments().len()..];
+        let mut segments: Vec<PathSegment> = new.segments().to_vec();
+        segments.extend_from_slice(rest);
+        spans.entry(ConfigPath::from(segments)).or_insert(entry);
+    }
+}
+
+/// Pure function: migrate a config document's source text through
+/// `adapter`, returning the new text and one line per applied migration.
+///
+/// Each change is one [`FileEdit`] against the text so far, so the
+/// adapter's comment preservation holds. Keys resolve to the document's
+/// own spelling (by dash/underscore equivalence under `normalize_keys`).
+/// Profile sections under `profile_key` migrate with their document.
+/// With `version_key`, the document's version selects the migrations and
+/// is then stamped with the latest one.
+pub(crate) fn migrate_document(
+    adapter: &dyn FormatAdapter,
+    content: &str,
+    migrations: &[Migration],
+    version_key: Option<&str>,
+    profile_key: Option<&str>,
+    normalize_keys: bool,
+) -> Result<(String, Vec<String>), ClapfigError> {
+    let spell = |key: &str| {
+        if normalize_keys {
+            normalize_key(key)
+        } else {
+            key.to_owned()
+        }
+    };
+    let mut doc = match adapter.parse(content).map_err(ClapfigError::from)?.value {
+        Value::Map(map) => map,
+        other => {
+            return Err(ClapfigError::invalid_value(
+                "",
+                format!(
+                    "config documents must be maps at the root, got {}",
+                    other.type_str()
+                ),
+            ));
+        }
+    };
+    if normalize_keys {
+        crate::normalize::normalize_table(&mut doc).map_err(|c| c.into_error(Path::new("")))?;
+    }
+    let version_key = version_key.map(spell);
+    let version = match version_key
+        .as_deref()
+        .and_then(|key| doc.get(key).map(|v| (key, v)))
+    {
+        Some((key, value)) => {
+            parse_version(key, value).map_err(|reason| ClapfigError::invalid_value(key, reason))?
+        }
+        None => 0,
+    };
+
+    let refused = |refused: Refused, base: &[String]| {
+        let key: Vec<String> = base.iter().cloned().chain(refused.path).collect();
+        ClapfigError::invalid_value(key.join("."), refused.reason)
+    };
+    let plan = Plan {
+        migrations,
+        version,
+        normalize_keys,
+    };
+    let mut changes: Vec<Change> = Vec::new();
+    let mut described = Vec::new();
+    for step in apply(&mut doc, &plan).map_err(|r| refused(r, &[]))? {
+        described.push(step.migration.to_string());
+        changes.extend(step.changes);
+    }
+    if let Some(key) = profile_key.map(spell)
+        && let Some(Value::Map(profiles)) = doc.get_mut(&key)
+    {
+        for (name, section) in profiles.iter_mut() {
+            let Value::Map(section) = section else {
+                continue;
+            };
+            let base = [key.clone(), name.clone()];
+            for step in apply(section, &plan).map_err(|r| refused(r, &base))? {
+                described.push(format!("{} in `{key}.{name}`", step.migration));
+                changes.extend(step.changes.into_iter().map(|change| match change {
+                    Change::Set { path, value } => Change::Set {
+                        path: base.iter().cloned().chain(path).collect(),
+                        value,
+                    },
+                    Change::Unset { path } => Change::Unset {
+                        path: base.iter().cloned().chain(path).collect(),
+                    },
+                    Change::Rename { path, to } => Change::Rename {
+                        path: base.iter().cloned().chain(path).collect(),
+                        to,
+                    },
+                }));
+            }
+        }
+    }
+    if let (Some(key), Some(latest)) = (&version_key, latest_version(migrations))
+        && version < latest
+    {
+        let stamp = i64::try_from(latest).unwrap_or(i64::MAX);
+        changes.push(Change::Set {
+            path: vec![key.clone()],
+            value: Value::Integer(stamp),
+        });
+        described.push(format!("set `{key}` to {latest}"));
+    }
+
+    let mut text = content.to_owned();
+    for change in changes {
+        let tree = adapter.parse(&text).map_err(ClapfigError::from)?.value;
+        let resolve = |canonical: &[String]| {
+            crate::persist::resolve_document_path(&tree, &canonical.join("."), normalize_keys)
+                .map_err(|c| c.into_error(Path::new("")))
+        };
+        let (segments, exists) = match &change {
+            Change::Set { path, .. } | Change::Unset { path } | Change::Rename { path, .. } => {
+                resolve(path)?
+            }
+        };
+        let path = crate::persist::config_path(&segments);
+        let edit = |text: &str, edit| adapter.edit(text, edit).map_err(ClapfigError::from);
+        text = match &change {
+            Change::Set { value, .. } => edit(
+                &text,
+                FileEdit::Set {
+                    path: &path,
+                    value,
+                    target: if exists {
+                        SetTarget::ExistingValue
+                    } else {
+                        SetTarget::MissingKey
+                    },
+                },
+            )?,
+            Change::Unset { .. } | Change::Rename { .. } if !exists => continue,
+            Change::Unset { .. } => edit(&text, FileEdit::Unset { path: &path })?,
+            Change::Rename { path: old, to } => {
+                let mut renamed = old.clone();
+                *renamed.last_mut().expect("rename paths are not empty") = to.clone();
+                let (target, _) = resolve(&renamed)?;
+                if adapter.supports(Operation::EditRename) {
+                    let to = target.last().expect("rename paths are not empty");
+                    edit(&text, FileEdit::Rename { path: &path, to })?
+                } else {
+                    // Without the rename row the adapter still gets the
+                    // change, as a set at the new key and an unset.
+                    let value = value_at(&tree, &segments).expect("the path exists");
+                    let target = crate::persist::config_path(&target);
+                    let moved = edit(
+                        &text,
+                        FileEdit::Set {
+                            path: &target,
+                            value,
+                            target: SetTarget::MissingKey,
+                        },
+                    )?;
+                    edit(&moved, FileEdit::Unset { path: &path })?
+                }
+            }
+        };
+    }
+    Ok((text, described))
+}
+
+/// The value at `path` in a parsed document tree.
+fn value_at<'a>(tree: &'a Value, path: &[String]) -> Option<&'a Value> {
+    path.iter()
+        .try_fold(tree, |node, seg| node.as_map().and_then(|map| map.get(seg)))
+}
+
+/// I/O wrapper: migrates the file at `file_path` in place, writing it
+/// only when a migration applied. A missing file has nothing to migrate.
+pub(crate) fn migrate_file(
+    adapter: &dyn FormatAdapter,
+    file_path: &Path,
+    migrations: &[Migration],
+    version_key: Option<&str>,
+    profile_key: Option<&str>,
+    normalize_keys: bool,
+) -> Result<Vec<String>, ClapfigError> {
+    let content = match std::fs::read_to_string(file_path) {
+        Ok(c) => c,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => {
+            return Err(ClapfigError::IoError {
+                path: file_path.to_path_buf(),
+                source: e,
+            });
+        }
+    };
+    let (text, described) = migrate_document(
+        adapter,
+        &content,
+        migrations,
+        version_key,
+        profile_key,
+        normalize_keys,
+    )
+    .map_err(|e| crate::persist::stamp_collision_path(e, file_path))?;
+    if text != content {
+        std::fs::write(file_path, &text).map_err(|e| ClapfigError::IoError {
+            path: file_path.to_path_buf(),
+            source: e,
+        })?;
+    }
+    crate::trace::persist_migrate(file_path, described.len());
+    Ok(described)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::{Json5Adapter, JsonAdapter, TomlAdapter, YamlAdapter};
+
+    fn migrations() -> Vec<Migration> {
+        vec![
+            Migration::rename("server.port", "listen.port").at_version(2),
+            Migration::remove("legacy").at_version(2),
+            Migration::transform("timeout", |v| match v {
+                Value::Integer(secs) if *secs < 1000 => Ok(Value::Integer(secs * 1000)),
+                Value::Integer(_) => Ok(v.clone()),
+                _ => Err("expected an integer".into()),
+            })
+            .at_version(3),
+        ]
+    }
+
+    fn migrate(adapter: &dyn FormatAdapter, content: &str) -> (String, Vec<String>) {
+        migrate_document(
+            adapter,
+            content,
+            &migrations(),
+            Some("schema_version"),
+            None,
+            false,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn toml_rewrite_moves_keys_and_keeps_comments() {
+        let (out, applied) = migrate(
+            &TomlAdapter,
+            "# top comment\nhost = \"x\" # kept\ntimeout = 5\nlegacy = true\n\n[server]\nport = 8080\n",
+        );
+        assert_eq!(
+            applied,
+            [
+                "renamed `server.port` to `listen.port` (version 2)",
+                "removed `legacy` (version 2)",
+                "transformed `timeout` (version 3)",
+                "set `schema_version` to 3",
+            ]
+        );
+        assert!(
+            out.starts_with("# top comment\nhost = \"x\" # kept\n"),
+            "{out}"
+        );
+        assert!(out.contains("timeout = 5000"), "{out}");
+        assert!(!out.contains("legacy"), "{out}");
+        assert!(!out.contains("[server]"), "{out}");
+        let doc = TomlAdapter.parse(&out).unwrap().value;
+        let doc = doc.as_map().unwrap();
+        assert_eq!(
+            doc["listen"].as_map().unwrap()["port"],
+            Value::Integer(8080)
+        );
+        assert_eq!(doc["schema_version"], Value::Integer(3));
+    }
+
+    #[test]
+    fn yaml_and_json_rewrite_through_their_adapters() {
+        let (out, _) = migrate(
+            &YamlAdapter,
+            "# top comment\ntimeout: 5  # seconds\nserver:\n  port: 8080\n",
+        );
+        assert!(
+            out.starts_with("# top comment\ntimeout: 5000  # seconds\n"),
+            "{out}"
+        );
+        let doc = YamlAdapter.parse(&out).unwrap().value;
+        assert_eq!(
+            doc.as_map().unwrap()["listen"].as_map().unwrap()["port"],
+            Value::Integer(8080)
+        );
+        assert!(!doc.as_map().unwrap().contains_key("server"));
+
+        let (out, _) = migrate(
+            &JsonAdapter,
+            "{\n  \"timeout\": 5,\n  \"server\": { \"port\": 8080 }\n}\n",
+        );
+        let doc = JsonAdapter.parse(&out).unwrap().value;
+        let doc = doc.as_map().unwrap();
+        assert_eq!(doc["timeout"], Value::Integer(5000));
+        assert_eq!(
+            doc["listen"].as_map().unwrap()["port"],
+            Value::Integer(8080)
+        );
+        assert_eq!(doc["schema_version"], Value::Integer(3));
+    }
+
+    #[test]
+    fn renames_keep_comments_on_the_renamed_key_and_inside_the_renamed_section() {
+        let renames = [
+            Migration::rename("host", "hostname"),
+            Migration::rename("db", "database"),
+        ];
+        let rename = |adapter: &dyn FormatAdapter, content: &str| {
+            migrate_document(adapter, content, &renames, None, None, false)
+                .unwrap()
+                .0
+        };
+
+        let out = rename(
+            &TomlAdapter,
+            "# host comment\nhost = \"x\" # trailing\n\n# db doc\n[db]\n# url doc\nurl = \"pg\"\npool = 3\n",
+        );
+        assert_eq!(
+            out,
+            "# host comment\nhostname = \"x\" # trailing\n\n# db doc\n[database]\n# url doc\nurl = \"pg\"\npool = 3\n"
+        );
+
+        let out = rename(
+            &YamlAdapter,
+            "# host comment\nhost: x  # trailing\ndb:\n  # url doc\n  url: pg\n  pool: 3\n",
+        );
+        assert_eq!(
+            out,
+            "# host comment\nhostname: x  # trailing\ndatabase:\n  # url doc\n  url: pg\n  pool: 3\n"
+        );
+
+        let out = rename(
+            &Json5Adapter,
+            "{\n  // host comment\n  host: \"x\", // trailing\n  db: {\n    // url doc\n    url: \"pg\",\n  },\n}\n",
+        );
+        assert_eq!(
+            out,
+            "{\n  // host comment\n  \"hostname\": \"x\", // trailing\n  \"database\": {\n    // url doc\n    url: \"pg\",\n  },\n}\n"
+        );
+    }
+
+    /// TOML without the rename row, like an adapter written before it.
+    struct NoRename;
+
+    impl FormatAdapter for NoRename {
+        fn name(&self) -> &'static str {
+            "norename"
+        }
+
+        fn extensions(&self) -> &'static [&'static str] {
+            &["toml"]
+        }
+
+        fn capabilities(&self) -> &'static [Operation] {
+            &[
+                Operation::Parse,
+                Operation::EditSet,
+                Operation::EditCreateKey,
+                Operation::EditUnset,
+            ]
+        }
+
+        fn parse(&self, text: &str) -> Result<crate::format::Parsed, crate::format::FormatError> {
+            TomlAdapter.parse(text)
+        }
+
+        fn serialize(&self, value: &Value) -> Result<String, crate::format::FormatError> {
+            TomlAdapter.serialize(value)
+        }
+
+        fn template(
+            &self,
+            shape: &crate::runtime::Shape,
+        ) -> Result<String, crate::format::FormatError> {
+            TomlAdapter.template(shape)
+        }
+
+        fn edit(
+            &self,
+            source: &str,
+            edit: FileEdit<'_>,
+        ) -> Result<String, crate::format::FormatError> {
+            self.require(edit.operation())?;
+            TomlAdapter.edit(source, edit)
+        }
+    }
+
+    #[test]
+    fn adapters_without_the_rename_row_get_a_set_and_an_unset() {
+        let (out, applied) = migrate_document(
+            &NoRename,
+            "host = \"x\"\nport = 1\n",
+            &[Migration::rename("host", "hostname")],
+            None,
+            None,
+            false,
+        )
+        .unwrap();
+        assert_eq!(applied, ["renamed `host` to `hostname`"]);
+        let doc = TomlAdapter.parse(&out).unwrap().value;
+        let doc = doc.as_map().unwrap();
+        assert_eq!(doc["hostname"], Value::from("x"));
+        assert!(!doc.contains_key("host"));
+    }
+
+    #[test]
+    fn version_selects_migrations_and_current_files_are_untouched() {
+        let content = "schema_version = 2\ntimeout = 5\n\n[server]\nport = 8080\n";
+        let (out, applied) = migrate(&TomlAdapter, content);
+        assert_eq!(
+            applied,
+            [
+                "transformed `timeout` (version 3)",
+                "set `schema_version` to 3",
+            ]
+        );
+        assert!(out.contains("[server]\nport = 8080"), "{out}");
+
+        let current = "schema_version = 3\ntimeout = 5\n";
+        let (out, applied) = migrate(&TomlAdapter, current);
+        assert!(applied.is_empty());
+        assert_eq!(out, current);
+    }
+
+    #[test]
+    fn rename_keeps_a_value_already_at_the_new_key() {
+        let mut doc = crate::fixtures::test::parse_toml(
+            "[server]\nport = 1\nhost = \"a\"\n\n[listen]\nport = 2\n",
+        );
+        let migrations = [Migration::rename("server", "listen")];
+        let plan = Plan {
+            migrations: &migrations,
+            version: 0,
+            normalize_keys: false,
+        };
+        let applied = apply(&mut doc, &plan).ok().unwrap();
+        assert_eq!(applied.len(), 1);
+        let listen = doc["listen"].as_map().unwrap();
+        assert_eq!(listen["port"], Value::Integer(2));
+        assert_eq!(listen["host"], Value::from("a"));
+        assert!(!doc.contains_key("server"));
+    }
+
+    #[test]
+    fn refused_transform_is_invalid_value_at_the_key() {
+        let err = migrate_document(
+            &TomlAdapter,
+            "timeout = \"soon\"\n",
+            &migrations(),
+            None,
+            None,
+            false,
+        )
+        .unwrap_err();
+        match err {
+            ClapfigError::InvalidValue { key, reason, .. } => {
+                assert_eq!(key, "timeout");
+                assert_eq!(reason, "expected an integer");
+            }
+            other => panic!("expected InvalidValue, got {other:?}"),
+        }
+    }
+}
@@ -1,13 +1,18 @@
 //! Config operations: template generation, key lookup, listing, and result types.
 //!
-//! Provides the logic behind `config list`, `config gen`, `config get`, and the
-//! `ConfigResult` enum that callers use to display results.
+//! Provides the logic behind `config list`, `config gen`, `config get`,
+//! `config diff`, and the `ConfigResult` enum that callers use to display
+//! results.
 
+use std::collections::BTreeMap;
 use std::fmt;
 use std::path::{Path, PathBuf};
 
-use crate::error::ClapfigError;
-use crate::format::FormatAdapter;
+use crate::diff::ConfigDiff;
+use crate::error::{ClapfigError, OriginFacts};
+use crate::format::{ConfigPath, FormatAdapter, SpanEntry};
+use crate::provenance::Explanation;
+use crate::types::Layer;
 use crate::value::{Map, Value};
 
 /// Result of a config operation. Returned to the caller for display.
@@ -21,6 +26,11 @@ use crate::value::{Map, Value};
 /// scope file's format for scoped operations and the preferred
 /// (first-enabled) format for merged views.
 ///
+/// Origin-annotated results (`config list --origins`, `config get
+/// --origin`) append each value's [`OriginFacts`] to its assignment line
+/// as a trailing comment in the same spelling:
+/// `database.pool_size = 5  # env: MYAPP__DATABASE__POOL_SIZE`.
+///
 /// [`display_comment`]: FormatAdapter::display_comment
 #[derive(Debug, Clone, PartialEq)]
 pub enum ConfigResult {
@@ -37,6 +47,12 @@ pub enum ConfigResult {
         key: String,
         value: String,
         doc: Vec<String>,
+        /// Where the value came from, when the action asked for it
+        /// (`origin: true`) and the value has a recorded origin.
+        origin: Option<OriginFacts>,
+        /// The string as written, when `value` is the result of `${...}`
+        /// interpolation.
+        raw: Option<String>,
         /// The display block (comment lines + assignment) in the active
         /// format's spelling; what `Display` prints.
         rendered: String,
@@ -50,34 +66,71 @@ pub enum ConfigResult {
     },
     /// Confirmation that a value was removed.
     ValueUnset { key: String },
+    /// The files `config migrate` rewrote, each with one line per
+    /// migration it applied. Empty when every file was current.
+    Migrated { files: Vec<(PathBuf, Vec<String>)> },
+    /// Every input that supplied one key's value, and which one won.
+    Explanation {
+        explanation: Explanation,
+        /// The resolved assignment followed by one line per candidate
+        /// in precedence order; what `Display` prints.
+        rendered: String,
+    },
+    /// Every leaf that differs between two configurations.
+    Diff {
+        diff: ConfigDiff,
+        /// A `---` / `+++` header naming both sides, then per entry a
+        /// `-` line for the left value and a `+` line for the right, each
+        /// an assignment in the active format's spelling with its origin
+        /// as a trailing comment; what `Display` prints.
+        rendered: String,
+    },
     /// All resolved configuration key-value pairs.
     Listing {
         entries: Vec<(String, String)>,
+        /// Empty unless the action asked for origins (`origins: true`);
+        /// then one slot per entry, `None` for a value without a
+        /// recorded origin.
+        origins: Vec<Option<OriginFacts>>,
+        /// The active profile, for a merged listing with profiles
+        /// enabled.
+        profile: Option<String>,
         /// One assignment line per entry in the active format's spelling,
-        /// newline-joined; what `Display` prints.
+        /// newline-joined, after a `profile:` comment when a profile is
+        /// active; what `Display` prints.
         rendered: String,
     },
 }
 
 impl ConfigResult {
     /// Build a [`ConfigResult::KeyValue`], rendering the display block
-    /// (doc-comment lines, then the assignment) through `adapter`.
+    /// (doc-comment lines, a `raw:` comment for an interpolated value,
+    /// then the assignment, then the `origin` annotation when present)
+    /// through `adapter`.
     pub(crate) fn key_value(
         adapter: &dyn FormatAdapter,
         key: String,
         value: String,
         doc: Vec<String>,
+        origin: Option<OriginFacts>,
+        raw: Option<String>,
     ) -> Self {
         let mut rendered = String::new();
         for line in &doc {
             rendered.push_str(&adapter.display_comment(line));
             rendered.push('\n');
         }
-        rendered.push_str(&adapter.display_entry(&key, &value));
+        if let Some(raw) = &raw {
+            rendered.push_str(&adapter.display_comment(&format!("raw: {raw}")));
+            rendered.push('\n');
+        }
+        rendered.push_str(&annotated_entry(adapter, &key, &value, origin.as_ref()));
         ConfigResult::KeyValue {
             key,
             value,
             doc,
+            origin,
+            raw,
             rendered,
         }
     }
@@ -101,7 +154,177 @@ impl ConfigResult {
             .map(|(key, value)| adapter.display_entry(key, value))
             .collect::<Vec<_>>()
             .join("\n");
-        ConfigResult::Listing { entries, rendered }
+        ConfigResult::Listing {
+            entries,
+            origins: Vec::new(),
+            profile: None,
+            rendered,
+        }
+    }
+
+    /// Build an origin-annotated [`ConfigResult::Listing`]: each line is
+    /// the entry's assignment plus its origin as a trailing comment.
+    pub(crate) fn annotated_listing(
+        adapter: &dyn FormatAdapter,
+        annotated: Vec<(String, String, Option<OriginFacts>)>,
+    ) -> Self {
+        let rendered = annotated
+            .iter()
+            .map(|(key, value, origin)| annotated_entry(adapter, key, value, origin.as_ref()))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let (entries, origins) = annotated
+            .into_iter()
+            .map(|(key, value, origin)| ((key, value), origin))
+            .unzip();
+        ConfigResult::Listing {
+            entries,
+            origins,
+            profile: None,
+            rendered,
+        }
+    }
+
+    /// Name the active profile on a [`ConfigResult::Listing`], as a
+    /// leading `profile:` comment in `adapter`'s spelling. Other results
+    /// pass through.
+    pub(crate) fn with_profile(self, adapter: &dyn FormatAdapter, name: Option<String>) -> Self {
+        match (self, name) {
+            (
+                ConfigResult::Listing {
+                    entries,
+                    origins,
+                    rendered,
+                    ..
+                },
+                Some(name),
+            ) => {
+                let mut header = adapter.display_comment(&format!("profile: {name}"));
+                if !rendered.is_empty() {
+                    header.push('\n');
+                    header.push_str(&rendered);
+                }
+                ConfigResult::Listing {
+                    entries,
+                    origins,
+                    profile: Some(name),
+                    rendered: header,
+                }
+            }
+            (result, _) => result,
+        }
+    }
+}
+
+impl ConfigResult {
+    /// Build a [`ConfigResult::Explanation`]: the resolved assignment in
+    /// `adapter`'s spelling, the precedence the merge applied, each
+    /// candidate lowest-precedence first with the winner marked, and any
+    /// file a first-match search never read. `combines` says the key is
+    /// an array whose merge strategy keeps lower layers' elements.
+    pub(crate) fn explanation(
+        adapter: &dyn FormatAdapter,
+        explanation: Explanation,
+        combines: bool,
+    ) -> Self {
+        let key = &explanation.key;
+        let mut lines = vec![match &explanation.value {
+            Some(value) => adapter.display_entry(key, &format_value(value)),
+            None => format!("{key} is not set"),
+        }];
+        let mut precedence = vec!["default"];
+        precedence.extend(explanation.layer_order.iter().map(|layer| match layer {
+            Layer::Files => "files",
+            Layer::Env => "env",
+            #[cfg(feature = "url")]
+            Layer::Url => "url",
+            Layer::Cli => "overrides",
+        }));
+        lines.push(format!(
+            "precedence, lowest first: {}",
+            precedence.join(" < ")
+        ));
+        if explanation.candidates.is_empty() {
+            lines.push("  no input supplied a value".into());
+        }
+        for candidate in &explanation.candidates {
+            let mut line = format!("  {}: {}", candidate.origin, format_value(&candidate.value));
+            // Sections deep-merge, and so do arrays with an append,
+            // prepend, union, or merge-by-key strategy: a losing candidate
+            // contributed to the result rather than being replaced.
+            line.push_str(match (&candidate.value, candidate.won) {
+                (_, true) => "  <- wins",
+                (Value::Map(_), false) => "  (merged)",
+                (Value::Array(_), false) if combines => "  (merged)",
+                (_, false) => "  (overridden)",
+            });
+            lines.push(line);
+        }
+        if !explanation.not_probed.is_empty() {
+            lines.push("not read (first-match search stopped at a higher-priority file):".into());
+            for path in &explanation.not_probed {
+                lines.push(format!("  {}", path.display()));
+            }
+        }
+        ConfigResult::Explanation {
+            explanation,
+            rendered: lines.join("\n"),
+        }
+    }
+}
+
+impl ConfigResult {
+    /// Build a [`ConfigResult::Diff`] between the sides labelled `left`
+    /// and `right`, rendering each value through `adapter`.
+    pub(crate) fn diff(
+        adapter: &dyn FormatAdapter,
+        left: &str,
+        right: &str,
+        diff: ConfigDiff,
+    ) -> Self {
+        let mut lines = vec![format!("--- {left}"), format!("+++ {right}")];
+        if diff.is_empty() {
+            lines.push("no differences".into());
+        }
+        for entry in &diff.entries {
+            if let Some(value) = &entry.left {
+                let line = annotated_entry(
+                    adapter,
+                    &entry.key,
+                    &format_value(value),
+                    entry.left_origin.as_ref(),
+                );
+                lines.push(format!("- {line}"));
+            }
+            if let Some(value) = &entry.right {
+                let line = annotated_entry(
+                    adapter,
+                    &entry.key,
+                    &format_value(value),
+                    entry.right_origin.as_ref(),
+                );
+                lines.push(format!("+ {line}"));
+            }
+        }
+        ConfigResult::Diff {
+            diff,
+            rendered: lines.join("\n"),
+        }
+    }
+}
+
+/// One assignment line, with `origin` appended as a trailing comment in
+/// the adapter's spelling when present.
+fn annotated_entry(
+    adapter: &dyn FormatAdapter,
+    key: &str,
+    value: &str,
+    origin: Option<&OriginFacts>,
+) -> String {
+    let entry = adapter.display_entry(key, value);
+    match origin {
+        Some(origin) => format!("{entry}  {}", adapter.display_comment(&origin.to_string())),
+        None => entry,
     }
 }
 
@@ -119,6 +342,23 @@ impl fmt::Display for ConfigResult {
             ConfigResult::KeyValue { rendered, .. } => write!(f, "{rendered}"),
             ConfigResult::ValueSet { rendered, .. } => write!(f, "Set {rendered}"),
             ConfigResult::ValueUnset { key } => write!(f, "Unset {key}"),
+            ConfigResult::Migrated { files } if files.is_empty() => {
+                write!(f, "Nothing to migrate")
+            }
+            ConfigResult::Migrated { files } => {
+                for (i, (path, applied)) in files.iter().enumerate() {
+                    if i > 0 {
+                        writeln!(f)?;
+                    }
+                    write!(f, "Migrated {}", path.display())?;
+                    for line in applied {
+                        write!(f, "\n  {line}")?;
+                    }
+                }
+                Ok(())
+            }
+            ConfigResult::Explanation { rendered, .. } => write!(f, "{rendered}"),
+            ConfigResult::Diff { rendered, .. } => write!(f, "{rendered}"),
             ConfigResult::Listing { rendered, .. } => write!(f, "{rendered}"),
         }
     }
@@ -151,6 +391,29 @@ fn kebab_rename_shape(shape: &mut crate::runtime::Shape) {
     }
 }
 
+/// Remove every deprecated field from a shape, at any depth.
+fn drop_deprecated(shape: &mut crate::runtime::Shape) {
+    use crate::runtime::Shape;
+    match shape {
+        Shape::Object(schema) => drop_deprecated_fields(schema),
+        Shape::Array(array) => drop_deprecated(&mut array.item),
+        Shape::Map(map) => drop_deprecated(&mut map.item),
+        Shape::Tagged(tagged) => {
+            for variant in &mut tagged.variants {
+                drop_deprecated_fields(&mut variant.schema);
+            }
+        }
+        Shape::Leaf(_) => {}
+    }
+}
+
+fn drop_deprecated_fields(schema: &mut crate::runtime::Schema) {
+    schema.fields.retain(|nf| nf.field.deprecated().is_none());
+    for nf in &mut schema.fields {
+        drop_deprecated(&mut nf.field);
+    }
+}
+
 /// Template generator: renders the documented config template for a
 /// schema through the given format adapter. With `kebab` set, the schema's
 /// field names are structurally renamed to kebab-case first (see
@@ -158,6 +421,9 @@ fn kebab_rename_shape(shape: &mut crate::runtime::Shape) {
 /// `normalize_keys(true)` builder accepts. Tagged unions rename the tag
 /// key and every variant field; discriminator *values* are unchanged.
 ///
+/// [Deprecated](crate::runtime::Leaf::deprecated) fields are left out:
+/// a fresh config should not start on a key it will be warned about.
+///
 /// The template body — doc comments, `Allowed:` enum lines, commented
 /// placeholders for defaultless leaves — is the adapter's
 /// [`template`](crate::format::FormatAdapter::template) output; this
@@ -168,14 +434,32 @@ pub(crate) fn generate_template(
     shape: &crate::runtime::Shape,
     kebab: bool,
 ) -> Result<String, ClapfigError> {
-    let shaped = if kebab {
+    let mut shaped = if kebab {
         kebab_renamed_shape(shape)
     } else {
         shape.clone()
     };
+    drop_deprecated(&mut shaped);
     Ok(adapter.template(&shaped)?)
 }
 
+/// `shape` with `lines` appended to its root doc comment — the notes
+/// `config gen` adds about behavior the builder configures (profiles).
+pub(crate) fn with_root_notes(
+    shape: &crate::runtime::Shape,
+    lines: &[String],
+) -> crate::runtime::Shape {
+    use crate::runtime::Shape;
+    let mut shape = shape.clone();
+    match &mut shape {
+        Shape::Object(schema) => schema.doc.extend_from_slice(lines),
+        Shape::Map(map) => map.doc.extend_from_slice(lines),
+        Shape::Tagged(tagged) => tagged.doc.extend_from_slice(lines),
+        Shape::Leaf(_) | Shape::Array(_) => {}
+    }
+    shape
+}
+
 /// Recursively rewrite field names in a document-root shape to kebab-case.
 fn kebab_renamed_shape(shape: &crate::runtime::Shape) -> crate::runtime::Shape {
     let mut shaped = shape.clone();
@@ -186,10 +470,15 @@ fn kebab_renamed_shape(shape: &crate::runtime::Shape) -> crate::runtime::Shape {
 /// List entries from a single scope's config file (raw file content, not merged).
 ///
 /// The file is parsed through `adapter` — the routing seam for scoped
-/// reads. If the file does not exist, returns an empty listing.
+/// reads. If the file does not exist, returns an empty listing. With
+/// `origins`, every entry is annotated with the scope file itself and,
+/// when the adapter's span index locates it, the entry's line.
+/// Values `shape` marks sensitive are redacted.
 pub(crate) fn list_scope_file(
     adapter: &dyn FormatAdapter,
+    shape: &crate::runtime::Shape,
     file_path: &Path,
+    origins: bool,
 ) -> Result<ConfigResult, ClapfigError> {
     let content = match std::fs::read_to_string(file_path) {
         Ok(c) => c,
@@ -211,8 +500,8 @@ pub(crate) fn list_scope_file(
             source: Box::new(e),
             source_text: Some(std::sync::Arc::from(content.as_str())),
         })?;
-    let table = match parsed.value {
-        Value::Map(map) => map,
+    let mut table = match &parsed.value {
+        Value::Map(map) => map.clone(),
         other => {
             return Err(ClapfigError::invalid_value(
                 file_path.display().to_string(),
@@ -223,24 +512,74 @@ pub(crate) fn list_scope_file(
             ));
         }
     };
+    crate::redact::redact_table(shape, &mut table);
 
     let mut entries = Vec::new();
-    flatten_value_map(&table, "", &mut entries);
+    flatten_value_map(&table, "", &ConfigPath::new(), &mut entries);
+
+    if !origins {
+        let entries = entries.into_iter().map(|(_, k, v)| (k, v)).collect();
+        return Ok(ConfigResult::listing(adapter, entries));
+    }
+    let raw = parsed.value.as_map().expect("checked above");
+    let (source, spans) = scope_file_source(shape, raw, parsed.spans, &content);
+    let annotated = entries
+        .into_iter()
+        .map(|(path, key, value)| {
+            let origin = scope_file_origin(&spans, &path, file_path, &source);
+            (key, value, Some(origin))
+        })
+        .collect();
+    Ok(ConfigResult::annotated_listing(adapter, annotated))
+}
+
+/// A scope file's text and span index for origins, with every secret
+/// `shape` declares masked (see [`redact::mask_source`](crate::redact::mask_source)).
+pub(crate) fn scope_file_source(
+    shape: &crate::runtime::Shape,
+    table: &Map,
+    spans: BTreeMap<ConfigPath, SpanEntry>,
+    content: &str,
+) -> (std::sync::Arc<str>, BTreeMap<ConfigPath, SpanEntry>) {
+    crate::runtime::DocumentRoot::of(shape)
+        .and_then(|root| crate::redact::mask_source(root, table, &spans, content))
+        .unwrap_or_else(|| (std::sync::Arc::from(content), spans))
+}
 
-    Ok(ConfigResult::listing(adapter, entries))
+/// The origin of `path` in a scope file read outside the merge: the file
+/// itself, with the value's span when the span index has the path.
+pub(crate) fn scope_file_origin(
+    spans: &BTreeMap<ConfigPath, SpanEntry>,
+    path: &ConfigPath,
+    file_path: &Path,
+    source: &std::sync::Arc<str>,
+) -> OriginFacts {
+    crate::origin::Origin::file_with_span(
+        file_path.to_path_buf(),
+        spans.get(path).map(|entry| entry.value),
+        std::sync::Arc::clone(source),
+    )
+    .to_facts()
 }
 
-/// Recursively flatten a value map into dotted key-value pairs.
-fn flatten_value_map(table: &Map, prefix: &str, entries: &mut Vec<(String, String)>) {
+/// Recursively flatten a value map into dotted key-value pairs, keeping
+/// each entry's structured path for span / origin lookups.
+fn flatten_value_map(
+    table: &Map,
+    prefix: &str,
+    path: &ConfigPath,
+    entries: &mut Vec<(ConfigPath, String, String)>,
+) {
     for (key, value) in table {
         let full_key = if prefix.is_empty() {
             key.clone()
         } else {
             format!("{prefix}.{key}")
         };
+        let full_path = path.clone().key(key);
         match value {
-            Value::Map(t) => flatten_value_map(t, &full_key, entries),
-            _ => entries.push((full_key, format_value(value))),
+            Value::Map(t) => flatten_value_map(t, &full_key, &full_path, entries),
+            _ => entries.push((full_path, full_key, format_value(value))),
         }
     }
 }
@@ -281,6 +620,26 @@ pub(crate) fn table_get_normalized<'a>(
     Ok(None)
 }
 
+/// The structured path a canonical dotted key names in a document —
+/// segment by segment the document's own spelling under
+/// `normalize_keys` (so a kebab-case file's span index answers), the
+/// segments verbatim otherwise. Used to find a located key's span.
+pub(crate) fn document_path(table: &Map, canonical: &str, normalize_keys: bool) -> ConfigPath {
+    let mut path = ConfigPath::new();
+    let mut current = Some(table);
+    for seg in canonical.split('.') {
+        let key = match current {
+            Some(map) if normalize_keys => crate::normalize::resolve_table_key(map, seg)
+                .map(String::as_str)
+                .unwrap_or(seg),
+            _ => seg,
+        };
+        current = current.and_then(|map| map.get(key)).and_then(Value::as_map);
+        path = path.key(key);
+    }
+    path
+}
+
 /// Navigate a value [`Map`] by dotted key path (e.g. `"database.url"`).
 pub(crate) fn table_get<'a>(table: &'a Map, dotted_key: &str) -> Option<&'a Value> {
     let (path, leaf) = match dotted_key.rsplit_once('.') {
@@ -319,6 +678,7 @@ mod tests {
     use super::*;
     use crate::fixtures::test::test_schema;
     use crate::format::TomlAdapter;
+    use crate::runtime::Shape;
 
     fn template_for(schema: &crate::runtime::Schema, kebab: bool) -> String {
         generate_template(
@@ -378,6 +738,29 @@ mod tests {
         assert!(!raw.contains("pool-size"));
     }
 
+    #[test]
+    fn generate_template_leaves_out_deprecated_fields() {
+        use crate::runtime::{Field as RtField, Schema as RtSchema};
+        let schema = RtSchema::object("App")
+            .field("deadline", RtField::integer().default(30i64))
+            .field(
+                "timeout",
+                RtField::integer().deprecated("use `deadline`").optional(),
+            )
+            .nested(
+                "db",
+                RtSchema::object("Db")
+                    .field("url", RtField::string().default("pg://"))
+                    .field("uri", RtField::string().deprecated("use `url`").optional()),
+            )
+            .build();
+        let template = template_for(&schema, false);
+        assert!(template.contains("deadline"), "{template}");
+        assert!(template.contains("url"), "{template}");
+        assert!(!template.contains("timeout"), "{template}");
+        assert!(!template.contains("uri"), "{template}");
+    }
+
     // -- kebab rename (structural, format-agnostic) -------------------------
 
     #[test]
@@ -524,7 +907,14 @@ mod tests {
         let get = |a: &dyn FormatAdapter| {
             format!(
                 "{}",
-                ConfigResult::key_value(a, "port".into(), "8080".into(), vec!["The port.".into()])
+                ConfigResult::key_value(
+                    a,
+                    "port".into(),
+                    "8080".into(),
+                    vec!["The port.".into()],
+                    None,
+                    None,
+                )
             )
         };
         assert_eq!(get(&TomlAdapter), "# The port.\nport = 8080");
@@ -532,6 +922,37 @@ mod tests {
         assert_eq!(get(&JsonAdapter), "// The port.\n\"port\": 8080");
     }
 
+    #[test]
+    fn annotated_listing_appends_origin_comments() {
+        // The origin rides as a trailing comment in the active format's
+        // spelling; an entry without a recorded origin stays bare.
+        use crate::format::JsonAdapter;
+        use crate::types::InputType;
+        let env = OriginFacts {
+            env_var: Some("APP__PORT".into()),
+            input_type: Some(InputType::Env),
+            ..OriginFacts::default()
+        };
+        let annotated = |a: &dyn FormatAdapter| {
+            ConfigResult::annotated_listing(
+                a,
+                vec![
+                    ("port".into(), "8080".into(), Some(env.clone())),
+                    ("host".into(), "h".into(), None),
+                ],
+            )
+            .to_string()
+        };
+        assert_eq!(
+            annotated(&TomlAdapter),
+            "port = 8080  # env: APP__PORT\nhost = h"
+        );
+        assert_eq!(
+            annotated(&JsonAdapter),
+            "\"port\": 8080  // env: APP__PORT\n\"host\": h"
+        );
+    }
+
     #[test]
     fn value_set_display_is_format_aware() {
         use crate::format::YamlAdapter;
@@ -547,7 +968,8 @@ mod tests {
         let path = dir.path().join("config.toml");
         std::fs::write(&path, "port = 3000\nhost = \"localhost\"\n").unwrap();
 
-        let result = list_scope_file(&TomlAdapter, &path).unwrap();
+        let result =
+            list_scope_file(&TomlAdapter, &Shape::Object(test_schema()), &path, false).unwrap();
         match result {
             ConfigResult::Listing { entries, .. } => {
                 assert_eq!(entries.len(), 2);
@@ -564,7 +986,8 @@ mod tests {
         let path = dir.path().join("config.toml");
         std::fs::write(&path, "[database]\npool_size = 10\nurl = \"pg://\"\n").unwrap();
 
-        let result = list_scope_file(&TomlAdapter, &path).unwrap();
+        let result =
+            list_scope_file(&TomlAdapter, &Shape::Object(test_schema()), &path, false).unwrap();
         match result {
             ConfigResult::Listing { entries, .. } => {
                 assert!(entries.contains(&("database.pool_size".into(), "10".into())));
@@ -574,12 +997,37 @@ mod tests {
         }
     }
 
+    #[test]
+    fn list_scope_file_origins_name_the_file_and_line() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(&path, "port = 3000\n\n[database]\nurl = \"pg://\"\n").unwrap();
+
+        let result =
+            list_scope_file(&TomlAdapter, &Shape::Object(test_schema()), &path, true).unwrap();
+        match &result {
+            ConfigResult::Listing { origins, .. } => {
+                let lines: Vec<_> = origins
+                    .iter()
+                    .map(|o| o.as_ref().and_then(OriginFacts::line_col).map(|(l, _)| l))
+                    .collect();
+                assert_eq!(lines, [Some(4), Some(1)]);
+            }
+            other => panic!("Expected Listing, got {other:?}"),
+        }
+        assert!(result.to_string().starts_with(&format!(
+            "database.url = pg://  # file: {}:4",
+            path.display()
+        )));
+    }
+
     #[test]
     fn list_scope_file_missing_returns_empty() {
         let dir = tempfile::TempDir::new().unwrap();
         let path = dir.path().join("nonexistent.toml");
 
-        let result = list_scope_file(&TomlAdapter, &path).unwrap();
+        let result =
+            list_scope_file(&TomlAdapter, &Shape::Object(test_schema()), &path, false).unwrap();
         match result {
             ConfigResult::Listing { entries, .. } => assert!(entries.is_empty()),
             other => panic!("Expected empty Listing, got {other:?}"),
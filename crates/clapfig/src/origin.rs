@@ -14,7 +14,9 @@
 //!
 //! This type stays crate-private. Public errors carry flattened origin
 //! facts ([`InputType`](crate::InputType), file, span, env var, URL key)
-//! rather than an `Origin` value — there is no public Origin API.
+//! rather than an `Origin` value, and the public read-only tree
+//! ([`Provenance`](crate::Provenance)) answers lookups with the same
+//! flattened facts.
 
 use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
@@ -63,6 +65,20 @@ pub(crate) struct Origin {
     /// Override key (`InputType::Override`) or schema key
     /// (`InputType::Default`).
     pub key: Option<String>,
+    /// File the value was read from, when the layer supplied a reference
+    /// to it (a `_FILE` environment variable or a
+    /// [`from_file`](crate::runtime::Leaf::from_file) leaf). The fields
+    /// above still name where the reference came from.
+    pub secret_file: Option<PathBuf>,
+    /// The string as written, when the value was produced by `${...}`
+    /// interpolation (see [`crate::interpolate`]). Read through
+    /// [`Provenance::raw`](crate::Provenance::raw) rather than
+    /// [`OriginFacts`], which names inputs, not values.
+    pub raw: Option<String>,
+    /// The layer supplied the [unset marker](crate::Builder::unset_marker)
+    /// here: the inherited value was dropped, and whatever the key holds
+    /// now (nothing, or its schema default) stands in for it.
+    pub reset: bool,
 }
 
 impl Origin {
@@ -96,6 +112,9 @@ impl Origin {
             env_vars: Vec::new(),
             url_key: None,
             key: None,
+            secret_file: None,
+            raw: None,
+            reset: false,
         }
     }
 
@@ -110,6 +129,9 @@ impl Origin {
             env_vars: vars.into(),
             url_key: None,
             key: None,
+            secret_file: None,
+            raw: None,
+            reset: false,
         }
     }
 
@@ -125,6 +147,9 @@ impl Origin {
             env_vars: Vec::new(),
             url_key: Some(query_key.into()),
             key: None,
+            secret_file: None,
+            raw: None,
+            reset: false,
         }
     }
 
@@ -140,6 +165,9 @@ impl Origin {
             env_vars: Vec::new(),
             url_key: None,
             key: Some(override_key.into()),
+            secret_file: None,
+            raw: None,
+            reset: false,
         }
     }
 
@@ -154,6 +182,9 @@ impl Origin {
             env_vars: Vec::new(),
             url_key: None,
             key: Some(schema_key.into()),
+            secret_file: None,
+            raw: None,
+            reset: false,
         }
     }
 
@@ -202,6 +233,8 @@ impl Origin {
             url_key: self.url_key.clone(),
             key: self.key.clone(),
             input_type: Some(self.layer),
+            secret_file: self.secret_file.clone(),
+            reset: self.reset,
         }
     }
 }
@@ -292,6 +325,11 @@ impl OriginNode {
 
 /// Look up the origin of the node at `path` in a root origin map.
 pub(crate) fn lookup<'a>(map: &'a OriginMap, path: &ConfigPath) -> Option<&'a Origin> {
+    lookup_node(map, path).map(|node| &node.origin)
+}
+
+/// The node at `path` in a root origin map, children included.
+pub(crate) fn lookup_node<'a>(map: &'a OriginMap, path: &ConfigPath) -> Option<&'a OriginNode> {
     let mut segments = path.segments().iter();
     let PathSegment::Key(first) = segments.next()? else {
         return None;
@@ -304,7 +342,24 @@ pub(crate) fn lookup<'a>(map: &'a OriginMap, path: &ConfigPath) -> Option<&'a Or
             _ => return None,
         };
     }
-    Some(&node.origin)
+    Some(node)
+}
+
+/// Mutable [`lookup`]: the origin of the node at `path`.
+pub(crate) fn lookup_mut<'a>(map: &'a mut OriginMap, path: &ConfigPath) -> Option<&'a mut Origin> {
+    let mut segments = path.segments().iter();
+    let PathSegment::Key(first) = segments.next()? else {
+        return None;
+    };
+    let mut node = map.get_mut(first)?;
+    for segment in segments {
+        node = match (segment, &mut node.children) {
+            (PathSegment::Key(k), OriginChildren::Map(m)) => m.get_mut(k)?,
+            (PathSegment::Index(i), OriginChildren::Array(items)) => items.get_mut(*i)?,
+            _ => return None,
+        };
+    }
+    Some(&mut node.origin)
 }
 
 /// Build an origin map from a parsed file table and its span index.
@@ -400,11 +455,21 @@ fn walk_env_value(value: &Value, path: ConfigPath, winners: &EnvWinners) -> Orig
     );
     match value {
         Value::Map(m) => OriginNode::map(origin, walk_env_map(m, path, winners)),
+        // Indexed variables (`MYAPP__SERVERS__0__HOST`) record a winner
+        // per element; a list value's items share the array's variable.
         Value::Array(items) => OriginNode::array(
             origin.clone(),
             items
                 .iter()
-                .map(|item| OriginNode::from_value(item, origin.clone()))
+                .enumerate()
+                .map(|(i, item)| {
+                    let element = path.clone().index(i);
+                    if winners.contains_key(&element) {
+                        walk_env_value(item, element, winners)
+                    } else {
+                        OriginNode::from_value(item, origin.clone())
+                    }
+                })
                 .collect(),
         ),
         _ => OriginNode::leaf(origin),
@@ -425,6 +490,25 @@ pub(crate) fn take_map_children(node: Option<OriginNode>) -> (Option<Origin>, Or
     }
 }
 
+/// Split an array origin node into its own origin and one child per
+/// element of a `len`-element array. Children the node lacks (one
+/// origin recorded for the whole array) take the node's origin.
+pub(crate) fn take_array_children(
+    node: Option<OriginNode>,
+    len: usize,
+) -> Option<(Origin, Vec<OriginNode>)> {
+    let (origin, mut items) = match node? {
+        OriginNode {
+            origin,
+            children: OriginChildren::Array(items),
+        } => (origin, items),
+        OriginNode { origin, .. } => (origin, Vec::new()),
+    };
+    items.truncate(len);
+    items.resize_with(len, || OriginNode::leaf(origin.clone()));
+    Some((origin, items))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
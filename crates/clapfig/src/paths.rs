@@ -0,0 +1,262 @@
+//! [`Path`](crate::runtime::LeafType::Path) leaves: values that name a
+//! place on disk.
+//!
+//! After merge, each path leaf's string is rewritten to the path it
+//! means. `$VAR` / `${VAR}` expand from the environment when the leaf
+//! asks for it (interpolation leaves such a leaf alone, so `${VAR}` is
+//! never a key reference), then `~` and relative paths resolve like any
+//! reference ([`reference_path`]): a relative path from a config file is
+//! relative to that file's directory, whatever the working directory
+//! is. The existence checks run last, through the injected
+//! [`StatPath`], so the walk does no I/O of its own.
+
+use std::path::Path;
+
+use crate::error::ClapfigError;
+use crate::file_ref::reference_path;
+use crate::format::ConfigPath;
+use crate::interpolate::LookupEnv;
+use crate::origin::{OriginMap, lookup};
+use crate::runtime::{DocumentRoot, Leaf, LeafType, PathKind, Schema, Shape};
+use crate::value::{Map, Value};
+
+/// What a path names on disk, as far as the existence checks care.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Entry {
+    File,
+    Dir,
+    Other,
+}
+
+/// Looks up what `path` names, following links; `None` when nothing is
+/// there (or it cannot be inspected).
+pub(crate) type StatPath = fn(&Path) -> Option<Entry>;
+
+/// The production [`StatPath`].
+pub(crate) fn stat_from_disk(path: &Path) -> Option<Entry> {
+    let meta = std::fs::metadata(path).ok()?;
+    Some(if meta.is_file() {
+        Entry::File
+    } else if meta.is_dir() {
+        Entry::Dir
+    } else {
+        Entry::Other
+    })
+}
+
+/// Resolve every path leaf in the merged table in place. Runs after
+/// defaults are filled, so a default path resolves too (against the
+/// working directory, as it comes from no file). A failure is an
+/// `InvalidValue` located at the leaf's origin.
+pub(crate) fn resolve_path_leaves_root(
+    table: &mut Map,
+    origins: &OriginMap,
+    root: DocumentRoot<'_>,
+    lookup_env: LookupEnv,
+    stat: StatPath,
+) -> Result<(), ClapfigError> {
+    let walk = PathLeafWalk {
+        origins,
+        lookup_env,
+        stat,
+    };
+    let path = ConfigPath::new();
+    match root {
+        DocumentRoot::Object(schema) => walk.table(table, schema, &path),
+        DocumentRoot::Map(map) => {
+            for (key, value) in table.iter_mut() {
+                walk.value(value, &map.item, &path.clone().key(key))?;
+            }
+            Ok(())
+        }
+        DocumentRoot::Tagged(tagged) => match tagged.selected(table) {
+            Some(variant) => walk.table(table, &variant.schema, &path),
+            None => Ok(()),
+        },
+    }
+}
+
+struct PathLeafWalk<'a> {
+    origins: &'a OriginMap,
+    lookup_env: LookupEnv,
+    stat: StatPath,
+}
+
+impl PathLeafWalk<'_> {
+    fn table(
+        &self,
+        table: &mut Map,
+        schema: &Schema,
+        path: &ConfigPath,
+    ) -> Result<(), ClapfigError> {
+        for nf in &schema.fields {
+            if let Some(value) = table.get_mut(&nf.name) {
+                self.value(value, &nf.field, &path.clone().key(&nf.name))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn value(
+        &self,
+        value: &mut Value,
+        shape: &Shape,
+        path: &ConfigPath,
+    ) -> Result<(), ClapfigError> {
+        match (shape, value) {
+            (Shape::Leaf(leaf), Value::String(raw)) if matches!(leaf.ty, LeafType::Path { .. }) => {
+                *raw = self.resolve(raw, leaf, path).map_err(|reason| {
+                    ClapfigError::invalid_value_at(path.to_string(), reason, self.origins, path)
+                })?;
+                Ok(())
+            }
+            (Shape::Object(schema), Value::Map(table)) => self.table(table, schema, path),
+            (Shape::Tagged(tagged), Value::Map(table)) => match tagged.selected(table) {
+                Some(variant) => self.table(table, &variant.schema, path),
+                None => Ok(()),
+            },
+            (Shape::Map(map), Value::Map(entries)) => {
+                for (key, entry) in entries.iter_mut() {
+                    self.value(entry, &map.item, &path.clone().key(key))?;
+                }
+                Ok(())
+            }
+            (Shape::Array(array), Value::Array(items)) => {
+                for (i, item) in items.iter_mut().enumerate() {
+                    self.value(item, &array.item, &path.clone().index(i))?;
+                }
+                Ok(())
+            }
+            // Anything but a string fails the finalize type check.
+            _ => Ok(()),
+        }
+    }
+
+    /// The resolved form of `raw`, or the reason it is not acceptable.
+    fn resolve(&self, raw: &str, leaf: &Leaf, path: &ConfigPath) -> Result<String, String> {
+        let LeafType::Path {
+            expand_vars,
+            must_exist,
+            kind,
+        } = leaf.ty
+        else {
+            unreachable!("caller matched a path leaf");
+        };
+        let shown = |p: &Path| {
+            if leaf.sensitive {
+                crate::redact::REDACTED.to_string()
+            } else {
+                format!("'{}'", p.display())
+            }
+        };
+        let expanded = if expand_vars {
+            expand_env(raw, self.lookup_env)?
+        } else {
+            raw.to_owned()
+        };
+        // Joined to a file's directory, an empty path would name the
+        // directory itself.
+        if expanded.is_empty() {
+            return Err("path is empty".into());
+        }
+        let resolved = reference_path(&expanded, lookup(self.origins, path));
+        if must_exist || kind.is_some() {
+            let found = (self.stat)(&resolved);
+            match (found, kind) {
+                (None, _) => return Err(format!("path {} does not exist", shown(&resolved))),
+                (Some(entry), Some(PathKind::Dir)) if entry != Entry::Dir => {
+                    return Err(format!("path {} is not a directory", shown(&resolved)));
+                }
+                (Some(entry), Some(PathKind::File)) if entry != Entry::File => {
+                    return Err(format!("path {} is not a file", shown(&resolved)));
+                }
+                _ => {}
+            }
+        }
+        resolved
+            .into_os_string()
+            .into_string()
+            .map_err(|p| format!("path {} is not valid UTF-8", shown(Path::new(&p))))
+    }
+}
+
+/// Expand `$NAME` and `${NAME}` in `raw` with `lookup`. `$$` is a
+/// literal `$`, and a `$` that starts neither form stays as written.
+fn expand_env(raw: &str, lookup: LookupEnv) -> Result<String, String> {
+    let is_name = |c: char| c == '_' || c.is_ascii_alphanumeric();
+    let mut out = String::with_capacity(raw.len());
+    let mut rest = raw;
+    while let Some(at) = rest.find('$') {
+        out.push_str(&rest[..at]);
+        let after = &rest[at + 1..];
+        let (name, tail) = if let Some(tail) = after.strip_prefix('$') {
+            out.push('$');
+            rest = tail;
+            continue;
+        } else if let Some(braced) = after.strip_prefix('{') {
+            let end = braced
+                .find('}')
+                .ok_or_else(|| format!("unterminated `${{` in path '{raw}'"))?;
+            (&braced[..end], &braced[end + 1..])
+        } else {
+            let end = after.find(|c| !is_name(c)).unwrap_or(after.len());
+            if end == 0 || after.starts_with(|c: char| c.is_ascii_digit()) {
+                out.push('$');
+                rest = after;
+                continue;
+            }
+            (&after[..end], &after[end..])
+        };
+        if name.is_empty() || !name.chars().all(is_name) {
+            return Err(format!("invalid variable name `{name}` in path '{raw}'"));
+        }
+        let value =
+            lookup(name).ok_or_else(|| format!("environment variable {name} is not set"))?;
+        out.push_str(&value);
+        rest = tail;
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env(name: &str) -> Option<String> {
+        match name {
+            "DATA" => Some("/srv/data".into()),
+            "USER" => Some("ada".into()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn expands_plain_and_braced_variables() {
+        assert_eq!(expand_env("$DATA/cache", env).unwrap(), "/srv/data/cache");
+        assert_eq!(expand_env("${USER}_logs", env).unwrap(), "ada_logs");
+        assert_eq!(expand_env("no vars", env).unwrap(), "no vars");
+    }
+
+    #[test]
+    fn dollar_escapes_and_stray_dollars_stay_literal() {
+        assert_eq!(expand_env("a$$b", env).unwrap(), "a$b");
+        assert_eq!(expand_env("cost $5/", env).unwrap(), "cost $5/");
+        assert_eq!(expand_env("end$", env).unwrap(), "end$");
+    }
+
+    #[test]
+    fn unset_and_malformed_variables_are_errors() {
+        assert!(
+            expand_env("$NOPE/x", env)
+                .unwrap_err()
+                .contains("NOPE is not set")
+        );
+        assert!(
+            expand_env("${DATA", env)
+                .unwrap_err()
+                .contains("unterminated")
+        );
+        assert!(expand_env("${}", env).unwrap_err().contains("invalid"));
+    }
+}
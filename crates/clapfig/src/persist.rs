@@ -60,6 +60,13 @@ use crate::value::Value;
 /// document the load path refuses (the I/O wrapper stamps the file path;
 /// from this pure function the error's path is empty).
 ///
+/// A field's [alias](crate::runtime::FieldBuilder::alias) is accepted
+/// for its name. The edit lands on whichever spelling the document
+/// already uses, so `pool_size` replaces an existing `pool = 7` rather
+/// than adding a sibling the load would reject; a new key is written
+/// under the field's own name. A document already giving one field two
+/// spellings fails with [`ClapfigError::AliasCollision`].
+///
 /// If `content` is `None` (file doesn't exist yet), starts from the
 /// adapter's generated template — rendered with the same `normalize_keys`
 /// setting, so the seeded file and `config gen` agree on key spelling —
@@ -90,21 +97,8 @@ pub fn set_in_document(
     raw_value: &str,
     normalize_keys: bool,
 ) -> Result<String, ClapfigError> {
-    let canonical = canonical_key(key, normalize_keys);
-    let valid_keys = crate::overrides::valid_keys_shape(shape);
-    if !valid_keys.contains(&canonical) {
-        if let Some((section, kind)) = unaddressable_container_shape(shape, &canonical) {
-            return Err(ClapfigError::UnaddressableKey {
-                key: key.into(),
-                section,
-                kind,
-            });
-        }
-        return Err(ClapfigError::KeyNotFound {
-            key: key.into(),
-            suggestion: crate::meta::nearest_key_shape(shape, &canonical, normalize_keys),
-        });
-    }
+    let canonical = unaliased_key(shape, canonical_key(key, normalize_keys), normalize_keys);
+    require_settable_key(shape, key, &canonical, normalize_keys)?;
 
     // Parse an existing document before typed lookup so a tagged
     // object's selected discriminator can resolve variant fields.
@@ -123,43 +117,15 @@ pub fn set_in_document(
     }
     let existing = parsed.as_ref().map(|p| &p.value);
 
-    let disc_shape;
-    let field = match persist_target(shape, &canonical, existing, normalize_keys) {
-        PersistTarget::Shape(s) => Some(s),
-        PersistTarget::Discriminator(tagged) => {
-            disc_shape = crate::runtime::Shape::leaf(tagged.discriminator_leaf_type());
-            Some(&disc_shape)
-        }
-        PersistTarget::Unaddressable { section, kind } => {
-            return Err(ClapfigError::UnaddressableKey {
-                key: key.into(),
-                section,
-                kind,
-            });
-        }
-        PersistTarget::Missing => {
-            return Err(ClapfigError::KeyNotFound {
-                key: key.into(),
-                suggestion: crate::meta::nearest_key_shape(shape, &canonical, normalize_keys),
-            });
-        }
-    };
-    let mut value = parse_raw_value(raw_value, field)
-        .map_err(|reason| ClapfigError::invalid_value(key, reason))?;
-    if let Some(shape) = field {
-        crate::schema_walk::coerce_value(&mut value, shape);
-        shape
-            .check_value(&value)
-            .map_err(|reason| ClapfigError::invalid_value(key, reason))?;
-    }
+    let value = typed_value(shape, key, &canonical, raw_value, existing, normalize_keys)?;
 
     let (base, target, path) = match (content, parsed) {
         (Some(c), Some(parsed)) => {
             // Replace vs create-key depends on whether the path already
             // resolves; classification uses the document parsed above.
-            let (segments, exists) =
-                resolve_document_path(&parsed.value, &canonical, normalize_keys)
-                    .map_err(|c| c.into_error(Path::new("")))?;
+            let spelled = document_key(shape, &parsed.value, &canonical, normalize_keys)?;
+            let (segments, exists) = resolve_document_path(&parsed.value, &spelled, normalize_keys)
+                .map_err(|c| c.into_error(Path::new("")))?;
             let target = if exists {
                 SetTarget::ExistingValue
             } else {
@@ -205,11 +171,121 @@ pub fn set_in_document(
         .map_err(ClapfigError::from)
 }
 
+/// Parse one `key=value` assignment (`myapp -c port=8080`) into an
+/// override pair, exactly as `config set` would parse `key` and `value`:
+/// an unknown key is [`ClapfigError::KeyNotFound`] with the nearest-key
+/// suggestion, and the value is typed by the key's declared shape. With
+/// no document to select a variant, a tagged-union field is accepted
+/// only when every variant declares it with the same shape. A value
+/// equal to the builder's `unset_marker` passes through as that string,
+/// untyped, for the resolve pipeline to treat as a reset. The key keeps
+/// the caller's spelling; the resolve pipeline normalizes override keys
+/// itself.
+pub(crate) fn parse_assignment(
+    shape: &crate::runtime::Shape,
+    assignment: &str,
+    normalize_keys: bool,
+    unset_marker: Option<&str>,
+) -> Result<(String, Value), ClapfigError> {
+    let Some((key, raw_value)) = assignment.split_once('=') else {
+        return Err(ClapfigError::invalid_value(
+            assignment,
+            format!("expected key=value, got '{assignment}'"),
+        ));
+    };
+    let canonical = unaliased_key(shape, canonical_key(key, normalize_keys), normalize_keys);
+    require_settable_key(shape, key, &canonical, normalize_keys)?;
+    if unset_marker == Some(raw_value) {
+        return Ok((key.to_owned(), Value::String(raw_value.to_owned())));
+    }
+    let value = typed_value(shape, key, &canonical, raw_value, None, normalize_keys)?;
+    Ok((key.to_owned(), value))
+}
+
+/// Fail unless `canonical` names a settable schema key: a container the
+/// dotted key cannot index into is [`ClapfigError::UnaddressableKey`],
+/// anything else unknown is [`ClapfigError::KeyNotFound`].
+fn require_settable_key(
+    shape: &crate::runtime::Shape,
+    key: &str,
+    canonical: &str,
+    normalize_keys: bool,
+) -> Result<(), ClapfigError> {
+    if crate::overrides::valid_keys_shape(shape).contains(canonical) {
+        return Ok(());
+    }
+    if let Some((section, kind)) = unaddressable_container_shape(shape, canonical) {
+        return Err(ClapfigError::UnaddressableKey {
+            key: key.into(),
+            section,
+            kind,
+        });
+    }
+    Err(ClapfigError::KeyNotFound {
+        key: key.into(),
+        suggestion: crate::meta::nearest_key_shape(shape, canonical, normalize_keys),
+    })
+}
+
+/// `raw_value` parsed toward the shape at `canonical`, coerced, and
+/// checked. `existing` is the document being edited, which selects a
+/// tagged union's variant.
+fn typed_value(
+    shape: &crate::runtime::Shape,
+    key: &str,
+    canonical: &str,
+    raw_value: &str,
+    existing: Option<&Value>,
+    normalize_keys: bool,
+) -> Result<Value, ClapfigError> {
+    let disc_shape;
+    let field = match persist_target(shape, canonical, existing, normalize_keys) {
+        PersistTarget::Shape(s) => Some(s),
+        PersistTarget::Discriminator(tagged) => {
+            disc_shape = crate::runtime::Shape::leaf(tagged.discriminator_leaf_type());
+            Some(&disc_shape)
+        }
+        PersistTarget::Unaddressable { section, kind } => {
+            return Err(ClapfigError::UnaddressableKey {
+                key: key.into(),
+                section,
+                kind,
+            });
+        }
+        PersistTarget::Missing => {
+            return Err(ClapfigError::KeyNotFound {
+                key: key.into(),
+                suggestion: crate::meta::nearest_key_shape(shape, canonical, normalize_keys),
+            });
+        }
+    };
+    let mut value = parse_raw_value(raw_value, field)
+        .map_err(|reason| ClapfigError::invalid_value(key, reason))?;
+    if let Some(shape) = field {
+        crate::schema_walk::coerce_value(&mut value, shape);
+        shape
+            .check_value(&value)
+            .map_err(|reason| ClapfigError::invalid_value(key, reason))?;
+    }
+    Ok(value)
+}
+
 /// Stamp `file_path` onto a collision error raised by a document-level
 /// (pure, pathless) persist function, so the reported error names the
 /// file actually edited — the same shape the load path reports.
-fn stamp_collision_path(err: ClapfigError, file_path: &Path) -> ClapfigError {
+pub(crate) fn stamp_collision_path(err: ClapfigError, file_path: &Path) -> ClapfigError {
     match err {
+        ClapfigError::AliasCollision {
+            input,
+            section,
+            key,
+            originals,
+        } if input.is_empty() => ClapfigError::AliasCollision {
+            input: file_path.display().to_string(),
+            section,
+            key,
+            originals,
+        },
         ClapfigError::NormalizedKeyCollision {
             path,
             section,
@@ -229,7 +305,8 @@ fn stamp_collision_path(err: ClapfigError, file_path: &Path) -> ClapfigError {
 /// (if it exists), patches it, writes back. Creates parent directories if
 /// needed. Collision errors from the document layer get this file's path.
 /// A successful write emits a `debug` persist event naming the file and
-/// key, never the assigned value.
+/// key, never the assigned value; the returned confirmation shows a
+/// sensitive value as `<redacted>`.
 pub fn persist_value(
     adapter: &dyn FormatAdapter,
     shape: &crate::runtime::Shape,
@@ -271,7 +348,13 @@ pub fn persist_value(
     })?;
 
     crate::trace::persist_set(file_path, key);
-    Ok(ConfigResult::value_set(adapter, key.into(), value.into()))
+    // Confirm a secret without echoing it back.
+    let shown = if crate::redact::is_sensitive_at(shape, key) {
+        crate::redact::REDACTED
+    } else {
+        value
+    };
+    Ok(ConfigResult::value_set(adapter, key.into(), shown.into()))
 }
 
 /// Typed persist target for a canonical dotted key.
@@ -290,8 +373,11 @@ enum PersistTarget<'a> {
 /// Pure function: remove a key from a config document string through
 /// `adapter`.
 ///
-/// If the key doesn't exist, returns the document unchanged.
-/// Navigates dotted key paths (e.g. `"database.pool_size"`). With
+/// Navigates dotted key paths (e.g. `"database.pool_size"`). A field's
+/// alias is accepted for its name, and whichever of the field's
+/// spellings the document uses is the one removed. A key the document
+/// does not hold under any spelling is [`ClapfigError::KeyNotFound`], so
+/// an unset never reports success without changing anything. With
 /// `normalize_keys`, the document is parsed first and the key is resolved
 /// by dash/underscore equivalence, so `unset pool_size` removes an
 /// existing `pool-size` entry (and vice versa) — parse failures propagate
@@ -305,29 +391,39 @@ enum PersistTarget<'a> {
 /// Returns the modified document string.
 pub fn unset_in_document(
     adapter: &dyn FormatAdapter,
+    shape: &crate::runtime::Shape,
     content: &str,
     key: &str,
     normalize_keys: bool,
 ) -> Result<String, ClapfigError> {
-    let path = if normalize_keys {
-        let tree = adapter.parse(content).map_err(ClapfigError::from)?.value;
-        let (segments, _) = resolve_document_path(&tree, &normalize_key(key), true)
-            .map_err(|c| c.into_error(Path::new("")))?;
-        config_path(&segments)
-    } else {
-        dotted_config_path(key)
-    };
+    let canonical = unaliased_key(shape, canonical_key(key, normalize_keys), normalize_keys);
+    let tree = adapter.parse(content).map_err(ClapfigError::from)?.value;
+    if normalize_keys && let Value::Map(map) = &tree {
+        check_collisions(map).map_err(|c| c.into_error(Path::new("")))?;
+    }
+    let spelled = document_key(shape, &tree, &canonical, normalize_keys)?;
+    let (segments, exists) = resolve_document_path(&tree, &spelled, normalize_keys)
+        .map_err(|c| c.into_error(Path::new("")))?;
+    if !exists {
+        return Err(ClapfigError::KeyNotFound {
+            key: key.into(),
+            suggestion: crate::meta::nearest_key_shape(shape, &canonical, normalize_keys),
+        });
+    }
+    let path = config_path(&segments);
     adapter
         .edit(content, FileEdit::Unset { path: &path })
         .map_err(ClapfigError::from)
 }
 
 /// I/O wrapper: reads file, removes the key, writes back.
-/// If the file doesn't exist, succeeds silently (nothing to unset).
+/// If the file doesn't exist, succeeds silently (nothing to unset); a
+/// file that does not hold the key fails as [`unset_in_document`] does.
 /// A successful unset (including the missing-file no-op) emits a `debug`
 /// persist event naming the file and key.
 pub fn unset_value(
     adapter: &dyn FormatAdapter,
+    shape: &crate::runtime::Shape,
     file_path: &Path,
     key: &str,
     normalize_keys: bool,
@@ -346,7 +442,7 @@ pub fn unset_value(
         }
     };
 
-    let new_content = unset_in_document(adapter, &content, key, normalize_keys)
+    let new_content = unset_in_document(adapter, shape, &content, key, normalize_keys)
         .map_err(|e| stamp_collision_path(e, file_path))?;
 
     std::fs::write(file_path, &new_content).map_err(|e| ClapfigError::IoError {
@@ -370,6 +466,72 @@ fn canonical_key(key: &str, normalize_keys: bool) -> String {
     }
 }
 
+/// A user-supplied action key as the schema names it: normalized per
+/// [`canonical_key`], then with every alias segment rewritten to its
+/// field's name. `config get` reads through the same rule `config set`
+/// writes through.
+pub(crate) fn schema_key(shape: &crate::runtime::Shape, key: &str, normalize_keys: bool) -> String {
+    unaliased_key(shape, canonical_key(key, normalize_keys), normalize_keys)
+}
+
+/// `canonical` with every alias segment rewritten to its field's name, so
+/// `config set` and `-c` accept an alias wherever the inputs do.
+fn unaliased_key(shape: &crate::runtime::Shape, canonical: String, normalize_keys: bool) -> String {
+    let Some(root) = crate::runtime::DocumentRoot::of(shape) else {
+        return canonical;
+    };
+    let mut entries = [(canonical, String::new(), ())];
+    // A single key cannot reach a field by two spellings.
+    let _ = crate::alias::canonicalize_keys(&mut entries, root, normalize_keys);
+    let [(canonical, _, ())] = entries;
+    canonical
+}
+
+/// `canonical` (field names) respelled with the alias segments `tree`
+/// already uses, so an edit lands on the document's own spelling rather
+/// than beside it. A document giving one field two spellings fails as
+/// [`ClapfigError::AliasCollision`], the error its load reports (empty
+/// input here; the I/O wrapper stamps the file).
+pub(crate) fn document_key(
+    shape: &crate::runtime::Shape,
+    tree: &Value,
+    canonical: &str,
+    normalize_keys: bool,
+) -> Result<String, ClapfigError> {
+    let (Some(root), Value::Map(map)) = (crate::runtime::DocumentRoot::of(shape), tree) else {
+        return Ok(canonical.to_owned());
+    };
+    // Aliases compare in the spelling the load path sees.
+    let mut probe = map.clone();
+    if normalize_keys {
+        crate::normalize::normalize_table(&mut probe).map_err(|c| c.into_error(Path::new("")))?;
+    }
+    let renamed = crate::alias::canonicalize_table(&mut probe, root, normalize_keys)
+        .map_err(|collision| collision.into_error(""))?;
+    // A rename under a renamed parent names the parent by its field
+    // name, so undo the innermost first.
+    let keys = |path: &ConfigPath| -> Option<Vec<String>> {
+        path.segments()
+            .iter()
+            .map(|segment| match segment {
+                crate::format::PathSegment::Key(key) => Some(key.clone()),
+                crate::format::PathSegment::Index(_) => None,
+            })
+            .collect()
+    };
+    let mut segments: Vec<String> = canonical.split('.').map(str::to_owned).collect();
+    for renamed in renamed.iter().rev() {
+        // Renames inside arrays are out of a dotted key's reach.
+        let (Some(from), Some(to)) = (keys(&renamed.from), keys(&renamed.to)) else {
+            continue;
+        };
+        if let Some(rest) = segments.strip_prefix(to.as_slice()) {
+            segments = from.into_iter().chain(rest.iter().cloned()).collect();
+        }
+    }
+    Ok(segments.join("."))
+}
+
 /// The spelling emitted into a document for a path segment that is not
 /// already present: kebab-case when normalization is enabled (matching
 /// `config gen` output), the canonical snake_case otherwise.
@@ -397,7 +559,7 @@ fn emitted_spelling(segment: &str, normalize_keys: bool) -> String {
 /// ([`resolve_table_key`]); segments with no match resolve to their
 /// emitted (kebab-case) spelling. Without normalization, matching is
 /// exact and missing segments keep their canonical spelling.
-fn resolve_document_path(
+pub(crate) fn resolve_document_path(
     tree: &Value,
     canonical: &str,
     normalize_keys: bool,
@@ -440,7 +602,7 @@ fn resolve_document_path(
 }
 
 /// Build a structured [`ConfigPath`] from already-resolved path segments.
-fn config_path(segments: &[String]) -> ConfigPath {
+pub(crate) fn config_path(segments: &[String]) -> ConfigPath {
     let mut path = ConfigPath::new();
     for segment in segments {
         path = path.key(segment);
@@ -448,17 +610,6 @@ fn config_path(segments: &[String]) -> ConfigPath {
     path
 }
 
-/// Build a structured [`ConfigPath`] from a dotted persist key. Persist
-/// keys are schema-validated dotted paths, so every `.` is a nesting
-/// separator (schema field names cannot contain dots).
-fn dotted_config_path(key: &str) -> ConfigPath {
-    let mut path = ConfigPath::new();
-    for segment in key.split('.') {
-        path = path.key(segment);
-    }
-    path
-}
-
 /// If the canonical dotted key targets a
 /// [`Shape::Array`](crate::runtime::Shape::Array) /
 /// [`Shape::Map`](crate::runtime::Shape::Map) of objects (or a root map)
@@ -833,26 +984,32 @@ fn parse_raw_value(raw: &str, shape: Option<&crate::runtime::Shape>) -> Result<V
     let Some(shape) = shape else {
         return Ok(crate::env::parse_env_value(raw));
     };
+    // A secret's raw string stays out of the reason.
+    let shown = if shape.is_sensitive() {
+        crate::redact::REDACTED.to_owned()
+    } else {
+        format!("'{raw}'")
+    };
     match shape {
-        Shape::Array(_) => parse_inline_container(raw, "array", "[\"a\", \"b\"]"),
-        Shape::Map(_) => parse_inline_container(raw, "map", "{key = \"value\"}"),
+        Shape::Array(_) => parse_inline_container(raw, &shown, "array", "[\"a\", \"b\"]"),
+        Shape::Map(_) => parse_inline_container(raw, &shown, "map", "{key = \"value\"}"),
         Shape::Leaf(leaf) => match &leaf.ty {
-            LeafType::String => Ok(Value::String(raw.to_owned())),
+            LeafType::String | LeafType::Path { .. } => Ok(Value::String(raw.to_owned())),
             LeafType::Integer { .. } => raw
                 .parse::<i64>()
                 .map(Value::Integer)
-                .map_err(|_| format!("expected integer, got '{raw}'")),
+                .map_err(|_| format!("expected integer, got {shown}")),
             LeafType::Float => raw
                 .parse::<f64>()
                 .map(Value::Float)
-                .map_err(|_| format!("expected float, got '{raw}'")),
+                .map_err(|_| format!("expected float, got {shown}")),
             LeafType::Bool => {
                 if raw.eq_ignore_ascii_case("true") {
                     Ok(Value::Boolean(true))
                 } else if raw.eq_ignore_ascii_case("false") {
                     Ok(Value::Boolean(false))
                 } else {
-                    Err(format!("expected bool ('true' or 'false'), got '{raw}'"))
+                    Err(format!("expected bool ('true' or 'false'), got {shown}"))
                 }
             }
             LeafType::DateTime => Ok(Value::String(raw.to_owned())),
@@ -876,9 +1033,14 @@ fn parse_raw_value(raw: &str, shape: Option<&crate::runtime::Shape>) -> Result<V
 /// file's format; the resulting [`Value`] is then written through the
 /// active format's adapter like any other. A raw string TOML cannot
 /// parse as a value errors naming the expected container type with an
-/// example spelling.
-fn parse_inline_container(raw: &str, kind: &str, example: &str) -> Result<Value, String> {
-    let refuse = || format!("expected {kind} in TOML inline syntax (e.g. {example}), got '{raw}'");
+/// example spelling; `shown` is how the error names the raw string.
+fn parse_inline_container(
+    raw: &str,
+    shown: &str,
+    kind: &str,
+    example: &str,
+) -> Result<Value, String> {
+    let refuse = || format!("expected {kind} in TOML inline syntax (e.g. {example}), got {shown}");
     let doc = format!("v = {raw}");
     match crate::format::TomlAdapter.parse(&doc) {
         // Require exactly the probe key back: a raw string smuggling
@@ -1121,6 +1283,75 @@ mod tests {
         }
     }
 
+    #[test]
+    fn assignments_parse_like_config_set() {
+        let shape = Shape::Object(test_schema());
+        assert_eq!(
+            parse_assignment(&shape, "database.pool_size=10", false, None).unwrap(),
+            ("database.pool_size".to_string(), Value::Integer(10))
+        );
+        // Only the first `=` splits; a string leaf keeps the rest verbatim.
+        assert_eq!(
+            parse_assignment(&shape, "host=a=b", false, None).unwrap(),
+            ("host".to_string(), Value::String("a=b".into()))
+        );
+        assert!(matches!(
+            parse_assignment(&shape, "port=eighty", false, None),
+            Err(ClapfigError::InvalidValue { .. })
+        ));
+        assert!(matches!(
+            parse_assignment(&shape, "port", false, None),
+            Err(ClapfigError::InvalidValue { .. })
+        ));
+        match parse_assignment(&shape, "databse.url=x", false, None).unwrap_err() {
+            ClapfigError::KeyNotFound { key, suggestion } => {
+                assert_eq!(key, "databse.url");
+                assert_eq!(suggestion.as_deref(), Some("database.url"));
+            }
+            other => panic!("expected KeyNotFound, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn assignments_and_set_accept_an_alias_for_its_field() {
+        use crate::runtime::{Field, Schema};
+        let shape = Shape::Object(
+            Schema::object("T")
+                .field("timeout", Field::integer().alias("timeout_secs"))
+                .build(),
+        );
+        assert_eq!(
+            parse_assignment(&shape, "timeout_secs=5", false, None).unwrap(),
+            ("timeout_secs".to_string(), Value::Integer(5))
+        );
+        assert!(matches!(
+            parse_assignment(&shape, "timeout_secs=soon", false, None),
+            Err(ClapfigError::InvalidValue { .. })
+        ));
+        let doc =
+            set_in_document(&TomlAdapter, &shape, Some(""), "timeout_secs", "5", false).unwrap();
+        assert_eq!(doc.trim(), "timeout = 5");
+    }
+
+    #[test]
+    fn an_assignment_of_the_unset_marker_stays_untyped() {
+        let shape = Shape::Object(test_schema());
+        assert_eq!(
+            parse_assignment(&shape, "port=<unset>", false, Some("<unset>")).unwrap(),
+            ("port".to_string(), Value::String("<unset>".into()))
+        );
+        // Without the marker enabled it is an ordinary bad integer.
+        assert!(matches!(
+            parse_assignment(&shape, "port=<unset>", false, None),
+            Err(ClapfigError::InvalidValue { .. })
+        ));
+        // The key is still checked.
+        assert!(matches!(
+            parse_assignment(&shape, "prot=<unset>", false, Some("<unset>")),
+            Err(ClapfigError::KeyNotFound { .. })
+        ));
+    }
+
     #[test]
     fn set_rejects_invalid_enum_value() {
         let result = set_in_document(
@@ -1862,7 +2093,14 @@ mod tests {
     fn normalized_unset_removes_equivalent_spelling() {
         for (adapter, doc) in docs("pool-size") {
             for action_key in ["database.pool-size", "database.pool_size"] {
-                let out = unset_in_document(adapter, &doc, action_key, true).unwrap();
+                let out = unset_in_document(
+                    adapter,
+                    &Shape::Object(test_schema()),
+                    &doc,
+                    action_key,
+                    true,
+                )
+                .unwrap();
                 let map = doc_map(adapter, &out);
                 let db = map
                     .get("database")
@@ -1995,7 +2233,8 @@ mod tests {
         // Unset runs the same whole-document validation.
         for (adapter, doc) in colliding_docs() {
             let doc = with_host(adapter, doc);
-            let result = unset_in_document(adapter, &doc, "host", true);
+            let result =
+                unset_in_document(adapter, &Shape::Object(test_schema()), &doc, "host", true);
             assert_collision(
                 result,
                 "database",
@@ -2023,7 +2262,13 @@ mod tests {
         // Unset resolves through the same collision-aware traversal.
         for (adapter, doc) in colliding_docs() {
             for action_key in ["database.pool-size", "database.pool_size"] {
-                let result = unset_in_document(adapter, doc, action_key, true);
+                let result = unset_in_document(
+                    adapter,
+                    &Shape::Object(test_schema()),
+                    doc,
+                    action_key,
+                    true,
+                );
                 assert_collision(
                     result,
                     "database",
@@ -2053,7 +2298,13 @@ mod tests {
                 "20",
                 true,
             ),
-            unset_value(&TomlAdapter, &path, "database.pool_size", true),
+            unset_value(
+                &TomlAdapter,
+                &Shape::Object(test_schema()),
+                &path,
+                "database.pool_size",
+                true,
+            ),
         ] {
             match result.unwrap_err() {
                 ClapfigError::NormalizedKeyCollision { path: reported, .. } => {
@@ -2068,10 +2319,15 @@ mod tests {
     }
 
     #[test]
-    fn normalized_unset_missing_key_is_noop() {
-        let out =
-            unset_in_document(&TomlAdapter, "host = \"h\"\n", "database.pool_size", true).unwrap();
-        assert!(out.contains("host = \"h\""));
+    fn normalized_unset_missing_key_is_key_not_found() {
+        let result = unset_in_document(
+            &TomlAdapter,
+            &Shape::Object(test_schema()),
+            "host = \"h\"\n",
+            "database.pool_size",
+            true,
+        );
+        assert!(matches!(result, Err(ClapfigError::KeyNotFound { .. })));
     }
 
     #[test]
@@ -2079,14 +2335,47 @@ mod tests {
         // With normalization off there is no equivalence: a kebab entry
         // is not touched by the snake key (and would be an unknown key at
         // load time anyway).
-        let out = unset_doc("[database]\npool-size = 5\n", "database.pool_size").unwrap();
-        assert!(out.contains("pool-size = 5"));
+        let result = unset_doc("[database]\npool-size = 5\n", "database.pool_size");
+        assert!(matches!(result, Err(ClapfigError::KeyNotFound { .. })));
+    }
+
+    #[test]
+    fn normalized_edits_follow_a_kebab_alias_spelling() {
+        use crate::runtime::{Field, Schema};
+        let shape = Shape::Object(
+            Schema::object("App")
+                .nested(
+                    "database",
+                    Schema::object("Db")
+                        .field("pool_size", Field::integer().alias("max_conn").optional()),
+                )
+                .build(),
+        );
+        let doc = "[database]\nmax-conn = 7\n";
+        let out = set_in_document(
+            &TomlAdapter,
+            &shape,
+            Some(doc),
+            "database.pool-size",
+            "4",
+            true,
+        )
+        .unwrap();
+        assert_eq!(out, "[database]\nmax-conn = 4\n");
+        let out = unset_in_document(&TomlAdapter, &shape, doc, "database.pool_size", true).unwrap();
+        assert_eq!(out, "[database]\n");
     }
 
     // --- unset tests ---
 
     fn unset_doc(content: &str, key: &str) -> Result<String, ClapfigError> {
-        unset_in_document(&TomlAdapter, content, key, false)
+        unset_in_document(
+            &TomlAdapter,
+            &Shape::Object(test_schema()),
+            content,
+            key,
+            false,
+        )
     }
 
     #[test]
@@ -2106,17 +2395,22 @@ mod tests {
     }
 
     #[test]
-    fn unset_nonexistent_key_is_noop() {
+    fn unset_nonexistent_key_is_key_not_found() {
+        // Nothing to remove is an error, not a success that changed
+        // nothing.
         let content = "port = 8080\n";
-        let result = unset_doc(content, "missing").unwrap();
-        assert!(result.contains("port = 8080"));
+        let result = unset_doc(content, "host");
+        assert!(
+            matches!(&result, Err(ClapfigError::KeyNotFound { key, .. }) if key == "host"),
+            "{result:?}"
+        );
     }
 
     #[test]
-    fn unset_nonexistent_nested_key_is_noop() {
+    fn unset_nonexistent_nested_key_is_key_not_found() {
         let content = "port = 8080\n";
-        let result = unset_doc(content, "database.missing").unwrap();
-        assert!(result.contains("port = 8080"));
+        let result = unset_doc(content, "database.url");
+        assert!(matches!(result, Err(ClapfigError::KeyNotFound { .. })));
     }
 
     #[test]
@@ -2134,7 +2428,14 @@ mod tests {
         let path = dir.path().join("config.toml");
         fs::write(&path, "port = 8080\nhost = \"localhost\"\n").unwrap();
 
-        let result = unset_value(&TomlAdapter, &path, "port", false).unwrap();
+        let result = unset_value(
+            &TomlAdapter,
+            &Shape::Object(test_schema()),
+            &path,
+            "port",
+            false,
+        )
+        .unwrap();
         assert!(matches!(result, ConfigResult::ValueUnset { .. }));
 
         let content = fs::read_to_string(&path).unwrap();
@@ -2147,7 +2448,14 @@ mod tests {
         let dir = TempDir::new().unwrap();
         let path = dir.path().join("nonexistent.toml");
 
-        let result = unset_value(&TomlAdapter, &path, "port", false).unwrap();
+        let result = unset_value(
+            &TomlAdapter,
+            &Shape::Object(test_schema()),
+            &path,
+            "port",
+            false,
+        )
+        .unwrap();
         assert!(matches!(result, ConfigResult::ValueUnset { .. }));
     }
 
@@ -0,0 +1,194 @@
+//! Profiles: named variants of a config, selected at load time.
+//!
+//! With [`Builder::profile_key`](crate::Builder::profile_key) set, a
+//! config file may hold one table per profile under that key:
+//!
+//! ```toml
+//! port = 8080
+//!
+//! [profile.prod]
+//! port = 443
+//! ```
+//!
+//! The resolve pipeline takes the profile table out of every document
+//! ([`take_sections`]) before validation. Each section is checked against
+//! the same schema as the document around it — unknown keys and all —
+//! whether or not its profile is active. The active profile's section is
+//! then one more sparse overlay, **just above** the document that holds
+//! it, with origins pointing into that file.
+//!
+//! On the I/O side, a discovered `myapp.toml` also loads a sibling
+//! `myapp.prod.toml` ([`sibling_path`]) just above it while `prod` is
+//! active.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::error::ClapfigError;
+use crate::format::{ConfigPath, SpanEntry};
+use crate::origin::Origin;
+use crate::value::{Map, Value};
+
+/// The key profile tables live under when
+/// [`Builder::profile`](crate::Builder::profile) enables profiles on its
+/// own.
+pub(crate) const DEFAULT_KEY: &str = "profile";
+
+/// One profile's table from a config document.
+pub(crate) struct Section {
+    pub name: String,
+    /// Where the section sits in the file's span index.
+    pub at: ConfigPath,
+    pub table: Map,
+}
+
+/// Remove the profile table at `key` from a parsed document and split it
+/// into one [`Section`] per profile. A profile table that is not a table
+/// of tables is an [`InvalidValue`](ClapfigError::InvalidValue) located
+/// in the file.
+pub(crate) fn take_sections(
+    table: &mut Map,
+    spans: &BTreeMap<ConfigPath, SpanEntry>,
+    key: &str,
+    path: &Path,
+    source: &Arc<str>,
+) -> Result<Vec<Section>, ClapfigError> {
+    let Some(profiles) = table.remove(key) else {
+        return Ok(Vec::new());
+    };
+    let root = ConfigPath::new().key(key);
+    let Value::Map(profiles) = profiles else {
+        return Err(invalid(
+            &root,
+            "expected a table of profiles",
+            spans,
+            path,
+            source,
+        ));
+    };
+    let mut sections = Vec::new();
+    for (name, section) in profiles {
+        let at = root.clone().key(&name);
+        let Value::Map(section) = section else {
+            return Err(invalid(
+                &at,
+                &format!("profile {name:?} must be a table"),
+                spans,
+                path,
+                source,
+            ));
+        };
+        sections.push(Section {
+            name,
+            at,
+            table: section,
+        });
+    }
+    Ok(sections)
+}
+
+/// The entries of `spans` below `at`, with `at` stripped from their
+/// paths — a section's span index, so `port`, not `profile.prod.port`.
+pub(crate) fn rooted_at(
+    spans: &BTreeMap<ConfigPath, SpanEntry>,
+    at: &ConfigPath,
+) -> BTreeMap<ConfigPath, SpanEntry> {
+    let prefix = at.segments();
+    spans
+        .iter()
+        .filter(|(path, _)| path.segments().starts_with(prefix))
+        .map(|(path, entry)| {
+            let rest = &path.segments()[prefix.len()..];
+            (ConfigPath::from(rest.to_vec()), *entry)
+        })
+        .collect()
+}
+
+fn invalid(
+    at: &ConfigPath,
+    reason: &str,
+    spans: &BTreeMap<ConfigPath, SpanEntry>,
+    path: &Path,
+    source: &Arc<str>,
+) -> ClapfigError {
+    let entry = spans.get(at);
+    let origin = Origin::file_with_spans(
+        path.to_path_buf(),
+        entry.and_then(|e| e.key),
+        entry.map(|e| e.value),
+        Arc::clone(source),
+    );
+    ClapfigError::InvalidValue {
+        key: at.to_string(),
+        reason: reason.into(),
+        origin: Box::new(origin.to_facts()),
+    }
+}
+
+/// The file that holds `profile`'s settings beside `file`:
+/// `myapp.toml` → `myapp.prod.toml`. `None` for an extensionless file,
+/// whose sibling would have no format to parse it by.
+pub(crate) fn sibling_path(file: &Path, profile: &str) -> Option<PathBuf> {
+    let stem = file.file_stem()?.to_string_lossy();
+    let ext = file.extension()?.to_string_lossy();
+    Some(file.with_file_name(format!("{stem}.{profile}.{ext}")))
+}
+
+/// The environment variable that selects a profile under env `prefix`:
+/// `MYAPP_PROFILE`. One underscore, so it never reads as a config key.
+pub(crate) fn env_var(prefix: &str) -> String {
+    format!("{prefix}_PROFILE")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::{FormatAdapter, TomlAdapter};
+
+    fn parse(text: &str) -> (Map, BTreeMap<ConfigPath, SpanEntry>, Arc<str>) {
+        let parsed = TomlAdapter.parse(text).unwrap();
+        let Value::Map(table) = parsed.value else {
+            panic!("TOML documents are tables");
+        };
+        (table, parsed.spans, Arc::from(text))
+    }
+
+    #[test]
+    fn sections_leave_the_document_with_rooted_spans() {
+        let text = "port = 1\n\n[profile.prod]\nport = 2\n\n[profile.dev]\nhost = \"x\"\n";
+        let (mut table, spans, source) = parse(text);
+        let sections =
+            take_sections(&mut table, &spans, "profile", Path::new("a.toml"), &source).unwrap();
+        assert!(!table.contains_key("profile"));
+        let names: Vec<_> = sections.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["dev", "prod"]);
+        let prod = &sections[1];
+        assert_eq!(prod.table.get("port"), Some(&Value::Integer(2)));
+        let span = rooted_at(&spans, &prod.at)[&ConfigPath::new().key("port")].value;
+        assert_eq!(&text[span.start..span.end], "2");
+    }
+
+    #[test]
+    fn a_non_table_profile_is_located() {
+        let text = "[profile]\nprod = 3\n";
+        let (mut table, spans, source) = parse(text);
+        match take_sections(&mut table, &spans, "profile", Path::new("a.toml"), &source) {
+            Err(ClapfigError::InvalidValue { key, origin, .. }) => {
+                assert_eq!(key, "profile.prod");
+                assert_eq!(origin.line_col(), Some((2, 8)));
+            }
+            Err(other) => panic!("expected InvalidValue, got {other:?}"),
+            Ok(_) => panic!("expected InvalidValue"),
+        }
+    }
+
+    #[test]
+    fn siblings_insert_the_profile_before_the_extension() {
+        assert_eq!(
+            sibling_path(Path::new("/etc/myapp/myapp.toml"), "prod"),
+            Some(PathBuf::from("/etc/myapp/myapp.prod.toml"))
+        );
+        assert_eq!(sibling_path(Path::new("/home/u/.myapprc"), "prod"), None);
+    }
+}
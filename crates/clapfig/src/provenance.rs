@@ -0,0 +1,232 @@
+//! Public, read-only view of a resolution's origin shadow tree.
+//!
+//! Every resolution already builds an origin tree in lockstep with the
+//! value merge (ADR-0004): one node per resolved value, naming the
+//! provenance layer that won it and whatever that layer knows (file and
+//! span, environment variable, URL query key, override key, schema key).
+//! [`Provenance`] hands that tree to callers so a tool can answer "why is
+//! this value what it is?" without scraping `RUST_LOG=clapfig=trace`
+//! output.
+//!
+//! Produced by [`Builder::load_with_origins`](crate::Builder::load_with_origins),
+//! [`Resolver::resolve_at_with_origins`](crate::Resolver::resolve_at_with_origins),
+//! and their typed counterparts. Lookups return the same flattened
+//! [`OriginFacts`] that [`ClapfigError::InvalidValue`](crate::ClapfigError::InvalidValue)
+//! carries — the crate-private origin payload stays private.
+//!
+//! The tree is **winner-only**: it names the input that supplied the
+//! effective value, not the candidates it beat. Keys absent from the
+//! resolved config have no origin, unless an input reset them with the
+//! unset marker: that input is the origin, with
+//! [`reset`](OriginFacts::reset) set. [`Explanation`] is the per-key
+//! complement: `config explain <key>` re-runs resolution recording every
+//! input that supplied the key, in precedence order, and which one won.
+
+use std::path::PathBuf;
+
+use crate::error::OriginFacts;
+use crate::format::ConfigPath;
+use crate::origin::{self, OriginChildren, OriginMap, OriginNode};
+use crate::types::Layer;
+use crate::value::Value;
+
+/// One map-key segment per `.`.
+fn dotted_path(key: &str) -> ConfigPath {
+    key.split('.')
+        .fold(ConfigPath::new(), |path, segment| path.key(segment))
+}
+
+/// Where every value of one resolved configuration came from.
+///
+/// Paths are the same structured [`ConfigPath`]s the span index and
+/// error rendering use, so a literal key containing `.` stays distinct
+/// from nesting and array elements are addressable by index.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Provenance {
+    root: OriginMap,
+}
+
+impl Provenance {
+    pub(crate) fn new(root: OriginMap) -> Self {
+        Self { root }
+    }
+
+    /// Origin of the node at `path` — a leaf, or the map / array node
+    /// itself. `None` when the resolved config has no value there.
+    pub fn get(&self, path: &ConfigPath) -> Option<OriginFacts> {
+        origin::lookup(&self.root, path).map(origin::Origin::to_facts)
+    }
+
+    /// Origin of the node at a dotted key (`"database.pool_size"`).
+    ///
+    /// Each `.` separates one map-key segment. Keys that contain a `.`
+    /// themselves, and array elements, are addressed with
+    /// [`get`](Self::get) and a structured [`ConfigPath`].
+    pub fn get_dotted(&self, key: &str) -> Option<OriginFacts> {
+        self.get(&dotted_path(key))
+    }
+
+    /// The string as written at `path`, before `${...}` interpolation
+    /// ([`Builder::interpolate`](crate::Builder::interpolate)). `None`
+    /// when the value there was not interpolated.
+    pub fn raw(&self, path: &ConfigPath) -> Option<&str> {
+        origin::lookup(&self.root, path).and_then(|origin| origin.raw.as_deref())
+    }
+
+    /// [`raw`](Self::raw) at a dotted key, addressed as in
+    /// [`get_dotted`](Self::get_dotted).
+    pub fn raw_dotted(&self, key: &str) -> Option<&str> {
+        self.raw(&dotted_path(key))
+    }
+
+    /// Every scalar leaf's path and origin, in key order (array elements
+    /// in index order). Empty maps and arrays contribute their own node,
+    /// so a schema-materialized `[]` still names its origin.
+    pub fn leaves(&self) -> Vec<(ConfigPath, OriginFacts)> {
+        let mut out = Vec::new();
+        for (key, node) in &self.root {
+            collect_leaves(node, ConfigPath::new().key(key), &mut out);
+        }
+        out
+    }
+
+    /// Whether the resolved config has no values (and so no origins).
+    pub fn is_empty(&self) -> bool {
+        self.root.is_empty()
+    }
+}
+
+/// Every input that supplied a value for one key, produced by
+/// [`Builder::explain`](crate::Builder::explain) /
+/// [`Resolver::explain_at`](crate::Resolver::explain_at) and rendered by
+/// `config explain <key>`.
+///
+/// Candidates are in precedence order — the schema default first, then
+/// each layer of [`layer_order`](Self::layer_order), files in discovery
+/// order — so the last scalar candidate is the one the merge kept.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Explanation {
+    /// The explained key, in its canonical dotted spelling.
+    pub key: String,
+    /// The resolved value, or `None` when no input supplied the key and
+    /// the schema declares no default.
+    pub value: Option<Value>,
+    /// Every input that supplied a value for the key, lowest precedence
+    /// first.
+    pub candidates: Vec<Candidate>,
+    /// The merge order that applied, lowest priority first. Layers
+    /// omitted from the builder's `layer_order` never contribute.
+    pub layer_order: Vec<Layer>,
+    /// Candidate files discovery never read because
+    /// [`SearchMode::FirstMatch`](crate::SearchMode::FirstMatch) stopped
+    /// at a higher-priority file.
+    pub not_probed: Vec<PathBuf>,
+}
+
+/// One input's value for an explained key.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Candidate {
+    /// Where the value came from.
+    pub origin: OriginFacts,
+    /// The value as that input supplied it, before the schema's final
+    /// type check.
+    pub value: Value,
+    /// Whether this candidate is the one the resolved config names as
+    /// the key's origin.
+    pub won: bool,
+}
+
+fn collect_leaves(node: &OriginNode, path: ConfigPath, out: &mut Vec<(ConfigPath, OriginFacts)>) {
+    match &node.children {
+        OriginChildren::Map(children) if !children.is_empty() => {
+            for (key, child) in children {
+                collect_leaves(child, path.clone().key(key), out);
+            }
+        }
+        OriginChildren::Array(items) if !items.is_empty() => {
+            for (i, item) in items.iter().enumerate() {
+                collect_leaves(item, path.clone().index(i), out);
+            }
+        }
+        _ => out.push((path, node.origin.to_facts())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::Span;
+    use crate::origin::Origin;
+    use crate::types::InputType;
+    use std::sync::Arc;
+
+    fn sample() -> Provenance {
+        let source: Arc<str> = Arc::from("[database]\nurl = \"pg://x\"\n");
+        let mut database = OriginMap::new();
+        database.insert(
+            "url".into(),
+            OriginNode::leaf(Origin::file(
+                "/etc/app.toml".into(),
+                Span { start: 17, end: 25 },
+                Arc::clone(&source),
+            )),
+        );
+        database.insert(
+            "pool_size".into(),
+            OriginNode::leaf(Origin::env(vec!["APP__DATABASE__POOL_SIZE".into()])),
+        );
+        let mut root = OriginMap::new();
+        root.insert(
+            "database".into(),
+            OriginNode::map(
+                Origin::file_with_span("/etc/app.toml".into(), None, source),
+                database,
+            ),
+        );
+        root.insert(
+            "tags".into(),
+            OriginNode::array(
+                Origin::r#override("tags"),
+                vec![OriginNode::leaf(Origin::r#override("tags"))],
+            ),
+        );
+        root.insert("a.b".into(), OriginNode::leaf(Origin::default("a.b")));
+        Provenance::new(root)
+    }
+
+    #[test]
+    fn get_dotted_names_the_winning_input() {
+        let provenance = sample();
+        let url = provenance.get_dotted("database.url").unwrap();
+        assert_eq!(url.input_type, Some(InputType::File));
+        assert_eq!(url.line_col(), Some((2, 7)));
+        let pool = provenance.get_dotted("database.pool_size").unwrap();
+        assert_eq!(pool.env_var.as_deref(), Some("APP__DATABASE__POOL_SIZE"));
+        assert!(provenance.get_dotted("database.missing").is_none());
+    }
+
+    #[test]
+    fn structured_paths_reach_literal_dotted_keys_and_indexes() {
+        let provenance = sample();
+        assert!(provenance.get_dotted("a.b").is_none());
+        let literal = provenance.get(&ConfigPath::new().key("a.b")).unwrap();
+        assert_eq!(literal.input_type, Some(InputType::Default));
+        let item = provenance
+            .get(&ConfigPath::new().key("tags").index(0))
+            .unwrap();
+        assert_eq!(item.key.as_deref(), Some("tags"));
+    }
+
+    #[test]
+    fn leaves_walk_every_scalar_in_key_order() {
+        let paths: Vec<String> = sample()
+            .leaves()
+            .into_iter()
+            .map(|(path, _)| path.to_string())
+            .collect();
+        assert_eq!(
+            paths,
+            ["\"a.b\"", "database.pool_size", "database.url", "tags[0]"]
+        );
+    }
+}
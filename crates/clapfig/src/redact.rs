@@ -0,0 +1,432 @@
+//! Redaction of [`sensitive`](crate::runtime::Leaf::sensitive) values.
+//!
+//! Loading never redacts — the app gets its secrets. Display does: every
+//! `config` result that prints values (`list`, `get`, `explain`, `diff`,
+//! the `set` confirmation) passes them through [`redact`] first, so a
+//! secret shows as [`REDACTED`]. A homogeneous array or map of sensitive
+//! items is replaced whole, so not even its length shows.
+//!
+//! Errors get the same treatment in two places: `InvalidValue` reasons
+//! for sensitive leaves omit the value
+//! ([`Leaf::check`](crate::runtime::Leaf)), and [`mask_source`] rewrites
+//! a parsed file's text — the text every origin from that file shares,
+//! and the one renderers quote in snippets — with each secret replaced by
+//! [`REDACTED`], remapping the span index to match.
+//!
+//! Key segments match with the [`meta`](crate::meta) lenient spelling
+//! rule, so un-normalized (kebab-case) scope files redact the same as
+//! the merged table.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use crate::format::{ConfigPath, Span, SpanEntry};
+use crate::meta::segment_matches;
+use crate::runtime::{DocumentRoot, KeyAcrossVariants, Schema, Shape, TaggedShape};
+use crate::value::{Map, Value};
+
+/// What a sensitive value displays as.
+pub(crate) const REDACTED: &str = "<redacted>";
+
+/// A table-shaped node: the fields a key of the table can name.
+#[derive(Clone, Copy)]
+enum Table<'a> {
+    Object(&'a Schema),
+    /// Entry keys are user data; every entry is `item`.
+    Map(&'a Shape),
+    Tagged(&'a TaggedShape),
+}
+
+impl<'a> Table<'a> {
+    fn of(shape: &'a Shape) -> Option<Self> {
+        match shape {
+            Shape::Object(schema) => Some(Table::Object(schema)),
+            Shape::Map(map) => Some(Table::Map(&map.item)),
+            Shape::Tagged(tagged) => Some(Table::Tagged(tagged)),
+            Shape::Leaf(_) | Shape::Array(_) => None,
+        }
+    }
+
+    fn root(root: DocumentRoot<'a>) -> Self {
+        match root {
+            DocumentRoot::Object(schema) => Table::Object(schema),
+            DocumentRoot::Map(map) => Table::Map(&map.item),
+            DocumentRoot::Tagged(tagged) => Table::Tagged(tagged),
+        }
+    }
+
+    /// The shapes `key` may have. A tagged union answers with its
+    /// selected variant when `table` names one, and otherwise with every
+    /// variant declaring `key` — a display that cannot tell the branch
+    /// apart errs toward hiding the value.
+    fn child(self, key: &str, table: Option<&Map>) -> Vec<&'a Shape> {
+        let find = |schema: &'a Schema| {
+            schema
+                .fields
+                .iter()
+                .find(|field| segment_matches(&field.name, key))
+                .map(|field| &field.field)
+        };
+        match self {
+            Table::Object(schema) => find(schema).into_iter().collect(),
+            Table::Map(item) => vec![item],
+            Table::Tagged(tagged) => match table.and_then(|table| tagged.selected(table)) {
+                Some(variant) => find(&variant.schema).into_iter().collect(),
+                None => match tagged.resolve_key_with(key, segment_matches) {
+                    KeyAcrossVariants::Every(shapes) | KeyAcrossVariants::Partial(shapes) => shapes,
+                    KeyAcrossVariants::Tag | KeyAcrossVariants::Absent => Vec::new(),
+                },
+            },
+        }
+    }
+}
+
+/// Replace every sensitive value inside `value`, which sits at `shape`.
+pub(crate) fn redact(shape: &Shape, value: &mut Value) {
+    if shape.is_sensitive() {
+        *value = Value::String(REDACTED.into());
+        return;
+    }
+    match (shape, value) {
+        (_, Value::Map(table)) => redact_table(shape, table),
+        (Shape::Array(array), Value::Array(items)) => {
+            for item in items {
+                redact(&array.item, item);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// [`redact`] for a table — a resolved document under its root shape, or
+/// a section under its object / map / tagged shape.
+pub(crate) fn redact_table(shape: &Shape, table: &mut Map) {
+    if let Some(node) = Table::of(shape) {
+        redact_in(node, table);
+    }
+}
+
+fn redact_in(node: Table<'_>, table: &mut Map) {
+    let children: Vec<(String, Vec<&Shape>)> = table
+        .keys()
+        .map(|key| (key.clone(), node.child(key, Some(table))))
+        .collect();
+    for (key, shapes) in children {
+        if let Some(child) = table.get_mut(&key) {
+            for shape in shapes {
+                redact(shape, child);
+            }
+        }
+    }
+}
+
+/// Replace every sensitive value inside `value`, which sits at the
+/// dotted `key` under the document root `shape`. Keys the schema does not
+/// declare are left alone.
+pub(crate) fn redact_at(shape: &Shape, key: &str, value: &mut Value) {
+    let segments: Vec<&str> = key.split('.').collect();
+    redact_segments(shape, &segments, value);
+}
+
+fn redact_segments(shape: &Shape, segments: &[&str], value: &mut Value) {
+    let Some((head, rest)) = segments.split_first() else {
+        redact(shape, value);
+        return;
+    };
+    if shape.is_sensitive() {
+        *value = Value::String(REDACTED.into());
+        return;
+    }
+    // Dotted keys do not index into arrays or below a leaf.
+    let Some(node) = Table::of(shape) else {
+        return;
+    };
+    for child in node.child(head, None) {
+        redact_segments(child, rest, value);
+    }
+}
+
+/// Whether the value at the dotted `key` under `shape` is a secret
+/// (a sensitive leaf, or anything below one).
+pub(crate) fn is_sensitive_at(shape: &Shape, key: &str) -> bool {
+    let segments: Vec<&str> = key.split('.').collect();
+    sensitive_segments(shape, &segments)
+}
+
+fn sensitive_segments(shape: &Shape, segments: &[&str]) -> bool {
+    if shape.is_sensitive() {
+        return true;
+    }
+    let (Some((head, rest)), Some(node)) = (segments.split_first(), Table::of(shape)) else {
+        return false;
+    };
+    node.child(head, None)
+        .into_iter()
+        .any(|child| sensitive_segments(child, rest))
+}
+
+/// Mask every secret a parsed file assigns in its `source` text.
+///
+/// `table` is the file's document (after key normalization) under the
+/// schema `root`, `spans` its span index. Returns `None` when the file
+/// holds no secret; otherwise the masked text and the span index remapped
+/// onto it — spans inside a masked value collapse onto its
+/// [`REDACTED`] placeholder. Build origins from the pair so every
+/// snippet, caret, and line number lines up with what renderers show.
+pub(crate) fn mask_source(
+    root: DocumentRoot<'_>,
+    table: &Map,
+    spans: &BTreeMap<ConfigPath, SpanEntry>,
+    source: &str,
+) -> Option<(Arc<str>, BTreeMap<ConfigPath, SpanEntry>)> {
+    mask_source_documents(root, &[(ConfigPath::new(), table)], spans, source)
+}
+
+/// [`mask_source`] for a file holding several documents under the same
+/// root, each at its path in `spans` — a base document and its profile
+/// sections. One pass masks them all, so every origin built from the
+/// file shares one masked text.
+pub(crate) fn mask_source_documents(
+    root: DocumentRoot<'_>,
+    documents: &[(ConfigPath, &Map)],
+    spans: &BTreeMap<ConfigPath, SpanEntry>,
+    source: &str,
+) -> Option<(Arc<str>, BTreeMap<ConfigPath, SpanEntry>)> {
+    let mut secrets = Vec::new();
+    for (at, table) in documents {
+        collect_in(Table::root(root), table, at, spans, &mut secrets);
+    }
+    secrets.retain(|span: &Span| source.get(span.start..span.end).is_some());
+    if secrets.is_empty() {
+        return None;
+    }
+    secrets.sort_by_key(|span| span.start);
+    // Drop spans nested in an earlier one (an array of secrets inside a
+    // secret section).
+    let mut masked: Vec<Span> = Vec::with_capacity(secrets.len());
+    for span in secrets {
+        if masked.last().is_none_or(|last| span.start >= last.end) {
+            masked.push(span);
+        }
+    }
+
+    let mut text = String::with_capacity(source.len());
+    let mut cursor = 0;
+    for span in &masked {
+        text.push_str(&source[cursor..span.start]);
+        text.push_str(REDACTED);
+        cursor = span.end;
+    }
+    text.push_str(&source[cursor..]);
+
+    let remap = |span: Span| -> Span {
+        let mut shift: isize = 0;
+        for secret in &masked {
+            let start = secret.start.saturating_add_signed(shift);
+            if span.start >= secret.start && span.end <= secret.end {
+                return Span {
+                    start,
+                    end: start + REDACTED.len(),
+                };
+            }
+            if secret.start >= span.end {
+                break;
+            }
+            shift += REDACTED.len() as isize - (secret.end - secret.start) as isize;
+        }
+        Span {
+            start: span.start.saturating_add_signed(shift),
+            end: span.end.saturating_add_signed(shift),
+        }
+    };
+    let spans = spans
+        .iter()
+        .map(|(path, entry)| {
+            let entry = SpanEntry {
+                key: entry.key.map(remap),
+                value: remap(entry.value),
+            };
+            (path.clone(), entry)
+        })
+        .collect();
+    Some((Arc::from(text), spans))
+}
+
+fn collect(
+    shape: &Shape,
+    value: &Value,
+    path: &ConfigPath,
+    spans: &BTreeMap<ConfigPath, SpanEntry>,
+    out: &mut Vec<Span>,
+) {
+    if shape.is_sensitive() {
+        out.extend(spans.get(path).map(|entry| entry.value));
+        return;
+    }
+    match (shape, value) {
+        (_, Value::Map(table)) => {
+            if let Some(node) = Table::of(shape) {
+                collect_in(node, table, path, spans, out);
+            }
+        }
+        (Shape::Array(array), Value::Array(items)) => {
+            for (i, item) in items.iter().enumerate() {
+                collect(&array.item, item, &path.clone().index(i), spans, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn collect_in(
+    node: Table<'_>,
+    table: &Map,
+    path: &ConfigPath,
+    spans: &BTreeMap<ConfigPath, SpanEntry>,
+    out: &mut Vec<Span>,
+) {
+    for (key, child) in table {
+        let child_path = path.clone().key(key);
+        for shape in node.child(key, Some(table)) {
+            collect(shape, child, &child_path, spans, out);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::Field;
+
+    fn shape() -> Shape {
+        Schema::object("App")
+            .field("host", Field::string())
+            .field("api_token", Field::string().sensitive())
+            .field("keys", Field::array_of_type(Field::string()).sensitive())
+            .nested(
+                "db",
+                Schema::object("Db")
+                    .field("url", Field::string())
+                    .field("password", Field::string().sensitive()),
+            )
+            .build()
+            .into()
+    }
+
+    fn toml(src: &str) -> Value {
+        crate::format::builtin_adapter("toml")
+            .unwrap()
+            .parse(src)
+            .unwrap()
+            .value
+    }
+
+    fn redacted() -> Value {
+        Value::String(REDACTED.into())
+    }
+
+    #[test]
+    fn redacts_sensitive_leaves_and_keeps_the_rest() {
+        let mut value = toml(
+            "host = \"h\"\napi-token = \"t\"\nkeys = [\"a\", \"b\"]\n[db]\nurl = \"u\"\npassword = \"p\"\n",
+        );
+        redact(&shape(), &mut value);
+        let table = value.as_map().unwrap();
+        assert_eq!(table["host"], Value::String("h".into()));
+        assert_eq!(table["api-token"], redacted());
+        assert_eq!(table["keys"], redacted(), "arrays of secrets redact whole");
+        let db = table["db"].as_map().unwrap();
+        assert_eq!(db["url"], Value::String("u".into()));
+        assert_eq!(db["password"], redacted());
+    }
+
+    #[test]
+    fn redact_at_follows_dotted_keys() {
+        let shape = shape();
+        let mut leaf = Value::String("p".into());
+        redact_at(&shape, "db.password", &mut leaf);
+        assert_eq!(leaf, redacted());
+
+        let mut section = Value::Map(Map::from([
+            ("url".to_string(), Value::String("u".into())),
+            ("password".to_string(), Value::String("p".into())),
+        ]));
+        redact_at(&shape, "db", &mut section);
+        assert_eq!(section.as_map().unwrap()["password"], redacted());
+        assert_eq!(section.as_map().unwrap()["url"], Value::String("u".into()));
+
+        assert!(is_sensitive_at(&shape, "api_token"));
+        assert!(!is_sensitive_at(&shape, "host"));
+        assert!(!is_sensitive_at(&shape, "nope"));
+    }
+
+    #[test]
+    fn tagged_unions_redact_by_selected_variant() {
+        let shape = Shape::from(
+            Shape::tagged("Auth", "kind")
+                .variant(
+                    "token",
+                    Schema::object("Token")
+                        .field("secret", Field::string().sensitive())
+                        .build(),
+                )
+                .variant(
+                    "basic",
+                    Schema::object("Basic")
+                        .field("user", Field::string())
+                        .build(),
+                )
+                .build(),
+        );
+        let mut value = toml("kind = \"token\"\nsecret = \"s\"\n");
+        redact(&shape, &mut value);
+        assert_eq!(value.as_map().unwrap()["secret"], redacted());
+        assert!(is_sensitive_at(&shape, "secret"));
+    }
+
+    #[test]
+    fn mask_source_replaces_secrets_and_remaps_spans() {
+        let source = "api_token = \"hunter2\"\nhost = \"h\"\n[db]\npassword = \"p\"\n";
+        let parsed = crate::format::builtin_adapter("toml")
+            .unwrap()
+            .parse(source)
+            .unwrap();
+        let shape = shape();
+        let Shape::Object(schema) = &shape else {
+            unreachable!()
+        };
+        let (text, spans) = mask_source(
+            DocumentRoot::Object(schema),
+            parsed.value.as_map().unwrap(),
+            &parsed.spans,
+            source,
+        )
+        .unwrap();
+        assert_eq!(
+            &*text,
+            "api_token = <redacted>\nhost = \"h\"\n[db]\npassword = <redacted>\n"
+        );
+        let at = |path: ConfigPath| {
+            let span = spans[&path].value;
+            &text[span.start..span.end]
+        };
+        assert_eq!(at(ConfigPath::new().key("api_token")), REDACTED);
+        assert_eq!(at(ConfigPath::new().key("host")), "\"h\"");
+        assert_eq!(at(ConfigPath::new().key("db").key("password")), REDACTED);
+
+        let plain = "host = \"h\"\n";
+        let parsed = crate::format::builtin_adapter("toml")
+            .unwrap()
+            .parse(plain)
+            .unwrap();
+        assert!(
+            mask_source(
+                DocumentRoot::Object(schema),
+                parsed.value.as_map().unwrap(),
+                &parsed.spans,
+                plain,
+            )
+            .is_none()
+        );
+    }
+}
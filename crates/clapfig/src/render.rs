@@ -1,8 +1,10 @@
-//! Rendering [`ClapfigError`] for human consumption.
+//! Rendering [`ClapfigError`] and [`Warning`] for human consumption.
 //!
 //! [`ClapfigError`] is the *data layer*: structured facts about what went
-//! wrong, with no opinions on how to show them. This module is the
-//! *presentation layer*.
+//! wrong, with no opinions on how to show them. [`Warning`]s from
+//! [`load_with_diagnostics`](crate::Builder::load_with_diagnostics) are
+//! the same for loads that succeeded. This module is the *presentation
+//! layer*.
 //!
 //! - [`render_plain`] — ANSI-free, deterministic text. Safe for logs, CI
 //!   output, or anywhere color would be noise. Always available.
@@ -10,7 +12,8 @@
 //!   aligned gutters, built on [`miette`]. Behind the `rich-errors` Cargo
 //!   feature.
 //!
-//! Both functions take `&ClapfigError` and return a `String` — they never
+//! Both functions take a `&ClapfigError` or a `&Warning` (anything that
+//! converts into a [`Diagnostic`]) and return a `String` — they never
 //! touch stdout/stderr themselves. That keeps the caller in charge of
 //! where the output lands (terminal, log file, TUI pane, etc.).
 //!
@@ -32,15 +35,43 @@
 //! }
 //! ```
 
+use crate::diagnostics::Warning;
 use crate::error::ClapfigError;
 
-/// Render an error as plain, ANSI-free text.
+/// Something the renderers can draw: an error, or a warning from a load
+/// that succeeded. Both `&ClapfigError` and `&Warning` convert into it,
+/// so callers pass either directly.
+#[derive(Debug, Clone, Copy)]
+pub enum Diagnostic<'a> {
+    /// A failed operation.
+    Error(&'a ClapfigError),
+    /// A non-fatal finding.
+    Warning(&'a Warning),
+}
+
+impl<'a> From<&'a ClapfigError> for Diagnostic<'a> {
+    fn from(err: &'a ClapfigError) -> Self {
+        Diagnostic::Error(err)
+    }
+}
+
+impl<'a> From<&'a Warning> for Diagnostic<'a> {
+    fn from(warning: &'a Warning) -> Self {
+        Diagnostic::Warning(warning)
+    }
+}
+
+/// Render an error or warning as plain, ANSI-free text.
 ///
-/// Produces a multi-line, human-readable message. For unknown-key errors
-/// and parse errors that retained their source text, a short snippet
-/// showing the offending line is included. No colors, no Unicode drawing
-/// characters — safe for any output target.
-pub fn render_plain(err: &ClapfigError) -> String {
+/// Produces a multi-line, human-readable message. For unknown-key errors,
+/// parse errors, and values (invalid, or warned about) whose file text was
+/// retained, a short snippet showing the offending line is included. No
+/// colors, no Unicode drawing characters — safe for any output target.
+pub fn render_plain<'a>(diagnostic: impl Into<Diagnostic<'a>>) -> String {
+    let err = match diagnostic.into() {
+        Diagnostic::Error(err) => err,
+        Diagnostic::Warning(warning) => return render_warning_plain(warning),
+    };
     match err {
         ClapfigError::UnknownKeys(infos) => render_unknown_keys_plain(infos),
         ClapfigError::ParseError {
@@ -49,13 +80,41 @@ pub fn render_plain(err: &ClapfigError) -> String {
             source_text,
         } => render_parse_error_plain(path, source.as_ref(), source_text.as_deref()),
         ClapfigError::InvalidValue { origin, .. } => render_invalid_value_plain(err, origin),
+        ClapfigError::DeniedWarnings(warnings) => render_denied_warnings_plain(warnings),
         other => other.to_string(),
     }
 }
 
+fn render_warning_plain(warning: &Warning) -> String {
+    let mut out = format!("warning: {warning}");
+    if let Some(origin) = warning.origin() {
+        push_value_snippet(&mut out, origin);
+    }
+    out
+}
+
+fn render_denied_warnings_plain(warnings: &[Warning]) -> String {
+    let header = if warnings.len() == 1 {
+        String::from("error: warning denied by policy")
+    } else {
+        format!("error: {} warnings denied by policy", warnings.len())
+    };
+    std::iter::once(header)
+        .chain(warnings.iter().map(render_warning_plain))
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
 fn render_invalid_value_plain(err: &ClapfigError, origin: &crate::error::OriginFacts) -> String {
-    use std::fmt::Write;
     let mut out = err.to_string();
+    push_value_snippet(&mut out, origin);
+    out
+}
+
+/// Append the line a file origin's value span sits on, with carets under
+/// the value. Non-file origins and origins without a span add nothing.
+fn push_value_snippet(out: &mut String, origin: &crate::error::OriginFacts) {
+    use std::fmt::Write;
     if origin.input_type == Some(crate::types::InputType::File)
         && let (Some(span), Some(src)) = (origin.span, origin.source.as_deref())
     {
@@ -71,7 +130,6 @@ fn render_invalid_value_plain(err: &ClapfigError, origin: &crate::error::OriginF
             let _ = write!(out, "\n{pad}{carets}");
         }
     }
-    out
 }
 
 fn render_unknown_keys_plain(infos: &[crate::error::UnknownKeyInfo]) -> String {
@@ -273,7 +331,8 @@ fn unknown_key_snippet(info: &crate::error::UnknownKeyInfo) -> UnknownKeySnippet
     }
 }
 
-/// Render an error with colors, source snippets, and aligned gutters.
+/// Render an error or warning with colors, source snippets, and aligned
+/// gutters.
 ///
 /// Uses [`miette`](https://docs.rs/miette)'s graphical report handler.
 /// Output includes ANSI color codes and Unicode box-drawing characters;
@@ -282,10 +341,13 @@ fn unknown_key_snippet(info: &crate::error::UnknownKeyInfo) -> UnknownKeySnippet
 ///
 /// Requires the `rich-errors` Cargo feature.
 #[cfg(feature = "rich-errors")]
-pub fn render_rich(err: &ClapfigError) -> String {
+pub fn render_rich<'a>(diagnostic: impl Into<Diagnostic<'a>>) -> String {
     use miette::{GraphicalReportHandler, MietteDiagnostic, NamedSource};
 
-    let diagnostic = build_diagnostic(err);
+    let diagnostic = match diagnostic.into() {
+        Diagnostic::Error(err) => build_diagnostic(err),
+        Diagnostic::Warning(warning) => build_warning_diagnostic(warning),
+    };
     let mut out = String::new();
     let handler = GraphicalReportHandler::new();
 
@@ -308,9 +370,9 @@ pub fn render_rich(err: &ClapfigError) -> String {
                 .with_source_code(NamedSource::new(source_name, source_text));
             let _ = handler.render_report(&mut out, report.as_ref());
         }
-        RichDiagnostic::Plain(s) => {
+        RichDiagnostic::Plain(s, severity) => {
             let mut diag = MietteDiagnostic::new(s);
-            diag.severity = Some(miette::Severity::Error);
+            diag.severity = Some(severity);
             let report = miette::Report::new(diag);
             let _ = handler.render_report(&mut out, report.as_ref());
         }
@@ -329,7 +391,7 @@ enum RichDiagnostic {
         severity: miette::Severity,
         help: Option<String>,
     },
-    Plain(String),
+    Plain(String, miette::Severity),
 }
 
 #[cfg(feature = "rich-errors")]
@@ -339,7 +401,7 @@ fn build_diagnostic(err: &ClapfigError) -> RichDiagnostic {
     match err {
         ClapfigError::UnknownKeys(infos) => {
             let Some(source) = infos.iter().find_map(|i| i.source.as_deref()) else {
-                return RichDiagnostic::Plain(render_plain(err));
+                return RichDiagnostic::Plain(render_plain(err), miette::Severity::Error);
             };
             let source_name = infos[0].path.display().to_string();
             let source_text: String = source.to_string();
@@ -402,11 +464,11 @@ fn build_diagnostic(err: &ClapfigError) -> RichDiagnostic {
             source_text,
         } => {
             let Some(src) = source_text.as_deref() else {
-                return RichDiagnostic::Plain(render_plain(err));
+                return RichDiagnostic::Plain(render_plain(err), miette::Severity::Error);
             };
             let span = match source.parse_span() {
                 Some(s) => s,
-                None => return RichDiagnostic::Plain(render_plain(err)),
+                None => return RichDiagnostic::Plain(render_plain(err), miette::Severity::Error),
             };
             let labels = vec![LabeledSpan::at(span.start..span.end, source.detail())];
             RichDiagnostic::WithSource {
@@ -424,10 +486,10 @@ fn build_diagnostic(err: &ClapfigError) -> RichDiagnostic {
             origin,
         } => {
             let Some(src) = origin.source.as_deref() else {
-                return RichDiagnostic::Plain(err.to_string());
+                return RichDiagnostic::Plain(err.to_string(), miette::Severity::Error);
             };
             let Some(span) = origin.span else {
-                return RichDiagnostic::Plain(err.to_string());
+                return RichDiagnostic::Plain(err.to_string(), miette::Severity::Error);
             };
             let source_name = origin
                 .file
@@ -447,7 +509,34 @@ fn build_diagnostic(err: &ClapfigError) -> RichDiagnostic {
                 help: None,
             }
         }
-        other => RichDiagnostic::Plain(other.to_string()),
+        other => RichDiagnostic::Plain(other.to_string(), miette::Severity::Error),
+    }
+}
+
+#[cfg(feature = "rich-errors")]
+fn build_warning_diagnostic(warning: &Warning) -> RichDiagnostic {
+    use miette::LabeledSpan;
+
+    let rendered = warning.to_string();
+    let message = rendered.lines().next().unwrap_or_default().to_string();
+    let located = warning.origin().and_then(|origin| {
+        let file = origin.file.as_ref()?;
+        Some((file, origin.span?, origin.source.as_deref()?))
+    });
+    let Some((file, span, src)) = located else {
+        return RichDiagnostic::Plain(rendered, miette::Severity::Warning);
+    };
+    let label = match warning {
+        Warning::EnvShadowsFile { env_var, .. } => format!("overridden by {env_var}"),
+        _ => "set here".to_string(),
+    };
+    RichDiagnostic::WithSource {
+        message,
+        labels: vec![LabeledSpan::at(span.start..span.end, label)],
+        source_name: file.display().to_string(),
+        source_text: src.to_string(),
+        severity: miette::Severity::Warning,
+        help: None,
     }
 }
 
@@ -753,6 +842,64 @@ mod tests {
         );
     }
 
+    fn deprecated_warning() -> Warning {
+        use crate::error::OriginFacts;
+        use crate::format::Span;
+        use crate::types::InputType;
+        Warning::DeprecatedKey {
+            key: "verbose".into(),
+            note: "use `log_level`".into(),
+            origin: Box::new(OriginFacts {
+                file: Some("app.toml".into()),
+                span: Some(Span { start: 19, end: 23 }),
+                source: Some(Arc::from("port = 1\nverbose = true\n")),
+                input_type: Some(InputType::File),
+                ..OriginFacts::default()
+            }),
+        }
+    }
+
+    #[test]
+    fn plain_warning_carets_the_value_it_points_at() {
+        let out = render_plain(&deprecated_warning());
+        assert!(
+            out.starts_with("warning: Deprecated key 'verbose': use `log_level`\n  --> app.toml:2"),
+            "{out}"
+        );
+        assert!(out.contains("   2 | verbose = true"), "{out}");
+        let caret = out.lines().find(|l| l.contains('^')).expect("{out}");
+        assert_eq!(caret.trim_start(), "^^^^", "{out}");
+    }
+
+    #[test]
+    fn plain_denied_warnings_render_each_warning() {
+        let err = ClapfigError::DeniedWarnings(vec![
+            deprecated_warning(),
+            Warning::MixedFormats {
+                files: vec!["/a/app.toml".into(), "/b/app.yaml".into()],
+            },
+        ]);
+        let out = render_plain(&err);
+        assert!(
+            out.starts_with("error: 2 warnings denied by policy\n\nwarning: Deprecated key"),
+            "{out}"
+        );
+        assert!(out.contains("verbose = true"), "{out}");
+        assert!(
+            out.ends_with("warning: Config files in more than one format: /a/app.toml, /b/app.yaml — keep one format"),
+            "{out}"
+        );
+    }
+
+    #[cfg(feature = "rich-errors")]
+    #[test]
+    fn rich_warning_shows_source_and_note() {
+        let out = render_rich(&deprecated_warning());
+        assert!(out.contains("use `log_level`"), "{out}");
+        assert!(out.contains("app.toml"), "{out}");
+        assert!(out.contains("verbose = true"), "{out}");
+    }
+
     #[cfg(feature = "rich-errors")]
     #[test]
     fn rich_contains_key_and_path() {
@@ -20,16 +20,22 @@
 //! `Clapfig::typed::<C>()` — thread in the same [`Schema`]; the typed path
 //! deserializes the returned [`Map`] afterwards.
 
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
+use crate::diagnostics::{FileMode, Warning, WarningKind};
 use crate::env;
-use crate::error::{ClapfigError, DiscoveryRecord};
-use crate::format::{self, ConfigPath, FormatRegistry};
+use crate::error::{ClapfigError, DiscoveryRecord, OriginFacts};
+use crate::file_ref::ReadFile;
+use crate::format::{ConfigPath, FormatAdapter, FormatRegistry};
+use crate::interpolate::LookupEnv;
 use crate::merge::deep_merge;
+use crate::migrate::Migration;
 use crate::normalize::{normalize_key, normalize_table_and_spans};
-use crate::origin::{Origin, OriginMap, origin_map_from_env, origin_map_from_file};
+use crate::origin::{Origin, OriginMap, lookup_mut, origin_map_from_env, origin_map_from_file};
 use crate::overrides;
+use crate::paths::StatPath;
 use crate::runtime::DocumentRoot;
 use crate::schema_walk;
 use crate::strict::{CollectedUnknown, StrictnessOverrides, UnknownKeyHook};
@@ -59,10 +65,54 @@ pub(crate) struct ResolveInput<'a> {
     /// [`ClapfigError::MissingRequired`]. Injectable so this walk stays
     /// I/O-free; production discovery fills it from the real search.
     pub discovery: DiscoveryRecord,
+    /// The include directive's key, when includes are enabled. Discovery
+    /// has already spliced the included files into
+    /// [`files`](Self::files); each document drops the key before
+    /// validation.
+    pub include_key: Option<String>,
+    /// The key profile tables live under, when profiles are enabled.
+    /// Every document drops it before validation; see [`crate::profile`].
+    pub profile_key: Option<String>,
+    /// The active profile, whose section in each document is layered
+    /// just above that document. Discovery has already added the
+    /// profile's sibling files to [`files`](Self::files).
+    pub profile: Option<String>,
     /// Raw environment variable pairs (pass `std::env::vars().collect()` or synthetic data).
     pub env_vars: Vec<(String, String)>,
     /// Env var prefix (e.g. `"MYAPP"`). `None` means env disabled.
     pub env_prefix: Option<String>,
+    /// Separator between the items of an env list value on an array
+    /// field (`MYAPP__HOSTS=a,b,c`).
+    pub env_list_separator: String,
+    /// Whether a Docker-style `…_FILE` variable sets its leaf to the
+    /// contents of the file it names (`MYAPP__DB__PASSWORD_FILE`).
+    pub env_file_secrets: bool,
+    /// Reads the files `_FILE` variables and
+    /// [`from_file`](crate::runtime::Leaf::from_file) leaves name.
+    /// Injectable so tests need no real files.
+    pub read_file: ReadFile,
+    /// Reads a loaded file's permission bits, to warn when one holding
+    /// secrets is world-readable. Injectable so tests need no real files.
+    pub file_mode: FileMode,
+    /// Inspects what a [path](crate::runtime::LeafType::Path) leaf names,
+    /// for its existence checks. Injectable so tests need no real files.
+    pub stat_path: StatPath,
+    /// Whether to expand `${key}` / `${env:NAME}` references in merged
+    /// string values ([`crate::interpolate`]).
+    pub interpolate: bool,
+    /// Reads the process variables `${env:NAME}` names. Injectable so
+    /// tests need not touch the real environment.
+    pub lookup_env: LookupEnv,
+    /// The string that unsets a key when a layer supplies it, when the
+    /// unset marker is enabled. See [`crate::merge`].
+    pub unset_marker: Option<String>,
+    /// Migrations applied, in order, to every parsed document before
+    /// validation. See [`crate::migrate`].
+    pub migrations: Vec<Migration>,
+    /// The reserved key holding each document's schema version, when
+    /// versioned migrations are enabled. Every document drops it before
+    /// validation.
+    pub schema_version_key: Option<String>,
     /// URL query parameter overrides as `(dotted_key, value)` pairs.
     #[cfg(feature = "url")]
     pub url_overrides: Vec<(String, Value)>,
@@ -87,6 +137,38 @@ pub(crate) struct ResolveInput<'a> {
     /// Layer merge order, from lowest to highest priority.
     /// `None` uses the default: `[Files, Env, Url, Cli]`.
     pub layer_order: Option<Vec<Layer>>,
+    /// Warning kinds that fail the resolve as
+    /// [`ClapfigError::DeniedWarnings`] instead of being returned.
+    pub deny_warnings: Vec<WarningKind>,
+}
+
+/// The adapter that parses the config file at `path`.
+///
+/// Extensionless (rc-style) names fall back to the preferred adapter; an
+/// extension no enabled adapter claims is a hard
+/// [`ClapfigError::UnknownFormat`] — the documented explicit-path rule,
+/// never a silent parse under another format.
+pub(crate) fn file_adapter<'r>(
+    registry: &'r FormatRegistry,
+    path: &Path,
+) -> Result<&'r dyn FormatAdapter, ClapfigError> {
+    match path.extension() {
+        None => registry
+            .preferred()
+            .ok_or_else(|| ClapfigError::UnknownFormat {
+                name: path.display().to_string(),
+                available: registry.names(),
+            }),
+        Some(ext) => {
+            let ext = ext.to_string_lossy();
+            registry
+                .by_extension(&ext)
+                .ok_or_else(|| ClapfigError::UnknownFormat {
+                    name: ext.into_owned(),
+                    available: registry.names(),
+                })
+        }
+    }
 }
 
 /// Rewrite the dotted-key half of each override pair, applying the same
@@ -110,6 +192,49 @@ fn normalize_override_keys(
         .collect()
 }
 
+/// Warn once for each deprecated field `table` (one input) sets, naming
+/// the input through `origins`.
+fn warn_deprecated(
+    table: &Map,
+    origins: &OriginMap,
+    root: DocumentRoot<'_>,
+    warnings: &mut Vec<Warning>,
+) {
+    for (key, note) in crate::alias::deprecated_keys(table, root) {
+        let origin = crate::origin::lookup(origins, &key);
+        let label = origin.map(Origin::label).unwrap_or_default();
+        let facts = origin.map(Origin::to_facts).unwrap_or_default();
+        let line = facts.line_col().map(|(line, _)| line);
+        crate::trace::deprecated_key(&key, note, &label, line);
+        warnings.push(Warning::DeprecatedKey {
+            key: key.to_string(),
+            note: note.to_owned(),
+            origin: Box::new(facts),
+        });
+    }
+}
+
+/// [`Warning::EnvShadowsFile`] for each environment variable whose value
+/// replaces a scalar the files layer set.
+fn warn_env_shadows(
+    winners: &env::EnvWinners,
+    files_origins: &OriginMap,
+    warnings: &mut Vec<Warning>,
+) {
+    for (path, var) in winners {
+        let Some(node) = crate::origin::lookup_node(files_origins, path) else {
+            continue;
+        };
+        if matches!(node.children, crate::origin::OriginChildren::None) {
+            warnings.push(Warning::EnvShadowsFile {
+                key: path.to_string(),
+                env_var: var.clone(),
+                file: Box::new(node.origin.to_facts()),
+            });
+        }
+    }
+}
+
 /// Returns the default layer order: `[Files, Env, Url, Cli]`.
 pub(crate) fn default_layer_order() -> Vec<Layer> {
     vec![
@@ -130,9 +255,80 @@ pub(crate) fn default_layer_order() -> Vec<Layer> {
 /// [`UnknownKeyDecision::Collect`](crate::UnknownKeyDecision::Collect);
 /// callers that don't need the collected list (the plain `load()`
 /// surface) simply discard it via `let (out, _) = resolve(...)?;`.
+/// Production callers go through [`resolve_with_diagnostics`].
+#[cfg(test)]
 pub(crate) fn resolve(
     input: ResolveInput<'_>,
 ) -> Result<(Map, Vec<CollectedUnknown>), ClapfigError> {
+    resolve_with_origins(input).map(|(table, unknowns, _)| (table, unknowns))
+}
+
+/// [`resolve`], also returning the winner-only origin tree built in
+/// lockstep with the merge (ADR-0004) — the payload behind the public
+/// [`Provenance`](crate::provenance::Provenance) view.
+#[cfg(test)]
+pub(crate) fn resolve_with_origins(
+    input: ResolveInput<'_>,
+) -> Result<(Map, Vec<CollectedUnknown>, OriginMap), ClapfigError> {
+    resolve_with_diagnostics(input).map(|(table, unknowns, origins, _)| (table, unknowns, origins))
+}
+
+/// [`resolve_with_origins`], also returning the [`Warning`]s the
+/// resolve raised that the deny policy let through.
+pub(crate) fn resolve_with_diagnostics(
+    input: ResolveInput<'_>,
+) -> Result<(Map, Vec<CollectedUnknown>, OriginMap, Vec<Warning>), ClapfigError> {
+    let mut warnings = Vec::new();
+    let (table, unknowns, origins) =
+        resolve_recording(input, None, &mut Vec::new(), &mut warnings)?;
+    Ok((table, unknowns, origins, warnings))
+}
+
+/// One input's value at an explained key and that input's origin.
+pub(crate) type RecordedCandidate = (OriginFacts, Value);
+
+/// [`resolve_with_origins`], also recording every input that supplied a
+/// value at `key` — the schema default first, then each layer in merge
+/// order (files in precedence order). The payload behind
+/// [`Explanation`](crate::provenance::Explanation): the merge itself stays
+/// winner-only, so the losers are captured from each layer's table just
+/// before it merges.
+pub(crate) fn resolve_explaining(
+    input: ResolveInput<'_>,
+    key: &ConfigPath,
+) -> Result<(Map, OriginMap, Vec<RecordedCandidate>), ClapfigError> {
+    let mut candidates = Vec::new();
+    let mut defaults = Map::new();
+    let mut default_origins = OriginMap::new();
+    schema_walk::fill_defaults_into_root(&mut defaults, &mut default_origins, input.schema);
+    record_candidate(&defaults, &default_origins, key, &mut candidates);
+    let (table, _, origins) =
+        resolve_recording(input, Some(key), &mut candidates, &mut Vec::new())?;
+    Ok((table, origins, candidates))
+}
+
+/// Push the value `table` holds at `key` (and its origin) onto
+/// `candidates`, if any.
+fn record_candidate(
+    table: &Map,
+    origins: &OriginMap,
+    key: &ConfigPath,
+    candidates: &mut Vec<RecordedCandidate>,
+) {
+    if let Some(value) = crate::validate::lookup_value(table, key) {
+        let origin = crate::origin::lookup(origins, key)
+            .map(Origin::to_facts)
+            .unwrap_or_default();
+        candidates.push((origin, value.clone()));
+    }
+}
+
+fn resolve_recording(
+    input: ResolveInput<'_>,
+    explain: Option<&ConfigPath>,
+    candidates: &mut Vec<RecordedCandidate>,
+    warnings: &mut Vec<Warning>,
+) -> Result<(Map, Vec<CollectedUnknown>, OriginMap), ClapfigError> {
     // Build each layer independently, then merge in the configured order.
 
     let validate_ctx = ValidateContext {
@@ -161,8 +357,15 @@ pub(crate) fn resolve(
     // when `Layer::Files` was omitted).
     let default_order = default_layer_order();
     let order = input.layer_order.as_deref().unwrap_or(&default_order);
+    let unset = input.unset_marker.as_deref();
 
     crate::trace::discovery_complete(&input.discovery);
+    if order.contains(&Layer::Files) {
+        warnings.extend(crate::diagnostics::mixed_formats(
+            &input.files,
+            input.registry,
+        ));
+    }
 
     // Files layer: parse → (optionally) normalize → validate → merge.
     // Validation runs against the parsed Table — never the raw text — so
@@ -170,32 +373,24 @@ pub(crate) fn resolve(
     // Origin trees are built after normalize so lookup keys match the
     // value tree; span bytes still point at the user's original spelling.
     let mut collected_unknowns: Vec<CollectedUnknown> = Vec::new();
+    let mut file_candidates = Vec::new();
+    // Sections are found after normalize, so the section key and the
+    // active name take the same spelling rule as the keys users write.
+    let spell = |name: &String| {
+        if input.normalize_keys {
+            normalize_key(name)
+        } else {
+            name.clone()
+        }
+    };
+    let profile_key = input.profile_key.as_ref().map(spell);
+    let version_key = input.schema_version_key.as_ref().map(spell);
+    let active_profile = input.profile.as_ref().map(spell);
     let (files_table, files_origins) = if order.contains(&Layer::Files) {
         let mut t = Map::new();
         let mut origins = OriginMap::new();
         for (path, content) in &input.files {
-            // Extensionless (rc-style) names fall back to the preferred
-            // adapter; an extension no enabled adapter claims is a hard
-            // UnknownFormat — the documented explicit-path rule, never a
-            // silent parse under another format.
-            let adapter = match path.extension() {
-                None => input
-                    .registry
-                    .preferred()
-                    .ok_or_else(|| ClapfigError::UnknownFormat {
-                        name: path.display().to_string(),
-                        available: format::builtin_names(),
-                    })?,
-                Some(ext) => {
-                    let ext = ext.to_string_lossy();
-                    input.registry.by_extension(&ext).ok_or_else(|| {
-                        ClapfigError::UnknownFormat {
-                            name: ext.into_owned(),
-                            available: format::builtin_names(),
-                        }
-                    })?
-                }
-            };
+            let adapter = file_adapter(input.registry, path)?;
             let source: Arc<str> = Arc::from(content.as_str());
             let parsed = adapter
                 .parse(content)
@@ -221,26 +416,152 @@ pub(crate) fn resolve(
                     });
                 }
             };
+            // The include directive was expanded at discovery; it is not
+            // config.
+            if let Some(key) = &input.include_key {
+                table.remove(key);
+            }
             let mut spans = parsed.spans;
             if input.normalize_keys {
                 normalize_table_and_spans(&mut table, &mut spans)
                     .map_err(|c| c.into_error(path))?;
             }
+            let version = match &version_key {
+                Some(key) => crate::migrate::take_version(&mut table, key, &spans, path, &source)?,
+                None => 0,
+            };
+            // Profile sections leave the document after normalize, so
+            // their names and keys follow the same spelling rule.
+            let sections = match &profile_key {
+                Some(key) => crate::profile::take_sections(&mut table, &spans, key, path, &source)?,
+                None => Vec::new(),
+            };
+            // Migrations run before string scalars are typed, so a moved
+            // value is typed at its new key.
+            let plan = crate::migrate::Plan {
+                migrations: &input.migrations,
+                version,
+                normalize_keys: input.normalize_keys,
+            };
+            let mut migrated = crate::migrate::apply_loaded(
+                &plan,
+                &mut table,
+                &mut spans,
+                &ConfigPath::new(),
+                path,
+                &source,
+            )?;
+            crate::alias::canonicalize_document(
+                &mut table,
+                &mut spans,
+                &ConfigPath::new(),
+                input.schema,
+                input.normalize_keys,
+                path,
+            )?;
+            let mut sections = sections
+                .into_iter()
+                .map(|mut section| {
+                    for applied in crate::migrate::apply_loaded(
+                        &plan,
+                        &mut section.table,
+                        &mut spans,
+                        &section.at,
+                        path,
+                        &source,
+                    )? {
+                        // One warning per file: a migration the root and
+                        // a section both needed is named once.
+                        if !migrated.contains(&applied) {
+                            migrated.push(applied);
+                        }
+                    }
+                    crate::alias::canonicalize_document(
+                        &mut section.table,
+                        &mut spans,
+                        &section.at,
+                        input.schema,
+                        input.normalize_keys,
+                        path,
+                    )?;
+                    if adapter.scalars_are_strings() {
+                        schema_walk::type_string_scalars_root(&mut section.table, input.schema);
+                    }
+                    Ok(section)
+                })
+                .collect::<Result<Vec<_>, ClapfigError>>()?;
+            if !migrated.is_empty() {
+                warnings.push(Warning::UnmigratedFile {
+                    path: path.clone(),
+                    migrations: migrated,
+                });
+            }
+            if adapter.scalars_are_strings() {
+                schema_walk::type_string_scalars_root(&mut table, input.schema);
+            }
+            // Every origin from this file shares its text; mask secrets
+            // once so no snippet or debug print can quote one.
+            let documents: Vec<(ConfigPath, &Map)> = std::iter::once((ConfigPath::new(), &table))
+                .chain(sections.iter().map(|s| (s.at.clone(), &s.table)))
+                .collect();
+            let masked =
+                crate::redact::mask_source_documents(input.schema, &documents, &spans, content);
+            if masked.is_some() {
+                warnings.extend(crate::diagnostics::world_readable(path, input.file_mode));
+            }
+            let (source, spans) = masked.unwrap_or((source, spans));
             if cascade_active {
                 let mut per_file = crate::validate::validate_unknown_root(
                     &table,
                     input.schema,
                     &UnknownKeySource::File {
                         path,
-                        source: content,
+                        source: &source,
                         spans: &spans,
                     },
                     &validate_ctx,
                 )?;
                 collected_unknowns.append(&mut per_file);
             }
+            // Every section is held to the schema, active or not, so a
+            // typo in `[profile.prod]` fails on a dev machine too.
+            let mut active_section = None;
+            for section in sections.drain(..) {
+                let section_spans = crate::profile::rooted_at(&spans, &section.at);
+                if cascade_active {
+                    let mut per_section = crate::validate::validate_unknown_root(
+                        &section.table,
+                        input.schema,
+                        &UnknownKeySource::File {
+                            path,
+                            source: &source,
+                            spans: &section_spans,
+                        },
+                        &validate_ctx,
+                    )?;
+                    collected_unknowns.append(&mut per_section);
+                }
+                if active_profile.as_deref() == Some(section.name.as_str()) {
+                    active_section = Some((section.name, section.table, section_spans));
+                }
+            }
             let file_origins = origin_map_from_file(&table, &spans, path, &source);
-            (t, origins) = deep_merge(t, table, origins, file_origins);
+            warn_deprecated(&table, &file_origins, input.schema, warnings);
+            if let Some(key) = explain {
+                record_candidate(&table, &file_origins, key, &mut file_candidates);
+            }
+            (t, origins) = deep_merge(input.schema, unset, t, table, origins, file_origins);
+            // The active section sits just above the document holding it.
+            if let Some((name, section, section_spans)) = active_section {
+                crate::trace::profile_section_applied(path, &name);
+                let section_origins = origin_map_from_file(&section, &section_spans, path, &source);
+                warn_deprecated(&section, &section_origins, input.schema, warnings);
+                if let Some(key) = explain {
+                    record_candidate(&section, &section_origins, key, &mut file_candidates);
+                }
+                (t, origins) =
+                    deep_merge(input.schema, unset, t, section, origins, section_origins);
+            }
         }
         crate::trace::files_layer_constructed(input.files.len(), t.len());
         (t, origins)
@@ -258,7 +579,39 @@ pub(crate) fn resolve(
         input
             .env_prefix
             .as_ref()
-            .map(|prefix| env::env_to_table_with_sources(prefix, input.env_vars))
+            .map(|prefix| {
+                let (mut table, mut sources, mut winners) =
+                    env::env_to_table_with_sources(prefix, input.env_vars);
+                crate::alias::canonicalize_env(
+                    &mut table,
+                    &mut sources,
+                    &mut winners,
+                    input.schema,
+                    input.normalize_keys,
+                )?;
+                env::build_env_containers(
+                    &mut table,
+                    &mut sources,
+                    &mut winners,
+                    input.schema,
+                    &input.env_list_separator,
+                    unset,
+                );
+                let files = if input.env_file_secrets {
+                    env::read_env_file_secrets(
+                        &mut table,
+                        &mut sources,
+                        &mut winners,
+                        input.schema,
+                        input.read_file,
+                    )?
+                } else {
+                    BTreeMap::new()
+                };
+                schema_walk::type_raw_scalars_root(&mut table, input.schema);
+                Ok::<_, ClapfigError>((table, sources, winners, files))
+            })
+            .transpose()?
     } else {
         None
     };
@@ -271,11 +624,11 @@ pub(crate) fn resolve(
     // file-supplied `rogue = 1` key, and a violation renders as an env
     // error naming the exact variable to unset.
     //
-    // The walker is schema-driven (no typed deserialize) so type
-    // mismatches between env's heuristic value parsing (e.g. string
-    // "1.5" for an integer field) don't fail validation — that's
-    // still the job of the final-merge type check inside `finalize`.
-    if cascade_active && let Some((env_table_ref, sources, _)) = env_layer.as_ref() {
+    // The walker is schema-driven (no typed deserialize) so an env value
+    // that does not spell its leaf's type (string "1.5" for an integer
+    // field) doesn't fail validation — that's still the job of the
+    // final-merge type check inside `finalize`.
+    if cascade_active && let Some((env_table_ref, sources, _, _)) = env_layer.as_ref() {
         let mut env_filtered = crate::validate::validate_unknown_root(
             env_table_ref,
             input.schema,
@@ -284,9 +637,22 @@ pub(crate) fn resolve(
         )?;
         collected_unknowns.append(&mut env_filtered);
     }
-    let env_layer = env_layer.map(|(table, _, winners)| {
+    // Env shadows a file value only when it merges above the files.
+    let position = |layer| order.iter().position(|l| *l == layer);
+    let env_above_files =
+        position(Layer::Env) > position(Layer::Files) && position(Layer::Files).is_some();
+    let env_layer = env_layer.map(|(table, _, winners, files)| {
         crate::trace::env_layer_constructed(table.len());
-        let origins = origin_map_from_env(&table, &winners);
+        if env_above_files {
+            warn_env_shadows(&winners, &files_origins, warnings);
+        }
+        let mut origins = origin_map_from_env(&table, &winners);
+        for (path, file) in files {
+            if let Some(origin) = lookup_mut(&mut origins, &path) {
+                origin.secret_file = Some(file);
+            }
+        }
+        warn_deprecated(&table, &origins, input.schema, warnings);
         (table, origins)
     });
 
@@ -298,10 +664,13 @@ pub(crate) fn resolve(
     let url_layer = if !order.contains(&Layer::Url) || input.url_overrides.is_empty() {
         None
     } else {
-        let layer = overrides::overrides_to_table_with_original_keys(
-            &normalize_override_keys(&input.url_overrides, input.normalize_keys),
-            |k| Origin::url(k),
-        );
+        let mut entries = normalize_override_keys(&input.url_overrides, input.normalize_keys);
+        crate::alias::canonicalize_keys(&mut entries, input.schema, input.normalize_keys)
+            .map_err(|collision| collision.into_error("URL query"))?;
+        let mut layer =
+            overrides::overrides_to_table_with_original_keys(&entries, |k| Origin::url(k));
+        schema_walk::type_raw_scalars_root(&mut layer.0, input.schema);
+        warn_deprecated(&layer.0, &layer.1, input.schema, warnings);
         crate::trace::url_layer_constructed(layer.0.len());
         Some(layer)
     };
@@ -309,10 +678,12 @@ pub(crate) fn resolve(
     let cli_layer = if !order.contains(&Layer::Cli) || input.cli_overrides.is_empty() {
         None
     } else {
-        let layer = overrides::overrides_to_table_with_original_keys(
-            &normalize_override_keys(&input.cli_overrides, input.normalize_keys),
-            |k| Origin::r#override(k),
-        );
+        let mut entries = normalize_override_keys(&input.cli_overrides, input.normalize_keys);
+        crate::alias::canonicalize_keys(&mut entries, input.schema, input.normalize_keys)
+            .map_err(|collision| collision.into_error("overrides"))?;
+        let layer =
+            overrides::overrides_to_table_with_original_keys(&entries, |k| Origin::r#override(k));
+        warn_deprecated(&layer.0, &layer.1, input.schema, warnings);
         crate::trace::cli_layer_constructed(layer.0.len());
         Some(layer)
     };
@@ -328,8 +699,17 @@ pub(crate) fn resolve(
             Layer::Url => url_layer.clone(),
             Layer::Cli => cli_layer.clone(),
         };
+        if let Some(key) = explain {
+            match (layer, &table_and_origins) {
+                (Layer::Files, _) => candidates.append(&mut file_candidates),
+                (_, Some((t, layer_origins))) => {
+                    record_candidate(t, layer_origins, key, candidates)
+                }
+                (_, None) => {}
+            }
+        }
         if let Some((t, layer_origins)) = table_and_origins {
-            (merged, origins) = deep_merge(merged, t, origins, layer_origins);
+            (merged, origins) = deep_merge(input.schema, unset, merged, t, origins, layer_origins);
         }
     }
     crate::trace::merge_complete(merged.len());
@@ -339,6 +719,18 @@ pub(crate) fn resolve(
     // Default origins fill in the same walk (ADR-0004).
     schema_walk::fill_defaults_into_root(&mut merged, &mut origins, input.schema);
 
+    // Interpolation sees the merged, default-filled table, and runs before
+    // validation so the expanded value is what gets checked.
+    if input.interpolate {
+        crate::interpolate::interpolate_root(
+            &mut merged,
+            &mut origins,
+            input.schema,
+            input.lookup_env,
+            input.normalize_keys,
+        )?;
+    }
+
     // Selection is traced here — before phase 2 — so a valid discriminator
     // that then fails on a branch-exclusive key still records that a
     // variant was selected. Missing / mistyped / unknown tags emit nothing.
@@ -366,9 +758,40 @@ pub(crate) fn resolve(
         collected_unknowns.append(&mut phase2);
     }
 
+    // `from_file` leaves: the merged value is a path; load what it names.
+    // After defaults so a default path loads too, and after unknown-key
+    // checks so a stray key is reported before any file is touched.
+    crate::file_ref::read_file_leaves_root(
+        &mut merged,
+        &mut origins,
+        input.schema,
+        input.read_file,
+    )?;
+
+    // Path leaves: expand and anchor each to the file that set it, then
+    // check it exists if asked. After `from_file` so a path read from a
+    // secret file resolves too.
+    crate::paths::resolve_path_leaves_root(
+        &mut merged,
+        &origins,
+        input.schema,
+        input.lookup_env,
+        input.stat_path,
+    )?;
+
     let output = schema_walk::finalize_root(merged, &origins, input.schema, &input.discovery)?;
     crate::trace::validation_complete();
-    Ok((output, collected_unknowns))
+    // The deny policy runs last, so a load that is also wrong reports
+    // the error, not the warning.
+    let denied: Vec<Warning> = warnings
+        .iter()
+        .filter(|w| input.deny_warnings.contains(&w.kind()))
+        .cloned()
+        .collect();
+    if !denied.is_empty() {
+        return Err(ClapfigError::DeniedWarnings(denied));
+    }
+    Ok((output, collected_unknowns, origins))
 }
 
 #[cfg(test)]
@@ -407,8 +830,21 @@ mod tests {
             registry: toml_only_registry(),
             files: vec![],
             discovery: DiscoveryRecord::empty(),
+            include_key: None,
+            profile_key: None,
+            profile: None,
             env_vars: vec![],
             env_prefix: None,
+            env_list_separator: env::DEFAULT_LIST_SEPARATOR.into(),
+            env_file_secrets: false,
+            read_file: crate::file_ref::read_from_disk,
+            file_mode: |_| None,
+            stat_path: |_| None,
+            interpolate: false,
+            lookup_env: |_| None,
+            unset_marker: None,
+            migrations: vec![],
+            schema_version_key: None,
             #[cfg(feature = "url")]
             url_overrides: vec![],
             cli_overrides: vec![],
@@ -417,6 +853,7 @@ mod tests {
             unknown_key_hook: None,
             normalize_keys: false,
             layer_order: None,
+            deny_warnings: vec![],
         }
     }
 
@@ -509,6 +946,116 @@ mod tests {
         assert_eq!(get(&table, "port").unwrap().as_integer(), Some(5000));
     }
 
+    fn typed_env_schema() -> Schema {
+        use crate::runtime::Field;
+        Schema::object("T")
+            .field("zip", Field::string())
+            .field("tag", Field::string())
+            .field("port", Field::integer_in(Some(1), Some(65535)))
+            .field("ratio", Field::float())
+            .field("debug", Field::boolean())
+            .field("level", Field::enum_of(["1", "high"]))
+            .field("code", Field::enum_of([1i64, 2]))
+            .field("stamp", Field::datetime())
+            .field("extra", Field::value())
+            .build()
+    }
+
+    fn typed_env_vars(overrides: &[(&str, &str)]) -> Vec<(String, String)> {
+        let mut vars: Vec<(String, String)> = [
+            ("ZIP", "01234"),
+            ("TAG", "true"),
+            ("PORT", "8080"),
+            ("RATIO", "2"),
+            ("DEBUG", "TRUE"),
+            ("LEVEL", "1"),
+            ("CODE", "2"),
+            ("STAMP", "2026-01-02"),
+            ("EXTRA", "42"),
+        ]
+        .iter()
+        .map(|(k, v)| (format!("MYAPP__{k}"), v.to_string()))
+        .collect();
+        vars.extend(
+            overrides
+                .iter()
+                .map(|(k, v)| (format!("MYAPP__{k}"), v.to_string())),
+        );
+        vars
+    }
+
+    #[test]
+    fn env_values_parse_by_declared_leaf_type() {
+        let spec = typed_env_schema();
+        let input = ResolveInput {
+            env_vars: typed_env_vars(&[]),
+            env_prefix: Some("MYAPP".into()),
+            ..empty_input(&spec)
+        };
+        let (table, _) = resolve(input).unwrap();
+        assert_eq!(table["zip"], Value::String("01234".into()));
+        assert_eq!(table["tag"], Value::String("true".into()));
+        assert_eq!(table["port"], Value::Integer(8080));
+        assert_eq!(table["ratio"], Value::Float(2.0));
+        assert_eq!(table["debug"], Value::Boolean(true));
+        assert_eq!(table["level"], Value::String("1".into()));
+        assert_eq!(table["code"], Value::Integer(2));
+        assert!(matches!(table["stamp"], Value::Datetime(_)));
+        assert_eq!(table["extra"], Value::Integer(42));
+    }
+
+    #[test]
+    fn env_bools_take_the_same_words_as_ini() {
+        let spec = typed_env_schema();
+        for (raw, expected) in [
+            ("yes", true),
+            ("On", true),
+            ("1", true),
+            ("NO", false),
+            ("off", false),
+            ("0", false),
+        ] {
+            let input = ResolveInput {
+                env_vars: typed_env_vars(&[("DEBUG", raw)]),
+                env_prefix: Some("MYAPP".into()),
+                ..empty_input(&spec)
+            };
+            let (table, _) = resolve(input).unwrap();
+            assert_eq!(table["debug"], Value::Boolean(expected), "DEBUG={raw}");
+        }
+    }
+
+    #[test]
+    fn env_value_not_spelling_its_type_fails_naming_the_variable() {
+        let spec = typed_env_schema();
+        for (var, raw) in [("PORT", "70000"), ("PORT", "eighty"), ("DEBUG", "maybe")] {
+            let input = ResolveInput {
+                env_vars: typed_env_vars(&[(var, raw)]),
+                env_prefix: Some("MYAPP".into()),
+                ..empty_input(&spec)
+            };
+            let err = resolve(input).unwrap_err();
+            assert!(
+                err.to_string().contains(&format!("MYAPP__{var}")),
+                "{var}={raw}: {err}"
+            );
+        }
+    }
+
+    #[cfg(feature = "url")]
+    #[test]
+    fn url_values_parse_by_declared_leaf_type() {
+        let spec = test_spec();
+        let input = ResolveInput {
+            url_overrides: crate::url::query_to_overrides("host=8080&port=9090&debug=true"),
+            ..empty_input(&spec)
+        };
+        let (table, _) = resolve(input).unwrap();
+        assert_eq!(table["host"], Value::String("8080".into()));
+        assert_eq!(table["port"], Value::Integer(9090));
+        assert_eq!(table["debug"], Value::Boolean(true));
+    }
+
     #[test]
     fn cli_overrides_all() {
         let spec = test_spec();
@@ -587,131 +1134,789 @@ mod tests {
     }
 
     #[test]
-    fn strict_rejects_unknown_key() {
+    fn strict_rejects_unknown_key() {
+        let spec = test_spec();
+        let input = ResolveInput {
+            files: vec![("bad.toml".into(), "typo = 1\n".into())],
+            ..empty_input(&spec)
+        };
+        let result = resolve(input);
+        assert!(result.is_err());
+        let msg = result.unwrap_err().to_string();
+        assert!(msg.contains("typo") || msg.contains("Unknown"));
+    }
+
+    #[test]
+    fn toml_unknown_key_in_array_of_tables_carets_the_right_line() {
+        use crate::runtime::Field;
+        let schema = Schema::object("App")
+            .array_of(
+                "servers",
+                Schema::object("Server").field("host", Field::string().optional()),
+            )
+            .build();
+        let source = "[[servers]]\nhost = \"a\"\n[[servers]]\nrogue = 1\n";
+        let input = ResolveInput {
+            files: vec![("config.toml".into(), source.into())],
+            ..empty_input(&schema)
+        };
+        let err = resolve(input).unwrap_err();
+        let keys = err.unknown_keys().expect("expected UnknownKeys");
+        assert_eq!(keys[0].key, "servers[1].rogue");
+        assert_eq!(keys[0].line, 4);
+        let span = keys[0].span.expect("key span");
+        assert_eq!(&source[span.start..span.end], "rogue");
+        let out = crate::render::render_plain(&err);
+        assert!(out.contains("rogue = 1"), "{out}");
+        assert!(out.contains(":4"), "{out}");
+    }
+
+    #[test]
+    fn toml_unknown_under_quoted_dotted_map_of_key_carets_the_key() {
+        use crate::runtime::Field;
+        let schema = Schema::object("App")
+            .map_of(
+                "plugins",
+                Schema::object("Plugin").field("host", Field::string().optional()),
+            )
+            .build();
+        let source = "[plugins.\"acme.prod\"]\nrogue = 1\n";
+        let input = ResolveInput {
+            files: vec![("config.toml".into(), source.into())],
+            ..empty_input(&schema)
+        };
+        let err = resolve(input).unwrap_err();
+        let keys = err.unknown_keys().expect("expected UnknownKeys");
+        assert_eq!(keys[0].line, 2);
+        let span = keys[0].span.expect("key span");
+        assert_eq!(&source[span.start..span.end], "rogue");
+        let out = crate::render::render_plain(&err);
+        assert!(out.contains("rogue = 1"), "{out}");
+        assert!(out.contains(":2"), "{out}");
+    }
+
+    #[test]
+    fn env_unknown_key_rejected_when_strict() {
+        // Issue #54 item 3: env-derived unknown keys used to merge in
+        // unnoticed. They now flow through the same cascade as file
+        // keys — `MYAPP__ROGUE=1` errors the same way `rogue = 1` in a
+        // file would.
+        let spec = test_spec();
+        let input = ResolveInput {
+            env_vars: vec![("MYAPP__ROGUE_KEY".into(), "1".into())],
+            env_prefix: Some("MYAPP".into()),
+            ..empty_input(&spec)
+        };
+        let err = resolve(input).unwrap_err();
+        let keys = err.unknown_keys().expect("expected UnknownKeys");
+        assert_eq!(keys.len(), 1);
+        assert_eq!(keys[0].key, "rogue_key");
+        // The violation is reported as an env problem naming the exact
+        // variable to unset, never dressed as a config-file error.
+        assert_eq!(keys[0].env_var.as_deref(), Some("MYAPP__ROGUE_KEY"));
+        let msg = err.to_string();
+        assert!(msg.contains("MYAPP__ROGUE_KEY"), "{msg}");
+        assert!(!msg.contains("config file"), "{msg}");
+    }
+
+    #[test]
+    fn env_unknown_key_names_the_mixed_case_variable() {
+        // The suffix is accepted in any case and lowercased for the
+        // table path. The error must name the spelling that is actually
+        // set — unsetting a reconstructed MYAPP__ROGUE_KEY would leave
+        // MYAPP__rogue_key in place on a case-sensitive platform.
+        let spec = test_spec();
+        let input = ResolveInput {
+            env_vars: vec![("MYAPP__rogue_key".into(), "1".into())],
+            env_prefix: Some("MYAPP".into()),
+            ..empty_input(&spec)
+        };
+        let err = resolve(input).unwrap_err();
+        let keys = err.unknown_keys().expect("expected UnknownKeys");
+        assert_eq!(keys.len(), 1);
+        assert_eq!(keys[0].key, "rogue_key");
+        assert_eq!(keys[0].env_var.as_deref(), Some("MYAPP__rogue_key"));
+        let msg = err.to_string();
+        assert!(msg.contains("MYAPP__rogue_key"), "{msg}");
+        assert!(!msg.contains("MYAPP__ROGUE_KEY"), "{msg}");
+    }
+
+    #[test]
+    fn env_unknown_nested_section_names_the_variable() {
+        // No `database` field: the walker reports `database`, not
+        // `database.rogue`. The original variable must still be named.
+        use crate::runtime::{Field, Schema};
+        let spec = Schema::object("App")
+            .field("host", Field::string().default("localhost"))
+            .build();
+        let input = ResolveInput {
+            env_vars: vec![("MYAPP__DATABASE__ROGUE".into(), "1".into())],
+            env_prefix: Some("MYAPP".into()),
+            ..empty_input(&spec)
+        };
+        let err = resolve(input).unwrap_err();
+        let keys = err.unknown_keys().expect("expected UnknownKeys");
+        assert_eq!(keys.len(), 1);
+        assert_eq!(keys[0].key, "database");
+        assert_eq!(keys[0].env_var.as_deref(), Some("MYAPP__DATABASE__ROGUE"));
+    }
+
+    fn env_container_schema() -> Schema {
+        use crate::runtime::{Field, Schema};
+        Schema::object("App")
+            .field("ports", Field::array_of_type(Field::integer()).optional())
+            .array_of(
+                "servers",
+                Schema::object("Server")
+                    .field("host", Field::string())
+                    .field("port", Field::integer().default(80i64)),
+            )
+            .build()
+    }
+
+    #[test]
+    fn env_arrays_load_with_an_origin_per_element() {
+        let spec = env_container_schema();
+        let input = ResolveInput {
+            env_vars: vec![
+                ("MYAPP__PORTS".into(), "80,443".into()),
+                ("MYAPP__SERVERS__0__HOST".into(), "a".into()),
+                ("MYAPP__SERVERS__1__HOST".into(), "b".into()),
+                ("MYAPP__SERVERS__1__PORT".into(), "8080".into()),
+            ],
+            env_prefix: Some("MYAPP".into()),
+            ..empty_input(&spec)
+        };
+        let (table, _, origins) = resolve_with_origins(input).unwrap();
+        assert_eq!(
+            table["ports"],
+            Value::Array(vec![Value::Integer(80), Value::Integer(443)])
+        );
+        let servers = table["servers"].as_array().unwrap();
+        assert_eq!(servers[0].as_map().unwrap()["port"], Value::Integer(80));
+        assert_eq!(servers[1].as_map().unwrap()["port"], Value::Integer(8080));
+
+        let env_var = |path: ConfigPath| {
+            crate::origin::lookup(&origins, &path)
+                .unwrap()
+                .to_facts()
+                .env_var
+        };
+        let port = ConfigPath::new().key("servers").index(1).key("port");
+        assert_eq!(env_var(port).as_deref(), Some("MYAPP__SERVERS__1__PORT"));
+        let item = ConfigPath::new().key("ports").index(1);
+        assert_eq!(env_var(item).as_deref(), Some("MYAPP__PORTS"));
+    }
+
+    #[test]
+    fn env_array_element_errors_name_the_element_variable() {
+        let spec = env_container_schema();
+        let input = ResolveInput {
+            env_vars: vec![
+                ("MYAPP__PORTS__0".into(), "80".into()),
+                ("MYAPP__PORTS__1".into(), "eighty".into()),
+            ],
+            env_prefix: Some("MYAPP".into()),
+            ..empty_input(&spec)
+        };
+        let err = resolve(input).unwrap_err();
+        assert!(err.to_string().contains("MYAPP__PORTS__1"), "{err}");
+
+        let input = ResolveInput {
+            env_vars: vec![
+                ("MYAPP__SERVERS__0__HOST".into(), "a".into()),
+                ("MYAPP__SERVERS__0__PORT".into(), "x".into()),
+            ],
+            env_prefix: Some("MYAPP".into()),
+            ..empty_input(&spec)
+        };
+        let err = resolve(input).unwrap_err();
+        assert!(err.to_string().contains("MYAPP__SERVERS__0__PORT"), "{err}");
+    }
+
+    #[test]
+    fn env_unknown_key_inside_an_indexed_element_names_the_variable() {
+        let spec = env_container_schema();
+        let input = ResolveInput {
+            env_vars: vec![
+                ("MYAPP__SERVERS__0__HOST".into(), "a".into()),
+                ("MYAPP__SERVERS__0__ROGUE".into(), "1".into()),
+            ],
+            env_prefix: Some("MYAPP".into()),
+            ..empty_input(&spec)
+        };
+        let err = resolve(input).unwrap_err();
+        let keys = err.unknown_keys().expect("expected UnknownKeys");
+        assert_eq!(keys.len(), 1);
+        assert_eq!(keys[0].env_var.as_deref(), Some("MYAPP__SERVERS__0__ROGUE"));
+    }
+
+    /// In-memory files for `_FILE` and `from_file` tests.
+    fn fake_read(path: &std::path::Path) -> std::io::Result<String> {
+        match path.to_str() {
+            Some("/run/secrets/db") => Ok("postgres://secret\n".into()),
+            Some("/run/secrets/pool") => Ok("12\n".into()),
+            Some("/etc/app/token.txt") => Ok("abc123\r\n".into()),
+            _ => Err(std::io::ErrorKind::NotFound.into()),
+        }
+    }
+
+    #[test]
+    fn env_file_secret_reads_the_named_file() {
+        let spec = test_spec();
+        let input = ResolveInput {
+            env_vars: vec![
+                ("MYAPP__DATABASE__URL_FILE".into(), "/run/secrets/db".into()),
+                (
+                    "MYAPP__DATABASE__POOL_SIZE_FILE".into(),
+                    "/run/secrets/pool".into(),
+                ),
+            ],
+            env_prefix: Some("MYAPP".into()),
+            env_file_secrets: true,
+            read_file: fake_read,
+            ..empty_input(&spec)
+        };
+        let (table, _, origins) = resolve_with_origins(input).unwrap();
+        assert_eq!(
+            get(&table, "database.url").unwrap().as_str(),
+            Some("postgres://secret")
+        );
+        // The contents parse by the leaf type like any env value.
+        assert_eq!(
+            get(&table, "database.pool_size").unwrap().as_integer(),
+            Some(12)
+        );
+
+        let url = ConfigPath::new().key("database").key("url");
+        let facts = crate::origin::lookup(&origins, &url).unwrap().to_facts();
+        assert_eq!(facts.env_var.as_deref(), Some("MYAPP__DATABASE__URL_FILE"));
+        assert_eq!(
+            facts.secret_file.as_deref(),
+            Some(std::path::Path::new("/run/secrets/db"))
+        );
+        assert_eq!(
+            facts.to_string(),
+            "env: MYAPP__DATABASE__URL_FILE (read from /run/secrets/db)"
+        );
+    }
+
+    #[test]
+    fn env_file_secrets_off_leaves_file_vars_unknown() {
+        let spec = test_spec();
+        let input = ResolveInput {
+            env_vars: vec![("MYAPP__DATABASE__URL_FILE".into(), "/run/secrets/db".into())],
+            env_prefix: Some("MYAPP".into()),
+            read_file: fake_read,
+            ..empty_input(&spec)
+        };
+        let err = resolve(input).unwrap_err();
+        let keys = err.unknown_keys().expect("expected UnknownKeys");
+        assert_eq!(keys[0].key, "database.url_file");
+    }
+
+    #[test]
+    fn env_file_secret_read_failure_names_variable_and_file() {
+        let spec = test_spec();
+        let input = ResolveInput {
+            env_vars: vec![(
+                "MYAPP__DATABASE__URL_FILE".into(),
+                "/run/secrets/gone".into(),
+            )],
+            env_prefix: Some("MYAPP".into()),
+            env_file_secrets: true,
+            read_file: fake_read,
+            ..empty_input(&spec)
+        };
+        let err = resolve(input).unwrap_err();
+        let facts = assert_invalid_value(&err, "database.url", crate::types::InputType::Env);
+        assert_eq!(facts.env_var.as_deref(), Some("MYAPP__DATABASE__URL_FILE"));
+        let msg = err.to_string();
+        assert!(msg.contains("cannot read file /run/secrets/gone"), "{msg}");
+        assert!(msg.contains("read from file /run/secrets/gone"), "{msg}");
+    }
+
+    #[test]
+    fn env_file_secret_conflicts_with_the_plain_variable() {
+        let spec = test_spec();
+        let input = ResolveInput {
+            env_vars: vec![
+                ("MYAPP__DATABASE__URL".into(), "postgres://plain".into()),
+                ("MYAPP__DATABASE__URL_FILE".into(), "/run/secrets/db".into()),
+            ],
+            env_prefix: Some("MYAPP".into()),
+            env_file_secrets: true,
+            read_file: fake_read,
+            ..empty_input(&spec)
+        };
+        let err = resolve(input).unwrap_err();
+        assert_invalid_value(&err, "database.url", crate::types::InputType::Env);
+        let msg = err.to_string();
+        assert!(
+            msg.contains("set by both MYAPP__DATABASE__URL and MYAPP__DATABASE__URL_FILE"),
+            "{msg}"
+        );
+    }
+
+    fn from_file_schema() -> Schema {
+        use crate::runtime::{Field, Schema};
+        Schema::object("App")
+            .field("token", Field::string().from_file().optional())
+            .build()
+    }
+
+    #[test]
+    fn from_file_leaf_resolves_relative_to_the_config_file() {
+        let spec = from_file_schema();
+        let input = ResolveInput {
+            files: vec![(
+                "/etc/app/config.toml".into(),
+                "token = \"token.txt\"\n".into(),
+            )],
+            read_file: fake_read,
+            ..empty_input(&spec)
+        };
+        let (table, _, origins) = resolve_with_origins(input).unwrap();
+        assert_eq!(table["token"], Value::String("abc123".into()));
+        let token = ConfigPath::new().key("token");
+        let facts = crate::origin::lookup(&origins, &token).unwrap().to_facts();
+        assert_eq!(
+            facts.file.as_deref(),
+            Some(std::path::Path::new("/etc/app/config.toml"))
+        );
+        assert_eq!(
+            facts.secret_file.as_deref(),
+            Some(std::path::Path::new("/etc/app/token.txt"))
+        );
+    }
+
+    #[test]
+    fn from_file_read_failure_points_at_the_config_line() {
+        let spec = from_file_schema();
+        let source = "token = \"missing.txt\"\n";
+        let input = ResolveInput {
+            files: vec![("/etc/app/config.toml".into(), source.into())],
+            read_file: fake_read,
+            ..empty_input(&spec)
+        };
+        let err = resolve(input).unwrap_err();
+        let facts = assert_invalid_value(&err, "token", crate::types::InputType::File);
+        let span = facts.span.expect("value span");
+        assert_eq!(&source[span.start..span.end], "\"missing.txt\"");
+        let msg = err.to_string();
+        assert!(
+            msg.contains("cannot read file /etc/app/missing.txt"),
+            "{msg}"
+        );
+    }
+
+    fn origin_at<'a>(origins: &'a OriginMap, dotted: &str) -> Option<&'a Origin> {
+        let path = dotted
+            .split('.')
+            .fold(ConfigPath::new(), |p, seg| p.key(seg));
+        crate::origin::lookup(origins, &path)
+    }
+
+    #[test]
+    fn unset_marker_resets_values_from_every_input() {
+        let spec = test_spec();
+        let input = ResolveInput {
+            files: vec![
+                (
+                    "global.toml".into(),
+                    "port = 3000\nhost = \"example.com\"\n[database]\nurl = \"pg://a\"\n".into(),
+                ),
+                ("project.toml".into(), "host = \"<unset>\"\n".into()),
+            ],
+            env_vars: vec![("MYAPP__PORT".into(), "<unset>".into())],
+            env_prefix: Some("MYAPP".into()),
+            cli_overrides: vec![("database.url".into(), Value::String("<unset>".into()))],
+            unset_marker: Some("<unset>".into()),
+            ..empty_input(&spec)
+        };
+        let (table, _, origins) = resolve_with_origins(input).unwrap();
+        // A defaulted leaf reverts to its default; the reset is recorded
+        // against the input that supplied the marker.
+        assert_eq!(get(&table, "port").unwrap().as_integer(), Some(8080));
+        let port = origin_at(&origins, "port").unwrap();
+        assert!(port.reset);
+        assert_eq!(port.env_vars, ["MYAPP__PORT"]);
+        assert_eq!(get(&table, "host").unwrap().as_str(), Some("localhost"));
+        let host = origin_at(&origins, "host").unwrap();
+        assert_eq!(
+            host.file.as_deref(),
+            Some(std::path::Path::new("project.toml"))
+        );
+        assert!(host.reset);
+        // An optional leaf is absent again.
+        assert!(get(&table, "database.url").is_none());
+        let url = origin_at(&origins, "database.url").unwrap();
+        assert_eq!(url.layer, crate::types::InputType::Override);
+        assert!(url.reset);
+        assert_eq!(url.to_facts().to_string(), "override: database.url (reset)");
+    }
+
+    #[test]
+    fn unset_marker_is_a_plain_string_until_enabled() {
+        let spec = test_spec();
+        let marker_over_nothing = ResolveInput {
+            files: vec![("a.toml".into(), "[database]\nurl = \"<unset>\"\n".into())],
+            unset_marker: Some("<unset>".into()),
+            ..empty_input(&spec)
+        };
+        let (table, _) = resolve(marker_over_nothing).unwrap();
+        assert!(get(&table, "database.url").is_none());
+
+        let disabled = ResolveInput {
+            files: vec![("a.toml".into(), "[database]\nurl = \"<unset>\"\n".into())],
+            ..empty_input(&spec)
+        };
+        let (table, _) = resolve(disabled).unwrap();
+        assert_eq!(
+            get(&table, "database.url").unwrap().as_str(),
+            Some("<unset>")
+        );
+    }
+
+    #[test]
+    fn migrations_apply_to_documents_and_profile_sections_before_validation() {
+        let spec = test_spec();
+        let text = "version = 1\nport = 3000\n[db]\nurl = \"pg://a\"\n\n[profile.prod.db]\nurl = \"pg://b\"\n";
+        let input = ResolveInput {
+            files: vec![("a.toml".into(), text.into())],
+            profile_key: Some("profile".into()),
+            profile: Some("prod".into()),
+            migrations: vec![
+                Migration::rename("db", "database"),
+                Migration::remove("port").at_version(1),
+            ],
+            schema_version_key: Some("version".into()),
+            ..empty_input(&spec)
+        };
+        let (table, _, origins) = resolve_with_origins(input).unwrap();
+        assert_eq!(
+            get(&table, "database.url").unwrap().as_str(),
+            Some("pg://b")
+        );
+        // Version 1 is not below 1, so `port` stays.
+        assert_eq!(get(&table, "port").unwrap().as_integer(), Some(3000));
+        // The origin still points at the text the value came from.
+        let url = origin_at(&origins, "database.url").unwrap();
+        let span = url.span.unwrap();
+        assert_eq!(&text[span.start..span.end], "\"pg://b\"");
+    }
+
+    fn alias_spec() -> Schema {
+        use crate::runtime::{Field, LeafType};
+        Schema::object("App")
+            .field(
+                "timeout",
+                Field::integer()
+                    .alias("timeout_secs")
+                    .alias("wait")
+                    .optional(),
+            )
+            .field(
+                "tags",
+                Field::array_of_type(LeafType::String).alias("labels"),
+            )
+            .nested(
+                "db",
+                Schema::object("Db").field("url", Field::string().alias("uri").optional()),
+            )
+            .build()
+    }
+
+    #[test]
+    fn aliases_are_accepted_from_files_env_and_overrides() {
+        let spec = alias_spec();
+        let text = "timeout_secs = 5\n[db]\nuri = \"pg://file\"\n";
+        let input = ResolveInput {
+            files: vec![("a.toml".into(), text.into())],
+            env_prefix: Some("MYAPP".into()),
+            env_vars: vec![("MYAPP__LABELS".into(), "a,b".into())],
+            cli_overrides: vec![("db.uri".into(), Value::String("pg://cli".into()))],
+            ..empty_input(&spec)
+        };
+        let (table, _, origins) = resolve_with_origins(input).unwrap();
+        assert_eq!(get(&table, "timeout").unwrap().as_integer(), Some(5));
+        assert_eq!(get(&table, "db.url").unwrap().as_str(), Some("pg://cli"));
+        let tags = get(&table, "tags").unwrap().as_array().unwrap();
+        assert_eq!(tags.len(), 2, "the env list splits at the field's own name");
+        // Origins sit at the field's name but keep the input's spelling.
+        let timeout = origin_at(&origins, "timeout").unwrap();
+        let span = timeout.span.unwrap();
+        assert_eq!(&text[span.start..span.end], "5");
+        assert_eq!(
+            origin_at(&origins, "tags").unwrap().env_vars,
+            vec!["MYAPP__LABELS".to_string()]
+        );
+        assert_eq!(
+            origin_at(&origins, "db.url").unwrap().key.as_deref(),
+            Some("db.uri")
+        );
+    }
+
+    #[test]
+    fn one_input_spelling_a_field_two_ways_is_an_alias_collision() {
+        let spec = alias_spec();
+        let file = ResolveInput {
+            files: vec![("a.toml".into(), "timeout = 1\nwait = 2\n".into())],
+            ..empty_input(&spec)
+        };
+        match resolve(file).unwrap_err() {
+            ClapfigError::AliasCollision {
+                input,
+                section,
+                key,
+                originals,
+            } => {
+                assert_eq!(input, "a.toml");
+                assert_eq!(section, "");
+                assert_eq!(key, "timeout");
+                assert_eq!(originals, vec!["timeout", "wait"]);
+            }
+            other => panic!("expected AliasCollision, got {other:?}"),
+        }
+
+        let env = ResolveInput {
+            env_prefix: Some("MYAPP".into()),
+            env_vars: vec![
+                ("MYAPP__DB__URL".into(), "a".into()),
+                ("MYAPP__DB__URI".into(), "b".into()),
+            ],
+            ..empty_input(&spec)
+        };
+        let err = resolve(env).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Conflicting keys in environment: 'url' (under [db]) is defined by \
+             ['MYAPP__DB__URI', 'MYAPP__DB__URL'], which name the same field"
+        );
+
+        // Overrides collide per input too — the files above are another
+        // input, so a file alias under a CLI name is fine.
+        let overrides = ResolveInput {
+            files: vec![("a.toml".into(), "wait = 1\n".into())],
+            cli_overrides: vec![
+                ("timeout_secs".into(), Value::Integer(2)),
+                ("timeout".into(), Value::Integer(3)),
+            ],
+            ..empty_input(&spec)
+        };
+        assert!(matches!(
+            resolve(overrides).unwrap_err(),
+            ClapfigError::AliasCollision { input, .. } if input == "overrides"
+        ));
+    }
+
+    #[test]
+    fn resetting_a_required_leaf_is_missing_required() {
+        let spec = Schema::object("App")
+            .field("token", crate::runtime::Field::string())
+            .build();
+        let input = ResolveInput {
+            files: vec![("a.toml".into(), "token = \"t\"\n".into())],
+            cli_overrides: vec![("token".into(), Value::String("-".into()))],
+            unset_marker: Some("-".into()),
+            ..empty_input(&spec)
+        };
+        let err = resolve(input).unwrap_err();
+        assert!(
+            matches!(err, ClapfigError::MissingRequired { .. }),
+            "{err:?}"
+        );
+    }
+
+    fn fake_env(name: &str) -> Option<String> {
+        (name == "DB_HOST").then(|| "db.internal".into())
+    }
+
+    fn interpolate_input<'a>(spec: &'a Schema, source: &str) -> ResolveInput<'a> {
+        ResolveInput {
+            files: vec![("test.toml".into(), source.into())],
+            interpolate: true,
+            lookup_env: fake_env,
+            ..empty_input(spec)
+        }
+    }
+
+    #[test]
+    fn interpolation_expands_keys_env_and_escapes() {
+        let spec = test_spec();
+        let source = "host = \"${env:DB_HOST}\"\n\
+                      [database]\n\
+                      url = \"pg://${host}:${port}/app?q=$${literal}\"\n\
+                      pool_size = \"${port}\"\n";
+        let (table, _, origins) = resolve_with_origins(interpolate_input(&spec, source)).unwrap();
+        assert_eq!(get(&table, "host").unwrap().as_str(), Some("db.internal"));
+        assert_eq!(
+            get(&table, "database.url").unwrap().as_str(),
+            Some("pg://db.internal:8080/app?q=${literal}")
+        );
+        // The expanded string parses by the leaf's declared type.
+        assert_eq!(
+            get(&table, "database.pool_size").unwrap().as_integer(),
+            Some(8080)
+        );
+
+        let provenance = crate::Provenance::new(origins);
+        assert_eq!(
+            provenance.raw_dotted("database.url"),
+            Some("pg://${host}:${port}/app?q=$${literal}")
+        );
+        assert_eq!(provenance.raw_dotted("port"), None);
+        let facts = provenance.get_dotted("database.url").unwrap();
+        assert_eq!(facts.input_type, Some(crate::types::InputType::File));
+    }
+
+    #[test]
+    fn interpolation_is_off_by_default() {
+        let spec = test_spec();
+        let input = ResolveInput {
+            files: vec![("test.toml".into(), "host = \"${port}\"\n".into())],
+            ..empty_input(&spec)
+        };
+        let (table, _) = resolve(input).unwrap();
+        assert_eq!(get(&table, "host").unwrap().as_str(), Some("${port}"));
+    }
+
+    #[test]
+    fn interpolation_cycle_points_at_the_offending_string() {
+        let spec = test_spec();
+        let source = "host = \"${database.url}\"\n[database]\nurl = \"x${host}\"\n";
+        let err = resolve(interpolate_input(&spec, source)).unwrap_err();
+        let facts = assert_invalid_value(&err, "database.url", crate::types::InputType::File);
+        let span = facts.span.expect("value span");
+        assert_eq!(&source[span.start..span.end], "\"x${host}\"");
+        let msg = err.to_string();
+        assert!(
+            msg.contains("interpolation cycle: database.url -> host -> database.url"),
+            "{msg}"
+        );
+    }
+
+    #[test]
+    fn interpolation_missing_references_are_located_errors() {
+        let spec = test_spec();
+        let err = resolve(interpolate_input(&spec, "host = \"${nope}\"\n")).unwrap_err();
+        assert_invalid_value(&err, "host", crate::types::InputType::File);
+        assert!(err.to_string().contains("`${nope}`: no such key"), "{err}");
+
+        let err = resolve(interpolate_input(&spec, "host = \"${env:UNSET}\"\n")).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("environment variable UNSET is not set"),
+            "{err}"
+        );
+
+        let err = resolve(interpolate_input(&spec, "host = \"${database}\"\n")).unwrap_err();
+        assert!(err.to_string().contains("is a table"), "{err}");
+    }
+
+    fn profile_input<'a>(
+        spec: &'a Schema,
+        files: Vec<(&str, &str)>,
+        profile: Option<&str>,
+    ) -> ResolveInput<'a> {
+        ResolveInput {
+            files: files
+                .into_iter()
+                .map(|(path, text)| (path.into(), text.into()))
+                .collect(),
+            profile_key: Some("profile".into()),
+            profile: profile.map(str::to_owned),
+            ..empty_input(spec)
+        }
+    }
+
+    #[test]
+    fn active_profile_section_layers_just_above_its_file() {
         let spec = test_spec();
-        let input = ResolveInput {
-            files: vec![("bad.toml".into(), "typo = 1\n".into())],
-            ..empty_input(&spec)
-        };
-        let result = resolve(input);
-        assert!(result.is_err());
-        let msg = result.unwrap_err().to_string();
-        assert!(msg.contains("typo") || msg.contains("Unknown"));
+        let global = "port = 1\nhost = \"g\"\n\n[profile.prod]\nport = 2\nhost = \"gp\"\n";
+        let local = "host = \"l\"\n";
+        let (table, _, origins) = resolve_with_origins(profile_input(
+            &spec,
+            vec![("g.toml", global), ("l.toml", local)],
+            Some("prod"),
+        ))
+        .unwrap();
+        // The section beats its own file, and a later file beats both.
+        assert_eq!(get(&table, "port").unwrap().as_integer(), Some(2));
+        assert_eq!(get(&table, "host").unwrap().as_str(), Some("l"));
+        let port = crate::origin::lookup(&origins, &ConfigPath::new().key("port"))
+            .unwrap()
+            .to_facts();
+        assert_eq!(port.file.as_deref(), Some(std::path::Path::new("g.toml")));
+        assert_eq!(port.line_col(), Some((5, 8)));
+
+        let (table, _) = resolve(profile_input(&spec, vec![("g.toml", global)], None)).unwrap();
+        assert_eq!(get(&table, "port").unwrap().as_integer(), Some(1));
     }
 
     #[test]
-    fn toml_unknown_key_in_array_of_tables_carets_the_right_line() {
-        use crate::runtime::Field;
-        let schema = Schema::object("App")
-            .array_of(
-                "servers",
-                Schema::object("Server").field("host", Field::string().optional()),
-            )
-            .build();
-        let source = "[[servers]]\nhost = \"a\"\n[[servers]]\nrogue = 1\n";
-        let input = ResolveInput {
-            files: vec![("config.toml".into(), source.into())],
-            ..empty_input(&schema)
-        };
-        let err = resolve(input).unwrap_err();
+    fn every_profile_section_is_held_to_the_schema() {
+        let spec = test_spec();
+        let text = "port = 1\n\n[profile.prod]\nrogue = 2\n";
+        let err = resolve(profile_input(&spec, vec![("a.toml", text)], Some("dev"))).unwrap_err();
         let keys = err.unknown_keys().expect("expected UnknownKeys");
-        assert_eq!(keys[0].key, "servers[1].rogue");
+        assert_eq!(keys[0].key, "rogue");
         assert_eq!(keys[0].line, 4);
-        let span = keys[0].span.expect("key span");
-        assert_eq!(&source[span.start..span.end], "rogue");
-        let out = crate::render::render_plain(&err);
-        assert!(out.contains("rogue = 1"), "{out}");
-        assert!(out.contains(":4"), "{out}");
-    }
 
-    #[test]
-    fn toml_unknown_under_quoted_dotted_map_of_key_carets_the_key() {
-        use crate::runtime::Field;
-        let schema = Schema::object("App")
-            .map_of(
-                "plugins",
-                Schema::object("Plugin").field("host", Field::string().optional()),
-            )
-            .build();
-        let source = "[plugins.\"acme.prod\"]\nrogue = 1\n";
+        // Without profiles the table is an ordinary unknown key.
         let input = ResolveInput {
-            files: vec![("config.toml".into(), source.into())],
-            ..empty_input(&schema)
+            profile_key: None,
+            ..profile_input(&spec, vec![("a.toml", "[profile.prod]\nport = 2\n")], None)
         };
-        let err = resolve(input).unwrap_err();
-        let keys = err.unknown_keys().expect("expected UnknownKeys");
-        assert_eq!(keys[0].line, 2);
-        let span = keys[0].span.expect("key span");
-        assert_eq!(&source[span.start..span.end], "rogue");
-        let out = crate::render::render_plain(&err);
-        assert!(out.contains("rogue = 1"), "{out}");
-        assert!(out.contains(":2"), "{out}");
-    }
+        let keys = resolve(input).unwrap_err().unknown_keys().unwrap().to_vec();
+        assert_eq!(keys[0].key, "profile");
 
-    #[test]
-    fn env_unknown_key_rejected_when_strict() {
-        // Issue #54 item 3: env-derived unknown keys used to merge in
-        // unnoticed. They now flow through the same cascade as file
-        // keys — `MYAPP__ROGUE=1` errors the same way `rogue = 1` in a
-        // file would.
-        let spec = test_spec();
-        let input = ResolveInput {
-            env_vars: vec![("MYAPP__ROGUE_KEY".into(), "1".into())],
-            env_prefix: Some("MYAPP".into()),
-            ..empty_input(&spec)
-        };
-        let err = resolve(input).unwrap_err();
-        let keys = err.unknown_keys().expect("expected UnknownKeys");
-        assert_eq!(keys.len(), 1);
-        assert_eq!(keys[0].key, "rogue_key");
-        // The violation is reported as an env problem naming the exact
-        // variable to unset, never dressed as a config-file error.
-        assert_eq!(keys[0].env_var.as_deref(), Some("MYAPP__ROGUE_KEY"));
-        let msg = err.to_string();
-        assert!(msg.contains("MYAPP__ROGUE_KEY"), "{msg}");
-        assert!(!msg.contains("config file"), "{msg}");
+        let text = "[profile.prod]\nport = \"x\"\n";
+        let err = resolve(profile_input(&spec, vec![("a.toml", text)], Some("prod"))).unwrap_err();
+        let facts = assert_invalid_value(&err, "port", crate::types::InputType::File);
+        assert_eq!(facts.line_col(), Some((2, 8)));
     }
 
     #[test]
-    fn env_unknown_key_names_the_mixed_case_variable() {
-        // The suffix is accepted in any case and lowercased for the
-        // table path. The error must name the spelling that is actually
-        // set — unsetting a reconstructed MYAPP__ROGUE_KEY would leave
-        // MYAPP__rogue_key in place on a case-sensitive platform.
+    fn profile_names_follow_key_normalization() {
         let spec = test_spec();
         let input = ResolveInput {
-            env_vars: vec![("MYAPP__rogue_key".into(), "1".into())],
-            env_prefix: Some("MYAPP".into()),
-            ..empty_input(&spec)
+            normalize_keys: true,
+            ..profile_input(
+                &spec,
+                vec![(
+                    "a.toml",
+                    "[profile.load-test]\nport = 9\n\n[profile.load-test.database]\npool-size = 3\n",
+                )],
+                Some("load-test"),
+            )
         };
-        let err = resolve(input).unwrap_err();
-        let keys = err.unknown_keys().expect("expected UnknownKeys");
-        assert_eq!(keys.len(), 1);
-        assert_eq!(keys[0].key, "rogue_key");
-        assert_eq!(keys[0].env_var.as_deref(), Some("MYAPP__rogue_key"));
-        let msg = err.to_string();
-        assert!(msg.contains("MYAPP__rogue_key"), "{msg}");
-        assert!(!msg.contains("MYAPP__ROGUE_KEY"), "{msg}");
+        let (table, _) = resolve(input).unwrap();
+        assert_eq!(get(&table, "port").unwrap().as_integer(), Some(9));
+        assert_eq!(
+            get(&table, "database.pool_size").unwrap().as_integer(),
+            Some(3)
+        );
     }
 
     #[test]
-    fn env_unknown_nested_section_names_the_variable() {
-        // No `database` field: the walker reports `database`, not
-        // `database.rogue`. The original variable must still be named.
-        use crate::runtime::{Field, Schema};
+    fn profile_section_secrets_are_masked_in_every_origin() {
+        use crate::runtime::Field;
         let spec = Schema::object("App")
-            .field("host", Field::string().default("localhost"))
+            .field("host", Field::string().default("h"))
+            .field("password", Field::string().optional().sensitive())
             .build();
-        let input = ResolveInput {
-            env_vars: vec![("MYAPP__DATABASE__ROGUE".into(), "1".into())],
-            env_prefix: Some("MYAPP".into()),
-            ..empty_input(&spec)
-        };
-        let err = resolve(input).unwrap_err();
-        let keys = err.unknown_keys().expect("expected UnknownKeys");
-        assert_eq!(keys.len(), 1);
-        assert_eq!(keys[0].key, "database");
-        assert_eq!(keys[0].env_var.as_deref(), Some("MYAPP__DATABASE__ROGUE"));
+        let text = "host = \"base\"\n\n[profile.prod]\npassword = \"hunter2\"\n";
+        let (_, _, origins) =
+            resolve_with_origins(profile_input(&spec, vec![("a.toml", text)], Some("prod")))
+                .unwrap();
+        for key in ["host", "password"] {
+            let facts = crate::origin::lookup(&origins, &ConfigPath::new().key(key))
+                .unwrap()
+                .to_facts();
+            let source = facts.source.expect("file origin");
+            assert!(!source.contains("hunter2"), "{source}");
+        }
     }
 
     #[test]
@@ -1308,6 +2513,146 @@ mod tests {
         );
     }
 
+    #[test]
+    fn constraint_failures_are_located_invalid_values() {
+        use crate::runtime::Field;
+        let spec = Schema::object("App")
+            .field("name", Field::string().pattern("^[a-z-]+$"))
+            .field(
+                "ratio",
+                Field::float().exclusive_min(0.0).max(1.0).default(0.5),
+            )
+            .field(
+                "hosts",
+                Field::array_of_type(Field::string()).non_empty().unique(),
+            )
+            .build();
+        let source = "name = \"My App\"\nhosts = [\"a\"]\n";
+        let input = ResolveInput {
+            files: vec![("app.toml".into(), source.into())],
+            ..empty_input(&spec)
+        };
+        let err = resolve(input).unwrap_err();
+        let facts = assert_invalid_value(&err, "name", crate::types::InputType::File);
+        let span = facts.span.expect("value span");
+        assert_eq!(&source[span.start..span.end], "\"My App\"");
+        assert!(err.to_string().contains("does not match pattern"), "{err}");
+
+        let source = "name = \"app\"\nhosts = [\"a\", \"a\"]\n";
+        let input = ResolveInput {
+            files: vec![("app.toml".into(), source.into())],
+            ..empty_input(&spec)
+        };
+        let err = resolve(input).unwrap_err();
+        let facts = assert_invalid_value(&err, "hosts", crate::types::InputType::File);
+        let span = facts.span.expect("array span");
+        assert_eq!(&source[span.start..span.end], "[\"a\", \"a\"]");
+        assert!(
+            err.to_string().contains("array[1] duplicates array[0]"),
+            "{err}"
+        );
+
+        let input = ResolveInput {
+            files: vec![(
+                "app.toml".into(),
+                "name = \"app\"\nhosts = [\"a\"]\n".into(),
+            )],
+            cli_overrides: vec![("ratio".into(), Value::Float(0.0))],
+            ..empty_input(&spec)
+        };
+        let err = resolve(input).unwrap_err();
+        assert_invalid_value(&err, "ratio", crate::types::InputType::Override);
+    }
+
+    #[test]
+    fn path_leaves_resolve_against_the_file_that_set_them() {
+        use crate::paths::Entry;
+        use crate::runtime::Field;
+        let spec = Schema::object("App")
+            .field("data", Field::path())
+            .field("cache", Field::path().expand_vars().optional())
+            .field("logs", Field::path().is_dir().optional())
+            .build();
+        let input = |source: &str, overrides: Vec<(String, Value)>| ResolveInput {
+            files: vec![("/etc/app/app.toml".into(), source.into())],
+            cli_overrides: overrides,
+            lookup_env: |name| (name == "CACHE_ROOT").then(|| "/var/cache".into()),
+            stat_path: |path| match path.to_str() {
+                Some("/etc/app/logs") => Some(Entry::Dir),
+                Some("/etc/app/app.log") => Some(Entry::File),
+                _ => None,
+            },
+            ..empty_input(&spec)
+        };
+
+        let source = "data = \"state\"\ncache = \"$CACHE_ROOT/app\"\nlogs = \"logs\"\n";
+        let (table, _) = resolve(input(source, vec![])).unwrap();
+        assert_eq!(
+            get(&table, "data").unwrap().as_str(),
+            Some("/etc/app/state")
+        );
+        assert_eq!(
+            get(&table, "cache").unwrap().as_str(),
+            Some("/var/cache/app")
+        );
+        assert_eq!(get(&table, "logs").unwrap().as_str(), Some("/etc/app/logs"));
+
+        // An override is not in any file: it stays relative to the
+        // working directory.
+        let overrides = vec![("data".into(), Value::String("state".into()))];
+        let (table, _) = resolve(input("data = \"x\"\n", overrides)).unwrap();
+        assert_eq!(get(&table, "data").unwrap().as_str(), Some("state"));
+
+        let source = "data = \"d\"\nlogs = \"app.log\"\n";
+        let err = resolve(input(source, vec![])).unwrap_err();
+        let facts = assert_invalid_value(&err, "logs", crate::types::InputType::File);
+        let span = facts.span.expect("value span");
+        assert_eq!(&source[span.start..span.end], "\"app.log\"");
+        assert!(
+            err.to_string()
+                .contains("path '/etc/app/app.log' is not a directory"),
+            "{err}"
+        );
+
+        let err = resolve(input("data = \"d\"\ncache = \"$NOPE\"\n", vec![])).unwrap_err();
+        assert_invalid_value(&err, "cache", crate::types::InputType::File);
+        assert!(err.to_string().contains("NOPE is not set"), "{err}");
+
+        let err = resolve(input("data = \"\"\n", vec![])).unwrap_err();
+        assert_invalid_value(&err, "data", crate::types::InputType::File);
+        assert!(err.to_string().contains("path is empty"), "{err}");
+
+        if let Some(user) = directories::UserDirs::new() {
+            let (table, _) = resolve(input("data = \"~\"\n", vec![])).unwrap();
+            assert_eq!(
+                get(&table, "data").unwrap().as_str(),
+                user.home_dir().to_str()
+            );
+        }
+    }
+
+    #[test]
+    fn interpolation_leaves_expand_vars_path_leaves_to_the_environment() {
+        use crate::runtime::Field;
+        let spec = Schema::object("App")
+            .field("base", Field::string())
+            .field("data", Field::path())
+            .field("cache", Field::path().expand_vars())
+            .build();
+        let source = "base = \"/srv\"\ndata = \"${base}/state\"\ncache = \"${CACHE_ROOT}/x\"\n";
+        let input = ResolveInput {
+            files: vec![("/etc/app/app.toml".into(), source.into())],
+            interpolate: true,
+            lookup_env: |name| (name == "CACHE_ROOT").then(|| "/var/cache".into()),
+            ..empty_input(&spec)
+        };
+        let (table, _) = resolve(input).unwrap();
+        // A plain path leaf interpolates `${base}` as a key reference.
+        assert_eq!(get(&table, "data").unwrap().as_str(), Some("/srv/state"));
+        // An expand_vars leaf reads `${CACHE_ROOT}` from the environment.
+        assert_eq!(get(&table, "cache").unwrap().as_str(), Some("/var/cache/x"));
+    }
+
     fn assert_yaml_root_invalid(source: &str, expected_slice: &str) {
         let spec = test_spec();
         let input = ResolveInput {
@@ -1555,4 +2900,184 @@ mod tests {
         assert!(!msg.contains("set by"), "{msg}");
         assert!(!msg.contains("-->"), "{msg}");
     }
+
+    fn warnings_of(input: ResolveInput<'_>) -> Vec<Warning> {
+        resolve_with_diagnostics(input).unwrap().3
+    }
+
+    fn diagnostics_spec() -> Schema {
+        use crate::runtime::Field;
+        Schema::object("App")
+            .field("port", Field::integer().default(8080))
+            .field("token", Field::string().optional().sensitive())
+            .field(
+                "verbose",
+                Field::boolean().optional().deprecated("use `log_level`"),
+            )
+            .build()
+    }
+
+    #[test]
+    fn deprecated_keys_are_returned_as_located_warnings() {
+        let spec = diagnostics_spec();
+        let text = "port = 1\nverbose = true\n";
+        let input = ResolveInput {
+            files: vec![("a.toml".into(), text.into())],
+            cli_overrides: vec![("verbose".into(), Value::Boolean(false))],
+            ..empty_input(&spec)
+        };
+        let warnings = warnings_of(input);
+        assert_eq!(warnings.len(), 2, "{warnings:?}");
+        let Warning::DeprecatedKey { key, note, origin } = &warnings[0] else {
+            panic!("expected DeprecatedKey, got {:?}", warnings[0]);
+        };
+        assert_eq!(
+            (key.as_str(), note.as_str()),
+            ("verbose", "use `log_level`")
+        );
+        assert_eq!(origin.line_col(), Some((2, 11)));
+        let rendered = warnings[0].to_string();
+        assert_eq!(
+            rendered,
+            "Deprecated key 'verbose': use `log_level`\n  --> a.toml:2"
+        );
+        assert_eq!(
+            warnings[1].origin().unwrap().input_type,
+            Some(crate::types::InputType::Override)
+        );
+    }
+
+    #[test]
+    fn env_var_overriding_a_file_scalar_warns_only_when_env_wins() {
+        let spec = diagnostics_spec();
+        let input = |order: Option<Vec<Layer>>| ResolveInput {
+            files: vec![("a.toml".into(), "port = 1\n".into())],
+            env_prefix: Some("MYAPP".into()),
+            env_vars: vec![("MYAPP__PORT".into(), "2".into())],
+            layer_order: order,
+            ..empty_input(&spec)
+        };
+        let warnings = warnings_of(input(None));
+        assert_eq!(warnings.len(), 1, "{warnings:?}");
+        let Warning::EnvShadowsFile { key, env_var, file } = &warnings[0] else {
+            panic!("expected EnvShadowsFile, got {:?}", warnings[0]);
+        };
+        assert_eq!((key.as_str(), env_var.as_str()), ("port", "MYAPP__PORT"));
+        assert_eq!(file.file.as_deref(), Some(Path::new("a.toml")));
+        assert_eq!(file.line_col(), Some((1, 8)));
+
+        let files_win = warnings_of(input(Some(vec![Layer::Env, Layer::Files])));
+        assert!(files_win.is_empty(), "{files_win:?}");
+    }
+
+    #[test]
+    fn world_readable_file_holding_secrets_warns() {
+        let spec = diagnostics_spec();
+        let input = |text: &str, file_mode: FileMode| ResolveInput {
+            files: vec![("a.toml".into(), text.into())],
+            file_mode,
+            ..empty_input(&spec)
+        };
+        let warnings = warnings_of(input("token = \"s3cret\"\n", |_| Some(0o100644)));
+        assert_eq!(
+            warnings,
+            vec![Warning::WorldReadableSecrets {
+                path: "a.toml".into(),
+                mode: 0o100644,
+            }]
+        );
+        assert!(
+            warnings[0].to_string().contains("(mode 644)"),
+            "{}",
+            warnings[0]
+        );
+
+        let private = warnings_of(input("token = \"s3cret\"\n", |_| Some(0o100600)));
+        assert!(private.is_empty(), "{private:?}");
+        let no_secret = warnings_of(input("port = 1\n", |_| Some(0o100644)));
+        assert!(no_secret.is_empty(), "{no_secret:?}");
+    }
+
+    #[test]
+    fn one_file_name_in_two_formats_warns() {
+        use std::sync::OnceLock;
+        static REGISTRY: OnceLock<FormatRegistry> = OnceLock::new();
+        let registry = REGISTRY.get_or_init(|| {
+            let mut r = FormatRegistry::new();
+            r.register(Box::new(crate::format::TomlAdapter));
+            r.register(Box::new(crate::format::YamlAdapter));
+            r
+        });
+        let spec = diagnostics_spec();
+        let input = |second: &str| ResolveInput {
+            registry,
+            files: vec![
+                ("/home/app.toml".into(), "port = 1\n".into()),
+                (second.into(), "port: 2\n".into()),
+            ],
+            ..empty_input(&spec)
+        };
+        let warnings = warnings_of(input("/work/app.yaml"));
+        assert_eq!(
+            warnings,
+            vec![Warning::MixedFormats {
+                files: vec!["/home/app.toml".into(), "/work/app.yaml".into()],
+            }]
+        );
+        assert!(warnings_of(input("/work/other.yaml")).is_empty());
+    }
+
+    #[test]
+    fn a_file_migrated_in_memory_warns_once() {
+        let spec = test_spec();
+        let input = |text: &str| ResolveInput {
+            files: vec![("a.toml".into(), text.into())],
+            profile_key: Some("profile".into()),
+            migrations: vec![Migration::rename("db", "database")],
+            ..empty_input(&spec)
+        };
+        let warnings = warnings_of(input(
+            "[db]\nurl = \"pg://a\"\n\n[profile.prod.db]\nurl = \"pg://b\"\n",
+        ));
+        assert_eq!(
+            warnings,
+            vec![Warning::UnmigratedFile {
+                path: "a.toml".into(),
+                migrations: vec!["renamed `db` to `database`".into()],
+            }]
+        );
+        assert_eq!(
+            warnings[0].to_string(),
+            "a.toml needs migration (renamed `db` to `database`) — rewrite it with `config migrate`"
+        );
+        assert!(warnings_of(input("[database]\nurl = \"pg://a\"\n")).is_empty());
+    }
+
+    #[test]
+    fn denied_warning_kinds_fail_the_resolve() {
+        let spec = diagnostics_spec();
+        let input = |deny: Vec<WarningKind>| ResolveInput {
+            files: vec![("a.toml".into(), "verbose = true\n".into())],
+            env_prefix: Some("MYAPP".into()),
+            env_vars: vec![("MYAPP__VERBOSE".into(), "false".into())],
+            deny_warnings: deny,
+            ..empty_input(&spec)
+        };
+        let allowed = warnings_of(input(vec![WarningKind::MixedFormats]));
+        assert_eq!(allowed.len(), 3, "{allowed:?}");
+
+        let err = resolve(input(vec![WarningKind::EnvShadowsFile])).unwrap_err();
+        let denied = err.denied_warnings().expect("DeniedWarnings");
+        assert_eq!(denied.len(), 1);
+        assert_eq!(denied[0].kind(), WarningKind::EnvShadowsFile);
+        assert_eq!(
+            err.to_string(),
+            "Warning denied by policy:\n\
+             Environment variable MYAPP__VERBOSE overrides 'verbose' from a config file\n  \
+             --> a.toml:1"
+        );
+
+        let all = resolve(input(WarningKind::ALL.to_vec())).unwrap_err();
+        assert_eq!(all.denied_warnings().map(<[_]>::len), Some(3));
+    }
 }
@@ -110,11 +110,10 @@ impl SchemaBuilder {
     /// cascade lookup) down the line.
     pub fn field(mut self, name: impl Into<String>, field: impl Into<Shape>) -> Self {
         let name = name.into();
+        let field = field.into();
         validate_field_name(&self.schema, &name);
-        self.schema.fields.push(NamedField {
-            name,
-            field: field.into(),
-        });
+        validate_aliases(&self.schema, &name, field.aliases());
+        self.schema.fields.push(NamedField { name, field });
         self
     }
 
@@ -240,6 +239,21 @@ impl Field {
         FieldBuilder::new(LeafType::DateTime)
     }
 
+    /// Start a leaf builder for a filesystem path (see
+    /// [`LeafType::Path`]). `~` expands and a relative path resolves
+    /// against the config file that set it;
+    /// [`expand_vars`](FieldBuilder::expand_vars),
+    /// [`must_exist`](FieldBuilder::must_exist),
+    /// [`is_dir`](FieldBuilder::is_dir), and
+    /// [`is_file`](FieldBuilder::is_file) add to that.
+    pub fn path() -> FieldBuilder {
+        FieldBuilder::new(LeafType::Path {
+            expand_vars: false,
+            must_exist: false,
+            kind: None,
+        })
+    }
+
     /// Start a builder for a homogeneous array whose item is `item`.
     ///
     /// A `LeafType` or [`FieldBuilder`] converts to a leaf item; an
@@ -296,6 +310,177 @@ pub struct Leaf {
     /// Optional explicit env-var name override. Without this, the env layer
     /// derives names from the field path (`PREFIX__SECTION__FIELD`).
     pub env: Option<String>,
+    /// `true` for secrets (API tokens, passwords). The value is replaced
+    /// with `<redacted>` wherever clapfig displays it — `config
+    /// list`/`get`/`explain`/`diff` output, `config set` confirmations,
+    /// `InvalidValue` reasons and source snippets — and the JSON Schema
+    /// property is marked `writeOnly`. Loading is unaffected.
+    pub sensitive: bool,
+    /// `true` for a file-reference leaf: the value any layer supplies is
+    /// a path, and loading replaces it with that file's contents (one
+    /// trailing newline trimmed). A relative path resolves against the
+    /// directory of the config file that set it, `~/` against the home
+    /// directory. The origin records the file read; a read failure is an
+    /// [`InvalidValue`](crate::error::ClapfigError::InvalidValue) located
+    /// at the key. Only valid on [`LeafType::String`].
+    pub from_file: bool,
+    /// Other names the field accepts in every input — files, env vars,
+    /// URL parameters, and overrides. Each input is rewritten to the
+    /// field's own name before it is validated; giving one field two of
+    /// its spellings in the same input is an
+    /// [`AliasCollision`](crate::error::ClapfigError::AliasCollision).
+    pub aliases: Vec<String>,
+    /// Deprecation note (`"use X instead"`). Any input that sets the
+    /// field emits a warning naming where it was set; JSON Schema marks
+    /// the property `deprecated` and `config gen` leaves it out.
+    pub deprecated: Option<String>,
+    /// Declarative value checks run after the type check: a string
+    /// pattern and length, float bounds.
+    pub constraints: Constraints,
+}
+
+impl Leaf {
+    /// [`LeafType::check`], then the leaf's [`Constraints`], keeping the
+    /// value out of the reason when the leaf is
+    /// [`sensitive`](Self::sensitive).
+    pub(crate) fn check(&self, value: &Value) -> Result<(), String> {
+        self.ty.check_shown(value, self.sensitive)?;
+        self.constraints.check(value, self.sensitive)
+    }
+}
+
+/// Value constraints on a [`Leaf`], beyond its type.
+///
+/// String constraints apply to [`LeafType::String`] leaves and bounds to
+/// [`LeafType::Float`]; [`FieldBuilder`] panics on a mismatch. They are
+/// checked by the same walker as the type, so a failure is an
+/// [`InvalidValue`](crate::error::ClapfigError::InvalidValue) naming the
+/// key and the input that set it. JSON Schema export carries each one as
+/// its keyword (`pattern`, `minLength`, `exclusiveMinimum`, …).
+#[derive(Debug, Clone, Default)]
+pub struct Constraints {
+    /// Regular expression the string must match. Unanchored, as in JSON
+    /// Schema: write `^…$` to match the whole value.
+    pub pattern: Option<Pattern>,
+    /// Fewest characters the string may hold.
+    pub min_len: Option<usize>,
+    /// Most characters the string may hold.
+    pub max_len: Option<usize>,
+    /// Inclusive lower bound on a float.
+    pub min: Option<f64>,
+    /// Inclusive upper bound on a float.
+    pub max: Option<f64>,
+    /// Exclusive lower bound on a float.
+    pub exclusive_min: Option<f64>,
+    /// Exclusive upper bound on a float.
+    pub exclusive_max: Option<f64>,
+}
+
+impl Constraints {
+    fn has_bounds(&self) -> bool {
+        self.min.is_some()
+            || self.max.is_some()
+            || self.exclusive_min.is_some()
+            || self.exclusive_max.is_some()
+    }
+
+    /// Check a value that already passed its leaf's type check. Values
+    /// of other kinds (the members of an enum, a `Value` leaf) pass.
+    fn check(&self, value: &Value, redact: bool) -> Result<(), String> {
+        let shown = |v: &Value| {
+            if redact {
+                crate::redact::REDACTED.to_string()
+            } else {
+                format_value(v)
+            }
+        };
+        match value {
+            Value::String(s) => {
+                let len = s.chars().count();
+                if len == 0 && self.min_len.is_some_and(|lo| lo > 0) {
+                    return Err("value must not be empty".into());
+                }
+                if self.min_len.is_some_and(|lo| len < lo)
+                    || self.max_len.is_some_and(|hi| len > hi)
+                {
+                    return Err(format!(
+                        "value {} is {len} characters long (allowed: {})",
+                        shown(value),
+                        format_bounds(self.min_len, self.max_len)
+                    ));
+                }
+                if let Some(pattern) = &self.pattern
+                    && !pattern.is_match(s)
+                {
+                    return Err(format!(
+                        "value {} does not match pattern `{}`",
+                        shown(value),
+                        pattern.as_str()
+                    ));
+                }
+                Ok(())
+            }
+            Value::Float(_) | Value::Integer(_) if self.has_bounds() => {
+                let f = match value {
+                    Value::Float(f) => *f,
+                    Value::Integer(i) => *i as f64,
+                    _ => unreachable!("matched a number"),
+                };
+                let in_range = self.min.is_none_or(|lo| f >= lo)
+                    && self.max.is_none_or(|hi| f <= hi)
+                    && self.exclusive_min.is_none_or(|lo| f > lo)
+                    && self.exclusive_max.is_none_or(|hi| f < hi);
+                if in_range {
+                    Ok(())
+                } else {
+                    Err(format!(
+                        "value {} is out of range (allowed: {})",
+                        shown(value),
+                        self.float_bounds()
+                    ))
+                }
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// `>= 0 and < 1`, for range errors.
+    fn float_bounds(&self) -> String {
+        [
+            self.min.map(|v| format!(">= {v}")),
+            self.exclusive_min.map(|v| format!("> {v}")),
+            self.max.map(|v| format!("<= {v}")),
+            self.exclusive_max.map(|v| format!("< {v}")),
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join(" and ")
+    }
+}
+
+/// A compiled [`Constraints::pattern`], keeping its source for error
+/// messages and JSON Schema export.
+#[derive(Debug, Clone)]
+pub struct Pattern(regex::Regex);
+
+impl Pattern {
+    /// Compile `source`. The error is the reason it does not parse.
+    pub fn new(source: &str) -> Result<Self, String> {
+        regex::Regex::new(source)
+            .map(Self)
+            .map_err(|e| e.to_string())
+    }
+
+    /// The pattern as written.
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+
+    /// Whether `text` contains a match.
+    pub fn is_match(&self, text: &str) -> bool {
+        self.0.is_match(text)
+    }
 }
 
 /// Leaf type discriminant — the value-level shape clapfig validates.
@@ -318,6 +503,20 @@ pub enum LeafType {
     /// matching one of the forms are coerced during finalization —
     /// schema-driven coercion, per ADR-0001.
     DateTime,
+    /// Filesystem path, held as a string. Loading expands a leading `~`
+    /// to the home directory and, with `expand_vars`, `$VAR` / `${VAR}`
+    /// from the environment; a relative path set in a config file then
+    /// resolves against that file's directory (relative paths from other
+    /// inputs stay relative to the working directory). With `must_exist`
+    /// the path must exist; `kind` requires an existing file or
+    /// directory. A failure is an
+    /// [`InvalidValue`](crate::error::ClapfigError::InvalidValue) at the
+    /// key.
+    Path {
+        expand_vars: bool,
+        must_exist: bool,
+        kind: Option<PathKind>,
+    },
     /// Constrained value: must equal one of the listed values.
     Enum {
         values: Vec<Value>,
@@ -330,6 +529,15 @@ pub enum LeafType {
     Value,
 }
 
+/// What a [`LeafType::Path`] must name, when it names anything specific.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathKind {
+    /// A regular file (or a link to one).
+    File,
+    /// A directory (or a link to one).
+    Dir,
+}
+
 impl LeafType {
     /// Human-readable name for use in error messages.
     pub(crate) fn name(&self) -> &'static str {
@@ -339,6 +547,7 @@ impl LeafType {
             LeafType::Float => "float",
             LeafType::Bool => "bool",
             LeafType::DateTime => "datetime",
+            LeafType::Path { .. } => "path",
             LeafType::Enum { .. } => "enum",
             LeafType::Value => "value",
         }
@@ -355,12 +564,26 @@ impl LeafType {
     /// returns a human-readable reason suitable for
     /// `ClapfigError::InvalidValue::reason`.
     pub(crate) fn check(&self, value: &Value) -> Result<(), String> {
+        self.check_shown(value, false)
+    }
+
+    /// [`check`](Self::check), naming the value in the reason as
+    /// `<redacted>` when `redact` is set.
+    fn check_shown(&self, value: &Value, redact: bool) -> Result<(), String> {
+        let shown = |v: &Value| {
+            if redact {
+                crate::redact::REDACTED.to_string()
+            } else {
+                format_value(v)
+            }
+        };
         match (self, value) {
             (LeafType::String, Value::String(_)) => Ok(()),
             (LeafType::Integer { min, max }, Value::Integer(i)) => {
                 if min.is_some_and(|lo| *i < lo) || max.is_some_and(|hi| *i > hi) {
                     Err(format!(
-                        "value {i} is out of range (allowed: {})",
+                        "value {} is out of range (allowed: {})",
+                        shown(value),
                         format_bounds(*min, *max)
                     ))
                 } else {
@@ -375,6 +598,7 @@ impl LeafType {
             (LeafType::Float, Value::Integer(_)) => Ok(()),
             (LeafType::Bool, Value::Boolean(_)) => Ok(()),
             (LeafType::DateTime, Value::Datetime(_)) => Ok(()),
+            (LeafType::Path { .. }, Value::String(_)) => Ok(()),
             (LeafType::Enum { values }, v) => {
                 if values.iter().any(|allowed| allowed == v) {
                     Ok(())
@@ -386,7 +610,7 @@ impl LeafType {
                         .join(" | ");
                     Err(format!(
                         "value {} is not in allowed set: {listed}",
-                        format_value(v)
+                        shown(v)
                     ))
                 }
             }
@@ -416,11 +640,16 @@ impl FieldBuilder {
                 default: None,
                 optional: false,
                 env: None,
+                sensitive: false,
+                from_file: false,
+                aliases: Vec::new(),
+                deprecated: None,
+                constraints: Constraints::default(),
             }),
         }
     }
 
-    fn from_shape(shape: Shape) -> Self {
+    pub(crate) fn from_shape(shape: Shape) -> Self {
         Self { shape }
     }
 
@@ -483,6 +712,287 @@ impl FieldBuilder {
         self
     }
 
+    /// Accept `name` as another spelling of this field in every input
+    /// (see [`Leaf::aliases`]). Repeatable. Panics on a name that is not
+    /// a valid path segment, like [`SchemaBuilder::field`].
+    pub fn alias(mut self, name: impl Into<String>) -> Self {
+        let name = name.into();
+        validate_path_segment("field alias", &name);
+        match &mut self.shape {
+            Shape::Leaf(leaf) => leaf.aliases.push(name),
+            Shape::Map(map) => map.aliases.push(name),
+            Shape::Array(array) => array.aliases.push(name),
+            other => panic!(
+                "clapfig: .alias() is only valid on Leaf, Map, and Array, got {}",
+                other.constructor_name()
+            ),
+        }
+        self
+    }
+
+    /// Mark this field deprecated, with a note such as `"use X
+    /// instead"` (see [`Leaf::deprecated`]).
+    pub fn deprecated(mut self, note: impl Into<String>) -> Self {
+        let note = note.into();
+        match &mut self.shape {
+            Shape::Leaf(leaf) => leaf.deprecated = Some(note),
+            Shape::Map(map) => map.deprecated = Some(note),
+            Shape::Array(array) => array.deprecated = Some(note),
+            other => panic!(
+                "clapfig: .deprecated() is only valid on Leaf, Map, and Array, got {}",
+                other.constructor_name()
+            ),
+        }
+        self
+    }
+
+    /// Mark this field as a secret: its value is redacted wherever
+    /// clapfig displays it (see [`Leaf::sensitive`]). On a map or array
+    /// of leaves, marks the item leaf, so every entry is a secret.
+    pub fn sensitive(mut self) -> Self {
+        let mut current = &mut self.shape;
+        loop {
+            match current {
+                Shape::Leaf(leaf) => {
+                    leaf.sensitive = true;
+                    break;
+                }
+                Shape::Map(map) => current = map.item.as_mut(),
+                Shape::Array(array) => current = array.item.as_mut(),
+                other => panic!(
+                    "clapfig: .sensitive() is only valid on Leaf, or a Map/Array of leaves, got {}",
+                    other.constructor_name()
+                ),
+            }
+        }
+        self
+    }
+
+    /// Mark this field a file reference: its value names a file whose
+    /// contents load in its place (see [`Leaf::from_file`]). Only valid
+    /// on [`Field::string()`].
+    pub fn from_file(mut self) -> Self {
+        match &mut self.shape {
+            Shape::Leaf(leaf) if matches!(leaf.ty, LeafType::String) => leaf.from_file = true,
+            other => panic!(
+                "clapfig: .from_file() is only valid on a String leaf, got {}",
+                other.constructor_name()
+            ),
+        }
+        self
+    }
+
+    /// Require the string to match the regular expression `pattern` (see
+    /// [`Constraints::pattern`]). Like [`sensitive`](Self::sensitive),
+    /// lands on the item leaf of a map or array of strings. Panics if
+    /// the pattern does not compile, or on a non-string leaf.
+    pub fn pattern(mut self, pattern: &str) -> Self {
+        let pattern = Pattern::new(pattern)
+            .unwrap_or_else(|e| panic!("clapfig: invalid .pattern() {pattern:?}: {e}"));
+        self.constrained_leaf("pattern", is_string)
+            .constraints
+            .pattern = Some(pattern);
+        self
+    }
+
+    /// Require at least `n` characters (string leaves, or the item of a
+    /// map or array of strings).
+    pub fn min_len(mut self, n: usize) -> Self {
+        let constraints = &mut self.constrained_leaf("min_len", is_string).constraints;
+        constraints.min_len = Some(n);
+        check_range("min_len", constraints.min_len, constraints.max_len);
+        self
+    }
+
+    /// Allow at most `n` characters (string leaves, or the item of a map
+    /// or array of strings).
+    pub fn max_len(mut self, n: usize) -> Self {
+        let constraints = &mut self.constrained_leaf("max_len", is_string).constraints;
+        constraints.max_len = Some(n);
+        check_range("max_len", constraints.min_len, constraints.max_len);
+        self
+    }
+
+    /// Reject an empty value: on a string leaf, `min_len(1)`; on an
+    /// array, [`min_items(1)`](Self::min_items).
+    pub fn non_empty(self) -> Self {
+        match &self.shape {
+            Shape::Leaf(_) => {
+                let mut this = self;
+                let leaf = this.constrained_leaf("non_empty", is_string);
+                let n = leaf.constraints.min_len.unwrap_or(0).max(1);
+                this.min_len(n)
+            }
+            Shape::Array(array) => {
+                let n = array.min_items.unwrap_or(0).max(1);
+                self.min_items(n)
+            }
+            other => panic!(
+                "clapfig: .non_empty() is only valid on a String leaf or an Array, got {}",
+                other.constructor_name()
+            ),
+        }
+    }
+
+    /// Inclusive lower bound (float leaves, or the item of a map or array
+    /// of floats).
+    pub fn min(mut self, value: f64) -> Self {
+        let constraints = &mut self.constrained_leaf("min", is_float).constraints;
+        constraints.min = Some(value);
+        check_float_range("min", constraints);
+        self
+    }
+
+    /// Inclusive upper bound (float leaves, or the item of a map or array
+    /// of floats).
+    pub fn max(mut self, value: f64) -> Self {
+        let constraints = &mut self.constrained_leaf("max", is_float).constraints;
+        constraints.max = Some(value);
+        check_float_range("max", constraints);
+        self
+    }
+
+    /// Exclusive lower bound: the value must be greater than `value`.
+    pub fn exclusive_min(mut self, value: f64) -> Self {
+        let constraints = &mut self.constrained_leaf("exclusive_min", is_float).constraints;
+        constraints.exclusive_min = Some(value);
+        check_float_range("exclusive_min", constraints);
+        self
+    }
+
+    /// Exclusive upper bound: the value must be less than `value`.
+    pub fn exclusive_max(mut self, value: f64) -> Self {
+        let constraints = &mut self.constrained_leaf("exclusive_max", is_float).constraints;
+        constraints.exclusive_max = Some(value);
+        check_float_range("exclusive_max", constraints);
+        self
+    }
+
+    /// Expand `$VAR` and `${VAR}` in a path from the environment (`$$`
+    /// is a literal `$`); an unset variable fails the load. With
+    /// [`interpolate`](crate::Builder::interpolate) on, interpolation
+    /// skips the leaf, so `${VAR}` is never a key reference. Valid on a
+    /// path leaf or a map or array of paths, like the rest of the path
+    /// options below.
+    pub fn expand_vars(mut self) -> Self {
+        if let LeafType::Path { expand_vars, .. } = &mut self.path_leaf("expand_vars").ty {
+            *expand_vars = true;
+        }
+        self
+    }
+
+    /// Require the path to exist.
+    pub fn must_exist(mut self) -> Self {
+        if let LeafType::Path { must_exist, .. } = &mut self.path_leaf("must_exist").ty {
+            *must_exist = true;
+        }
+        self
+    }
+
+    /// Require the path to name an existing directory.
+    ///
+    /// Panics if combined with [`is_file`](Self::is_file).
+    pub fn is_dir(self) -> Self {
+        self.path_kind("is_dir", PathKind::Dir)
+    }
+
+    /// Require the path to name an existing regular file.
+    ///
+    /// Panics if combined with [`is_dir`](Self::is_dir).
+    pub fn is_file(self) -> Self {
+        self.path_kind("is_file", PathKind::File)
+    }
+
+    fn path_kind(mut self, method: &str, wanted: PathKind) -> Self {
+        if let LeafType::Path { kind, .. } = &mut self.path_leaf(method).ty {
+            assert!(
+                kind.is_none_or(|k| k == wanted),
+                "clapfig: .is_dir() and .is_file() exclude each other"
+            );
+            *kind = Some(wanted);
+        }
+        self
+    }
+
+    fn path_leaf(&mut self, method: &str) -> &mut Leaf {
+        self.constrained_leaf(method, |ty| matches!(ty, LeafType::Path { .. }))
+    }
+
+    /// Require at least `n` items (see [`ArrayShape::min_items`]).
+    pub fn min_items(mut self, n: usize) -> Self {
+        let array = self.array_mut("min_items");
+        array.min_items = Some(n);
+        check_range("min_items", array.min_items, array.max_items);
+        self
+    }
+
+    /// Allow at most `n` items (see [`ArrayShape::max_items`]).
+    pub fn max_items(mut self, n: usize) -> Self {
+        let array = self.array_mut("max_items");
+        array.max_items = Some(n);
+        check_range("max_items", array.min_items, array.max_items);
+        self
+    }
+
+    /// Reject arrays holding two equal items (see [`ArrayShape::unique`]).
+    pub fn unique(mut self) -> Self {
+        self.array_mut("unique").unique = true;
+        self
+    }
+
+    /// The leaf a value constraint lands on: this leaf, or the item leaf
+    /// of a map or array of leaves. Panics naming `.method()` unless it
+    /// passes `accepts`.
+    fn constrained_leaf(&mut self, method: &str, accepts: fn(&LeafType) -> bool) -> &mut Leaf {
+        let mut current = &mut self.shape;
+        while let Shape::Map(_) | Shape::Array(_) = current {
+            current = match current {
+                Shape::Map(map) => map.item.as_mut(),
+                Shape::Array(array) => array.item.as_mut(),
+                _ => unreachable!("loop condition matched a container"),
+            };
+        }
+        match current {
+            Shape::Leaf(leaf) => {
+                assert!(
+                    accepts(&leaf.ty),
+                    "clapfig: .{method}() is not valid on a leaf of type {}",
+                    leaf.ty.name()
+                );
+                leaf
+            }
+            other => panic!(
+                "clapfig: .{method}() is only valid on Leaf, or a Map/Array of leaves, got {}",
+                other.constructor_name()
+            ),
+        }
+    }
+
+    fn array_mut(&mut self, method: &str) -> &mut ArrayShape {
+        match &mut self.shape {
+            Shape::Array(array) => array,
+            other => panic!(
+                "clapfig: .{method}() is only valid on Array, got {}",
+                other.constructor_name()
+            ),
+        }
+    }
+
+    /// Set how a higher layer's array or map combines with this one (see
+    /// [`MergeStrategy`]). Panics on a leaf, or on a strategy the node
+    /// does not take.
+    pub fn merge(mut self, strategy: MergeStrategy) -> Self {
+        if let Err(reason) = check_merge_strategy(&self.shape, &strategy) {
+            panic!("clapfig: {reason}");
+        }
+        match &mut self.shape {
+            Shape::Map(map) => map.merge = Some(strategy),
+            Shape::Array(array) => array.merge = Some(strategy),
+            _ => unreachable!("check_merge_strategy accepts only Map and Array"),
+        }
+        self
+    }
+
     pub(crate) fn build(self) -> Shape {
         self.shape
     }
@@ -542,6 +1052,18 @@ pub(crate) enum DocumentRoot<'a> {
     Tagged(&'a TaggedShape),
 }
 
+impl<'a> DocumentRoot<'a> {
+    /// The document root `shape` is, or `None` for a leaf or array.
+    pub(crate) fn of(shape: &'a Shape) -> Option<Self> {
+        match shape {
+            Shape::Object(schema) => Some(DocumentRoot::Object(schema)),
+            Shape::Map(map) => Some(DocumentRoot::Map(map)),
+            Shape::Tagged(tagged) => Some(DocumentRoot::Tagged(tagged)),
+            Shape::Leaf(_) | Shape::Array(_) => None,
+        }
+    }
+}
+
 impl Shape {
     /// A leaf node from a [`LeafType`]. Not a legal document root.
     pub fn leaf(ty: LeafType) -> Self {
@@ -551,6 +1073,11 @@ impl Shape {
             default: None,
             optional: false,
             env: None,
+            sensitive: false,
+            from_file: false,
+            aliases: Vec::new(),
+            deprecated: None,
+            constraints: Constraints::default(),
         })
     }
 
@@ -574,6 +1101,9 @@ impl Shape {
                 default: None,
                 optional: false,
                 env: None,
+                merge: None,
+                aliases: Vec::new(),
+                deprecated: None,
             },
         }
     }
@@ -589,6 +1119,12 @@ impl Shape {
                 default: None,
                 optional: false,
                 env: None,
+                merge: None,
+                aliases: Vec::new(),
+                deprecated: None,
+                min_items: None,
+                max_items: None,
+                unique: false,
             },
         }
     }
@@ -658,6 +1194,27 @@ impl Shape {
         }
     }
 
+    /// Field-site aliases (leaf / map / array); objects and tagged
+    /// unions take none.
+    pub(crate) fn aliases(&self) -> &[String] {
+        match self {
+            Shape::Leaf(leaf) => &leaf.aliases,
+            Shape::Map(map) => &map.aliases,
+            Shape::Array(array) => &array.aliases,
+            Shape::Object(_) | Shape::Tagged(_) => &[],
+        }
+    }
+
+    /// Field-site deprecation note (leaf / map / array).
+    pub(crate) fn deprecated(&self) -> Option<&str> {
+        match self {
+            Shape::Leaf(leaf) => leaf.deprecated.as_deref(),
+            Shape::Map(map) => map.deprecated.as_deref(),
+            Shape::Array(array) => array.deprecated.as_deref(),
+            Shape::Object(_) | Shape::Tagged(_) => None,
+        }
+    }
+
     /// True when this field renders and address as a value (scalar, enum,
     /// `Value`, or a homogeneous array/map of those) rather than a nested
     /// object, array-of-tables, or map-of-objects.
@@ -686,17 +1243,24 @@ impl Shape {
         }
     }
 
+    /// True for a [`sensitive`](Leaf::sensitive) leaf, or a homogeneous
+    /// array/map whose item leaf is one — a value redacted whole.
+    pub(crate) fn is_sensitive(&self) -> bool {
+        matches!(self.peel_containers(), Shape::Leaf(leaf) if leaf.sensitive)
+    }
+
     /// Type-check `value` against this shape. Object / tagged payloads
     /// are checked field-by-field by the walker; this only asserts the
     /// container kind. Homogeneous arrays and maps recurse into items.
     pub(crate) fn check_value(&self, value: &Value) -> Result<(), String> {
         match (self, value) {
-            (Shape::Leaf(leaf), v) => leaf.ty.check(v),
+            (Shape::Leaf(leaf), v) => leaf.check(v),
             (Shape::Object(_), Value::Map(_)) => Ok(()),
             (Shape::Object(_), other) => {
                 Err(format!("expected map, got {}", value_type_name(other)))
             }
             (Shape::Array(array), Value::Array(items)) => {
+                array.check_items(items)?;
                 for (i, item) in items.iter().enumerate() {
                     array
                         .item
@@ -762,6 +1326,7 @@ fn leaf_types_agree(a: &LeafType, b: &LeafType) -> bool {
         | (LeafType::Float, LeafType::Float)
         | (LeafType::Bool, LeafType::Bool)
         | (LeafType::DateTime, LeafType::DateTime)
+        | (LeafType::Path { .. }, LeafType::Path { .. })
         | (LeafType::Value, LeafType::Value) => true,
         (
             LeafType::Integer {
@@ -859,6 +1424,14 @@ pub struct MapShape {
     pub default: Option<Value>,
     pub optional: bool,
     pub env: Option<String>,
+    /// How a higher layer's map combines with this one. `None` merges
+    /// entry by entry; the only other strategy a map takes is
+    /// [`MergeStrategy::Replace`].
+    pub merge: Option<MergeStrategy>,
+    /// Other names the field accepts (see [`Leaf::aliases`]).
+    pub aliases: Vec<String>,
+    /// Deprecation note (see [`Leaf::deprecated`]).
+    pub deprecated: Option<String>,
 }
 
 impl MapShape {
@@ -871,6 +1444,9 @@ impl MapShape {
             default: None,
             optional: false,
             env: None,
+            merge: None,
+            aliases: Vec::new(),
+            deprecated: None,
         }
     }
 
@@ -883,6 +1459,9 @@ impl MapShape {
             default: None,
             optional: false,
             env: None,
+            merge: None,
+            aliases: Vec::new(),
+            deprecated: None,
         }
     }
 }
@@ -906,6 +1485,17 @@ impl MapShapeBuilder {
         self
     }
 
+    /// Set how a higher layer's map combines with this one. Maps merge
+    /// entry by entry unless this is [`MergeStrategy::Replace`]; any other
+    /// strategy panics.
+    pub fn merge(mut self, strategy: MergeStrategy) -> Self {
+        if let Err(reason) = check_map_merge(&strategy) {
+            panic!("clapfig: {reason}");
+        }
+        self.map.merge = Some(strategy);
+        self
+    }
+
     /// Finalize into a [`MapShape`].
     pub fn build(self) -> MapShape {
         self.map
@@ -927,6 +1517,19 @@ pub struct ArrayShape {
     pub default: Option<Value>,
     pub optional: bool,
     pub env: Option<String>,
+    /// How a higher layer's array combines with this one. `None` is
+    /// [`MergeStrategy::Replace`].
+    pub merge: Option<MergeStrategy>,
+    /// Other names the field accepts (see [`Leaf::aliases`]).
+    pub aliases: Vec<String>,
+    /// Deprecation note (see [`Leaf::deprecated`]).
+    pub deprecated: Option<String>,
+    /// Fewest items the merged array may hold.
+    pub min_items: Option<usize>,
+    /// Most items the merged array may hold.
+    pub max_items: Option<usize>,
+    /// `true` to reject an array holding two equal items.
+    pub unique: bool,
 }
 
 impl ArrayShape {
@@ -939,6 +1542,12 @@ impl ArrayShape {
             default: None,
             optional: false,
             env: None,
+            merge: None,
+            aliases: Vec::new(),
+            deprecated: None,
+            min_items: None,
+            max_items: None,
+            unique: false,
         }
     }
 
@@ -951,7 +1560,34 @@ impl ArrayShape {
             default: None,
             optional: false,
             env: None,
+            merge: None,
+            aliases: Vec::new(),
+            deprecated: None,
+            min_items: None,
+            max_items: None,
+            unique: false,
+        }
+    }
+
+    /// Check the item count and uniqueness of a merged array. Items are
+    /// named by index, never by value, so secrets stay out of the reason.
+    pub(crate) fn check_items(&self, items: &[Value]) -> Result<(), String> {
+        let len = items.len();
+        if self.min_items.is_some_and(|lo| len < lo) || self.max_items.is_some_and(|hi| len > hi) {
+            return Err(format!(
+                "array has {len} item{} (allowed: {})",
+                if len == 1 { "" } else { "s" },
+                format_bounds(self.min_items, self.max_items)
+            ));
+        }
+        if self.unique {
+            for (i, item) in items.iter().enumerate() {
+                if let Some(j) = items[..i].iter().position(|earlier| earlier == item) {
+                    return Err(format!("array[{i}] duplicates array[{j}]"));
+                }
+            }
         }
+        Ok(())
     }
 }
 
@@ -974,12 +1610,119 @@ impl ArrayShapeBuilder {
         self
     }
 
+    /// Set how a higher layer's array combines with this one (default
+    /// [`MergeStrategy::Replace`]). Panics on
+    /// [`MergeByKey`](MergeStrategy::MergeByKey) unless the item is an
+    /// object that declares the key field.
+    pub fn merge(mut self, strategy: MergeStrategy) -> Self {
+        if let Err(reason) = check_array_merge(&self.array.item, &strategy) {
+            panic!("clapfig: {reason}");
+        }
+        self.array.merge = Some(strategy);
+        self
+    }
+
+    /// Require at least `n` items.
+    pub fn min_items(mut self, n: usize) -> Self {
+        self.array.min_items = Some(n);
+        check_range("min_items", self.array.min_items, self.array.max_items);
+        self
+    }
+
+    /// Allow at most `n` items.
+    pub fn max_items(mut self, n: usize) -> Self {
+        self.array.max_items = Some(n);
+        check_range("max_items", self.array.min_items, self.array.max_items);
+        self
+    }
+
+    /// Reject arrays holding two equal items.
+    pub fn unique(mut self) -> Self {
+        self.array.unique = true;
+        self
+    }
+
     /// Finalize into an [`ArrayShape`].
     pub fn build(self) -> ArrayShape {
         self.array
     }
 }
 
+/// How an array or map in a higher layer combines with the same key from
+/// the layers below it.
+///
+/// Set per node with [`ArrayShapeBuilder::merge`],
+/// [`MapShapeBuilder::merge`], or [`FieldBuilder::merge`]; the derive
+/// spelling is `#[clapfig(merge = "append")]` /
+/// `#[clapfig(merge_by_key = "name")]`. Without one, arrays replace
+/// wholesale and maps merge entry by entry. Every element keeps the
+/// origin of the layer that supplied it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MergeStrategy {
+    /// The higher layer's value replaces the lower one whole. The array
+    /// default, and the one strategy a map takes.
+    Replace,
+    /// Lower layer's elements, then the higher layer's.
+    Append,
+    /// Higher layer's elements, then the lower layer's.
+    Prepend,
+    /// Like [`Append`](Self::Append), skipping elements equal to one
+    /// already present.
+    Union,
+    /// Arrays of objects: an element whose `key` field matches an earlier
+    /// element's merges into it, deep, like a map entry; the rest append.
+    MergeByKey(String),
+}
+
+impl MergeStrategy {
+    /// [`MergeStrategy::MergeByKey`] on `key`.
+    pub fn merge_by_key(key: impl Into<String>) -> Self {
+        Self::MergeByKey(key.into())
+    }
+}
+
+/// Check that `strategy` applies to `shape`: any strategy on an array
+/// (`MergeByKey` only when the item is an object declaring the key),
+/// only `Replace` on a map, nothing on a leaf, object, or union. The
+/// error is the reason, for the caller's authoring panic.
+pub(crate) fn check_merge_strategy(shape: &Shape, strategy: &MergeStrategy) -> Result<(), String> {
+    match shape {
+        Shape::Array(array) => check_array_merge(&array.item, strategy),
+        Shape::Map(_) => check_map_merge(strategy),
+        other => Err(format!(
+            ".merge() is only valid on Map and Array, got {}",
+            other.constructor_name()
+        )),
+    }
+}
+
+fn check_array_merge(item: &Shape, strategy: &MergeStrategy) -> Result<(), String> {
+    let MergeStrategy::MergeByKey(key) = strategy else {
+        return Ok(());
+    };
+    match item {
+        Shape::Object(item) if item.fields.iter().any(|nf| nf.name == *key) => Ok(()),
+        Shape::Object(item) => Err(format!(
+            "merge_by_key({key:?}) names a field `{}` does not declare",
+            item.name
+        )),
+        other => Err(format!(
+            "merge_by_key({key:?}) needs an array of objects, got an array of {}",
+            other.constructor_name()
+        )),
+    }
+}
+
+fn check_map_merge(strategy: &MergeStrategy) -> Result<(), String> {
+    if *strategy == MergeStrategy::Replace {
+        Ok(())
+    } else {
+        Err(format!(
+            "a map merges entry by entry or is replaced whole; {strategy:?} only applies to arrays"
+        ))
+    }
+}
+
 /// Internally tagged union of objects.
 ///
 /// The tag is declared here (serde `tag = "..."`) and is **not** a field
@@ -1010,7 +1753,7 @@ pub struct TaggedVariant {
 
 /// How a single key is declared across a tagged union's variants.
 ///
-/// Classification is shared; combination is not. The four consumers keep
+/// Classification is shared; combination is not. The five consumers keep
 /// distinct policies because they answer different questions — they must
 /// not be unified:
 ///
@@ -1029,6 +1772,9 @@ pub struct TaggedVariant {
 ///   when **every** variant declares the key (`Every`) and those
 ///   declarations [agree structurally](Shape::structurally_agrees_with).
 ///   `Partial` is unaddressable until a discriminator selects a branch.
+/// - **redact** (`redact_segments`): secret if **any** declaring variant
+///   marks it [`sensitive`](Leaf::sensitive) — a display that cannot
+///   tell the branch apart errs toward hiding the value.
 ///
 /// "Unknown only if every variant says unknown" is not "settable only if
 /// every variant agrees."
@@ -1236,25 +1982,89 @@ pub(crate) fn reject_variant_tag_clash<'a>(
 ///
 /// Path-segment rules are [`validate_path_segment`]. Duplicate names
 /// within one schema make `find_field` order-dependent and `valid_keys`
-/// collide.
+/// collide; another field's alias counts as a name.
 fn validate_field_name(schema: &Schema, name: &str) {
     validate_path_segment("field name", name);
     assert!(
-        !schema.fields.iter().any(|f| f.name == name),
+        !schema
+            .fields
+            .iter()
+            .any(|f| f.name == name || f.field.aliases().iter().any(|a| a == name)),
         "clapfig: duplicate field name {name:?} on schema {:?}",
         schema.name
     );
 }
 
+/// Reject aliases that would make one key name two fields: each must
+/// differ from every field name and alias already on the schema, from
+/// the field's own name, and from its other aliases.
+fn validate_aliases(schema: &Schema, name: &str, aliases: &[String]) {
+    for (i, alias) in aliases.iter().enumerate() {
+        assert!(
+            alias != name && !aliases[..i].contains(alias),
+            "clapfig: field {name:?} lists alias {alias:?} twice or as its own name"
+        );
+        assert!(
+            !schema
+                .fields
+                .iter()
+                .any(|f| f.name == *alias || f.field.aliases().contains(alias)),
+            "clapfig: alias {alias:?} of field {name:?} is already a key on schema {:?}",
+            schema.name
+        );
+    }
+}
+
 /// Human-readable spelling of an integer leaf's declared bounds for error
 /// messages: `0..=255`, `>= 0`, or `<= 100`. Callers never pass
 /// `(None, None)` (an unbounded integer has no range to violate).
-fn format_bounds(min: Option<i64>, max: Option<i64>) -> String {
+fn format_bounds<T: std::fmt::Display>(min: Option<T>, max: Option<T>) -> String {
     match (min, max) {
         (Some(lo), Some(hi)) => format!("{lo}..={hi}"),
         (Some(lo), None) => format!(">= {lo}"),
         (None, Some(hi)) => format!("<= {hi}"),
-        (None, None) => unreachable!("unbounded integers cannot be out of range"),
+        (None, None) => unreachable!("an unbounded value cannot be out of range"),
+    }
+}
+
+fn is_string(ty: &LeafType) -> bool {
+    matches!(ty, LeafType::String)
+}
+
+fn is_float(ty: &LeafType) -> bool {
+    matches!(ty, LeafType::Float)
+}
+
+/// Panic when a count or length range is empty (`min > max`), naming the
+/// builder method that made it so.
+fn check_range(method: &str, min: Option<usize>, max: Option<usize>) {
+    if let (Some(lo), Some(hi)) = (min, max) {
+        assert!(
+            lo <= hi,
+            "clapfig: .{method}() leaves an empty range (min {lo} > max {hi})"
+        );
+    }
+}
+
+/// Panic if the float bounds on `constraints` admit no value. A range
+/// with an exclusive end is empty once its ends meet.
+fn check_float_range(method: &str, constraints: &Constraints) {
+    let lower = [(constraints.min, false), (constraints.exclusive_min, true)];
+    let upper = [(constraints.max, false), (constraints.exclusive_max, true)];
+    for (lo, lo_open) in lower {
+        for (hi, hi_open) in upper {
+            if let (Some(lo), Some(hi)) = (lo, hi) {
+                let empty = if lo_open || hi_open {
+                    lo >= hi
+                } else {
+                    lo > hi
+                };
+                assert!(
+                    !empty,
+                    "clapfig: .{method}() leaves an empty range (lower {lo}, upper {hi})"
+                );
+            }
+        }
     }
 }
 
@@ -1460,6 +2270,207 @@ mod tests {
         let _ = Field::integer_in(None, None);
     }
 
+    #[test]
+    #[should_panic(expected = "from_file() is only valid on a String leaf")]
+    fn from_file_rejects_non_string_leaf() {
+        let _ = Field::integer().from_file();
+    }
+
+    #[test]
+    #[should_panic(expected = "alias \"port\" of field \"listen\" is already a key")]
+    fn alias_must_not_name_another_field() {
+        let _ = Schema::object("App")
+            .field("port", Field::integer())
+            .field("listen", Field::integer().alias("port"));
+    }
+
+    #[test]
+    #[should_panic(expected = "duplicate field name \"old\"")]
+    fn field_name_must_not_be_another_fields_alias() {
+        let _ = Schema::object("App")
+            .field("new", Field::string().alias("old"))
+            .field("old", Field::string());
+    }
+
+    #[test]
+    #[should_panic(expected = "only applies to arrays")]
+    fn map_merge_takes_only_replace() {
+        let _ = Field::map_of(Field::string()).merge(MergeStrategy::Append);
+    }
+
+    #[test]
+    #[should_panic(expected = "names a field `Plugin` does not declare")]
+    fn merge_by_key_needs_a_declared_key_field() {
+        let plugin = Schema::object("Plugin")
+            .field("id", Field::string())
+            .build();
+        let _ = Shape::array("plugins", plugin).merge(MergeStrategy::merge_by_key("name"));
+    }
+
+    #[test]
+    fn merge_strategies_land_on_the_node() {
+        let plugin = Schema::object("Plugin")
+            .field("name", Field::string())
+            .build();
+        let by_name = Shape::array("plugins", plugin)
+            .merge(MergeStrategy::merge_by_key("name"))
+            .build();
+        assert_eq!(
+            by_name.merge,
+            Some(MergeStrategy::MergeByKey("name".into()))
+        );
+        match Field::array_of_type(Field::string())
+            .merge(MergeStrategy::Union)
+            .build()
+        {
+            Shape::Array(array) => assert_eq!(array.merge, Some(MergeStrategy::Union)),
+            other => panic!("expected Array, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn string_constraints_check_pattern_and_length() {
+        let name = Shape::from(Field::string().pattern("^[a-z]+$").min_len(2).max_len(8));
+        assert!(name.check_value(&Value::String("api".into())).is_ok());
+        let err = name.check_value(&Value::String("API".into())).unwrap_err();
+        assert_eq!(err, "value \"API\" does not match pattern `^[a-z]+$`");
+        let err = name.check_value(&Value::String("a".into())).unwrap_err();
+        assert_eq!(err, "value \"a\" is 1 characters long (allowed: 2..=8)");
+        let err = name.check_value(&Value::String(String::new())).unwrap_err();
+        assert_eq!(err, "value must not be empty");
+    }
+
+    #[test]
+    fn constraint_reasons_redact_sensitive_values() {
+        let token = Shape::from(Field::string().pattern("^tok_").sensitive());
+        let err = token
+            .check_value(&Value::String("hunter2".into()))
+            .unwrap_err();
+        assert!(!err.contains("hunter2"), "{err}");
+        assert!(err.contains("<redacted>"), "{err}");
+    }
+
+    #[test]
+    fn float_bounds_accept_integers_and_honor_exclusivity() {
+        let ratio = Shape::from(Field::float().min(0.0).exclusive_max(1.0));
+        assert!(ratio.check_value(&Value::Float(0.0)).is_ok());
+        assert!(ratio.check_value(&Value::Integer(0)).is_ok());
+        let err = ratio.check_value(&Value::Float(1.0)).unwrap_err();
+        assert_eq!(err, "value 1 is out of range (allowed: >= 0 and < 1)");
+        assert!(ratio.check_value(&Value::Integer(-1)).is_err());
+    }
+
+    #[test]
+    fn value_constraints_land_on_array_items() {
+        let tags = Shape::from(Field::array_of_type(Field::string()).non_empty().max_len(3));
+        let Shape::Array(array) = &tags else {
+            panic!("expected Array");
+        };
+        assert_eq!(array.min_items, Some(1));
+        let err = tags.check_value(&Value::Array(vec![])).unwrap_err();
+        assert_eq!(err, "array has 0 items (allowed: >= 1)");
+        let err = tags
+            .check_value(&Value::Array(vec![Value::String("long".into())]))
+            .unwrap_err();
+        assert!(
+            err.starts_with("array[0]: value \"long\" is 4 characters"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn array_item_count_and_uniqueness() {
+        let ports = Shape::from(
+            Field::array_of_type(unbounded_integer())
+                .max_items(3)
+                .unique(),
+        );
+        let ints = |xs: &[i64]| Value::Array(xs.iter().copied().map(Value::Integer).collect());
+        assert!(ports.check_value(&ints(&[80, 443])).is_ok());
+        let err = ports.check_value(&ints(&[80, 443, 80])).unwrap_err();
+        assert_eq!(err, "array[2] duplicates array[0]");
+        let err = ports.check_value(&ints(&[1, 2, 3, 4])).unwrap_err();
+        assert_eq!(err, "array has 4 items (allowed: <= 3)");
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid .pattern() \"[a-\"")]
+    fn pattern_must_compile() {
+        let _ = Field::string().pattern("[a-");
+    }
+
+    #[test]
+    #[should_panic(expected = ".min() is not valid on a leaf of type integer")]
+    fn float_bounds_reject_integer_leaves() {
+        let _ = Field::integer().min(1.0);
+    }
+
+    #[test]
+    #[should_panic(expected = ".max() leaves an empty range (lower 5, upper 2)")]
+    fn float_range_must_not_be_empty() {
+        let _ = Field::float().min(5.0).max(2.0);
+    }
+
+    #[test]
+    #[should_panic(expected = ".exclusive_min() leaves an empty range (lower 1, upper 1)")]
+    fn exclusive_float_range_must_not_be_empty() {
+        let _ = Field::float().max(1.0).exclusive_min(1.0);
+    }
+
+    #[test]
+    fn float_range_may_be_a_single_point() {
+        let _ = Field::float().min(1.0).max(1.0);
+    }
+
+    #[test]
+    #[should_panic(expected = ".non_empty() is not valid on a leaf of type integer")]
+    fn non_empty_names_itself_on_other_leaves() {
+        let _ = Field::integer().non_empty();
+    }
+
+    #[test]
+    #[should_panic(expected = ".unique() is only valid on Array, got Leaf")]
+    fn item_constraints_reject_leaves() {
+        let _ = Field::string().unique();
+    }
+
+    #[test]
+    #[should_panic(expected = "empty range (min 5 > max 2)")]
+    fn length_range_must_not_be_empty() {
+        let _ = Field::string().min_len(5).max_len(2);
+    }
+
+    #[test]
+    fn path_options_land_on_map_items() {
+        let roots = Shape::from(Field::map_of(Field::path()).expand_vars().is_dir());
+        let Shape::Map(map) = &roots else {
+            panic!("expected Map");
+        };
+        let Shape::Leaf(leaf) = map.item.as_ref() else {
+            panic!("expected a leaf item");
+        };
+        assert!(matches!(
+            leaf.ty,
+            LeafType::Path {
+                expand_vars: true,
+                must_exist: false,
+                kind: Some(PathKind::Dir),
+            }
+        ));
+    }
+
+    #[test]
+    #[should_panic(expected = ".must_exist() is not valid on a leaf of type string")]
+    fn path_options_reject_string_leaves() {
+        let _ = Field::string().must_exist();
+    }
+
+    #[test]
+    #[should_panic(expected = ".is_dir() and .is_file() exclude each other")]
+    fn path_kinds_exclude_each_other() {
+        let _ = Field::path().is_dir().is_file();
+    }
+
     #[test]
     fn shape_check_array_recurses() {
         let arr = Shape::from(Field::array_of_type(unbounded_integer()));
@@ -47,7 +47,9 @@
 use crate::error::{ClapfigError, DiscoveryRecord};
 use crate::format::ConfigPath;
 use crate::origin::{Origin, OriginMap, OriginNode};
-use crate::runtime::{DocumentRoot, KeyAcrossVariants, NamedField, Schema, Shape, TaggedShape};
+use crate::runtime::{
+    ArrayShape, DocumentRoot, KeyAcrossVariants, NamedField, Schema, Shape, TaggedShape,
+};
 use crate::validate::UnknownKey;
 use crate::value::{Map, Value};
 
@@ -953,10 +955,12 @@ fn insert_default(
     filled: &mut usize,
 ) {
     trace_default_filled(key, value.type_str(), filled);
-    origins.insert(
-        name.to_string(),
-        OriginNode::from_value(&value, Origin::default(schema_key)),
-    );
+    // A key a layer reset keeps the origin naming that layer.
+    let origin = match origins.get(name) {
+        Some(node) if node.origin.reset => node.origin.clone(),
+        _ => Origin::default(schema_key),
+    };
+    origins.insert(name.to_string(), OriginNode::from_value(&value, origin));
     table.insert(name.to_string(), value);
 }
 
@@ -1012,11 +1016,7 @@ pub(crate) fn finalize(
 /// bare integers. Detection is never value-sniffing: only the declared
 /// leaf type makes a value a candidate.
 fn coerce_leaf_values(table: &mut Map, schema: &Schema) {
-    for nf in &schema.fields {
-        if let Some(value) = table.get_mut(&nf.name) {
-            coerce_value(value, &nf.field);
-        }
-    }
+    for_each_leaf_in(table, schema, &mut coerce_leaf);
 }
 
 /// Coerce one value against its declared shape (datetime strings on
@@ -1024,37 +1024,127 @@ fn coerce_leaf_values(table: &mut Map, schema: &Schema) {
 /// declared containers. Shared with the persist path, which validates
 /// `config set` values against the same declarations.
 pub(crate) fn coerce_value(value: &mut Value, shape: &Shape) {
+    for_each_leaf(value, shape, &mut coerce_leaf);
+}
+
+/// Type one stringly-typed file's scalars by the schema, before the file
+/// merges: for every leaf declared [`LeafType::Bool`](crate::runtime::LeafType::Bool),
+/// [`Integer`](crate::runtime::LeafType::Integer),
+/// [`Float`](crate::runtime::LeafType::Float), or a non-string
+/// [`Enum`](crate::runtime::LeafType::Enum) member, a string value that
+/// spells one is replaced with the typed value. Runs only for adapters
+/// whose [`FormatAdapter::scalars_are_strings`](crate::format::FormatAdapter::scalars_are_strings)
+/// is true (INI), so typed formats keep rejecting `port = "80"`.
+///
+/// Same rule as the datetime seam: the declared leaf type decides,
+/// never the value's look. Strings that spell nothing valid stay
+/// strings, and the finalize type check reports them against the
+/// file's span. A tagged root or field is typed only when this file
+/// carries its discriminator.
+pub(crate) fn type_string_scalars_root(table: &mut Map, root: DocumentRoot<'_>) {
+    for_each_leaf_root(table, root, &mut type_string_leaf);
+}
+
+fn for_each_leaf_root(
+    table: &mut Map,
+    root: DocumentRoot<'_>,
+    visit: &mut dyn FnMut(&mut Value, &crate::runtime::LeafType),
+) {
+    match root {
+        DocumentRoot::Object(schema) => for_each_leaf_in(table, schema, visit),
+        DocumentRoot::Map(map) => {
+            for entry in table.values_mut() {
+                for_each_leaf(entry, &map.item, visit);
+            }
+        }
+        DocumentRoot::Tagged(tagged) => for_each_leaf_tagged(table, tagged, visit),
+    }
+}
+
+/// Visit every present value with the leaf type the schema declares for
+/// it, recursing through declared containers (objects, arrays, maps, the
+/// selected tagged variant). A value the schema does not declare — an
+/// unknown key, or any key but the discriminator of a tagged section
+/// with no variant selected — is visited as
+/// [`LeafType::Value`](crate::runtime::LeafType::Value): the schema
+/// names no type for it.
+fn for_each_leaf_in(
+    table: &mut Map,
+    schema: &Schema,
+    visit: &mut dyn FnMut(&mut Value, &crate::runtime::LeafType),
+) {
+    for (key, value) in table.iter_mut() {
+        match schema.fields.iter().find(|nf| nf.name == *key) {
+            Some(nf) => for_each_leaf(value, &nf.field, visit),
+            None => for_each_undeclared(value, visit),
+        }
+    }
+}
+
+fn for_each_leaf(
+    value: &mut Value,
+    shape: &Shape,
+    visit: &mut dyn FnMut(&mut Value, &crate::runtime::LeafType),
+) {
     match shape {
-        Shape::Leaf(leaf) => coerce_leaf(value, &leaf.ty),
+        Shape::Leaf(leaf) => visit(value, &leaf.ty),
         Shape::Object(nested) => {
             if let Value::Map(t) = value {
-                coerce_leaf_values(t, nested);
+                for_each_leaf_in(t, nested, visit);
             }
         }
         Shape::Array(array) => {
             if let Value::Array(items) = value {
                 for item in items {
-                    coerce_value(item, &array.item);
+                    for_each_leaf(item, &array.item, visit);
                 }
             }
         }
         Shape::Map(map) => {
             if let Value::Map(entries) = value {
                 for entry in entries.values_mut() {
-                    coerce_value(entry, &map.item);
+                    for_each_leaf(entry, &map.item, visit);
                 }
             }
         }
         Shape::Tagged(tagged) => {
-            if let Value::Map(inner) = value
-                && let Some(variant) = tagged.selected(inner)
-            {
-                coerce_leaf_values(inner, &variant.schema);
+            if let Value::Map(inner) = value {
+                for_each_leaf_tagged(inner, tagged, visit);
             }
         }
     }
 }
 
+fn for_each_leaf_tagged(
+    table: &mut Map,
+    tagged: &crate::runtime::TaggedShape,
+    visit: &mut dyn FnMut(&mut Value, &crate::runtime::LeafType),
+) {
+    if let Some(variant) = tagged.selected(table) {
+        for_each_leaf_in(table, &variant.schema, visit);
+        return;
+    }
+    for (key, value) in table.iter_mut() {
+        if *key != tagged.tag {
+            for_each_undeclared(value, visit);
+        }
+    }
+}
+
+fn for_each_undeclared(
+    value: &mut Value,
+    visit: &mut dyn FnMut(&mut Value, &crate::runtime::LeafType),
+) {
+    match value {
+        Value::Map(t) => {
+            for entry in t.values_mut() {
+                for_each_undeclared(entry, visit);
+            }
+        }
+        other => visit(other, &crate::runtime::LeafType::Value),
+    }
+}
+
 fn coerce_leaf(value: &mut Value, ty: &crate::runtime::LeafType) {
     use crate::runtime::LeafType;
     match ty {
@@ -1074,6 +1164,85 @@ fn coerce_leaf(value: &mut Value, ty: &crate::runtime::LeafType) {
     }
 }
 
+/// The words a stringly-typed source may spell a bool leaf with, in any
+/// case: `true`/`false`, `yes`/`no`, `on`/`off`, `1`/`0`.
+fn parse_bool_word(s: &str) -> Option<bool> {
+    match s.to_ascii_lowercase().as_str() {
+        "true" | "yes" | "on" | "1" => Some(true),
+        "false" | "no" | "off" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+fn type_string_leaf(value: &mut Value, ty: &crate::runtime::LeafType) {
+    use crate::runtime::LeafType;
+    let Value::String(s) = value else {
+        return;
+    };
+    let typed = match ty {
+        LeafType::Bool => parse_bool_word(s).map(Value::Boolean),
+        LeafType::Integer { .. } => s.parse::<i64>().ok().map(Value::Integer),
+        LeafType::Float => s.parse::<f64>().ok().map(Value::Float),
+        // A string member matches as-is; only non-string members (an
+        // integer enum) need the string spelled back into their type.
+        LeafType::Enum { values } if !values.iter().any(|v| v.as_str() == Some(s)) => values
+            .iter()
+            .find(|v| v.as_str().is_none() && v.to_string() == *s)
+            .cloned(),
+        _ => None,
+    };
+    if let Some(typed) = typed {
+        *value = typed;
+    }
+}
+
+/// Type one layer of raw strings — environment variables, URL query
+/// values — by the schema, before the layer merges: each value is parsed
+/// toward the leaf type declared at its path.
+///
+/// - `String` leaves keep the raw string, so `01234` and `true` stay
+///   strings.
+/// - `Integer` / `Float` / `Bool` leaves parse as that type; bools take
+///   the same words as INI ([`parse_bool_word`]).
+/// - `Enum` leaves match a string member literally before trying the
+///   members' own types.
+/// - `DateTime` leaves keep the string for finalize's coercion, which
+///   accepts the four TOML lexical forms.
+/// - `Value` leaves and paths the schema does not declare keep the
+///   [`parse_env_value`](crate::env::parse_env_value) heuristic — the
+///   schema names no type to parse toward.
+///
+/// A string that does not spell the declared type stays a string, so the
+/// finalize type check (integer bounds included) reports it against the
+/// variable or query key that set it. As with
+/// [`type_string_scalars_root`], a tagged root or section is typed by its
+/// selected variant only when the layer itself carries the discriminator;
+/// otherwise its keys fall back to the heuristic.
+pub(crate) fn type_raw_scalars_root(table: &mut Map, root: DocumentRoot<'_>) {
+    for_each_leaf_root(table, root, &mut type_raw_leaf);
+}
+
+pub(crate) fn type_raw_leaf(value: &mut Value, ty: &crate::runtime::LeafType) {
+    use crate::runtime::LeafType;
+    let Value::String(raw) = value else {
+        return;
+    };
+    let typed = match ty {
+        LeafType::String | LeafType::DateTime | LeafType::Path { .. } => None,
+        LeafType::Integer { .. } => raw.parse::<i64>().ok().map(Value::Integer),
+        LeafType::Float => raw.parse::<f64>().ok().map(Value::Float),
+        LeafType::Bool => parse_bool_word(raw).map(Value::Boolean),
+        LeafType::Enum { values } if values.iter().any(|v| v.as_str() == Some(raw)) => None,
+        LeafType::Enum { values } => {
+            Some(crate::env::parse_env_value(raw)).filter(|sniffed| values.contains(sniffed))
+        }
+        LeafType::Value => Some(crate::env::parse_env_value(raw)),
+    };
+    if let Some(typed) = typed {
+        *value = typed;
+    }
+}
+
 /// Recursively validate required-field presence and per-leaf types.
 fn check_required_and_types(
     table: &Map,
@@ -1121,7 +1290,7 @@ fn check_field(
                 }
                 Ok(())
             }
-            Some(value) => leaf.ty.check(value).map_err(|reason| {
+            Some(value) => leaf.check(value).map_err(|reason| {
                 ClapfigError::invalid_value_at(display.to_string(), reason, origins, path)
             }),
         },
@@ -1152,12 +1321,35 @@ fn check_field(
         },
         Shape::Array(array) => match value {
             None => Ok(()),
+            // Checked per element so the error carries the element's own
+            // origin when it has one (an indexed env var, a file span).
+            Some(Value::Array(items)) if array.item.is_value_field() => {
+                check_array_items(array, items, origins, display, path)?;
+                for (i, item) in items.iter().enumerate() {
+                    array.item.check_value(item).map_err(|reason| {
+                        let element = path.clone().index(i);
+                        let at = if crate::origin::lookup(origins, &element).is_some() {
+                            &element
+                        } else {
+                            path
+                        };
+                        ClapfigError::invalid_value_at(
+                            display.to_string(),
+                            format!("array[{i}]: {reason}"),
+                            origins,
+                            at,
+                        )
+                    })?;
+                }
+                Ok(())
+            }
             Some(value) if array.item.is_value_field() => {
                 shape.check_value(value).map_err(|reason| {
                     ClapfigError::invalid_value_at(display.to_string(), reason, origins, path)
                 })
             }
             Some(Value::Array(items)) => {
+                check_array_items(array, items, origins, display, path)?;
                 for (i, item) in items.iter().enumerate() {
                     let indexed = format!("{display}[{i}]");
                     let indexed_path = path.clone().index(i);
@@ -1232,6 +1424,19 @@ fn check_field(
     }
 }
 
+/// [`ArrayShape::check_items`], located at the array.
+fn check_array_items(
+    array: &ArrayShape,
+    items: &[Value],
+    origins: &OriginMap,
+    display: &str,
+    path: &ConfigPath,
+) -> Result<(), ClapfigError> {
+    array.check_items(items).map_err(|reason| {
+        ClapfigError::invalid_value_at(display.to_string(), reason, origins, path)
+    })
+}
+
 fn tag_display(object_display: &str, tag: &str) -> String {
     if object_display.is_empty() {
         tag.to_string()
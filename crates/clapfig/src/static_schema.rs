@@ -22,10 +22,12 @@
 use std::sync::{Arc, OnceLock};
 
 use crate::runtime::{
-    ArrayShape as RuntimeArrayShape, Leaf as RuntimeLeaf, LeafType as RuntimeLeafType,
-    MapShape as RuntimeMapShape, NamedField as RuntimeNamedField, Schema as RuntimeSchema,
-    Shape as RuntimeShape, TaggedShape as RuntimeTaggedShape,
-    TaggedVariant as RuntimeTaggedVariant, reject_variant_tag_clash, validate_path_segment,
+    ArrayShape as RuntimeArrayShape, Constraints, FieldBuilder as RuntimeFieldBuilder,
+    Leaf as RuntimeLeaf, LeafType as RuntimeLeafType, MapShape as RuntimeMapShape,
+    MergeStrategy as RuntimeMergeStrategy, NamedField as RuntimeNamedField, Pattern,
+    Schema as RuntimeSchema, Shape as RuntimeShape, TaggedShape as RuntimeTaggedShape,
+    TaggedVariant as RuntimeTaggedVariant, check_merge_strategy, reject_variant_tag_clash,
+    validate_path_segment,
 };
 use crate::value::Value;
 
@@ -117,6 +119,11 @@ pub enum FieldStatic {
         schema: &'static SchemaStatic,
         /// Field-site `///` doc lines (may be empty).
         doc: &'static [&'static str],
+        /// `#[clapfig(merge = ...)]` / `#[clapfig(merge_by_key = ...)]`.
+        merge: Option<MergeStatic>,
+        /// `#[clapfig(min_items = ..)]`, `max_items`, `unique`,
+        /// `non_empty`. Item-value constraints are not valid here.
+        constraints: ConstraintsStatic,
     },
     /// Maps of nested objects (TOML `[name.<key>]`). Emitted by the
     /// derive macro for `HashMap<String, NestedStruct>` /
@@ -129,6 +136,8 @@ pub enum FieldStatic {
         schema: &'static SchemaStatic,
         /// Field-site `///` doc lines (may be empty).
         doc: &'static [&'static str],
+        /// `#[clapfig(merge = "replace")]`.
+        merge: Option<MergeStatic>,
     },
 }
 
@@ -140,6 +149,124 @@ pub struct LeafStatic {
     pub default: Option<ValueStatic>,
     pub optional: bool,
     pub env: Option<&'static str>,
+    /// `#[clapfig(secret)]`. On a `Vec` / map leaf, marks the item.
+    pub sensitive: bool,
+    /// `#[clapfig(from_file)]`. Only on string leaves.
+    pub from_file: bool,
+    /// `#[clapfig(merge = ...)]`. Only on `Vec` / map leaves.
+    pub merge: Option<MergeStatic>,
+    /// `#[clapfig(alias = "...")]`, in declaration order.
+    pub aliases: &'static [&'static str],
+    /// `#[clapfig(deprecated = "...")]`.
+    pub deprecated: Option<&'static str>,
+    /// `#[clapfig(pattern = "...")]`, `min_len`, `min`, `unique`, ….
+    pub constraints: ConstraintsStatic,
+}
+
+/// `const`-friendly mirror of [`runtime::Constraints`](crate::runtime::Constraints)
+/// plus the item-count constraints of
+/// [`runtime::ArrayShape`](crate::runtime::ArrayShape), one field per
+/// derive attribute. Conversion applies them through
+/// [`FieldBuilder`](crate::runtime::FieldBuilder): string and float
+/// constraints land on the leaf (or a `Vec` leaf's item), `non_empty` is
+/// `min_len = 1` on a string and `min_items = 1` on a `Vec`. The path
+/// options (`expand_vars`, `must_exist`, `is_dir`, `is_file`) go on a
+/// [`LeafTypeStatic::Path`] leaf the same way.
+#[derive(Debug)]
+pub struct ConstraintsStatic {
+    pub pattern: Option<&'static str>,
+    pub min_len: Option<usize>,
+    pub max_len: Option<usize>,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub exclusive_min: Option<f64>,
+    pub exclusive_max: Option<f64>,
+    pub non_empty: bool,
+    pub min_items: Option<usize>,
+    pub max_items: Option<usize>,
+    pub unique: bool,
+    pub expand_vars: bool,
+    pub must_exist: bool,
+    pub is_dir: bool,
+    pub is_file: bool,
+}
+
+impl ConstraintsStatic {
+    /// No constraints — the base for `..ConstraintsStatic::NONE` in
+    /// derive emission.
+    pub const NONE: Self = Self {
+        pattern: None,
+        min_len: None,
+        max_len: None,
+        min: None,
+        max: None,
+        exclusive_min: None,
+        exclusive_max: None,
+        non_empty: false,
+        min_items: None,
+        max_items: None,
+        unique: false,
+        expand_vars: false,
+        must_exist: false,
+        is_dir: false,
+        is_file: false,
+    };
+
+    /// Apply to a converted field. An invalid pattern panics naming the
+    /// field, at the first `schema()` call — the macro does not compile
+    /// regexes.
+    fn apply(&self, field: RuntimeShape, field_name: &str) -> RuntimeShape {
+        let mut builder = RuntimeFieldBuilder::from_shape(field);
+        if let Some(pattern) = self.pattern {
+            if let Err(e) = Pattern::new(pattern) {
+                panic!("clapfig: field `{field_name}` has an invalid pattern {pattern:?}: {e}");
+            }
+            builder = builder.pattern(pattern);
+        }
+        if let Some(n) = self.min_len {
+            builder = builder.min_len(n);
+        }
+        if let Some(n) = self.max_len {
+            builder = builder.max_len(n);
+        }
+        if let Some(value) = self.min {
+            builder = builder.min(value);
+        }
+        if let Some(value) = self.max {
+            builder = builder.max(value);
+        }
+        if let Some(value) = self.exclusive_min {
+            builder = builder.exclusive_min(value);
+        }
+        if let Some(value) = self.exclusive_max {
+            builder = builder.exclusive_max(value);
+        }
+        if let Some(n) = self.min_items {
+            builder = builder.min_items(n);
+        }
+        if let Some(n) = self.max_items {
+            builder = builder.max_items(n);
+        }
+        if self.non_empty {
+            builder = builder.non_empty();
+        }
+        if self.unique {
+            builder = builder.unique();
+        }
+        if self.expand_vars {
+            builder = builder.expand_vars();
+        }
+        if self.must_exist {
+            builder = builder.must_exist();
+        }
+        if self.is_dir {
+            builder = builder.is_dir();
+        }
+        if self.is_file {
+            builder = builder.is_file();
+        }
+        builder.build()
+    }
 }
 
 /// `const`-friendly mirror of [`runtime::LeafType`](crate::runtime::LeafType).
@@ -178,6 +305,9 @@ pub enum LeafTypeStatic {
     Float,
     Bool,
     DateTime,
+    /// Filesystem path; the derive maps `PathBuf` here. Its options ride
+    /// [`ConstraintsStatic`].
+    Path,
     Array(&'static LeafTypeStatic),
     Map(&'static LeafTypeStatic),
     Enum {
@@ -211,6 +341,29 @@ pub enum LeafTypeStatic {
     Value,
 }
 
+/// `const`-friendly mirror of
+/// [`runtime::MergeStrategy`](crate::runtime::MergeStrategy).
+#[derive(Debug)]
+pub enum MergeStatic {
+    Replace,
+    Append,
+    Prepend,
+    Union,
+    MergeByKey(&'static str),
+}
+
+impl MergeStatic {
+    pub fn to_runtime(&self) -> RuntimeMergeStrategy {
+        match self {
+            MergeStatic::Replace => RuntimeMergeStrategy::Replace,
+            MergeStatic::Append => RuntimeMergeStrategy::Append,
+            MergeStatic::Prepend => RuntimeMergeStrategy::Prepend,
+            MergeStatic::Union => RuntimeMergeStrategy::Union,
+            MergeStatic::MergeByKey(key) => RuntimeMergeStrategy::MergeByKey((*key).to_string()),
+        }
+    }
+}
+
 /// `const`-friendly mirror of [`Value`] for default-value emission.
 ///
 /// Datetimes are stored as their string form and parsed on conversion,
@@ -332,9 +485,40 @@ fn tagged_static_to_runtime(
 
 impl NamedFieldStatic {
     fn to_runtime(&self) -> RuntimeNamedField {
+        let mut field = self.field.to_runtime();
+        if let Some(merge) = self.field.merge() {
+            // Deferred like the enum-kind checks: the macro cannot see
+            // whether a `Vec<T>` item declares the `merge_by_key` field.
+            let strategy = merge.to_runtime();
+            if let Err(reason) = check_merge_strategy(&field, &strategy) {
+                panic!("clapfig: field `{}`: {reason}", self.name);
+            }
+            match &mut field {
+                RuntimeShape::Array(array) => array.merge = Some(strategy),
+                RuntimeShape::Map(map) => map.merge = Some(strategy),
+                _ => unreachable!("check_merge_strategy accepts only Map and Array"),
+            }
+        }
+        if let Some(constraints) = self.field.constraints() {
+            field = constraints.apply(field, self.name);
+        }
+        if let FieldStatic::Leaf(leaf) = &self.field {
+            let aliases = leaf.aliases.iter().map(|a| (*a).to_string()).collect();
+            let deprecated = leaf.deprecated.map(str::to_string);
+            match &mut field {
+                RuntimeShape::Leaf(runtime) => {
+                    (runtime.aliases, runtime.deprecated) = (aliases, deprecated);
+                }
+                RuntimeShape::Array(array) => {
+                    (array.aliases, array.deprecated) = (aliases, deprecated)
+                }
+                RuntimeShape::Map(map) => (map.aliases, map.deprecated) = (aliases, deprecated),
+                RuntimeShape::Object(_) | RuntimeShape::Tagged(_) => {}
+            }
+        }
         RuntimeNamedField {
             name: self.name.to_string(),
-            field: self.field.to_runtime(),
+            field,
         }
     }
 }
@@ -371,10 +555,31 @@ fn unit_enum_leaf(s: &SchemaStatic) -> RuntimeLeaf {
         default: None,
         optional: false,
         env: None,
+        sensitive: false,
+        from_file: false,
+        aliases: Vec::new(),
+        deprecated: None,
+        constraints: Constraints::default(),
     }
 }
 
 impl FieldStatic {
+    fn merge(&self) -> Option<&MergeStatic> {
+        match self {
+            FieldStatic::Leaf(leaf) => leaf.merge.as_ref(),
+            FieldStatic::ArrayOf { merge, .. } | FieldStatic::MapOf { merge, .. } => merge.as_ref(),
+            FieldStatic::Nested { .. } => None,
+        }
+    }
+
+    fn constraints(&self) -> Option<&ConstraintsStatic> {
+        match self {
+            FieldStatic::Leaf(leaf) => Some(&leaf.constraints),
+            FieldStatic::ArrayOf { constraints, .. } => Some(constraints),
+            FieldStatic::Nested { .. } | FieldStatic::MapOf { .. } => None,
+        }
+    }
+
     fn to_runtime(&self) -> RuntimeShape {
         match self {
             FieldStatic::Leaf(leaf) => leaf_static_to_shape(leaf),
@@ -393,6 +598,11 @@ impl FieldStatic {
                     default: None,
                     optional: false,
                     env: None,
+                    sensitive: false,
+                    from_file: false,
+                    aliases: Vec::new(),
+                    deprecated: None,
+                    constraints: Constraints::default(),
                 })
             }
             FieldStatic::Nested { schema: s, doc } if s.is_tagged() => {
@@ -408,7 +618,7 @@ impl FieldStatic {
             // enum leaf — keeping the object item would emit a schema of
             // zero fields that rejects every string entry at load
             // ("expected map, got string").
-            FieldStatic::ArrayOf { schema: s, doc } if s.is_enum() => {
+            FieldStatic::ArrayOf { schema: s, doc, .. } if s.is_enum() => {
                 RuntimeShape::Array(array_shape_from_item(
                     effective_doc(doc, s.doc),
                     RuntimeShape::Leaf(RuntimeLeaf {
@@ -419,13 +629,18 @@ impl FieldStatic {
                         default: None,
                         optional: false,
                         env: None,
+                        sensitive: false,
+                        from_file: false,
+                        aliases: Vec::new(),
+                        deprecated: None,
+                        constraints: Constraints::default(),
                     }),
                     None,
                     false,
                     None,
                 ))
             }
-            FieldStatic::ArrayOf { schema: s, doc } if s.is_tagged() => {
+            FieldStatic::ArrayOf { schema: s, doc, .. } if s.is_tagged() => {
                 let doc = effective_doc(doc, s.doc);
                 RuntimeShape::Array(array_shape_from_item(
                     doc.clone(),
@@ -435,7 +650,7 @@ impl FieldStatic {
                     None,
                 ))
             }
-            FieldStatic::ArrayOf { schema: s, doc } => {
+            FieldStatic::ArrayOf { schema: s, doc, .. } => {
                 let mut runtime = s.to_runtime();
                 runtime.doc = effective_doc(doc, s.doc);
                 RuntimeShape::Array(array_shape_from_item(
@@ -457,7 +672,7 @@ impl FieldStatic {
             // `fill_defaults` materializes `{}` for non-optional map
             // fields and the typed deserialize yields an empty `HashMap`
             // instead of a missing-required error.
-            FieldStatic::MapOf { schema: s, doc } if s.is_enum() => {
+            FieldStatic::MapOf { schema: s, doc, .. } if s.is_enum() => {
                 RuntimeShape::Map(map_shape_from_item(
                     effective_doc(doc, s.doc),
                     RuntimeShape::Leaf(RuntimeLeaf {
@@ -468,13 +683,18 @@ impl FieldStatic {
                         default: None,
                         optional: false,
                         env: None,
+                        sensitive: false,
+                        from_file: false,
+                        aliases: Vec::new(),
+                        deprecated: None,
+                        constraints: Constraints::default(),
                     }),
                     None,
                     false,
                     None,
                 ))
             }
-            FieldStatic::MapOf { schema: s, doc } if s.is_tagged() => {
+            FieldStatic::MapOf { schema: s, doc, .. } if s.is_tagged() => {
                 let doc = effective_doc(doc, s.doc);
                 RuntimeShape::Map(map_shape_from_item(
                     doc.clone(),
@@ -484,7 +704,7 @@ impl FieldStatic {
                     None,
                 ))
             }
-            FieldStatic::MapOf { schema: s, doc } => {
+            FieldStatic::MapOf { schema: s, doc, .. } => {
                 let mut runtime = s.to_runtime();
                 runtime.doc = effective_doc(doc, s.doc);
                 RuntimeShape::Map(map_shape_from_item(
@@ -514,6 +734,12 @@ fn array_shape_from_item(
         default,
         optional,
         env,
+        merge: None,
+        aliases: Vec::new(),
+        deprecated: None,
+        min_items: None,
+        max_items: None,
+        unique: false,
     }
 }
 
@@ -532,6 +758,9 @@ fn map_shape_from_item(
         default,
         optional,
         env,
+        merge: None,
+        aliases: Vec::new(),
+        deprecated: None,
     }
 }
 
@@ -561,7 +790,7 @@ fn leaf_static_to_shape(leaf: &LeafStatic) -> RuntimeShape {
             }
             RuntimeShape::Array(array_shape_from_item(
                 leaf.doc.iter().map(|s| (*s).to_string()).collect(),
-                leaf_type_static_to_item_shape(elem),
+                leaf_type_static_to_item_shape(elem, leaf.sensitive),
                 leaf.default.as_ref().map(ValueStatic::to_value),
                 leaf.optional,
                 leaf.env.map(|s| s.to_string()),
@@ -569,7 +798,7 @@ fn leaf_static_to_shape(leaf: &LeafStatic) -> RuntimeShape {
         }
         LeafTypeStatic::Map(elem) => RuntimeShape::Map(map_shape_from_item(
             leaf.doc.iter().map(|s| (*s).to_string()).collect(),
-            leaf_type_static_to_item_shape(elem),
+            leaf_type_static_to_item_shape(elem, leaf.sensitive),
             leaf.default.as_ref().map(ValueStatic::to_value),
             leaf.optional,
             leaf.env.map(|s| s.to_string()),
@@ -578,18 +807,18 @@ fn leaf_static_to_shape(leaf: &LeafStatic) -> RuntimeShape {
     }
 }
 
-fn leaf_type_static_to_item_shape(ty: &LeafTypeStatic) -> RuntimeShape {
+fn leaf_type_static_to_item_shape(ty: &LeafTypeStatic, sensitive: bool) -> RuntimeShape {
     match ty {
         LeafTypeStatic::Array(elem) => RuntimeShape::Array(array_shape_from_item(
             Vec::new(),
-            leaf_type_static_to_item_shape(elem),
+            leaf_type_static_to_item_shape(elem, sensitive),
             None,
             false,
             None,
         )),
         LeafTypeStatic::Map(elem) => RuntimeShape::Map(map_shape_from_item(
             Vec::new(),
-            leaf_type_static_to_item_shape(elem),
+            leaf_type_static_to_item_shape(elem, sensitive),
             None,
             false,
             None,
@@ -600,6 +829,11 @@ fn leaf_type_static_to_item_shape(ty: &LeafTypeStatic) -> RuntimeShape {
             default: None,
             optional: false,
             env: None,
+            sensitive,
+            from_file: false,
+            aliases: Vec::new(),
+            deprecated: None,
+            constraints: Constraints::default(),
         }),
     }
 }
@@ -634,6 +868,11 @@ impl LeafStatic {
             default: self.default.as_ref().map(ValueStatic::to_value),
             optional: self.optional,
             env: self.env.map(|s| s.to_string()),
+            sensitive: self.sensitive,
+            from_file: self.from_file,
+            aliases: Vec::new(),
+            deprecated: None,
+            constraints: Constraints::default(),
         }
     }
 }
@@ -649,6 +888,11 @@ impl LeafTypeStatic {
             LeafTypeStatic::Float => RuntimeLeafType::Float,
             LeafTypeStatic::Bool => RuntimeLeafType::Bool,
             LeafTypeStatic::DateTime => RuntimeLeafType::DateTime,
+            LeafTypeStatic::Path => RuntimeLeafType::Path {
+                expand_vars: false,
+                must_exist: false,
+                kind: None,
+            },
             LeafTypeStatic::Array(_) | LeafTypeStatic::Map(_) => {
                 unreachable!(
                     "clapfig: LeafTypeStatic::Array/Map collapse into Shape::Array/Map at \
@@ -743,9 +987,9 @@ impl ValueStatic {
     message = "`{Self}` is not a field type `#[derive(clapfig::Schema)]` supports",
     label = "no schema shape for this type",
     note = "supported scalars: String, bool, integers (i8–i64, u8–u64, usize, isize), f32/f64, \
-            clapfig::value::Datetime, clapfig::value::Value; wrappers: Option<T>, Vec<T> (scalar \
-            or Schema-deriving element), HashMap/BTreeMap<String, V>",
-    note = "other types (PathBuf, Duration, char, newtypes, type aliases, third-party maps, …) \
+            clapfig::value::Datetime, clapfig::value::Value, std::path::PathBuf; wrappers: \
+            Option<T>, Vec<T> (scalar or Schema-deriving element), HashMap/BTreeMap<String, V>",
+    note = "other types (Duration, char, newtypes, type aliases, third-party maps, …) \
             have no TOML-faithful schema shape: either add `#[derive(clapfig::Schema)]` to the \
             type (structs with named fields, unit-only enums, or internally tagged \
             `#[serde(tag = \"...\")]` enums), or mark the field \
@@ -873,6 +1117,9 @@ fn root_map_shape(item: crate::runtime::Shape) -> crate::runtime::Shape {
         default: None,
         optional: false,
         env: None,
+        merge: None,
+        aliases: Vec::new(),
+        deprecated: None,
     })
 }
 
@@ -962,6 +1209,22 @@ impl IsClapfigDatetime for crate::value::Datetime {}
 pub trait IsClapfigValue {}
 impl IsClapfigValue for crate::value::Value {}
 
+/// Derive-support marker: asserts a field type the macro claimed as a
+/// path leaf really is [`std::path::PathBuf`]. Same rationale as
+/// [`IsClapfigDatetime`] — the macro matches exactly the spellings
+/// `PathBuf`, `path::PathBuf`, and `std::path::PathBuf`.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` is not `std::path::PathBuf`",
+    label = "field type claimed as a path leaf by #[derive(clapfig::Schema)]",
+    note = "the derive matches path fields by type name, and only `std::path::PathBuf` \
+            carries the schema's path semantics. If this is your own type: spell it with a \
+            qualified path the derive won't claim (e.g. `my_mod::PathBuf`) and give it its \
+            own schema shape — derive `clapfig::Schema` for it, or mark the field \
+            `#[clapfig(value)]`"
+)]
+pub trait IsStdPathBuf {}
+impl IsStdPathBuf for std::path::PathBuf {}
+
 /// Depth-first walk of a [`SchemaStatic`] tree that appends each node's
 /// dotted path to `out`. Backs [`Schema::field_paths`]; exposed publicly
 /// because consumers that already have a `&SchemaStatic` (rare — usually
@@ -1092,6 +1355,12 @@ mod tests {
                 default: Some(ValueStatic::Integer(8080)),
                 optional: false,
                 env: None,
+                sensitive: false,
+                from_file: false,
+                merge: None,
+                aliases: &[],
+                deprecated: None,
+                constraints: ConstraintsStatic::NONE,
             }),
         }],
         enum_variants: &[],
@@ -1184,6 +1453,12 @@ mod tests {
                 default: None,
                 optional: true,
                 env: None,
+                sensitive: false,
+                from_file: false,
+                merge: None,
+                aliases: &[],
+                deprecated: None,
+                constraints: ConstraintsStatic::NONE,
             }),
         }],
         enum_variants: &[],
@@ -1265,6 +1540,7 @@ mod tests {
             field: FieldStatic::MapOf {
                 schema: &ENUM_PDF_PAGE,
                 doc: &["Per-target page size."],
+                merge: None,
             },
         }],
         enum_variants: &[],
@@ -1310,6 +1586,8 @@ mod tests {
             field: FieldStatic::ArrayOf {
                 schema: &ENUM_PDF_PAGE,
                 doc: &["Accepted page sizes."],
+                merge: None,
+                constraints: ConstraintsStatic::NONE,
             },
         }],
         enum_variants: &[],
@@ -1355,6 +1633,8 @@ mod tests {
             field: FieldStatic::ArrayOf {
                 schema: &NESTED_INNER,
                 doc: &["Installed plugins."],
+                merge: None,
+                constraints: ConstraintsStatic::NONE,
             },
         }],
         enum_variants: &[],
@@ -1406,6 +1686,12 @@ mod tests {
                     default: None,
                     optional: true,
                     env: None,
+                    sensitive: false,
+                    from_file: false,
+                    merge: None,
+                    aliases: &[],
+                    deprecated: None,
+                    constraints: ConstraintsStatic::NONE,
                 }),
             }],
             enum_variants: &[],
@@ -1460,6 +1746,12 @@ mod tests {
                     default: Some(ValueStatic::String("tabloid")),
                     optional: false,
                     env: None,
+                    sensitive: false,
+                    from_file: false,
+                    merge: None,
+                    aliases: &[],
+                    deprecated: None,
+                    constraints: ConstraintsStatic::NONE,
                 }),
             }],
             enum_variants: &[],
@@ -1538,6 +1830,8 @@ mod tests {
             field: FieldStatic::ArrayOf {
                 schema: &TAGGED_ITEM_SCHEMA,
                 doc: &["Installed blocks."],
+                merge: None,
+                constraints: ConstraintsStatic::NONE,
             },
         }],
         enum_variants: &[],
@@ -1554,6 +1848,7 @@ mod tests {
             field: FieldStatic::MapOf {
                 schema: &TAGGED_ITEM_SCHEMA,
                 doc: &["Named blocks."],
+                merge: None,
             },
         }],
         enum_variants: &[],
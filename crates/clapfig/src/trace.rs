@@ -77,6 +77,16 @@ pub(crate) fn parsed_file(path: &Path, format: &str) {
     );
 }
 
+/// The active profile's section of a file layered above it.
+pub(crate) fn profile_section_applied(path: &Path, profile: &str) {
+    tracing::trace!(
+        target: TARGET,
+        path = %path.display(),
+        profile,
+        "profile section applied"
+    );
+}
+
 /// Files layer assembled from the loaded documents.
 pub(crate) fn files_layer_constructed(files: usize, keys: usize) {
     tracing::debug!(target: TARGET, files, keys, "files layer constructed");
@@ -131,6 +141,12 @@ pub(crate) fn overlay_win(
     );
 }
 
+/// A layer's unset marker dropped the value below it, if there was one.
+/// Names the input that supplied the marker.
+pub(crate) fn value_reset(key: &ConfigPath, origin: &str) {
+    tracing::trace!(target: TARGET, key = %key, origin, "value reset");
+}
+
 /// Layers have been merged in the configured order.
 pub(crate) fn merge_complete(keys: usize) {
     tracing::debug!(target: TARGET, keys, "merge complete");
@@ -189,6 +205,53 @@ pub(crate) fn persist_unset(path: &Path, key: &str) {
     );
 }
 
+/// A config file still uses a key a migration renamed, moved, or
+/// removed; loading migrated it in memory. `config migrate` rewrites
+/// the file for good.
+pub(crate) fn migration_applied(path: &Path, migration: &str) {
+    tracing::warn!(
+        target: TARGET,
+        path = %path.display(),
+        migration,
+        "config file needs migration; run `config migrate`"
+    );
+}
+
+/// An input spelled a field by one of its aliases; the key was
+/// rewritten to the field's name.
+pub(crate) fn alias_applied(alias: &ConfigPath, key: &ConfigPath) {
+    tracing::debug!(
+        target: TARGET,
+        alias = %alias,
+        key = %key,
+        "alias rewritten to field name"
+    );
+}
+
+/// An input set a key the schema marks deprecated. `origin` is that
+/// input's label (`file:/etc/app.toml`, `env:APP__OLD`, …); `line` is
+/// set for file origins whose span is known.
+pub(crate) fn deprecated_key(key: &ConfigPath, note: &str, origin: &str, line: Option<usize>) {
+    tracing::warn!(
+        target: TARGET,
+        key = %key,
+        note,
+        origin,
+        line,
+        "deprecated config key"
+    );
+}
+
+/// `config migrate` applied `migrations` changes to `path`.
+pub(crate) fn persist_migrate(path: &Path, migrations: usize) {
+    tracing::debug!(
+        target: TARGET,
+        path = %path.display(),
+        migrations,
+        "persist migrate"
+    );
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::BTreeMap;
@@ -496,6 +559,55 @@ mod tests {
         assert_eq!(host.field("loser_type"), Some("string"));
     }
 
+    #[test]
+    fn deprecated_keys_warn_with_origin_and_line_per_input() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("app.toml"),
+            format!("port = 1000\nlegacy_host = \"{SENTINEL}\"\n"),
+        )
+        .unwrap();
+        let schema = Schema::object("TraceConfig")
+            .field("port", RtField::integer().default(8080i64))
+            .field(
+                "legacy_host",
+                RtField::string()
+                    .alias("old_host")
+                    .deprecated("use `host` instead")
+                    .optional(),
+            )
+            .build();
+
+        let (events, result) = capture(|| {
+            Clapfig::builder(schema)
+                .app_name("app")
+                .file_name("app.toml")
+                .no_env()
+                .search_paths(vec![SearchPath::Path(dir.path().to_path_buf())])
+                .cli_override("old_host", Some("cli"))
+                .load()
+        });
+        result.expect("deprecated keys still load");
+
+        let logs = blob(&events);
+        assert!(!logs.contains(SENTINEL), "{logs}");
+        let warnings = named(&events, "deprecated config key");
+        assert_eq!(warnings.len(), 2, "one per input that sets it:\n{logs}");
+        for warning in &warnings {
+            assert_eq!(warning.level, Level::WARN);
+            assert_eq!(warning.field("key"), Some("legacy_host"));
+            assert_eq!(warning.field("note"), Some("use `host` instead"));
+        }
+        let file = warnings[0];
+        assert!(
+            file.field("origin").unwrap().ends_with("app.toml"),
+            "{logs}"
+        );
+        assert_eq!(file.field("line"), Some("2"));
+        assert_eq!(warnings[1].field("origin"), Some("override:old_host"));
+        assert_eq!(warnings[1].field("line"), None);
+    }
+
     #[test]
     fn omitted_cli_and_url_inputs_do_not_emit_layer_constructed() {
         let (events, result) = capture(|| {
@@ -617,7 +729,15 @@ mod tests {
             "persist must stay at debug/trace:\n{logs}"
         );
 
-        let (events, result) = capture(|| persist::unset_value(&adapter, &path, "token", false));
+        let (events, result) = capture(|| {
+            persist::unset_value(
+                &adapter,
+                &crate::runtime::Shape::Object(schema.clone()),
+                &path,
+                "token",
+                false,
+            )
+        });
         result.expect("persist unset");
         let logs = blob(&events);
         assert!(
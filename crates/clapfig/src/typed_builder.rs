@@ -19,11 +19,17 @@ use serde::Serialize;
 use serde::de::DeserializeOwned;
 
 use crate::builder::{Builder, Resolver};
+use crate::diagnostics::{Warning, WarningKind};
+use crate::diff::ConfigDiff;
 use crate::error::ClapfigError;
+use crate::format::FormatAdapter;
+use crate::migrate::Migration;
 use crate::ops::ConfigResult;
+use crate::provenance::{Explanation, Provenance};
 use crate::static_schema::DocumentRoot;
-use crate::types::{ConfigAction, Layer, SearchMode, SearchPath};
+use crate::types::{CacheFreshness, ConfigAction, DiffTarget, Layer, SearchMode, SearchPath};
 use crate::value::{Map, Value, from_value};
+use crate::watch::{ConfigWatch, WatchOptions};
 
 /// Typed-config builder driven by a [`DocumentRoot`] (a named-field
 /// `#[derive(clapfig::Schema)]` struct, an internally tagged
@@ -101,6 +107,13 @@ impl<C: DocumentRoot> TypedBuilder<C> {
         self
     }
 
+    /// Register a custom format adapter. See
+    /// [`Builder::format_adapter`](crate::Builder::format_adapter).
+    pub fn format_adapter(mut self, adapter: Box<dyn FormatAdapter>) -> Self {
+        self.inner = self.inner.format_adapter(adapter);
+        self
+    }
+
     /// Replace the default search paths entirely.
     pub fn search_paths(mut self, paths: Vec<SearchPath>) -> Self {
         self.inner = self.inner.search_paths(paths);
@@ -119,12 +132,38 @@ impl<C: DocumentRoot> TypedBuilder<C> {
         self
     }
 
+    /// Set when the built resolver re-reads files it has already cached.
+    /// See [`Builder::cache_freshness`].
+    pub fn cache_freshness(mut self, freshness: CacheFreshness) -> Self {
+        self.inner = self.inner.cache_freshness(freshness);
+        self
+    }
+
     /// Register a named persist scope for `config set`/`unset`.
     pub fn persist_scope(mut self, name: &str, path: SearchPath) -> Self {
         self.inner = self.inner.persist_scope(name, path);
         self
     }
 
+    /// Let config files include others through a top-level `key`.
+    pub fn include_key(mut self, key: &str) -> Self {
+        self.inner = self.inner.include_key(key);
+        self
+    }
+
+    /// Let config files hold named profiles under a top-level `key`.
+    /// Required for `MYAPP_PROFILE` to select one.
+    pub fn profile_key(mut self, key: &str) -> Self {
+        self.inner = self.inner.profile_key(key);
+        self
+    }
+
+    /// Select the active profile, ahead of `MYAPP_PROFILE`.
+    pub fn profile(mut self, name: &str) -> Self {
+        self.inner = self.inner.profile(name);
+        self
+    }
+
     /// Override the env var prefix.
     pub fn env_prefix(mut self, prefix: &str) -> Self {
         self.inner = self.inner.env_prefix(prefix);
@@ -137,6 +176,42 @@ impl<C: DocumentRoot> TypedBuilder<C> {
         self
     }
 
+    /// Set the separator between env list items (default `","`).
+    pub fn env_list_separator(mut self, separator: &str) -> Self {
+        self.inner = self.inner.env_list_separator(separator);
+        self
+    }
+
+    /// Expand `${...}` references in string values (default `false`).
+    pub fn interpolate(mut self, enabled: bool) -> Self {
+        self.inner = self.inner.interpolate(enabled);
+        self
+    }
+
+    /// Treat the string `marker` as "unset this key" (default: off).
+    pub fn unset_marker(mut self, marker: impl Into<String>) -> Self {
+        self.inner = self.inner.unset_marker(marker);
+        self
+    }
+
+    /// Register a [`Migration`] for a renamed, moved, or removed key.
+    pub fn migration(mut self, migration: Migration) -> Self {
+        self.inner = self.inner.migration(migration);
+        self
+    }
+
+    /// Keep each config file's schema version under the top-level `key`.
+    pub fn schema_version_key(mut self, key: &str) -> Self {
+        self.inner = self.inner.schema_version_key(key);
+        self
+    }
+
+    /// Read Docker-style `_FILE` variables (default `false`).
+    pub fn env_file_secrets(mut self, enabled: bool) -> Self {
+        self.inner = self.inner.env_file_secrets(enabled);
+        self
+    }
+
     /// Set the whole-resolution strictness default.
     pub fn strict(mut self, strict: bool) -> Self {
         self.inner = self.inner.strict(strict);
@@ -183,6 +258,13 @@ impl<C: DocumentRoot> TypedBuilder<C> {
         self
     }
 
+    /// Fail the load when it raises a warning of one of these kinds. See
+    /// [`Builder::deny_warnings`](crate::Builder::deny_warnings).
+    pub fn deny_warnings(mut self, kinds: impl IntoIterator<Item = WarningKind>) -> Self {
+        self.inner = self.inner.deny_warnings(kinds);
+        self
+    }
+
     /// Add a URL query string as a config layer.
     #[cfg(feature = "url")]
     pub fn url_query(mut self, query: &str) -> Self {
@@ -201,6 +283,27 @@ impl<C: DocumentRoot> TypedBuilder<C> {
         self.inner = self.inner.cli_overrides_from(source);
         self
     }
+
+    /// Add `key=value` assignments (`-c port=8080`) as CLI overrides.
+    pub fn cli_assignments<I, S>(mut self, assignments: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.inner = self.inner.cli_assignments(assignments);
+        self
+    }
+
+    /// Add the parsed [`ConfigFlags`](crate::ConfigFlags) as CLI overrides.
+    #[cfg(feature = "clap")]
+    pub fn cli_overrides_from_matches(
+        mut self,
+        flags: &crate::ConfigFlags,
+        matches: &clap::ArgMatches,
+    ) -> Self {
+        self.inner = self.inner.cli_overrides_from_matches(flags, matches);
+        self
+    }
 }
 
 impl<C: DocumentRoot + DeserializeOwned> TypedBuilder<C> {
@@ -251,6 +354,41 @@ impl<C: DocumentRoot + DeserializeOwned> TypedBuilder<C> {
         Ok((typed, unknowns))
     }
 
+    /// Same as [`load`](Self::load) but also returns the [`Warning`]s the
+    /// load raised. See
+    /// [`Builder::load_with_diagnostics`](crate::Builder::load_with_diagnostics).
+    pub fn load_with_diagnostics(self) -> Result<(C, Vec<Warning>), ClapfigError> {
+        let (table, warnings) = self.inner.load_with_diagnostics()?;
+        let typed = deserialize_table::<C>(table)?;
+        run_typed_hook(self.post_validate.as_ref(), &typed)?;
+        Ok((typed, warnings))
+    }
+
+    /// Same as [`load`](Self::load) but also returns the [`Provenance`]
+    /// of every resolved value. See
+    /// [`Builder::load_with_origins`](crate::Builder::load_with_origins).
+    pub fn load_with_origins(self) -> Result<(C, Provenance), ClapfigError> {
+        let (table, provenance) = self.inner.load_with_origins()?;
+        let typed = deserialize_table::<C>(table)?;
+        run_typed_hook(self.post_validate.as_ref(), &typed)?;
+        Ok((typed, provenance))
+    }
+
+    /// Explain where `key`'s value comes from: every input that supplied
+    /// it, in precedence order, and which one won. See
+    /// [`Builder::explain`](crate::Builder::explain); no typed value is
+    /// built, so the typed `post_validate` hook does not run.
+    pub fn explain(self, key: &str) -> Result<Explanation, ClapfigError> {
+        self.inner.explain(key)
+    }
+
+    /// Compare the configurations at two [`DiffTarget`]s. See
+    /// [`Builder::diff`](crate::Builder::diff); the diff is over resolved
+    /// maps, so the typed `post_validate` hook does not run.
+    pub fn diff(self, left: &DiffTarget, right: &DiffTarget) -> Result<ConfigDiff, ClapfigError> {
+        self.inner.diff(left, right)
+    }
+
     /// Build a reusable [`TypedResolver<C>`] for tree-walk resolution —
     /// the typed counterpart of
     /// [`Builder::build_resolver`](crate::Builder::build_resolver).
@@ -278,7 +416,7 @@ impl<C: DocumentRoot + DeserializeOwned> TypedBuilder<C> {
     /// Dispatch a [`ConfigAction`] and return the rendered output.
     ///
     /// The action surface is identical to the Map-out path —
-    /// `gen | schema | get | list | set | unset` all delegate. A typed
+    /// `gen | schema | get | list | explain | set | unset` all delegate. A typed
     /// [`post_validate`](Self::post_validate) hook still guards the
     /// merged `get`/`list` views: it is bridged into the Map-out builder
     /// (deserializing a `C` to run it) since no typed value is returned
@@ -333,9 +471,9 @@ impl<C: DocumentRoot + DeserializeOwned> TypedBuilder<C> {
 /// [`Resolver`](crate::Resolver), built by
 /// [`TypedBuilder::build_resolver`].
 ///
-/// Wraps the Map-out resolver, so anchoring semantics and the
-/// per-resolver file cache (including its no-mtime-check contract) are
-/// identical; each call adds one final `Map → C` deserialize through the
+/// Wraps the Map-out resolver, so anchoring semantics, the per-resolver
+/// file cache (including its no-mtime-check contract), and
+/// [`watch`](Self::watch) are identical; each call adds one final `Map → C` deserialize through the
 /// value model's serde bridge, and then runs the typed
 /// [`post_validate`](TypedBuilder::post_validate) hook (carried on this
 /// resolver, not the wrapped one) on that same instance.
@@ -372,6 +510,77 @@ impl<C: DocumentRoot + DeserializeOwned> TypedResolver<C> {
         Ok((typed, unknowns))
     }
 
+    /// Same as [`resolve_at`](Self::resolve_at) but also returns the
+    /// [`Provenance`] of every resolved value. See
+    /// [`Resolver::resolve_at_with_origins`](crate::Resolver::resolve_at_with_origins).
+    pub fn resolve_at_with_origins(
+        &self,
+        start_dir: impl AsRef<std::path::Path>,
+    ) -> Result<(C, Provenance), ClapfigError> {
+        let (table, provenance) = self.inner.resolve_at_with_origins(start_dir)?;
+        let typed = deserialize_table::<C>(table)?;
+        run_typed_hook(self.post_validate.as_ref(), &typed)?;
+        Ok((typed, provenance))
+    }
+
+    /// Same as [`resolve_at`](Self::resolve_at) but also returns the
+    /// [`Warning`]s the resolution raised. See
+    /// [`Resolver::resolve_at_with_diagnostics`](crate::Resolver::resolve_at_with_diagnostics).
+    pub fn resolve_at_with_diagnostics(
+        &self,
+        start_dir: impl AsRef<std::path::Path>,
+    ) -> Result<(C, Vec<Warning>), ClapfigError> {
+        let (table, warnings) = self.inner.resolve_at_with_diagnostics(start_dir)?;
+        let typed = deserialize_table::<C>(table)?;
+        run_typed_hook(self.post_validate.as_ref(), &typed)?;
+        Ok((typed, warnings))
+    }
+
+    /// Watch the files discovery probes at `start_dir` and push typed
+    /// configs as they change. See
+    /// [`Resolver::watch`](crate::Resolver::watch); each reload is
+    /// deserialized once and passed through the typed
+    /// [`post_validate`](TypedBuilder::post_validate) hook, and one it
+    /// rejects arrives as `Err`.
+    pub fn watch(
+        self,
+        start_dir: impl AsRef<std::path::Path>,
+        options: WatchOptions,
+    ) -> Result<ConfigWatch<C>, ClapfigError>
+    where
+        C: Send + 'static,
+    {
+        let hook = self.post_validate;
+        crate::watch::spawn(self.inner, start_dir.as_ref(), options, move |table| {
+            let typed = deserialize_table::<C>(table)?;
+            run_typed_hook(hook.as_ref(), &typed)?;
+            Ok(typed)
+        })
+    }
+
+    /// Explain where `key`'s value comes from when resolving at
+    /// `start_dir`. See
+    /// [`Resolver::explain_at`](crate::Resolver::explain_at).
+    pub fn explain_at(
+        &self,
+        start_dir: impl AsRef<std::path::Path>,
+        key: &str,
+    ) -> Result<Explanation, ClapfigError> {
+        self.inner.explain_at(start_dir, key)
+    }
+
+    /// Drop `path` from the wrapped resolver's file cache. See
+    /// [`Resolver::invalidate`](crate::Resolver::invalidate).
+    pub fn invalidate(&self, path: impl AsRef<std::path::Path>) -> bool {
+        self.inner.invalidate(path)
+    }
+
+    /// Drop every file from the wrapped resolver's cache. See
+    /// [`Resolver::clear_cache`](crate::Resolver::clear_cache).
+    pub fn clear_cache(&self) {
+        self.inner.clear_cache()
+    }
+
     /// Number of files currently held in the wrapped resolver's cache.
     /// Intended for tests and diagnostics; production code should not
     /// branch on this.
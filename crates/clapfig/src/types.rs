@@ -60,6 +60,7 @@
 //! style configs from every ancestor directory and merges them, with deeper
 //! directories taking precedence — like `.editorconfig`.
 
+use std::fmt;
 use std::path::PathBuf;
 
 /// A configuration layer in the merge pipeline.
@@ -104,7 +105,9 @@ pub enum Layer {
 /// `Default` for schema-filled values.
 ///
 /// The pipeline's crate-private origin payload uses the same variants
-/// under the name `OriginLayer`. There is no public `Origin` type.
+/// under the name `OriginLayer`. There is no public `Origin` type;
+/// [`Provenance`](crate::Provenance) reports origins as flattened
+/// [`OriginFacts`](crate::OriginFacts).
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum InputType {
     /// A config file that discovery loaded.
@@ -198,6 +201,40 @@ pub enum SearchMode {
     FirstMatch,
 }
 
+/// When a [`Resolver`](crate::Resolver) re-reads a config file it has
+/// already cached.
+///
+/// The resolver caches file contents by absolute path so a tree walk pays
+/// disk I/O once per unique file. The policy decides whether a cache hit is
+/// trusted as-is or checked against the file on disk first. Whatever the
+/// policy, [`Resolver::invalidate`](crate::Resolver::invalidate) and
+/// [`Resolver::clear_cache`](crate::Resolver::clear_cache) drop entries
+/// explicitly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CacheFreshness {
+    /// Serve cached contents until they are invalidated explicitly.
+    ///
+    /// This is the default: edits made on disk after the first read are not
+    /// seen. Use it for one-shot loads and tree walks over files that do not
+    /// change during the walk.
+    #[default]
+    Never,
+    /// Check the file's length and modification time on every cache hit and
+    /// re-read it when either differs from when it was cached.
+    ///
+    /// One `stat` per file per resolution. A rewrite that keeps both the
+    /// length and the timestamp (possible on filesystems with coarse
+    /// timestamps) goes unnoticed.
+    Metadata,
+    /// Re-read the file on every resolution and replace the cached contents
+    /// when they differ.
+    ///
+    /// Catches every edit, at the cost of one read per file per resolution.
+    /// Comparing a content hash would need the same read, so this is the
+    /// content check.
+    Always,
+}
+
 /// A config operation, independent of any CLI framework.
 /// The CLI layer converts parsed clap args into this.
 ///
@@ -205,16 +242,23 @@ pub enum SearchMode {
 /// When `scope` is `None`:
 /// - **`List` / `Get`**: return the merged resolved configuration (all layers).
 /// - **`Set` / `Unset`**: write to the default (first) persist scope.
+/// - **`Migrate`**: rewrites every persist scope's file.
 ///
 /// When `scope` is `Some(name)`:
 /// - **`List` / `Get`**: return entries from that scope's config file only.
-/// - **`Set` / `Unset`**: write to that scope's config file.
+/// - **`Set` / `Unset` / `Migrate`**: write to that scope's config file.
+///
+/// `List { origins: true }` / `Get { origin: true }` annotate values with
+/// their [`OriginFacts`](crate::OriginFacts): the winning input of the
+/// merged view, or the scope file (and line) of a scoped read.
 #[derive(Debug, Clone, PartialEq)]
 pub enum ConfigAction {
     /// Show configuration key-value pairs.
     List {
         /// Target a specific persist scope's file, or `None` for merged view.
         scope: Option<String>,
+        /// Annotate every entry with where its value came from.
+        origins: bool,
     },
     Gen {
         output: Option<PathBuf>,
@@ -228,6 +272,19 @@ pub enum ConfigAction {
         key: String,
         /// Target a specific persist scope's file, or `None` for merged view.
         scope: Option<String>,
+        /// Annotate the value with where it came from.
+        origin: bool,
+    },
+    /// Show every input that supplied a key's value, in precedence
+    /// order, and which one won. Always explains the merged view.
+    Explain {
+        key: String,
+    },
+    /// Show every leaf whose resolved value differs between two
+    /// configurations, with both values and where each came from.
+    Diff {
+        left: DiffTarget,
+        right: DiffTarget,
     },
     /// Persist a value to a config file.
     Set {
@@ -242,4 +299,45 @@ pub enum ConfigAction {
         /// Target scope, or `None` for the default (first) scope.
         scope: Option<String>,
     },
+    /// Rewrite config files in place through the builder's
+    /// [`Migration`](crate::Migration)s, keeping comments.
+    Migrate {
+        /// Target scope, or `None` for every persist scope's file.
+        scope: Option<String>,
+    },
+}
+
+/// One side of a [`ConfigAction::Diff`].
+///
+/// Parsed from the CLI as `@name` for a scope and any other string for a
+/// directory.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DiffTarget {
+    /// The merged configuration resolved at a directory, as
+    /// [`Resolver::resolve_at`](crate::Resolver::resolve_at) would produce
+    /// it. A relative path is taken from the current directory.
+    Dir(PathBuf),
+    /// A named persist scope's config file on its own (not merged). A
+    /// missing file diffs as empty.
+    Scope(String),
+}
+
+impl std::str::FromStr for DiffTarget {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.strip_prefix('@') {
+            Some(scope) => DiffTarget::Scope(scope.to_owned()),
+            None => DiffTarget::Dir(PathBuf::from(s)),
+        })
+    }
+}
+
+impl fmt::Display for DiffTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DiffTarget::Dir(dir) => write!(f, "{}", dir.display()),
+            DiffTarget::Scope(scope) => write!(f, "@{scope}"),
+        }
+    }
 }
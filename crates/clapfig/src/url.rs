@@ -1,27 +1,26 @@
 //! Convert URL query parameters into config overrides for merging.
 //!
-//! Query parameters use dotted keys for nesting (`database.url=pg://`) and the
-//! same heuristic value parsing as environment variables (bool > int > float >
-//! string). Values are percent-decoded before parsing.
+//! Query parameters use dotted keys for nesting (`database.url=pg://`).
+//! Values are percent-decoded and kept as raw strings; resolve types them
+//! by the schema the same way as environment variables
+//! ([`type_raw_scalars_root`](crate::schema_walk::type_raw_scalars_root)).
 
 use percent_encoding::percent_decode_str;
 
 use crate::value::Value;
 
-use crate::env::parse_env_value;
-
 /// Parse a URL query string into `(dotted_key, Value)` pairs.
 ///
 /// Keys use `.` for nesting (same as CLI overrides). Values are
-/// percent-decoded, then parsed with the same bool/int/float/string heuristic
-/// used for env vars.
+/// percent-decoded into [`Value::String`]; the URL layer types them by the
+/// schema at resolve time.
 ///
 /// A leading `?` is stripped if present. Empty keys and bare `&` separators are
 /// silently skipped.
 ///
 /// ```ignore
 /// let overrides = query_to_overrides("port=9090&database.url=pg%3A%2F%2Fprod&debug=true");
-/// // [("port", Integer(9090)), ("database.url", String("pg://prod")), ("debug", Boolean(true))]
+/// // [("port", String("9090")), ("database.url", String("pg://prod")), ("debug", String("true"))]
 /// ```
 pub fn query_to_overrides(query: &str) -> Vec<(String, Value)> {
     let query = query.strip_prefix('?').unwrap_or(query);
@@ -47,7 +46,7 @@ pub fn query_to_overrides(query: &str) -> Vec<(String, Value)> {
             .decode_utf8_lossy()
             .into_owned();
 
-        overrides.push((key, parse_env_value(&value)));
+        overrides.push((key, Value::String(value)));
     }
 
     overrides
@@ -66,21 +65,12 @@ mod tests {
     }
 
     #[test]
-    fn integer_value() {
-        let o = query_to_overrides("port=8080");
-        assert_eq!(o[0].1, Value::Integer(8080));
-    }
-
-    #[test]
-    fn bool_value() {
-        let o = query_to_overrides("debug=true");
-        assert_eq!(o[0].1, Value::Boolean(true));
-    }
-
-    #[test]
-    fn float_value() {
-        let o = query_to_overrides("rate=1.5");
-        assert_eq!(o[0].1, Value::Float(1.5));
+    fn values_stay_raw_strings() {
+        // Typing is the schema's job at resolve time.
+        let o = query_to_overrides("port=8080&debug=true&rate=1.5");
+        assert_eq!(o[0].1, Value::String("8080".into()));
+        assert_eq!(o[1].1, Value::String("true".into()));
+        assert_eq!(o[2].1, Value::String("1.5".into()));
     }
 
     #[test]
@@ -100,7 +90,7 @@ mod tests {
     fn leading_question_mark_stripped() {
         let o = query_to_overrides("?port=3000");
         assert_eq!(o[0].0, "port");
-        assert_eq!(o[0].1, Value::Integer(3000));
+        assert_eq!(o[0].1, Value::String("3000".into()));
     }
 
     #[test]
@@ -149,8 +139,8 @@ mod tests {
         let o = query_to_overrides("port=3000&port=5000");
         assert_eq!(o.len(), 2);
         // Both are collected — the resolve pipeline handles last-wins via overrides_to_table
-        assert_eq!(o[0].1, Value::Integer(3000));
-        assert_eq!(o[1].1, Value::Integer(5000));
+        assert_eq!(o[0].1, Value::String("3000".into()));
+        assert_eq!(o[1].1, Value::String("5000".into()));
     }
 
     #[test]
@@ -379,7 +379,7 @@ fn locate_unknown_key<'a>(
 /// missing key, or an out-of-bounds array index. The callback receives
 /// this `Option` through
 /// [`UnknownKeyContext::value`](crate::UnknownKeyContext::value).
-fn lookup_value<'a>(table: &'a Map, path: &ConfigPath) -> Option<&'a Value> {
+pub(crate) fn lookup_value<'a>(table: &'a Map, path: &ConfigPath) -> Option<&'a Value> {
     enum Cursor<'a> {
         Map(&'a Map),
         Array(&'a [Value]),
@@ -0,0 +1,274 @@
+//! Live reload: poll the files a resolution probed and push re-resolved
+//! configs.
+//!
+//! [`Resolver::watch`](crate::Resolver::watch) (and the typed
+//! [`TypedResolver::watch`](crate::TypedResolver::watch)) hand the
+//! resolver to a background thread that watches every candidate path
+//! the last resolution's [`DiscoveryRecord`](crate::DiscoveryRecord)
+//! named — misses and FirstMatch's not-probed candidates included, so
+//! creating a config file where none existed is noticed like editing
+//! one. The backend is polling (file length and modification time), so
+//! it works wherever `std::fs::metadata` does: containers, network
+//! mounts, no inotify required. A rewrite that keeps both the length
+//! and the modification time (possible on filesystems with coarse
+//! timestamps) is not seen until the next change that alters either.
+//!
+//! A change is debounced — the watcher waits until the probed files
+//! stop changing for [`WatchOptions::debounce`] — then the changed
+//! paths are evicted from the resolver's file cache and the **full**
+//! pipeline re-runs: discovery, every layer, validation, and the
+//! `post_validate` hook. A config that resolves and validates is
+//! delivered as `Ok` (only when it differs from the last one delivered,
+//! or follows a failure); a reload that fails is delivered as `Err` so
+//! the caller can report it while it keeps running on its last good
+//! config. The watched path set is refreshed from every successful
+//! discovery, so a newly reachable candidate joins the watch.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use crate::builder::Resolver;
+use crate::error::ClapfigError;
+use crate::file::{self, FileStamp};
+use crate::value::Map;
+
+/// Polling and debounce timing for a config watch.
+///
+/// The defaults (poll every 500 ms, settle for 200 ms) suit config files
+/// edited by hand or rewritten by deployment tooling.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WatchOptions {
+    /// How often the watched paths are checked for changes.
+    pub poll_interval: Duration,
+    /// How long the watched paths must stay unchanged after a change
+    /// before the config is re-resolved, so an editor's
+    /// truncate-then-write (or a burst of writes across several files)
+    /// reloads once.
+    pub debounce: Duration,
+}
+
+impl Default for WatchOptions {
+    fn default() -> Self {
+        Self {
+            poll_interval: Duration::from_millis(500),
+            debounce: Duration::from_millis(200),
+        }
+    }
+}
+
+impl WatchOptions {
+    /// Set [`poll_interval`](Self::poll_interval).
+    pub fn poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// Set [`debounce`](Self::debounce).
+    pub fn debounce(mut self, debounce: Duration) -> Self {
+        self.debounce = debounce;
+        self
+    }
+}
+
+/// A running config watch, produced by
+/// [`Resolver::watch`](crate::Resolver::watch) and
+/// [`TypedResolver::watch`](crate::TypedResolver::watch).
+///
+/// [`initial`](Self::initial) is the config resolved when the watch
+/// started; every later reload arrives through [`recv`](Self::recv) and
+/// its siblings. Dropping the handle stops the watcher thread.
+pub struct ConfigWatch<T> {
+    initial: T,
+    updates: Receiver<Result<T, ClapfigError>>,
+    stop: Option<Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl<T> ConfigWatch<T> {
+    /// The config resolved when the watch started.
+    pub fn initial(&self) -> &T {
+        &self.initial
+    }
+
+    /// Block until the next reload. `Ok` carries a config that resolved
+    /// and validated; `Err` reports a reload that failed (the previous
+    /// config is still the last good one). `None` once the watcher has
+    /// stopped.
+    pub fn recv(&self) -> Option<Result<T, ClapfigError>> {
+        self.updates.recv().ok()
+    }
+
+    /// Like [`recv`](Self::recv), but give up after `timeout`.
+    pub fn recv_timeout(&self, timeout: Duration) -> Option<Result<T, ClapfigError>> {
+        self.updates.recv_timeout(timeout).ok()
+    }
+
+    /// The next reload if one is already waiting, without blocking.
+    pub fn try_recv(&self) -> Option<Result<T, ClapfigError>> {
+        self.updates.try_recv().ok()
+    }
+
+    /// Iterate reloads as they arrive, blocking between them.
+    pub fn iter(&self) -> impl Iterator<Item = Result<T, ClapfigError>> + '_ {
+        std::iter::from_fn(|| self.recv())
+    }
+}
+
+impl<T> Drop for ConfigWatch<T> {
+    fn drop(&mut self) {
+        // Dropping the sender wakes the watcher out of its wait.
+        self.stop.take();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+fn stamps(paths: &[PathBuf]) -> BTreeMap<PathBuf, FileStamp> {
+    paths
+        .iter()
+        .map(|path| (path.clone(), file::stamp(path)))
+        .collect()
+}
+
+/// Resolve once at `start_dir`, then hand `resolver` to a watcher thread.
+///
+/// `convert` turns each validated [`Map`] into the delivered config (the
+/// identity for the Map-out resolver; deserialize plus the typed hook for
+/// the typed one). The initial resolution's failure is returned, not
+/// delivered: a watch never starts on a config that does not validate.
+pub(crate) fn spawn<T, F>(
+    resolver: Resolver,
+    start_dir: &Path,
+    options: WatchOptions,
+    convert: F,
+) -> Result<ConfigWatch<T>, ClapfigError>
+where
+    T: Send + 'static,
+    F: Fn(Map) -> Result<T, ClapfigError> + Send + 'static,
+{
+    let start_dir = start_dir.to_path_buf();
+    let (paths, result) = resolver.resolve_watched(&start_dir);
+    let table = result?;
+    let paths = paths.unwrap_or_default();
+    // No path is known before the first resolution, so these stamps
+    // follow it; stamping before returning at least makes an edit made
+    // right after `watch()` comes back a change rather than the baseline.
+    let seen = stamps(&paths);
+    let initial = convert(table.clone())?;
+    let (updates_tx, updates) = mpsc::channel();
+    let (stop, stop_rx) = mpsc::channel();
+    let thread = std::thread::spawn(move || {
+        let watcher = Watcher {
+            resolver,
+            start_dir,
+            options,
+            paths,
+            seen,
+            last: table,
+            failed: false,
+            stop: stop_rx,
+        };
+        watcher.run(&updates_tx, &convert);
+    });
+    Ok(ConfigWatch {
+        initial,
+        updates,
+        stop: Some(stop),
+        thread: Some(thread),
+    })
+}
+
+struct Watcher {
+    resolver: Resolver,
+    start_dir: PathBuf,
+    options: WatchOptions,
+    paths: Vec<PathBuf>,
+    /// `paths` as of the last resolution.
+    seen: BTreeMap<PathBuf, FileStamp>,
+    /// The last config delivered (or the initial one).
+    last: Map,
+    /// Whether the last delivery was an `Err`.
+    failed: bool,
+    stop: Receiver<()>,
+}
+
+impl Watcher {
+    /// Sleep for `duration`; `false` when the handle was dropped.
+    fn wait(&self, duration: Duration) -> bool {
+        matches!(
+            self.stop.recv_timeout(duration),
+            Err(RecvTimeoutError::Timeout)
+        )
+    }
+
+    fn run<T>(
+        mut self,
+        updates: &Sender<Result<T, ClapfigError>>,
+        convert: &dyn Fn(Map) -> Result<T, ClapfigError>,
+    ) {
+        loop {
+            if !self.wait(self.options.poll_interval) {
+                return;
+            }
+            let mut now = stamps(&self.paths);
+            if now == self.seen {
+                continue;
+            }
+            // Settle: reload once the files stop changing.
+            loop {
+                if !self.wait(self.options.debounce) {
+                    return;
+                }
+                let settled = stamps(&self.paths);
+                if settled == now {
+                    break;
+                }
+                now = settled;
+            }
+            let changed: Vec<PathBuf> = now
+                .iter()
+                .filter(|(path, stamp)| self.seen.get(*path) != Some(stamp))
+                .map(|(path, _)| path.clone())
+                .collect();
+            tracing::debug!(changed = changed.len(), "config watch: reloading");
+            for path in &changed {
+                self.resolver.invalidate(path);
+            }
+            // Take the baseline before resolving: an edit saved while the
+            // resolution runs then differs from it and reloads again,
+            // rather than being recorded as already seen.
+            self.seen = now;
+            let (paths, result) = self.resolver.resolve_watched(&self.start_dir);
+            if let Some(paths) = paths {
+                // Only paths this discovery newly named lack a baseline.
+                let mut seen = std::mem::take(&mut self.seen);
+                seen.retain(|path, _| paths.contains(path));
+                for path in &paths {
+                    seen.entry(path.clone())
+                        .or_insert_with(|| file::stamp(path));
+                }
+                self.seen = seen;
+                self.paths = paths;
+            }
+            let update = match result {
+                // Unchanged and already delivered: nothing to report. After
+                // a failed reload the same config is re-sent, so the caller
+                // learns the failure was fixed.
+                Ok(table) if table == self.last && !self.failed => continue,
+                Ok(table) => {
+                    self.last = table.clone();
+                    convert(table)
+                }
+                Err(e) => Err(e),
+            };
+            self.failed = update.is_err();
+            if updates.send(update).is_err() {
+                return;
+            }
+        }
+    }
+}
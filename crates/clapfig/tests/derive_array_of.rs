@@ -472,3 +472,74 @@ fn declared_array_default_wins_over_empty_materialization() {
         .unwrap();
     assert_eq!(cfg.tags, vec!["a".to_string(), "b".to_string()]);
 }
+
+// -- Merge strategies across layers ------------------------------------------
+
+#[derive(Schema, Serialize, Deserialize, Debug)]
+struct LayeredCfg {
+    /// Accumulates across files.
+    #[clapfig(merge = "append")]
+    tags: Vec<String>,
+    /// Same-named plugins merge; new ones append.
+    #[clapfig(merge_by_key = "name")]
+    plugins: Vec<Plugin>,
+    /// The highest file's table wins whole.
+    #[clapfig(merge = "replace")]
+    aliases: std::collections::BTreeMap<String, String>,
+}
+
+#[test]
+fn merge_strategies_combine_files_by_field() {
+    let global = TempDir::new().unwrap();
+    let project = TempDir::new().unwrap();
+    std::fs::write(
+        global.path().join("t.toml"),
+        "tags = [\"a\"]\n[[plugins]]\nname = \"audit\"\npriority = 5\n\
+         [aliases]\nb = \"build\"\n",
+    )
+    .unwrap();
+    std::fs::write(
+        project.path().join("t.toml"),
+        "tags = [\"b\"]\n[[plugins]]\nname = \"audit\"\npriority = 1\n\
+         [[plugins]]\nname = \"lint\"\n[aliases]\nc = \"check\"\n",
+    )
+    .unwrap();
+    let cfg: LayeredCfg = Clapfig::typed::<LayeredCfg>()
+        .app_name("t")
+        .search_paths(vec![
+            SearchPath::Path(global.path().to_path_buf()),
+            SearchPath::Path(project.path().to_path_buf()),
+        ])
+        .no_env()
+        .load()
+        .unwrap();
+    assert_eq!(cfg.tags, ["a", "b"]);
+    assert_eq!(
+        cfg.plugins,
+        vec![
+            Plugin {
+                name: "audit".into(),
+                priority: 1,
+            },
+            Plugin {
+                name: "lint".into(),
+                priority: 10,
+            },
+        ]
+    );
+    assert_eq!(cfg.aliases.keys().collect::<Vec<_>>(), ["c"]);
+}
+
+#[derive(Schema, Serialize, Deserialize, Debug)]
+struct BadMergeKeyCfg {
+    #[clapfig(merge_by_key = "id")]
+    plugins: Vec<Plugin>,
+}
+
+#[test]
+#[should_panic(
+    expected = "field `plugins`: merge_by_key(\"id\") names a field `Plugin` does not declare"
+)]
+fn merge_by_key_on_an_undeclared_field_panics_at_first_schema_call() {
+    let _ = BadMergeKeyCfg::schema();
+}
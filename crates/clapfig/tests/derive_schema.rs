@@ -295,6 +295,75 @@ fn explicit_env_attribute_is_carried_into_static_schema() {
     assert_eq!(leaf.env, Some("X_PORT"));
 }
 
+// -- `#[clapfig(secret)]` marks the leaf sensitive -------------------------
+
+#[derive(Schema, Serialize, Deserialize, Debug)]
+struct SecretConfig {
+    /// API token.
+    #[clapfig(secret)]
+    token: Option<String>,
+
+    /// Fallback tokens.
+    #[clapfig(secret)]
+    spares: Vec<String>,
+
+    #[clapfig(default = "h")]
+    host: String,
+}
+
+#[test]
+fn secret_attribute_marks_the_leaf_sensitive() {
+    let shape = SecretConfig::schema();
+    let field = |name: &str| &shape.fields.iter().find(|f| f.name == name).unwrap().field;
+    match field("token") {
+        clapfig::runtime::Shape::Leaf(leaf) => assert!(leaf.sensitive),
+        other => panic!("expected Leaf, got {other:?}"),
+    }
+    match field("spares") {
+        clapfig::runtime::Shape::Array(array) => match array.item.as_ref() {
+            clapfig::runtime::Shape::Leaf(leaf) => assert!(leaf.sensitive, "item is marked"),
+            other => panic!("expected Leaf item, got {other:?}"),
+        },
+        other => panic!("expected Array, got {other:?}"),
+    }
+    match field("host") {
+        clapfig::runtime::Shape::Leaf(leaf) => assert!(!leaf.sensitive),
+        other => panic!("expected Leaf, got {other:?}"),
+    }
+
+    let json = clapfig::json_schema::generate_schema(SecretConfig::schema().clone());
+    assert_eq!(json["properties"]["token"]["writeOnly"], true);
+    assert_eq!(json["properties"]["spares"]["writeOnly"], true);
+    assert!(json["properties"]["host"].get("writeOnly").is_none());
+}
+
+#[derive(Schema, Serialize, Deserialize, Debug)]
+struct FileRefConfig {
+    /// Path to the API token.
+    #[clapfig(from_file, secret)]
+    token: Option<String>,
+
+    #[clapfig(default = "h")]
+    host: String,
+}
+
+#[test]
+fn from_file_attribute_marks_the_leaf() {
+    let shape = FileRefConfig::schema();
+    let field = |name: &str| &shape.fields.iter().find(|f| f.name == name).unwrap().field;
+    match field("token") {
+        clapfig::runtime::Shape::Leaf(leaf) => {
+            assert!(leaf.from_file);
+            assert!(leaf.optional);
+        }
+        other => panic!("expected Leaf, got {other:?}"),
+    }
+    match field("host") {
+        clapfig::runtime::Shape::Leaf(leaf) => assert!(!leaf.from_file),
+        other => panic!("expected Leaf, got {other:?}"),
+    }
+}
+
 // -- `#[clapfig(value)]` opt-in to LeafType::Value --------------------------
 
 #[derive(Schema, Serialize, Deserialize, Debug)]
@@ -1153,3 +1222,205 @@ fn clapfig_value_opts_a_shape_changing_deserializer_out_of_the_type_check() {
         .unwrap();
     assert_eq!(cfg.port, 8080);
 }
+
+#[derive(Schema, Serialize, Deserialize, Debug, PartialEq)]
+struct AliasConfig {
+    /// Request timeout in seconds.
+    #[clapfig(default = 30, alias = "timeout_secs")]
+    timeout: u64,
+    /// Hosts to try, in order.
+    #[clapfig(default = [], alias = "servers")]
+    hosts: Vec<String>,
+    /// Old verbosity switch.
+    #[clapfig(deprecated = "use `log_level` instead")]
+    verbose: Option<bool>,
+}
+
+#[test]
+fn alias_and_deprecated_attributes_reach_load_and_outputs() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(
+        dir.path().join("alias.toml"),
+        "timeout_secs = 5\nservers = [\"a\", \"b\"]\nverbose = true\n",
+    )
+    .unwrap();
+
+    let cfg: AliasConfig = Clapfig::typed::<AliasConfig>()
+        .app_name("alias")
+        .file_name("alias.toml")
+        .search_paths(vec![SearchPath::Path(dir.path().to_path_buf())])
+        .no_env()
+        .load()
+        .unwrap();
+    assert_eq!(
+        cfg,
+        AliasConfig {
+            timeout: 5,
+            hosts: vec!["a".into(), "b".into()],
+            verbose: Some(true),
+        }
+    );
+
+    let json = clapfig::json_schema::generate_schema(AliasConfig::schema().clone());
+    assert_eq!(json["properties"]["verbose"]["deprecated"], true);
+    assert!(json["properties"]["timeout"].get("deprecated").is_none());
+
+    let result = Clapfig::typed::<AliasConfig>()
+        .app_name("alias")
+        .no_env()
+        .handle(&ConfigAction::Gen { output: None })
+        .unwrap();
+    let ConfigResult::Template(t) = result else {
+        panic!("expected Template, got {result:?}");
+    };
+    assert!(t.contains("timeout"), "template:\n{t}");
+    assert!(!t.contains("verbose"), "deprecated field left out:\n{t}");
+}
+
+#[derive(Schema, Serialize, Deserialize, Debug, PartialEq)]
+struct ConstraintConfig {
+    /// Service name: lowercase letters and dashes.
+    #[clapfig(default = "api", pattern = "^[a-z-]+$", max_len = 32)]
+    name: String,
+    /// Fraction of requests to sample.
+    #[clapfig(default = 0.1, exclusive_min = 0, max = 1.0)]
+    sample_rate: f64,
+    /// Upstream hosts, each listed once.
+    #[clapfig(default = ["a"], non_empty, unique, max_items = 8)]
+    hosts: Vec<String>,
+}
+
+fn load_constraint_config(text: &str) -> Result<ConstraintConfig, clapfig::ClapfigError> {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(dir.path().join("svc.toml"), text).unwrap();
+    Clapfig::typed::<ConstraintConfig>()
+        .app_name("svc")
+        .file_name("svc.toml")
+        .search_paths(vec![SearchPath::Path(dir.path().to_path_buf())])
+        .no_env()
+        .load()
+}
+
+#[test]
+fn constraint_attributes_validate_at_load_and_reach_json_schema() {
+    let cfg = load_constraint_config("name = \"edge-proxy\"\nhosts = [\"a\", \"b\"]\n").unwrap();
+    assert_eq!(cfg.name, "edge-proxy");
+    assert_eq!(cfg.hosts, ["a", "b"]);
+
+    let cases = [
+        ("name = \"Edge\"\n", "name", "does not match pattern"),
+        (
+            "sample_rate = 0\n",
+            "sample_rate",
+            "out of range (allowed: > 0 and <= 1)",
+        ),
+        (
+            "hosts = []\n",
+            "hosts",
+            "array has 0 items (allowed: 1..=8)",
+        ),
+        (
+            "hosts = [\"a\", \"a\"]\n",
+            "hosts",
+            "array[1] duplicates array[0]",
+        ),
+    ];
+    for (text, key, reason) in cases {
+        let err = load_constraint_config(text).unwrap_err();
+        let clapfig::ClapfigError::InvalidValue { origin, .. } = &err else {
+            panic!("{text}: expected InvalidValue, got {err:?}");
+        };
+        assert_eq!(origin.line_col().map(|(line, _)| line), Some(1), "{text}");
+        let msg = err.to_string();
+        assert!(msg.contains(&format!("'{key}'")), "{text}: {msg}");
+        assert!(msg.contains(reason), "{text}: {msg}");
+    }
+
+    let json = clapfig::json_schema::generate_schema(ConstraintConfig::schema().clone());
+    let props = &json["properties"];
+    assert_eq!(props["name"]["pattern"], "^[a-z-]+$");
+    assert_eq!(props["name"]["maxLength"], 32);
+    assert_eq!(props["sample_rate"]["exclusiveMinimum"], 0.0);
+    assert_eq!(props["sample_rate"]["maximum"], 1.0);
+    assert_eq!(props["hosts"]["minItems"], 1);
+    assert_eq!(props["hosts"]["maxItems"], 8);
+    assert_eq!(props["hosts"]["uniqueItems"], true);
+}
+
+#[derive(Schema, Serialize, Deserialize, Debug, PartialEq)]
+struct PathConfig {
+    /// Where state lives.
+    #[clapfig(is_dir)]
+    data_dir: std::path::PathBuf,
+    /// Plugins to load, each an existing file.
+    #[clapfig(is_file)]
+    plugins: Vec<std::path::PathBuf>,
+    /// Log file; need not exist yet.
+    log_file: Option<std::path::PathBuf>,
+}
+
+fn load_path_config(
+    dir: &std::path::Path,
+    text: &str,
+) -> Result<PathConfig, clapfig::ClapfigError> {
+    std::fs::write(dir.join("svc.toml"), text).unwrap();
+    Clapfig::typed::<PathConfig>()
+        .app_name("svc")
+        .file_name("svc.toml")
+        .search_paths(vec![SearchPath::Path(dir.to_path_buf())])
+        .no_env()
+        .load()
+}
+
+#[test]
+fn pathbuf_fields_resolve_against_their_config_file() {
+    let dir = TempDir::new().unwrap();
+    std::fs::create_dir(dir.path().join("data")).unwrap();
+    std::fs::write(dir.path().join("a.so"), "").unwrap();
+
+    let cfg = load_path_config(
+        dir.path(),
+        "data_dir = \"data\"\nplugins = [\"a.so\"]\nlog_file = \"logs/svc.log\"\n",
+    )
+    .unwrap();
+    assert_eq!(cfg.data_dir, dir.path().join("data"));
+    assert_eq!(cfg.plugins, [dir.path().join("a.so")]);
+    assert_eq!(cfg.log_file, Some(dir.path().join("logs/svc.log")));
+
+    let cases = [
+        ("data_dir = \"nope\"\n", "data_dir", "does not exist"),
+        ("data_dir = \"a.so\"\n", "data_dir", "is not a directory"),
+        (
+            "data_dir = \"data\"\nplugins = [\"data\"]\n",
+            "plugins[0]",
+            "is not a file",
+        ),
+    ];
+    for (text, key, reason) in cases {
+        let err = load_path_config(dir.path(), text).unwrap_err();
+        let msg = err.to_string();
+        assert!(msg.contains(&format!("'{key}'")), "{text}: {msg}");
+        assert!(msg.contains(reason), "{text}: {msg}");
+    }
+
+    let json = clapfig::json_schema::generate_schema(PathConfig::schema().clone());
+    let props = &json["properties"];
+    assert_eq!(props["data_dir"]["type"], "string");
+    assert_eq!(props["data_dir"]["x-path"]["kind"], "dir");
+    assert_eq!(props["plugins"]["items"]["x-path"]["kind"], "file");
+
+    let result = Clapfig::typed::<PathConfig>()
+        .app_name("svc")
+        .no_env()
+        .handle(&ConfigAction::Gen { output: None })
+        .unwrap();
+    let ConfigResult::Template(t) = result else {
+        panic!("expected Template, got {result:?}");
+    };
+    assert!(
+        t.contains(
+            "# Path: relative to this file's directory; ~ expands; must be an existing directory."
+        ),
+        "template:\n{t}"
+    );
+}
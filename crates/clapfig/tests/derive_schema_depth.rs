@@ -487,6 +487,7 @@ fn handle_set_then_get_roundtrips_through_macro_path() {
         .handle(&ConfigAction::Get {
             key: "port".into(),
             scope: Some("local".into()),
+            origin: false,
         })
         .unwrap();
     match got {
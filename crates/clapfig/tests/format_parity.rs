@@ -4,7 +4,10 @@
 //! [`Value`] tree, produce identical validation errors on the same
 //! mistakes, and yield the same JSON Schema. This file carries the YAML
 //! slice (WS03): every case pairs the TOML original with its YAML
-//! spelling and asserts the outcomes are equal — not merely similar.
+//! spelling and asserts the outcomes are equal — not merely similar. The
+//! INI cases pair TOML with INI, whose values are all strings until the
+//! schema types them; the JSON5 cases pair TOML with JSON5, whose comments
+//! and trailing commas must never reach the tree.
 //!
 //! Divergence-by-design (datetime lexical forms ride strings in YAML and
 //! are coerced by the schema pass) is covered by the same equality: the
@@ -179,3 +182,127 @@ fn json_schema_export_is_format_independent() {
     assert_eq!(from_toml_context["type"], serde_json::json!("object"));
     assert!(from_toml_context["properties"]["database"]["properties"]["pool_size"].is_object());
 }
+
+const TOML_SPELLING: &str = r#"host = "example.com"
+port = 9090
+level = "debug"
+launched = 2020-05-27T07:32:00Z
+
+[database]
+url = "pg://prod"
+pool_size = 8
+"#;
+
+#[test]
+fn ini_config_resolves_to_the_identical_value_tree() {
+    let toml = load("toml", TOML_SPELLING).unwrap();
+    let ini = load(
+        "ini",
+        "; production
+host = example.com
+port = 9090
+level = debug
+launched = 2020-05-27T07:32:00Z
+
+[database]
+url = pg://prod
+pool_size = 8
+",
+    )
+    .unwrap();
+    assert_eq!(toml, ini, "the schema types every INI string");
+    assert_eq!(ini["port"], Value::Integer(9090));
+}
+
+#[test]
+fn ini_type_mismatch_produces_the_identical_error_at_the_ini_line() {
+    let toml = load("toml", "port = \"not-a-number\"\n").unwrap_err();
+    let ini = load("ini", "; comment\nport = not-a-number\n").unwrap_err();
+    match (toml, ini) {
+        (
+            ClapfigError::InvalidValue {
+                key: tk,
+                reason: tr,
+                ..
+            },
+            ClapfigError::InvalidValue {
+                key: ik,
+                reason: ir,
+                origin,
+            },
+        ) => {
+            assert_eq!(tk, ik);
+            assert_eq!(tr, ir);
+            assert_eq!(origin.line_col(), Some((2, 8)));
+        }
+        (t, i) => panic!("expected matching InvalidValue, got {t:?} vs {i:?}"),
+    }
+}
+
+#[test]
+fn ini_unknown_key_produces_the_identical_error_at_the_ini_line() {
+    let toml = load("toml", "bogus = 1\n").unwrap_err();
+    let ini = load("ini", "port = 1\n[database]\nbogus = 1\n").unwrap_err();
+    match (toml, ini) {
+        (ClapfigError::UnknownKeys(t), ClapfigError::UnknownKeys(i)) => {
+            assert_eq!(t[0].key, "bogus");
+            assert_eq!(i[0].key, "database.bogus");
+            assert_eq!(i[0].line, 3);
+        }
+        (t, i) => panic!("expected UnknownKeys, got {t:?} vs {i:?}"),
+    }
+}
+
+#[test]
+fn json5_config_resolves_to_the_identical_value_tree() {
+    let toml = load("toml", TOML_SPELLING).unwrap();
+    let json5 = load(
+        "json5",
+        r#"// production
+{
+  host: 'example.com',
+  port: 9090, /* the public port */
+  level: "debug",
+  launched: "2020-05-27T07:32:00Z",
+  database: {
+    url: "pg://prod",
+    pool_size: 8,
+  },
+}
+"#,
+    )
+    .unwrap();
+    assert_eq!(
+        toml, json5,
+        "comments and trailing commas never reach the tree"
+    );
+}
+
+#[test]
+fn json5_type_mismatch_produces_the_identical_error_at_the_json5_line() {
+    let toml = load("toml", "port = \"not-a-number\"\n").unwrap_err();
+    let json5 = load(
+        "jsonc",
+        "{\n  // comment\n  \"port\": \"not-a-number\"\n}\n",
+    )
+    .unwrap_err();
+    match (toml, json5) {
+        (
+            ClapfigError::InvalidValue {
+                key: tk,
+                reason: tr,
+                ..
+            },
+            ClapfigError::InvalidValue {
+                key: jk,
+                reason: jr,
+                origin,
+            },
+        ) => {
+            assert_eq!(tk, jk);
+            assert_eq!(tr, jr);
+            assert_eq!(origin.line_col(), Some((3, 11)));
+        }
+        (t, j) => panic!("expected matching InvalidValue, got {t:?} vs {j:?}"),
+    }
+}
@@ -124,8 +124,12 @@ fn handle_get_list_deserialize_failure_is_a_type_error_not_a_hook_rejection() {
         ConfigAction::Get {
             key: "rule".into(),
             scope: None,
+            origin: false,
+        },
+        ConfigAction::List {
+            scope: None,
+            origins: false,
         },
-        ConfigAction::List { scope: None },
     ] {
         let err = builder().handle(&action).unwrap_err();
         match &err {
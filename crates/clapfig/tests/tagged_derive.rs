@@ -337,6 +337,7 @@ fn tagged_config_get_returns_tag_documentation() {
         .handle(&clapfig::ConfigAction::Get {
             key: "kind".into(),
             scope: None,
+            origin: false,
         })
         .unwrap();
     match result {
@@ -286,3 +286,41 @@ fn typed_resolve_at_with_unknowns_routes_collected_keys() {
     assert_eq!(unknowns.len(), 1);
     assert_eq!(unknowns[0].path, "extra_key");
 }
+
+#[test]
+fn typed_resolve_at_with_origins_names_the_contributing_file() {
+    let root = content_tree();
+    let resolver = site_builder().build_resolver().unwrap();
+
+    let (at_blog, provenance) = resolver
+        .resolve_at_with_origins(root.path().join("blog").join("posts"))
+        .unwrap();
+    assert_eq!(at_blog.layout, "root");
+    assert!(at_blog.drafts);
+
+    // Each key names the file that won it, not the last file merged.
+    let layout = provenance.get_dotted("layout").unwrap();
+    assert_eq!(layout.file, Some(root.path().join("site.toml")));
+    let drafts = provenance.get_dotted("drafts").unwrap();
+    assert_eq!(
+        drafts.file,
+        Some(root.path().join("blog").join("site.toml"))
+    );
+    assert_eq!(drafts.line_col(), Some((1, 10)));
+}
+
+#[test]
+fn typed_load_with_origins_reports_defaults() {
+    let dir = TempDir::new().unwrap();
+    let (cfg, provenance) = Clapfig::typed::<SiteConfig>()
+        .app_name("site")
+        .file_name("site.toml")
+        .search_paths(vec![SearchPath::Path(dir.path().to_path_buf())])
+        .no_env()
+        .load_with_origins()
+        .unwrap();
+    assert_eq!(cfg.layout, "default");
+    let layout = provenance.get_dotted("layout").unwrap();
+    assert_eq!(layout.input_type, Some(clapfig::InputType::Default));
+    assert_eq!(provenance.leaves().len(), 2);
+}
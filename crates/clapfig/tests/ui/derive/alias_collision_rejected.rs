@@ -0,0 +1,13 @@
+use clapfig::Schema;
+
+// An alias that is already another field's name would make one key name
+// two fields.
+#[derive(Schema)]
+struct Bad {
+    port: u16,
+
+    #[clapfig(alias = "port")]
+    listen: u16,
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use clapfig::Schema;
+
+// `from_file` replaces a path with the file's contents, which only a
+// string leaf can hold.
+#[derive(Schema)]
+struct Bad {
+    #[clapfig(from_file)]
+    port: u16,
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use clapfig::Schema;
+use std::collections::BTreeMap;
+
+// A map merges entry by entry or is replaced whole; the array strategies
+// have no meaning for it.
+#[derive(Schema)]
+struct Bad {
+    #[clapfig(merge = "append")]
+    aliases: BTreeMap<String, String>,
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use clapfig::Schema;
+
+// The path options belong to `PathBuf` fields: a `String` is not
+// resolved against its config file, so checking it on disk would test
+// the wrong path.
+#[derive(Schema)]
+struct Bad {
+    #[clapfig(must_exist)]
+    data_dir: String,
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use clapfig::Schema;
+
+// `pattern` constrains text; an integer field already carries its
+// type's range.
+#[derive(Schema)]
+struct Bad {
+    #[clapfig(pattern = "^[0-9]+$")]
+    port: u16,
+}
+
+fn main() {}
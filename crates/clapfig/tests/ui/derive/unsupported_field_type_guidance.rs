@@ -5,7 +5,7 @@ use clapfig::Schema;
 // set and the `#[clapfig(value)]` escape hatch — not a raw E0277.
 #[derive(Schema)]
 struct Bad {
-    path: std::path::PathBuf,
+    timeout: std::time::Duration,
 }
 
 fn main() {}
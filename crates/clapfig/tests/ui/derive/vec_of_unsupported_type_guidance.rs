@@ -7,7 +7,7 @@ use clapfig::Schema;
 // blanket syntactic rejection.
 #[derive(Schema)]
 struct Bad {
-    paths: Vec<std::path::PathBuf>,
+    timeouts: Vec<std::time::Duration>,
 }
 
 fn main() {}
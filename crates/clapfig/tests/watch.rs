@@ -0,0 +1,208 @@
+//! Integration tests for live reload: `Resolver::watch` and
+//! `TypedResolver::watch` polling every probed path, debouncing, and
+//! pushing only configs that resolved and passed `post_validate`.
+
+#![cfg(feature = "derive")]
+
+use clapfig::{Boundary, Clapfig, ClapfigError, Schema, SearchPath, WatchOptions};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::time::Duration;
+use tempfile::TempDir;
+
+#[derive(Schema, Serialize, Deserialize, Debug, PartialEq)]
+struct SiteConfig {
+    /// Page layout name.
+    #[clapfig(default = "default")]
+    layout: String,
+
+    /// Worker count.
+    #[clapfig(default = 1)]
+    workers: i64,
+}
+
+/// A marker-bounded ancestor-walking typed builder over `site.toml`.
+fn site_builder() -> clapfig::TypedBuilder<SiteConfig> {
+    Clapfig::typed::<SiteConfig>()
+        .app_name("site")
+        .file_name("site.toml")
+        .search_paths(vec![SearchPath::Ancestors(Boundary::Marker(".marker"))])
+        .no_env()
+}
+
+fn fast() -> WatchOptions {
+    WatchOptions::default()
+        .poll_interval(Duration::from_millis(20))
+        .debounce(Duration::from_millis(20))
+}
+
+const WAIT: Duration = Duration::from_secs(5);
+
+#[test]
+fn editing_a_probed_file_delivers_the_new_config() {
+    let root = TempDir::new().unwrap();
+    fs::write(root.path().join(".marker"), "").unwrap();
+    let path = root.path().join("site.toml");
+    fs::write(&path, "layout = \"a\"\n").unwrap();
+
+    let watch = site_builder()
+        .build_resolver()
+        .unwrap()
+        .watch(root.path(), fast())
+        .unwrap();
+    assert_eq!(watch.initial().layout, "a");
+
+    fs::write(&path, "layout = \"blog\"\n").unwrap();
+    let next = watch.recv_timeout(WAIT).expect("a reload").unwrap();
+    assert_eq!(next.layout, "blog");
+}
+
+#[test]
+fn creating_a_previously_missing_file_is_noticed() {
+    let root = TempDir::new().unwrap();
+    fs::write(root.path().join(".marker"), "").unwrap();
+    let leaf = root.path().join("posts");
+    fs::create_dir(&leaf).unwrap();
+
+    let watch = site_builder()
+        .build_resolver()
+        .unwrap()
+        .watch(&leaf, fast())
+        .unwrap();
+    assert_eq!(watch.initial().layout, "default");
+
+    // Nothing existed at the leaf: the miss is watched all the same.
+    fs::write(leaf.join("site.toml"), "workers = 4\n").unwrap();
+    let next = watch.recv_timeout(WAIT).expect("a reload").unwrap();
+    assert_eq!(next.workers, 4);
+}
+
+#[test]
+fn a_rejected_reload_arrives_as_err_and_a_fix_recovers() {
+    let root = TempDir::new().unwrap();
+    fs::write(root.path().join(".marker"), "").unwrap();
+    let path = root.path().join("site.toml");
+    fs::write(&path, "workers = 2\n").unwrap();
+
+    let watch = site_builder()
+        .post_validate(|c: &SiteConfig| {
+            if c.workers > 0 {
+                Ok(())
+            } else {
+                Err("workers must be positive".into())
+            }
+        })
+        .build_resolver()
+        .unwrap()
+        .watch(root.path(), fast())
+        .unwrap();
+
+    fs::write(&path, "workers = -10\n").unwrap();
+    let err = watch.recv_timeout(WAIT).expect("a reload").unwrap_err();
+    assert!(
+        matches!(err, ClapfigError::PostValidationFailed(ref m) if m.contains("positive")),
+        "{err:?}"
+    );
+
+    fs::write(&path, "workers = 2  # fixed\n").unwrap();
+    let next = watch.recv_timeout(WAIT).expect("a reload").unwrap();
+    assert_eq!(
+        next.workers, 2,
+        "the fix is delivered even though it matches the initial config"
+    );
+}
+
+#[test]
+fn an_unparseable_edit_arrives_as_err() {
+    let root = TempDir::new().unwrap();
+    fs::write(root.path().join(".marker"), "").unwrap();
+    let path = root.path().join("site.toml");
+    fs::write(&path, "workers = 2\n").unwrap();
+
+    let watch = Clapfig::builder(SiteConfig::schema().clone())
+        .app_name("site")
+        .file_name("site.toml")
+        .search_paths(vec![SearchPath::Ancestors(Boundary::Marker(".marker"))])
+        .no_env()
+        .build_resolver()
+        .unwrap()
+        .watch(root.path(), fast())
+        .unwrap();
+    assert_eq!(
+        watch.initial().get("workers"),
+        Some(&clapfig::value::Value::Integer(2))
+    );
+
+    fs::write(&path, "workers = [1,\n").unwrap();
+    assert!(watch.recv_timeout(WAIT).expect("a reload").is_err());
+}
+
+#[test]
+fn an_edit_saved_during_a_reload_is_not_lost() {
+    let root = TempDir::new().unwrap();
+    fs::write(root.path().join(".marker"), "").unwrap();
+    let path = root.path().join("site.toml");
+    fs::write(&path, "workers = 1\n").unwrap();
+
+    // The hook runs inside the reload, after the file was read: saving
+    // from it is an edit landing while the resolution is in progress.
+    let hook_path = path.clone();
+    let watch = Clapfig::builder(SiteConfig::schema().clone())
+        .app_name("site")
+        .file_name("site.toml")
+        .search_paths(vec![SearchPath::Ancestors(Boundary::Marker(".marker"))])
+        .no_env()
+        .post_validate(move |table| {
+            if table.get("workers") == Some(&clapfig::value::Value::Integer(2)) {
+                fs::write(&hook_path, "workers = 30\n").unwrap();
+            }
+            Ok(())
+        })
+        .build_resolver()
+        .unwrap()
+        .watch(root.path(), fast())
+        .unwrap();
+
+    fs::write(&path, "workers = 2\n").unwrap();
+    let next = watch.recv_timeout(WAIT).expect("a reload").unwrap();
+    assert_eq!(
+        next.get("workers"),
+        Some(&clapfig::value::Value::Integer(2))
+    );
+    let next = watch
+        .recv_timeout(WAIT)
+        .expect("the mid-reload edit reloads again")
+        .unwrap();
+    assert_eq!(
+        next.get("workers"),
+        Some(&clapfig::value::Value::Integer(30))
+    );
+}
+
+#[test]
+fn a_failing_initial_resolution_starts_no_watch() {
+    let root = TempDir::new().unwrap();
+    fs::write(root.path().join(".marker"), "").unwrap();
+    fs::write(root.path().join("site.toml"), "workers = \"many\"\n").unwrap();
+
+    let result = site_builder()
+        .build_resolver()
+        .unwrap()
+        .watch(root.path(), fast());
+    assert!(result.is_err());
+}
+
+#[test]
+fn dropping_the_handle_stops_the_watcher() {
+    let root = TempDir::new().unwrap();
+    fs::write(root.path().join(".marker"), "").unwrap();
+
+    let watch = site_builder()
+        .build_resolver()
+        .unwrap()
+        .watch(root.path(), fast())
+        .unwrap();
+    // Drop joins the thread; a watcher that ignored the stop would hang
+    // the test here.
+    drop(watch);
+}